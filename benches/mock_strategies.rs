@@ -1,25 +1,19 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use metis::adapters::{mock_strategy::MockStrategyHandler, state_manager::StateManager};
-use metis::config::{MockConfig, MockStrategyType};
-use serde_json::json;
+use metis::config::MockConfig;
+use serde_json::{json, Value};
 use std::sync::Arc;
 
+/// Mock config from its JSON form, leaving every other option unset
+fn mock(config: Value) -> MockConfig {
+    serde_json::from_value(config).unwrap()
+}
+
 fn benchmark_static_strategy(c: &mut Criterion) {
     let state_manager = Arc::new(StateManager::new());
     let handler = MockStrategyHandler::new(state_manager);
     
-    let config = MockConfig {
-        strategy: MockStrategyType::Static,
-        template: None,
-        faker_type: None,
-        stateful: None,
-        script: None,
-        script_lang: None,
-        file: None,
-        pattern: None,
-        llm: None,
-        database: None,
-    };
+    let config = mock(json!({"strategy": "static"}));
 
     c.bench_function("static_strategy", |b| {
         b.iter(|| {
@@ -35,18 +29,7 @@ fn benchmark_template_strategy(c: &mut Criterion) {
     let state_manager = Arc::new(StateManager::new());
     let handler = MockStrategyHandler::new(state_manager);
     
-    let config = MockConfig {
-        strategy: MockStrategyType::Template,
-        template: Some("Hello, {{ name | default(value='World') }}!".to_string()),
-        faker_type: None,
-        stateful: None,
-        script: None,
-        script_lang: None,
-        file: None,
-        pattern: None,
-        llm: None,
-        database: None,
-    };
+    let config = mock(json!({"strategy": "template", "template": "Hello, {{ name | default(value='World') }}!"}));
 
     c.bench_function("template_strategy", |b| {
         b.iter(|| {
@@ -63,18 +46,7 @@ fn benchmark_random_strategy(c: &mut Criterion) {
     let state_manager = Arc::new(StateManager::new());
     let handler = MockStrategyHandler::new(state_manager);
     
-    let config = MockConfig {
-        strategy: MockStrategyType::Random,
-        template: None,
-        faker_type: Some("name".to_string()),
-        stateful: None,
-        script: None,
-        script_lang: None,
-        file: None,
-        pattern: None,
-        llm: None,
-        database: None,
-    };
+    let config = mock(json!({"strategy": "random", "faker_type": "name"}));
 
     c.bench_function("random_strategy", |b| {
         b.iter(|| {
@@ -90,18 +62,7 @@ fn benchmark_pattern_strategy(c: &mut Criterion) {
     let state_manager = Arc::new(StateManager::new());
     let handler = MockStrategyHandler::new(state_manager);
     
-    let config = MockConfig {
-        strategy: MockStrategyType::Pattern,
-        template: None,
-        faker_type: None,
-        stateful: None,
-        script: None,
-        script_lang: None,
-        file: None,
-        pattern: Some("ID-\\d\\d\\d\\d-\\w\\w\\w\\w".to_string()),
-        llm: None,
-        database: None,
-    };
+    let config = mock(json!({"strategy": "pattern", "pattern": "ID-\\d\\d\\d\\d-\\w\\w\\w\\w"}));
 
     c.bench_function("pattern_strategy", |b| {
         b.iter(|| {
@@ -117,18 +78,7 @@ fn benchmark_script_strategy(c: &mut Criterion) {
     let state_manager = Arc::new(StateManager::new());
     let handler = MockStrategyHandler::new(state_manager);
     
-    let config = MockConfig {
-        strategy: MockStrategyType::Script,
-        template: None,
-        faker_type: None,
-        stateful: None,
-        script: Some("let x = 10; let y = 20; #{ \"sum\": x + y }".to_string()),
-        script_lang: None,
-        file: None,
-        pattern: None,
-        llm: None,
-        database: None,
-    };
+    let config = mock(json!({"strategy": "script", "script": "let x = 10; let y = 20; #{ \"sum\": x + y }"}));
 
     c.bench_function("script_strategy", |b| {
         b.iter(|| {
@@ -146,54 +96,10 @@ fn benchmark_all_strategies(c: &mut Criterion) {
     let handler = MockStrategyHandler::new(state_manager);
 
     let strategies = vec![
-        ("static", MockConfig {
-            strategy: MockStrategyType::Static,
-            template: None,
-            faker_type: None,
-            stateful: None,
-            script: None,
-            script_lang: None,
-            file: None,
-            pattern: None,
-            llm: None,
-            database: None,
-        }),
-        ("template", MockConfig {
-            strategy: MockStrategyType::Template,
-            template: Some("Hello {{ name }}".to_string()),
-            faker_type: None,
-            stateful: None,
-            script: None,
-            script_lang: None,
-            file: None,
-            pattern: None,
-            llm: None,
-            database: None,
-        }),
-        ("random", MockConfig {
-            strategy: MockStrategyType::Random,
-            template: None,
-            faker_type: Some("name".to_string()),
-            stateful: None,
-            script: None,
-            script_lang: None,
-            file: None,
-            pattern: None,
-            llm: None,
-            database: None,
-        }),
-        ("pattern", MockConfig {
-            strategy: MockStrategyType::Pattern,
-            template: None,
-            faker_type: None,
-            stateful: None,
-            script: None,
-            script_lang: None,
-            file: None,
-            pattern: Some("ID-\\d\\d\\d".to_string()),
-            llm: None,
-            database: None,
-        }),
+        ("static", mock(json!({"strategy": "static"}))),
+        ("template", mock(json!({"strategy": "template", "template": "Hello {{ name }}"}))),
+        ("random", mock(json!({"strategy": "random", "faker_type": "name"}))),
+        ("pattern", mock(json!({"strategy": "pattern", "pattern": "ID-\\d\\d\\d"}))),
     ];
 
     for (name, config) in strategies {
@@ -1,150 +1,91 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
 use metis::adapters::{
-    mcp_protocol_handler::{McpProtocolHandler, JsonRpcRequest},
-    health_handler::HealthHandler,
-    metrics_handler::{MetricsCollector, MetricsHandler},
-    resource_handler::InMemoryResourceHandler,
-    tool_handler::BasicToolHandler,
-    prompt_handler::InMemoryPromptHandler,
-    logging_handler::LoggingHandler,
     mock_strategy::MockStrategyHandler,
+    prompt_handler::InMemoryPromptHandler,
+    resource_handler::InMemoryResourceHandler,
     state_manager::StateManager,
+    tool_handler::BasicToolHandler,
 };
 use metis::config::Settings;
+use metis::domain::{PromptPort, ResourcePort, ToolPort};
 use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-fn create_test_app() -> (Arc<McpProtocolHandler>, Arc<HealthHandler>, Arc<MetricsHandler>) {
-    let settings = Arc::new(RwLock::new(Settings {
-        server: metis::config::ServerSettings {
-            host: "127.0.0.1".to_string(),
-            port: 3000,
-        },
-        auth: Default::default(),
-        resources: vec![],
-        tools: vec![],
-        prompts: vec![],
-        rate_limit: None,
-    }));
-
-    let state_manager = Arc::new(StateManager::new());
-    let mock_strategy = Arc::new(MockStrategyHandler::new(state_manager));
-    let resource_handler = Arc::new(InMemoryResourceHandler::new(settings.clone(), mock_strategy.clone()));
-    let tool_handler = Arc::new(BasicToolHandler::new(settings.clone(), mock_strategy.clone()));
-    let prompt_handler = Arc::new(InMemoryPromptHandler::new(settings.clone()));
-    let logging_handler = Arc::new(LoggingHandler::new());
-    let health_handler = Arc::new(HealthHandler::new(settings.clone()));
-    let metrics_collector = Arc::new(MetricsCollector::new().unwrap());
-    let metrics_handler = Arc::new(MetricsHandler::new(metrics_collector));
+struct Handlers {
+    resources: InMemoryResourceHandler,
+    tools: BasicToolHandler,
+    prompts: InMemoryPromptHandler,
+}
 
-    let protocol_handler = Arc::new(McpProtocolHandler::new(
-        resource_handler,
-        tool_handler,
-        prompt_handler,
-        logging_handler,
-    ));
+fn create_handlers() -> Handlers {
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "resources": [{"uri": "file:///readme.txt", "name": "Readme", "content": "Hello"}],
+        "tools": [{"name": "echo", "description": "Echo", "input_schema": {}, "static_response": {"ok": true}}],
+        "prompts": [{"name": "greet", "description": "Greet", "messages": [{"role": "user", "content": "Hello"}]}]
+    }))
+    .unwrap();
+    let settings = Arc::new(RwLock::new(settings));
 
-    (protocol_handler, health_handler, metrics_handler)
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    Handlers {
+        resources: InMemoryResourceHandler::new(settings.clone(), mock_strategy.clone()),
+        tools: BasicToolHandler::new(settings.clone(), mock_strategy),
+        prompts: InMemoryPromptHandler::new(settings),
+    }
 }
 
-fn benchmark_mcp_initialize(c: &mut Criterion) {
-    let (protocol_handler, _, _) = create_test_app();
-    
-    let request = JsonRpcRequest {
-        jsonrpc: "2.0".to_string(),
-        method: "initialize".to_string(),
-        params: Some(json!({
-            "protocolVersion": "2024-11-05",
-            "capabilities": {},
-            "clientInfo": {
-                "name": "benchmark",
-                "version": "1.0.0"
-            }
-        })),
-        id: Some(json!(1)),
-    };
+fn benchmark_tools_list(c: &mut Criterion) {
+    let handlers = create_handlers();
+    let rt = tokio::runtime::Runtime::new().unwrap();
 
-    c.bench_function("mcp_initialize", |b| {
-        b.iter(|| {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async {
-                protocol_handler.handle_request(black_box(request.clone())).await
-            })
-        });
+    c.bench_function("tools_list", |b| {
+        b.iter(|| rt.block_on(async { handlers.tools.list_tools().await.unwrap() }));
     });
 }
 
-fn benchmark_mcp_ping(c: &mut Criterion) {
-    let (protocol_handler, _, _) = create_test_app();
-    
-    let request = JsonRpcRequest {
-        jsonrpc: "2.0".to_string(),
-        method: "ping".to_string(),
-        params: None,
-        id: Some(json!(1)),
-    };
+fn benchmark_tools_call(c: &mut Criterion) {
+    let handlers = create_handlers();
+    let rt = tokio::runtime::Runtime::new().unwrap();
 
-    c.bench_function("mcp_ping", |b| {
+    c.bench_function("tools_call", |b| {
         b.iter(|| {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async {
-                protocol_handler.handle_request(black_box(request.clone())).await
-            })
+            rt.block_on(async { handlers.tools.execute_tool(black_box("echo"), json!({})).await.unwrap() })
         });
     });
 }
 
-fn benchmark_mcp_resources_list(c: &mut Criterion) {
-    let (protocol_handler, _, _) = create_test_app();
-    
-    let request = JsonRpcRequest {
-        jsonrpc: "2.0".to_string(),
-        method: "resources/list".to_string(),
-        params: None,
-        id: Some(json!(1)),
-    };
+fn benchmark_resources_list(c: &mut Criterion) {
+    let handlers = create_handlers();
+    let rt = tokio::runtime::Runtime::new().unwrap();
 
-    c.bench_function("mcp_resources_list", |b| {
-        b.iter(|| {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async {
-                protocol_handler.handle_request(black_box(request.clone())).await
-            })
-        });
+    c.bench_function("resources_list", |b| {
+        b.iter(|| rt.block_on(async { handlers.resources.list_resources().await.unwrap() }));
     });
 }
 
 fn benchmark_request_throughput(c: &mut Criterion) {
-    let (protocol_handler, _, _) = create_test_app();
-    
+    let handlers = create_handlers();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
     let mut group = c.benchmark_group("throughput");
     group.throughput(Throughput::Elements(1));
-    
-    let request = JsonRpcRequest {
-        jsonrpc: "2.0".to_string(),
-        method: "ping".to_string(),
-        params: None,
-        id: Some(json!(1)),
-    };
 
-    group.bench_function("requests_per_second", |b| {
+    group.bench_function("prompts_get", |b| {
         b.iter(|| {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async {
-                protocol_handler.handle_request(black_box(request.clone())).await
-            })
+            rt.block_on(async { handlers.prompts.get_prompt(black_box("greet"), None).await.unwrap() })
         });
     });
-    
+
     group.finish();
 }
 
 criterion_group!(
     benches,
-    benchmark_mcp_initialize,
-    benchmark_mcp_ping,
-    benchmark_mcp_resources_list,
+    benchmark_tools_list,
+    benchmark_tools_call,
+    benchmark_resources_list,
     benchmark_request_throughput
 );
 criterion_main!(benches);
@@ -1,4 +1,8 @@
 fn main() {
     // Tell Cargo to rerun this build script (and thus recompile) when the UI dist changes
     println!("cargo:rerun-if-changed=ui/dist");
+
+    // The config JSON Schema is derived from `Settings`, which a build script
+    // can't see since it is compiled before the crate. `just schema` writes it
+    // to docs/config-schema.json through `metis config-schema` instead.
 }
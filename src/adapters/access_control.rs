@@ -0,0 +1,218 @@
+//! Client IP allow/deny lists
+//!
+//! The client address is the TCP peer, unless the peer is a trusted proxy: then
+//! `X-Forwarded-For` is read right to left, skipping trusted proxies, and the
+//! first other address is the client. A client matching `deny` is rejected;
+//! otherwise it must match `allow` when that list is not empty.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::config::AccessControlConfig;
+
+/// Client address resolved by the access control middleware, kept in request extensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Parse an IP address or CIDR block; a bare address matches only itself
+pub fn parse_network(value: &str) -> Result<IpNet, String> {
+    let value = value.trim();
+    value
+        .parse::<IpNet>()
+        .or_else(|_| value.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| format!("'{}' is not an IP address or CIDR block", value))
+}
+
+fn parse_networks(values: &[String]) -> Result<Vec<IpNet>, String> {
+    values.iter().map(|v| parse_network(v)).collect()
+}
+
+pub struct AccessControl {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+    trusted_proxies: Vec<IpNet>,
+}
+
+pub type SharedAccessControl = Arc<AccessControl>;
+
+impl AccessControl {
+    pub fn new(config: &AccessControlConfig) -> Result<Self, String> {
+        Ok(Self {
+            allow: parse_networks(&config.allow)?,
+            deny: parse_networks(&config.deny)?,
+            trusted_proxies: parse_networks(&config.trusted_proxies)?,
+        })
+    }
+
+    /// Access control that rejects every client, in place of a config that
+    /// can't be parsed
+    pub fn deny_all() -> Self {
+        let everything: Vec<IpNet> = vec![IpNet::V4(Default::default()), IpNet::V6(Default::default())];
+        // Every address is denied, and the allow list keeps out unknown ones
+        Self {
+            allow: everything.clone(),
+            deny: everything,
+            trusted_proxies: Vec::new(),
+        }
+    }
+
+    /// Address of the client, looking through trusted proxies
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let mut client = peer?.to_canonical();
+        if !self.is_trusted_proxy(client) {
+            return Some(client);
+        }
+
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        for hop in forwarded.into_iter().rev() {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = ip.to_canonical();
+            if !self.is_trusted_proxy(client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+
+    /// Whether a client may connect. Unknown addresses pass only without an allow list.
+    pub fn is_allowed(&self, ip: Option<IpAddr>) -> bool {
+        match ip {
+            Some(ip) => {
+                !self.deny.iter().any(|net| net.contains(&ip))
+                    && (self.allow.is_empty() || self.allow.iter().any(|net| net.contains(&ip)))
+            }
+            None => self.allow.is_empty(),
+        }
+    }
+
+    fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+}
+
+pub async fn access_control_middleware(
+    State(access): State<SharedAccessControl>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+    let client_ip = access.client_ip(peer, request.headers());
+
+    if !access.is_allowed(client_ip) {
+        tracing::warn!("Rejected request from {:?}: not allowed by access control", client_ip);
+        return (StatusCode::FORBIDDEN, "Access denied").into_response();
+    }
+
+    if let Some(ip) = client_ip {
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn control(allow: &[&str], deny: &[&str], trusted_proxies: &[&str]) -> AccessControl {
+        let list = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        AccessControl::new(&AccessControlConfig {
+            allow: list(allow),
+            deny: list(deny),
+            trusted_proxies: list(trusted_proxies),
+        })
+        .unwrap()
+    }
+
+    fn ip(value: &str) -> Option<IpAddr> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn test_parse_network() {
+        assert!(parse_network("10.0.0.0/8").is_ok());
+        assert!(parse_network("192.168.1.7").is_ok());
+        assert!(parse_network("fd00::/8").is_ok());
+        assert!(parse_network("10.0.0.0/33").is_err());
+        assert!(parse_network("internal").is_err());
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let access = control(&["10.0.0.0/8", "::1"], &["10.0.5.0/24"], &[]);
+
+        assert!(access.is_allowed(ip("10.1.2.3")));
+        assert!(access.is_allowed(ip("::1")));
+        assert!(!access.is_allowed(ip("10.0.5.9")));
+        assert!(!access.is_allowed(ip("192.168.1.1")));
+        assert!(!access.is_allowed(None));
+
+        // Deny-only lists let everyone else through
+        let access = control(&[], &["203.0.113.0/24"], &[]);
+        assert!(access.is_allowed(ip("198.51.100.1")));
+        assert!(!access.is_allowed(ip("203.0.113.10")));
+        assert!(access.is_allowed(None));
+    }
+
+    #[test]
+    fn test_deny_all_rejects_every_client() {
+        let access = AccessControl::deny_all();
+        assert!(!access.is_allowed(ip("127.0.0.1")));
+        assert!(!access.is_allowed(ip("::1")));
+        assert!(!access.is_allowed(None));
+    }
+
+    #[test]
+    fn test_forwarded_for_only_from_trusted_proxies() {
+        let access = control(&[], &[], &["10.0.0.0/8"]);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("198.51.100.7, 203.0.113.5, 10.0.0.2"));
+
+        // The last untrusted hop is the client; earlier entries could be spoofed
+        assert_eq!(access.client_ip(ip("10.0.0.1"), &headers), ip("203.0.113.5"));
+        // Untrusted peers can't claim another address
+        assert_eq!(access.client_ip(ip("192.0.2.1"), &headers), ip("192.0.2.1"));
+        // IPv4-mapped peers are matched as IPv4
+        assert_eq!(access.client_ip(ip("::ffff:192.0.2.1"), &HeaderMap::new()), ip("192.0.2.1"));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rejects_with_forbidden() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let access: SharedAccessControl = Arc::new(control(&["127.0.0.0/8"], &[], &[]));
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(access, access_control_middleware));
+        let request = |peer: &str| {
+            Request::builder()
+                .uri("/")
+                .extension(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4000)))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("127.0.0.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(request("192.0.2.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
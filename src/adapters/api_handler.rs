@@ -4,35 +4,50 @@
 
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::adapters::mock_strategy::MockStrategyHandler;
+use crate::adapters::config_history::{ConfigEdit, ConfigHistory};
+use crate::adapters::idempotency::IdempotencyCache;
+use crate::adapters::content_negotiation::{accepts_ndjson, Ndjson, Negotiated};
+use crate::adapters::metrics_handler::MetricsCollector;
+use crate::adapters::mock_strategy::{faker_choices, FakerChoices, MockStrategyHandler};
+use crate::agents::confirmation::PendingConfirmation;
+use crate::agents::traces::{AgentTrace, AgentTraceStore, AgentTraceSummary};
 use crate::adapters::rmcp_server::SharedNotificationBroadcaster;
 use crate::adapters::secrets::{SharedSecretsStore, SharedPassphraseStore};
 use crate::adapters::state_manager::StateManager;
-use crate::adapters::tool_handler::AGENT_TOOL_PREFIX;
+use crate::adapters::strategy_catalog::{strategy_catalog, StrategyInfo};
+use crate::adapters::test_assertions::{Assertion, AssertionReport};
+use crate::adapters::tool_handler::{BasicToolHandler, AGENT_TOOL_PREFIX};
+use crate::adapters::tool_overrides::{ActiveOverride, ToolOverrideResponse, ToolOverrides};
 use crate::adapters::workflow_engine::WorkflowEngine;
 use crate::agents::config::{
     AgentConfig, AgentReference, LlmProviderConfig, LlmProviderType, MemoryConfig,
     MergeStrategy, OrchestrationConfig, OrchestrationPattern,
 };
-use crate::agents::domain::{AgentPort, AgentType};
+use crate::agents::domain::{AgentInitReport, AgentPort, AgentType, ConversationSession, SessionImportSummary};
+use crate::agents::memory::ConversationStore;
 use crate::adapters::encryption;
 use crate::adapters::secrets::keys;
 use crate::config::{
-    MockConfig, PromptArgument, PromptConfig, PromptMessage, RateLimitConfig, ResourceConfig,
-    ResourceTemplateConfig, SchemaConfig, SecretsConfig, Settings, ToolConfig, WorkflowConfig, WorkflowStep,
+    LatencyConfig, MockConfig, PromptArgument, PromptConfig, PromptMessage, RateLimitAlgorithm, RateLimitConfig,
+    ResourceConfig, ResourceTemplateConfig, SchemaConfig, SecretsConfig, Settings, ToolConfig, WorkflowConfig,
+    WorkflowStep,
 };
+use crate::config::redact::redact_secrets;
+use crate::config::validator::{ConfigValidator, ValidationError};
 use crate::domain::ToolPort;
-use crate::persistence::models::{ArchetypeType, Changeset, Commit, Tag};
-use crate::persistence::repository::{ArchetypeRepository, CommitRepository};
+use crate::persistence::models::{ArchetypeType, Changeset, Commit, ListSort, Operation, Tag};
+use crate::persistence::repository::{ArchetypeRepository, ChangesetInput, CommitRepository};
 use crate::persistence::DataStore;
 
 /// Shared application state for API handlers
@@ -58,6 +73,27 @@ pub struct ApiState {
     pub file_storage: Option<Arc<crate::adapters::file_storage::FileStorageHandler>>,
     /// DataFusion handler for SQL queries
     pub datafusion: Option<Arc<crate::adapters::datafusion_handler::DataFusionHandler>>,
+    /// Limits LLM-backed mock generation requests
+    pub mock_generation_limiter: crate::adapters::rate_limit::SharedRateLimiter,
+    /// Runtime tool overrides, shared with the tool handler
+    pub tool_overrides: Arc<ToolOverrides>,
+    /// Settings snapshots for undoing and redoing config edits
+    pub config_history: Arc<ConfigHistory>,
+    /// Metrics collector, which also accumulates agents' LLM costs
+    pub metrics: Arc<MetricsCollector>,
+    /// Responses of POST requests by `Idempotency-Key`
+    pub idempotency: Arc<IdempotencyCache>,
+    /// Custom LLM providers agents and mock generation can name
+    pub llm_providers: Arc<crate::agents::llm::ProviderRegistry>,
+    /// In-memory conversation sessions, shared by every agent handler
+    pub agent_sessions: Arc<dyn ConversationStore>,
+}
+
+impl ApiState {
+    /// Write access to the settings for an edit that undo can revert
+    pub async fn edit_settings(&self) -> ConfigEdit<'_> {
+        ConfigEdit::new(self.settings.write().await, &self.config_history)
+    }
 }
 
 /// Tool handler for workflow testing that uses mock strategies
@@ -131,6 +167,7 @@ impl ToolPort for TestToolHandler {
         let settings = self.settings.read().await;
         if let Some(config) = settings.tools.iter().find(|t| t.name == name) {
             let config = config.clone();
+            let default_response = settings.default_tool_response.clone();
             drop(settings);
 
             if let Some(mock_config) = &config.mock {
@@ -138,7 +175,7 @@ impl ToolPort for TestToolHandler {
             } else if let Some(static_response) = &config.static_response {
                 Ok(static_response.clone())
             } else {
-                Ok(Value::Null)
+                default_response.resolve(name)
             }
         } else {
             Err(anyhow::anyhow!("Tool not found: {}", name))
@@ -207,6 +244,16 @@ pub struct ConfigOverview {
     pub data_lakes_count: usize,
     /// Version number for optimistic locking (incremented on each save)
     pub config_version: u64,
+    /// Config profile selected at launch with `--profile`/`METIS_PROFILE`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// Result of validating the live configuration
+#[derive(Serialize)]
+pub struct ConfigValidationReport {
+    pub valid: bool,
+    pub errors: Vec<ValidationError>,
 }
 
 /// Request body for save operations with optimistic locking
@@ -375,6 +422,12 @@ pub struct ToolDto {
     pub static_response: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mock: Option<MockConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_response: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyConfig>,
 }
 
 impl From<&ToolConfig> for ToolDto {
@@ -387,6 +440,9 @@ impl From<&ToolConfig> for ToolDto {
             output_schema: t.output_schema.clone(),
             static_response: t.static_response.clone(),
             mock: t.mock.clone(),
+            dedup_window_ms: t.dedup_window_ms,
+            normalize_response: t.normalize_response,
+            latency: t.latency.clone(),
         }
     }
 }
@@ -401,6 +457,9 @@ impl From<ToolDto> for ToolConfig {
             output_schema: dto.output_schema,
             static_response: dto.static_response,
             mock: dto.mock,
+            dedup_window_ms: dto.dedup_window_ms,
+            normalize_response: dto.normalize_response,
+            latency: dto.latency,
         }
     }
 }
@@ -505,6 +564,10 @@ pub struct WorkflowDto {
     pub steps: Vec<WorkflowStepDto>,
     #[serde(default)]
     pub on_error: crate::config::ErrorStrategy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mapping: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_transform: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -526,6 +589,10 @@ pub struct WorkflowStepDto {
     pub loop_concurrency: u32,
     #[serde(default)]
     pub on_error: crate::config::ErrorStrategy,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_lock_keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inject_context: Vec<crate::config::ContextField>,
 }
 
 fn default_loop_var() -> String {
@@ -546,6 +613,8 @@ impl From<&WorkflowConfig> for WorkflowDto {
             output_schema: w.output_schema.clone(),
             steps: w.steps.iter().map(WorkflowStepDto::from).collect(),
             on_error: w.on_error.clone(),
+            output_mapping: w.output_mapping.clone(),
+            output_transform: w.output_transform.clone(),
         }
     }
 }
@@ -562,6 +631,8 @@ impl From<&WorkflowStep> for WorkflowStepDto {
             loop_var: s.loop_var.clone(),
             loop_concurrency: s.loop_concurrency,
             on_error: s.on_error.clone(),
+            state_lock_keys: s.state_lock_keys.clone(),
+            inject_context: s.inject_context.clone(),
         }
     }
 }
@@ -576,6 +647,8 @@ impl From<WorkflowDto> for WorkflowConfig {
             output_schema: dto.output_schema,
             steps: dto.steps.into_iter().map(WorkflowStep::from).collect(),
             on_error: dto.on_error,
+            output_mapping: dto.output_mapping,
+            output_transform: dto.output_transform,
         }
     }
 }
@@ -592,6 +665,8 @@ impl From<WorkflowStepDto> for WorkflowStep {
             loop_var: dto.loop_var,
             loop_concurrency: dto.loop_concurrency,
             on_error: dto.on_error,
+            state_lock_keys: dto.state_lock_keys,
+            inject_context: dto.inject_context,
         }
     }
 }
@@ -600,9 +675,10 @@ impl From<WorkflowStepDto> for WorkflowStep {
 // Config Overview Endpoints
 // ============================================================================
 
-/// GET /api/config - Get configuration overview
+/// GET /api/config - Get configuration overview as JSON, YAML or TOML per `Accept`
 pub async fn get_config_overview(
     State(state): State<ApiState>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let settings = state.settings.read().await;
 
@@ -640,9 +716,25 @@ pub async fn get_config_overview(
         schemas_count: settings.schemas.len(),
         data_lakes_count: settings.data_lakes.len(),
         config_version: settings.version,
+        profile: settings.profile.clone(),
     };
 
-    (StatusCode::OK, Json(ApiResponse::success(overview)))
+    Negotiated::new(&headers, overview)
+}
+
+/// GET /api/config/validate - Run the config validator over the live configuration
+pub async fn validate_config(State(state): State<ApiState>) -> impl IntoResponse {
+    let settings = state.settings.read().await;
+    let mut errors = ConfigValidator::validate(&settings).err().unwrap_or_default();
+    errors.extend(ConfigValidator::validate_llm_providers(&settings, &state.llm_providers).err().unwrap_or_default());
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(ConfigValidationReport {
+            valid: errors.is_empty(),
+            errors,
+        })),
+    )
 }
 
 // ============================================================================
@@ -681,6 +773,9 @@ pub struct RateLimitConfigDto {
     pub enabled: bool,
     pub requests_per_second: u32,
     pub burst_size: u32,
+    /// Left as configured when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<RateLimitAlgorithm>,
 }
 
 impl From<&RateLimitConfig> for RateLimitConfigDto {
@@ -689,6 +784,7 @@ impl From<&RateLimitConfig> for RateLimitConfigDto {
             enabled: r.enabled,
             requests_per_second: r.requests_per_second,
             burst_size: r.burst_size,
+            algorithm: Some(r.algorithm),
         }
     }
 }
@@ -829,9 +925,10 @@ pub struct ServerSettingsDto {
     pub file_storage: Option<FileStorageConfigDto>,
 }
 
-/// GET /api/config/settings - Get editable server settings
+/// GET /api/config/settings - Get editable server settings as JSON, YAML or TOML per `Accept`
 pub async fn get_server_settings(
     State(state): State<ApiState>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let settings = state.settings.read().await;
 
@@ -855,7 +952,7 @@ pub async fn get_server_settings(
         file_storage: settings.file_storage.as_ref().map(FileStorageConfigDto::from),
     };
 
-    (StatusCode::OK, Json(ApiResponse::success(dto)))
+    Negotiated::new(&headers, dto)
 }
 
 /// PUT /api/config/settings - Update server settings
@@ -863,7 +960,7 @@ pub async fn update_server_settings(
     State(state): State<ApiState>,
     Json(dto): Json<ServerSettingsDto>,
 ) -> impl IntoResponse {
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Update auth settings
     settings.auth.enabled = dto.auth.enabled;
@@ -886,11 +983,16 @@ pub async fn update_server_settings(
             rate_limit.enabled = rate_limit_dto.enabled;
             rate_limit.requests_per_second = rate_limit_dto.requests_per_second;
             rate_limit.burst_size = rate_limit_dto.burst_size;
+            if let Some(algorithm) = rate_limit_dto.algorithm {
+                rate_limit.algorithm = algorithm;
+            }
         } else {
             settings.rate_limit = Some(RateLimitConfig {
                 enabled: rate_limit_dto.enabled,
                 requests_per_second: rate_limit_dto.requests_per_second,
                 burst_size: rate_limit_dto.burst_size,
+                bypass_tokens: Vec::new(),
+                algorithm: rate_limit_dto.algorithm.unwrap_or_default(),
             });
         }
     }
@@ -990,33 +1092,81 @@ pub async fn get_metrics_json(
 // Resource CRUD Endpoints
 // ============================================================================
 
+/// Query parameters of the list endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct ListQuery {
+    /// `name` (default), `created` or `updated`
+    #[serde(default)]
+    pub sort: ListSort,
+}
+
+/// Sort in-memory items for a listing by `key`. Config items carry no
+/// timestamps, so `created` and `updated` keep config order, where items
+/// added through the API come last.
+fn sort_items<T>(items: &mut [T], sort: ListSort, key: impl Fn(&T) -> &str) {
+    if sort == ListSort::Name {
+        items.sort_by(|a, b| key(a).cmp(key(b)));
+    }
+}
+
 /// GET /api/resources - List all resources
 pub async fn list_resources(
     State(state): State<ApiState>,
-) -> impl IntoResponse {
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let ndjson = accepts_ndjson(&headers);
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Resource.as_str()).await {
+        if ndjson {
+            return stream_archetypes::<ResourceDto>(store, ArchetypeType::Resource, query.sort);
+        }
+        match store.archetypes().list_sorted(ArchetypeType::Resource.as_str(), query.sort).await {
             Ok(resources) => {
                 let dtos: Vec<ResourceDto> = resources
                     .into_iter()
                     .filter_map(|v| serde_json::from_value(v).ok())
                     .collect();
-                return (StatusCode::OK, Json(ApiResponse::success(dtos)));
+                return (StatusCode::OK, Json(ApiResponse::success(dtos))).into_response();
             }
             Err(e) => {
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ApiResponse::<Vec<ResourceDto>>::error(e.to_string())),
-                );
+                )
+                    .into_response();
             }
         }
     }
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let resources: Vec<ResourceDto> = settings.resources.iter().map(ResourceDto::from).collect();
-    (StatusCode::OK, Json(ApiResponse::success(resources)))
+    let mut resources: Vec<ResourceDto> = settings.resources.iter().map(ResourceDto::from).collect();
+    sort_items(&mut resources, query.sort, |item| item.uri.as_str());
+    if ndjson {
+        return stream_items(resources);
+    }
+    (StatusCode::OK, Json(ApiResponse::success(resources))).into_response()
+}
+
+/// Stream stored archetypes as NDJSON while they're read, skipping those that
+/// don't parse as `D` like the JSON listings do
+fn stream_archetypes<D>(store: &DataStore, archetype_type: ArchetypeType, sort: ListSort) -> Response
+where
+    D: Serialize + serde::de::DeserializeOwned + Send + 'static,
+{
+    let items = store.archetypes().list_stream(archetype_type.as_str(), sort).filter_map(|item| async move {
+        match item {
+            Ok(value) => serde_json::from_value::<D>(value).ok().map(Ok),
+            Err(e) => Some(Err(e)),
+        }
+    });
+    Ndjson(items).into_response()
+}
+
+/// Stream in-memory items as NDJSON
+fn stream_items<D: Serialize + Send + 'static>(items: Vec<D>) -> Response {
+    Ndjson(futures::stream::iter(items.into_iter().map(Ok::<_, std::convert::Infallible>))).into_response()
 }
 
 /// GET /api/resources/:uri - Get a single resource
@@ -1066,6 +1216,9 @@ pub async fn create_resource(
     State(state): State<ApiState>,
     Json(dto): Json<ResourceDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Resource, &ResourceConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -1083,7 +1236,7 @@ pub async fn create_resource(
             Ok(()) => {
                 // CRITICAL: Also add to in-memory settings so MCP handlers see the new resource
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.resources.push(crate::config::ResourceConfig {
                         uri: dto.uri.clone(),
                         name: dto.name.clone(),
@@ -1122,7 +1275,7 @@ pub async fn create_resource(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate URI
     if settings.resources.iter().any(|r| r.uri == dto.uri) {
@@ -1158,6 +1311,9 @@ pub async fn update_resource(
     Path(uri): Path<String>,
     Json(dto): Json<ResourceDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Resource, &ResourceConfig::from(dto.clone())).await {
+        return response;
+    }
     let decoded_uri = urlencoding::decode(&uri).map(|s| s.into_owned()).unwrap_or(uri.clone());
 
     // Use database if available
@@ -1176,7 +1332,7 @@ pub async fn update_resource(
             Ok(_) => {
                 // CRITICAL: Also update in-memory settings so MCP handlers see the change
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     if let Some(resource) = settings.resources.iter_mut().find(|r| r.uri == decoded_uri) {
                         resource.name = dto.name.clone();
                         resource.description = dto.description.clone();
@@ -1211,7 +1367,7 @@ pub async fn update_resource(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(resource) = settings.resources.iter_mut().find(|r| r.uri == decoded_uri) {
         resource.name = dto.name.clone();
@@ -1255,7 +1411,7 @@ pub async fn delete_resource(
             Ok(true) => {
                 // CRITICAL: Also remove from in-memory settings so MCP handlers don't see deleted resource
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.resources.retain(|r| r.uri != decoded_uri);
                 }
                 // Auto-delete from S3 if configured
@@ -1282,7 +1438,7 @@ pub async fn delete_resource(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.resources.len();
     settings.resources.retain(|r| r.uri != decoded_uri);
@@ -1316,30 +1472,41 @@ pub async fn delete_resource(
 /// GET /api/tools - List all tools
 pub async fn list_tools(
     State(state): State<ApiState>,
-) -> impl IntoResponse {
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let ndjson = accepts_ndjson(&headers);
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Tool.as_str()).await {
+        if ndjson {
+            return stream_archetypes::<ToolDto>(store, ArchetypeType::Tool, query.sort);
+        }
+        match store.archetypes().list_sorted(ArchetypeType::Tool.as_str(), query.sort).await {
             Ok(tools) => {
                 let dtos: Vec<ToolDto> = tools
                     .into_iter()
                     .filter_map(|v| serde_json::from_value(v).ok())
                     .collect();
-                return (StatusCode::OK, Json(ApiResponse::success(dtos)));
+                return (StatusCode::OK, Json(ApiResponse::success(dtos))).into_response();
             }
             Err(e) => {
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ApiResponse::<Vec<ToolDto>>::error(e.to_string())),
-                );
+                )
+                    .into_response();
             }
         }
     }
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let tools: Vec<ToolDto> = settings.tools.iter().map(ToolDto::from).collect();
-    (StatusCode::OK, Json(ApiResponse::success(tools)))
+    let mut tools: Vec<ToolDto> = settings.tools.iter().map(ToolDto::from).collect();
+    sort_items(&mut tools, query.sort, |item| item.name.as_str());
+    if ndjson {
+        return stream_items(tools);
+    }
+    (StatusCode::OK, Json(ApiResponse::success(tools))).into_response()
 }
 
 /// GET /api/tools/:name - Get a single tool
@@ -1391,6 +1558,9 @@ pub async fn create_tool(
     Json(dto): Json<ToolDto>,
 ) -> impl IntoResponse {
     tracing::info!("create_tool: Received tool '{}' with output_schema = {:?}", dto.name, dto.output_schema.as_ref().map(|s| serde_json::to_string(s).unwrap_or_default()));
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Tool, &ToolConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -1407,7 +1577,7 @@ pub async fn create_tool(
             Ok(()) => {
                 // CRITICAL: Also add to in-memory settings so MCP handlers see the new tool
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.tools.push(ToolConfig::from(dto.clone()));
                 }
                 // Auto-sync to S3 if configured
@@ -1436,7 +1606,7 @@ pub async fn create_tool(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.tools.iter().any(|t| t.name == dto.name) {
@@ -1463,6 +1633,15 @@ pub async fn create_tool(
     (StatusCode::CREATED, Json(ApiResponse::success(dto)))
 }
 
+/// POST /api/tools/:name/clone - Copy a tool under a new name
+pub async fn clone_tool(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CloneRequest>,
+) -> Response {
+    clone_item::<ToolConfig, _, _>(state, &name, req.new_name, create_tool).await
+}
+
 /// PUT /api/tools/:name - Update a tool
 pub async fn update_tool(
     State(state): State<ApiState>,
@@ -1470,6 +1649,9 @@ pub async fn update_tool(
     Json(dto): Json<ToolDto>,
 ) -> impl IntoResponse {
     tracing::info!("update_tool: Received tool '{}' with output_schema = {:?}", dto.name, dto.output_schema.as_ref().map(|s| serde_json::to_string(s).unwrap_or_default()));
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Tool, &ToolConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -1486,7 +1668,7 @@ pub async fn update_tool(
             Ok(_) => {
                 // CRITICAL: Also update in-memory settings so MCP handlers see the change
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     // Replace the tool config entirely with the new one
                     if let Some(idx) = settings.tools.iter().position(|t| t.name == name) {
                         settings.tools[idx] = ToolConfig::from(dto.clone());
@@ -1515,7 +1697,7 @@ pub async fn update_tool(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(tool) = settings.tools.iter_mut().find(|t| t.name == name) {
         tool.description = dto.description.clone();
@@ -1553,7 +1735,7 @@ pub async fn delete_tool(
             Ok(true) => {
                 // CRITICAL: Also remove from in-memory settings so MCP handlers don't see deleted tool
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.tools.retain(|t| t.name != name);
                 }
                 // Auto-delete from S3 if configured
@@ -1579,7 +1761,7 @@ pub async fn delete_tool(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.tools.len();
     settings.tools.retain(|t| t.name != name);
@@ -1610,30 +1792,41 @@ pub async fn delete_tool(
 /// GET /api/prompts - List all prompts
 pub async fn list_prompts(
     State(state): State<ApiState>,
-) -> impl IntoResponse {
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let ndjson = accepts_ndjson(&headers);
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Prompt.as_str()).await {
+        if ndjson {
+            return stream_archetypes::<PromptDto>(store, ArchetypeType::Prompt, query.sort);
+        }
+        match store.archetypes().list_sorted(ArchetypeType::Prompt.as_str(), query.sort).await {
             Ok(prompts) => {
                 let dtos: Vec<PromptDto> = prompts
                     .into_iter()
                     .filter_map(|v| serde_json::from_value(v).ok())
                     .collect();
-                return (StatusCode::OK, Json(ApiResponse::success(dtos)));
+                return (StatusCode::OK, Json(ApiResponse::success(dtos))).into_response();
             }
             Err(e) => {
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ApiResponse::<Vec<PromptDto>>::error(e.to_string())),
-                );
+                )
+                    .into_response();
             }
         }
     }
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let prompts: Vec<PromptDto> = settings.prompts.iter().map(PromptDto::from).collect();
-    (StatusCode::OK, Json(ApiResponse::success(prompts)))
+    let mut prompts: Vec<PromptDto> = settings.prompts.iter().map(PromptDto::from).collect();
+    sort_items(&mut prompts, query.sort, |item| item.name.as_str());
+    if ndjson {
+        return stream_items(prompts);
+    }
+    (StatusCode::OK, Json(ApiResponse::success(prompts))).into_response()
 }
 
 /// GET /api/prompts/:name - Get a single prompt
@@ -1681,6 +1874,9 @@ pub async fn create_prompt(
     State(state): State<ApiState>,
     Json(dto): Json<PromptDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Prompt, &PromptConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -1697,7 +1893,7 @@ pub async fn create_prompt(
             Ok(()) => {
                 // CRITICAL: Also add to in-memory settings so MCP handlers see the new prompt
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.prompts.push(PromptConfig::from(dto.clone()));
                 }
                 // Auto-sync to S3 if configured
@@ -1725,7 +1921,7 @@ pub async fn create_prompt(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.prompts.iter().any(|p| p.name == dto.name) {
@@ -1752,12 +1948,24 @@ pub async fn create_prompt(
     (StatusCode::CREATED, Json(ApiResponse::success(dto)))
 }
 
+/// POST /api/prompts/:name/clone - Copy a prompt under a new name
+pub async fn clone_prompt(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CloneRequest>,
+) -> Response {
+    clone_item::<PromptConfig, _, _>(state, &name, req.new_name, create_prompt).await
+}
+
 /// PUT /api/prompts/:name - Update a prompt
 pub async fn update_prompt(
     State(state): State<ApiState>,
     Path(name): Path<String>,
     Json(dto): Json<PromptDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Prompt, &PromptConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -1774,7 +1982,7 @@ pub async fn update_prompt(
             Ok(_) => {
                 // CRITICAL: Also update in-memory settings so MCP handlers see the change
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     // Replace the prompt config entirely with the new one
                     if let Some(idx) = settings.prompts.iter().position(|p| p.name == name) {
                         settings.prompts[idx] = PromptConfig::from(dto.clone());
@@ -1802,7 +2010,7 @@ pub async fn update_prompt(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(prompt) = settings.prompts.iter_mut().find(|p| p.name == name) {
         prompt.description = dto.description.clone();
@@ -1853,7 +2061,7 @@ pub async fn delete_prompt(
             Ok(true) => {
                 // CRITICAL: Also remove from in-memory settings so MCP handlers don't see deleted prompt
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.prompts.retain(|p| p.name != name);
                 }
                 // Auto-delete from S3 if configured
@@ -1878,7 +2086,7 @@ pub async fn delete_prompt(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.prompts.len();
     settings.prompts.retain(|p| p.name != name);
@@ -1909,30 +2117,41 @@ pub async fn delete_prompt(
 /// GET /api/workflows - List all workflows
 pub async fn list_workflows(
     State(state): State<ApiState>,
-) -> impl IntoResponse {
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let ndjson = accepts_ndjson(&headers);
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Workflow.as_str()).await {
+        if ndjson {
+            return stream_archetypes::<WorkflowDto>(store, ArchetypeType::Workflow, query.sort);
+        }
+        match store.archetypes().list_sorted(ArchetypeType::Workflow.as_str(), query.sort).await {
             Ok(workflows) => {
                 let dtos: Vec<WorkflowDto> = workflows
                     .into_iter()
                     .filter_map(|v| serde_json::from_value(v).ok())
                     .collect();
-                return (StatusCode::OK, Json(ApiResponse::success(dtos)));
+                return (StatusCode::OK, Json(ApiResponse::success(dtos))).into_response();
             }
             Err(e) => {
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ApiResponse::<Vec<WorkflowDto>>::error(e.to_string())),
-                );
+                )
+                    .into_response();
             }
         }
     }
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let workflows: Vec<WorkflowDto> = settings.workflows.iter().map(WorkflowDto::from).collect();
-    (StatusCode::OK, Json(ApiResponse::success(workflows)))
+    let mut workflows: Vec<WorkflowDto> = settings.workflows.iter().map(WorkflowDto::from).collect();
+    sort_items(&mut workflows, query.sort, |item| item.name.as_str());
+    if ndjson {
+        return stream_items(workflows);
+    }
+    (StatusCode::OK, Json(ApiResponse::success(workflows))).into_response()
 }
 
 /// GET /api/workflows/:name - Get a single workflow
@@ -1980,6 +2199,9 @@ pub async fn create_workflow(
     State(state): State<ApiState>,
     Json(dto): Json<WorkflowDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Workflow, &WorkflowConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -2019,7 +2241,7 @@ pub async fn create_workflow(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.workflows.iter().any(|w| w.name == dto.name) {
@@ -2046,12 +2268,24 @@ pub async fn create_workflow(
     (StatusCode::CREATED, Json(ApiResponse::success(dto)))
 }
 
+/// POST /api/workflows/:name/clone - Copy a workflow under a new name
+pub async fn clone_workflow(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CloneRequest>,
+) -> Response {
+    clone_item::<WorkflowConfig, _, _>(state, &name, req.new_name, create_workflow).await
+}
+
 /// PUT /api/workflows/:name - Update a workflow
 pub async fn update_workflow(
     State(state): State<ApiState>,
     Path(name): Path<String>,
     Json(dto): Json<WorkflowDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Workflow, &WorkflowConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -2088,7 +2322,7 @@ pub async fn update_workflow(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(workflow) = settings.workflows.iter_mut().find(|w| w.name == name) {
         workflow.name = dto.name.clone();
@@ -2146,7 +2380,7 @@ pub async fn delete_workflow(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.workflows.len();
     settings.workflows.retain(|w| w.name != name);
@@ -2177,10 +2411,11 @@ pub async fn delete_workflow(
 /// GET /api/resource-templates - List all resource templates
 pub async fn list_resource_templates(
     State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
 ) -> impl IntoResponse {
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::ResourceTemplate.as_str()).await {
+        match store.archetypes().list_sorted(ArchetypeType::ResourceTemplate.as_str(), query.sort).await {
             Ok(templates) => {
                 let dtos: Vec<ResourceTemplateDto> = templates
                     .into_iter()
@@ -2199,11 +2434,12 @@ pub async fn list_resource_templates(
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let templates: Vec<ResourceTemplateDto> = settings
+    let mut templates: Vec<ResourceTemplateDto> = settings
         .resource_templates
         .iter()
         .map(ResourceTemplateDto::from)
         .collect();
+    sort_items(&mut templates, query.sort, |item| item.uri_template.as_str());
     (StatusCode::OK, Json(ApiResponse::success(templates)))
 }
 
@@ -2266,6 +2502,9 @@ pub async fn create_resource_template(
     State(state): State<ApiState>,
     Json(dto): Json<ResourceTemplateDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::ResourceTemplate, &ResourceTemplateConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -2283,7 +2522,7 @@ pub async fn create_resource_template(
             Ok(()) => {
                 // CRITICAL: Also add to in-memory settings so MCP handlers see the new template
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.resource_templates.push(ResourceTemplateConfig::from(dto.clone()));
                 }
                 // Auto-sync to S3 if configured
@@ -2313,7 +2552,7 @@ pub async fn create_resource_template(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate URI template
     if settings
@@ -2354,6 +2593,9 @@ pub async fn update_resource_template(
     Path(uri_template): Path<String>,
     Json(dto): Json<ResourceTemplateDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::ResourceTemplate, &ResourceTemplateConfig::from(dto.clone())).await {
+        return response;
+    }
     let decoded_uri = urlencoding::decode(&uri_template)
         .map(|s| s.into_owned())
         .unwrap_or(uri_template.clone());
@@ -2374,7 +2616,7 @@ pub async fn update_resource_template(
             Ok(_) => {
                 // CRITICAL: Also update in-memory settings so MCP handlers see the change
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     if let Some(template) = settings.resource_templates.iter_mut().find(|r| r.uri_template == decoded_uri) {
                         template.name = dto.name.clone();
                         template.description = dto.description.clone();
@@ -2410,7 +2652,7 @@ pub async fn update_resource_template(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(template) = settings
         .resource_templates
@@ -2463,7 +2705,7 @@ pub async fn delete_resource_template(
             Ok(true) => {
                 // CRITICAL: Also remove from in-memory settings so MCP handlers don't see deleted template
                 {
-                    let mut settings = state.settings.write().await;
+                    let mut settings = state.edit_settings().await;
                     settings.resource_templates.retain(|r| r.uri_template != decoded_uri);
                 }
                 // Auto-delete from S3 if configured
@@ -2490,7 +2732,7 @@ pub async fn delete_resource_template(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.resource_templates.len();
     settings
@@ -2586,6 +2828,8 @@ pub async fn test_resource_template(
                                 output: Value::Null,
                                 error: Some(format!("Mock strategy error: {}", e)),
                                 execution_time_ms: elapsed,
+                                raw_output: None,
+                                assertions: None,
                             })),
                         );
                     }
@@ -2610,6 +2854,8 @@ pub async fn test_resource_template(
                             output: Value::Null,
                             error: Some(format!("Mock strategy error: {}", e)),
                             execution_time_ms: elapsed,
+                            raw_output: None,
+                            assertions: None,
                         })),
                     );
                 }
@@ -2642,6 +2888,8 @@ pub async fn test_resource_template(
             output,
             error: None,
             execution_time_ms: elapsed,
+            raw_output: None,
+            assertions: None,
         })),
     )
 }
@@ -2668,12 +2916,89 @@ fn resolve_uri_template(uri_template: &str, args: &Value) -> String {
 // State Management Endpoints
 // ============================================================================
 
-/// GET /api/state - Get all stateful mock state
+#[derive(Deserialize)]
+pub struct StateQuery {
+    /// Only return keys starting with this prefix
+    pub prefix: Option<String>,
+}
+
+/// GET /api/state - Get stateful mock state, optionally filtered by key prefix
 pub async fn get_state(
     State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<StateQuery>,
+) -> impl IntoResponse {
+    let entries = match query.prefix {
+        Some(prefix) => state.state_manager.get_by_prefix(&prefix).await,
+        None => state.state_manager.get_all().await,
+    };
+    (StatusCode::OK, Json(ApiResponse::success(entries)))
+}
+
+#[derive(Deserialize)]
+pub struct BulkStateRequest {
+    pub operations: Vec<crate::adapters::state_manager::StateOperation>,
+}
+
+#[derive(Serialize)]
+pub struct BulkStateResponse {
+    pub applied: usize,
+    pub errors: Vec<String>,
+}
+
+/// POST /api/state/bulk - Apply several state operations atomically
+pub async fn bulk_state(
+    State(state): State<ApiState>,
+    Json(request): Json<BulkStateRequest>,
+) -> impl IntoResponse {
+    match state.state_manager.apply_batch(&request.operations).await {
+        Ok(applied) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(BulkStateResponse { applied, errors: vec![] })),
+        ),
+        Err(errors) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                success: false,
+                data: Some(BulkStateResponse { applied: 0, errors }),
+                error: Some("No state operations applied".to_string()),
+            }),
+        ),
+    }
+}
+
+/// Full contents of the state, as exported and imported
+#[derive(Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub entries: HashMap<String, Value>,
+}
+
+/// GET /api/state/export - Snapshot all state, ready to import elsewhere
+pub async fn export_state(State(state): State<ApiState>) -> impl IntoResponse {
+    let entries = state.state_manager.get_all().await;
+    (StatusCode::OK, Json(ApiResponse::success(StateSnapshot { entries })))
+}
+
+#[derive(Deserialize)]
+pub struct ImportStateRequest {
+    pub entries: HashMap<String, Value>,
+    #[serde(default)]
+    pub mode: crate::adapters::state_manager::StateImportMode,
+}
+
+#[derive(Serialize)]
+pub struct ImportStateResponse {
+    pub imported: usize,
+    pub total: usize,
+}
+
+/// POST /api/state/import - Replace the state with a snapshot, or merge it in
+pub async fn import_state(
+    State(state): State<ApiState>,
+    Json(request): Json<ImportStateRequest>,
 ) -> impl IntoResponse {
-    let all_state = state.state_manager.get_all().await;
-    (StatusCode::OK, Json(ApiResponse::success(all_state)))
+    let imported = request.entries.len();
+    let total = state.state_manager.import(request.entries, request.mode).await;
+    (StatusCode::OK, Json(ApiResponse::success(ImportStateResponse { imported, total })))
 }
 
 /// DELETE /api/state - Reset all stateful mock state
@@ -2734,6 +3059,7 @@ pub async fn save_config_to_disk(
         .config_path
         .clone()
         .unwrap_or_else(|| std::path::PathBuf::from("metis.toml"));
+    let config_backups = settings_guard.server.config_backups;
 
     // Create a copy of settings for serialization (with new version)
     let mut settings: Settings = serde_json::from_value(
@@ -2766,6 +3092,14 @@ pub async fn save_config_to_disk(
         }
     };
 
+    // Keep the file being replaced, in case the new config turns out bad
+    if let Err(e) = crate::config::backup::backup_config(&config_path, config_backups) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<Value>::error(format!("Failed to back up {}: {}", config_path.display(), e)))
+        );
+    }
+
     // Write to config file
     if let Err(e) = std::fs::write(&config_path, toml_content) {
         return (
@@ -2845,6 +3179,39 @@ async fn upload_item_to_s3<T: Serialize>(
     Ok(())
 }
 
+/// Validate an item before it is written, if `validate_on_write` is enabled.
+/// On failure, returns the 422 response the handler should send instead.
+pub async fn validate_item_on_write<T>(
+    state: &ApiState,
+    item_type: ArchetypeType,
+    item: &impl Serialize,
+) -> Result<(), (StatusCode, Json<ApiResponse<T>>)> {
+    if !state.settings.read().await.validate_on_write {
+        return Ok(());
+    }
+
+    let value = serde_json::to_value(item).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(format!("Invalid {} data: {}", item_type.section(), e))),
+        )
+    })?;
+
+    let mut result = ConfigValidator::validate_partial(item_type, &value);
+    if item_type == ArchetypeType::Agent && result.is_ok() {
+        if let Ok(agent) = serde_json::from_value::<AgentConfig>(value) {
+            result = ConfigValidator::validate_agent_llm_provider(&agent, &state.llm_providers);
+        }
+    }
+    result.map_err(|errors| {
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::error(format!("Validation failed: {}", messages.join("; ")))),
+        )
+    })
+}
+
 /// Sync a single item to S3 if S3 backend is configured and active.
 /// This is called automatically when items are created or updated through the UI.
 /// Returns Ok(true) if synced, Ok(false) if S3 not active, Err on failure.
@@ -3193,20 +3560,128 @@ pub async fn save_config_to_s3(
     (StatusCode::OK, Json(ApiResponse::success(serde_json::to_value(SaveConfigResponse { new_version }).unwrap())))
 }
 
-/// GET /api/config/export - Export current configuration as JSON for browser download
+/// Query parameters for the config schema
+#[derive(Debug, Deserialize)]
+pub struct ConfigSchemaQuery {
+    /// Inline nested types where they are used instead of referencing `$defs`
+    #[serde(default)]
+    pub inline: bool,
+}
+
+/// GET /api/config/schema - JSON Schema of the config file (public, no auth required)
+pub async fn get_config_schema(
+    axum::extract::Query(query): axum::extract::Query<ConfigSchemaQuery>,
+) -> impl IntoResponse {
+    Json(Settings::json_schema(query.inline))
+}
+
+/// Query parameters for config export
+#[derive(Debug, Deserialize)]
+pub struct ExportConfigQuery {
+    /// `json` (default) or `bruno`
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// GET /api/config/export - Export current configuration as JSON (or YAML/TOML per `Accept`)
+/// for browser download, or as a Bruno collection ZIP with `?format=bruno`
 pub async fn export_config(
     State(state): State<ApiState>,
-) -> impl IntoResponse {
+    axum::extract::Query(query): axum::extract::Query<ExportConfigQuery>,
+    headers: HeaderMap,
+) -> axum::response::Response {
     let settings = state.settings.read().await;
 
-    // Serialize settings to JSON
-    match serde_json::to_value(&*settings) {
-        Ok(json_value) => (StatusCode::OK, Json(ApiResponse::success(json_value))),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<Value>::error(format!("Failed to serialize config: {}", e)))
-        ),
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => {}
+        "bruno" => {
+            return match crate::adapters::bruno_export::export_collection(&settings) {
+                Ok(archive) => (
+                    StatusCode::OK,
+                    [
+                        (header::CONTENT_TYPE, "application/zip".to_string()),
+                        (
+                            header::CONTENT_DISPOSITION,
+                            format!(
+                                "attachment; filename=\"{}\"",
+                                crate::adapters::bruno_export::ARCHIVE_FILE_NAME
+                            ),
+                        ),
+                    ],
+                    archive,
+                )
+                    .into_response(),
+                Err(e) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error(format!("Failed to build Bruno collection: {}", e))),
+                )
+                    .into_response(),
+            };
+        }
+        other => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!(
+                    "Unsupported export format '{}', expected 'json' or 'bruno'",
+                    other
+                ))),
+            )
+                .into_response();
+        }
+    }
+
+    Negotiated::new(&headers, &*settings).into_response()
+}
+
+/// GET /api/config/effective - The configuration as it's being served, with schema `$ref`s
+/// expanded, runtime tool overrides applied and credentials redacted, as JSON
+/// (or YAML/TOML per `Accept`)
+pub async fn get_effective_config(State(state): State<ApiState>, headers: HeaderMap) -> Response {
+    // Settings isn't Clone, so copy it through its serialized form
+    let copied = {
+        let settings = state.settings.read().await;
+        serde_json::to_value(&*settings).and_then(serde_json::from_value::<Settings>)
+    };
+    let mut settings = match copied {
+        Ok(settings) => settings,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Failed to copy settings: {}", e))),
+            )
+                .into_response();
+        }
+    };
+    settings.expand_schema_refs();
+
+    for active in state.tool_overrides.list() {
+        let Some(tool) = settings.tools.iter_mut().find(|t| t.name == active.tool) else {
+            continue;
+        };
+        match active.response {
+            ToolOverrideResponse::Mock(mock) => {
+                tool.mock = Some(*mock);
+                tool.static_response = None;
+            }
+            ToolOverrideResponse::Value(value) => {
+                tool.mock = None;
+                tool.static_response = Some(value);
+            }
+        }
     }
+
+    let mut effective = match serde_json::to_value(&settings) {
+        Ok(effective) => effective,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Failed to serialize settings: {}", e))),
+            )
+                .into_response();
+        }
+    };
+    redact_secrets(&mut effective);
+    Negotiated::new(&headers, effective).into_response()
 }
 
 /// POST /api/config/import - Import configuration from JSON
@@ -3214,7 +3689,7 @@ pub async fn import_config(
     State(state): State<ApiState>,
     Json(new_settings): Json<Settings>,
 ) -> impl IntoResponse {
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Replace the current settings with the imported ones
     *settings = new_settings;
@@ -3222,55 +3697,224 @@ pub async fn import_config(
     (StatusCode::OK, Json(ApiResponse::<()>::ok()))
 }
 
-/// Response for merge operation showing what was added
+/// Undo/redo stack sizes after restoring a snapshot
 #[derive(Serialize)]
-pub struct MergeResult {
-    pub resources_added: usize,
-    pub resource_templates_added: usize,
-    pub tools_added: usize,
-    pub prompts_added: usize,
-    pub workflows_added: usize,
-    pub agents_added: usize,
-    pub orchestrations_added: usize,
-    pub mcp_servers_added: usize,
+pub struct ConfigHistoryStatus {
+    pub undo_depth: usize,
+    pub redo_depth: usize,
 }
 
-/// POST /api/config/merge - Merge configuration from JSON, only adding new elements
-pub async fn merge_config(
-    State(state): State<ApiState>,
-    Json(new_settings): Json<Settings>,
-) -> impl IntoResponse {
+/// POST /api/config/undo - Restore the config as it was before the last edit
+pub async fn undo_config(State(state): State<ApiState>) -> impl IntoResponse {
+    restore_config_snapshot(&state, false).await
+}
+
+/// POST /api/config/redo - Reapply the last undone config edit
+pub async fn redo_config(State(state): State<ApiState>) -> impl IntoResponse {
+    restore_config_snapshot(&state, true).await
+}
+
+async fn restore_config_snapshot(
+    state: &ApiState,
+    redo: bool,
+) -> (StatusCode, Json<ApiResponse<ConfigHistoryStatus>>) {
     let mut settings = state.settings.write().await;
-    let mut result = MergeResult {
-        resources_added: 0,
-        resource_templates_added: 0,
-        tools_added: 0,
-        prompts_added: 0,
-        workflows_added: 0,
-        agents_added: 0,
-        orchestrations_added: 0,
-        mcp_servers_added: 0,
+    let current = match serde_json::to_value(&*settings) {
+        Ok(current) => current,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Failed to snapshot settings: {}", e))),
+            );
+        }
+    };
+    let snapshot = if redo {
+        state.config_history.redo(current)
+    } else {
+        state.config_history.undo(current)
+    };
+    let Some(snapshot) = snapshot else {
+        let message = if redo { "Nothing to redo" } else { "Nothing to undo" };
+        return (StatusCode::CONFLICT, Json(ApiResponse::error(message)));
     };
 
-    // Merge resources (keyed by uri)
-    for resource in new_settings.resources {
-        if !settings.resources.iter().any(|r| r.uri == resource.uri) {
-            settings.resources.push(resource);
-            result.resources_added += 1;
+    // Snapshots are only taken of valid settings, so this can't fail in practice
+    let mut restored: Settings = match serde_json::from_value(snapshot) {
+        Ok(restored) => restored,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Failed to restore settings: {}", e))),
+            );
         }
-    }
+    };
+    // The file path and save version describe the running server, not the edit
+    restored.config_path = settings.config_path.take();
+    restored.version = settings.version;
+    *settings = restored;
+    drop(settings);
+    refresh_after_config_restore(state).await;
 
-    // Merge resource templates (keyed by uri_template)
-    for template in new_settings.resource_templates {
-        if !settings.resource_templates.iter().any(|t| t.uri_template == template.uri_template) {
-            settings.resource_templates.push(template);
-            result.resource_templates_added += 1;
+    let (undo_depth, redo_depth) = state.config_history.depths();
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(ConfigHistoryStatus { undo_depth, redo_depth })),
+    )
+}
+
+/// Any item may have changed, so refresh agents and every MCP list
+async fn refresh_after_config_restore(state: &ApiState) {
+    *state.test_agent_handler.write().await = None;
+    if let Some(tool_handler) = &state.tool_handler {
+        if let Err(e) = tool_handler.reinitialize_agents().await {
+            tracing::warn!("Failed to reinitialize agents after config restore: {}", e);
         }
     }
-
-    // Merge tools (keyed by name)
-    for tool in new_settings.tools {
-        if !settings.tools.iter().any(|t| t.name == tool.name) {
+    if let Some(broadcaster) = &state.broadcaster {
+        broadcaster.notify_tools_changed().await;
+        broadcaster.notify_resources_changed().await;
+        broadcaster.notify_prompts_changed().await;
+    }
+}
+
+fn config_file_path(settings: &Settings) -> std::path::PathBuf {
+    settings
+        .config_path
+        .clone()
+        .unwrap_or_else(|| std::path::PathBuf::from("metis.toml"))
+}
+
+/// GET /api/config/backups - Backups of the config file taken on save, newest first
+pub async fn list_config_backups(State(state): State<ApiState>) -> impl IntoResponse {
+    let settings = state.settings.read().await;
+    let config_path = config_file_path(&settings);
+    drop(settings);
+    match crate::config::backup::list_backups(&config_path) {
+        Ok(backups) => (StatusCode::OK, Json(ApiResponse::success(backups))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to list config backups: {}", e))),
+        ),
+    }
+}
+
+/// Request body for restoring a config backup
+#[derive(Debug, Deserialize)]
+pub struct RestoreBackupRequest {
+    /// Name of the backup, as listed by `GET /api/config/backups`
+    pub name: String,
+}
+
+/// POST /api/config/restore-backup - Load a config backup and write it back
+/// to the config file. The file it replaces is backed up first, so a restore
+/// can itself be undone.
+pub async fn restore_config_backup(
+    State(state): State<ApiState>,
+    Json(request): Json<RestoreBackupRequest>,
+) -> impl IntoResponse {
+    let mut settings = state.edit_settings().await;
+    let config_path = config_file_path(&settings);
+
+    let Some(backup) = crate::config::backup::backup_path(&config_path, &request.name) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(format!("Config backup '{}' not found", request.name))),
+        );
+    };
+    let mut restored = match Settings::from_backup(&config_path, &backup, settings.profile.as_deref()) {
+        Ok(restored) => restored,
+        Err(e) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::error(format!("Config backup '{}' is invalid: {}", request.name, e))),
+            );
+        }
+    };
+
+    // Backing up the current file rotates old backups, which may delete the
+    // one being restored, so read it first
+    let contents = match std::fs::read(&backup) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Failed to read {}: {}", backup.display(), e))),
+            );
+        }
+    };
+    if let Err(e) = crate::config::backup::backup_config(&config_path, settings.server.config_backups) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to back up {}: {}", config_path.display(), e))),
+        );
+    }
+    if let Err(e) = std::fs::write(&config_path, &contents) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to write {}: {}", config_path.display(), e))),
+        );
+    }
+
+    restored.config_path = settings.config_path.take();
+    restored.profile = settings.profile.take();
+    restored.version = settings.version;
+    restored.increment_version();
+    *settings = restored;
+    drop(settings);
+    refresh_after_config_restore(&state).await;
+
+    (StatusCode::OK, Json(ApiResponse::<()>::ok()))
+}
+
+/// Response for merge operation showing what was added
+#[derive(Serialize)]
+pub struct MergeResult {
+    pub resources_added: usize,
+    pub resource_templates_added: usize,
+    pub tools_added: usize,
+    pub prompts_added: usize,
+    pub workflows_added: usize,
+    pub agents_added: usize,
+    pub orchestrations_added: usize,
+    pub mcp_servers_added: usize,
+}
+
+/// POST /api/config/merge - Merge configuration from JSON, only adding new elements
+pub async fn merge_config(
+    State(state): State<ApiState>,
+    Json(new_settings): Json<Settings>,
+) -> impl IntoResponse {
+    let mut settings = state.edit_settings().await;
+    let mut result = MergeResult {
+        resources_added: 0,
+        resource_templates_added: 0,
+        tools_added: 0,
+        prompts_added: 0,
+        workflows_added: 0,
+        agents_added: 0,
+        orchestrations_added: 0,
+        mcp_servers_added: 0,
+    };
+
+    // Merge resources (keyed by uri)
+    for resource in new_settings.resources {
+        if !settings.resources.iter().any(|r| r.uri == resource.uri) {
+            settings.resources.push(resource);
+            result.resources_added += 1;
+        }
+    }
+
+    // Merge resource templates (keyed by uri_template)
+    for template in new_settings.resource_templates {
+        if !settings.resource_templates.iter().any(|t| t.uri_template == template.uri_template) {
+            settings.resource_templates.push(template);
+            result.resource_templates_added += 1;
+        }
+    }
+
+    // Merge tools (keyed by name)
+    for tool in new_settings.tools {
+        if !settings.tools.iter().any(|t| t.name == tool.name) {
             settings.tools.push(tool);
             result.tools_added += 1;
         }
@@ -3319,6 +3963,342 @@ pub async fn merge_config(
     (StatusCode::OK, Json(ApiResponse::success(result)))
 }
 
+// ============================================================================
+// Batch Config Apply
+// ============================================================================
+
+/// A single create/update/delete operation in a batch config apply
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfigOperation {
+    /// Operation to perform
+    pub op: Operation,
+    /// Archetype type targeted by the operation
+    #[serde(rename = "type")]
+    pub archetype_type: ArchetypeType,
+    /// Identifier of the target item (name, or URI/URI template for resources)
+    pub name: String,
+    /// Full item definition, required for create and update
+    #[serde(default)]
+    pub definition: Option<Value>,
+}
+
+/// Request body for POST /api/config/apply
+#[derive(Debug, Deserialize)]
+pub struct ApplyConfigRequest {
+    /// Operations to apply, in order
+    pub operations: Vec<ConfigOperation>,
+    /// Commit message recorded in version history (database persistence only)
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Response for a successful batch config apply
+#[derive(Debug, Serialize)]
+pub struct ApplyConfigResult {
+    /// Number of operations applied
+    pub applied: usize,
+    /// Hash of the commit recorded for the batch (database persistence only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_hash: Option<String>,
+}
+
+/// Copy of every archetype list, used to roll back a failed batch
+#[derive(Debug)]
+struct ArchetypeSnapshot {
+    resources: Vec<ResourceConfig>,
+    resource_templates: Vec<ResourceTemplateConfig>,
+    tools: Vec<ToolConfig>,
+    prompts: Vec<PromptConfig>,
+    workflows: Vec<WorkflowConfig>,
+    agents: Vec<AgentConfig>,
+    orchestrations: Vec<OrchestrationConfig>,
+    schemas: Vec<SchemaConfig>,
+    data_lakes: Vec<crate::config::DataLakeConfig>,
+}
+
+impl ArchetypeSnapshot {
+    fn capture(settings: &Settings) -> Self {
+        Self {
+            resources: settings.resources.clone(),
+            resource_templates: settings.resource_templates.clone(),
+            tools: settings.tools.clone(),
+            prompts: settings.prompts.clone(),
+            workflows: settings.workflows.clone(),
+            agents: settings.agents.clone(),
+            orchestrations: settings.orchestrations.clone(),
+            schemas: settings.schemas.clone(),
+            data_lakes: settings.data_lakes.clone(),
+        }
+    }
+
+    fn restore(self, settings: &mut Settings) {
+        settings.resources = self.resources;
+        settings.resource_templates = self.resource_templates;
+        settings.tools = self.tools;
+        settings.prompts = self.prompts;
+        settings.workflows = self.workflows;
+        settings.agents = self.agents;
+        settings.orchestrations = self.orchestrations;
+        settings.schemas = self.schemas;
+        settings.data_lakes = self.data_lakes;
+    }
+}
+
+/// Apply one operation to an archetype list, returning the changeset it produced
+fn apply_operation_to_vec<T>(
+    items: &mut Vec<T>,
+    key: fn(&T) -> &str,
+    op: &ConfigOperation,
+) -> Result<ChangesetInput, String>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+{
+    let type_name = op.archetype_type.as_str();
+    let position = items.iter().position(|item| key(item) == op.name);
+
+    let parse_definition = || -> Result<(T, Value), String> {
+        let definition = op
+            .definition
+            .clone()
+            .ok_or_else(|| format!("{} '{}': definition is required for {}", type_name, op.name, op.op))?;
+        let item: T = serde_json::from_value(definition.clone())
+            .map_err(|e| format!("{} '{}': invalid definition: {}", type_name, op.name, e))?;
+        if key(&item) != op.name {
+            return Err(format!(
+                "{} '{}': definition identifier '{}' does not match operation name",
+                type_name,
+                op.name,
+                key(&item)
+            ));
+        }
+        Ok((item, definition))
+    };
+
+    let (old_definition, new_definition) = match op.op {
+        Operation::Create => {
+            if position.is_some() {
+                return Err(format!("{} '{}' already exists", type_name, op.name));
+            }
+            let (item, definition) = parse_definition()?;
+            items.push(item);
+            (None, Some(definition))
+        }
+        Operation::Update => {
+            let idx = position.ok_or_else(|| format!("{} '{}' not found", type_name, op.name))?;
+            let (item, definition) = parse_definition()?;
+            let old = serde_json::to_value(&items[idx]).ok();
+            items[idx] = item;
+            (old, Some(definition))
+        }
+        Operation::Delete => {
+            let idx = position.ok_or_else(|| format!("{} '{}' not found", type_name, op.name))?;
+            let old = serde_json::to_value(items.remove(idx)).ok();
+            (old, None)
+        }
+    };
+
+    Ok(ChangesetInput {
+        operation: op.op,
+        archetype_type: type_name.to_string(),
+        archetype_name: op.name.clone(),
+        old_definition,
+        new_definition,
+    })
+}
+
+/// Apply one operation to the in-memory settings
+fn apply_config_operation(settings: &mut Settings, op: &ConfigOperation) -> Result<ChangesetInput, String> {
+    match op.archetype_type {
+        ArchetypeType::Resource => apply_operation_to_vec(&mut settings.resources, |r| &r.uri, op),
+        ArchetypeType::ResourceTemplate => {
+            apply_operation_to_vec(&mut settings.resource_templates, |t| &t.uri_template, op)
+        }
+        ArchetypeType::Tool => apply_operation_to_vec(&mut settings.tools, |t| &t.name, op),
+        ArchetypeType::Prompt => apply_operation_to_vec(&mut settings.prompts, |p| &p.name, op),
+        ArchetypeType::Workflow => apply_operation_to_vec(&mut settings.workflows, |w| &w.name, op),
+        ArchetypeType::Agent => apply_operation_to_vec(&mut settings.agents, |a| &a.name, op),
+        ArchetypeType::Orchestration => apply_operation_to_vec(&mut settings.orchestrations, |o| &o.name, op),
+        ArchetypeType::Schema => apply_operation_to_vec(&mut settings.schemas, |s| &s.name, op),
+        ArchetypeType::DataLake => apply_operation_to_vec(&mut settings.data_lakes, |d| &d.name, op),
+    }
+}
+
+/// Apply all operations and validate the result, rolling back on any failure.
+/// On success, returns the changesets along with a snapshot of the previous state.
+fn apply_config_operations(
+    settings: &mut Settings,
+    operations: &[ConfigOperation],
+) -> Result<(Vec<ChangesetInput>, ArchetypeSnapshot), Vec<String>> {
+    let snapshot = ArchetypeSnapshot::capture(settings);
+
+    let mut changes = Vec::with_capacity(operations.len());
+    for op in operations {
+        match apply_config_operation(settings, op) {
+            Ok(change) => changes.push(change),
+            Err(e) => {
+                snapshot.restore(settings);
+                return Err(vec![e]);
+            }
+        }
+    }
+
+    if let Err(errors) = crate::config::validator::ConfigValidator::validate(settings) {
+        snapshot.restore(settings);
+        return Err(errors.iter().map(|e| e.to_string()).collect());
+    }
+
+    Ok((changes, snapshot))
+}
+
+/// Persist applied changesets to the database, undoing already-written rows on failure
+async fn persist_config_changes(
+    store: &DataStore,
+    changes: &[ChangesetInput],
+) -> Result<(), crate::persistence::error::PersistenceError> {
+    let repo = store.archetypes();
+
+    for (idx, change) in changes.iter().enumerate() {
+        let result = match (&change.operation, &change.new_definition) {
+            (Operation::Create, Some(def)) => repo.create(&change.archetype_type, &change.archetype_name, def).await,
+            (Operation::Update, Some(def)) => repo
+                .update(&change.archetype_type, &change.archetype_name, def, None)
+                .await
+                .map(|_| ()),
+            _ => repo.delete(&change.archetype_type, &change.archetype_name).await.map(|_| ()),
+        };
+
+        if let Err(e) = result {
+            // Compensate for the rows already written, newest first
+            for applied in changes[..idx].iter().rev() {
+                let undo = match (&applied.operation, &applied.old_definition) {
+                    (Operation::Create, _) => repo.delete(&applied.archetype_type, &applied.archetype_name).await.map(|_| ()),
+                    (Operation::Update, Some(old)) => repo
+                        .update(&applied.archetype_type, &applied.archetype_name, old, None)
+                        .await
+                        .map(|_| ()),
+                    (Operation::Delete, Some(old)) => repo.create(&applied.archetype_type, &applied.archetype_name, old).await,
+                    _ => Ok(()),
+                };
+                if let Err(undo_err) = undo {
+                    tracing::error!(
+                        "Failed to roll back {} '{}' after batch apply error: {}",
+                        applied.archetype_type,
+                        applied.archetype_name,
+                        undo_err
+                    );
+                }
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+/// POST /api/config/apply - Apply a batch of create/update/delete operations atomically
+/// All operations are validated together under a single write lock; if any fails, none are kept.
+/// One commit is recorded and one set of notifications is sent for the whole batch.
+pub async fn apply_config(
+    State(state): State<ApiState>,
+    Json(request): Json<ApplyConfigRequest>,
+) -> impl IntoResponse {
+    if request.operations.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<ApplyConfigResult>::error("No operations provided")),
+        );
+    }
+
+    let mut settings = state.edit_settings().await;
+
+    let (changes, snapshot) = match apply_config_operations(&mut settings, &request.operations) {
+        Ok(result) => result,
+        Err(errors) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<ApplyConfigResult>::error(format!(
+                    "Config apply failed, no changes were made:\n{}",
+                    errors.join("\n")
+                ))),
+            );
+        }
+    };
+
+    // Persist while still holding the lock so clients never observe a partial batch
+    let mut commit_hash = None;
+    if let Some(store) = &state.data_store {
+        if let Err(e) = persist_config_changes(store, &changes).await {
+            snapshot.restore(&mut settings);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<ApplyConfigResult>::error(format!(
+                    "Failed to persist config changes: {}",
+                    e
+                ))),
+            );
+        }
+
+        let message = request
+            .message
+            .clone()
+            .unwrap_or_else(|| format!("Apply {} config operations", changes.len()));
+        match store.commits().create_commit(&message, changes.clone(), Some("api")).await {
+            Ok(commit) => commit_hash = Some(commit.commit_hash),
+            Err(e) => tracing::warn!("Failed to record commit for config apply: {}", e),
+        }
+    }
+    drop(settings);
+
+    // Sync changed items to S3 if configured
+    for (op, change) in request.operations.iter().zip(&changes) {
+        let subdir = op.archetype_type.section();
+        let s3_name = match op.archetype_type {
+            ArchetypeType::Resource => sanitize_uri_for_s3(&op.name),
+            ArchetypeType::ResourceTemplate => sanitize_uri_template_for_s3(&op.name),
+            _ => op.name.clone(),
+        };
+        let result = match &change.new_definition {
+            Some(definition) => sync_item_to_s3_if_active(&state, subdir, &s3_name, definition).await,
+            None => delete_item_from_s3_if_active(&state, subdir, &s3_name).await,
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to sync {} '{}' to S3: {}", subdir, op.name, e);
+        }
+    }
+
+    let touched = |types: &[ArchetypeType]| request.operations.iter().any(|op| types.contains(&op.archetype_type));
+
+    if touched(&[ArchetypeType::Agent]) {
+        *state.test_agent_handler.write().await = None;
+        if let Some(tool_handler) = &state.tool_handler {
+            if let Err(e) = tool_handler.reinitialize_agents().await {
+                tracing::warn!("Failed to reinitialize agents after config apply: {}", e);
+            }
+        }
+    }
+
+    // Notify connected MCP clients once per affected list
+    if let Some(broadcaster) = &state.broadcaster {
+        if touched(&[ArchetypeType::Tool, ArchetypeType::Workflow, ArchetypeType::Agent]) {
+            broadcaster.notify_tools_changed().await;
+        }
+        if touched(&[ArchetypeType::Resource, ArchetypeType::ResourceTemplate, ArchetypeType::DataLake]) {
+            broadcaster.notify_resources_changed().await;
+        }
+        if touched(&[ArchetypeType::Prompt]) {
+            broadcaster.notify_prompts_changed().await;
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(ApplyConfigResult {
+            applied: changes.len(),
+            commit_hash,
+        })),
+    )
+}
+
 /// Build AWS SDK configuration for S3 operations
 /// Uses credentials from secrets store (UI) with fallback to environment variables
 async fn build_s3_config(
@@ -3379,6 +4359,148 @@ async fn build_s3_config(
 // Test Endpoints - Execute tools, resources, prompts, workflows
 // ============================================================================
 
+/// Request body for clone endpoints
+#[derive(Deserialize)]
+pub struct CloneRequest {
+    pub new_name: String,
+}
+
+/// Named config item that the clone endpoints can copy
+trait ClonableItem: Sized {
+    type Dto: for<'a> From<&'a Self>;
+
+    /// Name of the item type in error messages
+    const KIND: &'static str;
+
+    fn collection(settings: &Settings) -> &[Self];
+    fn name(&self) -> &str;
+    fn rename(dto: &mut Self::Dto, name: String);
+}
+
+impl ClonableItem for ToolConfig {
+    type Dto = ToolDto;
+    const KIND: &'static str = "Tool";
+
+    fn collection(settings: &Settings) -> &[Self] {
+        &settings.tools
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(dto: &mut ToolDto, name: String) {
+        dto.name = name;
+    }
+}
+
+impl ClonableItem for PromptConfig {
+    type Dto = PromptDto;
+    const KIND: &'static str = "Prompt";
+
+    fn collection(settings: &Settings) -> &[Self] {
+        &settings.prompts
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(dto: &mut PromptDto, name: String) {
+        dto.name = name;
+    }
+}
+
+impl ClonableItem for WorkflowConfig {
+    type Dto = WorkflowDto;
+    const KIND: &'static str = "Workflow";
+
+    fn collection(settings: &Settings) -> &[Self] {
+        &settings.workflows
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(dto: &mut WorkflowDto, name: String) {
+        dto.name = name;
+    }
+}
+
+impl ClonableItem for AgentConfig {
+    type Dto = AgentDto;
+    const KIND: &'static str = "Agent";
+
+    fn collection(settings: &Settings) -> &[Self] {
+        &settings.agents
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(dto: &mut AgentDto, name: String) {
+        dto.name = name;
+    }
+}
+
+impl ClonableItem for OrchestrationConfig {
+    type Dto = OrchestrationDto;
+    const KIND: &'static str = "Orchestration";
+
+    fn collection(settings: &Settings) -> &[Self] {
+        &settings.orchestrations
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(dto: &mut OrchestrationDto, name: String) {
+        dto.name = name;
+    }
+}
+
+impl ClonableItem for SchemaConfig {
+    type Dto = SchemaDto;
+    const KIND: &'static str = "Schema";
+
+    fn collection(settings: &Settings) -> &[Self] {
+        &settings.schemas
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(dto: &mut SchemaDto, name: String) {
+        dto.name = name;
+    }
+}
+
+/// Copy the item `name` of type `C` as `new_name`. Creating it goes through
+/// the regular create handler, which rejects taken names with 409.
+async fn clone_item<C, F, Fut>(state: ApiState, name: &str, new_name: String, create: F) -> Response
+where
+    C: ClonableItem,
+    F: FnOnce(State<ApiState>, Json<C::Dto>) -> Fut,
+    Fut: std::future::Future,
+    Fut::Output: IntoResponse,
+{
+    if new_name.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error("new_name must not be empty"))).into_response();
+    }
+    let settings = state.settings.read().await;
+    let source = C::collection(&settings).iter().find(|x| x.name() == name).map(C::Dto::from);
+    drop(settings);
+    let Some(mut dto) = source else {
+        return (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(format!("{} not found", C::KIND)))).into_response();
+    };
+    C::rename(&mut dto, new_name);
+    create(State(state), Json(dto)).await.into_response()
+}
+
 /// Request body for test endpoints
 #[derive(Deserialize)]
 pub struct TestRequest {
@@ -3387,6 +4509,9 @@ pub struct TestRequest {
     /// Optional session ID for multi-turn conversations
     #[serde(default)]
     pub session_id: Option<String>,
+    /// Checks on the output, reported in the result's `assertions`
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
 }
 
 /// Response for test endpoints
@@ -3396,6 +4521,12 @@ pub struct TestResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub execution_time_ms: u64,
+    /// Workflow result before its output mapping was applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<Value>,
+    /// Outcome of the request's assertions, if it had any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assertions: Option<AssertionReport>,
 }
 
 /// POST /api/tools/:name/test - Execute a tool with test inputs
@@ -3417,6 +4548,7 @@ pub async fn test_tool(
             );
         }
     };
+    let default_response = settings.default_tool_response.clone();
     drop(settings);
 
     // Execute the mock strategy
@@ -3437,6 +4569,8 @@ pub async fn test_tool(
                                 output: Value::Null,
                                 error: Some(format!("Mock strategy error: {}", e)),
                                 execution_time_ms: elapsed,
+                                raw_output: None,
+                                assertions: AssertionReport::evaluate(&req.assertions, None),
                             })),
                         );
                     }
@@ -3453,28 +4587,266 @@ pub async fn test_tool(
                             output: Value::Null,
                             error: Some(format!("Mock strategy error: {}", e)),
                             execution_time_ms: elapsed,
+                            raw_output: None,
+                            assertions: AssertionReport::evaluate(&req.assertions, None),
                         })),
                     );
                 }
             }
         }
-    } else if let Some(static_response) = &tool.static_response {
-        static_response.clone()
-    } else {
-        Value::Null
+    } else if let Some(static_response) = &tool.static_response {
+        static_response.clone()
+    } else {
+        match default_response.resolve(&name) {
+            Ok(value) => value,
+            Err(e) => {
+                let elapsed = start.elapsed().as_millis() as u64;
+                return (
+                    StatusCode::OK,
+                    Json(ApiResponse::success(TestResult {
+                        output: Value::Null,
+                        error: Some(e.to_string()),
+                        execution_time_ms: elapsed,
+                        raw_output: None,
+                        assertions: AssertionReport::evaluate(&req.assertions, None),
+                    })),
+                );
+            }
+        }
+    };
+
+    let elapsed = start.elapsed().as_millis() as u64;
+    let assertions = AssertionReport::evaluate(&req.assertions, Some(&output));
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(TestResult {
+            output,
+            error: None,
+            execution_time_ms: elapsed,
+            raw_output: None,
+            assertions,
+        })),
+    )
+}
+
+/// Request body for previewing a mock strategy
+#[derive(Deserialize)]
+pub struct StrategyPreviewRequest {
+    pub mock: MockConfig,
+    /// Sample arguments, as a tool call would receive them
+    #[serde(default)]
+    pub args: Value,
+}
+
+/// POST /api/strategies/preview - Run a mock config against sample args without saving anything
+pub async fn preview_strategy(
+    State(state): State<ApiState>,
+    Json(req): Json<StrategyPreviewRequest>,
+) -> impl IntoResponse {
+    let start = std::time::Instant::now();
+    let (output, error) = match state.mock_strategy.generate(&req.mock, Some(&req.args)).await {
+        Ok(output) => (output, None),
+        Err(e) => (Value::Null, Some(format!("Mock strategy error: {}", e))),
+    };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(TestResult {
+            output,
+            error,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+            raw_output: None,
+            assertions: None,
+        })),
+    )
+}
+
+/// GET /api/strategies - Describe every mock strategy and the config fields it reads
+pub async fn list_strategies() -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::<Vec<StrategyInfo>>::success(strategy_catalog())))
+}
+
+/// Request body for listing the faker choices of a schema
+#[derive(Deserialize)]
+pub struct FakerChoicesRequest {
+    pub schema: Value,
+}
+
+/// POST /api/faker/choices - List the faker types available for each property of a schema
+pub async fn get_faker_choices(Json(req): Json<FakerChoicesRequest>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::<FakerChoices>::success(faker_choices(&req.schema))))
+}
+
+/// POST /api/tools/:name/generate-mock - Suggest a mock config for a tool using an LLM
+///
+/// The suggestion is only returned for review, never applied to the tool.
+pub async fn generate_tool_mock(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    use crate::adapters::mock_generator::{generate_mock, MockGenerationResult, REQUESTS_PER_MINUTE};
+
+    let (tool, provider_config) = {
+        let settings = state.settings.read().await;
+        let Some(tool) = settings.tools.iter().find(|t| t.name == name).cloned() else {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<MockGenerationResult>::error(format!("Tool '{}' not found", name))),
+            );
+        };
+        let provider_config = settings
+            .generation_provider
+            .clone()
+            .or_else(|| settings.agents.first().map(|a| a.llm.clone()));
+        (tool, provider_config)
+    };
+
+    let Some(provider_config) = provider_config else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<MockGenerationResult>::error(
+                "No LLM configured: set generation_provider or add an agent",
+            )),
+        );
+    };
+
+    // Only requests that reach the LLM use up the limit
+    if state.mock_generation_limiter.check().is_err() {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(ApiResponse::<MockGenerationResult>::error(format!(
+                "Mock generation is limited to {} requests per minute",
+                REQUESTS_PER_MINUTE
+            ))),
+        );
+    }
+
+    let provider = match crate::agents::llm::create_provider_with_secrets(&provider_config, state.secrets.clone(), &state.llm_providers).await {
+        Ok(provider) => provider,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<MockGenerationResult>::error(format!("Failed to create LLM provider: {}", e))),
+            );
+        }
     };
 
-    let elapsed = start.elapsed().as_millis() as u64;
+    match generate_mock(provider.as_ref(), &tool).await {
+        Ok(result) => (StatusCode::OK, Json(ApiResponse::success(result))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<MockGenerationResult>::error(e.to_string())),
+        ),
+    }
+}
+
+/// Request body for a runtime tool override
+#[derive(Deserialize)]
+pub struct SetToolOverrideRequest {
+    /// Either `{"mock": <MockConfig>}` or `{"value": <literal>}`
+    #[serde(flatten)]
+    pub response: ToolOverrideResponse,
+    /// Seconds until the override expires; without it the override lasts until deleted
+    pub ttl_secs: Option<u64>,
+}
+
+/// POST /api/tools/:name/override - Temporarily force a tool's response
+///
+/// Overrides are kept in memory only and are never saved to disk or S3.
+pub async fn set_tool_override(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<SetToolOverrideRequest>,
+) -> impl IntoResponse {
+    let exists = {
+        let settings = state.settings.read().await;
+        settings.tools.iter().any(|t| t.name == name) || settings.workflows.iter().any(|w| w.name == name)
+    };
+    if !exists {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<ActiveOverride>::error(format!("Tool '{}' not found", name))),
+        );
+    }
+
+    let ttl = req.ttl_secs.map(std::time::Duration::from_secs);
+    if let Err(e) = state.tool_overrides.set(&name, req.response.clone(), ttl) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<ActiveOverride>::error(e.to_string())));
+    }
+
     (
         StatusCode::OK,
-        Json(ApiResponse::success(TestResult {
-            output,
-            error: None,
-            execution_time_ms: elapsed,
+        Json(ApiResponse::success(ActiveOverride {
+            tool: name,
+            response: req.response,
+            expires_in_secs: req.ttl_secs,
         })),
     )
 }
 
+/// DELETE /api/tools/:name/override - Remove a runtime tool override
+pub async fn delete_tool_override(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    if state.tool_overrides.remove(&name) {
+        tracing::info!("Override removed for tool '{}'", name);
+        (StatusCode::OK, Json(ApiResponse::<()>::ok()))
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(format!("No active override for tool '{}'", name))),
+        )
+    }
+}
+
+/// GET /api/overrides - List active runtime tool overrides
+pub async fn list_tool_overrides(State(state): State<ApiState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.tool_overrides.list())))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListCallbacksQuery {
+    /// Only list callbacks that have not been sent yet
+    #[serde(default)]
+    pub pending: bool,
+}
+
+/// GET /api/callbacks - List callbacks scheduled by the callback strategy
+pub async fn list_callbacks(
+    State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<ListCallbacksQuery>,
+) -> impl IntoResponse {
+    let callbacks = state.mock_strategy.callbacks().list(query.pending);
+    (StatusCode::OK, Json(ApiResponse::success(callbacks)))
+}
+
+/// DELETE /api/callbacks - Forget recorded callbacks
+pub async fn clear_callbacks(State(state): State<ApiState>) -> impl IntoResponse {
+    state.mock_strategy.callbacks().clear();
+    (StatusCode::OK, Json(ApiResponse::<()>::ok()))
+}
+
+/// GET /api/errors - Recent failures of tools' mock strategies, newest first
+pub async fn list_strategy_errors(State(state): State<ApiState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.mock_strategy.errors().list())))
+}
+
+/// DELETE /api/errors - Forget recorded strategy failures
+pub async fn clear_strategy_errors(State(state): State<ApiState>) -> impl IntoResponse {
+    state.mock_strategy.errors().clear();
+    (StatusCode::OK, Json(ApiResponse::<()>::ok()))
+}
+
+/// GET /api/mcp-servers - Connection status of the external MCP servers
+pub async fn list_mcp_servers(State(state): State<ApiState>) -> impl IntoResponse {
+    let servers = match &state.tool_handler {
+        Some(tool_handler) => tool_handler.mcp_client().server_statuses().await,
+        None => Vec::new(),
+    };
+    (StatusCode::OK, Json(ApiResponse::success(servers)))
+}
+
 /// POST /api/resources/:uri/test - Read a resource and get its content
 pub async fn test_resource(
     State(state): State<ApiState>,
@@ -3526,6 +4898,8 @@ pub async fn test_resource(
                                 output: Value::Null,
                                 error: Some(format!("Mock strategy error: {}", e)),
                                 execution_time_ms: elapsed,
+                                raw_output: None,
+                                assertions: None,
                             })),
                         );
                     }
@@ -3549,6 +4923,8 @@ pub async fn test_resource(
                             output: Value::Null,
                             error: Some(format!("Mock strategy error: {}", e)),
                             execution_time_ms: elapsed,
+                            raw_output: None,
+                            assertions: None,
                         })),
                     );
                 }
@@ -3577,6 +4953,8 @@ pub async fn test_resource(
             output,
             error: None,
             execution_time_ms: elapsed,
+            raw_output: None,
+            assertions: None,
         })),
     )
 }
@@ -3637,6 +5015,8 @@ pub async fn test_prompt(
             output,
             error: None,
             execution_time_ms: elapsed,
+            raw_output: None,
+            assertions: None,
         })),
     )
 }
@@ -3678,16 +5058,25 @@ pub async fn test_workflow(
     // Create workflow engine
     let workflow_engine = WorkflowEngine::new(tool_handler);
 
-    // Execute the workflow
-    match workflow_engine.execute(&workflow, req.args).await {
-        Ok(output) => {
+    // Execute the workflow, keeping its result from before the output mapping
+    let result = match workflow_engine.execute_unmapped(&workflow, req.args.clone()).await {
+        Ok(raw) => workflow_engine
+            .map_output(&workflow, &req.args, raw.clone())
+            .map(|output| (output, raw)),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok((output, raw)) => {
             let elapsed = start.elapsed().as_millis() as u64;
+            let mapped = workflow.output_mapping.is_some() || workflow.output_transform.is_some();
             (
                 StatusCode::OK,
                 Json(ApiResponse::success(TestResult {
                     output,
                     error: None,
                     execution_time_ms: elapsed,
+                    raw_output: mapped.then_some(raw),
+                    assertions: None,
                 })),
             )
         }
@@ -3699,12 +5088,63 @@ pub async fn test_workflow(
                     output: Value::Null,
                     error: Some(format!("Workflow execution error: {}", e)),
                     execution_time_ms: elapsed,
+                    raw_output: None,
+                    assertions: None,
                 })),
             )
         }
     }
 }
 
+/// POST /api/workflows/:name/estimate - Estimate the LLM tokens and cost of a workflow run
+pub async fn estimate_workflow(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<TestRequest>,
+) -> impl IntoResponse {
+    use crate::adapters::workflow_estimate::{self, WorkflowEstimate};
+    use crate::agents::llm::{approximate_token_count, create_provider_with_secrets, LlmProvider};
+    use crate::config::{LLMConfig, LLMProvider};
+
+    let settings = state.settings.read().await;
+    let Some(workflow) = settings.workflows.iter().find(|w| w.name == name).cloned() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<WorkflowEstimate>::error("Workflow not found")),
+        );
+    };
+    let tools = settings.tools.clone();
+    let pricing = settings.pricing.clone();
+    drop(settings);
+
+    // Count tokens with each model's provider; without an API key it can't be
+    // created, so fall back to an approximation
+    let mut providers: HashMap<(LLMProvider, String), Option<Arc<dyn LlmProvider>>> =
+        HashMap::new();
+    for step in &workflow.steps {
+        let Some(llm) = tools.iter().find(|t| t.name == step.tool).and_then(workflow_estimate::llm_config) else {
+            continue;
+        };
+        if let std::collections::hash_map::Entry::Vacant(entry) =
+            providers.entry((llm.provider.clone(), llm.model.clone()))
+        {
+            let provider = create_provider_with_secrets(&LlmProviderConfig::from(llm), state.secrets.clone(), &state.llm_providers)
+                .await
+                .ok();
+            entry.insert(provider);
+        }
+    }
+    let count_tokens = |llm: &LLMConfig, text: &str| match providers.get(&(llm.provider.clone(), llm.model.clone())) {
+        Some(Some(provider)) => provider.count_tokens(text),
+        _ => approximate_token_count(text),
+    };
+
+    match workflow_estimate::estimate_workflow(&workflow, &tools, &pricing, &req.args, count_tokens) {
+        Ok(estimate) => (StatusCode::OK, Json(ApiResponse::success(estimate))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e))),
+    }
+}
+
 // ============================================================================
 // Agent DTOs
 // ============================================================================
@@ -3752,6 +5192,29 @@ pub struct AgentDto {
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub persona_commands: bool,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub personas: HashMap<String, String>,
+    /// Glob patterns of tools that need operator approval before a call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub require_confirmation_for: Vec<String>,
+    #[serde(default = "default_confirmation_timeout_ms")]
+    pub confirmation_timeout_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_window: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_result_tokens: Option<u32>,
 }
 
 fn default_max_iterations() -> u32 {
@@ -3762,6 +5225,10 @@ fn default_timeout() -> u64 {
     120
 }
 
+fn default_confirmation_timeout_ms() -> u64 {
+    300_000
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct LlmProviderConfigDto {
     pub provider: LlmProviderType,
@@ -3794,6 +5261,8 @@ pub struct MemoryConfigDto {
     pub file_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<crate::agents::config::SummaryConfig>,
 }
 
 fn default_max_messages() -> u32 {
@@ -3810,7 +5279,7 @@ impl From<&AgentConfig> for AgentDto {
             input_schema: a.input_schema.clone(),
             output_schema: a.output_schema.clone(),
             llm: LlmProviderConfigDto {
-                provider: a.llm.provider,
+                provider: a.llm.provider.clone(),
                 model: a.llm.model.clone(),
                 api_key_env: a.llm.api_key_env.clone(),
                 base_url: a.llm.base_url.clone(),
@@ -3831,11 +5300,23 @@ impl From<&AgentConfig> for AgentDto {
                 max_messages: a.memory.max_messages,
                 file_path: a.memory.file_path.clone(),
                 database_url: a.memory.database_url.clone(),
+                summary: a.memory.summary.clone(),
             },
             max_iterations: a.max_iterations,
             timeout_seconds: a.timeout_seconds,
             temperature: a.temperature,
             max_tokens: a.max_tokens,
+            stop: a.stop.clone(),
+            top_p: a.top_p,
+            frequency_penalty: a.frequency_penalty,
+            presence_penalty: a.presence_penalty,
+            seed: a.seed,
+            persona_commands: a.persona_commands,
+            personas: a.personas.clone(),
+            require_confirmation_for: a.require_confirmation_for.clone(),
+            confirmation_timeout_ms: a.confirmation_timeout_ms,
+            history_window: a.history_window,
+            max_tool_result_tokens: a.max_tool_result_tokens,
         }
     }
 }
@@ -3871,11 +5352,23 @@ impl From<AgentDto> for AgentConfig {
                 max_messages: dto.memory.max_messages,
                 file_path: dto.memory.file_path,
                 database_url: dto.memory.database_url,
+                summary: dto.memory.summary,
             },
             max_iterations: dto.max_iterations,
             timeout_seconds: dto.timeout_seconds,
             temperature: dto.temperature,
             max_tokens: dto.max_tokens,
+            stop: dto.stop,
+            top_p: dto.top_p,
+            frequency_penalty: dto.frequency_penalty,
+            presence_penalty: dto.presence_penalty,
+            seed: dto.seed,
+            persona_commands: dto.persona_commands,
+            personas: dto.personas,
+            require_confirmation_for: dto.require_confirmation_for,
+            confirmation_timeout_ms: dto.confirmation_timeout_ms,
+            history_window: dto.history_window,
+            max_tool_result_tokens: dto.max_tool_result_tokens,
         }
     }
 }
@@ -3968,10 +5461,11 @@ impl From<OrchestrationDto> for OrchestrationConfig {
 /// GET /api/agents - List all agents
 pub async fn list_agents(
     State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
 ) -> impl IntoResponse {
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Agent.as_str()).await {
+        match store.archetypes().list_sorted(ArchetypeType::Agent.as_str(), query.sort).await {
             Ok(agents) => {
                 let dtos: Vec<AgentDto> = agents
                     .into_iter()
@@ -3990,7 +5484,8 @@ pub async fn list_agents(
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let agents: Vec<AgentDto> = settings.agents.iter().map(AgentDto::from).collect();
+    let mut agents: Vec<AgentDto> = settings.agents.iter().map(AgentDto::from).collect();
+    sort_items(&mut agents, query.sort, |item| item.name.as_str());
     (StatusCode::OK, Json(ApiResponse::success(agents)))
 }
 
@@ -4039,6 +5534,9 @@ pub async fn create_agent(
     State(state): State<ApiState>,
     Json(dto): Json<AgentDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Agent, &AgentConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -4084,7 +5582,7 @@ pub async fn create_agent(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.agents.iter().any(|a| a.name == dto.name) {
@@ -4121,12 +5619,24 @@ pub async fn create_agent(
     (StatusCode::CREATED, Json(ApiResponse::success(AgentDto::from(&agent))))
 }
 
+/// POST /api/agents/:name/clone - Copy an agent under a new name
+pub async fn clone_agent(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CloneRequest>,
+) -> Response {
+    clone_item::<AgentConfig, _, _>(state, &name, req.new_name, create_agent).await
+}
+
 /// PUT /api/agents/:name - Update an existing agent
 pub async fn update_agent(
     State(state): State<ApiState>,
     Path(name): Path<String>,
     Json(dto): Json<AgentDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Agent, &AgentConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -4169,7 +5679,7 @@ pub async fn update_agent(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(agent) = settings.agents.iter_mut().find(|a| a.name == name) {
         *agent = dto.clone().into();
@@ -4239,7 +5749,7 @@ pub async fn delete_agent(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.agents.len();
     settings.agents.retain(|a| a.name != name);
@@ -4273,102 +5783,408 @@ pub async fn delete_agent(
     }
 }
 
-/// POST /api/agents/:name/test - Test an agent
-pub async fn test_agent(
+/// POST /api/agents/reload - Rebuild every agent from the current config and
+/// report which ones loaded. Agents added by a config reload become available
+/// without a restart.
+pub async fn reload_agents(State(state): State<ApiState>) -> impl IntoResponse {
+    *state.test_agent_handler.write().await = None;
+
+    let mut handlers: Vec<Arc<dyn AgentPort>> = state.agent_handler.iter().cloned().collect();
+    if let Some(tool_handler) = &state.tool_handler {
+        // The tool handler's agent handler is reinitialized below, only once
+        if let Some(shared) = tool_handler.agent_handler().await {
+            handlers.retain(|handler| !Arc::ptr_eq(handler, &shared));
+        }
+    }
+    let mut report = match &state.tool_handler {
+        Some(tool_handler) => match tool_handler.reinitialize_agents().await {
+            Ok(report) => report,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<AgentInitReport>::error(format!("Failed to reload agents: {}", e))),
+                )
+            }
+        },
+        None => None,
+    };
+    if report.is_none() && handlers.is_empty() {
+        // Nothing is serving agents yet; the test handler reports on them
+        match shared_test_agent_handler(&state).await {
+            Ok(handler) => handlers.push(handler),
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e))),
+        }
+    }
+    for handler in handlers {
+        match handler.reinitialize().await {
+            Ok(reloaded) => report = report.or(Some(reloaded)),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(format!("Failed to reload agents: {}", e))),
+                )
+            }
+        }
+    }
+
+    // Agents are exposed as tools, so notify about tool list change
+    if let Some(broadcaster) = &state.broadcaster {
+        broadcaster.notify_tools_changed().await;
+    }
+
+    (StatusCode::OK, Json(ApiResponse::success(report.unwrap_or_default())))
+}
+
+/// Tool handler for agents run by the REST API. Its calls count against the
+/// server's `max_concurrent_tool_calls` like the MCP server's.
+fn api_tool_handler(state: &ApiState) -> BasicToolHandler {
+    let handler = BasicToolHandler::new(state.settings.clone(), state.mock_strategy.clone());
+    match &state.tool_handler {
+        Some(tool_handler) => handler.with_call_limiter(tool_handler.call_limiter().clone()),
+        None => handler,
+    }
+}
+
+/// Agent handler shared by the test endpoints, created on first use. It is kept
+/// across requests so its memory store preserves multi-turn sessions.
+async fn shared_test_agent_handler(state: &ApiState) -> Result<Arc<dyn AgentPort>, String> {
+    use crate::agents::handler::AgentHandler;
+
+    if let Some(handler) = state.test_agent_handler.read().await.clone() {
+        return Ok(handler);
+    }
+
+    let mut handler_guard = state.test_agent_handler.write().await;
+    // Double-check after acquiring write lock
+    if let Some(handler) = handler_guard.clone() {
+        return Ok(handler);
+    }
+
+    let tool_handler = Arc::new(api_tool_handler(state));
+    // Use new_with_secrets to enable API key lookup from secrets store
+    let agent_handler = AgentHandler::new_with_secrets(
+        state.settings.clone(),
+        tool_handler.clone(),
+        state.secrets.clone(),
+    )
+    .with_metrics(state.metrics.clone())
+    .with_llm_providers(state.llm_providers.clone())
+    .with_session_store(state.agent_sessions.clone());
+    let agent_handler = match &state.data_store {
+        Some(store) => agent_handler.with_traces(AgentTraceStore::new(store.records().clone())),
+        None => agent_handler,
+    };
+
+    // Initialize the agent handler to populate agent cache
+    agent_handler
+        .initialize()
+        .await
+        .map_err(|e| format!("Failed to initialize agent handler: {}", e))?;
+
+    // Wire up agent handler to tool handler so agents can call other agents
+    let agent_handler = Arc::new(agent_handler);
+    tool_handler.set_agent_handler(agent_handler.clone()).await;
+
+    *handler_guard = Some(agent_handler.clone());
+    Ok(agent_handler)
+}
+
+/// POST /api/agents/:name/test - Test an agent
+pub async fn test_agent(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<TestRequest>,
+) -> impl IntoResponse {
+    let start = std::time::Instant::now();
+
+    // Verify agent exists in config
+    let settings = state.settings.read().await;
+    if !settings.agents.iter().any(|a| a.name == name) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<TestResult>::error("Agent not found")),
+        );
+    }
+    drop(settings);
+
+    let agent_handler = match shared_test_agent_handler(&state).await {
+        Ok(handler) => handler,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<TestResult>::error(&e)),
+            );
+        }
+    };
+
+    // Execute the agent with optional session_id for multi-turn conversations
+    match agent_handler.execute(&name, req.args.clone(), req.session_id.clone()).await {
+        Ok(response) => {
+            let elapsed = start.elapsed().as_millis() as u64;
+            let output = json!({
+                "output": response.output,
+                "session_id": response.session_id,
+                "iterations": response.iterations,
+                "tool_calls": response.tool_calls.len(),
+                "reasoning_steps": response.reasoning_steps.len(),
+                "trace": response.trace,
+            });
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(TestResult {
+                    output,
+                    error: None,
+                    execution_time_ms: elapsed,
+                    raw_output: None,
+                    assertions: None,
+                })),
+            )
+        }
+        Err(e) => {
+            let elapsed = start.elapsed().as_millis() as u64;
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(TestResult {
+                    output: json!({}),
+                    error: Some(e.to_string()),
+                    execution_time_ms: elapsed,
+                    raw_output: None,
+                    assertions: None,
+                })),
+            )
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SessionExportQuery {
+    /// Export format; only `json` is supported
+    #[serde(default = "default_session_export_format")]
+    pub format: String,
+}
+
+fn default_session_export_format() -> String {
+    "json".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionConflict {
+    /// Keep the existing session
+    #[default]
+    Skip,
+    /// Overwrite the existing session
+    Replace,
+}
+
+#[derive(Deserialize)]
+pub struct SessionImportQuery {
+    #[serde(default)]
+    pub on_conflict: SessionConflict,
+}
+
+/// Resolve the agent handler for session endpoints, or an error response
+async fn session_agent_handler(
+    state: &ApiState,
+    name: &str,
+) -> Result<Arc<dyn AgentPort>, (StatusCode, String)> {
+    if !state.settings.read().await.agents.iter().any(|a| a.name == name) {
+        return Err((StatusCode::NOT_FOUND, "Agent not found".to_string()));
+    }
+    shared_test_agent_handler(state)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// GET /api/agents/:name/sessions/export - Export an agent's conversation sessions
+pub async fn export_agent_sessions(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SessionExportQuery>,
+) -> impl IntoResponse {
+    if !query.format.eq_ignore_ascii_case("json") {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<Vec<ConversationSession>>::error(format!(
+                "Unsupported export format '{}'; expected 'json'",
+                query.format
+            ))),
+        );
+    }
+
+    let agent_handler = match session_agent_handler(&state, &name).await {
+        Ok(handler) => handler,
+        Err((status, e)) => return (status, Json(ApiResponse::error(&e))),
+    };
+
+    match agent_handler.export_sessions(&name).await {
+        Ok(sessions) => (StatusCode::OK, Json(ApiResponse::success(sessions))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to export sessions: {}", e))),
+        ),
+    }
+}
+
+/// POST /api/agents/:name/sessions/import - Import conversation sessions for an agent
+pub async fn import_agent_sessions(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SessionImportQuery>,
+    Json(sessions): Json<Vec<ConversationSession>>,
+) -> impl IntoResponse {
+    if let Some(session) = sessions.iter().find(|s| s.session_id.is_empty()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<SessionImportSummary>::error(format!(
+                "Session with {} messages has an empty session_id",
+                session.messages.len()
+            ))),
+        );
+    }
+
+    let agent_handler = match session_agent_handler(&state, &name).await {
+        Ok(handler) => handler,
+        Err((status, e)) => return (status, Json(ApiResponse::error(&e))),
+    };
+
+    let replace = query.on_conflict == SessionConflict::Replace;
+    match agent_handler.import_sessions(&name, sessions, replace).await {
+        Ok(summary) => (StatusCode::OK, Json(ApiResponse::success(summary))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to import sessions: {}", e))),
+        ),
+    }
+}
+
+/// Agent handlers whose agents may be waiting for a tool confirmation: the
+/// shared test handler (if created yet) and the MCP server's
+async fn confirmation_agent_handlers(state: &ApiState) -> Vec<Arc<dyn AgentPort>> {
+    let mut handlers: Vec<Arc<dyn AgentPort>> = state.test_agent_handler.read().await.iter().cloned().collect();
+    handlers.extend(state.agent_handler.clone());
+    handlers
+}
+
+/// GET /api/agents/:name/confirmations - List tool calls waiting for operator approval
+pub async fn list_agent_confirmations(
     State(state): State<ApiState>,
     Path(name): Path<String>,
-    Json(req): Json<TestRequest>,
 ) -> impl IntoResponse {
-    use crate::adapters::tool_handler::BasicToolHandler;
-    use crate::agents::handler::AgentHandler;
+    if !state.settings.read().await.agents.iter().any(|a| a.name == name) {
+        return (StatusCode::NOT_FOUND, Json(ApiResponse::<Vec<PendingConfirmation>>::error("Agent not found")));
+    }
 
-    let start = std::time::Instant::now();
+    let mut pending: Vec<PendingConfirmation> = confirmation_agent_handlers(&state)
+        .await
+        .iter()
+        .flat_map(|handler| handler.pending_confirmations(&name))
+        .collect();
+    pending.sort_by_key(|p| p.requested_at);
+    (StatusCode::OK, Json(ApiResponse::success(pending)))
+}
 
-    // Verify agent exists in config
-    let settings = state.settings.read().await;
-    if !settings.agents.iter().any(|a| a.name == name) {
-        return (
+/// Request body for deciding a pending tool call
+#[derive(Deserialize)]
+pub struct ConfirmationDecisionRequest {
+    pub approved: bool,
+}
+
+/// POST /api/agents/:name/confirmations/:token - Approve or deny a pending tool call
+pub async fn resolve_agent_confirmation(
+    State(state): State<ApiState>,
+    Path((name, token)): Path<(String, String)>,
+    Json(req): Json<ConfirmationDecisionRequest>,
+) -> impl IntoResponse {
+    let resolved = confirmation_agent_handlers(&state)
+        .await
+        .iter()
+        .any(|handler| handler.resolve_confirmation(&name, &token, req.approved));
+
+    if resolved {
+        (StatusCode::OK, Json(ApiResponse::ok()))
+    } else {
+        (
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::<TestResult>::error("Agent not found")),
-        );
+            Json(ApiResponse::<()>::error(format!("No pending confirmation '{}' for agent '{}'", token, name))),
+        )
     }
-    drop(settings);
-
-    // Initialize agent handler only if not already present (preserves memory store for multi-turn)
-    {
-        let handler_guard = state.test_agent_handler.read().await;
-        if handler_guard.is_none() {
-            drop(handler_guard);
-            let mut handler_guard = state.test_agent_handler.write().await;
-            // Double-check after acquiring write lock
-            if handler_guard.is_none() {
-                let tool_handler = Arc::new(BasicToolHandler::new(
-                    state.settings.clone(),
-                    state.mock_strategy.clone(),
-                ));
-                // Use new_with_secrets to enable API key lookup from secrets store
-                let agent_handler = AgentHandler::new_with_secrets(
-                    state.settings.clone(),
-                    tool_handler.clone(),
-                    state.secrets.clone(),
-                );
+}
 
-                // Initialize the agent handler to populate agent cache
-                if let Err(e) = agent_handler.initialize().await {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse::<TestResult>::error(&format!("Failed to initialize agent handler: {}", e))),
-                    );
-                }
+#[derive(Deserialize)]
+pub struct AgentTracesQuery {
+    pub session_id: Option<String>,
+    #[serde(default = "default_agent_traces_limit")]
+    pub limit: usize,
+}
 
-                // Wire up agent handler to tool handler so agents can call other agents
-                let agent_handler = Arc::new(agent_handler);
-                tool_handler.set_agent_handler(agent_handler.clone()).await;
+fn default_agent_traces_limit() -> usize {
+    20
+}
 
-                *handler_guard = Some(agent_handler);
-            }
-        }
+/// Trace store for the trace endpoints, or an error response
+async fn agent_trace_store(state: &ApiState, name: &str) -> Result<AgentTraceStore, (StatusCode, String)> {
+    if !state.settings.read().await.agents.iter().any(|a| a.name == name) {
+        return Err((StatusCode::NOT_FOUND, "Agent not found".to_string()));
     }
+    match &state.data_store {
+        Some(store) => Ok(AgentTraceStore::new(store.records().clone())),
+        None => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Database not configured. Agent traces require database persistence.".to_string(),
+        )),
+    }
+}
 
-    // Use the shared handler
-    let handler_guard = state.test_agent_handler.read().await;
-    let agent_handler = handler_guard.as_ref().unwrap();
+/// GET /api/agents/:name/traces - List recent tool-calling traces of an agent
+pub async fn list_agent_traces(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<AgentTracesQuery>,
+) -> impl IntoResponse {
+    let traces = match agent_trace_store(&state, &name).await {
+        Ok(traces) => traces,
+        Err((status, e)) => return (status, Json(ApiResponse::<Vec<AgentTraceSummary>>::error(e))),
+    };
 
-    // Execute the agent with optional session_id for multi-turn conversations
-    match agent_handler.execute(&name, req.args.clone(), req.session_id.clone()).await {
-        Ok(response) => {
-            let elapsed = start.elapsed().as_millis() as u64;
-            let output = json!({
-                "output": response.output,
-                "session_id": response.session_id,
-                "iterations": response.iterations,
-                "tool_calls": response.tool_calls.len(),
-                "reasoning_steps": response.reasoning_steps.len(),
-            });
+    match traces.list(&name, query.session_id.as_deref(), query.limit).await {
+        Ok(summaries) => (StatusCode::OK, Json(ApiResponse::success(summaries))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to list traces: {}", e))),
+        ),
+    }
+}
 
-            (
-                StatusCode::OK,
-                Json(ApiResponse::success(TestResult {
-                    output,
-                    error: None,
-                    execution_time_ms: elapsed,
-                })),
-            )
-        }
-        Err(e) => {
-            let elapsed = start.elapsed().as_millis() as u64;
-            (
-                StatusCode::OK,
-                Json(ApiResponse::success(TestResult {
-                    output: json!({}),
-                    error: Some(e.to_string()),
-                    execution_time_ms: elapsed,
-                })),
-            )
-        }
+/// GET /api/agents/:name/traces/:trace_id - Get a trace with each tool call's arguments and result
+pub async fn get_agent_trace(
+    State(state): State<ApiState>,
+    Path((name, trace_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let traces = match agent_trace_store(&state, &name).await {
+        Ok(traces) => traces,
+        Err((status, e)) => return (status, Json(ApiResponse::<AgentTrace>::error(e))),
+    };
+
+    match traces.get(&name, &trace_id).await {
+        Ok(Some(trace)) => (StatusCode::OK, Json(ApiResponse::success(trace))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("No trace '{}' for agent '{}'", trace_id, name))),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to load trace: {}", e))),
+        ),
     }
 }
 
+/// GET /api/costs - LLM costs of agents' completions, per agent, provider and model
+pub async fn get_costs(State(state): State<ApiState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.metrics.llm_costs.report())))
+}
+
 // ============================================================================
 // Orchestration CRUD Handlers
 // ============================================================================
@@ -4376,10 +6192,11 @@ pub async fn test_agent(
 /// GET /api/orchestrations - List all orchestrations
 pub async fn list_orchestrations(
     State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
 ) -> impl IntoResponse {
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Orchestration.as_str()).await {
+        match store.archetypes().list_sorted(ArchetypeType::Orchestration.as_str(), query.sort).await {
             Ok(orchestrations) => {
                 let dtos: Vec<OrchestrationDto> = orchestrations
                     .into_iter()
@@ -4398,7 +6215,8 @@ pub async fn list_orchestrations(
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let orchestrations: Vec<OrchestrationDto> = settings.orchestrations.iter().map(OrchestrationDto::from).collect();
+    let mut orchestrations: Vec<OrchestrationDto> = settings.orchestrations.iter().map(OrchestrationDto::from).collect();
+    sort_items(&mut orchestrations, query.sort, |item| item.name.as_str());
     (StatusCode::OK, Json(ApiResponse::success(orchestrations)))
 }
 
@@ -4447,6 +6265,9 @@ pub async fn create_orchestration(
     State(state): State<ApiState>,
     Json(dto): Json<OrchestrationDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Orchestration, &OrchestrationConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -4479,7 +6300,7 @@ pub async fn create_orchestration(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.orchestrations.iter().any(|o| o.name == dto.name) {
@@ -4494,12 +6315,24 @@ pub async fn create_orchestration(
     (StatusCode::CREATED, Json(ApiResponse::success(OrchestrationDto::from(&orchestration))))
 }
 
+/// POST /api/orchestrations/:name/clone - Copy an orchestration under a new name
+pub async fn clone_orchestration(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CloneRequest>,
+) -> Response {
+    clone_item::<OrchestrationConfig, _, _>(state, &name, req.new_name, create_orchestration).await
+}
+
 /// PUT /api/orchestrations/:name - Update an existing orchestration
 pub async fn update_orchestration(
     State(state): State<ApiState>,
     Path(name): Path<String>,
     Json(dto): Json<OrchestrationDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Orchestration, &OrchestrationConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -4529,7 +6362,7 @@ pub async fn update_orchestration(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     if let Some(orchestration) = settings.orchestrations.iter_mut().find(|o| o.name == name) {
         *orchestration = dto.into();
@@ -4563,7 +6396,7 @@ pub async fn delete_orchestration(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.orchestrations.len();
     settings.orchestrations.retain(|o| o.name != name);
@@ -4581,7 +6414,6 @@ pub async fn test_orchestration(
     Path(name): Path<String>,
     Json(req): Json<TestRequest>,
 ) -> impl IntoResponse {
-    use crate::adapters::tool_handler::BasicToolHandler;
     use crate::agents::handler::AgentHandler;
 
     let start = std::time::Instant::now();
@@ -4600,17 +6432,17 @@ pub async fn test_orchestration(
     drop(settings);
 
     // Create agent handler on-demand for testing
-    let tool_handler = Arc::new(BasicToolHandler::new(
-        state.settings.clone(),
-        state.mock_strategy.clone(),
-    ));
-    let agent_handler = AgentHandler::new_with_secrets(state.settings.clone(), tool_handler, state.secrets.clone());
+    let tool_handler = Arc::new(api_tool_handler(&state));
+    let agent_handler = AgentHandler::new_with_secrets(state.settings.clone(), tool_handler, state.secrets.clone())
+        .with_metrics(state.metrics.clone())
+        .with_llm_providers(state.llm_providers.clone())
+        .with_session_store(state.agent_sessions.clone());
 
     // Initialize the agent handler to populate agent cache and orchestration engine
     if let Err(e) = agent_handler.initialize().await {
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<TestResult>::error(&format!("Failed to initialize agent handler: {}", e))),
+            Json(ApiResponse::<TestResult>::error(format!("Failed to initialize agent handler: {}", e))),
         );
     }
 
@@ -4634,6 +6466,8 @@ pub async fn test_orchestration(
                             output,
                             error: None,
                             execution_time_ms: elapsed,
+                            raw_output: None,
+                            assertions: None,
                         })),
                     )
                 }
@@ -4645,6 +6479,8 @@ pub async fn test_orchestration(
                             output: json!({}),
                             error: Some(e.to_string()),
                             execution_time_ms: elapsed,
+                            raw_output: None,
+                            assertions: None,
                         })),
                     )
                 }
@@ -4658,6 +6494,8 @@ pub async fn test_orchestration(
                     output: json!({}),
                     error: Some(e.to_string()),
                     execution_time_ms: elapsed,
+                    raw_output: None,
+                    assertions: None,
                 })),
             )
         }
@@ -5168,10 +7006,11 @@ impl From<SchemaDto> for SchemaConfig {
 /// GET /api/schemas - List all schemas
 pub async fn list_schemas(
     State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<ListQuery>,
 ) -> impl IntoResponse {
     // Use database if available
     if let Some(store) = &state.data_store {
-        match store.archetypes().list(ArchetypeType::Schema.as_str()).await {
+        match store.archetypes().list_sorted(ArchetypeType::Schema.as_str(), query.sort).await {
             Ok(schemas) => {
                 let dtos: Vec<SchemaDto> = schemas
                     .into_iter()
@@ -5190,7 +7029,8 @@ pub async fn list_schemas(
 
     // Fallback to in-memory settings
     let settings = state.settings.read().await;
-    let schemas: Vec<SchemaDto> = settings.schemas.iter().map(SchemaDto::from).collect();
+    let mut schemas: Vec<SchemaDto> = settings.schemas.iter().map(SchemaDto::from).collect();
+    sort_items(&mut schemas, query.sort, |item| item.name.as_str());
     (StatusCode::OK, Json(ApiResponse::success(schemas)))
 }
 
@@ -5239,6 +7079,9 @@ pub async fn create_schema(
     State(state): State<ApiState>,
     Json(dto): Json<SchemaDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Schema, &SchemaConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -5275,7 +7118,7 @@ pub async fn create_schema(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.schemas.iter().any(|s| s.name == dto.name) {
@@ -5297,12 +7140,24 @@ pub async fn create_schema(
     (StatusCode::CREATED, Json(ApiResponse::success(dto)))
 }
 
+/// POST /api/schemas/:name/clone - Copy a schema under a new name
+pub async fn clone_schema(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CloneRequest>,
+) -> Response {
+    clone_item::<SchemaConfig, _, _>(state, &name, req.new_name, create_schema).await
+}
+
 /// PUT /api/schemas/:name - Update a schema
 pub async fn update_schema(
     State(state): State<ApiState>,
     Path(name): Path<String>,
     Json(dto): Json<SchemaDto>,
 ) -> impl IntoResponse {
+    if let Err(response) = validate_item_on_write(&state, ArchetypeType::Schema, &SchemaConfig::from(dto.clone())).await {
+        return response;
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -5342,7 +7197,7 @@ pub async fn update_schema(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check if name is being changed and would conflict
     if dto.name != name {
@@ -5406,7 +7261,7 @@ pub async fn delete_schema(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.schemas.len();
     settings.schemas.retain(|s| s.name != name);
@@ -5483,10 +7338,7 @@ pub async fn get_database_status(
         let backend = store.pool().backend().name().to_string();
 
         // Get HEAD commit
-        let head = match store.commits().get_head().await {
-            Ok(h) => h,
-            Err(_) => None,
-        };
+        let head = store.commits().get_head().await.unwrap_or_default();
 
         // Get counts
         let total_commits = match store.commits().list_commits(10000, 0).await {
@@ -5760,11 +7612,325 @@ pub async fn delete_tag(
     }
 }
 
+// ============================================================================
+// Search
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+    /// Comma-separated archetype types to search; tools, resources, resource
+    /// templates, prompts and workflows if omitted
+    #[serde(default)]
+    pub types: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResult {
+    #[serde(rename = "type")]
+    pub archetype_type: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
+    /// URI of resources, URI template of resource templates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    pub score: u32,
+}
+
+/// GET /api/search?q=&types= - Full-text search across archetype names, descriptions and tags
+pub async fn search(
+    State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<SearchQuery>,
+) -> impl IntoResponse {
+    use crate::domain::search::{query_terms, score, SearchDocument};
+
+    let types = match &query.types {
+        Some(types) => {
+            let parsed: Result<Vec<ArchetypeType>, String> = types
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(|t| {
+                    let archetype_type: ArchetypeType = t.parse()?;
+                    if !TAGGED_TYPES.contains(&archetype_type) {
+                        return Err(format!("Searching {} archetypes is not supported", t));
+                    }
+                    Ok(archetype_type)
+                })
+                .collect();
+            match parsed {
+                Ok(types) => types,
+                Err(e) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e))),
+            }
+        }
+        None => TAGGED_TYPES.to_vec(),
+    };
+
+    let terms = query_terms(&query.q);
+    let settings = state.settings.read().await;
+    let mut results = Vec::new();
+    let mut add = |archetype_type: ArchetypeType, name: &str, description: Option<&str>, tags: &[String], uri: Option<&str>| {
+        if let Some(score) = score(&terms, &SearchDocument { name, description, tags }) {
+            results.push(SearchResult {
+                archetype_type: archetype_type.as_str().to_string(),
+                name: name.to_string(),
+                description: description.map(str::to_string),
+                tags: tags.to_vec(),
+                uri: uri.map(str::to_string),
+                score,
+            });
+        }
+    };
+
+    for archetype_type in types {
+        match archetype_type {
+            ArchetypeType::Tool => settings.tools.iter()
+                .for_each(|t| add(archetype_type, &t.name, Some(&t.description), &t.tags, None)),
+            ArchetypeType::Resource => settings.resources.iter()
+                .for_each(|r| add(archetype_type, &r.name, r.description.as_deref(), &r.tags, Some(&r.uri))),
+            ArchetypeType::ResourceTemplate => settings.resource_templates.iter()
+                .for_each(|t| add(archetype_type, &t.name, t.description.as_deref(), &t.tags, Some(&t.uri_template))),
+            ArchetypeType::Prompt => settings.prompts.iter()
+                .for_each(|p| add(archetype_type, &p.name, Some(&p.description), &p.tags, None)),
+            ArchetypeType::Workflow => settings.workflows.iter()
+                .for_each(|w| add(archetype_type, &w.name, Some(&w.description), &w.tags, None)),
+            _ => {}
+        }
+    }
+
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    (StatusCode::OK, Json(ApiResponse::success(results)))
+}
+
+// ============================================================================
+// Bulk Tagging
+// ============================================================================
+
+/// Archetypes selected by a bulk tag update
+#[derive(Debug, Default, Deserialize)]
+pub struct BulkTagsFilter {
+    /// `tool`, `resource`, `resource_template`, `prompt` or `workflow`; all if omitted
+    #[serde(rename = "type", default)]
+    pub archetype_type: Option<String>,
+    /// Tags an item must all have to be updated
+    #[serde(default)]
+    pub current_tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkTagsRequest {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+    #[serde(default)]
+    pub filter: BulkTagsFilter,
+    /// Report the changes without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaggedItem {
+    #[serde(rename = "type")]
+    pub archetype_type: String,
+    pub name: String,
+    pub new_tags: Vec<String>,
+    /// Key of the item in the database: the name, or the URI for resources
+    #[serde(skip)]
+    key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkTagsResponse {
+    pub updated_count: usize,
+    pub items_updated: Vec<TaggedItem>,
+    pub dry_run: bool,
+}
+
+/// Archetype types that carry tags
+const TAGGED_TYPES: &[ArchetypeType] = &[
+    ArchetypeType::Tool,
+    ArchetypeType::Resource,
+    ArchetypeType::ResourceTemplate,
+    ArchetypeType::Prompt,
+    ArchetypeType::Workflow,
+];
+
+/// Tags after removing `remove` and appending `add`, or None if unchanged
+fn retag(tags: &[String], add: &[String], remove: &[String]) -> Option<Vec<String>> {
+    let mut new_tags: Vec<String> = tags.iter().filter(|t| !remove.contains(t)).cloned().collect();
+    for tag in add {
+        if !new_tags.contains(tag) {
+            new_tags.push(tag.clone());
+        }
+    }
+    (new_tags != tags).then_some(new_tags)
+}
+
+/// Retag the matching items in `settings`, applying the changes unless `dry_run`
+fn apply_bulk_tags(settings: &mut Settings, request: &BulkTagsRequest) -> Result<Vec<TaggedItem>, String> {
+    let types: Vec<ArchetypeType> = match &request.filter.archetype_type {
+        Some(name) => {
+            let archetype_type: ArchetypeType = name.parse()?;
+            if !TAGGED_TYPES.contains(&archetype_type) {
+                return Err(format!("Archetype type '{}' has no tags", name));
+            }
+            vec![archetype_type]
+        }
+        None => TAGGED_TYPES.to_vec(),
+    };
+
+    let wanted = |archetype_type: ArchetypeType| types.contains(&archetype_type);
+    let mut candidates: Vec<(ArchetypeType, &str, &str, &mut Vec<String>)> = Vec::new();
+    if wanted(ArchetypeType::Tool) {
+        candidates.extend(settings.tools.iter_mut().map(|t| (ArchetypeType::Tool, t.name.as_str(), t.name.as_str(), &mut t.tags)));
+    }
+    if wanted(ArchetypeType::Resource) {
+        candidates.extend(settings.resources.iter_mut().map(|r| (ArchetypeType::Resource, r.name.as_str(), r.uri.as_str(), &mut r.tags)));
+    }
+    if wanted(ArchetypeType::ResourceTemplate) {
+        candidates.extend(settings.resource_templates.iter_mut().map(|t| {
+            (ArchetypeType::ResourceTemplate, t.name.as_str(), t.uri_template.as_str(), &mut t.tags)
+        }));
+    }
+    if wanted(ArchetypeType::Prompt) {
+        candidates.extend(settings.prompts.iter_mut().map(|p| (ArchetypeType::Prompt, p.name.as_str(), p.name.as_str(), &mut p.tags)));
+    }
+    if wanted(ArchetypeType::Workflow) {
+        candidates.extend(settings.workflows.iter_mut().map(|w| (ArchetypeType::Workflow, w.name.as_str(), w.name.as_str(), &mut w.tags)));
+    }
+
+    let mut updated = Vec::new();
+    for (archetype_type, name, key, tags) in candidates {
+        if !request.filter.current_tags.iter().all(|t| tags.contains(t)) {
+            continue;
+        }
+        let Some(new_tags) = retag(tags, &request.add, &request.remove) else {
+            continue;
+        };
+        if !request.dry_run {
+            *tags = new_tags.clone();
+        }
+        updated.push(TaggedItem {
+            archetype_type: archetype_type.as_str().to_string(),
+            name: name.to_string(),
+            new_tags,
+            key: key.to_string(),
+        });
+    }
+    Ok(updated)
+}
+
+/// POST /api/tags/bulk - Add and remove tags on all archetypes matching a filter
+pub async fn bulk_update_tags(
+    State(state): State<ApiState>,
+    Json(request): Json<BulkTagsRequest>,
+) -> impl IntoResponse {
+    if request.add.is_empty() && request.remove.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<BulkTagsResponse>::error("Nothing to add or remove")),
+        );
+    }
+
+    let items_updated = {
+        let mut settings = state.edit_settings().await;
+        match apply_bulk_tags(&mut settings, &request) {
+            Ok(items) => items,
+            Err(e) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e))),
+        }
+    };
+
+    if !request.dry_run {
+        if let Some(store) = &state.data_store {
+            for item in &items_updated {
+                let result = match store.archetypes().get(&item.archetype_type, &item.key).await {
+                    Ok(Some(mut definition)) => {
+                        definition["tags"] = json!(item.new_tags);
+                        store.archetypes().update(&item.archetype_type, &item.key, &definition, None).await.map(|_| ())
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Failed to save tags of {} '{}': {}", item.archetype_type, item.key, e);
+                }
+            }
+        }
+
+        if let Some(broadcaster) = &state.broadcaster {
+            let changed = |types: &[ArchetypeType]| {
+                items_updated.iter().any(|i| types.iter().any(|t| t.as_str() == i.archetype_type))
+            };
+            if changed(&[ArchetypeType::Tool, ArchetypeType::Workflow]) {
+                broadcaster.notify_tools_changed().await;
+            }
+            if changed(&[ArchetypeType::Resource, ArchetypeType::ResourceTemplate]) {
+                broadcaster.notify_resources_changed().await;
+            }
+            if changed(&[ArchetypeType::Prompt]) {
+                broadcaster.notify_prompts_changed().await;
+            }
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(BulkTagsResponse {
+            updated_count: items_updated.len(),
+            items_updated,
+            dry_run: request.dry_run,
+        })),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_apply_bulk_tags() {
+        let mut settings: Settings = serde_json::from_value(json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [
+                {"name": "a", "description": "A", "input_schema": {}, "static_response": null, "tags": ["api", "old"]},
+                {"name": "b", "description": "B", "input_schema": {}, "static_response": null, "tags": ["internal"]},
+                {"name": "c", "description": "C", "input_schema": {}, "static_response": null, "tags": ["api", "v2"]}
+            ],
+            "resources": [{"uri": "file:///a", "name": "doc", "tags": ["api"]}]
+        }))
+        .unwrap();
+        let request = |dry_run: bool| -> BulkTagsRequest {
+            serde_json::from_value(json!({
+                "add": ["v2"], "remove": ["old"],
+                "filter": {"type": "tool", "current_tags": ["api"]},
+                "dry_run": dry_run
+            }))
+            .unwrap()
+        };
+
+        let items = apply_bulk_tags(&mut settings, &request(true)).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "a");
+        assert_eq!(items[0].new_tags, vec!["api", "v2"]);
+        assert_eq!(settings.tools[0].tags, vec!["api", "old"]);
+
+        apply_bulk_tags(&mut settings, &request(false)).unwrap();
+        assert_eq!(settings.tools[0].tags, vec!["api", "v2"]);
+        assert_eq!(settings.tools[1].tags, vec!["internal"]);
+        assert_eq!(settings.resources[0].tags, vec!["api"]);
+
+        let mut invalid = request(false);
+        invalid.filter.archetype_type = Some("agent".to_string());
+        assert!(apply_bulk_tags(&mut settings, &invalid).is_err());
+    }
+
     #[test]
     fn test_tool_dto_output_schema_serialization() {
         // Create a ToolDto with output_schema
@@ -5781,6 +7947,9 @@ mod tests {
             })),
             static_response: None,
             mock: None,
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
         };
 
         // Serialize to JSON Value (what happens before storing in DB)
@@ -5811,6 +7980,9 @@ mod tests {
             output_schema: None,
             static_response: None,
             mock: None,
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
         };
 
         // Serialize to JSON Value
@@ -5843,4 +8015,82 @@ mod tests {
         let out_schema = tool.output_schema.unwrap();
         assert!(out_schema["properties"]["result"]["type"] == "string");
     }
+
+    fn settings_with_tool() -> Settings {
+        serde_json::from_value(json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [{
+                "name": "existing",
+                "description": "Existing tool",
+                "input_schema": {"type": "object"},
+                "static_response": {"ok": true}
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn tool_operation(op: &str, name: &str, definition: Option<Value>) -> ConfigOperation {
+        serde_json::from_value(json!({
+            "op": op,
+            "type": "tool",
+            "name": name,
+            "definition": definition
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_config_operations_applies_batch() {
+        let mut settings = settings_with_tool();
+        let operations = vec![
+            tool_operation(
+                "create",
+                "new_tool",
+                Some(json!({
+                    "name": "new_tool",
+                    "description": "New tool",
+                    "input_schema": {"type": "object"},
+                    "static_response": "hi"
+                })),
+            ),
+            tool_operation("delete", "existing", None),
+        ];
+
+        let (changes, _) = apply_config_operations(&mut settings, &operations).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].operation, Operation::Delete);
+        assert!(changes[1].old_definition.is_some());
+        assert_eq!(settings.tools.len(), 1);
+        assert_eq!(settings.tools[0].name, "new_tool");
+    }
+
+    #[test]
+    fn test_apply_config_operations_rolls_back_on_failure() {
+        let mut settings = settings_with_tool();
+        let operations = vec![
+            tool_operation("delete", "existing", None),
+            tool_operation("update", "missing", Some(json!({"name": "missing"}))),
+        ];
+
+        let errors = apply_config_operations(&mut settings, &operations).unwrap_err();
+
+        assert!(errors[0].contains("not found"));
+        assert_eq!(settings.tools.len(), 1);
+        assert_eq!(settings.tools[0].name, "existing");
+    }
+
+    #[test]
+    fn test_apply_config_operations_rolls_back_on_validation_error() {
+        let mut settings = settings_with_tool();
+        // Tools need either a static response or a mock to pass validation
+        let operations = vec![tool_operation(
+            "create",
+            "invalid",
+            Some(json!({"name": "invalid", "description": "No response", "input_schema": {}})),
+        )];
+
+        assert!(apply_config_operations(&mut settings, &operations).is_err());
+        assert_eq!(settings.tools.len(), 1);
+    }
 }
@@ -0,0 +1,311 @@
+//! Bruno collection export
+//!
+//! Builds a ZIP archive of a [Bruno](https://www.usebruno.com/) collection from
+//! the current configuration. Every tool and resource becomes a `.bru` request
+//! against the MCP streamable HTTP endpoint, so the mock server can be explored
+//! from a Git-friendly API client.
+//!
+//! Layout of the archive:
+//! - `bruno.json`: collection manifest
+//! - `environments/Local.bru`: `baseUrl` pointing at this server
+//! - `initialize.bru`: opens an MCP session and stores its id
+//! - `tools/[<tag>/]<name>.bru`: one `tools/call` request per tool, grouped by first tag
+//! - `resources/<name>.bru`: one `resources/read` request per resource
+
+use crate::config::{ResourceConfig, Settings, ToolConfig};
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Name of the collection inside Bruno
+const COLLECTION_NAME: &str = "Metis";
+
+/// File name used for the downloaded archive
+pub const ARCHIVE_FILE_NAME: &str = "metis-bruno-collection.zip";
+
+/// Build the Bruno collection for `settings` as an in-memory ZIP archive
+pub fn export_collection(settings: &Settings) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    let manifest = json!({
+        "version": "1",
+        "name": COLLECTION_NAME,
+        "type": "collection",
+        "ignore": ["node_modules", ".git"]
+    });
+    add_file(&mut zip, options, "bruno.json", &serde_json::to_string_pretty(&manifest)?)?;
+
+    let base_url = format!("http://{}:{}", settings.server.host, settings.server.port);
+    add_file(
+        &mut zip,
+        options,
+        "environments/Local.bru",
+        &format!("vars {{\n  baseUrl: {}\n}}\n", base_url),
+    )?;
+
+    add_file(&mut zip, options, "initialize.bru", &initialize_request())?;
+
+    let mut used_paths = HashSet::new();
+    for (idx, tool) in settings.tools.iter().enumerate() {
+        let dir = match tool.tags.first() {
+            Some(tag) => format!("tools/{}", sanitize_file_name(tag)),
+            None => "tools".to_string(),
+        };
+        let path = unique_path(&mut used_paths, &dir, &tool.name);
+        add_file(&mut zip, options, &path, &tool_request(tool, idx + 1))?;
+    }
+
+    for (idx, resource) in settings.resources.iter().enumerate() {
+        let path = unique_path(&mut used_paths, "resources", &resource.name);
+        add_file(&mut zip, options, &path, &resource_request(resource, idx + 1))?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn add_file(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: SimpleFileOptions,
+    path: &str,
+    contents: &str,
+) -> Result<()> {
+    zip.start_file(path, options)?;
+    zip.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Request that opens an MCP session; its post-response script stores the
+/// session id so the tool and resource requests can reuse it
+fn initialize_request() -> String {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": {"name": "bruno", "version": "1.0.0"}
+        }
+    });
+
+    let mut bru = bru_request("initialize", 0, &body);
+    bru.push_str(
+        "\nscript:post-response {\n  bru.setVar(\"mcpSessionId\", res.getHeader(\"mcp-session-id\"));\n}\n",
+    );
+    bru
+}
+
+fn tool_request(tool: &ToolConfig, seq: usize) -> String {
+    // MCP requires an object here even for tools with an empty schema
+    let arguments = match example_from_schema(&tool.input_schema) {
+        Value::Object(args) => Value::Object(args),
+        _ => json!({}),
+    };
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": seq,
+        "method": "tools/call",
+        "params": {
+            "name": tool.name,
+            "arguments": arguments
+        }
+    });
+
+    let mut bru = bru_request(&tool.name, seq, &body);
+    if !tool.description.is_empty() {
+        bru.push_str(&docs_block(&tool.description));
+    }
+    bru
+}
+
+fn resource_request(resource: &ResourceConfig, seq: usize) -> String {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": seq,
+        "method": "resources/read",
+        "params": {"uri": resource.uri}
+    });
+
+    let mut bru = bru_request(&resource.name, seq, &body);
+    if let Some(description) = resource.description.as_deref().filter(|d| !d.is_empty()) {
+        bru.push_str(&docs_block(description));
+    }
+    bru
+}
+
+/// Render a JSON-RPC POST to the MCP endpoint in Bruno's `.bru` markup
+fn bru_request(name: &str, seq: usize, body: &Value) -> String {
+    let body = serde_json::to_string_pretty(body).unwrap_or_default();
+    format!(
+        "meta {{\n  name: {name}\n  type: http\n  seq: {seq}\n}}\n\n\
+         post {{\n  url: {{{{baseUrl}}}}/mcp\n  body: json\n  auth: none\n}}\n\n\
+         headers {{\n  Content-Type: application/json\n  Accept: application/json, text/event-stream\n  Mcp-Session-Id: {{{{mcpSessionId}}}}\n}}\n\n\
+         body:json {{\n{}\n}}\n",
+        indent(&body),
+    )
+}
+
+fn docs_block(text: &str) -> String {
+    format!("\ndocs {{\n{}\n}}\n", indent(text))
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build example arguments from a JSON Schema.
+///
+/// Declared `default`s are used first, then the first of `examples`. Required
+/// properties without either get a placeholder for their type; optional ones
+/// are left out.
+fn example_from_schema(schema: &Value) -> Value {
+    if let Some(default) = schema.get("default") {
+        return default.clone();
+    }
+    if let Some(example) = schema.get("examples").and_then(|e| e.as_array()).and_then(|e| e.first()) {
+        return example.clone();
+    }
+
+    let schema_type = schema.get("type").and_then(|t| t.as_str());
+    if schema_type == Some("object") || schema.get("properties").is_some() {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut args = Map::new();
+        if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+            for (name, property) in properties {
+                let has_example = property.get("default").is_some() || property.get("examples").is_some();
+                if has_example || required.contains(&name.as_str()) {
+                    args.insert(name.clone(), example_from_schema(property));
+                }
+            }
+        }
+        return Value::Object(args);
+    }
+
+    match schema_type {
+        Some("string") => Value::String(String::new()),
+        Some("integer") | Some("number") => json!(0),
+        Some("boolean") => Value::Bool(false),
+        Some("array") => json!([]),
+        _ => Value::Null,
+    }
+}
+
+/// Replace characters that are not safe in file or directory names
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.') { c } else { '_' })
+        .collect();
+    let trimmed = sanitized.trim().trim_matches('.');
+    if trimmed.is_empty() {
+        "unnamed".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Build `<dir>/<name>.bru`, adding a numeric suffix if the path is taken
+fn unique_path(used: &mut HashSet<String>, dir: &str, name: &str) -> String {
+    let base = sanitize_file_name(name);
+    let mut path = format!("{}/{}.bru", dir, base);
+    let mut counter = 2;
+    while !used.insert(path.clone()) {
+        path = format!("{}/{}_{}.bru", dir, base, counter);
+        counter += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn read_archive(bytes: Vec<u8>) -> ZipArchive<Cursor<Vec<u8>>> {
+        ZipArchive::new(Cursor::new(bytes)).unwrap()
+    }
+
+    fn read_file(archive: &mut ZipArchive<Cursor<Vec<u8>>>, path: &str) -> String {
+        let mut contents = String::new();
+        archive.by_name(path).unwrap().read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_export_collection_layout() {
+        let settings: Settings = serde_json::from_value(json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [
+                {
+                    "name": "get_weather",
+                    "description": "Weather lookup",
+                    "tags": ["weather"],
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {
+                            "city": {"type": "string"},
+                            "units": {"type": "string", "default": "metric"},
+                            "days": {"type": "integer"}
+                        },
+                        "required": ["city"]
+                    },
+                    "static_response": {"ok": true}
+                },
+                {"name": "echo", "description": "", "input_schema": {}, "static_response": null}
+            ],
+            "resources": [
+                {"uri": "file:///data.json", "name": "Data", "description": null, "mime_type": null, "content": "{}"}
+            ]
+        }))
+        .unwrap();
+
+        let mut archive = read_archive(export_collection(&settings).unwrap());
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "bruno.json",
+                "environments/Local.bru",
+                "initialize.bru",
+                "resources/Data.bru",
+                "tools/echo.bru",
+                "tools/weather/get_weather.bru",
+            ]
+        );
+
+        let manifest: Value = serde_json::from_str(&read_file(&mut archive, "bruno.json")).unwrap();
+        assert_eq!(manifest["type"], "collection");
+
+        let tool = read_file(&mut archive, "tools/weather/get_weather.bru");
+        assert!(tool.contains("url: {{baseUrl}}/mcp"));
+        assert!(tool.contains("\"method\": \"tools/call\""));
+        assert!(tool.contains("\"units\": \"metric\""));
+        assert!(tool.contains("\"city\": \"\""));
+        assert!(!tool.contains("\"days\""));
+
+        let resource = read_file(&mut archive, "resources/Data.bru");
+        assert!(resource.contains("\"uri\": \"file:///data.json\""));
+    }
+
+    #[test]
+    fn test_unique_path_sanitizes_and_dedupes() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_path(&mut used, "tools", "a/b"), "tools/a_b.bru");
+        assert_eq!(unique_path(&mut used, "tools", "a:b"), "tools/a_b_2.bru");
+        assert_eq!(unique_path(&mut used, "tools", ".."), "tools/unnamed.bru");
+    }
+}
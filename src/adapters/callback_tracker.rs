@@ -0,0 +1,228 @@
+//! Delayed HTTP callbacks for the callback mock strategy
+//!
+//! Tools with asynchronous semantics acknowledge a call immediately and report
+//! the result later. The tracker sends those callbacks in the background and
+//! keeps a record of each one so tests can assert on what was (or will be) sent.
+
+use dashmap::DashMap;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Finished callbacks kept for inspection; older ones are dropped first
+const MAX_FINISHED_RECORDS: usize = 1000;
+
+/// Delivery state of a callback
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum CallbackStatus {
+    /// Waiting for its delay to elapse or for the request to complete
+    Pending,
+    /// The target responded (with any status code)
+    Delivered { status_code: u16 },
+    /// The request could not be sent
+    Failed { error: String },
+}
+
+/// A scheduled callback
+#[derive(Debug, Clone, Serialize)]
+pub struct CallbackRecord {
+    pub id: String,
+    pub url: String,
+    pub payload: Value,
+    /// Unix epoch milliseconds when the callback was scheduled
+    pub scheduled_at: u64,
+    /// Unix epoch milliseconds when the callback is due to be sent
+    pub deliver_at: u64,
+    pub status: CallbackStatus,
+}
+
+/// Sends scheduled callbacks and records their outcome
+pub struct CallbackTracker {
+    records: Arc<DashMap<String, CallbackRecord>>,
+    client: reqwest::Client,
+}
+
+impl Default for CallbackTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallbackTracker {
+    pub fn new() -> Self {
+        Self {
+            records: Arc::new(DashMap::new()),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// POST `payload` to `url` as JSON after `delay`, in the background.
+    /// Fails if the delivery time is beyond what a timestamp can hold.
+    pub fn schedule(
+        &self,
+        id: String,
+        url: String,
+        payload: Value,
+        headers: HashMap<String, String>,
+        delay: Duration,
+    ) -> anyhow::Result<()> {
+        let now = now_millis();
+        let deliver_at = deliver_at(now, delay)
+            .ok_or_else(|| anyhow::anyhow!("Callback delay of {} ms is too long", delay.as_millis()))?;
+        self.records.insert(
+            id.clone(),
+            CallbackRecord {
+                id: id.clone(),
+                url: url.clone(),
+                payload: payload.clone(),
+                scheduled_at: now,
+                deliver_at,
+                status: CallbackStatus::Pending,
+            },
+        );
+        self.prune();
+
+        let records = self.records.clone();
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            let mut request = client.post(&url).json(&payload);
+            for (name, value) in &headers {
+                request = request.header(name, value);
+            }
+            let status = match request.send().await {
+                Ok(response) => CallbackStatus::Delivered {
+                    status_code: response.status().as_u16(),
+                },
+                Err(e) => {
+                    tracing::warn!("Callback {} to {} failed: {}", id, url, e);
+                    CallbackStatus::Failed { error: e.to_string() }
+                }
+            };
+
+            if let Some(mut record) = records.get_mut(&id) {
+                record.status = status;
+            }
+        });
+        Ok(())
+    }
+
+    /// All recorded callbacks, oldest first. With `pending_only`, only those not yet sent.
+    pub fn list(&self, pending_only: bool) -> Vec<CallbackRecord> {
+        let mut records: Vec<CallbackRecord> = self
+            .records
+            .iter()
+            .filter(|r| !pending_only || r.status == CallbackStatus::Pending)
+            .map(|r| r.value().clone())
+            .collect();
+        records.sort_by_key(|r| r.scheduled_at);
+        records
+    }
+
+    /// Forget all recorded callbacks. Pending ones are still sent.
+    pub fn clear(&self) {
+        self.records.clear();
+    }
+
+    /// Drop the oldest finished records once there are too many
+    fn prune(&self) {
+        let mut finished: Vec<(u64, String)> = self
+            .records
+            .iter()
+            .filter(|r| r.status != CallbackStatus::Pending)
+            .map(|r| (r.scheduled_at, r.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_RECORDS {
+            return;
+        }
+
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_RECORDS] {
+            self.records.remove(id);
+        }
+    }
+}
+
+/// Unix epoch milliseconds `delay` after `now`, unless that overflows
+pub fn deliver_at(now: u64, delay: Duration) -> Option<u64> {
+    now.checked_add(u64::try_from(delay.as_millis()).ok()?)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_callback_is_delivered_after_delay() {
+        let (tx, mut rx) = mpsc::channel::<(Option<String>, Value)>(1);
+        let app = Router::new().route(
+            "/hook",
+            post(|State(tx): State<mpsc::Sender<(Option<String>, Value)>>, headers: HeaderMap, Json(body): Json<Value>| async move {
+                let token = headers.get("x-token").and_then(|v| v.to_str().ok()).map(String::from);
+                tx.send((token, body)).await.unwrap();
+            }),
+        )
+        .with_state(tx);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let tracker = CallbackTracker::new();
+        tracker.schedule(
+            "cb-1".to_string(),
+            format!("http://{}/hook", addr),
+            json!({"job": 7, "done": true}),
+            HashMap::from([("x-token".to_string(), "secret".to_string())]),
+            Duration::from_millis(20),
+        )
+        .unwrap();
+        assert_eq!(tracker.list(true).len(), 1);
+
+        let (token, body) = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(token.as_deref(), Some("secret"));
+        assert_eq!(body, json!({"job": 7, "done": true}));
+
+        // The status is updated once the response has been read
+        for _ in 0..50 {
+            if tracker.list(true).is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let records = tracker.list(false);
+        assert_eq!(records[0].status, CallbackStatus::Delivered { status_code: 200 });
+
+        tracker.clear();
+        assert!(tracker.list(false).is_empty());
+    }
+
+    #[test]
+    fn test_overlong_delay_is_refused() {
+        let tracker = CallbackTracker::new();
+        let err = tracker
+            .schedule(
+                "cb-1".to_string(),
+                "http://127.0.0.1:9/hook".to_string(),
+                Value::Null,
+                HashMap::new(),
+                Duration::from_millis(u64::MAX),
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), format!("Callback delay of {} ms is too long", u64::MAX));
+        assert!(tracker.list(false).is_empty());
+    }
+}
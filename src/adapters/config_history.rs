@@ -0,0 +1,178 @@
+//! Undo/redo history of configuration edits
+//!
+//! API handlers that edit the settings take the write lock through
+//! [`ConfigEdit`], which pushes the settings it replaced onto the undo stack,
+//! up to `server.config_history_depth` snapshots. Undoing moves the current
+//! settings to the redo stack; any new edit clears it. Snapshots are kept
+//! serialized, the same way settings are copied elsewhere.
+
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use tokio::sync::RwLockWriteGuard;
+
+use crate::config::Settings;
+
+#[derive(Default)]
+struct Stacks {
+    undo: VecDeque<Value>,
+    redo: Vec<Value>,
+}
+
+/// Bounded history of settings snapshots
+#[derive(Default)]
+pub struct ConfigHistory {
+    stacks: Mutex<Stacks>,
+}
+
+impl ConfigHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the settings as they were before an edit, keeping at most `depth` snapshots
+    pub fn record(&self, previous: Value, depth: usize) {
+        let mut stacks = self.stacks.lock().unwrap();
+        stacks.redo.clear();
+        stacks.undo.push_back(previous);
+        while stacks.undo.len() > depth {
+            stacks.undo.pop_front();
+        }
+    }
+
+    /// Settings before the last edit; `current` becomes redoable
+    pub fn undo(&self, current: Value) -> Option<Value> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let previous = stacks.undo.pop_back()?;
+        stacks.redo.push(current);
+        Some(previous)
+    }
+
+    /// Settings before the last undo; `current` becomes undoable again
+    pub fn redo(&self, current: Value) -> Option<Value> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let next = stacks.redo.pop()?;
+        stacks.undo.push_back(current);
+        Some(next)
+    }
+
+    /// Number of snapshots available to undo and to redo
+    pub fn depths(&self) -> (usize, usize) {
+        let stacks = self.stacks.lock().unwrap();
+        (stacks.undo.len(), stacks.redo.len())
+    }
+}
+
+/// Write access to the settings for an edit that undo can revert. The
+/// settings are snapshotted when the lock is taken, and if the edit changed
+/// them the snapshot is recorded before the lock is released, so a concurrent
+/// edit can't land between the snapshot and the change.
+pub struct ConfigEdit<'a> {
+    settings: RwLockWriteGuard<'a, Settings>,
+    history: &'a ConfigHistory,
+    /// Settings before the edit, unless history is off
+    before: Option<Value>,
+}
+
+impl<'a> ConfigEdit<'a> {
+    pub fn new(settings: RwLockWriteGuard<'a, Settings>, history: &'a ConfigHistory) -> Self {
+        let before = if settings.server.config_history_depth > 0 {
+            serde_json::to_value(&*settings).ok()
+        } else {
+            None
+        };
+        Self { settings, history, before }
+    }
+}
+
+impl Deref for ConfigEdit<'_> {
+    type Target = Settings;
+
+    fn deref(&self) -> &Settings {
+        &self.settings
+    }
+}
+
+impl DerefMut for ConfigEdit<'_> {
+    fn deref_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+}
+
+impl Drop for ConfigEdit<'_> {
+    fn drop(&mut self) {
+        let Some(before) = self.before.take() else {
+            return;
+        };
+        let depth = self.settings.server.config_history_depth;
+        let after = serde_json::to_value(&*self.settings);
+        if depth > 0 && after.is_ok_and(|after| after != before) {
+            self.history.record(before, depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(port: u16) -> Value {
+        serde_json::json!({"server": {"port": port}})
+    }
+
+    fn port(snapshot: Option<Value>) -> u64 {
+        snapshot.unwrap()["server"]["port"].as_u64().unwrap()
+    }
+
+    #[test]
+    fn test_undo_and_redo_walk_snapshots() {
+        let history = ConfigHistory::new();
+        history.record(snapshot(1), 10);
+        history.record(snapshot(2), 10);
+
+        assert_eq!(port(history.undo(snapshot(3))), 2);
+        assert_eq!(port(history.undo(snapshot(2))), 1);
+        assert!(history.undo(snapshot(1)).is_none());
+        assert_eq!(history.depths(), (0, 2));
+
+        assert_eq!(port(history.redo(snapshot(1))), 2);
+        assert_eq!(history.depths(), (1, 1));
+
+        // A new edit discards what could be redone
+        history.record(snapshot(2), 10);
+        assert!(history.redo(snapshot(4)).is_none());
+        assert_eq!(history.depths(), (2, 0));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let history = ConfigHistory::new();
+        for n in 1..=5 {
+            history.record(snapshot(n), 3);
+        }
+
+        assert_eq!(history.depths(), (3, 0));
+        assert_eq!(port(history.undo(snapshot(6))), 5);
+        assert_eq!(port(history.undo(snapshot(5))), 4);
+        assert_eq!(port(history.undo(snapshot(4))), 3);
+        assert!(history.undo(snapshot(3)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_config_edit_records_changes_only() {
+        let settings = tokio::sync::RwLock::new(
+            serde_json::from_value::<Settings>(serde_json::json!({"server": {"host": "127.0.0.1", "port": 3000}}))
+                .unwrap(),
+        );
+        let history = ConfigHistory::new();
+
+        drop(ConfigEdit::new(settings.write().await, &history));
+        assert_eq!(history.depths(), (0, 0));
+
+        ConfigEdit::new(settings.write().await, &history).server.port = 4000;
+        assert_eq!(history.depths(), (1, 0));
+        let current = serde_json::to_value(&*settings.read().await).unwrap();
+        assert_eq!(port(history.undo(current)), 3000);
+    }
+}
@@ -0,0 +1,210 @@
+//! `Accept`-based response formats for config endpoints
+//!
+//! JSON responses keep the usual `ApiResponse` envelope. YAML and TOML
+//! responses contain just the value, so they can be saved as config files.
+//! List endpoints can also stream their items as NDJSON, see [`Ndjson`].
+
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::adapters::api_handler::ApiResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ResponseFormat {
+    /// First supported media type in the `Accept` header; JSON if none is
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(|media_type| {
+                let media_type = media_type.split(';').next().unwrap_or_default().trim();
+                match media_type.to_ascii_lowercase().as_str() {
+                    "application/json" => Some(Self::Json),
+                    "application/yaml" | "application/x-yaml" | "text/yaml" => Some(Self::Yaml),
+                    "application/toml" => Some(Self::Toml),
+                    _ => None,
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Yaml => "application/yaml",
+            Self::Toml => "application/toml",
+        }
+    }
+}
+
+/// A successful response serialized in the negotiated format
+pub struct Negotiated<T> {
+    pub format: ResponseFormat,
+    pub value: T,
+}
+
+impl<T> Negotiated<T> {
+    pub fn new(headers: &HeaderMap, value: T) -> Self {
+        Self {
+            format: ResponseFormat::from_headers(headers),
+            value,
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let body = match self.format {
+            ResponseFormat::Json => {
+                return (StatusCode::OK, Json(ApiResponse::success(self.value))).into_response();
+            }
+            ResponseFormat::Yaml => serde_yaml::to_string(&self.value).map_err(|e| e.to_string()),
+            ResponseFormat::Toml => to_toml(&self.value),
+        };
+
+        match body {
+            Ok(body) => (StatusCode::OK, [(header::CONTENT_TYPE, self.format.content_type())], body).into_response(),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to serialize response as {}: {}",
+                    self.format.content_type(),
+                    e
+                ))),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Media type of newline-delimited JSON
+pub const NDJSON: &str = "application/x-ndjson";
+
+/// Whether the `Accept` header asks for NDJSON
+pub fn accepts_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_type| {
+            let media_type = media_type.split(';').next().unwrap_or_default().trim();
+            media_type.eq_ignore_ascii_case(NDJSON)
+        })
+}
+
+/// Items streamed as newline-delimited JSON, without an envelope. Each item
+/// is serialized as it arrives, so the whole list is never held as one body.
+/// An error from the stream aborts the response.
+pub struct Ndjson<S>(pub S);
+
+impl<S, T, E> IntoResponse for Ndjson<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn into_response(self) -> Response {
+        let lines = self.0.map(|item| -> Result<Bytes, Box<dyn std::error::Error + Send + Sync>> {
+            let mut line = serde_json::to_vec(&item?)?;
+            line.push(b'\n');
+            Ok(Bytes::from(line))
+        });
+        (StatusCode::OK, [(header::CONTENT_TYPE, NDJSON)], Body::from_stream(lines)).into_response()
+    }
+}
+
+/// TOML has no null, so values holding nulls (e.g. inside JSON schemas) are
+/// retried with the nulls dropped
+fn to_toml<T: Serialize>(value: &T) -> Result<String, String> {
+    toml::to_string_pretty(value).or_else(|_| {
+        let mut value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+        strip_nulls(&mut value);
+        toml::to_string_pretty(&value).map_err(|e| e.to_string())
+    })
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => {
+            items.retain(|v| !v.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_format_from_accept_header() {
+        assert_eq!(ResponseFormat::from_headers(&HeaderMap::new()), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_headers(&accept("application/yaml")), ResponseFormat::Yaml);
+        assert_eq!(ResponseFormat::from_headers(&accept("text/html, application/toml;q=0.9")), ResponseFormat::Toml);
+        assert_eq!(ResponseFormat::from_headers(&accept("*/*")), ResponseFormat::Json);
+    }
+
+    #[tokio::test]
+    async fn test_negotiated_bodies() {
+        let value = json!({"server": {"host": "0.0.0.0", "port": 3000}, "note": null});
+
+        let response = Negotiated::new(&accept("application/yaml"), value.clone()).into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/yaml");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: Value = serde_yaml::from_slice(&body).unwrap();
+        assert_eq!(parsed, value);
+
+        let response = Negotiated::new(&accept("application/toml"), value.clone()).into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/toml");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: Value = toml::from_str(std::str::from_utf8(&body).unwrap()).unwrap();
+        assert_eq!(parsed, json!({"server": {"host": "0.0.0.0", "port": 3000}}));
+
+        let response = Negotiated::new(&HeaderMap::new(), value.clone()).into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed, json!({"success": true, "data": value}));
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_body() {
+        assert!(accepts_ndjson(&accept("application/json, application/x-ndjson")));
+        assert!(!accepts_ndjson(&accept("application/json")));
+
+        let items = futures::stream::iter([json!({"name": "a"}), json!({"name": "b"})].map(Ok::<_, std::io::Error>));
+        let response = Ndjson(items).into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], NDJSON);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(std::str::from_utf8(&body).unwrap(), "{\"name\":\"a\"}\n{\"name\":\"b\"}\n");
+    }
+}
@@ -0,0 +1,96 @@
+//! Parsed files of the csv mock strategy
+//!
+//! Csv-strategy mocks keep the parsed rows of each file in memory instead of
+//! re-reading and re-parsing it on every call. Each entry remembers the size
+//! and modification time of the file it was parsed from, and a call whose file
+//! no longer matches parses it again, so edits are picked up without watching
+//! the directory.
+
+use dashmap::DashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Header and rows of a CSV file
+pub struct CsvTable {
+    pub headers: csv::StringRecord,
+    pub rows: Vec<csv::StringRecord>,
+}
+
+/// Size and modification time of a file, telling whether it changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    pub fn of(metadata: &std::fs::Metadata) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+/// CSV tables keyed by the canonical path of their file
+#[derive(Default)]
+pub struct CsvCache {
+    entries: DashMap<PathBuf, (FileStamp, Arc<CsvTable>)>,
+}
+
+impl CsvCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cache key of a CSV path: the canonical path if the file exists
+    fn key(path: &Path) -> PathBuf {
+        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// The table parsed from `path`, unless the file has changed since
+    pub fn get(&self, path: &str, stamp: FileStamp) -> Option<Arc<CsvTable>> {
+        self.entries
+            .get(&Self::key(Path::new(path)))
+            .filter(|entry| entry.value().0 == stamp)
+            .map(|entry| entry.value().1.clone())
+    }
+
+    pub fn insert(&self, path: &str, stamp: FileStamp, table: CsvTable) -> Arc<CsvTable> {
+        let table = Arc::new(table);
+        self.entries.insert(Self::key(Path::new(path)), (stamp, table.clone()));
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_file_misses_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("products.csv");
+        std::fs::write(&path, "sku\nA-1\n").unwrap();
+        let path = path.to_str().unwrap();
+        let stamp = FileStamp::of(&std::fs::metadata(path).unwrap());
+
+        let cache = CsvCache::new();
+        let mut reader = csv::Reader::from_reader("sku\nA-1\n".as_bytes());
+        let table = CsvTable {
+            headers: reader.headers().unwrap().clone(),
+            rows: reader.records().collect::<Result<_, _>>().unwrap(),
+        };
+        cache.insert(path, stamp, table);
+        assert_eq!(cache.get(path, stamp).unwrap().rows.len(), 1);
+
+        let grown = FileStamp { len: stamp.len + 4, ..stamp };
+        assert!(cache.get(path, grown).is_none());
+        let touched = FileStamp {
+            modified: stamp.modified.map(|m| m + std::time::Duration::from_secs(1)),
+            ..stamp
+        };
+        assert!(cache.get(path, touched).is_none());
+    }
+}
@@ -11,7 +11,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::adapters::api_handler::{ApiState, sync_item_to_s3_if_active, delete_item_from_s3_if_active};
+use crate::adapters::api_handler::{ApiState, sync_item_to_s3_if_active, delete_item_from_s3_if_active, validate_item_on_write};
 use crate::adapters::mock_strategy::MockStrategyHandler;
 use crate::config::{DataLakeConfig, DataLakeSchemaRef, DataRecord, FakerSchemaConfig};
 use crate::persistence::models::ArchetypeType;
@@ -397,6 +397,11 @@ pub async fn create_data_lake(
     State(state): State<ApiState>,
     Json(dto): Json<DataLakeDto>,
 ) -> impl IntoResponse {
+    if let Err((status, Json(response))) =
+        validate_item_on_write::<()>(&state, ArchetypeType::DataLake, &DataLakeConfig::from(dto.clone())).await
+    {
+        return (status, Json(ApiResponse::<DataLakeDto>::error(response.error.unwrap_or_default())));
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -433,7 +438,7 @@ pub async fn create_data_lake(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check for duplicate name
     if settings.data_lakes.iter().any(|d| d.name == dto.name) {
@@ -461,6 +466,11 @@ pub async fn update_data_lake(
     Path(name): Path<String>,
     Json(dto): Json<DataLakeDto>,
 ) -> impl IntoResponse {
+    if let Err((status, Json(response))) =
+        validate_item_on_write::<()>(&state, ArchetypeType::DataLake, &DataLakeConfig::from(dto.clone())).await
+    {
+        return (status, Json(ApiResponse::<DataLakeDto>::error(response.error.unwrap_or_default())));
+    }
     // Use database if available
     if let Some(store) = &state.data_store {
         let definition = match serde_json::to_value(&dto) {
@@ -500,7 +510,7 @@ pub async fn update_data_lake(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     // Check if name is being changed and would conflict
     if dto.name != name {
@@ -577,7 +587,7 @@ pub async fn delete_data_lake(
     }
 
     // Fallback to in-memory settings
-    let mut settings = state.settings.write().await;
+    let mut settings = state.edit_settings().await;
 
     let initial_len = settings.data_lakes.len();
     settings.data_lakes.retain(|d| d.name != name);
@@ -809,7 +819,7 @@ pub async fn create_record(
     // Handle file storage if needed
     if storage_mode.uses_files() {
         if let Some(file_storage) = &state.file_storage {
-            match file_storage.write_records(&name, &req.schema_name, &[record.clone()], file_format).await {
+            match file_storage.write_records(&name, &req.schema_name, std::slice::from_ref(&record), file_format).await {
                 Ok(_path) => {
                     // File write successful
                 }
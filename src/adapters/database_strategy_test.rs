@@ -71,13 +71,23 @@ async fn test_generate_database_sqlite() {
         llm: None,
         database: Some(DatabaseConfig {
             url: db_url.clone(),
+            connection: None,
             query: "SELECT name FROM users WHERE id = ?".to_string(),
             params: vec!["user_id".to_string()],
             db_type: crate::config::DatabaseType::Sqlite,
+            max_connections: 1,
+            result_mode: Default::default(),
+            column_aliases: Default::default(),
+            seed_file: None,
             datafusion: None,
         }),
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let args = json!({ "user_id": 1 });
@@ -99,3 +109,316 @@ async fn test_generate_database_sqlite() {
     assert_eq!(arr.len(), 1);
     assert_eq!(arr[0]["name"], "Alice");
 }
+
+#[tokio::test]
+async fn test_database_strategy_reuses_pool() {
+    use crate::adapters::db_strategy_pool::DatabaseStrategyPool;
+    use crate::adapters::metrics_handler::MetricsCollector;
+
+    let db_path = "test_db_pool.sqlite";
+    let _ = std::fs::remove_file(db_path);
+    let db_url = format!("sqlite://{}?mode=rwc", db_path);
+
+    let pool = SqlitePoolOptions::new()
+        .connect(&db_url)
+        .await
+        .expect("Failed to connect to SQLite");
+    sqlx::query("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    sqlx::query("INSERT INTO items (name) VALUES ('widget')")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert data");
+    pool.close().await;
+
+    let metrics = Arc::new(MetricsCollector::new().unwrap());
+    let db_pools = Arc::new(DatabaseStrategyPool::new().with_metrics(metrics.clone()));
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new())).with_db_pools(db_pools.clone());
+    let config: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"url": db_url, "query": "SELECT name FROM items", "db_type": "sqlite", "max_connections": 2}
+    }))
+    .unwrap();
+
+    for _ in 0..3 {
+        let value = handler.generate(&config, None).await.unwrap();
+        assert_eq!(value, json!([{"name": "widget"}]));
+    }
+
+    // All calls share one pool, which never exceeds max_connections
+    assert_eq!(db_pools.len(), 1);
+    assert_eq!(metrics.db_strategy_connections_acquired.get(), 3.0);
+    let open = metrics.db_strategy_pool_size.get();
+    assert!((1.0..=2.0).contains(&open), "unexpected pool size {}", open);
+
+    db_pools.close_all().await;
+    assert!(db_pools.is_empty());
+    assert_eq!(metrics.db_strategy_pool_size.get(), 0.0);
+
+    let _ = std::fs::remove_file(db_path);
+}
+
+#[test]
+fn test_database_placeholder_count() {
+    let count = |query: &str| -> usize {
+        let config: DatabaseConfig = serde_json::from_value(json!({"url": "", "query": query})).unwrap();
+        config.placeholder_count()
+    };
+
+    assert_eq!(count("SELECT * FROM users"), 0);
+    assert_eq!(count("SELECT * FROM users WHERE id = ? AND name = ?"), 2);
+    assert_eq!(count("SELECT * FROM users WHERE id = $2 OR parent = $1 OR owner = $2"), 2);
+    assert_eq!(count("SELECT * FROM users WHERE id = ?1 OR parent = ?1"), 1);
+    // Question marks in literals, quoted identifiers and comments are not placeholders
+    assert_eq!(count("SELECT 'why?', \"what?\" FROM t WHERE a = ? -- or b = ?\n/* c = ? */"), 1);
+    assert_eq!(count("SELECT * FROM $table WHERE a = 'it''s ?'"), 0);
+    // Nor in dollar-quoted strings
+    assert_eq!(count("SELECT $$why? $1$$, $body$ it's ? $body$ WHERE a = $1"), 1);
+    assert_eq!(count("SELECT $$unterminated ?"), 0);
+
+    // Postgres binds `$N` only, so `?` is the jsonb operator there
+    let postgres = |db_type: &str, url: &str, query: &str| -> usize {
+        let config: DatabaseConfig =
+            serde_json::from_value(json!({"url": url, "query": query, "db_type": db_type})).unwrap();
+        config.placeholder_count()
+    };
+    let query = "SELECT * FROM docs WHERE data ? 'tag' AND data ?| $1";
+    assert_eq!(postgres("postgres", "", query), 1);
+    assert_eq!(postgres("sqlite", "postgres://localhost/app", query), 1);
+    assert_eq!(postgres("sqlite", "sqlite::memory:", "SELECT ? ?"), 2);
+}
+
+#[tokio::test]
+async fn test_database_strategy_binds_injection_payload() {
+    let db_path = "test_db_injection.sqlite";
+    let _ = std::fs::remove_file(db_path);
+    let db_url = format!("sqlite://{}?mode=rwc", db_path);
+
+    let pool = SqlitePoolOptions::new()
+        .connect(&db_url)
+        .await
+        .expect("Failed to connect to SQLite");
+    sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    sqlx::query("INSERT INTO users (name) VALUES ('Alice'), ('Bob')")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert data");
+    pool.close().await;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"url": db_url, "query": "SELECT name FROM users WHERE name = ?", "params": ["name"]}
+    }))
+    .unwrap();
+
+    // The payload is bound as a plain string, so it matches nothing and drops nothing
+    let payload = json!({"name": "Alice' OR '1'='1'; DROP TABLE users; --"});
+    let value = handler.generate(&config, Some(&payload)).await.unwrap();
+    assert_eq!(value, json!([]));
+
+    let value = handler.generate(&config, Some(&json!({"name": "Bob"}))).await.unwrap();
+    assert_eq!(value, json!([{"name": "Bob"}]));
+
+    // A params list that doesn't match the placeholders is rejected before querying
+    let mismatched: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"url": db_url, "query": "SELECT name FROM users WHERE name = ?", "params": []}
+    }))
+    .unwrap();
+    let err = handler.generate(&mismatched, None).await.unwrap_err();
+    assert!(err.to_string().contains("1 placeholder(s) but 0 param(s)"), "{}", err);
+
+    handler.db_pools().close_all().await;
+    let _ = std::fs::remove_file(db_path);
+}
+
+#[tokio::test]
+async fn test_database_strategy_uses_named_connection() {
+    use crate::adapters::db_strategy_pool::DatabaseStrategyPool;
+    use crate::config::Settings;
+    use tokio::sync::RwLock;
+
+    let db_path = "test_db_named.sqlite";
+    let _ = std::fs::remove_file(db_path);
+    let db_url = format!("sqlite://{}?mode=rwc", db_path);
+
+    let pool = SqlitePoolOptions::new()
+        .connect(&db_url)
+        .await
+        .expect("Failed to connect to SQLite");
+    sqlx::query("CREATE TABLE orders (id INTEGER PRIMARY KEY, status TEXT)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    sqlx::query("INSERT INTO orders (status) VALUES ('shipped')")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert data");
+    pool.close().await;
+
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "databases": {"orders": {"url": db_url, "max_connections": 2}}
+    }))
+    .unwrap();
+    let db_pools = Arc::new(DatabaseStrategyPool::new());
+    let handler = MockStrategyHandler::new_with_datafusion(
+        Arc::new(StateManager::new()),
+        None,
+        Some(Arc::new(RwLock::new(settings))),
+        None,
+    )
+    .with_db_pools(db_pools.clone());
+
+    let by_id: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"connection": "orders", "query": "SELECT status FROM orders WHERE id = ?", "params": ["id"]}
+    }))
+    .unwrap();
+    let count: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"connection": "orders", "query": "SELECT COUNT(*) AS total FROM orders"}
+    }))
+    .unwrap();
+
+    let value = handler.generate(&by_id, Some(&json!({"id": 1}))).await.unwrap();
+    assert_eq!(value, json!([{"status": "shipped"}]));
+    let value = handler.generate(&count, None).await.unwrap();
+    assert_eq!(value, json!([{"total": 1}]));
+
+    // Both tools share the named database's pool
+    assert_eq!(db_pools.len(), 1);
+
+    let unknown: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"connection": "billing", "query": "SELECT 1"}
+    }))
+    .unwrap();
+    let err = handler.generate(&unknown, None).await.unwrap_err();
+    assert_eq!(err.to_string(), "Unknown database connection 'billing'");
+
+    db_pools.close_all().await;
+    let _ = std::fs::remove_file(db_path);
+}
+
+#[tokio::test]
+async fn test_database_strategy_shapes_result() {
+    let db_path = "test_db_shape.sqlite";
+    let _ = std::fs::remove_file(db_path);
+    let db_url = format!("sqlite://{}?mode=rwc", db_path);
+
+    let pool = SqlitePoolOptions::new()
+        .connect(&db_url)
+        .await
+        .expect("Failed to connect to SQLite");
+    sqlx::query("CREATE TABLE products (id INTEGER PRIMARY KEY, product_name TEXT)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    sqlx::query("INSERT INTO products (product_name) VALUES ('lamp'), ('desk')")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert data");
+    pool.close().await;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let generate = |database: serde_json::Value| {
+        let handler = &handler;
+        let db_url = db_url.clone();
+        async move {
+            let mut database = database;
+            database["url"] = json!(db_url);
+            let config: MockConfig =
+                serde_json::from_value(json!({"strategy": "database", "database": database})).unwrap();
+            handler.generate(&config, None).await
+        }
+    };
+
+    let rows = generate(json!({
+        "query": "SELECT id, product_name FROM products ORDER BY id",
+        "column_aliases": {"product_name": "name"}
+    }))
+    .await
+    .unwrap();
+    assert_eq!(rows, json!([{"id": 1, "name": "lamp"}, {"id": 2, "name": "desk"}]));
+
+    let first = generate(json!({
+        "query": "SELECT id, product_name FROM products ORDER BY id DESC",
+        "result_mode": "first_row",
+        "column_aliases": {"product_name": "name"}
+    }))
+    .await
+    .unwrap();
+    assert_eq!(first, json!({"id": 2, "name": "desk"}));
+
+    let none = generate(json!({"query": "SELECT id FROM products WHERE id > 5", "result_mode": "first_row"}))
+        .await
+        .unwrap();
+    assert_eq!(none, json!(null));
+
+    let scalar = generate(json!({"query": "SELECT COUNT(*) AS total FROM products", "result_mode": "scalar"}))
+        .await
+        .unwrap();
+    assert_eq!(scalar, json!(2));
+
+    let err = generate(json!({"query": "SELECT id, product_name FROM products", "result_mode": "scalar"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("single column"), "{}", err);
+
+    handler.db_pools().close_all().await;
+    let _ = std::fs::remove_file(db_path);
+}
+
+#[tokio::test]
+async fn test_database_strategy_seeds_sqlite_from_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let users = dir.path().join("users.json");
+    std::fs::write(&users, r#"[{"id": 1, "name": "Ada", "admin": true}, {"id": 2, "name": "Linus"}]"#).unwrap();
+    let orders = dir.path().join("orders.csv");
+    std::fs::write(&orders, "id,user_id,item,total\n1,1,\"Lamp, brass\",19.5\n2,2,Desk,120\n3,1,Chair,\n").unwrap();
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let mock = |query: &str, params: &[&str], seed_file: &std::path::Path| -> MockConfig {
+        serde_json::from_value(json!({
+            "strategy": "database",
+            "database": {
+                "url": "sqlite::memory:",
+                "query": query,
+                "params": params,
+                "seed_file": seed_file.to_str().unwrap()
+            }
+        }))
+        .unwrap()
+    };
+
+    let user = mock("SELECT name, admin FROM users WHERE id = ?", &["id"], &users);
+    let value = handler.generate(&user, Some(&json!({"id": 2}))).await.unwrap();
+    assert_eq!(value, json!([{"name": "Linus", "admin": null}]));
+
+    // CSV numbers are stored as numbers, so they compare against bound integers
+    let order = mock("SELECT item FROM orders WHERE user_id = ? ORDER BY id", &["user_id"], &orders);
+    let value = handler.generate(&order, Some(&json!({"user_id": 1}))).await.unwrap();
+    assert_eq!(value, json!([{"item": "Lamp, brass"}, {"item": "Chair"}]));
+
+    // Seeding happens once; later calls see the same rows
+    let count = mock("SELECT COUNT(*) AS total FROM users", &[], &users);
+    assert_eq!(handler.generate(&count, None).await.unwrap(), json!([{"total": 2}]));
+    assert_eq!(handler.generate(&count, None).await.unwrap(), json!([{"total": 2}]));
+
+    let remote: MockConfig = serde_json::from_value(json!({
+        "strategy": "database",
+        "database": {"url": "postgres://localhost/db", "query": "SELECT 1", "seed_file": users.to_str().unwrap()}
+    }))
+    .unwrap();
+    let err = handler.generate(&remote, None).await.unwrap_err();
+    assert!(err.to_string().contains("only supported for sqlite"), "{}", err);
+
+    handler.db_pools().close_all().await;
+}
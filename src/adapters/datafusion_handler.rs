@@ -148,7 +148,7 @@ impl DataFusionHandler {
             // Create a new schema for this data lake
             let schema_provider = Arc::new(MemorySchemaProvider::new());
             catalog.register_schema(&sanitized_name, schema_provider)
-                .map_err(|e| DataFusionHandlerError::DataFusion(e))?;
+                .map_err(DataFusionHandlerError::DataFusion)?;
             tracing::debug!("Created schema '{}' for data lake '{}'", sanitized_name, data_lake_name);
         }
 
@@ -0,0 +1,263 @@
+//! Connection pools for the `database` mock strategy
+//!
+//! One pool is created per database URL on first use and shared by every
+//! tool call that queries that URL, instead of connecting on each call.
+//! SQLite databases can be filled from a `seed_file` the first time a pool
+//! uses them, which keeps Database-strategy mocks self-contained.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use serde_json::Value;
+use sqlx::any::AnyPoolOptions;
+use sqlx::pool::PoolConnection;
+use sqlx::{Any, AnyPool};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::adapters::metrics_handler::MetricsCollector;
+
+#[derive(Default)]
+pub struct DatabaseStrategyPool {
+    pools: DashMap<String, AnyPool>,
+    /// `(url, seed file)` pairs already seeded
+    seeded: Mutex<HashSet<(String, String)>>,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl DatabaseStrategyPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Acquire a connection to `url`, creating its pool with `max_connections` on first use
+    pub async fn acquire(&self, url: &str, max_connections: u32) -> Result<PoolConnection<Any>> {
+        let pool = self.pool(url, max_connections).await?;
+        let connection = pool
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Database connection error: {}", e))?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.db_strategy_connections_acquired.inc();
+            metrics.db_strategy_pool_size.set(self.open_connections() as f64);
+        }
+        Ok(connection)
+    }
+
+    async fn pool(&self, url: &str, max_connections: u32) -> Result<AnyPool> {
+        if let Some(pool) = self.pools.get(url) {
+            return Ok(pool.clone());
+        }
+
+        // Ensure drivers are installed (safe to call multiple times)
+        sqlx::any::install_default_drivers();
+        let options = if is_sqlite_memory(url) {
+            // An in-memory SQLite database only lives as long as its connection,
+            // so every call must share one connection that is never closed
+            AnyPoolOptions::new().max_connections(1).idle_timeout(None).max_lifetime(None)
+        } else {
+            AnyPoolOptions::new().max_connections(max_connections.max(1))
+        };
+        let pool = options
+            .connect_lazy(url)
+            .map_err(|e| anyhow::anyhow!("Database connection error: {}", e))?;
+
+        // Keep the pool another call may have created meanwhile
+        Ok(self.pools.entry(url.to_string()).or_insert(pool).clone())
+    }
+
+    /// Fill the table for `seed_file` through `connection` the first time
+    /// `url` is used with it. Only SQLite databases can be seeded.
+    pub async fn seed_once(&self, url: &str, seed_file: &str, connection: &mut PoolConnection<Any>) -> Result<()> {
+        // Held while seeding so concurrent first calls don't insert twice
+        let mut seeded = self.seeded.lock().await;
+        let key = (url.to_string(), seed_file.to_string());
+        if seeded.contains(&key) {
+            return Ok(());
+        }
+        if !url.starts_with("sqlite:") {
+            return Err(anyhow::anyhow!("seed_file is only supported for sqlite databases, not '{}'", url));
+        }
+
+        let path = Path::new(seed_file);
+        let table = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Can't name a table after seed file '{}'", seed_file))?;
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read seed file '{}': {}", seed_file, e))?;
+        let (columns, rows) = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => parse_csv_seed(&content),
+            Some("json") => parse_json_seed(&content),
+            _ => Err(anyhow::anyhow!("expected a .csv or .json file")),
+        }
+        .map_err(|e| anyhow::anyhow!("Invalid seed file '{}': {}", seed_file, e))?;
+
+        seed_table(connection, table, &columns, &rows)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to seed table '{}' from '{}': {}", table, seed_file, e))?;
+        seeded.insert(key);
+        Ok(())
+    }
+
+    /// Open connections across all pools
+    pub fn open_connections(&self) -> u32 {
+        self.pools.iter().map(|pool| pool.size()).sum()
+    }
+
+    /// Number of database URLs with a pool
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// Close every pool, waiting for connections in use to be returned
+    pub async fn close_all(&self) {
+        let pools: Vec<AnyPool> = self.pools.iter().map(|pool| pool.clone()).collect();
+        self.pools.clear();
+        for pool in pools {
+            pool.close().await;
+        }
+        // In-memory databases are gone, so they need seeding again
+        self.seeded.lock().await.clear();
+        if let Some(metrics) = &self.metrics {
+            metrics.db_strategy_pool_size.set(0.0);
+        }
+    }
+}
+
+fn is_sqlite_memory(url: &str) -> bool {
+    url.starts_with("sqlite:") && (url.contains(":memory:") || url.contains("mode=memory"))
+}
+
+/// Columns and rows of a CSV file with a header line. Empty fields are null,
+/// and fields that parse as numbers become numbers.
+fn parse_csv_seed(content: &str) -> Result<(Vec<String>, Vec<Vec<Value>>)> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let columns: Vec<String> = reader.headers()?.iter().map(String::from).collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let row = record?
+            .iter()
+            .map(|field| {
+                if field.is_empty() {
+                    Value::Null
+                } else if let Ok(i) = field.parse::<i64>() {
+                    Value::from(i)
+                } else if let Some(n) = field.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+                    Value::Number(n)
+                } else {
+                    Value::String(field.to_string())
+                }
+            })
+            .collect();
+        rows.push(row);
+    }
+    Ok((columns, rows))
+}
+
+/// Columns and rows of a JSON array of objects. The columns are every key
+/// that appears in any object; keys an object lacks are null.
+fn parse_json_seed(content: &str) -> Result<(Vec<String>, Vec<Vec<Value>>)> {
+    let value: Value = serde_json::from_str(content)?;
+    let objects = value
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("expected an array of objects"))?
+        .iter()
+        .map(|item| item.as_object().ok_or_else(|| anyhow::anyhow!("expected an array of objects")))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut columns: Vec<String> = Vec::new();
+    for object in &objects {
+        for key in object.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    let rows = objects
+        .iter()
+        .map(|object| columns.iter().map(|c| object.get(c).cloned().unwrap_or(Value::Null)).collect())
+        .collect();
+    Ok((columns, rows))
+}
+
+/// Create `table` and insert `rows`, unless the table already exists
+async fn seed_table(
+    connection: &mut PoolConnection<Any>,
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<Value>],
+) -> Result<()> {
+    if columns.is_empty() {
+        return Err(anyhow::anyhow!("no columns"));
+    }
+
+    let existing: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(&mut **connection)
+        .await?;
+    if existing > 0 {
+        return Ok(());
+    }
+
+    // Column types come from the first non-null value, so numbers compare as numbers
+    let definitions: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            let column_type = match rows.iter().map(|row| &row[idx]).find(|v| !v.is_null()) {
+                Some(Value::Number(n)) if n.is_f64() => "REAL",
+                Some(Value::Number(_)) | Some(Value::Bool(_)) => "INTEGER",
+                _ => "TEXT",
+            };
+            format!("{} {}", quote_identifier(column), column_type)
+        })
+        .collect();
+    let names: Vec<String> = columns.iter().map(|c| quote_identifier(c)).collect();
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote_identifier(table),
+        names.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+
+    let mut tx = sqlx::Connection::begin(&mut **connection).await?;
+    sqlx::query(&format!("CREATE TABLE {} ({})", quote_identifier(table), definitions.join(", ")))
+        .execute(&mut *tx)
+        .await?;
+    for row in rows {
+        let mut query = sqlx::query(&insert);
+        for value in row {
+            query = match value {
+                Value::Null => query.bind(Option::<String>::None),
+                Value::Bool(b) => query.bind(*b),
+                Value::Number(n) => match n.as_i64() {
+                    Some(i) => query.bind(i),
+                    None => query.bind(n.as_f64()),
+                },
+                Value::String(s) => query.bind(s.clone()),
+                other => query.bind(other.to_string()),
+            };
+        }
+        query.execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,94 @@
+//! Turning subsystems off with `[features]`
+//!
+//! `create_app` leaves the `/api` router and the web UI unmounted when
+//! `features.admin_api` is off at startup. The other features are checked on
+//! each request, so a config reload can turn them off or on again: their API
+//! routes answer 404, and the tool and resource handlers hide their items from
+//! MCP clients and refuse calls to them.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::adapters::api_handler::ApiResponse;
+use crate::config::{FeaturesConfig, Settings};
+
+/// API route prefixes (relative to `/api`) of each subsystem that can be off
+const SUBSYSTEM_ROUTES: &[(&str, &str)] = &[
+    ("agents", "/agents"),
+    ("agents", "/orchestrations"),
+    ("agents", "/costs"),
+    ("agents", "/llm"),
+    ("data_lake", "/data-lakes"),
+    ("mcp_aggregation", "/mcp-servers"),
+];
+
+/// Feature turned off that the API route `path` belongs to, if any
+pub fn disabled_feature(features: &FeaturesConfig, path: &str) -> Option<&'static str> {
+    if !features.admin_api {
+        return Some("admin_api");
+    }
+    SUBSYSTEM_ROUTES
+        .iter()
+        .filter(|(_, prefix)| path == *prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/')))
+        .map(|(feature, _)| *feature)
+        .find(|feature| match *feature {
+            "agents" => !features.agents,
+            "data_lake" => !features.data_lake,
+            "mcp_aggregation" => !features.mcp_aggregation,
+            _ => false,
+        })
+}
+
+/// Answer requests to the routes of turned-off features with 404
+pub async fn features_middleware(
+    State(settings): State<Arc<RwLock<Settings>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let disabled = disabled_feature(&settings.read().await.features, request.uri().path());
+    match disabled {
+        Some(feature) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(format!("Disabled on this server (features.{} = false)", feature))),
+        )
+            .into_response(),
+        None => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_feature_matches_route_prefixes() {
+        let features = FeaturesConfig {
+            agents: false,
+            data_lake: false,
+            ..Default::default()
+        };
+
+        assert_eq!(disabled_feature(&features, "/agents"), Some("agents"));
+        assert_eq!(disabled_feature(&features, "/agents/a/test"), Some("agents"));
+        assert_eq!(disabled_feature(&features, "/orchestrations/o"), Some("agents"));
+        assert_eq!(disabled_feature(&features, "/llm/models/openai"), Some("agents"));
+        assert_eq!(disabled_feature(&features, "/data-lakes/lake/records"), Some("data_lake"));
+        assert_eq!(disabled_feature(&features, "/agents-list"), None);
+        assert_eq!(disabled_feature(&features, "/mcp-servers"), None);
+        assert_eq!(disabled_feature(&features, "/tools"), None);
+        assert_eq!(disabled_feature(&FeaturesConfig::default(), "/agents"), None);
+
+        let locked = FeaturesConfig {
+            admin_api: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled_feature(&locked, "/tools"), Some("admin_api"));
+    }
+}
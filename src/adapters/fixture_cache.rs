@@ -0,0 +1,89 @@
+//! Parsed fixture files of the file mock strategy
+//!
+//! When fixture directories are watched, File-strategy mocks keep the records
+//! of each fixture in memory instead of re-reading and re-parsing the file on
+//! every call. The watcher drops the entries of a directory whenever something
+//! in it changes, so the next call reads the edited file.
+
+use dashmap::DashMap;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Fixture records keyed by the canonical path of their file
+#[derive(Default)]
+pub struct FixtureCache {
+    entries: DashMap<PathBuf, Arc<Vec<Value>>>,
+}
+
+impl FixtureCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cache key of a fixture path: the canonical path if the file exists
+    fn key(path: &Path) -> PathBuf {
+        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    pub fn get(&self, path: &str) -> Option<Arc<Vec<Value>>> {
+        self.entries.get(&Self::key(Path::new(path))).map(|e| e.value().clone())
+    }
+
+    pub fn insert(&self, path: &str, records: Vec<Value>) -> Arc<Vec<Value>> {
+        let records = Arc::new(records);
+        self.entries.insert(Self::key(Path::new(path)), records.clone());
+        records
+    }
+
+    /// Drop the fixtures in the directories of the changed `paths`. Editors often
+    /// save through a temporary file and a rename, so a change anywhere in a
+    /// directory invalidates all of its fixtures.
+    pub fn invalidate(&self, paths: &[PathBuf]) {
+        let dirs: Vec<PathBuf> = paths
+            .iter()
+            .flat_map(|p| [Some(Self::key(p)), p.parent().map(Self::key)])
+            .flatten()
+            .collect();
+        self.entries
+            .retain(|file, _| !file.parent().is_some_and(|parent| dirs.iter().any(|d| d == parent)));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_change_in_directory_invalidates_its_fixtures() {
+        let dir = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let users = dir.path().join("users.json");
+        let orders = other.path().join("orders.json");
+        std::fs::write(&users, "[]").unwrap();
+        std::fs::write(&orders, "[]").unwrap();
+
+        let cache = FixtureCache::new();
+        cache.insert(users.to_str().unwrap(), vec![json!({"id": 1})]);
+        cache.insert(orders.to_str().unwrap(), vec![json!({"id": 2})]);
+        assert_eq!(cache.get(users.to_str().unwrap()).unwrap()[0], json!({"id": 1}));
+
+        // A temporary file saved next to the fixture counts as a change to it
+        cache.invalidate(&[dir.path().join(".users.json.swp")]);
+        assert!(cache.get(users.to_str().unwrap()).is_none());
+        assert!(cache.get(orders.to_str().unwrap()).is_some());
+
+        // So does an event for the directory itself
+        cache.invalidate(&[other.path().to_path_buf()]);
+        assert!(cache.is_empty());
+    }
+}
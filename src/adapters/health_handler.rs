@@ -84,16 +84,43 @@ impl HealthHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{ServerSettings, Settings};
+    use crate::config::{LogFormat, ServerSettings, Settings};
 
     #[tokio::test]
     async fn test_health_endpoint() {
         let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![],
@@ -101,6 +128,7 @@ mod tests {
             tools: vec![],
             prompts: vec![],
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -110,6 +138,8 @@ mod tests {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         };
         let handler = HealthHandler::new(Arc::new(RwLock::new(settings)));
@@ -122,10 +152,37 @@ mod tests {
     async fn test_ready_endpoint_with_config() {
         let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![],
@@ -133,6 +190,7 @@ mod tests {
             tools: vec![],
             prompts: vec![],
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -142,6 +200,8 @@ mod tests {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         };
         let handler = HealthHandler::new(Arc::new(RwLock::new(settings)));
@@ -155,10 +215,37 @@ mod tests {
     async fn test_live_endpoint() {
         let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![],
@@ -166,6 +253,7 @@ mod tests {
             tools: vec![],
             prompts: vec![],
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -175,6 +263,8 @@ mod tests {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         };
         let handler = HealthHandler::new(Arc::new(RwLock::new(settings)));
@@ -0,0 +1,166 @@
+//! Plain REST endpoints for tools, resources and prompts
+//!
+//! With `server.http_mock_mode` enabled, clients that don't speak MCP can call
+//! `POST /tools/{name}`, `GET /resources/{uri}` and `GET /prompts/{name}`.
+//! The routes look items up at request time, so tools added or removed through
+//! the API are available immediately. Requests that don't match an item fall
+//! through to the web UI, whose pages share these paths. Resources mocked from a
+//! file with `stream = true` are streamed from disk in chunks.
+
+use crate::adapters::mock_strategy::open_mock_file;
+use crate::adapters::ui_handler::{UIHandler, UiBasePath};
+use crate::config::{FileConfig, MockStrategyType, ResourceConfig, Settings};
+use crate::domain::{PromptPort, ResourceContentKind, ResourcePort, ToolPort};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+use tokio::sync::RwLock;
+use tokio_util::io::ReaderStream;
+
+#[derive(Clone)]
+pub struct HttpMockState {
+    pub settings: Arc<RwLock<Settings>>,
+    pub resources: Arc<dyn ResourcePort>,
+    pub tools: Arc<dyn ToolPort>,
+    pub prompts: Arc<dyn PromptPort>,
+}
+
+/// Routes for HTTP mock mode; they serve the web UI while the mode is disabled
+pub fn router(state: HttpMockState) -> Router {
+    Router::new()
+        .route("/tools/:name", post(call_tool).fallback(UIHandler::serve))
+        .route("/resources/*uri", get(read_resource).fallback(UIHandler::serve))
+        .route("/prompts/:name", get(get_prompt).fallback(UIHandler::serve))
+        .with_state(state)
+}
+
+async fn enabled(state: &HttpMockState) -> bool {
+    state.settings.read().await.server.http_mock_mode
+}
+
+fn error_response(status: StatusCode, message: impl ToString) -> Response {
+    (status, Json(json!({ "error": message.to_string() }))).into_response()
+}
+
+/// POST /tools/:name - Execute a tool with the request body as arguments
+async fn call_tool(
+    State(state): State<HttpMockState>,
+    Path(name): Path<String>,
+    base_path: Option<Extension<UiBasePath>>,
+    uri: Uri,
+    body: Bytes,
+) -> Response {
+    if !enabled(&state).await {
+        return UIHandler::serve(base_path, uri).await.into_response();
+    }
+
+    let known = match state.tools.list_tools().await {
+        Ok(tools) => tools.iter().any(|t| t.name == name),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if !known {
+        return error_response(StatusCode::NOT_FOUND, format!("Tool not found: {}", name));
+    }
+
+    let args = if body.is_empty() {
+        json!({})
+    } else {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(args) => args,
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, format!("Invalid JSON body: {}", e)),
+        }
+    };
+
+    match state.tools.execute_tool(&name, args).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// GET /resources/*uri - Read a resource, returned with its MIME type
+async fn read_resource(
+    State(state): State<HttpMockState>,
+    Path(resource_uri): Path<String>,
+    base_path: Option<Extension<UiBasePath>>,
+    uri: Uri,
+) -> Response {
+    let resource = match enabled(&state).await {
+        true => state.settings.read().await.resources.iter().find(|r| r.uri == resource_uri).cloned(),
+        false => None,
+    };
+    let Some(resource) = resource else {
+        return UIHandler::serve(base_path, uri).await.into_response();
+    };
+
+    if let Some(file_config) = streamed_file(&resource) {
+        let mime_type = resource.mime_type.clone().unwrap_or_else(|| "text/plain".to_string());
+        return match open_mock_file(file_config).await {
+            // Stop at the size sent as Content-Length, even if the file grows meanwhile
+            Ok((file, size)) => (
+                [(header::CONTENT_TYPE, mime_type), (header::CONTENT_LENGTH, size.to_string())],
+                Body::from_stream(ReaderStream::new(file.take(size))),
+            )
+                .into_response(),
+            Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+    }
+
+    let result = match state.resources.get_resource(&resource_uri).await {
+        Ok(result) => result,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    let mime_type = result.mime_type.unwrap_or_else(|| "text/plain".to_string());
+
+    let body = match ResourceContentKind::from_mime_type(Some(&mime_type)) {
+        ResourceContentKind::Binary => {
+            use base64::{engine::general_purpose, Engine as _};
+            match general_purpose::STANDARD.decode(result.content.trim()) {
+                Ok(bytes) => bytes,
+                Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Invalid base64 content: {}", e)),
+            }
+        }
+        _ => result.content.into_bytes(),
+    };
+
+    ([(header::CONTENT_TYPE, mime_type)], body).into_response()
+}
+
+/// File of a resource mocked with the File strategy and `stream = true`
+fn streamed_file(resource: &ResourceConfig) -> Option<&FileConfig> {
+    resource
+        .mock
+        .as_ref()
+        .filter(|mock| matches!(mock.strategy, MockStrategyType::File))
+        .and_then(|mock| mock.file.as_ref())
+        .filter(|file| file.stream)
+}
+
+/// GET /prompts/:name - Render a prompt with the query parameters as arguments
+async fn get_prompt(
+    State(state): State<HttpMockState>,
+    Path(name): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    base_path: Option<Extension<UiBasePath>>,
+    uri: Uri,
+) -> Response {
+    let known = enabled(&state).await
+        && state.settings.read().await.prompts.iter().any(|p| p.name == name);
+    if !known {
+        return UIHandler::serve(base_path, uri).await.into_response();
+    }
+
+    let arguments = (!params.is_empty()).then(|| json!(params));
+    match state.prompts.get_prompt(&name, arguments).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
@@ -0,0 +1,381 @@
+//! Idempotency keys for mutating API calls
+//!
+//! A POST to the API with an `Idempotency-Key` header runs once per key. Keys
+//! are scoped to the authenticated caller and the request's method and path,
+//! so two callers picking the same key don't see each other's responses. The
+//! response is kept for `server.idempotency_ttl_seconds`, and a retry with the
+//! same key, path and body gets that response back instead of creating the
+//! item again. A retry with a different body, or one that arrives while the
+//! first request is still running, is rejected. Server errors and streamed
+//! responses are not kept, so those requests can be retried.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::adapters::api_handler::{ApiResponse, ApiState};
+use crate::domain::auth::AuthContext;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header set on responses replayed for a repeated key
+pub const IDEMPOTENT_REPLAY_HEADER: &str = "idempotent-replayed";
+
+/// Memory kept responses may use at once; the oldest are dropped first
+const MAX_CACHED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest request or response body buffered for replay
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// A response kept for replay
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl IntoResponse for StoredResponse {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, self.body).into_response();
+        *response.headers_mut() = self.headers;
+        response
+            .headers_mut()
+            .insert(IDEMPOTENT_REPLAY_HEADER, header::HeaderValue::from_static("true"));
+        response
+    }
+}
+
+enum Outcome {
+    Running,
+    Done(StoredResponse),
+}
+
+struct Entry {
+    fingerprint: String,
+    started: Instant,
+    outcome: Outcome,
+    /// Bytes counted against [`MAX_CACHED_BYTES`]
+    size: usize,
+}
+
+impl Entry {
+    fn size(key: &str, fingerprint: &str, response: Option<&StoredResponse>) -> usize {
+        let response = response.map_or(0, |response| {
+            response.body.len()
+                + response
+                    .headers
+                    .iter()
+                    .map(|(name, value)| name.as_str().len() + value.len())
+                    .sum::<usize>()
+        });
+        key.len() + fingerprint.len() + response
+    }
+}
+
+/// What to do with a request carrying an idempotency key
+#[derive(Debug)]
+pub enum Claim {
+    /// First request with the key: run it
+    Run,
+    /// The key's request already finished: send its response again
+    Replay(StoredResponse),
+    /// The key's request is still running
+    InProgress,
+    /// The key was used for a different request
+    Mismatch,
+}
+
+/// Responses of recent requests by idempotency key
+#[derive(Default)]
+pub struct IdempotencyCache {
+    entries: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_key: HashMap<String, Entry>,
+    /// Sum of the entries' sizes
+    bytes: usize,
+}
+
+impl Entries {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.by_key.remove(key) {
+            self.bytes -= entry.size;
+        }
+    }
+
+    /// Drop the oldest entries, other than `keep`, until `extra` more bytes fit
+    fn make_room(&mut self, extra: usize, keep: &str) {
+        while self.bytes + extra > MAX_CACHED_BYTES {
+            let oldest = self
+                .by_key
+                .iter()
+                .filter(|(key, _)| key.as_str() != keep)
+                .min_by_key(|(_, entry)| entry.started)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => self.remove(&oldest),
+                None => break,
+            }
+        }
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim `key` for a request with `fingerprint`. Keys older than `ttl`
+    /// are forgotten.
+    pub fn claim(&self, key: &str, fingerprint: &str, ttl: Duration) -> Claim {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let expired: Vec<String> = entries
+            .by_key
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.started) >= ttl)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            entries.remove(&key);
+        }
+
+        if let Some(entry) = entries.by_key.get(key) {
+            return if entry.fingerprint != fingerprint {
+                Claim::Mismatch
+            } else {
+                match &entry.outcome {
+                    Outcome::Running => Claim::InProgress,
+                    Outcome::Done(response) => Claim::Replay(response.clone()),
+                }
+            };
+        }
+
+        let size = Entry::size(key, fingerprint, None);
+        entries.make_room(size, key);
+        entries.bytes += size;
+        entries.by_key.insert(
+            key.to_string(),
+            Entry {
+                fingerprint: fingerprint.to_string(),
+                started: now,
+                outcome: Outcome::Running,
+                size,
+            },
+        );
+        Claim::Run
+    }
+
+    /// Keep the response of the request that claimed `key`, dropping the
+    /// oldest responses if the cache is full
+    pub fn complete(&self, key: &str, response: StoredResponse) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = entries.by_key.get(key) else {
+            return;
+        };
+        let size = Entry::size(key, &entry.fingerprint, Some(&response));
+        let previous = entry.size;
+        entries.make_room(size.saturating_sub(previous), key);
+        if let Some(entry) = entries.by_key.get_mut(key) {
+            entry.outcome = Outcome::Done(response);
+            entry.size = size;
+        }
+        entries.bytes = entries.bytes - previous + size;
+    }
+
+    /// Forget `key`, so the request can be retried
+    pub fn release(&self, key: &str) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+}
+
+/// Releases a claimed key unless its response was kept, including when the
+/// request is cancelled
+struct ClaimGuard<'a> {
+    cache: &'a IdempotencyCache,
+    key: &'a str,
+    completed: bool,
+}
+
+impl Drop for ClaimGuard<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.cache.release(self.key);
+        }
+    }
+}
+
+/// Key a request is cached under: the caller's `Idempotency-Key`, scoped to
+/// the authenticated user and the request's method and path
+fn cache_key(auth: Option<&AuthContext>, method: &Method, path: &str, key: &str) -> String {
+    let principal = auth.and_then(|auth| auth.user_id.as_deref()).unwrap_or("");
+    format!("{}\0{}\0{}\0{}", principal, method, path, key)
+}
+
+/// SHA-256 of a request's method, path and body
+fn fingerprint(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update([0]);
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+/// Run each keyed POST once and replay its response to retries
+pub async fn idempotency_middleware(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(key) if request.method() == Method::POST => match key.to_str() {
+            Ok(key) if !key.is_empty() => key.to_string(),
+            _ => return error(StatusCode::BAD_REQUEST, "Invalid Idempotency-Key header"),
+        },
+        _ => return next.run(request).await,
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, MAX_BODY_BYTES).await else {
+        return error(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large");
+    };
+    let key = cache_key(parts.extensions.get::<AuthContext>(), &parts.method, parts.uri.path(), &key);
+    let fingerprint = fingerprint(&parts.method, parts.uri.path(), &body);
+    let ttl = Duration::from_secs(state.settings.read().await.server.idempotency_ttl_seconds);
+
+    match state.idempotency.claim(&key, &fingerprint, ttl) {
+        Claim::Run => {}
+        Claim::Replay(response) => return response.into_response(),
+        Claim::InProgress => {
+            return error(StatusCode::CONFLICT, "A request with this Idempotency-Key is still in progress")
+        }
+        Claim::Mismatch => {
+            return error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used for a different request",
+            )
+        }
+    }
+
+    let mut guard = ClaimGuard {
+        cache: &state.idempotency,
+        key: &key,
+        completed: false,
+    };
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    let streamed = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|t| t.as_bytes().starts_with(b"text/event-stream"));
+    if streamed || response.status().is_server_error() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, MAX_BODY_BYTES).await else {
+        return error(StatusCode::INTERNAL_SERVER_ERROR, "Response too large to keep for Idempotency-Key");
+    };
+    state.idempotency.complete(
+        &key,
+        StoredResponse {
+            status: parts.status,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+        },
+    );
+    guard.completed = true;
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created(body: &'static str) -> StoredResponse {
+        StoredResponse {
+            status: StatusCode::CREATED,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_repeated_key_replays_the_first_response() {
+        let cache = IdempotencyCache::new();
+        let ttl = Duration::from_secs(60);
+
+        assert!(matches!(cache.claim("k1", "a", ttl), Claim::Run));
+        assert!(matches!(cache.claim("k1", "a", ttl), Claim::InProgress));
+        cache.complete("k1", created("first"));
+
+        match cache.claim("k1", "a", ttl) {
+            Claim::Replay(response) => assert_eq!(response.body, "first"),
+            other => panic!("expected a replay, got {:?}", other),
+        }
+        assert!(matches!(cache.claim("k1", "b", ttl), Claim::Mismatch));
+
+        // Released keys and expired keys can be used again
+        assert!(matches!(cache.claim("k2", "a", ttl), Claim::Run));
+        cache.release("k2");
+        assert!(matches!(cache.claim("k2", "a", ttl), Claim::Run));
+        assert!(matches!(cache.claim("k1", "a", Duration::ZERO), Claim::Run));
+    }
+
+    #[test]
+    fn test_cache_is_bounded_by_bytes() {
+        let cache = IdempotencyCache::new();
+        let ttl = Duration::from_secs(60);
+        let large = || StoredResponse {
+            status: StatusCode::CREATED,
+            headers: HeaderMap::new(),
+            body: Bytes::from(vec![b'x'; MAX_CACHED_BYTES / 3]),
+        };
+        for key in ["k1", "k2", "k3"] {
+            assert!(matches!(cache.claim(key, "a", ttl), Claim::Run));
+            std::thread::sleep(Duration::from_millis(2));
+            cache.complete(key, large());
+        }
+
+        // The third response only fits once the first is dropped
+        let entries = cache.entries.lock().unwrap();
+        assert!(!entries.by_key.contains_key("k1"));
+        assert!(entries.by_key.contains_key("k2") && entries.by_key.contains_key("k3"));
+        assert!(entries.bytes <= MAX_CACHED_BYTES);
+        assert_eq!(entries.bytes, entries.by_key.values().map(|entry| entry.size).sum::<usize>());
+    }
+
+    #[test]
+    fn test_cache_key_is_scoped_to_caller_and_route() {
+        let alice = AuthContext {
+            authenticated: true,
+            user_id: Some("alice".to_string()),
+            ..Default::default()
+        };
+        let bob = AuthContext {
+            authenticated: true,
+            user_id: Some("bob".to_string()),
+            ..Default::default()
+        };
+        let key = |auth, path| cache_key(auth, &Method::POST, path, "k1");
+
+        assert_eq!(key(Some(&alice), "/tools"), key(Some(&alice), "/tools"));
+        assert_ne!(key(Some(&alice), "/tools"), key(Some(&bob), "/tools"));
+        assert_ne!(key(Some(&alice), "/tools"), key(None, "/tools"));
+        assert_ne!(key(Some(&alice), "/tools"), key(Some(&alice), "/prompts"));
+    }
+}
@@ -0,0 +1,140 @@
+//! Paths into JSON values
+//!
+//! Paths are dot-separated keys with optional array indexes, e.g. `user.email`,
+//! `items[0].id` or `items[*].id` for every element. A leading `$` (as in
+//! JSONPath's `$.items[0]`) is accepted, and an empty path or `$` is the whole
+//! value.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+/// One step of a path
+#[derive(Debug, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+    /// Every element of an array (`[*]`)
+    All,
+}
+
+/// Split a path like `items[*].id` into its segments
+pub fn parse(path: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let trimmed = match path.strip_prefix('$') {
+        Some(rest) => rest.strip_prefix('.').unwrap_or(rest),
+        None => path,
+    };
+    if trimmed.is_empty() {
+        return Ok(segments);
+    }
+    for part in trimmed.split('.') {
+        let (key, mut indexes) = match part.find('[') {
+            Some(start) => part.split_at(start),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        } else if indexes.is_empty() {
+            return Err(anyhow!("Invalid path '{}': empty segment", path));
+        }
+        while !indexes.is_empty() {
+            let (index, rest) = indexes
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+                .ok_or_else(|| anyhow!("Invalid path '{}': unclosed '['", path))?;
+            segments.push(match index {
+                "*" => Segment::All,
+                index => Segment::Index(
+                    index
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid path '{}': bad index '{}'", path, index))?,
+                ),
+            });
+            indexes = rest;
+        }
+    }
+    Ok(segments)
+}
+
+/// The values `segments` lead to, in document order
+pub fn select<'a>(value: &'a Value, segments: &[Segment]) -> Vec<&'a Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return vec![value];
+    };
+    match segment {
+        Segment::Key(key) => value.get(key).map(|child| select(child, rest)).unwrap_or_default(),
+        Segment::Index(index) => value.get(index).map(|child| select(child, rest)).unwrap_or_default(),
+        Segment::All => value
+            .as_array()
+            .map(|items| items.iter().flat_map(|child| select(child, rest)).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Call `f` on every value `segments` leads to. With `create`, missing keys
+/// are added (and null parents turned into objects) along the way.
+pub fn visit_mut(value: &mut Value, segments: &[Segment], create: bool, f: &mut dyn FnMut(&mut Value)) {
+    let Some((segment, rest)) = segments.split_first() else {
+        f(value);
+        return;
+    };
+    match segment {
+        Segment::Key(key) => {
+            if create && value.is_null() {
+                *value = Value::Object(Map::new());
+            }
+            let Value::Object(map) = value else {
+                return;
+            };
+            if create {
+                visit_mut(map.entry(key.clone()).or_insert(Value::Null), rest, create, f);
+            } else if let Some(child) = map.get_mut(key) {
+                visit_mut(child, rest, create, f);
+            }
+        }
+        Segment::Index(index) => {
+            if let Some(child) = value.as_array_mut().and_then(|items| items.get_mut(*index)) {
+                visit_mut(child, rest, create, f);
+            }
+        }
+        Segment::All => {
+            if let Some(items) = value.as_array_mut() {
+                for child in items {
+                    visit_mut(child, rest, create, f);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("items[*].tags[0]").unwrap(),
+            vec![
+                Segment::Key("items".to_string()),
+                Segment::All,
+                Segment::Key("tags".to_string()),
+                Segment::Index(0),
+            ]
+        );
+        assert_eq!(parse("$.items").unwrap(), parse("items").unwrap());
+        assert!(parse("$").unwrap().is_empty());
+        assert!(parse("items[").is_err());
+        assert!(parse("items[x]").is_err());
+        assert!(parse("a..b").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let value = json!({"items": [{"id": 1}, {"id": 2}, {"name": "x"}]});
+        assert_eq!(select(&value, &parse("$.items[*].id").unwrap()), vec![&json!(1), &json!(2)]);
+        assert_eq!(select(&value, &parse("items[2].name").unwrap()), vec![&json!("x")]);
+        assert!(select(&value, &parse("items[5]").unwrap()).is_empty());
+    }
+}
@@ -1,9 +1,14 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use std::time::{Duration, Instant};
 
+/// Default interval after which cached keys are refetched
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(900); // 15 minutes
+/// Minimum time between fetches triggered by unknown key IDs
+const DEFAULT_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Jwk {
     pub kid: String,
@@ -18,61 +23,230 @@ pub struct Jwks {
     pub keys: Vec<Jwk>,
 }
 
+#[derive(Default)]
+struct KeyCache {
+    keys: HashMap<String, Jwk>,
+    /// When the keys were last fetched successfully
+    fetched_at: Option<Instant>,
+    /// When a fetch was last attempted, successful or not
+    attempted_at: Option<Instant>,
+    /// When an unknown key ID last triggered a fetch
+    miss_refreshed_at: Option<Instant>,
+}
+
 #[derive(Clone)]
 pub struct JwksClient {
     url: String,
-    #[allow(clippy::type_complexity)]
-    cache: Arc<RwLock<Option<(HashMap<String, Jwk>, Instant)>>>,
-    ttl: Duration,
+    client: reqwest::Client,
+    cache: Arc<RwLock<KeyCache>>,
+    /// Held while fetching, so concurrent refreshes share one fetch
+    refreshing: Arc<Mutex<()>>,
+    refresh_interval: Duration,
+    min_refresh_interval: Duration,
 }
 
 impl JwksClient {
     pub fn new(url: String) -> Self {
         Self {
             url,
-            cache: Arc::new(RwLock::new(None)),
-            ttl: Duration::from_secs(900), // 15 minutes
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            cache: Arc::new(RwLock::new(KeyCache::default())),
+            refreshing: Arc::new(Mutex::new(())),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            min_refresh_interval: DEFAULT_MIN_REFRESH_INTERVAL,
         }
     }
 
+    /// Refetch the keys once they are older than `interval`
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Limit how often an unknown key ID or a failed fetch can trigger another fetch
+    pub fn with_min_refresh_interval(mut self, interval: Duration) -> Self {
+        self.min_refresh_interval = interval;
+        self
+    }
+
+    /// Look up a key by ID. Keys are refetched when the cache is stale or the
+    /// ID is unknown (e.g. after the issuer rotated its keys). If a fetch fails,
+    /// the last keys fetched successfully are used.
     pub async fn get_key(&self, kid: &str) -> Result<Jwk, anyhow::Error> {
-        // Try cache first
         {
             let cache = self.cache.read().await;
-            if let Some((keys, timestamp)) = &*cache {
-                if timestamp.elapsed() < self.ttl {
-                    if let Some(key) = keys.get(kid) {
-                        return Ok(key.clone());
-                    }
+            if self.is_fresh(&cache) {
+                if let Some(key) = cache.keys.get(kid) {
+                    return Ok(key.clone());
                 }
             }
         }
 
-        // Refresh cache
-        self.refresh().await?;
+        {
+            let mut cache = self.cache.write().await;
+            if self.is_fresh(&cache) {
+                // Another caller may have refreshed the keys meanwhile
+                if let Some(key) = cache.keys.get(kid) {
+                    return Ok(key.clone());
+                }
+                // Unknown key ID: refetch, but not more often than min_refresh_interval
+                if cache.miss_refreshed_at.is_some_and(|at| at.elapsed() < self.min_refresh_interval) {
+                    return Err(anyhow::anyhow!("Key ID {} not found in JWKS", kid));
+                }
+                cache.miss_refreshed_at = Some(Instant::now());
+            } else if cache.fetched_at.is_some()
+                && cache.attempted_at.is_some_and(|at| at.elapsed() < self.min_refresh_interval)
+            {
+                // A refresh failed moments ago; keep using the cached keys for now
+                return cache.keys.get(kid)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Key ID {} not found in JWKS", kid));
+            }
+        }
 
-        // Try cache again
-        let cache = self.cache.read().await;
-        if let Some((keys, _)) = &*cache {
-            if let Some(key) = keys.get(kid) {
-                return Ok(key.clone());
+        if let Err(e) = self.refresh(Instant::now()).await {
+            let cache = self.cache.read().await;
+            if cache.fetched_at.is_none() {
+                return Err(e);
             }
+            tracing::warn!("Failed to refresh JWKS from {}, using cached keys: {}", self.url, e);
         }
 
-        Err(anyhow::anyhow!("Key ID {} not found in JWKS", kid))
+        let cache = self.cache.read().await;
+        cache.keys.get(kid)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Key ID {} not found in JWKS", kid))
+    }
+
+    fn is_fresh(&self, cache: &KeyCache) -> bool {
+        cache.fetched_at.is_some_and(|at| at.elapsed() < self.refresh_interval)
     }
 
-    async fn refresh(&self) -> Result<(), anyhow::Error> {
-        let jwks: Jwks = reqwest::get(&self.url).await?.json().await?;
-        
-        let mut key_map = HashMap::new();
-        for key in jwks.keys {
-            key_map.insert(key.kid.clone(), key);
+    /// Fetch the keys, unless another caller did so after `requested_at`.
+    /// The cache stays readable during the fetch; it is only locked to swap
+    /// in the new keys.
+    async fn refresh(&self, requested_at: Instant) -> Result<(), anyhow::Error> {
+        let _refreshing = self.refreshing.lock().await;
+        {
+            let mut cache = self.cache.write().await;
+            if cache.attempted_at.is_some_and(|at| at > requested_at) {
+                return Ok(());
+            }
+            cache.attempted_at = Some(Instant::now());
         }
 
+        let jwks: Jwks = self.client.get(&self.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
         let mut cache = self.cache.write().await;
-        *cache = Some((key_map, Instant::now()));
-        
+        cache.keys = jwks.keys.into_iter().map(|key| (key.kid.clone(), key)).collect();
+        cache.fetched_at = Some(Instant::now());
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+    use serde_json::{json, Value};
+
+    /// JWKS endpoint serving `Some(keys)`, or 500 for `None`
+    async fn jwks_server(keys: Arc<RwLock<Option<Value>>>) -> String {
+        slow_jwks_server(keys, Duration::ZERO).await
+    }
+
+    /// JWKS endpoint that waits `delay` before answering
+    async fn slow_jwks_server(keys: Arc<RwLock<Option<Value>>>, delay: Duration) -> String {
+        let app = Router::new()
+            .route("/jwks.json", get(move |State(keys): State<Arc<RwLock<Option<Value>>>>| async move {
+                tokio::time::sleep(delay).await;
+                match keys.read().await.clone() {
+                    Some(keys) => Ok(Json(json!({ "keys": keys }))),
+                    None => Err(StatusCode::INTERNAL_SERVER_ERROR),
+                }
+            }))
+            .with_state(keys);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/jwks.json", addr)
+    }
+
+    fn jwk(kid: &str) -> Value {
+        json!({"kid": kid, "kty": "RSA", "n": "AQAB", "e": "AQAB"})
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refreshes_keys() {
+        let keys = Arc::new(RwLock::new(Some(json!([jwk("old")]))));
+        let client = JwksClient::new(jwks_server(keys.clone()).await)
+            .with_min_refresh_interval(Duration::ZERO);
+
+        assert_eq!(client.get_key("old").await.unwrap().kid, "old");
+
+        *keys.write().await = Some(json!([jwk("new")]));
+        assert_eq!(client.get_key("new").await.unwrap().kid, "new");
+        assert!(client.get_key("old").await.is_err());
+
+        *keys.write().await = Some(json!([jwk("newer")]));
+        assert_eq!(client.get_key("newer").await.unwrap().kid, "newer");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refresh_is_throttled() {
+        let keys = Arc::new(RwLock::new(Some(json!([jwk("old")]))));
+        let client = JwksClient::new(jwks_server(keys.clone()).await);
+
+        assert!(client.get_key("old").await.is_ok());
+        *keys.write().await = Some(json!([jwk("new")]));
+        assert!(client.get_key("new").await.is_ok());
+
+        // A second unknown key ID right away does not trigger another fetch
+        *keys.write().await = Some(json!([jwk("newer")]));
+        assert!(client.get_key("newer").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_serves_cached_keys() {
+        let keys = Arc::new(RwLock::new(Some(json!([jwk("current")]))));
+        let client = JwksClient::new(jwks_server(keys.clone()).await)
+            .with_refresh_interval(Duration::ZERO)
+            .with_min_refresh_interval(Duration::ZERO);
+
+        assert!(client.get_key("current").await.is_ok());
+
+        // The endpoint goes down; the cache is stale but still used
+        *keys.write().await = None;
+        assert_eq!(client.get_key("current").await.unwrap().kid, "current");
+    }
+
+    #[tokio::test]
+    async fn test_cached_keys_are_served_during_a_fetch() {
+        let keys = Arc::new(RwLock::new(Some(json!([jwk("old")]))));
+        let client = JwksClient::new(slow_jwks_server(keys.clone(), Duration::from_millis(500)).await)
+            .with_min_refresh_interval(Duration::ZERO);
+        assert!(client.get_key("old").await.is_ok());
+
+        // An unknown key ID starts a slow fetch...
+        let fetching = {
+            let client = client.clone();
+            tokio::spawn(async move { client.get_key("new").await })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // ...which doesn't hold up lookups of cached keys
+        let started = Instant::now();
+        assert!(client.get_key("old").await.is_ok());
+        assert!(started.elapsed() < Duration::from_millis(250), "{:?}", started.elapsed());
+        assert!(fetching.await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//! Log output and per-request IDs
+//!
+//! Logs are written as text or as JSON lines (`server.log_format`), to stdout
+//! and optionally appended to `server.log_file`. Every HTTP request runs in a
+//! `request` span holding a `request_id` (the `x-request-id` header, or a new
+//! UUID) and a `trace_id` (from a W3C `traceparent` header, or a new one).
+//! The span's IDs are kept in its extensions, and JSON lines logged while the
+//! request is handled carry them as top-level fields. Tool calls, workflows
+//! and agent runs each open an [`execution_span`] inside it, so the log lines
+//! of nested executions share the ID of the request that started them.
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use chrono::SecondsFormat;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Instrument, Span, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
+use uuid::Uuid;
+
+use crate::config::{LogFormat, ServerSettings};
+
+/// Header carrying the request ID, read from requests and set on responses
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// IDs of the request being handled
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub trace_id: String,
+}
+
+impl RequestContext {
+    /// Span making these the IDs of log lines within it, for work the request
+    /// hands to another task (such as MCP calls, run by the session's task)
+    pub fn span(&self) -> Span {
+        tracing::error_span!("request", request_id = %self.request_id, trace_id = %self.trace_id)
+    }
+
+    /// IDs of the request the current span belongs to, if any
+    pub fn current() -> Option<Self> {
+        Span::current()
+            .with_subscriber(|(id, dispatch)| {
+                let registry = dispatch.downcast_ref::<Registry>()?;
+                let span = registry.span(id)?;
+                let context = span
+                    .scope()
+                    .find_map(|span| span.extensions().get::<RequestContext>().cloned());
+                context
+            })
+            .flatten()
+    }
+}
+
+/// Span of a tool call, workflow or agent run (`kind`) named `name`. Within a
+/// request its log lines carry the request's IDs; otherwise, e.g. for a
+/// scheduled run, the span gets new IDs shared by everything nested in it.
+pub fn execution_span(kind: &'static str, name: &str) -> Span {
+    if RequestContext::current().is_some() {
+        return tracing::error_span!("execution", kind, name = %name);
+    }
+    let request_id = Uuid::new_v4().to_string();
+    let trace_id = Uuid::new_v4().simple().to_string();
+    tracing::error_span!(
+        "execution",
+        kind,
+        name = %name,
+        request_id = %request_id,
+        trace_id = %trace_id,
+    )
+}
+
+/// Install the global subscriber for `log_format`, `log_level` and `log_file`
+pub fn init(server: &ServerSettings) -> anyhow::Result<()> {
+    let level: LevelFilter = server
+        .log_level
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid log level '{}': {}", server.log_level, e))?;
+
+    match &server.log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| anyhow::anyhow!("Failed to open log file '{}': {}", path, e))?;
+            // No colour codes in the file
+            let writer = std::io::stdout.and(Mutex::new(file));
+            subscriber(server.log_format, level, writer, false).try_init()?;
+        }
+        None => subscriber(server.log_format, level, std::io::stdout, true).try_init()?,
+    }
+    Ok(())
+}
+
+/// Subscriber writing events at `level` and above to `writer` in `format`
+pub fn subscriber<W>(format: LogFormat, level: LevelFilter, writer: W, ansi: bool) -> impl Subscriber + Send + Sync
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let output = match format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer().event_format(JsonFormat).with_writer(writer).boxed(),
+    };
+    tracing_subscriber::registry()
+        .with(level)
+        .with(RequestContextLayer)
+        .with(output)
+}
+
+/// Run each request in a `request` span with its request and trace IDs, and
+/// echo the request ID in the `x-request-id` response header
+pub async fn request_context_middleware(mut request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let request_id = headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let trace_id = headers
+        .get("traceparent")
+        .and_then(|v| v.to_str().ok())
+        .and_then(trace_id_from_traceparent)
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+
+    // ERROR level so the span, and the IDs it carries, is enabled whatever `log_level` is
+    let span = tracing::error_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %request_id,
+        trace_id = %trace_id,
+    );
+    request.extensions_mut().insert(RequestContext {
+        request_id: request_id.clone(),
+        trace_id,
+    });
+
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Trace ID of a W3C `traceparent` header (`version-traceid-parentid-flags`)
+fn trace_id_from_traceparent(traceparent: &str) -> Option<String> {
+    let trace_id = traceparent.split('-').nth(1)?;
+    let valid = trace_id.len() == 32
+        && trace_id.chars().all(|c| c.is_ascii_hexdigit())
+        && trace_id.chars().any(|c| c != '0');
+    valid.then(|| trace_id.to_ascii_lowercase())
+}
+
+/// Stores the `request_id` and `trace_id` fields of new spans in their extensions
+pub struct RequestContextLayer;
+
+impl<S> Layer<S> for RequestContextLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut context = RequestContext::default();
+        attrs.record(&mut RequestContextVisitor(&mut context));
+        if context == RequestContext::default() {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(context);
+        }
+    }
+}
+
+struct RequestContextVisitor<'a>(&'a mut RequestContext);
+
+impl Visit for RequestContextVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "request_id" => self.0.request_id = value.to_string(),
+            "trace_id" => self.0.trace_id = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+/// Formats each event as a JSON object on one line: `timestamp`, `level`,
+/// `target`, `message`, the request's `request_id` and `trace_id` when logged
+/// while handling one, and any other event fields under `fields`
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            json!(chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        );
+        line.insert("level".to_string(), json!(metadata.level().as_str()));
+        line.insert("target".to_string(), json!(metadata.target()));
+        line.insert("message".to_string(), fields.message.unwrap_or_default());
+
+        let context = ctx
+            .event_scope()
+            .and_then(|mut scope| scope.find_map(|span| span.extensions().get::<RequestContext>().cloned()));
+        if let Some(context) = context {
+            line.insert("request_id".to_string(), json!(context.request_id));
+            line.insert("trace_id".to_string(), json!(context.trace_id));
+        }
+        if !fields.fields.is_empty() {
+            line.insert("fields".to_string(), Value::Object(fields.fields));
+        }
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[derive(Default)]
+struct JsonFields {
+    message: Option<Value>,
+    fields: Map<String, Value>,
+}
+
+impl JsonFields {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = Some(value);
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, json!(format!("{:?}", value)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    /// Captures everything written by a subscriber
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<Value> {
+            let output = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+        }
+    }
+
+    #[test]
+    fn test_json_lines_carry_request_ids() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = subscriber(LogFormat::Json, LevelFilter::INFO, move || writer.clone(), false);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(tool = "search", "before any request");
+            let span = tracing::error_span!("request", request_id = %"req-1", trace_id = %"abc123");
+            span.in_scope(|| {
+                tracing::warn!(attempt = 2, "inside a request");
+                tracing::debug!("below the configured level");
+            });
+        });
+
+        let lines = captured.lines();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["message"], "before any request");
+        assert_eq!(lines[0]["target"], module_path!());
+        assert_eq!(lines[0]["fields"]["tool"], "search");
+        assert!(lines[0]["timestamp"].is_string());
+        assert!(lines[0].get("request_id").is_none());
+
+        assert_eq!(lines[1]["level"], "WARN");
+        assert_eq!(lines[1]["message"], "inside a request");
+        assert_eq!(lines[1]["request_id"], "req-1");
+        assert_eq!(lines[1]["trace_id"], "abc123");
+        assert_eq!(lines[1]["fields"]["attempt"], 2);
+    }
+
+    #[test]
+    fn test_nested_executions_share_request_ids() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = subscriber(LogFormat::Json, LevelFilter::INFO, move || writer.clone(), false);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let request = RequestContext {
+                request_id: "req-1".to_string(),
+                trace_id: "abc123".to_string(),
+            };
+            request.span().in_scope(|| {
+                execution_span("workflow", "checkout").in_scope(|| {
+                    execution_span("tool", "charge").in_scope(|| tracing::info!("nested in a request"));
+                });
+            });
+            execution_span("workflow", "nightly").in_scope(|| {
+                execution_span("agent", "reporter").in_scope(|| tracing::info!("nested in a scheduled run"));
+                tracing::info!("in the scheduled run");
+            });
+        });
+
+        let lines = captured.lines();
+        assert_eq!(lines[0]["request_id"], "req-1");
+        assert_eq!(lines[0]["trace_id"], "abc123");
+        assert!(lines[1]["request_id"].is_string());
+        assert_ne!(lines[1]["request_id"], "req-1");
+        assert_eq!(lines[1]["request_id"], lines[2]["request_id"]);
+    }
+
+    #[test]
+    fn test_trace_id_from_traceparent() {
+        assert_eq!(
+            trace_id_from_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert!(trace_id_from_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none());
+        assert!(trace_id_from_traceparent("not a traceparent").is_none());
+    }
+}
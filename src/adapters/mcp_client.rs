@@ -4,16 +4,18 @@
 //! and call their tools. It manages connections to multiple MCP servers and
 //! routes tool calls appropriately.
 
-use crate::config::McpServerConfig;
+use crate::adapters::rmcp_server::SharedNotificationBroadcaster;
+use crate::config::{McpServerConfig, CONTEXT_KEY_PREFIX};
 use crate::domain::Tool;
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
 use tracing::{debug, error, info, warn};
 
 /// Prefix for MCP tools when exposed to agents
@@ -84,12 +86,39 @@ struct ContentItem {
     mime_type: Option<String>,
 }
 
+/// Whether an external MCP server's tools are available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpServerStatus {
+    Connected,
+    /// Not reachable yet; retrying with backoff
+    Reconnecting,
+    /// Gave up after `reconnect.max_attempts` retries
+    Down,
+}
+
+/// Status of an external MCP server, served by `GET /api/mcp-servers`
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerInfo {
+    pub name: String,
+    pub url: String,
+    pub status: McpServerStatus,
+    pub tool_count: usize,
+    /// Reconnection attempts since the server was last connected
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
 /// Connection state for an MCP server
 struct McpConnection {
     config: McpServerConfig,
     client: Client,
     tools: Vec<McpTool>,
     request_id: u64,
+    status: McpServerStatus,
+    attempts: u32,
+    last_error: Option<String>,
 }
 
 impl McpConnection {
@@ -105,6 +134,9 @@ impl McpConnection {
             client,
             tools: Vec::new(),
             request_id: 0,
+            status: McpServerStatus::Reconnecting,
+            attempts: 0,
+            last_error: None,
         }
     }
 
@@ -171,10 +203,31 @@ impl McpConnection {
             .ok_or_else(|| anyhow::anyhow!("No result in MCP response from {}", self.config.name))
     }
 
+    /// The server's tools that pass the `include` and `exclude` globs
     async fn list_tools(&mut self) -> Result<Vec<McpTool>> {
         let result = self.send_request("tools/list", None).await?;
         let list_result: ListToolsResult = serde_json::from_value(result)?;
-        Ok(list_result.tools)
+        Ok(list_result
+            .tools
+            .into_iter()
+            .filter(|t| self.config.exposes_tool(&t.name))
+            .collect())
+    }
+
+    /// The server's tool `tool` as exposed by Metis, under its renamed or prefixed name
+    fn exposed_tool(&self, tool: &McpTool) -> Tool {
+        Tool {
+            name: self.config.exposed_tool_name(&tool.name),
+            description: tool
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("MCP tool from {}", self.config.name)),
+            input_schema: tool
+                .input_schema
+                .clone()
+                .unwrap_or_else(|| json!({"type": "object"})),
+            output_schema: None,
+        }
     }
 
     async fn call_tool(&mut self, name: &str, arguments: Value) -> Result<Value> {
@@ -211,6 +264,10 @@ impl McpConnection {
 /// Manager for MCP client connections
 pub struct McpClientManager {
     connections: Arc<RwLock<HashMap<String, McpConnection>>>,
+    /// Told when a reconnected server's tools become available
+    broadcaster: Arc<RwLock<Option<SharedNotificationBroadcaster>>>,
+    /// Background reconnect task of each server, by name
+    reconnects: Mutex<HashMap<String, AbortHandle>>,
 }
 
 impl McpClientManager {
@@ -218,12 +275,22 @@ impl McpClientManager {
     pub fn new() -> Self {
         Self {
             connections: Arc::new(RwLock::new(HashMap::new())),
+            broadcaster: Arc::new(RwLock::new(None)),
+            reconnects: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Initialize connections to configured MCP servers
+    /// Set the broadcaster notified when a server reconnects
+    pub async fn set_broadcaster(&self, broadcaster: SharedNotificationBroadcaster) {
+        *self.broadcaster.write().await = Some(broadcaster);
+    }
+
+    /// Connect to the configured MCP servers, replacing the servers and
+    /// reconnect tasks of an earlier call
     pub async fn initialize(&self, configs: &[McpServerConfig]) -> Result<()> {
         let mut connections = self.connections.write().await;
+        self.abort_reconnects();
+        connections.clear();
 
         for config in configs {
             if !config.enabled {
@@ -246,6 +313,7 @@ impl McpClientManager {
                         debug!("  - {}: {:?}", tool.name, tool.description);
                     }
                     connection.tools = tools;
+                    connection.status = McpServerStatus::Connected;
                     connections.insert(config.name.clone(), connection);
                 }
                 Err(e) => {
@@ -253,8 +321,10 @@ impl McpClientManager {
                         "Failed to connect to MCP server '{}': {}",
                         config.name, e
                     );
-                    // Still add the connection so we can retry later
+                    // Keep the connection and retry in the background
+                    connection.last_error = Some(e.to_string());
                     connections.insert(config.name.clone(), connection);
+                    self.spawn_reconnect(config.clone());
                 }
             }
         }
@@ -262,6 +332,89 @@ impl McpClientManager {
         Ok(())
     }
 
+    /// Stop retrying `name` and forget its connection and tools
+    pub async fn remove_server(&self, name: &str) {
+        let mut connections = self.connections.write().await;
+        if let Some(reconnect) = self.reconnects.lock().unwrap_or_else(|e| e.into_inner()).remove(name) {
+            reconnect.abort();
+        }
+        connections.remove(name);
+    }
+
+    /// Stop every background reconnect
+    fn abort_reconnects(&self) {
+        for (_, reconnect) in self.reconnects.lock().unwrap_or_else(|e| e.into_inner()).drain() {
+            reconnect.abort();
+        }
+    }
+
+    /// Retry `config` with exponential backoff until it answers `tools/list`
+    /// or runs out of attempts, replacing any retry of the same server
+    fn spawn_reconnect(&self, config: McpServerConfig) {
+        let connections = self.connections.clone();
+        let broadcaster = self.broadcaster.clone();
+        let name = config.name.clone();
+
+        let task = tokio::spawn(async move {
+            let mut attempt = 0;
+            loop {
+                tokio::time::sleep(config.reconnect.delay(attempt)).await;
+                attempt += 1;
+
+                // Probe with a fresh connection so tool calls aren't blocked meanwhile
+                let mut probe = McpConnection::new(config.clone());
+                let result = probe.list_tools().await;
+
+                let mut connections = connections.write().await;
+                let Some(connection) = connections.get_mut(&config.name) else {
+                    return;
+                };
+                connection.attempts = attempt;
+                match result {
+                    Ok(tools) => {
+                        info!(
+                            "Reconnected to MCP server '{}' with {} tools after {} attempt(s)",
+                            config.name,
+                            tools.len(),
+                            attempt
+                        );
+                        connection.tools = tools;
+                        connection.status = McpServerStatus::Connected;
+                        connection.last_error = None;
+                        drop(connections);
+
+                        if let Some(broadcaster) = broadcaster.read().await.as_ref() {
+                            broadcaster.notify_tools_changed().await;
+                        }
+                        return;
+                    }
+                    Err(e) => {
+                        connection.last_error = Some(e.to_string());
+                        let max_attempts = config.reconnect.max_attempts;
+                        if max_attempts > 0 && attempt >= max_attempts {
+                            error!(
+                                "Giving up on MCP server '{}' after {} attempts: {}",
+                                config.name, attempt, e
+                            );
+                            connection.status = McpServerStatus::Down;
+                            return;
+                        }
+                        debug!("MCP server '{}' still unreachable: {}", config.name, e);
+                    }
+                }
+            }
+        });
+
+        let previous = self
+            .reconnects
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name, task.abort_handle());
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
     /// Refresh tools from all connected servers
     pub async fn refresh_tools(&self) -> Result<()> {
         let mut connections = self.connections.write().await;
@@ -275,6 +428,7 @@ impl McpClientManager {
                         tools.len()
                     );
                     connection.tools = tools;
+                    connection.status = McpServerStatus::Connected;
                 }
                 Err(e) => {
                     error!("Failed to refresh tools for MCP server '{}': {}", name, e);
@@ -290,24 +444,10 @@ impl McpClientManager {
         let connections = self.connections.read().await;
         let mut all_tools = Vec::new();
 
-        for (server_name, connection) in connections.iter() {
+        for connection in connections.values() {
             for tool in &connection.tools {
-                let prefixed_name = format!("{}{}_{}", MCP_TOOL_PREFIX, server_name, tool.name);
-                all_tools.push((
-                    prefixed_name.clone(),
-                    Tool {
-                        name: prefixed_name,
-                        description: tool
-                            .description
-                            .clone()
-                            .unwrap_or_else(|| format!("MCP tool from {}", server_name)),
-                        input_schema: tool
-                            .input_schema
-                            .clone()
-                            .unwrap_or_else(|| json!({"type": "object"})),
-                        output_schema: None,
-                    },
-                ));
+                let tool = connection.exposed_tool(tool);
+                all_tools.push((tool.name.clone(), tool));
             }
         }
 
@@ -322,21 +462,7 @@ impl McpClientManager {
             connection
                 .tools
                 .iter()
-                .map(|t| {
-                    let prefixed_name = format!("{}{}_{}", MCP_TOOL_PREFIX, server_name, t.name);
-                    Tool {
-                        name: prefixed_name,
-                        description: t
-                            .description
-                            .clone()
-                            .unwrap_or_else(|| format!("MCP tool from {}", server_name)),
-                        input_schema: t
-                            .input_schema
-                            .clone()
-                            .unwrap_or_else(|| json!({"type": "object"})),
-                        output_schema: None,
-                    }
-                })
+                .map(|t| connection.exposed_tool(t))
                 .collect()
         } else {
             Vec::new()
@@ -355,38 +481,12 @@ impl McpClientManager {
                     if tool_pattern == "*" {
                         // All tools from this server
                         for t in &connection.tools {
-                            let prefixed_name =
-                                format!("{}{}_{}", MCP_TOOL_PREFIX, server_name, t.name);
-                            tools.push(Tool {
-                                name: prefixed_name,
-                                description: t
-                                    .description
-                                    .clone()
-                                    .unwrap_or_else(|| format!("MCP tool from {}", server_name)),
-                                input_schema: t
-                                    .input_schema
-                                    .clone()
-                                    .unwrap_or_else(|| json!({"type": "object"})),
-                                output_schema: None,
-                            });
+                            tools.push(connection.exposed_tool(t));
                         }
                     } else {
                         // Specific tool
                         if let Some(t) = connection.tools.iter().find(|t| t.name == tool_pattern) {
-                            let prefixed_name =
-                                format!("{}{}_{}", MCP_TOOL_PREFIX, server_name, t.name);
-                            tools.push(Tool {
-                                name: prefixed_name,
-                                description: t
-                                    .description
-                                    .clone()
-                                    .unwrap_or_else(|| format!("MCP tool from {}", server_name)),
-                                input_schema: t
-                                    .input_schema
-                                    .clone()
-                                    .unwrap_or_else(|| json!({"type": "object"})),
-                                output_schema: None,
-                            });
+                            tools.push(connection.exposed_tool(t));
                         }
                     }
                 }
@@ -397,25 +497,28 @@ impl McpClientManager {
     }
 
     /// Call a tool on an MCP server
-    /// Tool name format: "mcp__{server}_{tool}"
-    pub async fn call_tool(&self, prefixed_name: &str, arguments: Value) -> Result<Value> {
-        // Parse the prefixed name to extract server and tool
-        let name_without_prefix = prefixed_name
-            .strip_prefix(MCP_TOOL_PREFIX)
-            .ok_or_else(|| anyhow::anyhow!("Invalid MCP tool name: {}", prefixed_name))?;
-
-        // Find the first underscore to split server and tool name
-        let (server_name, tool_name) = name_without_prefix
-            .split_once('_')
-            .ok_or_else(|| anyhow::anyhow!("Invalid MCP tool name format: {}", prefixed_name))?;
-
+    /// Tool name format: "mcp__{server}_{tool}", or the name it is renamed to
+    pub async fn call_tool(&self, exposed_name: &str, arguments: Value) -> Result<Value> {
         let mut connections = self.connections.write().await;
+        let (server_name, tool_name) = resolve_tool(&connections, exposed_name)?;
 
         let connection = connections
-            .get_mut(server_name)
+            .get_mut(&server_name)
             .ok_or_else(|| anyhow::anyhow!("MCP server not found: {}", server_name))?;
 
-        connection.call_tool(tool_name, arguments).await
+        connection.call_tool(&tool_name, strip_context_keys(arguments)).await
+    }
+
+    /// Whether calls to `name` go to an MCP server: it has the MCP prefix or
+    /// is the new name of a renamed tool
+    pub async fn routes_tool(&self, name: &str) -> bool {
+        if Self::is_mcp_tool(name) {
+            return true;
+        }
+        let connections = self.connections.read().await;
+        connections
+            .values()
+            .any(|c| c.config.rename.values().any(|renamed| renamed == name))
     }
 
     /// Check if a tool name is an MCP tool
@@ -423,6 +526,24 @@ impl McpClientManager {
         name.starts_with(MCP_TOOL_PREFIX)
     }
 
+    /// Status of every enabled server, sorted by name
+    pub async fn server_statuses(&self) -> Vec<McpServerInfo> {
+        let connections = self.connections.read().await;
+        let mut servers: Vec<McpServerInfo> = connections
+            .iter()
+            .map(|(name, connection)| McpServerInfo {
+                name: name.clone(),
+                url: connection.config.url.clone(),
+                status: connection.status,
+                tool_count: connection.tools.len(),
+                attempts: connection.attempts,
+                last_error: connection.last_error.clone(),
+            })
+            .collect();
+        servers.sort_by(|a, b| a.name.cmp(&b.name));
+        servers
+    }
+
     /// Get list of connected server names
     pub async fn list_servers(&self) -> Vec<String> {
         let connections = self.connections.read().await;
@@ -435,3 +556,219 @@ impl Default for McpClientManager {
         Self::new()
     }
 }
+
+impl Drop for McpClientManager {
+    fn drop(&mut self) {
+        self.abort_reconnects();
+    }
+}
+
+/// Server and tool name of the MCP tool exposed as `exposed_name`. A renamed
+/// tool can only be called by its new name, and a tool left out by the
+/// server's globs can't be called at all.
+fn resolve_tool(connections: &HashMap<String, McpConnection>, exposed_name: &str) -> Result<(String, String)> {
+    for (server_name, connection) in connections {
+        if let Some((tool, _)) = connection.config.rename.iter().find(|(_, renamed)| *renamed == exposed_name) {
+            return Ok((server_name.clone(), tool.clone()));
+        }
+    }
+
+    let name_without_prefix = exposed_name
+        .strip_prefix(MCP_TOOL_PREFIX)
+        .ok_or_else(|| anyhow::anyhow!("Invalid MCP tool name: {}", exposed_name))?;
+
+    // Server names may contain underscores, so match them against the known servers
+    let (server_name, tool_name) = connections
+        .keys()
+        .find_map(|server| {
+            let tool = name_without_prefix.strip_prefix(server.as_str())?.strip_prefix('_')?;
+            Some((server.as_str(), tool))
+        })
+        .or_else(|| name_without_prefix.split_once('_'))
+        .ok_or_else(|| anyhow::anyhow!("Invalid MCP tool name format: {}", exposed_name))?;
+
+    if let Some(connection) = connections.get(server_name) {
+        let config = &connection.config;
+        if config.rename.contains_key(tool_name) || !config.exposes_tool(tool_name) {
+            return Err(anyhow::anyhow!("MCP tool not found: {}", exposed_name));
+        }
+    }
+    Ok((server_name.to_string(), tool_name.to_string()))
+}
+
+/// Drop `inject_context` keys, which are for local tools and would fail the
+/// external server's validation against its `input_schema`
+fn strip_context_keys(arguments: Value) -> Value {
+    match arguments {
+        Value::Object(mut obj) => {
+            obj.retain(|key, _| !key.starts_with(CONTEXT_KEY_PREFIX));
+            Value::Object(obj)
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+
+    fn server_config(url: String) -> McpServerConfig {
+        serde_json::from_value(json!({
+            "name": "late",
+            "url": url,
+            "timeout_seconds": 2,
+            "reconnect": {"initial_delay_ms": 50, "max_delay_ms": 100, "max_attempts": 0}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reconnect_delay_doubles_up_to_max() {
+        let config: McpServerConfig =
+            serde_json::from_value(json!({"name": "s", "url": "http://localhost:1/mcp"})).unwrap();
+        let delays: Vec<u64> = (0..8).map(|a| config.reconnect.delay(a).as_millis() as u64).collect();
+        assert_eq!(delays, [1000, 2000, 4000, 8000, 16000, 32000, 60000, 60000]);
+        assert_eq!(config.reconnect.delay(100).as_millis(), 60000);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_reconnects() {
+        // Reserve a port, then start the server on it only after initializing
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let manager = McpClientManager::new();
+        manager.initialize(&[server_config(format!("http://{}/mcp", addr))]).await.unwrap();
+        let status = manager.server_statuses().await;
+        assert_eq!(status[0].status, McpServerStatus::Reconnecting);
+        assert!(status[0].last_error.is_some());
+        assert!(manager.list_all_tools().await.is_empty());
+
+        let app = Router::new().route(
+            "/mcp",
+            post(|Json(request): Json<Value>| async move {
+                Json(json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": {"tools": [{"name": "forecast", "description": "Weather"}]}
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        for _ in 0..50 {
+            if manager.server_statuses().await[0].status == McpServerStatus::Connected {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let status = manager.server_statuses().await;
+        assert_eq!(status[0].status, McpServerStatus::Connected);
+        assert_eq!(status[0].tool_count, 1);
+        assert!(status[0].last_error.is_none());
+        let tools = manager.list_all_tools().await;
+        assert_eq!(tools[0].0, "mcp__late_forecast");
+    }
+
+    #[tokio::test]
+    async fn test_tools_filtered_and_renamed() {
+        let app = Router::new().route(
+            "/mcp",
+            post(|Json(request): Json<Value>| async move {
+                let result = match request["method"].as_str() {
+                    Some("tools/list") => json!({"tools": [
+                        {"name": "get_user"}, {"name": "get_secret"}, {"name": "search"}, {"name": "delete_user"}
+                    ]}),
+                    _ => json!({"content": [{"type": "text", "text": request["params"]["name"].to_string()}]}),
+                };
+                Json(json!({"jsonrpc": "2.0", "id": request["id"], "result": result}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let config: McpServerConfig = serde_json::from_value(json!({
+            "name": "crm_prod",
+            "url": format!("http://{}/mcp", addr),
+            "include": ["get_*", "search"],
+            "exclude": ["get_secret"],
+            "rename": {"search": "crm_search"}
+        }))
+        .unwrap();
+        let manager = McpClientManager::new();
+        manager.initialize(&[config]).await.unwrap();
+
+        let mut names: Vec<String> = manager.list_all_tools().await.into_iter().map(|(name, _)| name).collect();
+        names.sort();
+        assert_eq!(names, ["crm_search", "mcp__crm_prod_get_user"]);
+
+        assert!(manager.routes_tool("crm_search").await);
+        assert!(!manager.routes_tool("search").await);
+        assert_eq!(manager.call_tool("crm_search", json!({})).await.unwrap(), json!("search"));
+        assert_eq!(manager.call_tool("mcp__crm_prod_get_user", json!({})).await.unwrap(), json!("get_user"));
+
+        // Renamed and filtered tools can't be called by their prefixed names
+        for name in ["mcp__crm_prod_search", "mcp__crm_prod_get_secret", "mcp__crm_prod_delete_user"] {
+            let err = manager.call_tool(name, json!({})).await.unwrap_err();
+            assert!(err.to_string().contains("MCP tool not found"), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_are_aborted_on_reload_and_removal() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let manager = McpClientManager::new();
+        let config = server_config(format!("http://{}/mcp", addr));
+        manager.initialize(std::slice::from_ref(&config)).await.unwrap();
+        let first = manager.reconnects.lock().unwrap()["late"].clone();
+
+        // Reloading replaces the retry instead of adding a second one
+        manager.initialize(std::slice::from_ref(&config)).await.unwrap();
+        let second = manager.reconnects.lock().unwrap()["late"].clone();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(first.is_finished());
+        assert!(!second.is_finished());
+
+        manager.remove_server("late").await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(second.is_finished());
+        assert!(manager.server_statuses().await.is_empty());
+
+        // A reload without the server drops it too
+        manager.initialize(std::slice::from_ref(&config)).await.unwrap();
+        let third = manager.reconnects.lock().unwrap()["late"].clone();
+        manager.initialize(&[]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(third.is_finished());
+        assert!(manager.server_statuses().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_server_marked_down_after_max_attempts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let mut config = server_config(format!("http://{}/mcp", addr));
+        config.reconnect.max_attempts = 2;
+        let manager = McpClientManager::new();
+        manager.initialize(&[config]).await.unwrap();
+
+        for _ in 0..50 {
+            if manager.server_statuses().await[0].status == McpServerStatus::Down {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let status = manager.server_statuses().await;
+        assert_eq!(status[0].status, McpServerStatus::Down);
+        assert_eq!(status[0].attempts, 2);
+    }
+}
@@ -4,6 +4,8 @@ use prometheus::{
 };
 use std::sync::Arc;
 
+use crate::agents::costs::CostLedger;
+
 pub struct MetricsCollector {
     registry: Registry,
     
@@ -20,6 +22,25 @@ pub struct MetricsCollector {
     // Cache metrics
     pub cache_hits: Counter,
     pub cache_misses: Counter,
+
+    // Rate limit metrics
+    pub bypass_token_uses: CounterVec,
+
+    // Agent metrics
+    pub agent_conversation_summaries: Counter,
+    pub llm_cost_usd: CounterVec,
+    /// Per-agent LLM costs, for `GET /api/costs`
+    pub llm_costs: CostLedger,
+
+    // Tool metrics
+    pub tool_dedup_hits: Counter,
+    pub tool_calls_in_flight: Gauge,
+    pub tool_calls_queued: Gauge,
+    pub tool_call_queue_timeouts: Counter,
+
+    // Database strategy metrics
+    pub db_strategy_pool_size: Gauge,
+    pub db_strategy_connections_acquired: Counter,
 }
 
 impl MetricsCollector {
@@ -70,6 +91,64 @@ impl MetricsCollector {
         
         let cache_misses = Counter::new("metis_cache_misses_total", "Total cache misses")?;
         registry.register(Box::new(cache_misses.clone()))?;
+
+        // Rate limit metrics
+        let bypass_token_uses = CounterVec::new(
+            Opts::new("metis_bypass_token_uses_total", "Total requests that skipped rate limiting via a bypass token"),
+            &["token_prefix"],
+        )?;
+        registry.register(Box::new(bypass_token_uses.clone()))?;
+
+        // Agent metrics
+        let agent_conversation_summaries = Counter::new(
+            "metis_agent_conversation_summaries_total",
+            "Total conversation summaries created by multi-turn agents",
+        )?;
+        registry.register(Box::new(agent_conversation_summaries.clone()))?;
+
+        let llm_cost_usd = CounterVec::new(
+            Opts::new("metis_llm_cost_usd_total", "Total cost in USD of agents' LLM completions"),
+            &["agent", "provider", "model"],
+        )?;
+        registry.register(Box::new(llm_cost_usd.clone()))?;
+
+        // Tool metrics
+        let tool_dedup_hits = Counter::new(
+            "metis_tool_dedup_hits_total",
+            "Total tool calls answered from the deduplication window",
+        )?;
+        registry.register(Box::new(tool_dedup_hits.clone()))?;
+
+        let tool_calls_in_flight = Gauge::new(
+            "metis_tool_calls_in_flight",
+            "Tool calls holding a slot under max_concurrent_tool_calls",
+        )?;
+        registry.register(Box::new(tool_calls_in_flight.clone()))?;
+
+        let tool_calls_queued = Gauge::new(
+            "metis_tool_calls_queued",
+            "Tool calls waiting for a slot under max_concurrent_tool_calls",
+        )?;
+        registry.register(Box::new(tool_calls_queued.clone()))?;
+
+        let tool_call_queue_timeouts = Counter::new(
+            "metis_tool_call_queue_timeouts_total",
+            "Total tool calls that failed waiting for a slot under max_concurrent_tool_calls",
+        )?;
+        registry.register(Box::new(tool_call_queue_timeouts.clone()))?;
+
+        // Database strategy metrics
+        let db_strategy_pool_size = Gauge::new(
+            "metis_db_strategy_pool_size_gauge",
+            "Open connections in the database mock strategy's pools",
+        )?;
+        registry.register(Box::new(db_strategy_pool_size.clone()))?;
+
+        let db_strategy_connections_acquired = Counter::new(
+            "metis_db_strategy_connections_acquired_total",
+            "Total connections acquired by the database mock strategy",
+        )?;
+        registry.register(Box::new(db_strategy_connections_acquired.clone()))?;
         
         Ok(Self {
             registry,
@@ -81,6 +160,16 @@ impl MetricsCollector {
             strategy_duration,
             cache_hits,
             cache_misses,
+            bypass_token_uses,
+            agent_conversation_summaries,
+            llm_cost_usd,
+            llm_costs: CostLedger::new(),
+            tool_dedup_hits,
+            tool_calls_in_flight,
+            tool_calls_queued,
+            tool_call_queue_timeouts,
+            db_strategy_pool_size,
+            db_strategy_connections_acquired,
         })
     }
     
@@ -107,6 +196,11 @@ impl MetricsHandler {
     pub fn new(collector: Arc<MetricsCollector>) -> Self {
         Self { collector }
     }
+
+    /// Get the underlying metrics collector
+    pub fn collector(&self) -> &Arc<MetricsCollector> {
+        &self.collector
+    }
     
     pub async fn metrics(&self) -> String {
         self.collector.encode().unwrap_or_else(|e| {
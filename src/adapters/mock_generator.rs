@@ -0,0 +1,267 @@
+//! AI-assisted mock generation
+//!
+//! Asks an LLM to suggest a [`MockConfig`] for a tool based on its name,
+//! description and schemas. The suggestion is parsed and validated but never
+//! applied automatically; callers return it to the user for review.
+
+use crate::agents::domain::Message;
+use crate::agents::llm::{CompletionRequest, LlmProvider};
+use crate::config::{CsvSelection, MockConfig, MockStrategyType, ScriptLang, ToolConfig};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::json;
+
+/// Rate limit for the generation endpoint, since every request costs an LLM call
+pub const REQUESTS_PER_MINUTE: u32 = 10;
+
+/// Phrases that indicate the LLM was unsure about its suggestion
+const HEDGES: &[&str] = &[
+    "not sure",
+    "unsure",
+    "uncertain",
+    "unclear",
+    "i think",
+    "i assume",
+    "assuming",
+    "might",
+    "may need",
+    "perhaps",
+    "possibly",
+    "guess",
+    "ambiguous",
+];
+
+/// Confidence lost for every hedge found in the response
+const HEDGE_PENALTY: f64 = 0.15;
+
+/// Lowest confidence reported for a suggestion that still parsed and validated
+const MIN_CONFIDENCE: f64 = 0.1;
+
+const SYSTEM_PROMPT: &str = r#"You generate mock configurations for Metis, an MCP mock server.
+Reply with a single JSON object that deserializes into a Metis MockConfig:
+{"strategy": "static" | "template" | "random" | "stateful" | "script" | "file" | "pattern", ...strategy fields}
+- template: {"strategy": "template", "template": "<Tera template producing JSON; tool arguments are available as variables>"}
+- random: {"strategy": "random", "faker_type": "name" | "title" | "email" | "username" | "word" | "sentence" | "paragraph"}
+- script: {"strategy": "script", "script_lang": "rhai", "script": "<Rhai script; arguments are in `input`>"}
+- pattern: {"strategy": "pattern", "pattern": "<pattern such as ORD-\\d{6}>"}
+Prefer "template" so the mock output matches the tool's output schema.
+Do not wrap the JSON in prose. If you are unsure about anything, say so in one short sentence after the JSON."#;
+
+/// A suggested mock config, returned to the user for review
+#[derive(Debug, Serialize)]
+pub struct MockGenerationResult {
+    pub mock: MockConfig,
+    /// 0.0 - 1.0, lowered when the LLM expressed uncertainty
+    pub confidence: f64,
+    /// Any commentary the LLM added next to the JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Ask `provider` for a mock config for `tool` and validate the reply
+pub async fn generate_mock(provider: &dyn LlmProvider, tool: &ToolConfig) -> Result<MockGenerationResult> {
+    let response = provider
+        .complete(build_request(tool))
+        .await
+        .map_err(|e| anyhow!("LLM request failed: {}", e))?;
+
+    parse_response(&response.message.content)
+}
+
+fn build_request(tool: &ToolConfig) -> CompletionRequest {
+    let tool_description = json!({
+        "name": tool.name,
+        "description": tool.description,
+        "input_schema": tool.input_schema,
+        "output_schema": tool.output_schema,
+    });
+
+    CompletionRequest {
+        messages: vec![
+            Message::system(SYSTEM_PROMPT),
+            Message::user(format!(
+                "Suggest a mock config for this tool:\n{}",
+                serde_json::to_string_pretty(&tool_description).unwrap_or_default()
+            )),
+        ],
+        temperature: Some(0.2),
+        ..Default::default()
+    }
+}
+
+/// Extract, parse and validate the MockConfig from an LLM reply
+fn parse_response(content: &str) -> Result<MockGenerationResult> {
+    let start = content
+        .find('{')
+        .ok_or_else(|| anyhow!("LLM response did not contain a JSON object"))?;
+    let end = content
+        .rfind('}')
+        .filter(|end| *end > start)
+        .ok_or_else(|| anyhow!("LLM response did not contain a JSON object"))?;
+
+    let mock: MockConfig = serde_json::from_str(&content[start..=end])
+        .map_err(|e| anyhow!("LLM response is not a valid mock config: {}", e))?;
+    validate_mock(&mock).map_err(|e| anyhow!("Generated mock config is invalid: {}", e))?;
+
+    // Drop code fences so only the LLM's own words remain
+    let notes = format!("{} {}", &content[..start], &content[end + 1..])
+        .replace("```json", "")
+        .replace("```", "");
+    let notes = notes.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Ok(MockGenerationResult {
+        mock,
+        confidence: confidence_from(&notes),
+        notes: (!notes.is_empty()).then_some(notes),
+    })
+}
+
+/// Confidence in a suggestion, based on hedging in the LLM's commentary
+fn confidence_from(notes: &str) -> f64 {
+    let notes = notes.to_lowercase();
+    let hedges = HEDGES.iter().filter(|h| notes.contains(*h)).count();
+    (1.0 - hedges as f64 * HEDGE_PENALTY).max(MIN_CONFIDENCE)
+}
+
+/// Check that the strategy has the fields it needs and that templates and
+/// Rhai scripts at least compile
+fn validate_mock(mock: &MockConfig) -> Result<(), String> {
+    let missing = |field: &str| Err(format!("strategy '{:?}' requires '{}'", mock.strategy, field));
+
+    match mock.strategy {
+        MockStrategyType::Static | MockStrategyType::Random => Ok(()),
+        MockStrategyType::Template => match &mock.template {
+            Some(template) => tera::Tera::default()
+                .add_raw_template("mock", template)
+                .map(|_| ())
+                .map_err(|e| format!("template does not compile: {}", e)),
+            None => missing("template"),
+        },
+        MockStrategyType::Script => match &mock.script {
+            Some(script) if matches!(mock.script_lang, None | Some(ScriptLang::Rhai)) => rhai::Engine::new()
+                .compile(script)
+                .map(|_| ())
+                .map_err(|e| format!("script does not compile: {}", e)),
+            Some(_) => Ok(()),
+            None => missing("script"),
+        },
+        MockStrategyType::Stateful if mock.stateful.is_none() => missing("stateful"),
+        MockStrategyType::File if mock.file.is_none() => missing("file"),
+        MockStrategyType::Pattern if mock.pattern.is_none() => missing("pattern"),
+        MockStrategyType::LLM if mock.llm.is_none() => missing("llm"),
+        MockStrategyType::Database => match &mock.database {
+            Some(database) => database.check_params(),
+            None => missing("database"),
+        },
+        MockStrategyType::DataLakeCrud if mock.data_lake_crud.is_none() => missing("data_lake_crud"),
+        MockStrategyType::Callback if mock.callback.is_none() => missing("callback"),
+        MockStrategyType::Csv => match &mock.csv {
+            Some(csv) if csv.selection == CsvSelection::Key && csv.key_column.is_none() => {
+                Err("csv selection 'key' needs a key_column".to_string())
+            }
+            Some(_) => Ok(()),
+            None => missing("csv"),
+        },
+        MockStrategyType::Matrix => match &mock.matrix {
+            Some(matrix) if matrix.pairs.is_empty() => Err("matrix has no pairs".to_string()),
+            Some(_) => Ok(()),
+            None => missing("matrix"),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::error::{LlmError, LlmResult};
+    use crate::agents::llm::{CompletionResponse, FinishReason, LlmStream};
+
+    /// LLM that always replies with the same text
+    struct FixedLlm(&'static str);
+
+    #[async_trait::async_trait]
+    impl LlmProvider for FixedLlm {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn model(&self) -> &str {
+            "fixed"
+        }
+
+        async fn complete(&self, _request: CompletionRequest) -> LlmResult<CompletionResponse> {
+            Ok(CompletionResponse {
+                message: Message::assistant(self.0),
+                finish_reason: FinishReason::Stop,
+                usage: None,
+            })
+        }
+
+        fn complete_stream(&self, _request: CompletionRequest) -> LlmStream {
+            let (tx, rx) = tokio::sync::mpsc::channel(1);
+            tx.try_send(Err(LlmError::InvalidRequest("not supported".to_string()))).unwrap();
+            LlmStream::new(rx)
+        }
+
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.len() as u32
+        }
+
+        fn context_window(&self) -> u32 {
+            8192
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            1024
+        }
+    }
+
+    fn weather_tool() -> ToolConfig {
+        serde_json::from_value(json!({
+            "name": "get_weather",
+            "description": "Current weather for a city",
+            "input_schema": {"type": "object", "properties": {"city": {"type": "string"}}},
+            "output_schema": {"type": "object", "properties": {"temp": {"type": "number"}}},
+            "static_response": null
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_generate_mock_parses_llm_response() {
+        let llm = FixedLlm(
+            "```json\n{\"strategy\": \"template\", \"template\": \"{\\\"city\\\": \\\"{{ city }}\\\", \\\"temp\\\": 21}\"}\n```",
+        );
+        let result = generate_mock(&llm, &weather_tool()).await.unwrap();
+
+        assert!(matches!(result.mock.strategy, MockStrategyType::Template));
+        assert!(result.mock.template.unwrap().contains("{{ city }}"));
+        assert_eq!(result.confidence, 1.0);
+        assert!(result.notes.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_generate_mock_lowers_confidence_when_unsure() {
+        let llm = FixedLlm(
+            "{\"strategy\": \"random\", \"faker_type\": \"word\"}\nI'm not sure what units the temperature uses, perhaps Celsius.",
+        );
+        let result = generate_mock(&llm, &weather_tool()).await.unwrap();
+
+        assert!(result.confidence < 0.8);
+        assert!(result.notes.unwrap().contains("not sure"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_mock_rejects_invalid_config() {
+        let missing_template = FixedLlm("{\"strategy\": \"template\"}");
+        let err = generate_mock(&missing_template, &weather_tool()).await.unwrap_err();
+        assert!(err.to_string().contains("requires 'template'"));
+
+        let bad_script = FixedLlm("{\"strategy\": \"script\", \"script\": \"let x = ;\"}");
+        assert!(generate_mock(&bad_script, &weather_tool()).await.is_err());
+
+        let no_json = FixedLlm("I can't help with that.");
+        assert!(generate_mock(&no_json, &weather_tool()).await.is_err());
+    }
+}
@@ -1,29 +1,55 @@
+use crate::adapters::callback_tracker::CallbackTracker;
 use crate::adapters::datafusion_handler::DataFusionHandler;
+use crate::adapters::db_strategy_pool::DatabaseStrategyPool;
+use crate::adapters::csv_cache::{CsvCache, CsvTable, FileStamp};
+use crate::adapters::fixture_cache::FixtureCache;
+use crate::adapters::metrics_handler::MetricsCollector;
+use crate::adapters::response_transform;
 use crate::adapters::file_storage::FileStorageHandler;
-use crate::adapters::state_manager::StateManager;
+use crate::adapters::state_manager::{session_key_prefix, StateManager};
+use crate::adapters::strategy_errors::StrategyErrorLog;
+use crate::adapters::tool_handler::strategy_name;
+use crate::domain::execution::ExecutionContext;
 use crate::config::{
     DataFusionConfig, DataLakeCrudConfig, DataLakeCrudOperation, MockConfig, MockStrategyType,
-    StateOperation, ScriptLang, Settings, FakerSchemaConfig, FakerFieldConfig, FakerFieldType,
-    FakerArrayConfig, DataLakeFileFormat, DataRecord,
+    StateOperation, StateScope, StatefulConfig, ScriptLang, Settings, FakerSchemaConfig, FakerFieldConfig, FakerFieldType,
+    FakerArrayConfig, DataLakeFileFormat, DataRecord, FileConfig, MatrixMode, ErrorMockConfig,
+    DatabaseConfig, DatabaseResultMode, CustomFaker, CsvSelection,
 };
 use anyhow::Result;
+use chrono::SecondsFormat;
+use futures::FutureExt;
 use fake::faker::address::en::{CityName, CountryName, PostCode, StateAbbr, StreetName};
 use fake::faker::internet::en::{SafeEmail, Username};
 use fake::faker::lorem::en::{Paragraph, Sentence, Word};
 use fake::faker::name::en::{FirstName, LastName, Name, Title};
 use fake::faker::phone_number::en::PhoneNumber;
-use fake::Fake;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
 use mlua::LuaSerdeExt;
 use rhai::{Engine, Scope};  // Engine used for per-request Rhai script execution
 use rustpython_vm::convert::IntoObject;
 use rustpython_vm::AsObject;
+use serde::Serialize;
 use serde_json::{json, Value};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 use tera::{Context, Tera};
 use tokio::sync::RwLock;
 
+/// Prompt sent by the LLM strategy when the tool call has no `prompt` argument
+pub const DEFAULT_LLM_PROMPT: &str = "Hello";
+
+/// Nesting depth at which schema-driven faker generation stops, so recursive `$ref`s end
+const MAX_FAKER_DEPTH: usize = 16;
+
+tokio::task_local! {
+    /// Random source of a call made with a seed (the `X-Metis-Seed` header)
+    static SEEDED_RNG: RefCell<StdRng>;
+}
+
 pub struct MockStrategyHandler {
     _tera: Tera,
     state_manager: Arc<StateManager>,
@@ -31,6 +57,15 @@ pub struct MockStrategyHandler {
     datafusion_handler: Option<Arc<DataFusionHandler>>,
     settings: Option<Arc<RwLock<Settings>>>,
     file_storage: Option<Arc<FileStorageHandler>>,
+    callbacks: Arc<CallbackTracker>,
+    /// Recent failures of tools' strategies
+    errors: Arc<StrategyErrorLog>,
+    db_pools: Arc<DatabaseStrategyPool>,
+    /// Parsed File-strategy fixtures, kept while their directories are watched
+    fixtures: Option<Arc<FixtureCache>>,
+    /// Parsed Csv-strategy files, re-read when they change
+    csv_tables: Arc<CsvCache>,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 impl MockStrategyHandler {
@@ -51,13 +86,183 @@ impl MockStrategyHandler {
             datafusion_handler,
             settings,
             file_storage,
+            callbacks: Arc::new(CallbackTracker::new()),
+            errors: Arc::new(StrategyErrorLog::new()),
+            db_pools: Arc::new(DatabaseStrategyPool::new()),
+            fixtures: None,
+            csv_tables: Arc::new(CsvCache::new()),
+            metrics: None,
         }
     }
 
+    /// Share a callback tracker with other handlers
+    pub fn with_callback_tracker(mut self, callbacks: Arc<CallbackTracker>) -> Self {
+        self.callbacks = callbacks;
+        self
+    }
+
+    /// Callbacks scheduled by the callback strategy
+    pub fn callbacks(&self) -> &Arc<CallbackTracker> {
+        &self.callbacks
+    }
+
+    /// Share the log of strategy failures with other handlers
+    pub fn with_error_log(mut self, errors: Arc<StrategyErrorLog>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    /// Recent failures of tools' strategies
+    pub fn errors(&self) -> &Arc<StrategyErrorLog> {
+        &self.errors
+    }
+
+    /// Share database connection pools with other handlers
+    pub fn with_db_pools(mut self, db_pools: Arc<DatabaseStrategyPool>) -> Self {
+        self.db_pools = db_pools;
+        self
+    }
+
+    /// Connection pools used by the database strategy
+    pub fn db_pools(&self) -> &Arc<DatabaseStrategyPool> {
+        &self.db_pools
+    }
+
+    /// Keep parsed File-strategy fixtures in `fixtures`. Only use this when the
+    /// fixture directories are watched and the cache is invalidated on change.
+    pub fn with_fixture_cache(mut self, fixtures: Arc<FixtureCache>) -> Self {
+        self.fixtures = Some(fixtures);
+        self
+    }
+
+    /// Cache of parsed File-strategy fixtures, if fixture directories are watched
+    pub fn fixtures(&self) -> Option<&Arc<FixtureCache>> {
+        self.fixtures.as_ref()
+    }
+
+    /// Count strategy errors (e.g. oversized responses) in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     pub async fn generate(
         &self,
         config: &MockConfig,
         args: Option<&serde_json::Value>,
+    ) -> Result<Value> {
+        // A seeded call draws all its random values from one generator seeded
+        // with it, so the same seed gives the same output
+        let seed = ExecutionContext::current().and_then(|context| context.seed);
+        match seed {
+            Some(seed) if !is_seeded() => {
+                let rng = RefCell::new(StdRng::seed_from_u64(seed));
+                SEEDED_RNG.scope(rng, self.generate_checked(config, args)).await
+            }
+            _ => self.generate_checked(config, args).await,
+        }
+    }
+
+    async fn generate_checked(
+        &self,
+        config: &MockConfig,
+        args: Option<&serde_json::Value>,
+    ) -> Result<Value> {
+        if let Some(error_mock) = &config.error_mock {
+            if let Some(error) = self.simulated_error(error_mock, args).await? {
+                return Err(error.into());
+            }
+        }
+        // A panic in a strategy (e.g. inside a script engine) fails only this call
+        let value = match AssertUnwindSafe(self.generate_unchecked(config, args)).catch_unwind().await {
+            Ok(result) => result?,
+            Err(panic) => return Err(self.strategy_panicked(config, panic)),
+        };
+        let value = response_transform::apply(&config.transform, value)?;
+        self.check_response_size(config, &value).await?;
+        Ok(value)
+    }
+
+    /// Count and log a panic caught while generating a response, and turn it into an error
+    fn strategy_panicked(&self, config: &MockConfig, panic: Box<dyn std::any::Any + Send>) -> anyhow::Error {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let strategy = strategy_name(config);
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .strategy_errors
+                .with_label_values(&[strategy.as_str(), "panic"])
+                .inc();
+        }
+        tracing::error!("{} strategy panicked: {}", strategy, message);
+        anyhow::anyhow!("{} strategy failed unexpectedly: {}", strategy, message)
+    }
+
+    /// The error to fail this call with, if `error_mock` rolls one. With `codes`,
+    /// failed calls rotate through them in order.
+    async fn simulated_error(&self, error_mock: &ErrorMockConfig, args: Option<&Value>) -> Result<Option<MockProtocolError>> {
+        if error_mock.rate <= 0.0 || with_rng(|rng| rng.gen::<f64>()) >= error_mock.rate {
+            return Ok(None);
+        }
+
+        let error = if error_mock.codes.is_empty() {
+            error_mock
+        } else {
+            let call = self.state_manager.increment(&rotation_state_key("error_mock", error_mock)?).await;
+            &error_mock.codes[((call - 1) as usize) % error_mock.codes.len()]
+        };
+
+        let message = if error.message_template.is_empty() {
+            format!("Mock error {}", error.code)
+        } else {
+            let mut context = Context::new();
+            if let Some(obj) = args.and_then(|a| a.as_object()) {
+                for (k, v) in obj {
+                    context.insert(k, v);
+                }
+            }
+            self.render_template(&error.message_template, &context)?
+        };
+        Ok(Some(MockProtocolError { code: error.code, message }))
+    }
+
+    /// Refuse a result whose JSON is larger than `server.max_response_bytes`
+    async fn check_response_size(&self, config: &MockConfig, value: &Value) -> Result<()> {
+        let Some(settings) = &self.settings else {
+            return Ok(());
+        };
+        let Some(max_bytes) = settings.read().await.server.max_response_bytes else {
+            return Ok(());
+        };
+
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, value)?;
+        if counter.0 <= max_bytes {
+            return Ok(());
+        }
+
+        let strategy = strategy_name(config);
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .strategy_errors
+                .with_label_values(&[strategy.as_str(), "response_too_large"])
+                .inc();
+        }
+        tracing::warn!("{} strategy produced a {} byte response, over the {} byte limit", strategy, counter.0, max_bytes);
+        Err(anyhow::anyhow!(
+            "Mock response is {} bytes, over the {} byte limit (server.max_response_bytes)",
+            counter.0,
+            max_bytes
+        ))
+    }
+
+    async fn generate_unchecked(
+        &self,
+        config: &MockConfig,
+        args: Option<&serde_json::Value>,
     ) -> Result<Value> {
         match config.strategy {
             MockStrategyType::Static => Ok(json!(null)),
@@ -70,6 +275,74 @@ impl MockStrategyHandler {
             MockStrategyType::LLM => self.generate_llm(config, args).await,
             MockStrategyType::Database => self.generate_database(config, args).await,
             MockStrategyType::DataLakeCrud => self.generate_data_lake_crud(config, args).await,
+            MockStrategyType::Callback => self.generate_callback(config, args),
+            MockStrategyType::Matrix => self.generate_matrix(config, args).await,
+            MockStrategyType::Csv => self.generate_csv(config, args).await,
+        }
+    }
+
+    /// Return one of the configured pairs' output, picked according to the matrix mode
+    async fn generate_matrix(&self, config: &MockConfig, args: Option<&Value>) -> Result<Value> {
+        let matrix = config.matrix.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Matrix config not provided"))?;
+        if matrix.pairs.is_empty() {
+            return Err(anyhow::anyhow!("Matrix config has no pairs"));
+        }
+
+        let pair = match matrix.mode {
+            MatrixMode::RoundRobin => {
+                // Each distinct matrix keeps its own position in the rotation
+                let call = self.state_manager.increment(&rotation_state_key("matrix", matrix)?).await;
+                &matrix.pairs[((call - 1) as usize) % matrix.pairs.len()]
+            }
+            MatrixMode::InputMatch => {
+                let args = args.cloned().unwrap_or_else(|| json!({}));
+                matrix.pairs.iter()
+                    .find(|pair| pair.input_match.as_ref().is_none_or(|m| json_contains(&args, m)))
+                    .ok_or_else(|| anyhow::anyhow!("No matrix pair matches the arguments {}", args))?
+            }
+            MatrixMode::Random => &matrix.pairs[with_rng(|rng| rng.gen_range(0..matrix.pairs.len()))],
+        };
+        Ok(pair.output.clone())
+    }
+
+    /// Schedule a POST to the configured URL and return an acknowledgment right away
+    fn generate_callback(&self, config: &MockConfig, args: Option<&Value>) -> Result<Value> {
+        let callback = config.callback.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Callback config not provided"))?;
+
+        let callback_id = uuid::Uuid::new_v4().to_string();
+        let mut context = Context::new();
+        if let Some(obj) = args.and_then(|a| a.as_object()) {
+            for (k, v) in obj {
+                context.insert(k, v);
+            }
+        }
+        context.insert("callback_id", &callback_id);
+
+        let url = Tera::one_off(&callback.url, &context, false)?;
+        let payload = match &callback.payload_template {
+            Some(template) => {
+                let rendered = Tera::one_off(template, &context, false)?;
+                serde_json::from_str(&rendered).unwrap_or(Value::String(rendered))
+            }
+            None => args.cloned().unwrap_or(Value::Null),
+        };
+
+        self.callbacks.schedule(
+            callback_id.clone(),
+            url,
+            payload,
+            callback.headers.clone(),
+            std::time::Duration::from_millis(callback.delay_ms),
+        )?;
+
+        match &callback.ack_template {
+            Some(template) => {
+                let rendered = Tera::one_off(template, &context, false)?;
+                Ok(serde_json::from_str(&rendered).unwrap_or(Value::String(rendered)))
+            }
+            None => Ok(json!({"status": "accepted", "callback_id": callback_id})),
         }
     }
 
@@ -82,12 +355,14 @@ impl MockStrategyHandler {
             .ok_or_else(|| anyhow::anyhow!("Database config not provided"))?;
 
         // Check if using DataFusion for datalake queries
-        if db_config.db_type == crate::config::DatabaseType::DataFusion {
-            return self.generate_datafusion(db_config, args).await;
-        }
+        let rows = if db_config.db_type == crate::config::DatabaseType::DataFusion {
+            self.generate_datafusion(db_config, args).await?
+        } else {
+            // Traditional database query via sqlx
+            self.generate_sqlx_database(db_config, args).await?
+        };
 
-        // Traditional database query via sqlx
-        self.generate_sqlx_database(db_config, args).await
+        shape_database_rows(rows, db_config)
     }
 
     async fn generate_datafusion(
@@ -406,53 +681,63 @@ impl MockStrategyHandler {
         }
     }
 
+    /// URL and pool size to query: the `[databases]` entry named by
+    /// `connection`, or the mock's own `url`
+    async fn database_connection(&self, db_config: &crate::config::DatabaseConfig) -> Result<(String, u32)> {
+        let Some(name) = &db_config.connection else {
+            return Ok((db_config.url.clone(), db_config.max_connections));
+        };
+        let settings = self.settings.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database connection '{}' needs settings to resolve", name))?;
+        let settings = settings.read().await;
+        let database = settings.databases.get(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown database connection '{}'", name))?;
+        Ok((database.url.clone(), database.max_connections))
+    }
+
     async fn generate_sqlx_database(
         &self,
         db_config: &crate::config::DatabaseConfig,
         args: Option<&serde_json::Value>,
     ) -> Result<Value> {
-        use sqlx::any::AnyPoolOptions;
         use sqlx::Row;
         use sqlx::Column;
 
-        // Ensure drivers are installed (safe to call multiple times)
-        sqlx::any::install_default_drivers();
+        // Arguments are only ever bound, so each placeholder needs exactly one param
+        db_config
+            .check_params()
+            .map_err(|e| anyhow::anyhow!("Invalid database config: {}", e))?;
 
-        // Create connection pool (in a real app, we should cache this)
-        // For now, we create a new pool for each request which is not optimal but functional
-        let pool = AnyPoolOptions::new()
-            .max_connections(1)
-            .connect(&db_config.url)
-            .await
-            .map_err(|e| anyhow::anyhow!("Database connection error: {}", e))?;
+        let (url, max_connections) = self.database_connection(db_config).await?;
+        if db_config.seed_file.is_some() && !url.starts_with("sqlite:") {
+            return Err(anyhow::anyhow!("seed_file is only supported for sqlite databases, not '{}'", url));
+        }
+        let mut connection = self.db_pools.acquire(&url, max_connections).await?;
+        if let Some(seed_file) = &db_config.seed_file {
+            self.db_pools.seed_once(&url, seed_file, &mut connection).await?;
+        }
 
         let mut query_builder = sqlx::query(&db_config.query);
 
-        // Bind parameters
-        if let Some(args_val) = args {
-            for param_name in &db_config.params {
-                if let Some(val) = args_val.get(param_name) {
-                    match val {
-                        Value::String(s) => query_builder = query_builder.bind(s),
-                        Value::Number(n) => {
-                            if let Some(i) = n.as_i64() {
-                                query_builder = query_builder.bind(i);
-                            } else if let Some(f) = n.as_f64() {
-                                query_builder = query_builder.bind(f);
-                            }
-                        }
-                        Value::Bool(b) => query_builder = query_builder.bind(b),
-                        _ => query_builder = query_builder.bind(val.to_string()),
+        // Bind parameters, with null for arguments the call didn't supply
+        for param_name in &db_config.params {
+            match args.and_then(|a| a.get(param_name)) {
+                Some(Value::String(s)) => query_builder = query_builder.bind(s),
+                Some(Value::Number(n)) => {
+                    if let Some(i) = n.as_i64() {
+                        query_builder = query_builder.bind(i);
+                    } else {
+                        query_builder = query_builder.bind(n.as_f64());
                     }
-                } else {
-                    // If param missing, bind null or error? Let's bind null for now
-                    query_builder = query_builder.bind(Option::<String>::None);
                 }
+                Some(Value::Bool(b)) => query_builder = query_builder.bind(b),
+                Some(Value::Null) | None => query_builder = query_builder.bind(Option::<String>::None),
+                Some(val) => query_builder = query_builder.bind(val.to_string()),
             }
         }
 
         let rows = query_builder
-            .fetch_all(&pool)
+            .fetch_all(&mut *connection)
             .await
             .map_err(|e| anyhow::anyhow!("Database query error: {}", e))?;
 
@@ -521,10 +806,10 @@ impl MockStrategyHandler {
         let prompt = if let Some(args) = args {
             args.get("prompt")
                 .and_then(|v| v.as_str())
-                .unwrap_or("Hello")
+                .unwrap_or(DEFAULT_LLM_PROMPT)
                 .to_string()
         } else {
-            "Hello".to_string()
+            DEFAULT_LLM_PROMPT.to_string()
         };
 
         match llm_config.provider {
@@ -599,6 +884,26 @@ impl MockStrategyHandler {
             request_builder.max_tokens(max_tokens as u16);
         }
 
+        if let Some(stop) = &config.stop {
+            request_builder.stop(Stop::StringArray(stop.clone()));
+        }
+
+        if let Some(top_p) = config.top_p {
+            request_builder.top_p(top_p);
+        }
+
+        if let Some(penalty) = config.frequency_penalty {
+            request_builder.frequency_penalty(penalty);
+        }
+
+        if let Some(penalty) = config.presence_penalty {
+            request_builder.presence_penalty(penalty);
+        }
+
+        if let Some(seed) = config.seed {
+            request_builder.seed(seed as i64);
+        }
+
         let request = request_builder.build()?;
 
         let response = client
@@ -643,6 +948,18 @@ impl MockStrategyHandler {
             request_body["temperature"] = json!(temp);
         }
 
+        if let Some(stop) = &config.stop {
+            request_body["stop_sequences"] = json!(stop);
+        }
+
+        if let Some(top_p) = config.top_p {
+            request_body["top_p"] = json!(top_p);
+        }
+
+        if config.frequency_penalty.is_some() || config.presence_penalty.is_some() || config.seed.is_some() {
+            tracing::debug!("Anthropic does not support frequency_penalty, presence_penalty or seed; ignoring them");
+        }
+
         let response = client
             .post("https://api.anthropic.com/v1/messages")
             .header("x-api-key", api_key)
@@ -707,6 +1024,26 @@ impl MockStrategyHandler {
             request_body["generationConfig"]["maxOutputTokens"] = json!(max_tokens);
         }
 
+        if let Some(stop) = &config.stop {
+            request_body["generationConfig"]["stopSequences"] = json!(stop);
+        }
+
+        if let Some(top_p) = config.top_p {
+            request_body["generationConfig"]["topP"] = json!(top_p);
+        }
+
+        if let Some(penalty) = config.frequency_penalty {
+            request_body["generationConfig"]["frequencyPenalty"] = json!(penalty);
+        }
+
+        if let Some(penalty) = config.presence_penalty {
+            request_body["generationConfig"]["presencePenalty"] = json!(penalty);
+        }
+
+        if let Some(seed) = config.seed {
+            request_body["generationConfig"]["seed"] = json!(seed);
+        }
+
         let url = format!(
             "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
             config.model, api_key
@@ -780,6 +1117,15 @@ impl MockStrategyHandler {
             request_body["options"] = json!({"temperature": temp});
         }
 
+        if config.stop.is_some()
+            || config.top_p.is_some()
+            || config.frequency_penalty.is_some()
+            || config.presence_penalty.is_some()
+            || config.seed.is_some()
+        {
+            tracing::debug!("Ollama mock strategy ignores stop, top_p, penalties and seed");
+        }
+
         let url = format!("{}/api/chat", base_url);
 
         let response = client
@@ -821,7 +1167,7 @@ impl MockStrategyHandler {
             }
             
             // One-off rendering for now. For performance, we should pre-compile templates.
-            let rendered = Tera::one_off(template_str, &context, false)?;
+            let rendered = self.render_template(template_str, &context)?;
             
             // Try to parse as JSON, otherwise return as string
             if let Ok(json_val) = serde_json::from_str::<Value>(&rendered) {
@@ -834,6 +1180,21 @@ impl MockStrategyHandler {
         }
     }
 
+    /// Render a one-off Tera template with `gen_id(key=...)` bound to this handler's state
+    fn render_template(&self, template: &str, context: &Context) -> Result<String> {
+        let mut tera = Tera::default();
+        tera.autoescape_on(vec![]);
+        let state_manager = self.state_manager.clone();
+        tera.register_function("gen_id", move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let key = args
+                .get("key")
+                .and_then(Value::as_str)
+                .ok_or_else(|| tera::Error::msg("gen_id requires a string `key` argument"))?;
+            Ok(json!(block_on_state(state_manager.stable_id(key))))
+        });
+        Ok(tera.render_str(template, context)?)
+    }
+
     async fn generate_random(&self, config: &MockConfig) -> Result<Value> {
         // If faker_schema is provided, use schema-driven generation
         if let Some(faker_schema) = &config.faker_schema {
@@ -842,14 +1203,17 @@ impl MockStrategyHandler {
 
         // Fall back to simple faker_type generation
         if let Some(faker_type) = &config.faker_type {
+            if let Some(custom) = self.custom_faker(faker_type).await {
+                return Ok(json!(self.generate_custom_faker(&custom)?));
+            }
             match faker_type.as_str() {
-                "name" => Ok(json!(Name().fake::<String>())),
-                "title" => Ok(json!(Title().fake::<String>())),
-                "email" => Ok(json!(SafeEmail().fake::<String>())),
-                "username" => Ok(json!(Username().fake::<String>())),
-                "word" => Ok(json!(Word().fake::<String>())),
-                "sentence" => Ok(json!(Sentence(1..10).fake::<String>())),
-                "paragraph" => Ok(json!(Paragraph(1..3).fake::<String>())),
+                "name" => Ok(json!(fake_string(Name()))),
+                "title" => Ok(json!(fake_string(Title()))),
+                "email" => Ok(json!(fake_string(SafeEmail()))),
+                "username" => Ok(json!(fake_string(Username()))),
+                "word" => Ok(json!(fake_string(Word()))),
+                "sentence" => Ok(json!(fake_string(Sentence(1..10)))),
+                "paragraph" => Ok(json!(fake_string(Paragraph(1..3)))),
                 _ => Ok(json!(format!("Unknown faker type: {}", faker_type))),
             }
         } else {
@@ -857,6 +1221,22 @@ impl MockStrategyHandler {
         }
     }
 
+    /// The `custom_fakers` entry called `name`, if any
+    async fn custom_faker(&self, name: &str) -> Option<CustomFaker> {
+        let settings = self.settings.as_ref()?;
+        settings.read().await.custom_fakers.get(name).cloned()
+    }
+
+    fn generate_custom_faker(&self, faker: &CustomFaker) -> Result<String> {
+        match faker {
+            // Generated values match the whole pattern, so anchors add nothing
+            CustomFaker::Regex(pattern) => {
+                self.expand_pattern(pattern.trim_start_matches('^').trim_end_matches('$'))
+            }
+            CustomFaker::Template { template } => Ok(self.generate_from_pattern(template)),
+        }
+    }
+
     /// Public method to generate data from a faker schema configuration
     /// Used by data lake record generation
     pub fn generate_from_faker_config(&self, schema_config: &FakerSchemaConfig) -> Result<Value> {
@@ -864,59 +1244,134 @@ impl MockStrategyHandler {
     }
 
     /// Generate data from a schema-driven faker configuration
+    ///
+    /// Walks `schema` (an empty object schema when unset) recursively, so
+    /// arrays of objects can nest to any depth. Properties and arrays that only
+    /// appear as `fields`/`arrays` paths (e.g. "orders[*].items[*].sku") are
+    /// generated as well.
     fn generate_from_schema(&self, schema_config: &FakerSchemaConfig) -> Result<Value> {
-        let mut result = serde_json::Map::new();
+        let root = schema_config.schema.clone().unwrap_or_else(|| json!({"type": "object"}));
+        self.generate_schema_node(&root, &root, "", schema_config, 0)
+    }
 
-        // Process all field configurations
-        for (path, field_config) in &schema_config.fields {
+    /// Generate the value at `path`, preferring its configured faker over the schema
+    fn generate_schema_node(
+        &self,
+        root: &Value,
+        schema: &Value,
+        path: &str,
+        config: &FakerSchemaConfig,
+        depth: usize,
+    ) -> Result<Value> {
+        let schema = resolve_schema_ref(root, schema);
+        if let Some(field_config) = config.fields.get(path) {
             let value = self.generate_faker_value(field_config)?;
-            self.set_nested_value(&mut result, path, value, &schema_config.arrays)?;
+            return Ok(constrain_to_schema(value, schema));
+        }
+        if depth > MAX_FAKER_DEPTH {
+            return Ok(Value::Null);
+        }
+
+        if let Some(variants) = schema_variants(schema) {
+            let idx = with_rng(|rng| rng.gen_range(0..variants.len()));
+            return self.generate_schema_node(root, &variants[idx], path, config, depth + 1);
         }
 
-        Ok(Value::Object(result))
+        match schema_node_kind(schema, path, config) {
+            "object" => {
+                let mut object = serde_json::Map::new();
+                for name in property_names(schema, path, config) {
+                    let property = schema.get("properties").and_then(|p| p.get(&name)).unwrap_or(&Value::Null);
+                    let value = self.generate_schema_node(root, property, &join_path(path, &name), config, depth + 1)?;
+                    object.insert(name, value);
+                }
+                Ok(Value::Object(object))
+            }
+            "array" => {
+                let (min_items, max_items) = array_bounds(schema, path, config);
+                let count = with_rng(|rng| rng.gen_range(min_items..=max_items));
+                let items = schema.get("items").unwrap_or(&Value::Null);
+                let item_path = format!("{}[*]", path);
+                let values = (0..count)
+                    .map(|_| self.generate_schema_node(root, items, &item_path, config, depth + 1))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Value::Array(values))
+            }
+            "boolean" => Ok(json!(with_rng(|rng| rng.gen_bool(0.5)))),
+            "null" => Ok(Value::Null),
+            _ => self.generate_schema_leaf(schema),
+        }
+    }
+
+    /// Generate a value for a leaf schema without a configured faker, honouring
+    /// its `const`, `enum`, `format` (date-time, date, email, uuid), `pattern`,
+    /// bounds and lengths
+    fn generate_schema_leaf(&self, schema: &Value) -> Result<Value> {
+        if let Some(constant) = schema.get("const") {
+            return Ok(constant.clone());
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array).filter(|v| !v.is_empty()) {
+            let idx = with_rng(|rng| rng.gen_range(0..values.len()));
+            return Ok(values[idx].clone());
+        }
+
+        let format = schema.get("format").and_then(Value::as_str);
+        match format {
+            Some("date-time") => return Ok(json!(random_past_time().to_rfc3339_opts(SecondsFormat::Secs, true))),
+            Some("date") => return Ok(json!(random_past_time().format("%Y-%m-%d").to_string())),
+            _ => {}
+        }
+        if let (None, Some(pattern)) = (format, schema.get("pattern").and_then(Value::as_str)) {
+            // JSON Schema patterns are unanchored regexes; anchors add nothing to a generated value
+            let pattern = pattern.trim_start_matches('^').trim_end_matches('$');
+            return Ok(constrain_to_schema(json!(self.expand_pattern(pattern)?), schema));
+        }
+
+        let value = self.generate_faker_value(&default_faker_config(schema))?;
+        Ok(constrain_to_schema(value, schema))
     }
 
     /// Generate a single faker value based on field configuration
     fn generate_faker_value(&self, config: &FakerFieldConfig) -> Result<Value> {
         match config.faker_type {
             // Personal
-            FakerFieldType::FirstName => Ok(json!(FirstName().fake::<String>())),
-            FakerFieldType::LastName => Ok(json!(LastName().fake::<String>())),
-            FakerFieldType::FullName => Ok(json!(Name().fake::<String>())),
-            FakerFieldType::Username => Ok(json!(Username().fake::<String>())),
+            FakerFieldType::FirstName => Ok(json!(fake_string(FirstName()))),
+            FakerFieldType::LastName => Ok(json!(fake_string(LastName()))),
+            FakerFieldType::FullName => Ok(json!(fake_string(Name()))),
+            FakerFieldType::Username => Ok(json!(fake_string(Username()))),
 
             // Contact
-            FakerFieldType::Email => Ok(json!(SafeEmail().fake::<String>())),
-            FakerFieldType::Phone => Ok(json!(PhoneNumber().fake::<String>())),
+            FakerFieldType::Email => Ok(json!(fake_string(SafeEmail()))),
+            FakerFieldType::Phone => Ok(json!(fake_string(PhoneNumber()))),
 
             // Address
-            FakerFieldType::StreetAddress => Ok(json!(StreetName().fake::<String>())),
-            FakerFieldType::City => Ok(json!(CityName().fake::<String>())),
-            FakerFieldType::State => Ok(json!(StateAbbr().fake::<String>())),
-            FakerFieldType::Country => Ok(json!(CountryName().fake::<String>())),
-            FakerFieldType::PostalCode => Ok(json!(PostCode().fake::<String>())),
+            FakerFieldType::StreetAddress => Ok(json!(fake_string(StreetName()))),
+            FakerFieldType::City => Ok(json!(fake_string(CityName()))),
+            FakerFieldType::State => Ok(json!(fake_string(StateAbbr()))),
+            FakerFieldType::Country => Ok(json!(fake_string(CountryName()))),
+            FakerFieldType::PostalCode => Ok(json!(fake_string(PostCode()))),
 
             // Text
-            FakerFieldType::Word => Ok(json!(Word().fake::<String>())),
-            FakerFieldType::Sentence => Ok(json!(Sentence(1..10).fake::<String>())),
-            FakerFieldType::Paragraph => Ok(json!(Paragraph(1..3).fake::<String>())),
+            FakerFieldType::Word => Ok(json!(fake_string(Word()))),
+            FakerFieldType::Sentence => Ok(json!(fake_string(Sentence(1..10)))),
+            FakerFieldType::Paragraph => Ok(json!(fake_string(Paragraph(1..3)))),
 
             // Numbers
             FakerFieldType::Integer => {
                 let min = config.min.unwrap_or(0.0) as i64;
                 let max = config.max.unwrap_or(100.0) as i64;
-                let value: i64 = rand::thread_rng().gen_range(min..=max);
+                let value: i64 = with_rng(|rng| rng.gen_range(min..=max));
                 Ok(json!(value))
             }
             FakerFieldType::Float => {
                 let min = config.min.unwrap_or(0.0);
                 let max = config.max.unwrap_or(100.0);
-                let value: f64 = rand::thread_rng().gen_range(min..=max);
+                let value: f64 = with_rng(|rng| rng.gen_range(min..=max));
                 Ok(json!(value))
             }
 
             // Identifiers
-            FakerFieldType::Uuid => Ok(json!(uuid::Uuid::new_v4().to_string())),
+            FakerFieldType::Uuid => Ok(json!(random_uuid().to_string())),
 
             // Special
             FakerFieldType::Pattern => {
@@ -930,7 +1385,7 @@ impl MockStrategyHandler {
             FakerFieldType::Enum => {
                 if let Some(enum_values) = &config.enum_values {
                     if !enum_values.is_empty() {
-                        let idx = rand::thread_rng().gen_range(0..enum_values.len());
+                        let idx = with_rng(|rng| rng.gen_range(0..enum_values.len()));
                         Ok(json!(enum_values[idx].clone()))
                     } else {
                         Ok(json!(""))
@@ -948,160 +1403,73 @@ impl MockStrategyHandler {
             }
 
             // Default
-            FakerFieldType::Lorem => Ok(json!(Sentence(1..5).fake::<String>())),
+            FakerFieldType::Lorem => Ok(json!(fake_string(Sentence(1..5)))),
         }
     }
 
     /// Generate string from a simple pattern (basic implementation)
     fn generate_from_pattern(&self, pattern: &str) -> String {
-        let mut result = String::new();
-        let mut rng = rand::thread_rng();
-
-        for c in pattern.chars() {
-            match c {
-                '#' => result.push_str(&rng.gen_range(0..10).to_string()),
-                '?' => result.push(rng.gen_range(b'a'..=b'z') as char),
-                '*' => {
-                    if rng.gen_bool(0.5) {
-                        result.push_str(&rng.gen_range(0..10).to_string())
-                    } else {
-                        result.push(rng.gen_range(b'a'..=b'z') as char)
-                    }
-                }
-                _ => result.push(c),
-            }
-        }
-        result
-    }
-
-    /// Set a value at a nested path in the result object
-    fn set_nested_value(
-        &self,
-        result: &mut serde_json::Map<String, Value>,
-        path: &str,
-        value: Value,
-        array_configs: &std::collections::HashMap<String, FakerArrayConfig>,
-    ) -> Result<()> {
-        // Parse path segments (e.g., "user.address.city" or "items[*].name")
-        let segments: Vec<&str> = path.split('.').collect();
-
-        if segments.is_empty() {
-            return Ok(());
-        }
-
-        // Handle array wildcard paths specially
-        if path.contains("[*]") {
-            // Find the array path
-            let array_path_end = path.find("[*]").unwrap();
-            let array_path = &path[..array_path_end];
-            let item_field = &path[array_path_end + 4..]; // Skip "[*]."
-
-            // Get array config for size
-            let array_config = array_configs.get(array_path);
-            let (min_items, max_items) = if let Some(cfg) = array_config {
-                (cfg.min_items, cfg.max_items)
-            } else {
-                (1, 3) // Default
-            };
-
-            let count = rand::thread_rng().gen_range(min_items..=max_items);
-
-            // Navigate to/create the array
-            let array_segments: Vec<&str> = array_path.split('.').collect();
-            let mut current = result;
-
-            for (i, seg) in array_segments.iter().enumerate() {
-                if i == array_segments.len() - 1 {
-                    // Last segment - create/get the array
-                    let arr = current.entry(seg.to_string())
-                        .or_insert_with(|| Value::Array(vec![]));
-
-                    if let Value::Array(items) = arr {
-                        // Ensure we have enough items
-                        while items.len() < count {
-                            items.push(Value::Object(serde_json::Map::new()));
-                        }
-
-                        // Set the field on each item
-                        for item in items.iter_mut() {
-                            if let Value::Object(obj) = item {
-                                // Handle nested fields in item
-                                if item_field.contains('.') {
-                                    let nested_segments: Vec<&str> = item_field.split('.').collect();
-                                    let mut nested_current = obj;
-
-                                    for (j, nested_seg) in nested_segments.iter().enumerate() {
-                                        if j == nested_segments.len() - 1 {
-                                            nested_current.insert(nested_seg.to_string(), value.clone());
-                                        } else {
-                                            nested_current = nested_current
-                                                .entry(nested_seg.to_string())
-                                                .or_insert_with(|| Value::Object(serde_json::Map::new()))
-                                                .as_object_mut()
-                                                .unwrap();
-                                        }
-                                    }
-                                } else {
-                                    obj.insert(item_field.to_string(), value.clone());
-                                }
-                            }
+        with_rng(|rng| {
+            let mut result = String::new();
+            for c in pattern.chars() {
+                match c {
+                    '#' => result.push_str(&rng.gen_range(0..10).to_string()),
+                    '?' => result.push(rng.gen_range(b'a'..=b'z') as char),
+                    '*' => {
+                        if rng.gen_bool(0.5) {
+                            result.push_str(&rng.gen_range(0..10).to_string())
+                        } else {
+                            result.push(rng.gen_range(b'a'..=b'z') as char)
                         }
                     }
-                } else {
-                    // Navigate deeper
-                    current = current
-                        .entry(seg.to_string())
-                        .or_insert_with(|| Value::Object(serde_json::Map::new()))
-                        .as_object_mut()
-                        .ok_or_else(|| anyhow::anyhow!("Expected object at path segment: {}", seg))?;
+                    _ => result.push(c),
                 }
             }
-        } else {
-            // Simple non-array path
-            let mut current = result;
+            result
+        })
+    }
 
-            for (i, seg) in segments.iter().enumerate() {
-                if i == segments.len() - 1 {
-                    // Last segment - insert the value
-                    current.insert(seg.to_string(), value.clone());
-                } else {
-                    // Navigate deeper, creating objects as needed
-                    current = current
-                        .entry(seg.to_string())
-                        .or_insert_with(|| Value::Object(serde_json::Map::new()))
-                        .as_object_mut()
-                        .ok_or_else(|| anyhow::anyhow!("Expected object at path segment: {}", seg))?;
-                }
+    /// State key a stateful mock reads and writes, prefixed with the MCP session
+    /// id for session-scoped state so each session gets its own value
+    async fn stateful_state_key(&self, config: &StatefulConfig) -> String {
+        let session = match config.scope {
+            StateScope::Global => None,
+            StateScope::Session => ExecutionContext::current().and_then(|ctx| ctx.session_id),
+        };
+        match session {
+            Some(session) => {
+                self.state_manager.touch_session(&session).await;
+                format!("{}{}", session_key_prefix(&session), config.state_key)
             }
+            None => config.state_key.clone(),
         }
-
-        Ok(())
     }
 
     async fn generate_stateful(&self, config: &MockConfig, args: Option<&Value>) -> Result<Value> {
         if let Some(stateful_config) = &config.stateful {
+            let state_key = self.stateful_state_key(stateful_config).await;
             match stateful_config.operation {
                 StateOperation::Get => {
-                    let value = self.state_manager.get(&stateful_config.state_key).await
+                    let value = self.state_manager.get(&state_key).await
                         .unwrap_or(Value::Null);
                     Ok(value)
                 }
                 StateOperation::Set => {
                     if let Some(args_val) = args {
-                        self.state_manager.set(stateful_config.state_key.clone(), args_val.clone()).await;
+                        self.state_manager.set(state_key, args_val.clone()).await;
                         Ok(args_val.clone())
                     } else {
                         Ok(Value::Null)
                     }
                 }
                 StateOperation::Increment => {
-                    let new_value = self.state_manager.increment(&stateful_config.state_key).await;
+                    let new_value = self.state_manager.increment(&state_key).await;
                     
                     // If template is provided, render it with the new value
                     if let Some(template_str) = &stateful_config.template {
                         let mut context = Context::new();
                         context.insert("value", &new_value);
-                        let rendered = Tera::one_off(template_str, &context, false)?;
+                        let rendered = self.render_template(template_str, &context)?;
                         if let Ok(json_val) = serde_json::from_str::<Value>(&rendered) {
                             Ok(json_val)
                         } else {
@@ -1190,9 +1558,11 @@ impl MockStrategyHandler {
         let mut engine = Engine::new();
 
         // Register standard faker functions
-        engine.register_fn("fake_name", || Name().fake::<String>());
-        engine.register_fn("fake_email", || SafeEmail().fake::<String>());
-        engine.register_fn("fake_sentence", || Sentence(1..10).fake::<String>());
+        engine.register_fn("fake_name", || fake_string(Name()));
+        engine.register_fn("fake_email", || fake_string(SafeEmail()));
+        engine.register_fn("fake_sentence", || fake_string(Sentence(1..10)));
+        let state_manager = self.state_manager.clone();
+        engine.register_fn("gen_id", move |key: &str| block_on_state(state_manager.stable_id(key)));
 
         // Register datafusion_query if configured
         if let Some(config) = df_config {
@@ -1582,25 +1952,43 @@ impl MockStrategyHandler {
 
     async fn generate_file(&self, config: &MockConfig) -> Result<Value> {
         if let Some(file_config) = &config.file {
-            // Read file content
-            let content = tokio::fs::read_to_string(&file_config.path).await?;
-
-            // Try to parse as JSON array first
-            let data: Vec<Value> = match serde_json::from_str(&content) {
-                Ok(arr) => arr,
-                Err(_) => {
-                    // Try parsing as JSON Lines (one JSON object per line)
-                    let lines: Vec<Value> = content
-                        .lines()
-                        .filter(|line| !line.trim().is_empty())
-                        .filter_map(|line| serde_json::from_str(line).ok())
-                        .collect();
-
-                    if lines.is_empty() {
-                        // Return raw content as string if not JSON
+            let cached = match &self.fixtures {
+                Some(fixtures) if !file_config.stream => fixtures.get(&file_config.path),
+                _ => None,
+            };
+            let data = match cached {
+                Some(records) => records,
+                None => {
+                    // Read file content
+                    let (mut file, size) = open_mock_file(file_config).await?;
+                    let mut content = String::with_capacity(size as usize);
+                    tokio::io::AsyncReadExt::read_to_string(&mut file, &mut content).await?;
+                    if file_config.stream {
                         return Ok(Value::String(content));
                     }
-                    lines
+
+                    // Try to parse as JSON array first
+                    let records: Vec<Value> = match serde_json::from_str(&content) {
+                        Ok(arr) => arr,
+                        Err(_) => {
+                            // Try parsing as JSON Lines (one JSON object per line)
+                            let lines: Vec<Value> = content
+                                .lines()
+                                .filter(|line| !line.trim().is_empty())
+                                .filter_map(|line| serde_json::from_str(line).ok())
+                                .collect();
+
+                            if lines.is_empty() {
+                                // Return raw content as string if not JSON
+                                return Ok(Value::String(content));
+                            }
+                            lines
+                        }
+                    };
+                    match &self.fixtures {
+                        Some(fixtures) => fixtures.insert(&file_config.path, records),
+                        None => Arc::new(records),
+                    }
                 }
             };
 
@@ -1611,9 +1999,7 @@ impl MockStrategyHandler {
             // Select based on strategy
             let selected = match file_config.selection.as_str() {
                 "random" => {
-                    use rand::Rng;
-                    let mut rng = rand::thread_rng();
-                    let idx = rng.gen_range(0..data.len());
+                    let idx = with_rng(|rng| rng.gen_range(0..data.len()));
                     data[idx].clone()
                 }
                 "sequential" => {
@@ -1635,6 +2021,75 @@ impl MockStrategyHandler {
         }
     }
 
+    /// Return one row of a CSV file, picked by `selection`, as an object keyed
+    /// by the column headers. Null when the file has no rows or no row matches.
+    async fn generate_csv(&self, config: &MockConfig, args: Option<&Value>) -> Result<Value> {
+        let csv_config = config.csv.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Csv config not provided"))?;
+        let is_excel = std::path::Path::new(&csv_config.path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx") || ext.eq_ignore_ascii_case("xls"));
+        if is_excel {
+            return Err(anyhow::anyhow!(
+                "'{}' is an Excel workbook; export the sheet as CSV for the Csv strategy",
+                csv_config.path
+            ));
+        }
+
+        let (mut file, size) = open_limited_file(&csv_config.path, csv_config.max_bytes, "csv.max_bytes").await?;
+        let stamp = FileStamp::of(&file.metadata().await?);
+        let table = match self.csv_tables.get(&csv_config.path, stamp) {
+            Some(table) => table,
+            None => {
+                let mut content = String::with_capacity(size as usize);
+                tokio::io::AsyncReadExt::read_to_string(&mut file, &mut content).await?;
+
+                let mut reader = csv::Reader::from_reader(content.as_bytes());
+                let headers = reader.headers()
+                    .map_err(|e| anyhow::anyhow!("Failed to read the headers of CSV file '{}': {}", csv_config.path, e))?
+                    .clone();
+                let rows = reader.records()
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| anyhow::anyhow!("Failed to parse CSV file '{}': {}", csv_config.path, e))?;
+                self.csv_tables.insert(&csv_config.path, stamp, CsvTable { headers, rows })
+            }
+        };
+        let (headers, rows) = (&table.headers, &table.rows);
+        if rows.is_empty() {
+            return Ok(Value::Null);
+        }
+
+        let row = match csv_config.selection {
+            CsvSelection::Random => {
+                let idx = with_rng(|rng| rng.gen_range(0..rows.len()));
+                rows.get(idx)
+            }
+            CsvSelection::Sequential => {
+                let state_key = format!("__csv_seq_{}", csv_config.path);
+                let current_idx = self.state_manager.increment(&state_key).await;
+                rows.get(((current_idx - 1) as usize) % rows.len())
+            }
+            CsvSelection::Index => rows.get(csv_config.index.unwrap_or(0)),
+            CsvSelection::Key => {
+                let column = csv_config.key_column.as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("Csv selection 'key' needs a key_column"))?;
+                let position = headers.iter().position(|h| h == column).ok_or_else(|| {
+                    anyhow::anyhow!("CSV file '{}' has no column '{}'", csv_config.path, column)
+                })?;
+                let wanted = match args.and_then(|a| a.get(column)) {
+                    Some(Value::String(value)) => value.clone(),
+                    Some(Value::Null) | None => {
+                        return Err(anyhow::anyhow!("Missing argument '{}' to look up in '{}'", column, csv_config.path));
+                    }
+                    Some(value) => value.to_string(),
+                };
+                rows.iter().find(|row| row.get(position) == Some(wanted.as_str()))
+            }
+        };
+
+        Ok(row.map(|row| csv_row_to_json(headers, row)).unwrap_or(Value::Null))
+    }
+
     fn generate_pattern(&self, config: &MockConfig) -> Result<Value> {
         if let Some(pattern) = &config.pattern {
             let result = self.expand_pattern(pattern)?;
@@ -1656,59 +2111,26 @@ impl MockStrategyHandler {
     /// - `\X` - random uppercase hex digit (0-9A-F)
     /// - `\s` - space character
     /// - `\n` - newline character
-    /// - `{n}` - repeat previous pattern n times
-    /// - `{n,m}` - repeat previous pattern n to m times
+    /// - `{n}` - generate the previous element n times
+    /// - `{n,m}` - generate the previous element n to m times
     /// - `[abc]` - one of the characters in brackets
     /// - `[a-z]` - one character from range
     /// - `\\` - literal backslash
     fn expand_pattern(&self, pattern: &str) -> Result<String> {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+        with_rng(|rng| self.expand_pattern_with(pattern, rng))
+    }
+
+    fn expand_pattern_with(&self, pattern: &str, rng: &mut dyn RngCore) -> Result<String> {
         let mut result = String::new();
         let mut chars = pattern.chars().peekable();
-        let mut last_char: Option<char> = None;
+        let mut last_atom: Option<PatternAtom> = None;
 
         while let Some(ch) = chars.next() {
-            match ch {
-                '\\' => {
-                    if let Some(next) = chars.next() {
-                        let generated = match next {
-                            'd' => rng.gen_range(0..10).to_string(),
-                            'D' => (rng.gen_range(b'a'..=b'z') as char).to_string(),
-                            'w' => {
-                                let choices: Vec<char> = ('a'..='z')
-                                    .chain('A'..='Z')
-                                    .chain('0'..='9')
-                                    .chain(std::iter::once('_'))
-                                    .collect();
-                                choices[rng.gen_range(0..choices.len())].to_string()
-                            }
-                            'W' => {
-                                let choices = [' ', '!', '@', '#', '$', '%', '^', '&', '*'];
-                                choices[rng.gen_range(0..choices.len())].to_string()
-                            }
-                            'a' => (rng.gen_range(b'a'..=b'z') as char).to_string(),
-                            'A' => (rng.gen_range(b'A'..=b'Z') as char).to_string(),
-                            'x' => {
-                                let hex_chars: Vec<char> = ('0'..='9').chain('a'..='f').collect();
-                                hex_chars[rng.gen_range(0..hex_chars.len())].to_string()
-                            }
-                            'X' => {
-                                let hex_chars: Vec<char> = ('0'..='9').chain('A'..='F').collect();
-                                hex_chars[rng.gen_range(0..hex_chars.len())].to_string()
-                            }
-                            's' => " ".to_string(),
-                            'n' => "\n".to_string(),
-                            't' => "\t".to_string(),
-                            '\\' => "\\".to_string(),
-                            _ => next.to_string(),
-                        };
-                        for c in generated.chars() {
-                            result.push(c);
-                            last_char = Some(c);
-                        }
-                    }
-                }
+            let atom = match ch {
+                '\\' => match chars.next() {
+                    Some(next) => PatternAtom::Escape(next),
+                    None => continue,
+                },
                 '[' => {
                     // Character class [abc] or [a-z]
                     let mut class_chars: Vec<char> = Vec::new();
@@ -1733,11 +2155,10 @@ impl MockStrategyHandler {
                         }
                         class_chars.push(c);
                     }
-                    if !class_chars.is_empty() {
-                        let selected = class_chars[rng.gen_range(0..class_chars.len())];
-                        result.push(selected);
-                        last_char = Some(selected);
+                    if class_chars.is_empty() {
+                        continue;
                     }
+                    PatternAtom::Class(class_chars)
                 }
                 '{' => {
                     // Repetition {n} or {n,m}
@@ -1762,25 +2183,24 @@ impl MockStrategyHandler {
                         (n, n)
                     };
 
-                    let repeat_count = if min == max {
+                    let repeat_count = if min >= max {
                         min
                     } else {
                         rng.gen_range(min..=max)
                     };
 
-                    // Repeat the last character
-                    if let Some(c) = last_char {
-                        // We already have one instance, add repeat_count - 1 more
+                    // Generate the previous atom again; it was already generated once
+                    if let Some(atom) = &last_atom {
                         for _ in 1..repeat_count {
-                            result.push(c);
+                            result.push_str(&atom.generate(rng));
                         }
                     }
+                    continue;
                 }
-                _ => {
-                    result.push(ch);
-                    last_char = Some(ch);
-                }
-            }
+                _ => PatternAtom::Literal(ch),
+            };
+            result.push_str(&atom.generate(rng));
+            last_atom = Some(atom);
         }
 
         Ok(result)
@@ -1802,6 +2222,12 @@ impl MockStrategyHandler {
         // Validate data lake exists
         let data_lake = {
             let settings_guard = settings.read().await;
+            if !settings_guard.features.data_lake {
+                return Err(anyhow::anyhow!(
+                    "Data lakes are disabled (features.data_lake = false): data_lake_crud on '{}'",
+                    crud_config.data_lake
+                ));
+            }
             settings_guard.data_lakes.iter()
                 .find(|dl| dl.name == crud_config.data_lake)
                 .cloned()
@@ -1853,7 +2279,7 @@ impl MockStrategyHandler {
         let format = self.get_data_lake_format(&config.data_lake).await?;
 
         // Write record
-        file_storage.write_records(&config.data_lake, &config.schema_name, &[record.clone()], &format).await
+        file_storage.write_records(&config.data_lake, &config.schema_name, std::slice::from_ref(&record), &format).await
             .map_err(|e| anyhow::anyhow!("Failed to create record: {}", e))?;
 
         Ok(serde_json::to_value(&record)?)
@@ -2028,6 +2454,552 @@ impl MockStrategyHandler {
     }
 }
 
+/// Faker types available for one leaf property of a schema
+#[derive(Debug, Clone, Serialize)]
+pub struct FakerFieldChoices {
+    /// Property path, as used by `FakerSchemaConfig::fields`
+    pub path: String,
+    /// JSON Schema type of the property
+    pub schema_type: String,
+    /// Faker types suited to the property, the one generated by default first
+    pub faker_types: Vec<FakerFieldType>,
+}
+
+/// Per-property faker choices and default array sizes of a schema
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FakerChoices {
+    pub fields: Vec<FakerFieldChoices>,
+    /// Array sizes from `minItems`/`maxItems`, keyed by array path
+    pub arrays: HashMap<String, FakerArrayConfig>,
+}
+
+/// List the faker choices for every leaf property of `schema`, keyed by the
+/// same paths as `FakerSchemaConfig`, so a form can offer them per property
+pub fn faker_choices(schema: &Value) -> FakerChoices {
+    let mut choices = FakerChoices::default();
+    collect_faker_choices(schema, schema, "", 0, &mut choices);
+    choices
+}
+
+fn collect_faker_choices(root: &Value, schema: &Value, path: &str, depth: usize, choices: &mut FakerChoices) {
+    if depth > MAX_FAKER_DEPTH {
+        return;
+    }
+    let schema = resolve_schema_ref(root, schema);
+    if let Some(variants) = schema_variants(schema) {
+        for variant in variants {
+            collect_faker_choices(root, variant, path, depth + 1, choices);
+        }
+        return;
+    }
+
+    let no_config = FakerSchemaConfig::default();
+    match schema_node_kind(schema, path, &no_config) {
+        "object" => {
+            for (name, property) in schema.get("properties").and_then(Value::as_object).into_iter().flatten() {
+                collect_faker_choices(root, property, &join_path(path, name), depth + 1, choices);
+            }
+        }
+        "array" => {
+            let (min_items, max_items) = array_bounds(schema, path, &no_config);
+            choices.arrays.insert(path.to_string(), FakerArrayConfig { min_items, max_items });
+            let items = schema.get("items").unwrap_or(&Value::Null);
+            collect_faker_choices(root, items, &format!("{}[*]", path), depth + 1, choices);
+        }
+        schema_type => {
+            if !choices.fields.iter().any(|field| field.path == path) {
+                choices.fields.push(FakerFieldChoices {
+                    path: path.to_string(),
+                    schema_type: schema_type.to_string(),
+                    faker_types: faker_types_for(schema),
+                });
+            }
+        }
+    }
+}
+
+/// Follow local `$ref`s (e.g. "#/definitions/Item") to the schema they point at
+fn resolve_schema_ref<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    let mut schema = schema;
+    for _ in 0..MAX_FAKER_DEPTH {
+        let target = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| root.pointer(pointer));
+        match target {
+            Some(target) => schema = target,
+            None => break,
+        }
+    }
+    schema
+}
+
+/// The `oneOf`/`anyOf` alternatives of a schema, if it has any
+fn schema_variants(schema: &Value) -> Option<&Vec<Value>> {
+    schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+        .filter(|variants| !variants.is_empty())
+}
+
+/// Declared type of a schema, skipping "null" in a list of types
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(t) => Some(t.as_str()),
+        Value::Array(types) => types.iter().filter_map(Value::as_str).find(|t| *t != "null"),
+        _ => None,
+    }
+}
+
+/// "object", "array" or the leaf type of a schema node. Untyped nodes take the
+/// shape implied by the paths configured below them, and are strings otherwise.
+fn schema_node_kind<'a>(schema: &'a Value, path: &str, config: &FakerSchemaConfig) -> &'a str {
+    if let Some(schema_type) = schema_type(schema) {
+        return schema_type;
+    }
+    if schema.get("properties").is_some() {
+        return "object";
+    }
+    if schema.get("items").is_some() {
+        return "array";
+    }
+
+    let item_path = format!("{}[*]", path);
+    let mut configured = config.fields.keys().chain(config.arrays.keys());
+    if config.arrays.contains_key(path) || configured.clone().any(|p| p.starts_with(&item_path)) {
+        "array"
+    } else if path.is_empty() || configured.any(|p| child_property(p, path).is_some()) {
+        "object"
+    } else {
+        "string"
+    }
+}
+
+/// Property names of an object node: those in the schema, then those only configured by path
+fn property_names(schema: &Value, path: &str, config: &FakerSchemaConfig) -> Vec<String> {
+    let mut names: Vec<String> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default();
+    let mut configured: Vec<&str> = config
+        .fields
+        .keys()
+        .chain(config.arrays.keys())
+        .filter_map(|p| child_property(p, path))
+        .collect();
+    configured.sort_unstable();
+    for name in configured {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Name of the direct child of the object at `parent` that `path` runs through
+fn child_property<'a>(path: &'a str, parent: &str) -> Option<&'a str> {
+    let rest = if parent.is_empty() {
+        path
+    } else {
+        path.strip_prefix(parent)?.strip_prefix('.')?
+    };
+    let end = rest.find(['.', '[']).unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+/// Item count range of the array at `path`: its `arrays` config, else the
+/// schema's `minItems`/`maxItems`, else 1 to 3
+fn array_bounds(schema: &Value, path: &str, config: &FakerSchemaConfig) -> (usize, usize) {
+    let (min, max) = match config.arrays.get(path) {
+        Some(array_config) => (array_config.min_items, array_config.max_items),
+        None => {
+            let max = schema.get("maxItems").and_then(Value::as_u64).map(|n| n as usize);
+            let min = schema
+                .get("minItems")
+                .and_then(Value::as_u64)
+                .map(|n| n as usize)
+                .unwrap_or_else(|| max.map_or(1, |max| max.min(1)));
+            (min, max.unwrap_or(min.max(3)))
+        }
+    };
+    (min.min(max), max.max(min))
+}
+
+/// Faker types suited to a leaf schema, the one generated by default first:
+/// its `x-fake-strategy`, then `enum` for enumerations, then the type's default
+fn faker_types_for(schema: &Value) -> Vec<FakerFieldType> {
+    let format = schema.get("format").and_then(Value::as_str);
+    let mut types = FakerFieldType::for_schema_type(schema_type(schema).unwrap_or("string"), format);
+    let preferred = schema
+        .get("x-fake-strategy")
+        .and_then(|strategy| serde_json::from_value::<FakerFieldType>(strategy.clone()).ok())
+        .or_else(|| schema.get("enum").map(|_| FakerFieldType::Enum));
+    if let Some(preferred) = preferred {
+        types.retain(|t| *t != preferred);
+        types.insert(0, preferred);
+    }
+    types
+}
+
+/// Faker configuration used for a leaf schema without one, bounded by its `minimum`/`maximum`
+fn default_faker_config(schema: &Value) -> FakerFieldConfig {
+    let (minimum, maximum) = numeric_bounds(schema, schema_type(schema) == Some("integer"));
+    let (min, max) = match (minimum, maximum) {
+        (Some(min), Some(max)) => (min, max.max(min)),
+        (Some(min), None) => (min, min + 100.0),
+        (None, Some(max)) => (max - 100.0, max),
+        (None, None) => (0.0, 100.0),
+    };
+    FakerFieldConfig {
+        faker_type: faker_types_for(schema).remove(0),
+        min: Some(min),
+        max: Some(max),
+        pattern: None,
+        enum_values: None,
+        constant: None,
+    }
+}
+
+/// Lowest and highest values a numeric schema allows, from `minimum`/`maximum`
+/// and `exclusiveMinimum`/`exclusiveMaximum`
+fn numeric_bounds(schema: &Value, integer: bool) -> (Option<f64>, Option<f64>) {
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+    let (lower, exclusive_lower) = (bound("minimum"), bound("exclusiveMinimum"));
+    let (upper, exclusive_upper) = (bound("maximum"), bound("exclusiveMaximum"));
+
+    let (lower, exclusive_lower, upper, exclusive_upper) = if integer {
+        (
+            lower.map(f64::ceil),
+            exclusive_lower.map(|x| x.floor() + 1.0),
+            upper.map(f64::floor),
+            exclusive_upper.map(|x| x.ceil() - 1.0),
+        )
+    } else {
+        // Step just inside exclusive bounds
+        let step = |x: f64| x.abs().max(1.0) * 1e-9;
+        (
+            lower,
+            exclusive_lower.map(|x| x + step(x)),
+            upper,
+            exclusive_upper.map(|x| x - step(x)),
+        )
+    };
+
+    let tightest = |a: Option<f64>, b: Option<f64>, pick: fn(f64, f64) -> f64| match (a, b) {
+        (Some(a), Some(b)) => Some(pick(a, b)),
+        (a, b) => a.or(b),
+    };
+    (
+        tightest(lower, exclusive_lower, f64::max),
+        tightest(upper, exclusive_upper, f64::min),
+    )
+}
+
+/// Fit a generated value to its schema: numbers are clamped into the schema's
+/// bounds (and rounded for integers), strings padded or cut to
+/// `minLength`/`maxLength`. Other values are returned unchanged.
+fn constrain_to_schema(value: Value, schema: &Value) -> Value {
+    match value {
+        Value::Number(number) => {
+            let integer = schema_type(schema) == Some("integer");
+            let mut x = number.as_f64().unwrap_or_default();
+            let (lower, upper) = numeric_bounds(schema, integer || !number.is_f64());
+            let out_of_bounds = lower.is_some_and(|l| x < l) || upper.is_some_and(|u| x > u);
+            let fractional_integer = integer && number.is_f64();
+            if !out_of_bounds && !fractional_integer {
+                return Value::Number(number);
+            }
+            if let Some(lower) = lower {
+                x = x.max(lower);
+            }
+            if let Some(upper) = upper {
+                x = x.min(upper);
+            }
+            if integer || !number.is_f64() {
+                json!(x.round() as i64)
+            } else {
+                json!(x)
+            }
+        }
+        Value::String(s) => {
+            let length = |key: &str| schema.get(key).and_then(Value::as_u64).map(|n| n as usize);
+            Value::String(fit_length(s, length("minLength"), length("maxLength")))
+        }
+        other => other,
+    }
+}
+
+/// Pad `s` with random letters up to `min` characters and cut it to `max`,
+/// without leaving trailing whitespace behind a cut
+fn fit_length(s: String, min: Option<usize>, max: Option<usize>) -> String {
+    let mut chars: Vec<char> = s.chars().collect();
+    if let Some(max) = max {
+        if chars.len() > max {
+            chars.truncate(max);
+            while chars.last().is_some_and(|c| c.is_whitespace()) {
+                chars.pop();
+            }
+        }
+    }
+    while chars.len() < min.unwrap_or(0) {
+        chars.push(with_rng(|rng| rng.gen_range(b'a'..=b'z') as char));
+    }
+    chars.into_iter().collect()
+}
+
+/// Rename the columns of database rows per `column_aliases`, then shape them per `result_mode`
+fn shape_database_rows(rows: Value, config: &DatabaseConfig) -> Result<Value> {
+    let rows: Vec<Value> = match rows {
+        Value::Array(rows) => rows,
+        other => return Ok(other),
+    };
+    let mut rows = rows.into_iter().map(|row| match row {
+        Value::Object(columns) if !config.column_aliases.is_empty() => Value::Object(
+            columns
+                .into_iter()
+                .map(|(name, value)| match config.column_aliases.get(&name) {
+                    Some(alias) => (alias.clone(), value),
+                    None => (name, value),
+                })
+                .collect(),
+        ),
+        row => row,
+    });
+
+    match config.result_mode {
+        DatabaseResultMode::Rows => Ok(Value::Array(rows.collect())),
+        DatabaseResultMode::FirstRow => Ok(rows.next().unwrap_or(Value::Null)),
+        DatabaseResultMode::Scalar => match rows.next() {
+            None => Ok(Value::Null),
+            Some(Value::Object(columns)) if columns.len() == 1 => {
+                Ok(columns.into_iter().next().map(|(_, value)| value).unwrap_or(Value::Null))
+            }
+            Some(Value::Object(columns)) => Err(anyhow::anyhow!(
+                "Scalar result_mode needs a single column, but the query returned {}",
+                columns.len()
+            )),
+            Some(value) => Ok(value),
+        },
+    }
+}
+
+/// Files larger than this are refused by the File and Csv strategies unless `max_bytes` says otherwise
+pub const DEFAULT_FILE_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Open the file of a File strategy, returning it with its size in bytes.
+/// Fails if the file is larger than its `max_bytes`.
+pub async fn open_mock_file(file_config: &FileConfig) -> Result<(tokio::fs::File, u64)> {
+    open_limited_file(&file_config.path, file_config.max_bytes, "file.max_bytes").await
+}
+
+/// Open `path`, failing if it's larger than `max_bytes` (the setting named `limit_name`)
+async fn open_limited_file(path: &str, max_bytes: Option<u64>, limit_name: &str) -> Result<(tokio::fs::File, u64)> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to open file '{}': {}", path, e))?;
+    let size = file.metadata().await?.len();
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_FILE_MAX_BYTES);
+    if size > max_bytes {
+        return Err(anyhow::anyhow!(
+            "File '{}' is {} bytes, over the {} byte limit ({})",
+            path,
+            size,
+            max_bytes,
+            limit_name
+        ));
+    }
+    Ok((file, size))
+}
+
+/// A CSV row as an object keyed by the column headers
+fn csv_row_to_json(headers: &csv::StringRecord, row: &csv::StringRecord) -> Value {
+    let object: serde_json::Map<String, Value> = headers
+        .iter()
+        .zip(row.iter())
+        .map(|(header, cell)| (header.to_string(), csv_cell_value(cell)))
+        .collect();
+    Value::Object(object)
+}
+
+/// Typed value of a CSV cell. Numbers and `true`/`false` are converted only
+/// when they read back the same, so `007` or `1.50` stay strings; empty cells
+/// are null.
+fn csv_cell_value(cell: &str) -> Value {
+    match cell {
+        "" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => {
+            if let Ok(n) = cell.parse::<i64>() {
+                if n.to_string() == cell {
+                    return json!(n);
+                }
+            }
+            if let Ok(f) = cell.parse::<f64>() {
+                if f.is_finite() && f.to_string() == cell {
+                    return json!(f);
+                }
+            }
+            Value::String(cell.to_string())
+        }
+    }
+}
+
+/// Error a mock fails a call with on purpose. The MCP server returns it as a
+/// JSON-RPC error with this code rather than as a generic tool failure.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct MockProtocolError {
+    pub code: i32,
+    pub message: String,
+}
+
+/// State key under which a rotating mock config keeps its position, distinct per config
+fn rotation_state_key<T: serde::Serialize>(prefix: &str, config: &T) -> Result<String> {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::hash::Hash::hash(&serde_json::to_string(config)?, &mut hasher);
+    Ok(format!("__{}_{:x}", prefix, std::hash::Hasher::finish(&hasher)))
+}
+
+/// Writer that only counts the bytes written to it
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Whether `value` contains `pattern`: objects contain each of the pattern's
+/// keys with a contained value, arrays contain a match for each pattern
+/// element, and other values must be equal
+fn json_contains(value: &Value, pattern: &Value) -> bool {
+    match (value, pattern) {
+        (Value::Object(value), Value::Object(pattern)) => pattern
+            .iter()
+            .all(|(key, p)| value.get(key).is_some_and(|v| json_contains(v, p))),
+        (Value::Array(value), Value::Array(pattern)) => pattern
+            .iter()
+            .all(|p| value.iter().any(|v| json_contains(v, p))),
+        _ => value == pattern,
+    }
+}
+
+/// Wait for a state lookup from a synchronous template or script function.
+/// On the server's multi-threaded runtime the worker hands its other tasks
+/// off while it waits, so they keep running; single-threaded runtimes (e.g.
+/// unit tests) have no other worker to hand them to and wait in place.
+fn block_on_state<F: std::future::Future>(future: F) -> F::Output {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        _ => futures::executor::block_on(future),
+    }
+}
+
+/// A random moment within the past year. Seeded calls count back from the start
+/// of 2025 instead of now, so their dates don't change from run to run.
+fn random_past_time() -> chrono::DateTime<chrono::Utc> {
+    let seconds = with_rng(|rng| rng.gen_range(0..365 * 24 * 60 * 60));
+    let now = if is_seeded() { SEEDED_EPOCH } else { chrono::Utc::now() };
+    now - chrono::Duration::seconds(seconds)
+}
+
+/// "Now" for the random dates of seeded calls: 2025-01-01T00:00:00Z
+const SEEDED_EPOCH: chrono::DateTime<chrono::Utc> = chrono::DateTime::from_timestamp_nanos(1_735_689_600_000_000_000);
+
+/// One unit of a pattern expanded by `MockStrategyHandler::expand_pattern`,
+/// kept so a following `{n}` generates it again rather than copying its output
+enum PatternAtom {
+    Literal(char),
+    Escape(char),
+    Class(Vec<char>),
+}
+
+impl PatternAtom {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        match self {
+            PatternAtom::Literal(c) => c.to_string(),
+            PatternAtom::Class(chars) => chars[rng.gen_range(0..chars.len())].to_string(),
+            PatternAtom::Escape(c) => match c {
+                'd' => rng.gen_range(0..10).to_string(),
+                'D' => (rng.gen_range(b'a'..=b'z') as char).to_string(),
+                'w' => {
+                    let choices: Vec<char> = ('a'..='z')
+                        .chain('A'..='Z')
+                        .chain('0'..='9')
+                        .chain(std::iter::once('_'))
+                        .collect();
+                    choices[rng.gen_range(0..choices.len())].to_string()
+                }
+                'W' => {
+                    let choices = [' ', '!', '@', '#', '$', '%', '^', '&', '*'];
+                    choices[rng.gen_range(0..choices.len())].to_string()
+                }
+                'a' => (rng.gen_range(b'a'..=b'z') as char).to_string(),
+                'A' => (rng.gen_range(b'A'..=b'Z') as char).to_string(),
+                'x' => {
+                    let hex_chars: Vec<char> = ('0'..='9').chain('a'..='f').collect();
+                    hex_chars[rng.gen_range(0..hex_chars.len())].to_string()
+                }
+                'X' => {
+                    let hex_chars: Vec<char> = ('0'..='9').chain('A'..='F').collect();
+                    hex_chars[rng.gen_range(0..hex_chars.len())].to_string()
+                }
+                's' => " ".to_string(),
+                'n' => "\n".to_string(),
+                't' => "\t".to_string(),
+                other => other.to_string(),
+            },
+        }
+    }
+}
+
+/// Run `f` with the random source of the current call: its seeded generator
+/// for a call made with a seed, the thread's generator otherwise
+fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    if is_seeded() {
+        SEEDED_RNG.with(|rng| f(&mut *rng.borrow_mut()))
+    } else {
+        f(&mut rand::thread_rng())
+    }
+}
+
+/// Whether the current call was made with a seed
+fn is_seeded() -> bool {
+    SEEDED_RNG.try_with(|_| ()).is_ok()
+}
+
+/// A fake string from `faker`, drawn from the call's random source
+fn fake_string<F>(faker: F) -> String
+where
+    String: fake::Dummy<F>,
+{
+    with_rng(|rng| fake::Dummy::dummy_with_rng(&faker, rng))
+}
+
+/// A random (v4) UUID drawn from the call's random source
+fn random_uuid() -> uuid::Uuid {
+    uuid::Builder::from_random_bytes(with_rng(|rng| rng.gen())).into_uuid()
+}
+
 /// Static helper to convert JSON to Python objects (for use in closures)
 fn json_to_python_static(vm: &rustpython_vm::VirtualMachine, value: &Value) -> rustpython_vm::PyObjectRef {
     use rustpython_vm::convert::ToPyObject;
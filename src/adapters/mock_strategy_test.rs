@@ -1,7 +1,7 @@
 use super::mock_strategy::MockStrategyHandler;
 use crate::adapters::state_manager::StateManager;
 use crate::config::{MockConfig, MockStrategyType};
-use serde_json::json;
+use serde_json::{json, Value};
 use std::sync::Arc;
 
 #[test]
@@ -28,6 +28,11 @@ async fn test_generate_template() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
     let args = json!({ "name": "World" });
 
@@ -53,6 +58,11 @@ async fn test_generate_template_missing_args() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
     
     // Tera renders missing variables as empty string by default or errors depending on config. 
@@ -80,6 +90,11 @@ async fn test_generate_random() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -106,6 +121,11 @@ async fn test_generate_random_unknown_type() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -136,6 +156,11 @@ async fn test_generate_script() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
     let args = json!({ "name": "Script" });
 
@@ -145,6 +170,69 @@ async fn test_generate_script() {
     assert_eq!(value, "Hello, Script!");
 }
 
+#[tokio::test]
+async fn test_gen_id_is_shared_across_templates_and_scripts() {
+    let state_manager = Arc::new(StateManager::new());
+    let handler = MockStrategyHandler::new(state_manager.clone());
+    let create_user = MockConfig {
+        strategy: MockStrategyType::Template,
+        template: Some(r#"{"id": "{{ gen_id(key="user") }}", "name": "{{ name }}"}"#.to_string()),
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    };
+    let get_user = MockConfig {
+        strategy: MockStrategyType::Script,
+        template: None,
+        script: Some(r#"#{ id: gen_id("user"), order_id: gen_id("order") }"#.to_string()),
+        ..create_user.clone()
+    };
+
+    let created = handler.generate(&create_user, Some(&json!({ "name": "Ada" }))).await.unwrap();
+    let fetched = handler.generate(&get_user, None).await.unwrap();
+
+    let id = created["id"].as_str().unwrap();
+    assert!(uuid::Uuid::parse_str(id).is_ok());
+    assert_eq!(fetched["id"], id);
+    assert_ne!(fetched["order_id"], id);
+    assert_eq!(state_manager.get("__id_user").await, Some(json!(id)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_gen_id_in_concurrent_templates_on_multi_threaded_runtime() {
+    let state_manager = Arc::new(StateManager::new());
+    let handler = Arc::new(MockStrategyHandler::new(state_manager.clone()));
+    let config: MockConfig = serde_json::from_value(json!({
+        "strategy": "template",
+        "template": r#"{"id": "{{ gen_id(key="shared") }}"}"#
+    }))
+    .unwrap();
+
+    let calls = (0..16).map(|_| {
+        let handler = handler.clone();
+        let config = config.clone();
+        tokio::spawn(async move { handler.generate(&config, None).await.unwrap() })
+    });
+    let results = futures::future::join_all(calls).await;
+
+    let id = state_manager.get("__id_shared").await.unwrap();
+    for result in results {
+        assert_eq!(result.unwrap()["id"], id);
+    }
+}
+
 #[tokio::test]
 async fn test_generate_script_lua() {
     use crate::config::ScriptLang;
@@ -164,6 +252,11 @@ async fn test_generate_script_lua() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
     let args = json!({ "name": "Lua" });
 
@@ -195,6 +288,11 @@ async fn test_generate_script_js() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
     let args = json!({ "name": "JS" });
 
@@ -226,6 +324,11 @@ output = "Hello, " + input["name"] + "!"
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
     let args = json!({ "name": "Python" });
 
@@ -254,6 +357,11 @@ async fn test_generate_pattern_basic() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -282,6 +390,11 @@ async fn test_generate_pattern_character_class() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -309,6 +422,11 @@ async fn test_generate_pattern_repetition() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -334,6 +452,11 @@ async fn test_generate_pattern_hex() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -364,6 +487,8 @@ async fn test_generate_file_random() {
         file: Some(FileConfig {
             path: test_file.to_string_lossy().to_string(),
             selection: "random".to_string(),
+            stream: false,
+            max_bytes: None,
         }),
         pattern: None,
         script: None,
@@ -372,6 +497,11 @@ async fn test_generate_file_random() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -406,6 +536,8 @@ async fn test_generate_file_sequential() {
         file: Some(FileConfig {
             path: test_file.to_string_lossy().to_string(),
             selection: "sequential".to_string(),
+            stream: false,
+            max_bytes: None,
         }),
         pattern: None,
         script: None,
@@ -414,6 +546,11 @@ async fn test_generate_file_sequential() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     // First call should return id: 1
@@ -458,6 +595,8 @@ async fn test_generate_file_jsonlines() {
         file: Some(FileConfig {
             path: test_file.to_string_lossy().to_string(),
             selection: "first".to_string(),
+            stream: false,
+            max_bytes: None,
         }),
         pattern: None,
         script: None,
@@ -466,6 +605,11 @@ async fn test_generate_file_jsonlines() {
         database: None,
         faker_schema: None,
         data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
     };
 
     let result = handler.generate(&config, None).await;
@@ -475,4 +619,846 @@ async fn test_generate_file_jsonlines() {
 
     // Cleanup
     std::fs::remove_file(&test_file).ok();
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_generate_file_stream_and_max_bytes() {
+    use crate::config::FileConfig;
+
+    let test_file = std::env::temp_dir().join("metis_test_stream.jsonl");
+    std::fs::write(&test_file, "{\"id\": 1}\n{\"id\": 2}\n").unwrap();
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let mut file_config = FileConfig {
+        path: test_file.to_string_lossy().to_string(),
+        selection: "first".to_string(),
+        stream: true,
+        max_bytes: None,
+    };
+    let config = |file: &FileConfig| MockConfig {
+        strategy: MockStrategyType::File,
+        template: None,
+        faker_type: None,
+        stateful: None,
+        file: Some(file.clone()),
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    };
+
+    // Streamed files are returned whole rather than as one record
+    let value = handler.generate(&config(&file_config), None).await.unwrap();
+    assert_eq!(value, "{\"id\": 1}\n{\"id\": 2}\n");
+
+    file_config.max_bytes = Some(8);
+    let err = handler.generate(&config(&file_config), None).await.unwrap_err();
+    assert!(err.to_string().contains("is 20 bytes, over the 8 byte limit"), "{}", err);
+
+    std::fs::remove_file(&test_file).ok();
+}
+
+#[tokio::test]
+async fn test_generate_file_reuses_cached_fixture_until_invalidated() {
+    use crate::adapters::fixture_cache::FixtureCache;
+    use crate::config::FileConfig;
+
+    let dir = tempfile::tempdir().unwrap();
+    let test_file = dir.path().join("users.json");
+    std::fs::write(&test_file, r#"[{"id": 1}]"#).unwrap();
+
+    let fixtures = Arc::new(FixtureCache::new());
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new())).with_fixture_cache(fixtures.clone());
+    let config = MockConfig {
+        strategy: MockStrategyType::File,
+        template: None,
+        faker_type: None,
+        stateful: None,
+        file: Some(FileConfig {
+            path: test_file.to_string_lossy().to_string(),
+            selection: "first".to_string(),
+            stream: false,
+            max_bytes: None,
+        }),
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    };
+
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!({"id": 1}));
+
+    // The edit is not seen until the watcher reports a change in the directory
+    std::fs::write(&test_file, r#"[{"id": 2}]"#).unwrap();
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!({"id": 1}));
+
+    fixtures.invalidate(std::slice::from_ref(&test_file));
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!({"id": 2}));
+}
+
+#[tokio::test]
+async fn test_generate_callback_acknowledges_and_records() {
+    use crate::adapters::callback_tracker::CallbackStatus;
+    use crate::config::CallbackConfig;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config = MockConfig {
+        strategy: MockStrategyType::Callback,
+        template: None,
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: Some(CallbackConfig {
+            url: "http://127.0.0.1:1/jobs/{{ job }}".to_string(),
+            payload_template: Some(r#"{"job": "{{ job }}", "id": "{{ callback_id }}"}"#.to_string()),
+            delay_ms: 60_000,
+            headers: Default::default(),
+            ack_template: None,
+        }),
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    };
+
+    let ack = handler.generate(&config, Some(&json!({"job": "export"}))).await.unwrap();
+    assert_eq!(ack["status"], "accepted");
+    let callback_id = ack["callback_id"].as_str().unwrap();
+
+    let pending = handler.callbacks().list(true);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, callback_id);
+    assert_eq!(pending[0].url, "http://127.0.0.1:1/jobs/export");
+    assert_eq!(pending[0].payload, json!({"job": "export", "id": callback_id}));
+    assert_eq!(pending[0].status, CallbackStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_generate_random_is_reproducible_with_seed() {
+    use crate::config::FakerSchemaConfig;
+    use crate::domain::execution::ExecutionContext;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "string", "format": "uuid"},
+            "email": {"type": "string", "format": "email"},
+            "created": {"type": "string", "format": "date-time"},
+            "score": {"type": "number", "minimum": 0, "maximum": 1}
+        }
+    });
+    let config = random_config(FakerSchemaConfig {
+        schema: Some(schema),
+        ..Default::default()
+    });
+    let seeded = |seed| ExecutionContext {
+        seed: Some(seed),
+        ..Default::default()
+    };
+
+    let first = seeded(42).scope(handler.generate(&config, None)).await.unwrap();
+    let second = seeded(42).scope(handler.generate(&config, None)).await.unwrap();
+    assert_eq!(first, second);
+
+    let other = seeded(7).scope(handler.generate(&config, None)).await.unwrap();
+    assert_ne!(first, other);
+    // Without a seed, output stays random
+    let unseeded = handler.generate(&config, None).await.unwrap();
+    assert_ne!(first["id"], unseeded["id"]);
+}
+
+#[tokio::test]
+async fn test_generate_stateful_session_scope_isolates_sessions() {
+    use crate::domain::execution::ExecutionContext;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let counter = |scope: &str| -> MockConfig {
+        serde_json::from_value(json!({
+            "strategy": "stateful",
+            "stateful": {"state_key": "counter", "operation": "increment", "scope": scope}
+        }))
+        .unwrap()
+    };
+    let session = |id: &str| ExecutionContext {
+        session_id: Some(id.to_string()),
+        ..Default::default()
+    };
+
+    let config = counter("session");
+    assert_eq!(session("a").scope(handler.generate(&config, None)).await.unwrap(), json!(1));
+    assert_eq!(session("a").scope(handler.generate(&config, None)).await.unwrap(), json!(2));
+    assert_eq!(session("b").scope(handler.generate(&config, None)).await.unwrap(), json!(1));
+    // Calls outside a session share the global value
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!(1));
+
+    let config = counter("global");
+    assert_eq!(session("a").scope(handler.generate(&config, None)).await.unwrap(), json!(2));
+    assert_eq!(session("b").scope(handler.generate(&config, None)).await.unwrap(), json!(3));
+}
+
+#[tokio::test]
+async fn test_generate_applies_transforms() {
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config: MockConfig = serde_json::from_value(json!({
+        "strategy": "template",
+        "template": "{\"name\": \"{{ name }}\", \"token\": \"secret\"}",
+        "transform": [
+            {"type": "redact", "path": "token", "replacement": null},
+            {"type": "wrap", "key": "user"}
+        ]
+    }))
+    .unwrap();
+
+    let value = handler.generate(&config, Some(&json!({"name": "ada"}))).await.unwrap();
+    assert_eq!(value, json!({"user": {"name": "ada", "token": null}}));
+}
+
+fn random_config(faker_schema: crate::config::FakerSchemaConfig) -> MockConfig {
+    MockConfig {
+        strategy: MockStrategyType::Random,
+        template: None,
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: Some(faker_schema),
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_generate_random_nested_arrays_from_schema() {
+    use crate::config::{FakerArrayConfig, FakerFieldConfig, FakerFieldType, FakerSchemaConfig};
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "orders": {
+                "type": "array",
+                "minItems": 2,
+                "maxItems": 2,
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": {"type": "string", "format": "uuid"},
+                        "status": {"type": "string", "enum": ["open", "shipped"]},
+                        "lines": {
+                            "type": "array",
+                            "minItems": 1,
+                            "maxItems": 4,
+                            "items": {"$ref": "#/definitions/Line"}
+                        }
+                    }
+                }
+            }
+        },
+        "definitions": {
+            "Line": {
+                "type": "object",
+                "properties": {
+                    "sku": {"type": "string"},
+                    "quantity": {"type": "integer", "minimum": 1, "maximum": 9},
+                    "gift": {"type": "boolean"}
+                }
+            }
+        }
+    });
+    let mut faker_schema = FakerSchemaConfig {
+        schema: Some(schema),
+        ..Default::default()
+    };
+    // Per-path settings override the schema
+    faker_schema.fields.insert(
+        "orders[*].lines[*].sku".to_string(),
+        FakerFieldConfig {
+            faker_type: FakerFieldType::Pattern,
+            min: None,
+            max: None,
+            pattern: Some("SKU-###".to_string()),
+            enum_values: None,
+            constant: None,
+        },
+    );
+    faker_schema
+        .arrays
+        .insert("orders[*].lines".to_string(), FakerArrayConfig { min_items: 3, max_items: 3 });
+
+    let value = handler.generate(&random_config(faker_schema), None).await.unwrap();
+
+    let orders = value["orders"].as_array().unwrap();
+    assert_eq!(orders.len(), 2);
+    for order in orders {
+        assert_eq!(order["id"].as_str().unwrap().len(), 36);
+        assert!(["open", "shipped"].contains(&order["status"].as_str().unwrap()));
+        let lines = order["lines"].as_array().unwrap();
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            let sku = line["sku"].as_str().unwrap();
+            assert!(sku.starts_with("SKU-") && sku.len() == 7, "{}", sku);
+            assert!((1..=9).contains(&line["quantity"].as_i64().unwrap()));
+            assert!(line["gift"].is_boolean());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_generate_random_nested_arrays_from_paths() {
+    use crate::config::{FakerArrayConfig, FakerFieldConfig, FakerFieldType, FakerSchemaConfig};
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let field = |faker_type| FakerFieldConfig {
+        faker_type,
+        min: None,
+        max: None,
+        pattern: None,
+        enum_values: None,
+        constant: None,
+    };
+    let mut faker_schema = FakerSchemaConfig::default();
+    faker_schema.fields.insert("customer.email".to_string(), field(FakerFieldType::Email));
+    faker_schema.fields.insert("orders[*].items[*].sku".to_string(), field(FakerFieldType::Uuid));
+    faker_schema.fields.insert("tags[*]".to_string(), field(FakerFieldType::Word));
+    faker_schema
+        .arrays
+        .insert("orders".to_string(), FakerArrayConfig { min_items: 2, max_items: 2 });
+
+    let value = handler.generate(&random_config(faker_schema), None).await.unwrap();
+
+    assert!(value["customer"]["email"].as_str().unwrap().contains('@'));
+    let orders = value["orders"].as_array().unwrap();
+    assert_eq!(orders.len(), 2);
+    for order in orders {
+        let items = order["items"].as_array().unwrap();
+        assert!((1..=3).contains(&items.len()));
+        assert!(items.iter().all(|item| item["sku"].is_string()));
+    }
+    assert!(value["tags"].as_array().unwrap().iter().all(Value::is_string));
+}
+
+#[test]
+fn test_faker_choices_for_nested_schema() {
+    use super::mock_strategy::faker_choices;
+    use crate::config::FakerFieldType;
+
+    let choices = faker_choices(&json!({
+        "type": "object",
+        "properties": {
+            "contact": {"type": "string", "format": "email"},
+            "teams": {
+                "type": "array",
+                "maxItems": 5,
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "size": {"type": "integer"},
+                        "lead": {"type": "string", "x-fake-strategy": "full_name"},
+                        "members": {"type": "array", "minItems": 2, "items": {"type": "string"}}
+                    }
+                }
+            }
+        }
+    }));
+
+    let types = |path: &str| {
+        choices.fields.iter().find(|f| f.path == path).unwrap().faker_types.clone()
+    };
+    assert_eq!(types("contact"), vec![FakerFieldType::Email]);
+    assert_eq!(types("teams[*].size")[0], FakerFieldType::Integer);
+    assert_eq!(types("teams[*].lead")[0], FakerFieldType::FullName);
+    assert_eq!(types("teams[*].members[*]")[0], FakerFieldType::Lorem);
+
+    assert_eq!((choices.arrays["teams"].min_items, choices.arrays["teams"].max_items), (1, 5));
+    let members = &choices.arrays["teams[*].members"];
+    assert_eq!((members.min_items, members.max_items), (2, 3));
+}
+
+#[tokio::test]
+async fn test_generate_random_honours_schema_constraints() {
+    use crate::config::{FakerFieldConfig, FakerFieldType, FakerSchemaConfig};
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let mut faker_schema = FakerSchemaConfig {
+        schema: Some(json!({
+            "type": "object",
+            "properties": {
+                "age": {"type": "integer", "minimum": 0, "maximum": 120},
+                "score": {"type": "integer", "exclusiveMinimum": 10, "exclusiveMaximum": 13},
+                "price": {"type": "number", "exclusiveMinimum": 0, "maximum": 9.5},
+                "nickname": {"type": "string", "minLength": 12, "maxLength": 16},
+                "initials": {"type": "string", "maxLength": 3},
+                "code": {"type": "string", "pattern": "^[A-Z]\\d\\d\\d$"},
+                "email": {"type": "string", "format": "email"},
+                "id": {"type": "string", "format": "uuid"},
+                "created_at": {"type": "string", "format": "date-time"},
+                "birthday": {"type": "string", "format": "date"}
+            }
+        })),
+        ..Default::default()
+    };
+    // A configured faker whose own range breaks the schema is still clamped into it
+    faker_schema.fields.insert(
+        "age".to_string(),
+        FakerFieldConfig {
+            faker_type: FakerFieldType::Integer,
+            min: Some(-500.0),
+            max: Some(-400.0),
+            pattern: None,
+            enum_values: None,
+            constant: None,
+        },
+    );
+    faker_schema.fields.insert(
+        "initials".to_string(),
+        FakerFieldConfig {
+            faker_type: FakerFieldType::Paragraph,
+            min: None,
+            max: None,
+            pattern: None,
+            enum_values: None,
+            constant: None,
+        },
+    );
+    let config = random_config(faker_schema);
+
+    for _ in 0..25 {
+        let value = handler.generate(&config, None).await.unwrap();
+
+        assert_eq!(value["age"], json!(0));
+        assert!((11..=12).contains(&value["score"].as_i64().unwrap()), "{}", value["score"]);
+        let price = value["price"].as_f64().unwrap();
+        assert!(price > 0.0 && price <= 9.5, "{}", price);
+
+        let nickname = value["nickname"].as_str().unwrap();
+        assert!((12..=16).contains(&nickname.chars().count()), "{:?}", nickname);
+        let initials = value["initials"].as_str().unwrap();
+        assert!(initials.chars().count() <= 3 && !initials.ends_with(' '), "{:?}", initials);
+
+        let code: Vec<char> = value["code"].as_str().unwrap().chars().collect();
+        assert_eq!(code.len(), 4);
+        assert!(code[0].is_ascii_uppercase() && code[1..].iter().all(char::is_ascii_digit));
+
+        assert!(value["email"].as_str().unwrap().contains('@'));
+        assert!(uuid::Uuid::parse_str(value["id"].as_str().unwrap()).is_ok());
+        assert!(chrono::DateTime::parse_from_rfc3339(value["created_at"].as_str().unwrap()).is_ok());
+        assert!(chrono::NaiveDate::parse_from_str(value["birthday"].as_str().unwrap(), "%Y-%m-%d").is_ok());
+    }
+}
+
+fn matrix_config(mode: crate::config::MatrixMode, pairs: Vec<(Option<Value>, Value)>) -> MockConfig {
+    use crate::config::{MatrixConfig, MatrixPair};
+
+    MockConfig {
+        strategy: MockStrategyType::Matrix,
+        template: None,
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: Some(MatrixConfig {
+            pairs: pairs
+                .into_iter()
+                .map(|(input_match, output)| MatrixPair { input_match, output })
+                .collect(),
+            mode,
+        }),
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_generate_matrix_round_robin_wraps_around() {
+    use crate::config::MatrixMode;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config = matrix_config(
+        MatrixMode::RoundRobin,
+        vec![(None, json!("a")), (None, json!("b")), (None, json!("c"))],
+    );
+    let other = matrix_config(MatrixMode::RoundRobin, vec![(None, json!(1)), (None, json!(2))]);
+
+    let mut outputs = Vec::new();
+    for _ in 0..4 {
+        outputs.push(handler.generate(&config, None).await.unwrap());
+    }
+    assert_eq!(outputs, vec![json!("a"), json!("b"), json!("c"), json!("a")]);
+
+    // Another matrix has its own rotation
+    assert_eq!(handler.generate(&other, None).await.unwrap(), json!(1));
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!("b"));
+}
+
+#[tokio::test]
+async fn test_generate_matrix_input_match_uses_containment() {
+    use crate::config::MatrixMode;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config = matrix_config(
+        MatrixMode::InputMatch,
+        vec![
+            (Some(json!({"user": {"role": "admin"}})), json!("admin")),
+            (Some(json!({"tags": ["beta"]})), json!("beta")),
+            (None, json!("default")),
+        ],
+    );
+
+    let args = json!({"user": {"id": 7, "role": "admin"}});
+    assert_eq!(handler.generate(&config, Some(&args)).await.unwrap(), json!("admin"));
+    let args = json!({"user": {"role": "guest"}, "tags": ["stable", "beta"]});
+    assert_eq!(handler.generate(&config, Some(&args)).await.unwrap(), json!("beta"));
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!("default"));
+
+    // Without a catch-all pair, unmatched arguments are an error
+    let strict = matrix_config(MatrixMode::InputMatch, vec![(Some(json!({"id": 1})), json!("one"))]);
+    assert!(handler.generate(&strict, Some(&json!({"id": 2}))).await.is_err());
+}
+
+#[tokio::test]
+async fn test_generate_matrix_random_picks_a_pair() {
+    use crate::config::MatrixMode;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config = matrix_config(MatrixMode::Random, vec![(None, json!("x")), (None, json!("y"))]);
+
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..100 {
+        seen.insert(handler.generate(&config, None).await.unwrap().to_string());
+    }
+    assert_eq!(seen.len(), 2);
+
+    let empty = matrix_config(MatrixMode::Random, vec![]);
+    assert!(handler.generate(&empty, None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_generate_rejects_response_over_max_bytes() {
+    use crate::adapters::metrics_handler::MetricsCollector;
+    use crate::config::Settings;
+    use tokio::sync::RwLock;
+
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000, "max_response_bytes": 16}
+    }))
+    .unwrap();
+    let metrics = Arc::new(MetricsCollector::new().unwrap());
+    let handler = MockStrategyHandler::new_with_datafusion(
+        Arc::new(StateManager::new()),
+        None,
+        Some(Arc::new(RwLock::new(settings))),
+        None,
+    )
+    .with_metrics(metrics.clone());
+    let config = |template: &str| MockConfig {
+        strategy: MockStrategyType::Template,
+        template: Some(template.to_string()),
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    };
+
+    // "abcdefghij" serializes to 12 bytes with its quotes
+    assert_eq!(handler.generate(&config("abcdefghij"), None).await.unwrap(), json!("abcdefghij"));
+
+    let err = handler
+        .generate(&config("{% for i in range(end=10) %}xxxx{% endfor %}"), None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("42 bytes, over the 16 byte limit"), "{}", err);
+    assert_eq!(
+        metrics
+            .strategy_errors
+            .with_label_values(&["template", "response_too_large"])
+            .get(),
+        1.0
+    );
+}
+
+#[tokio::test]
+async fn test_generate_error_mock_rotates_through_codes() {
+    use super::mock_strategy::MockProtocolError;
+    use crate::config::ErrorMockConfig;
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let error = |code: i32, message_template: &str| ErrorMockConfig {
+        code,
+        message_template: message_template.to_string(),
+        rate: 0.0,
+        codes: vec![],
+    };
+    let mut config = MockConfig {
+        strategy: MockStrategyType::Template,
+        template: Some("ok".to_string()),
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: Some(ErrorMockConfig {
+            rate: 1.0,
+            codes: vec![error(-32602, "Bad {{ field }}"), error(-32601, ""), error(4001, "Quota exceeded")],
+            ..Default::default()
+        }),
+        transform: vec![],
+    };
+
+    let args = json!({"field": "email"});
+    let mut failures = Vec::new();
+    for _ in 0..4 {
+        let err = handler.generate(&config, Some(&args)).await.unwrap_err();
+        let err = err.downcast::<MockProtocolError>().unwrap();
+        failures.push((err.code, err.message));
+    }
+    assert_eq!(
+        failures,
+        vec![
+            (-32602, "Bad email".to_string()),
+            (-32601, "Mock error -32601".to_string()),
+            (4001, "Quota exceeded".to_string()),
+            (-32602, "Bad email".to_string()),
+        ]
+    );
+
+    // A rate of 0 never fails
+    config.error_mock.as_mut().unwrap().rate = 0.0;
+    assert_eq!(handler.generate(&config, Some(&args)).await.unwrap(), json!("ok"));
+}
+
+#[tokio::test]
+async fn test_generate_catches_panicking_script() {
+    use crate::adapters::metrics_handler::MetricsCollector;
+
+    let metrics = Arc::new(MetricsCollector::new().unwrap());
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new())).with_metrics(metrics.clone());
+    let config: MockConfig = serde_json::from_value(json!({
+        "strategy": "script",
+        "script_lang": "rhai",
+        // Growing an array past the largest allocation panics inside the engine
+        "script": "let a = []; a.pad(1 << 62, 0); a"
+    }))
+    .unwrap();
+
+    let err = handler.generate(&config, None).await.unwrap_err();
+    assert!(err.to_string().starts_with("script strategy failed unexpectedly: "), "{}", err);
+    assert_eq!(metrics.strategy_errors.with_label_values(&["script", "panic"]).get(), 1.0);
+
+    // The handler keeps serving calls afterwards
+    let config: MockConfig = serde_json::from_value(json!({"strategy": "script", "script": "40 + 2"})).unwrap();
+    assert_eq!(handler.generate(&config, None).await.unwrap(), json!(42));
+}
+
+#[tokio::test]
+async fn test_generate_random_custom_fakers() {
+    use crate::config::Settings;
+    use tokio::sync::RwLock;
+
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "custom_fakers": {
+            "sku": "^SKU-[A-Z]{3}-\\d{4}$",
+            "invoice": {"template": "INV-####-??"},
+            "name": "Ada"
+        }
+    }))
+    .unwrap();
+    let handler = MockStrategyHandler::new_with_datafusion(
+        Arc::new(StateManager::new()),
+        None,
+        Some(Arc::new(RwLock::new(settings))),
+        None,
+    );
+    let config = |faker_type: &str| MockConfig {
+        strategy: MockStrategyType::Random,
+        template: None,
+        faker_type: Some(faker_type.to_string()),
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: None,
+        error_mock: None,
+        transform: vec![],
+    };
+
+    let mut letters = std::collections::HashSet::new();
+    for _ in 0..20 {
+        let sku = handler.generate(&config("sku"), None).await.unwrap();
+        let sku = sku.as_str().unwrap();
+        assert_eq!(sku.len(), 12, "{}", sku);
+        assert!(sku.starts_with("SKU-"));
+        assert!(sku[4..7].chars().all(|c| c.is_ascii_uppercase()));
+        assert!(sku[8..].chars().all(|c| c.is_ascii_digit()));
+        letters.extend(sku[4..7].chars());
+    }
+    // `{3}` generates the class again rather than repeating one letter
+    assert!(letters.len() > 3);
+
+    let invoice = handler.generate(&config("invoice"), None).await.unwrap();
+    let invoice = invoice.as_str().unwrap();
+    assert!(invoice.starts_with("INV-"));
+    assert!(invoice[4..8].chars().all(|c| c.is_ascii_digit()));
+    assert!(invoice[9..].chars().all(|c| c.is_ascii_lowercase()));
+
+    // Custom fakers take precedence over built-in types
+    assert_eq!(handler.generate(&config("name"), None).await.unwrap(), json!("Ada"));
+}
+
+#[tokio::test]
+async fn test_generate_csv_rows() {
+    use crate::config::{CsvConfig, CsvSelection};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("products.csv");
+    std::fs::write(&path, "sku,name,price,stock,active,zip\nA-1,Widget,2.5,10,true,007\nB-2,\"Gadget, large\",1.50,,false,12345\n").unwrap();
+
+    let handler = MockStrategyHandler::new(Arc::new(StateManager::new()));
+    let config = |selection: CsvSelection, index: Option<usize>| MockConfig {
+        strategy: MockStrategyType::Csv,
+        template: None,
+        faker_type: None,
+        stateful: None,
+        file: None,
+        pattern: None,
+        script: None,
+        script_lang: None,
+        llm: None,
+        database: None,
+        faker_schema: None,
+        data_lake_crud: None,
+        callback: None,
+        matrix: None,
+        csv: Some(CsvConfig {
+            path: path.to_str().unwrap().to_string(),
+            selection,
+            index,
+            key_column: Some("sku".to_string()),
+            max_bytes: None,
+        }),
+        error_mock: None,
+        transform: vec![],
+    };
+
+    // Cells are typed only when they read back the same
+    assert_eq!(
+        handler.generate(&config(CsvSelection::Index, Some(0)), None).await.unwrap(),
+        json!({"sku": "A-1", "name": "Widget", "price": 2.5, "stock": 10, "active": true, "zip": "007"})
+    );
+    assert_eq!(
+        handler.generate(&config(CsvSelection::Index, Some(1)), None).await.unwrap(),
+        json!({"sku": "B-2", "name": "Gadget, large", "price": "1.50", "stock": null, "active": false, "zip": 12345})
+    );
+    assert_eq!(handler.generate(&config(CsvSelection::Index, Some(5)), None).await.unwrap(), Value::Null);
+
+    let by_key = config(CsvSelection::Key, None);
+    let row = handler.generate(&by_key, Some(&json!({"sku": "B-2"}))).await.unwrap();
+    assert_eq!(row["name"], "Gadget, large");
+    assert_eq!(handler.generate(&by_key, Some(&json!({"sku": "C-3"}))).await.unwrap(), Value::Null);
+    assert!(handler.generate(&by_key, Some(&json!({}))).await.is_err());
+
+    let sequential = config(CsvSelection::Sequential, None);
+    for expected in ["A-1", "B-2", "A-1"] {
+        let row = handler.generate(&sequential, None).await.unwrap();
+        assert_eq!(row["sku"], expected);
+    }
+
+    let row = handler.generate(&config(CsvSelection::Random, None), None).await.unwrap();
+    assert!(row["sku"] == "A-1" || row["sku"] == "B-2");
+
+    // Rows are cached, but an edited file is parsed again
+    std::fs::write(&path, "sku,name\nC-3,Gizmo\n").unwrap();
+    assert_eq!(
+        handler.generate(&config(CsvSelection::Index, Some(0)), None).await.unwrap(),
+        json!({"sku": "C-3", "name": "Gizmo"})
+    );
+}
+
+#[tokio::test]
+async fn test_data_lake_crud_fails_while_data_lakes_are_disabled() {
+    let mut settings: crate::config::Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000}
+    }))
+    .unwrap();
+    settings.features.data_lake = false;
+    let handler = MockStrategyHandler::new_with_datafusion(
+        Arc::new(StateManager::new()),
+        None,
+        Some(Arc::new(tokio::sync::RwLock::new(settings))),
+        None,
+    );
+    let config: MockConfig = serde_json::from_value(json!({
+        "strategy": "data_lake_crud",
+        "data_lake_crud": {"data_lake": "orders", "schema_name": "Order", "operation": "read_all"}
+    }))
+    .unwrap();
+
+    let err = handler.generate(&config, Some(&json!({}))).await.unwrap_err();
+    assert!(err.to_string().contains("features.data_lake = false"), "{}", err);
+}
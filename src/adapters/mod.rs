@@ -1,25 +1,51 @@
+pub mod access_control;
 pub mod api_handler;
 pub mod auth_middleware;
+pub mod bruno_export;
+pub mod callback_tracker;
+pub mod config_history;
+pub mod content_negotiation;
+pub mod csv_cache;
 pub mod data_lake_handler;
 pub mod datafusion_handler;
+pub mod db_strategy_pool;
 pub mod encryption;
+pub mod features;
 pub mod file_storage;
+pub mod fixture_cache;
 pub mod health_handler;
+pub mod http_mock;
+pub mod idempotency;
+pub mod json_path;
 pub mod jwks;
+pub mod logging;
 pub mod mcp_client;
 pub mod metrics_handler;
+pub mod mock_generator;
 pub mod mock_strategy;
 pub mod prompt_handler;
 pub mod rate_limit;
+pub mod resource_content;
 pub mod resource_handler;
+pub mod resource_links;
+pub mod response_transform;
 pub mod rmcp_server;
 pub mod sampling_handler;
 pub mod secrets;
 pub mod session_manager;
+pub mod startup;
 pub mod state_manager;
+pub mod strategy_catalog;
+pub mod strategy_errors;
+pub mod test_assertions;
+pub mod tool_concurrency;
+pub mod tool_dedup;
 pub mod tool_handler;
+pub mod tool_latency;
+pub mod tool_overrides;
 pub mod ui_handler;
 pub mod workflow_engine;
+pub mod workflow_estimate;
 
 #[cfg(test)]
 mod database_strategy_test;
@@ -1,6 +1,6 @@
 use axum::{
-    extract::{Request, State},
-    http::StatusCode,
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -9,25 +9,442 @@ use governor::{
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use tokio::sync::RwLock;
+
+use crate::adapters::metrics_handler::MetricsCollector;
+use crate::config::{RateLimitAlgorithm, Settings};
+
+/// Header carrying a rate limit bypass token
+pub const BYPASS_HEADER: &str = "x-rate-limit-bypass";
+/// Most requests let through at once
+pub const LIMIT_HEADER: &str = "x-ratelimit-limit";
+/// Requests that would still be let through
+pub const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Seconds until the full limit is available again
+pub const RESET_HEADER: &str = "x-ratelimit-reset";
 
 // Define the type of our rate limiter
 pub type SharedRateLimiter = Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>;
 
+/// Decides whether a request may go through now, by one of the
+/// [`RateLimitAlgorithm`]s
+pub trait RequestLimiter: Send + Sync {
+    /// Count a request against the limit
+    fn check(&self) -> RateLimitDecision {
+        self.check_at(Instant::now())
+    }
+
+    /// Count a request made at `now` against the limit
+    fn check_at(&self, now: Instant) -> RateLimitDecision;
+}
+
+/// A request limiter shared by the middleware's clones
+pub type SharedRequestLimiter = Arc<dyn RequestLimiter>;
+
+/// Outcome of a [`RequestLimiter::check`], reported in the response headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// Most requests let through at once
+    pub limit: u32,
+    /// Requests that would still be let through right now
+    pub remaining: u32,
+    /// Time until the full limit is available again
+    pub reset: Duration,
+    /// Time until a request would be let through; zero when this one was
+    pub retry_after: Duration,
+}
+
+/// Generic cell rate algorithm: requests are spaced `interval` apart, and up
+/// to `burst` may come in at once. Keeps only the theoretical arrival time of
+/// the next request.
+pub struct GcraLimiter {
+    interval: Duration,
+    burst: u32,
+    next_arrival: Mutex<Option<Instant>>,
+}
+
+impl GcraLimiter {
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        Self {
+            // Over a billion requests per second would round down to no interval
+            interval: (Duration::from_secs(1) / requests_per_second.max(1)).max(Duration::from_nanos(1)),
+            burst: burst.max(1),
+            next_arrival: Mutex::new(None),
+        }
+    }
+}
+
+impl RequestLimiter for GcraLimiter {
+    fn check_at(&self, now: Instant) -> RateLimitDecision {
+        let mut next_arrival = self.next_arrival.lock().unwrap_or_else(|e| e.into_inner());
+        let arrival = next_arrival.filter(|at| *at > now).unwrap_or(now) + self.interval;
+        let capacity = self.interval * self.burst;
+        // How far ahead of schedule the requests so far, and this one, are
+        let backlog = arrival - now;
+
+        if backlog > capacity {
+            let reset = arrival - self.interval - now;
+            return RateLimitDecision {
+                allowed: false,
+                limit: self.burst,
+                remaining: 0,
+                reset,
+                retry_after: backlog - capacity,
+            };
+        }
+        *next_arrival = Some(arrival);
+        RateLimitDecision {
+            allowed: true,
+            limit: self.burst,
+            remaining: ((capacity - backlog).as_nanos() / self.interval.as_nanos()) as u32,
+            reset: backlog,
+            retry_after: Duration::ZERO,
+        }
+    }
+}
+
+/// Allows `limit` requests in any `window`, keeping the time of each one
+pub struct SlidingWindowLimiter {
+    limit: usize,
+    window: Duration,
+    requests: Mutex<VecDeque<Instant>>,
+}
+
+impl SlidingWindowLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit: limit.max(1) as usize,
+            window,
+            requests: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl RequestLimiter for SlidingWindowLimiter {
+    fn check_at(&self, now: Instant) -> RateLimitDecision {
+        let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        while requests.front().is_some_and(|at| now.duration_since(*at) >= self.window) {
+            requests.pop_front();
+        }
+        let allowed = requests.len() < self.limit;
+        if allowed {
+            requests.push_back(now);
+        }
+        // Requests leave the window oldest first
+        let until_expired =
+            |at: Option<&Instant>| at.map_or(Duration::ZERO, |at| self.window - now.duration_since(*at));
+        RateLimitDecision {
+            allowed,
+            limit: self.limit as u32,
+            remaining: (self.limit - requests.len()) as u32,
+            reset: until_expired(requests.back()),
+            retry_after: if allowed { Duration::ZERO } else { until_expired(requests.front()) },
+        }
+    }
+}
+
+/// State for the rate limit middleware
+#[derive(Clone)]
+pub struct RateLimitState {
+    pub limiter: SharedRequestLimiter,
+    /// Settings are read per request so bypass tokens follow config hot-reloads
+    pub settings: Arc<RwLock<Settings>>,
+    pub metrics: Option<Arc<MetricsCollector>>,
+    /// First client IP seen for each bypass token, used to flag unusual usage
+    known_ips: Arc<RwLock<HashMap<String, IpAddr>>>,
+}
+
+impl RateLimitState {
+    pub fn new(
+        limiter: SharedRequestLimiter,
+        settings: Arc<RwLock<Settings>>,
+        metrics: Option<Arc<MetricsCollector>>,
+    ) -> Self {
+        Self {
+            limiter,
+            settings,
+            metrics,
+            known_ips: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Return the configured bypass token matching the request header, if any
+    async fn matching_bypass_token(&self, headers: &HeaderMap) -> Option<String> {
+        let provided = headers.get(BYPASS_HEADER)?.as_bytes();
+        let settings = self.settings.read().await;
+        let tokens = &settings.rate_limit.as_ref()?.bypass_tokens;
+
+        // Check every token so timing doesn't reveal which one matched
+        let mut matched = None;
+        for token in tokens {
+            if bool::from(token.as_bytes().ct_eq(provided)) {
+                matched = Some(token.clone());
+            }
+        }
+        matched
+    }
+
+    /// Record a bypass token use, warning if it comes from a different IP than first seen
+    async fn record_bypass(&self, token: &str, client_ip: Option<IpAddr>) {
+        let prefix = token_prefix(token);
+
+        if let Some(metrics) = &self.metrics {
+            metrics.bypass_token_uses.with_label_values(&[&prefix]).inc();
+        }
+
+        let Some(ip) = client_ip else {
+            return;
+        };
+
+        let mut known_ips = self.known_ips.write().await;
+        match known_ips.get(token) {
+            Some(known) if *known != ip => {
+                tracing::warn!(
+                    "Rate limit bypass token '{}…' used from unusual IP {} (first seen from {})",
+                    prefix,
+                    ip,
+                    known
+                );
+            }
+            Some(_) => {}
+            None => {
+                known_ips.insert(token.to_string(), ip);
+            }
+        }
+    }
+}
+
+/// First 4 characters of a token, safe to expose in logs and metric labels
+fn token_prefix(token: &str) -> String {
+    token.chars().take(4).collect()
+}
+
 pub async fn rate_limit_middleware(
-    State(limiter): State<SharedRateLimiter>,
+    State(state): State<RateLimitState>,
     request: Request,
     next: Next,
 ) -> Response {
-    match limiter.check() {
-        Ok(_) => next.run(request).await,
-        Err(_) => (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").into_response(),
+    if let Some(token) = state.matching_bypass_token(request.headers()).await {
+        let client_ip = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0.ip());
+        state.record_bypass(&token, client_ip).await;
+        return next.run(request).await;
     }
+
+    let decision = state.limiter.check();
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(whole_seconds(decision.retry_after)));
+        response
+    };
+    let headers = response.headers_mut();
+    headers.insert(LIMIT_HEADER, HeaderValue::from(decision.limit));
+    headers.insert(REMAINING_HEADER, HeaderValue::from(decision.remaining));
+    headers.insert(RESET_HEADER, HeaderValue::from(whole_seconds(decision.reset)));
+    response
 }
 
-pub fn create_limiter(requests_per_second: u32, burst_size: u32) -> SharedRateLimiter {
-    let quota = Quota::per_second(NonZeroU32::new(requests_per_second).unwrap_or(NonZeroU32::new(1).unwrap()))
-        .allow_burst(NonZeroU32::new(burst_size).unwrap_or(NonZeroU32::new(1).unwrap()));
+/// Seconds in `duration`, rounded up so clients don't come back too early
+fn whole_seconds(duration: Duration) -> u64 {
+    duration.as_millis().div_ceil(1000) as u64
+}
+
+/// Create a limiter allowing `requests_per_second` on average by
+/// `algorithm`, with bursts of up to `burst_size`
+pub fn create_limiter(
+    algorithm: RateLimitAlgorithm,
+    requests_per_second: u32,
+    burst_size: u32,
+) -> SharedRequestLimiter {
+    match algorithm {
+        // GCRA with a burst allowance behaves as a token bucket
+        RateLimitAlgorithm::TokenBucket => Arc::new(GcraLimiter::new(requests_per_second, burst_size)),
+        // A window long enough for `burst_size` requests at the average rate
+        RateLimitAlgorithm::SlidingWindow => Arc::new(SlidingWindowLimiter::new(
+            burst_size,
+            Duration::from_secs(1) * burst_size.max(1) / requests_per_second.max(1),
+        )),
+        // Validation only lets `burst_size = 1` through for GCRA
+        RateLimitAlgorithm::Gcra => Arc::new(GcraLimiter::new(requests_per_second, burst_size)),
+    }
+}
+
+/// Create a limiter for expensive endpoints that allows `requests_per_minute`
+/// requests, refilled evenly over the minute
+pub fn create_per_minute_limiter(requests_per_minute: u32) -> SharedRateLimiter {
+    let quota = Quota::per_minute(NonZeroU32::new(requests_per_minute).unwrap_or(NonZeroU32::new(1).unwrap()));
     Arc::new(RateLimiter::direct(quota))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware::from_fn_with_state, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn settings_with_tokens(tokens: &[&str]) -> Arc<RwLock<Settings>> {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "rate_limit": {
+                "enabled": true,
+                "requests_per_second": 1,
+                "burst_size": 1,
+                "bypass_tokens": tokens
+            }
+        }))
+        .unwrap();
+        Arc::new(RwLock::new(settings))
+    }
+
+    fn app(state: RateLimitState) -> Router {
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(from_fn_with_state(state, rate_limit_middleware))
+    }
+
+    async fn status(app: &Router, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/");
+        if let Some(token) = token {
+            request = request.header(BYPASS_HEADER, token);
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_bypass_token_skips_rate_limit() {
+        let metrics = Arc::new(MetricsCollector::new().unwrap());
+        let state = RateLimitState::new(
+            create_limiter(RateLimitAlgorithm::TokenBucket, 1, 1),
+            settings_with_tokens(&["ci-secret-token"]),
+            Some(metrics.clone()),
+        );
+        let app = app(state);
+
+        assert_eq!(status(&app, None).await, StatusCode::OK);
+        assert_eq!(status(&app, None).await, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(status(&app, Some("wrong-token")).await, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(status(&app, Some("ci-secret-token")).await, StatusCode::OK);
+        assert_eq!(status(&app, Some("ci-secret-token")).await, StatusCode::OK);
+
+        let encoded = metrics.encode().unwrap();
+        assert!(encoded.contains("metis_bypass_token_uses_total{token_prefix=\"ci-s\"} 2"));
+    }
+
+    /// Requests let through by `limiter` out of `count` sent at `now`
+    fn allowed(limiter: &SharedRequestLimiter, count: usize, now: Instant) -> usize {
+        (0..count).filter(|_| limiter.check_at(now).allowed).count()
+    }
+
+    #[test]
+    fn test_algorithms_allow_different_bursts() {
+        let token_bucket = create_limiter(RateLimitAlgorithm::TokenBucket, 10, 5);
+        let sliding_window = create_limiter(RateLimitAlgorithm::SlidingWindow, 10, 5);
+        let gcra = create_limiter(RateLimitAlgorithm::Gcra, 10, 1);
+        let start = Instant::now();
+
+        assert_eq!(allowed(&token_bucket, 20, start), 5);
+        assert_eq!(allowed(&sliding_window, 20, start), 5);
+        assert_eq!(allowed(&gcra, 20, start), 1);
+
+        // A token bucket refills one request per interval, while a sliding
+        // window frees up only as the burst leaves its 500 ms window
+        let later = start + Duration::from_millis(150);
+        assert_eq!(allowed(&token_bucket, 20, later), 1);
+        assert_eq!(allowed(&sliding_window, 20, later), 0);
+        assert_eq!(allowed(&gcra, 20, later), 1);
+
+        assert_eq!(allowed(&sliding_window, 20, start + Duration::from_millis(500)), 5);
+    }
+
+    #[test]
+    fn test_sliding_window_frees_up_as_requests_age() {
+        let limiter = SlidingWindowLimiter::new(2, Duration::from_millis(50));
+        let start = Instant::now();
+        assert!(limiter.check_at(start).allowed);
+        assert!(limiter.check_at(start).allowed);
+        assert!(!limiter.check_at(start).allowed);
+
+        let later = start + Duration::from_millis(50);
+        assert!(limiter.check_at(later).allowed);
+        assert!(limiter.check_at(later).allowed);
+        assert!(!limiter.check_at(later).allowed);
+    }
+
+    #[test]
+    fn test_gcra_handles_rates_beyond_nanosecond_intervals() {
+        let limiter = GcraLimiter::new(u32::MAX, 2);
+        let now = Instant::now();
+        assert!(limiter.check_at(now).allowed);
+        assert!(limiter.check_at(now).allowed);
+        assert!(!limiter.check_at(now).allowed);
+        assert!(limiter.check_at(now + Duration::from_nanos(1)).allowed);
+    }
+
+    #[test]
+    fn test_gcra_reports_remaining_and_reset() {
+        let limiter = GcraLimiter::new(2, 3);
+        let first = limiter.check();
+        assert!(first.allowed);
+        assert_eq!((first.limit, first.remaining), (3, 2));
+        assert_eq!(first.reset, Duration::from_millis(500));
+
+        limiter.check();
+        limiter.check();
+        let rejected = limiter.check();
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.remaining, 0);
+        assert!(rejected.retry_after > Duration::from_millis(400));
+        assert!(rejected.retry_after <= Duration::from_millis(500));
+        assert!(rejected.reset > Duration::from_millis(1400));
+    }
+
+    #[tokio::test]
+    async fn test_rejections_say_when_to_retry() {
+        let limiter = create_limiter(RateLimitAlgorithm::TokenBucket, 1, 2);
+        let app = app(RateLimitState::new(limiter, settings_with_tokens(&[]), None));
+        let send = || app.clone().oneshot(Request::builder().uri("/").body(Body::empty()).unwrap());
+
+        let allowed = send().await.unwrap();
+        assert_eq!(allowed.status(), StatusCode::OK);
+        assert_eq!(allowed.headers()[LIMIT_HEADER], "2");
+        assert_eq!(allowed.headers()[REMAINING_HEADER], "1");
+        assert!(allowed.headers().get(header::RETRY_AFTER).is_none());
+
+        send().await.unwrap();
+        let rejected = send().await.unwrap();
+        assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(rejected.headers()[header::RETRY_AFTER], "1");
+        assert_eq!(rejected.headers()[REMAINING_HEADER], "0");
+        assert_eq!(rejected.headers()[RESET_HEADER], "2");
+    }
+
+    #[tokio::test]
+    async fn test_bypass_tokens_are_hot_reloaded() {
+        let settings = settings_with_tokens(&[]);
+        let limiter = create_limiter(RateLimitAlgorithm::TokenBucket, 1, 1);
+        let app = app(RateLimitState::new(limiter, settings.clone(), None));
+
+        assert_eq!(status(&app, None).await, StatusCode::OK);
+        assert_eq!(status(&app, Some("new-token")).await, StatusCode::TOO_MANY_REQUESTS);
+
+        settings.write().await.rate_limit.as_mut().unwrap().bypass_tokens = vec!["new-token".to_string()];
+        assert_eq!(status(&app, Some("new-token")).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,167 @@
+//! MIME-aware rendering of resource content
+//!
+//! Mock strategies and static content produce loosely typed values. Before a
+//! resource is returned, its content is rendered so that it matches the
+//! resource's declared `mime_type`:
+//! - `application/json`: validated and pretty-printed
+//! - `text/csv`: arrays of objects/arrays become header + rows
+//! - `application/octet-stream`: base64-encoded
+//! - anything else: plain text
+
+use crate::domain::ResourceContentKind;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde_json::Value;
+
+/// Render generated or static content according to the resource MIME type
+pub fn render_content(mime_type: Option<&str>, value: Value) -> Result<String> {
+    // Resources without content stay empty whatever their type
+    if matches!(&value, Value::String(s) if s.is_empty()) {
+        return Ok(String::new());
+    }
+
+    match ResourceContentKind::from_mime_type(mime_type) {
+        ResourceContentKind::Json => render_json(value),
+        ResourceContentKind::Csv => Ok(render_csv(&value)),
+        ResourceContentKind::Binary => {
+            Ok(base64::engine::general_purpose::STANDARD.encode(into_text(value)))
+        }
+        ResourceContentKind::Text => Ok(into_text(value)),
+    }
+}
+
+/// Strings are used as-is, everything else is serialized as compact JSON
+fn into_text(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+fn render_json(value: Value) -> Result<String> {
+    let value = match value {
+        Value::String(s) => serde_json::from_str::<Value>(&s)
+            .map_err(|e| anyhow!("Content is not valid JSON: {}", e))?,
+        other => other,
+    };
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Render a value as CSV.
+///
+/// Arrays of objects get a header row built from their keys (in order of first
+/// appearance), arrays of arrays are written row by row, and a single object
+/// becomes a one-row table. Strings are assumed to already be CSV.
+fn render_csv(value: &Value) -> String {
+    let rows: Vec<&Value> = match value {
+        Value::String(s) => return s.clone(),
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![value],
+        other => return csv_field(other),
+    };
+
+    let mut header: Vec<&str> = Vec::new();
+    for row in &rows {
+        if let Value::Object(obj) = row {
+            for key in obj.keys() {
+                if !header.contains(&key.as_str()) {
+                    header.push(key);
+                }
+            }
+        }
+    }
+
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    if !header.is_empty() {
+        lines.push(
+            header
+                .iter()
+                .map(|h| csv_field(&Value::String(h.to_string())))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    for row in rows {
+        let fields: Vec<String> = match row {
+            Value::Object(obj) => header
+                .iter()
+                .map(|h| obj.get(*h).map(csv_field).unwrap_or_default())
+                .collect(),
+            Value::Array(cells) => cells.iter().map(csv_field).collect(),
+            other => vec![csv_field(other)],
+        };
+        lines.push(fields.join(","));
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let mut csv = lines.join("\n");
+    csv.push('\n');
+    csv
+}
+
+/// Format a single CSV cell, quoting it when it contains separators or quotes
+fn csv_field(value: &Value) -> String {
+    let raw = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    if raw.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_content_is_pretty_printed() {
+        let rendered = render_content(Some("application/json"), json!("{\"a\":1}")).unwrap();
+        assert_eq!(rendered, "{\n  \"a\": 1\n}");
+
+        let rendered = render_content(Some("application/json; charset=utf-8"), json!([1])).unwrap();
+        assert_eq!(rendered, "[\n  1\n]");
+
+        assert!(render_content(Some("application/json"), json!("not json")).is_err());
+    }
+
+    #[test]
+    fn test_csv_content_from_rows() {
+        let rows = json!([
+            {"id": 1, "name": "Ada"},
+            {"id": 2, "name": "Lovelace, A.", "note": "say \"hi\""}
+        ]);
+        let rendered = render_content(Some("text/csv"), rows).unwrap();
+        assert_eq!(
+            rendered,
+            "id,name,note\n1,Ada,\n2,\"Lovelace, A.\",\"say \"\"hi\"\"\"\n"
+        );
+
+        let rendered = render_content(Some("text/csv"), json!([[1, "a"], [2, null]])).unwrap();
+        assert_eq!(rendered, "1,a\n2,\n");
+
+        let rendered = render_content(Some("text/csv"), json!("a,b\n1,2\n")).unwrap();
+        assert_eq!(rendered, "a,b\n1,2\n");
+    }
+
+    #[test]
+    fn test_binary_content_is_base64() {
+        let rendered = render_content(Some("application/octet-stream"), json!("hello")).unwrap();
+        assert_eq!(rendered, "aGVsbG8=");
+    }
+
+    #[test]
+    fn test_text_content_passes_through() {
+        assert_eq!(render_content(None, json!("plain")).unwrap(), "plain");
+        assert_eq!(render_content(Some("text/plain"), json!({"a": 1})).unwrap(), "{\"a\":1}");
+    }
+}
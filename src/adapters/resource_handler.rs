@@ -1,5 +1,6 @@
 use crate::adapters::file_storage::FileStorageHandler;
 use crate::adapters::mock_strategy::MockStrategyHandler;
+use crate::adapters::resource_content::render_content;
 use crate::config::{ResourceConfig, ResourceTemplateConfig, Settings};
 use crate::domain::{Resource, ResourcePort, ResourceTemplate};
 use anyhow::Result;
@@ -71,18 +72,65 @@ impl InMemoryResourceHandler {
         resolved
     }
 
-    /// Try to match a resolved URI against resource templates and extract arguments
-    /// Returns the matched template config and extracted arguments as JSON
-    async fn match_uri_to_template(&self, uri: &str) -> Option<(ResourceTemplateConfig, Value)> {
+    /// Reverse-resolve a concrete URI to the resource template it was produced from.
+    ///
+    /// Returns the matched template config and the `{variable}` values extracted
+    /// from the URI. When several templates match, the most specific one (the one
+    /// with the most literal characters) wins, so `db://users/{id}` is preferred
+    /// over `db://{table}/{id}`.
+    pub async fn match_uri_to_template(&self, uri: &str) -> Option<(ResourceTemplateConfig, Value)> {
         let settings = self.settings.read().await;
 
-        for template in &settings.resource_templates {
-            if let Some(args) = Self::extract_template_args(&template.uri_template, uri) {
-                return Some((template.clone(), args));
+        settings
+            .resource_templates
+            .iter()
+            // Reversed so that ties go to the template declared first
+            .rev()
+            .filter_map(|template| {
+                Self::extract_template_args(&template.uri_template, uri)
+                    .map(|args| (template, args))
+            })
+            .max_by_key(|(template, _)| Self::literal_len(&template.uri_template))
+            .map(|(template, args)| (template.clone(), args))
+    }
+
+    /// Number of characters in a URI template outside of `{variable}` placeholders
+    fn literal_len(template: &str) -> usize {
+        let mut in_placeholder = false;
+        template
+            .chars()
+            .filter(|c| match c {
+                '{' => {
+                    in_placeholder = true;
+                    false
+                }
+                '}' => {
+                    in_placeholder = false;
+                    false
+                }
+                _ => !in_placeholder,
+            })
+            .count()
+    }
+
+    /// Log the type and size of a generated resource value for debugging
+    fn log_mock_result(uri: &str, result: &Value) {
+        match result {
+            Value::Array(arr) => tracing::debug!(
+                "Resource {} mock result: JSON array with {} elements",
+                uri,
+                arr.len()
+            ),
+            Value::String(s) => {
+                tracing::debug!("Resource {} mock result: string, {} bytes", uri, s.len())
             }
+            other => tracing::debug!(
+                "Resource {} mock result: {}, {} bytes",
+                uri,
+                if other.is_object() { "object" } else { "scalar" },
+                other.to_string().len()
+            ),
         }
-
-        None
     }
 
     /// Extract arguments from a URI by matching against a template pattern
@@ -170,6 +218,9 @@ impl InMemoryResourceHandler {
 
     /// Handle data lake resource URIs (datalake://{lake}/{schema})
     async fn get_data_lake_resource(&self, uri: &str) -> Result<crate::domain::ResourceReadResult> {
+        if !self.settings.read().await.features.data_lake {
+            return Err(anyhow::anyhow!("Data lakes are disabled (features.data_lake = false)"));
+        }
         let file_storage = self.file_storage.as_ref()
             .ok_or_else(|| anyhow::anyhow!("File storage not configured"))?;
 
@@ -240,7 +291,7 @@ impl ResourcePort for InMemoryResourceHandler {
             .collect();
 
         // Add data lake resources if file storage is enabled
-        if self.file_storage.is_some() {
+        if self.file_storage.is_some() && settings.features.data_lake {
             for data_lake in &settings.data_lakes {
                 if data_lake.uses_files() {
                     for schema_ref in &data_lake.schemas {
@@ -300,7 +351,7 @@ impl ResourcePort for InMemoryResourceHandler {
             .collect();
 
         // Add SQL query resource template for data lakes with SQL enabled
-        if self.file_storage.is_some() {
+        if self.file_storage.is_some() && settings.features.data_lake {
             for data_lake in &settings.data_lakes {
                 if data_lake.enable_sql_queries {
                     templates.push(ResourceTemplate {
@@ -330,65 +381,19 @@ impl ResourcePort for InMemoryResourceHandler {
         }
 
         if let Some(config) = self.find_resource_config(uri).await {
-            let content = if let Some(mock_config) = &config.mock {
+            let value = if let Some(mock_config) = &config.mock {
                 let result = self.mock_strategy.generate(mock_config, None).await?;
-
-                // Log result type and size for debugging
-                let (content_str, array_len) = if let Some(arr) = result.as_array() {
-                    let s = result.to_string();
-                    tracing::debug!(
-                        "Resource {} mock result: JSON array with {} elements, {} bytes",
-                        uri,
-                        arr.len(),
-                        s.len()
-                    );
-                    (s, Some(arr.len()))
-                } else if let Some(s) = result.as_str() {
-                    tracing::debug!(
-                        "Resource {} mock result: string, {} bytes",
-                        uri,
-                        s.len()
-                    );
-                    (s.to_string(), None)
-                } else {
-                    let s = result.to_string();
-                    let type_str = match &result {
-                        serde_json::Value::Null => "null",
-                        serde_json::Value::Bool(_) => "boolean",
-                        serde_json::Value::Number(_) => "number",
-                        serde_json::Value::Object(_) => "object",
-                        _ => "other",
-                    };
-                    tracing::debug!(
-                        "Resource {} mock result: {}, {} bytes",
-                        uri,
-                        type_str,
-                        s.len()
-                    );
-                    (s, None)
-                };
-
-                // Verify array wasn't truncated during serialization
-                if let Some(len) = array_len {
-                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&content_str) {
-                        if let Some(parsed_arr) = parsed.as_array() {
-                            if parsed_arr.len() != len {
-                                tracing::warn!(
-                                    "Resource {} array length mismatch: original {} vs serialized {}",
-                                    uri, len, parsed_arr.len()
-                                );
-                            }
-                        }
-                    }
-                }
-
-                content_str
+                Self::log_mock_result(uri, &result);
+                result
             } else if let Some(c) = &config.content {
-                c.clone()
+                Value::String(c.clone())
             } else {
-                "".to_string()
+                Value::String(String::new())
             };
 
+            let content = render_content(config.mime_type.as_deref(), value)
+                .map_err(|e| anyhow::anyhow!("Resource {}: {}", uri, e))?;
+
             tracing::debug!("Resource {} returning {} bytes of content", uri, content.len());
 
             Ok(crate::domain::ResourceReadResult {
@@ -405,20 +410,18 @@ impl ResourcePort for InMemoryResourceHandler {
                 args
             );
 
-            let content = if let Some(mock_config) = &template_config.mock {
-                let result = self.mock_strategy.generate(mock_config, Some(&args)).await?;
-                if let Some(s) = result.as_str() {
-                    s.to_string()
-                } else {
-                    result.to_string()
-                }
+            let value = if let Some(mock_config) = &template_config.mock {
+                self.mock_strategy.generate(mock_config, Some(&args)).await?
             } else if let Some(c) = &template_config.content {
                 // Also resolve template variables in static content
-                Self::resolve_uri_template(c, Some(&args))
+                Value::String(Self::resolve_uri_template(c, Some(&args)))
             } else {
-                "".to_string()
+                Value::String(String::new())
             };
 
+            let content = render_content(template_config.mime_type.as_deref(), value)
+                .map_err(|e| anyhow::anyhow!("Resource {}: {}", uri, e))?;
+
             tracing::debug!("Resource template {} returning {} bytes of content", uri, content.len());
 
             Ok(crate::domain::ResourceReadResult {
@@ -440,20 +443,18 @@ impl ResourcePort for InMemoryResourceHandler {
             // Resolve the URI template with the provided arguments
             let resolved_uri = Self::resolve_uri_template(&config.uri_template, args);
 
-            let content = if let Some(mock_config) = &config.mock {
-                let result = self.mock_strategy.generate(mock_config, args).await?;
-                if let Some(s) = result.as_str() {
-                    s.to_string()
-                } else {
-                    result.to_string()
-                }
+            let value = if let Some(mock_config) = &config.mock {
+                self.mock_strategy.generate(mock_config, args).await?
             } else if let Some(c) = &config.content {
                 // Also resolve template variables in static content
-                Self::resolve_uri_template(c, args)
+                Value::String(Self::resolve_uri_template(c, args))
             } else {
-                "".to_string()
+                Value::String(String::new())
             };
 
+            let content = render_content(config.mime_type.as_deref(), value)
+                .map_err(|e| anyhow::anyhow!("Resource {}: {}", resolved_uri, e))?;
+
             Ok(crate::domain::ResourceReadResult {
                 uri: resolved_uri,
                 mime_type: config.mime_type.clone(),
@@ -1,7 +1,7 @@
 use super::resource_handler::InMemoryResourceHandler;
 use crate::adapters::mock_strategy::MockStrategyHandler;
 use crate::adapters::state_manager::StateManager;
-use crate::config::{MockConfig, MockStrategyType, ResourceConfig, Settings, ServerSettings};
+use crate::config::{LogFormat, MockConfig, MockStrategyType, ResourceConfig, Settings, ServerSettings};
 use crate::domain::ResourcePort;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -21,14 +21,27 @@ use tokio::sync::RwLock;
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: config,
         resource_templates: vec![],
         tools: vec![],
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -38,6 +51,8 @@ use tokio::sync::RwLock;
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = InMemoryResourceHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -70,20 +85,38 @@ use tokio::sync::RwLock;
             database: None,
             faker_schema: None,
             data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: None,
+            transform: vec![],
         }),
         tags: vec![],
     }];
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: config,
         resource_templates: vec![],
         tools: vec![],
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -93,6 +126,8 @@ use tokio::sync::RwLock;
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = InMemoryResourceHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -107,14 +142,27 @@ use tokio::sync::RwLock;
     let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: vec![],
         resource_templates: vec![],
         tools: vec![],
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -124,6 +172,8 @@ use tokio::sync::RwLock;
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = InMemoryResourceHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -159,14 +209,27 @@ use tokio::sync::RwLock;
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: config,
         resource_templates: vec![],
         tools: vec![],
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -176,6 +239,8 @@ use tokio::sync::RwLock;
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = InMemoryResourceHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -225,3 +290,57 @@ fn test_extract_template_args_no_placeholders() {
     let args = result.unwrap();
     assert!(args.as_object().unwrap().is_empty());
 }
+
+#[tokio::test]
+async fn test_match_uri_to_template_prefers_most_specific() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(serde_json::json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "resource_templates": [
+            {"uri_template": "db://{table}/{id}", "name": "Any row", "description": null, "mime_type": null, "content": "row"},
+            {"uri_template": "db://users/{id}", "name": "User", "description": null, "mime_type": null, "content": "user {id}"}
+        ]
+    }))
+    .unwrap();
+    let handler = InMemoryResourceHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    let (template, args) = handler.match_uri_to_template("db://users/42").await.unwrap();
+    assert_eq!(template.uri_template, "db://users/{id}");
+    assert_eq!(args, serde_json::json!({"id": "42"}));
+
+    let (template, args) = handler.match_uri_to_template("db://orders/7").await.unwrap();
+    assert_eq!(template.uri_template, "db://{table}/{id}");
+    assert_eq!(args, serde_json::json!({"table": "orders", "id": "7"}));
+
+    assert!(handler.match_uri_to_template("file://users/42").await.is_none());
+
+    let result = handler.get_resource("db://users/42").await.unwrap();
+    assert_eq!(result.content, "user 42");
+}
+
+#[tokio::test]
+async fn test_get_resource_renders_content_for_mime_type() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(serde_json::json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "resources": [
+            {"uri": "file:///data.json", "name": "JSON", "description": null, "mime_type": "application/json", "content": "{\"a\":1}"},
+            {"uri": "file:///data.bin", "name": "Binary", "description": null, "mime_type": "application/octet-stream", "content": "hello"},
+            {
+                "uri": "file:///data.csv", "name": "CSV", "description": null, "mime_type": "text/csv",
+                "mock": {"strategy": "template", "template": "[{\"id\": 1}, {\"id\": 2}]"}
+            }
+        ]
+    }))
+    .unwrap();
+    let handler = InMemoryResourceHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    let json = handler.get_resource("file:///data.json").await.unwrap();
+    assert_eq!(json.content, "{\n  \"a\": 1\n}");
+
+    let binary = handler.get_resource("file:///data.bin").await.unwrap();
+    assert_eq!(binary.content, "aGVsbG8=");
+
+    let csv = handler.get_resource("file:///data.csv").await.unwrap();
+    assert_eq!(csv.content, "id\n1\n2\n");
+}
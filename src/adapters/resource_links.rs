@@ -0,0 +1,113 @@
+//! Resource links in tool results
+//!
+//! A tool can point at resources of the catalog instead of inlining their
+//! content by returning a `{ content: [...] }` envelope with `resource_link`
+//! items, e.g. `{"type": "resource_link", "uri": "file:///report.md", "name": "Report"}`.
+//! MCP clients get the envelope's items as the result's content. A link's URI
+//! has to be a configured resource or match a resource template.
+
+use anyhow::{anyhow, bail, Result};
+use rmcp::model::Content;
+use serde_json::Value;
+
+use crate::adapters::resource_handler::InMemoryResourceHandler;
+use crate::config::Settings;
+
+/// Content type of a resource link item
+pub const RESOURCE_LINK_TYPE: &str = "resource_link";
+
+/// The `resource_link` items of a result envelope
+pub fn resource_links(result: &Value) -> Vec<&Value> {
+    result
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| item.get("type").and_then(Value::as_str) == Some(RESOURCE_LINK_TYPE))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check that every resource link of `result` has a name and a URI that
+/// `settings` can serve
+pub fn check_links(result: &Value, settings: &Settings) -> Result<()> {
+    for link in resource_links(result) {
+        let uri = link
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Resource link without a 'uri': {}", link))?;
+        if link.get("name").and_then(Value::as_str).is_none() {
+            bail!("Resource link to '{}' has no 'name'", uri);
+        }
+        if !is_resolvable(uri, settings) {
+            bail!("Resource link to '{}' matches no resource or resource template", uri);
+        }
+    }
+    Ok(())
+}
+
+/// Whether `uri` is a resource or matches a resource template
+fn is_resolvable(uri: &str, settings: &Settings) -> bool {
+    settings.resources.iter().any(|r| r.uri == uri)
+        || settings
+            .resource_templates
+            .iter()
+            .any(|t| InMemoryResourceHandler::extract_template_args(&t.uri_template, uri).is_some())
+}
+
+/// MCP content of a result envelope, in envelope order
+pub fn envelope_content(result: &Value) -> Result<Vec<Content>> {
+    let items = result.get("content").cloned().unwrap_or_default();
+    serde_json::from_value(items).map_err(|e| anyhow!("Invalid tool result content: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings() -> Settings {
+        serde_json::from_value(json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "resources": [{"uri": "file:///report.md", "name": "Report"}],
+            "resource_templates": [{"uri_template": "users://{id}/profile", "name": "Profile"}]
+        }))
+        .unwrap()
+    }
+
+    fn link(uri: &str) -> Value {
+        json!({"type": RESOURCE_LINK_TYPE, "uri": uri, "name": "Link"})
+    }
+
+    #[test]
+    fn test_links_must_resolve() {
+        let settings = settings();
+        let result = json!({"content": [
+            {"type": "text", "text": "See:"},
+            link("file:///report.md"),
+            link("users://42/profile")
+        ]});
+        assert_eq!(resource_links(&result).len(), 2);
+        assert!(check_links(&result, &settings).is_ok());
+
+        let unknown = json!({"content": [link("file:///missing.md")]});
+        let err = check_links(&unknown, &settings).unwrap_err();
+        assert!(err.to_string().contains("file:///missing.md"));
+
+        let unnamed = json!({"content": [{"type": RESOURCE_LINK_TYPE, "uri": "file:///report.md"}]});
+        assert!(check_links(&unnamed, &settings).is_err());
+    }
+
+    #[test]
+    fn test_envelope_content() {
+        let result = json!({"content": [{"type": "text", "text": "See:"}, link("file:///report.md")]});
+        let content = envelope_content(&result).unwrap();
+
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0].as_text().map(|t| t.text.as_str()), Some("See:"));
+        let resource = content[1].raw.as_resource_link().unwrap();
+        assert_eq!((resource.uri.as_str(), resource.name.as_str()), ("file:///report.md", "Link"));
+    }
+}
@@ -0,0 +1,99 @@
+//! Post-processing of mock responses
+//!
+//! A mock's `transform` steps run on whatever its strategy generated, in
+//! order, so envelopes, redactions and added fields don't have to be repeated
+//! in every template or script.
+
+use crate::adapters::json_path;
+use crate::config::ResponseTransform;
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+/// Run `transforms` over `value` in order
+pub fn apply(transforms: &[ResponseTransform], mut value: Value) -> Result<Value> {
+    for transform in transforms {
+        value = match transform {
+            ResponseTransform::Wrap { key } => Value::Object(Map::from_iter([(key.clone(), value)])),
+            ResponseTransform::Redact { path, replacement } => {
+                let segments = json_path::parse(path)?;
+                json_path::visit_mut(&mut value, &segments, false, &mut |target| *target = replacement.clone());
+                value
+            }
+            ResponseTransform::Set { path, value: new_value } => {
+                let segments = json_path::parse(path)?;
+                let new_value = render(new_value, &value)?;
+                json_path::visit_mut(&mut value, &segments, true, &mut |target| *target = new_value.clone());
+                value
+            }
+            ResponseTransform::Expr { expression } => evaluate(expression, value)?,
+        };
+    }
+    Ok(value)
+}
+
+/// Render the string templates in a `set` value against the output
+fn render(value: &Value, output: &Value) -> Result<Value> {
+    match value {
+        Value::String(template) => {
+            let mut context = tera::Context::new();
+            context.insert("output", output);
+            let rendered = tera::Tera::one_off(template, &context, false)
+                .map_err(|e| anyhow!("Transform template error: {}", e))?;
+            Ok(serde_json::from_str(&rendered).unwrap_or(Value::String(rendered)))
+        }
+        Value::Array(items) => items.iter().map(|item| render(item, output)).collect(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, item)| Ok((key.clone(), render(item, output)?)))
+            .collect::<Result<Map<_, _>>>()
+            .map(Value::Object),
+        _ => Ok(value.clone()),
+    }
+}
+
+/// Evaluate a Rhai expression with the output in scope as `output`
+fn evaluate(expression: &str, output: Value) -> Result<Value> {
+    let mut scope = rhai::Scope::new();
+    scope.push("output", serde_json::from_value::<rhai::Dynamic>(output)?);
+    let result = rhai::Engine::new()
+        .eval_with_scope::<rhai::Dynamic>(&mut scope, expression)
+        .map_err(|e| anyhow!("Transform expression failed: {}", e))?;
+    Ok(serde_json::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transforms(value: Value) -> Vec<ResponseTransform> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_transforms_apply_in_order() {
+        let output = json!({"users": [{"name": "ada", "ssn": "123"}, {"name": "bob"}]});
+        let steps = transforms(json!([
+            {"type": "redact", "path": "users[*].ssn"},
+            {"type": "set", "path": "meta.count", "value": "{{ output.users | length }}"},
+            {"type": "wrap", "key": "data"}
+        ]));
+
+        assert_eq!(
+            apply(&steps, output).unwrap(),
+            json!({"data": {
+                "users": [{"name": "ada", "ssn": "[REDACTED]"}, {"name": "bob"}],
+                "meta": {"count": 2}
+            }})
+        );
+    }
+
+    #[test]
+    fn test_expr_transform() {
+        let steps = transforms(json!([{"type": "expr", "expression": "output.items.len()"}]));
+        assert_eq!(apply(&steps, json!({"items": [1, 2, 3]})).unwrap(), json!(3));
+
+        let steps = transforms(json!([{"type": "expr", "expression": "output.("}]));
+        assert!(apply(&steps, json!({})).is_err());
+    }
+}
@@ -20,12 +20,30 @@
 //! - `notifications/prompts/list_changed`
 //!
 //! Use `NotificationBroadcaster` to send these notifications to all connected peers.
-
-use crate::domain::{PromptPort, ResourcePort, ToolPort};
+//!
+//! ## Pagination
+//!
+//! `tools/list`, `resources/list`, `resources/templates/list` and `prompts/list`
+//! return at most `[mcp] page_size` items. When more remain, the result carries a
+//! `nextCursor` the client passes back as `cursor` to get the next page.
+//!
+//! ## Resource Links
+//!
+//! A tool result envelope with `resource_link` items is returned as its content
+//! items instead of as text; see [`resource_links`].
+
+use crate::adapters::access_control::ClientIp;
+use crate::adapters::mock_strategy::MockProtocolError;
+use crate::adapters::resource_links;
+use crate::config::{McpCapability, McpConfig, Settings};
+use crate::domain::auth::AuthContext;
+use crate::domain::execution::ExecutionContext;
+use crate::domain::{PromptPort, ResourceContentKind, ResourcePort, ToolPort};
+use base64::Engine;
 use rmcp::{
     handler::server::ServerHandler,
     model::{
-        CallToolRequestParam, CallToolResult, Content, GetPromptRequestParam, GetPromptResult,
+        CallToolRequestParam, CallToolResult, Content, ErrorCode, GetPromptRequestParam, GetPromptResult,
         Implementation, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
         ListToolsResult, PaginatedRequestParam, Prompt, PromptArgument, PromptMessage,
         PromptMessageRole, PromptListChangedNotification, RawResource, RawResourceTemplate,
@@ -39,7 +57,7 @@ use rmcp::{
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, warn, Instrument};
 
 /// Type alias for a unique peer identifier
 pub type PeerId = String;
@@ -138,7 +156,7 @@ impl NotificationBroadcaster {
         let mut failed_peers = Vec::new();
 
         for (id, peer) in peers.iter() {
-            if let Err(e) = peer.send_notification(notification.clone().into()).await {
+            if let Err(e) = peer.send_notification(notification.clone()).await {
                 warn!("Failed to send notification to peer {}: {}", id, e);
                 failed_peers.push(id.clone());
             }
@@ -172,6 +190,8 @@ pub struct MetisServer {
     tool_handler: Arc<dyn ToolPort>,
     prompt_handler: Arc<dyn PromptPort>,
     broadcaster: SharedNotificationBroadcaster,
+    /// Source of the `[mcp]` section sent to clients on `initialize`
+    settings: Option<Arc<RwLock<Settings>>>,
 }
 
 impl MetisServer {
@@ -186,6 +206,7 @@ impl MetisServer {
             tool_handler,
             prompt_handler,
             broadcaster: Arc::new(NotificationBroadcaster::new()),
+            settings: None,
         }
     }
 
@@ -201,6 +222,21 @@ impl MetisServer {
             tool_handler,
             prompt_handler,
             broadcaster,
+            settings: None,
+        }
+    }
+
+    /// Report the name, version, instructions and capabilities from `[mcp]`
+    pub fn with_settings(mut self, settings: Arc<RwLock<Settings>>) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Most items per list page, from `[mcp] page_size`
+    async fn page_size(&self) -> usize {
+        match &self.settings {
+            Some(settings) => settings.read().await.mcp.page_size,
+            None => McpConfig::default().page_size,
         }
     }
 
@@ -208,29 +244,138 @@ impl MetisServer {
     pub fn broadcaster(&self) -> &SharedNotificationBroadcaster {
         &self.broadcaster
     }
+
+    /// Get the resource handler
+    pub fn resource_handler(&self) -> &Arc<dyn ResourcePort> {
+        &self.resource_handler
+    }
+
+    /// Get the tool handler
+    pub fn tool_handler(&self) -> &Arc<dyn ToolPort> {
+        &self.tool_handler
+    }
+
+    /// Get the prompt handler
+    pub fn prompt_handler(&self) -> &Arc<dyn PromptPort> {
+        &self.prompt_handler
+    }
+}
+
+/// `initialize` response for `settings`, or the default one without settings
+fn server_info(settings: Option<&Settings>) -> ServerInfo {
+    let default_config = McpConfig::default();
+    let config = settings.map_or(&default_config, |s| &s.mcp);
+    let capabilities = settings.map_or_else(
+        || vec![McpCapability::Tools, McpCapability::Resources, McpCapability::Prompts],
+        Settings::mcp_capabilities,
+    );
+
+    let mut server_capabilities = ServerCapabilities::default();
+    for capability in capabilities {
+        match capability {
+            McpCapability::Tools => server_capabilities.tools = Some(Default::default()),
+            McpCapability::Resources => server_capabilities.resources = Some(Default::default()),
+            McpCapability::Prompts => server_capabilities.prompts = Some(Default::default()),
+        }
+    }
+
+    ServerInfo {
+        protocol_version: Default::default(),
+        capabilities: server_capabilities,
+        server_info: Implementation {
+            name: config.name.clone(),
+            version: config
+                .version
+                .clone()
+                .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+            title: None,
+            website_url: None,
+            icons: None,
+        },
+        instructions: Some(config.instructions.clone()).filter(|i| !i.is_empty()),
+    }
+}
+
+/// Page of `items` starting at the request's cursor, and the cursor of the next page
+///
+/// Cursors are the base64-encoded offset of the page's first item. A `page_size` of
+/// 0 returns everything in one page. Lists are sorted by name or URI before paging,
+/// so an offset points at the same item on every call.
+fn paginate<T>(
+    items: Vec<T>,
+    request: Option<&PaginatedRequestParam>,
+    page_size: usize,
+) -> Result<(Vec<T>, Option<String>), McpError> {
+    let offset = match request.and_then(|r| r.cursor.as_deref()) {
+        Some(cursor) => decode_cursor(cursor)
+            .filter(|offset| *offset <= items.len())
+            .ok_or_else(|| McpError::invalid_params(format!("Invalid cursor: {}", cursor), None))?,
+        None => 0,
+    };
+    if page_size == 0 {
+        return Ok((items.into_iter().skip(offset).collect(), None));
+    }
+
+    let end = offset.saturating_add(page_size);
+    let next_cursor = (end < items.len()).then(|| encode_cursor(end));
+    let page = items.into_iter().skip(offset).take(page_size).collect();
+    Ok((page, next_cursor))
+}
+
+fn encode_cursor(offset: usize) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(offset.to_string())
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    String::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Header carrying the seed that makes a tool call's random output reproducible
+pub const SEED_HEADER: &str = "x-metis-seed";
+
+/// Caller details of an MCP request, from the HTTP request the transport received it in
+fn execution_context(parts: Option<&axum::http::request::Parts>) -> ExecutionContext {
+    let Some(parts) = parts else {
+        return ExecutionContext::default();
+    };
+    let caller_ip = parts
+        .extensions
+        .get::<ClientIp>()
+        .map(|ip| ip.0)
+        .or_else(|| {
+            parts
+                .extensions
+                .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+                .map(|info| info.0.ip())
+        });
+    ExecutionContext {
+        caller_ip: caller_ip.map(|ip| ip.to_canonical().to_string()),
+        identity: parts.extensions.get::<AuthContext>().and_then(|auth| auth.user_id.clone()),
+        trace_id: parts
+            .extensions
+            .get::<crate::adapters::logging::RequestContext>()
+            .map(|request| request.trace_id.clone()),
+        session_id: parts
+            .headers
+            .get("mcp-session-id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        seed: parts
+            .headers
+            .get(SEED_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok()),
+        ..Default::default()
+    }
 }
 
 impl ServerHandler for MetisServer {
     fn get_info(&self) -> ServerInfo {
-        ServerInfo {
-            protocol_version: Default::default(),
-            capabilities: ServerCapabilities::builder()
-                .enable_resources()
-                .enable_tools()
-                .enable_prompts()
-                .build(),
-            server_info: Implementation {
-                name: "metis-mock-server".to_string(),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-                title: None,
-                website_url: None,
-                icons: None,
-            },
-            instructions: Some(
-                "Metis MCP Mock Server - A configurable mock server for MCP protocol testing"
-                    .to_string(),
-            ),
-        }
+        // get_info can't wait for the lock; while settings are being written,
+        // fall back to the defaults with every capability
+        let settings = self.settings.as_ref().and_then(|s| s.try_read().ok());
+        server_info(settings.as_deref())
     }
 
     fn ping(
@@ -249,26 +394,29 @@ impl ServerHandler for MetisServer {
 
     fn list_resources(
         &self,
-        _request: Option<PaginatedRequestParam>,
+        request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
         let handler = self.resource_handler.clone();
         async move {
-            let resources = handler
+            let mut resources = handler
                 .list_resources()
                 .await
                 .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+            let (resources, next_cursor) =
+                paginate(resources, request.as_ref(), self.page_size().await)?;
 
             let mcp_resources: Vec<Resource> = resources
                 .into_iter()
                 .map(|r| {
                     Resource::new(
                         RawResource {
-                            uri: r.uri.into(),
-                            name: r.name.into(),
+                            uri: r.uri,
+                            name: r.name,
                             title: None,
-                            description: r.description.map(Into::into),
-                            mime_type: r.mime_type.map(Into::into),
+                            description: r.description,
+                            mime_type: r.mime_type,
                             size: None,
                             icons: None,
                         },
@@ -279,7 +427,7 @@ impl ServerHandler for MetisServer {
 
             Ok(ListResourcesResult {
                 resources: mcp_resources,
-                next_cursor: None,
+                next_cursor,
             })
         }
     }
@@ -297,35 +445,54 @@ impl ServerHandler for MetisServer {
                 .await
                 .map_err(|e| McpError::resource_not_found(e.to_string(), None))?;
 
+            // Binary content is already base64-encoded by the resource handler
+            let contents = match ResourceContentKind::from_mime_type(result.mime_type.as_deref()) {
+                ResourceContentKind::Binary => ResourceContents::BlobResourceContents {
+                    uri: result.uri,
+                    mime_type: result.mime_type,
+                    blob: result.content,
+                    meta: None,
+                },
+                _ => ResourceContents::TextResourceContents {
+                    uri: result.uri,
+                    mime_type: result.mime_type.or_else(|| Some("text".to_string())),
+                    text: result.content,
+                    meta: None,
+                },
+            };
+
             Ok(ReadResourceResult {
-                contents: vec![ResourceContents::text(result.content, result.uri)],
+                contents: vec![contents],
             })
         }
     }
 
     fn list_resource_templates(
         &self,
-        _request: Option<PaginatedRequestParam>,
+        request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourceTemplatesResult, McpError>> + Send + '_
     {
         let handler = self.resource_handler.clone();
         async move {
-            let templates = handler
+            let mut templates = handler
                 .list_resource_templates()
                 .await
                 .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            templates.sort_by(|a, b| a.uri_template.cmp(&b.uri_template));
+            let (templates, next_cursor) =
+                paginate(templates, request.as_ref(), self.page_size().await)?;
 
             let mcp_templates: Vec<ResourceTemplate> = templates
                 .into_iter()
                 .map(|t| {
                     ResourceTemplate::new(
                         RawResourceTemplate {
-                            uri_template: t.uri_template.into(),
-                            name: t.name.into(),
+                            uri_template: t.uri_template,
+                            name: t.name,
                             title: None,
-                            description: t.description.map(Into::into),
-                            mime_type: t.mime_type.map(Into::into),
+                            description: t.description,
+                            mime_type: t.mime_type,
                         },
                         None,
                     )
@@ -334,14 +501,14 @@ impl ServerHandler for MetisServer {
 
             Ok(ListResourceTemplatesResult {
                 resource_templates: mcp_templates,
-                next_cursor: None,
+                next_cursor,
             })
         }
     }
 
     fn list_tools(
         &self,
-        _request: Option<PaginatedRequestParam>,
+        request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListToolsResult, McpError>> + Send + '_ {
         let handler = self.tool_handler.clone();
@@ -351,10 +518,12 @@ impl ServerHandler for MetisServer {
             // - Workflow tools
             // - Agent tools (with agent_ prefix)
             // - MCP tools from external servers
-            let tools = handler
+            let mut tools = handler
                 .list_tools()
                 .await
                 .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            tools.sort_by(|a, b| a.name.cmp(&b.name));
+            let (tools, next_cursor) = paginate(tools, request.as_ref(), self.page_size().await)?;
 
             let mcp_tools: Vec<Tool> = tools
                 .into_iter()
@@ -370,7 +539,7 @@ impl ServerHandler for MetisServer {
 
             Ok(ListToolsResult {
                 tools: mcp_tools,
-                next_cursor: None,
+                next_cursor,
             })
         }
     }
@@ -378,10 +547,17 @@ impl ServerHandler for MetisServer {
     fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
         let handler = self.tool_handler.clone();
-        async move {
+        let settings = self.settings.clone();
+        let parts = context.extensions.get::<axum::http::request::Parts>();
+        let execution = execution_context(parts);
+        // The session's task runs the call, outside the HTTP request's span
+        let span = parts
+            .and_then(|parts| parts.extensions.get::<crate::adapters::logging::RequestContext>())
+            .map_or_else(tracing::Span::none, |request| request.span());
+        let call = async move {
             let name = request.name.as_ref();
             let args = request
                 .arguments
@@ -396,7 +572,26 @@ impl ServerHandler for MetisServer {
             let result = handler
                 .execute_tool(name, args)
                 .await
-                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                .map_err(|e| match e.downcast_ref::<MockProtocolError>() {
+                    Some(mock) => McpError::new(ErrorCode(mock.code), mock.message.clone(), None),
+                    None => McpError::invalid_params(e.to_string(), None),
+                })?;
+
+            // `{ content: [...] }` envelopes become content items, so clients see
+            // their text and resource links rather than the envelope as JSON
+            if result.get("content").is_some_and(serde_json::Value::is_array) {
+                let has_links = !resource_links::resource_links(&result).is_empty();
+                if let (true, Some(settings)) = (has_links, &settings) {
+                    resource_links::check_links(&result, &*settings.read().await)
+                        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                }
+                match resource_links::envelope_content(&result) {
+                    Ok(content) => return Ok(CallToolResult::success(content)),
+                    Err(e) if has_links => return Err(McpError::internal_error(e.to_string(), None)),
+                    // Not MCP content after all; it is sent as JSON text below
+                    Err(_) => {}
+                }
+            }
 
             let text = if let Some(s) = result.as_str() {
                 s.to_string()
@@ -405,20 +600,24 @@ impl ServerHandler for MetisServer {
             };
 
             Ok(CallToolResult::success(vec![Content::text(text)]))
-        }
+        };
+        execution.scope(call).instrument(span)
     }
 
     fn list_prompts(
         &self,
-        _request: Option<PaginatedRequestParam>,
+        request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListPromptsResult, McpError>> + Send + '_ {
         let handler = self.prompt_handler.clone();
         async move {
-            let prompts = handler
+            let mut prompts = handler
                 .list_prompts()
                 .await
                 .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            prompts.sort_by(|a, b| a.name.cmp(&b.name));
+            let (prompts, next_cursor) =
+                paginate(prompts, request.as_ref(), self.page_size().await)?;
 
             let mcp_prompts: Vec<Prompt> = prompts
                 .into_iter()
@@ -426,9 +625,9 @@ impl ServerHandler for MetisServer {
                     let args: Option<Vec<PromptArgument>> = p.arguments.map(|args| {
                         args.into_iter()
                             .map(|a| PromptArgument {
-                                name: a.name.into(),
+                                name: a.name,
                                 title: None,
-                                description: a.description.map(Into::into),
+                                description: a.description,
                                 required: Some(a.required),
                             })
                             .collect()
@@ -439,7 +638,7 @@ impl ServerHandler for MetisServer {
 
             Ok(ListPromptsResult {
                 prompts: mcp_prompts,
-                next_cursor: None,
+                next_cursor,
             })
         }
     }
@@ -472,9 +671,48 @@ impl ServerHandler for MetisServer {
                 .collect();
 
             Ok(GetPromptResult {
-                description: result.description.map(Into::into),
+                description: result.description,
                 messages,
             })
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(cursor: Option<String>) -> PaginatedRequestParam {
+        PaginatedRequestParam { cursor }
+    }
+
+    #[test]
+    fn test_paginate_walks_pages_with_cursors() {
+        let items: Vec<u32> = (0..5).collect();
+
+        let (page, next) = paginate(items.clone(), None, 2).unwrap();
+        assert_eq!(page, vec![0, 1]);
+        let (page, next) = paginate(items.clone(), Some(&cursor(next)), 2).unwrap();
+        assert_eq!(page, vec![2, 3]);
+        let (page, next) = paginate(items, Some(&cursor(next)), 2).unwrap();
+        assert_eq!(page, vec![4]);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn test_paginate_without_page_size_returns_everything() {
+        let (page, next) = paginate(vec![1, 2, 3], None, 0).unwrap();
+        assert_eq!(page, vec![1, 2, 3]);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn test_paginate_rejects_invalid_cursor() {
+        let invalid = Some("not a cursor".to_string());
+        let err = paginate(vec![1, 2, 3], Some(&cursor(invalid)), 2).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+        let past_end = Some(encode_cursor(10));
+        assert!(paginate(vec![1, 2, 3], Some(&cursor(past_end)), 2).is_err());
+    }
+}
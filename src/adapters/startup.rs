@@ -0,0 +1,180 @@
+//! Summary of what the server loaded, logged once it is ready to listen
+//!
+//! The summary is a single log line whose fields count each kind of item and
+//! say which optional features are on, so operators can confirm the effective
+//! configuration at a glance. With `server.log_format = "json"` the fields are
+//! top-level keys of the line.
+
+use tracing::info;
+
+use crate::config::{McpCapability, Settings};
+
+/// Counts and features of a started server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartupSummary {
+    pub listen: String,
+    pub resources: usize,
+    pub resource_templates: usize,
+    pub tools: usize,
+    pub prompts: usize,
+    pub workflows: usize,
+    pub agents: usize,
+    pub orchestrations: usize,
+    pub schemas: usize,
+    pub data_lakes: usize,
+    pub mcp_servers: usize,
+    /// Auth mode, or `none` when auth is off
+    pub auth: String,
+    /// `<requests per second>/s burst <burst size>`, or `off`
+    pub rate_limit: String,
+    pub s3: bool,
+    pub database: bool,
+    /// Who can read `/metrics`: `public`, `token` or `auth`
+    pub metrics: &'static str,
+    /// Capabilities advertised to MCP clients, comma separated
+    pub capabilities: String,
+    /// Subsystems turned off in `[features]`, comma separated, or `none`
+    pub disabled_features: String,
+}
+
+impl StartupSummary {
+    /// Summary of `settings` for a server listening on `listen`, with
+    /// `database` telling whether database persistence connected
+    pub fn new(settings: &Settings, listen: &str, database: bool) -> Self {
+        let auth = if settings.auth.enabled {
+            format!("{:?}", settings.auth.mode)
+        } else {
+            "none".to_string()
+        };
+        let rate_limit = match &settings.rate_limit {
+            Some(limit) if limit.enabled => format!("{}/s burst {}", limit.requests_per_second, limit.burst_size),
+            _ => "off".to_string(),
+        };
+        let metrics = if settings.auth.public_metrics || !settings.auth.enabled {
+            "public"
+        } else if settings.auth.metrics_token.is_some() {
+            "token"
+        } else {
+            "auth"
+        };
+        let capabilities = settings
+            .mcp_capabilities()
+            .iter()
+            .map(|capability| match capability {
+                McpCapability::Tools => "tools",
+                McpCapability::Resources => "resources",
+                McpCapability::Prompts => "prompts",
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let features = &settings.features;
+        let disabled_features = [
+            ("admin_api", features.admin_api),
+            ("agents", features.agents),
+            ("data_lake", features.data_lake),
+            ("mcp_aggregation", features.mcp_aggregation),
+        ]
+        .iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(",");
+
+        Self {
+            listen: listen.to_string(),
+            resources: settings.resources.len(),
+            resource_templates: settings.resource_templates.len(),
+            tools: settings.tools.len(),
+            prompts: settings.prompts.len(),
+            workflows: settings.workflows.len(),
+            agents: settings.agents.len(),
+            orchestrations: settings.orchestrations.len(),
+            schemas: settings.schemas.len(),
+            data_lakes: settings.data_lakes.len(),
+            mcp_servers: settings.mcp_servers.len(),
+            auth,
+            rate_limit,
+            s3: settings.s3.as_ref().is_some_and(|s3| s3.enabled),
+            database,
+            metrics,
+            capabilities,
+            disabled_features: if disabled_features.is_empty() { "none".to_string() } else { disabled_features },
+        }
+    }
+
+    /// Log the summary as one line with a field per entry
+    pub fn log(&self) {
+        info!(
+            version = env!("CARGO_PKG_VERSION"),
+            listen = %self.listen,
+            resources = self.resources,
+            resource_templates = self.resource_templates,
+            tools = self.tools,
+            prompts = self.prompts,
+            workflows = self.workflows,
+            agents = self.agents,
+            orchestrations = self.orchestrations,
+            schemas = self.schemas,
+            data_lakes = self.data_lakes,
+            mcp_servers = self.mcp_servers,
+            auth = %self.auth,
+            rate_limit = %self.rate_limit,
+            s3 = self.s3,
+            database = self.database,
+            metrics = self.metrics,
+            capabilities = %self.capabilities,
+            disabled_features = %self.disabled_features,
+            "Startup summary"
+        );
+    }
+}
+
+/// ASCII banner with the version, printed before the logs start
+pub fn banner() -> String {
+    format!(
+        r"                _   _
+ _ __ ___   ___| |_(_)___
+| '_ ` _ \ / _ \ __| / __|
+| | | | | |  __/ |_| \__ \
+|_| |_| |_|\___|\__|_|___/  v{}
+",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_summary_counts_items_and_features() {
+        let settings: Settings = serde_json::from_value(json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "auth": {"enabled": true, "mode": "ApiKey", "api_keys": ["k"], "metrics_token": "m"},
+            "rate_limit": {"enabled": true, "requests_per_second": 10, "burst_size": 20},
+            "tools": [
+                {"name": "a", "description": "A", "input_schema": {"type": "object"}, "static_response": null},
+                {"name": "b", "description": "B", "input_schema": {"type": "object"}, "static_response": null}
+            ],
+            "prompts": [{"name": "p", "description": "P"}]
+        }))
+        .unwrap();
+
+        let summary = StartupSummary::new(&settings, "127.0.0.1:3000", false);
+        assert_eq!(summary.tools, 2);
+        assert_eq!(summary.prompts, 1);
+        assert_eq!(summary.resources, 0);
+        assert_eq!(summary.auth, "ApiKey");
+        assert_eq!(summary.rate_limit, "10/s burst 20");
+        assert_eq!(summary.metrics, "token");
+        assert!(!summary.s3);
+        assert_eq!(summary.capabilities, "tools,prompts");
+        assert_eq!(summary.disabled_features, "none");
+    }
+
+    #[test]
+    fn test_banner_shows_version() {
+        assert!(banner().contains(env!("CARGO_PKG_VERSION")));
+    }
+}
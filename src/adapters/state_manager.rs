@@ -1,20 +1,107 @@
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// Kind of change made by a `StateOperation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StateOperationKind {
+    Set,
+    Delete,
+    Increment,
+}
+
+/// One change in a batch applied by `StateManager::apply_batch`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StateOperation {
+    pub key: String,
+    pub operation: StateOperationKind,
+    /// New value for `set`, or the integer step for `increment` (default 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+/// How `StateManager::import` combines imported entries with the current state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StateImportMode {
+    /// Drop the current state first
+    #[default]
+    Replace,
+    /// Keep current keys, overwriting those also in the import
+    Merge,
+}
+
+/// Prefix of the state keys holding the ids handed out by `StateManager::stable_id`
+pub const ID_KEY_PREFIX: &str = "__id_";
+
+/// How long a session keeps its session-scoped state without using it
+pub const SESSION_STATE_IDLE: Duration = Duration::from_secs(60 * 60);
+
+/// Prefix of the state keys a session-scoped stateful mock keeps for `session`
+pub fn session_key_prefix(session: &str) -> String {
+    format!("__session_{}:", session)
+}
+
 #[derive(Clone)]
 pub struct StateManager {
     state: Arc<RwLock<HashMap<String, Value>>>,
+    /// Sessions with session-scoped state, by when they last used it
+    sessions: Arc<Mutex<HashMap<String, Instant>>>,
 }
 
 impl StateManager {
     pub fn new() -> Self {
         Self {
             state: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Record that `session` used its state, dropping the state of sessions
+    /// idle for longer than `SESSION_STATE_IDLE`
+    pub async fn touch_session(&self, session: &str) {
+        self.touch_session_at(session, Instant::now()).await;
+    }
+
+    /// `touch_session` at a given instant
+    pub async fn touch_session_at(&self, session: &str, now: Instant) {
+        let expired: Vec<String> = {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions.insert(session.to_string(), now);
+            let expired: Vec<String> = sessions
+                .iter()
+                .filter(|(_, seen)| now.saturating_duration_since(**seen) > SESSION_STATE_IDLE)
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in &expired {
+                sessions.remove(id);
+            }
+            expired
+        };
+        if !expired.is_empty() {
+            let prefixes: Vec<String> = expired.iter().map(|id| session_key_prefix(id)).collect();
+            let mut state = self.state.write().await;
+            state.retain(|key, _| !prefixes.iter().any(|prefix| key.starts_with(prefix)));
+        }
+    }
+
+    /// Drop the state `session` keeps, once the session has ended
+    pub async fn end_session(&self, session: &str) {
+        self.sessions.lock().unwrap().remove(session);
+        let prefix = session_key_prefix(session);
+        let mut state = self.state.write().await;
+        state.retain(|key, _| !key.starts_with(&prefix));
+    }
+
     pub async fn get(&self, key: &str) -> Option<Value> {
         let state = self.state.read().await;
         state.get(key).cloned()
@@ -36,6 +123,19 @@ impl StateManager {
         new_value
     }
 
+    /// The id stored for `key`, generating and storing a UUID the first time the
+    /// key is asked for, so mocks of related entities agree on the same id
+    pub async fn stable_id(&self, key: &str) -> String {
+        let mut state = self.state.write().await;
+        let id = state
+            .entry(format!("{}{}", ID_KEY_PREFIX, key))
+            .or_insert_with(|| Value::String(uuid::Uuid::new_v4().to_string()));
+        match id {
+            Value::String(id) => id.clone(),
+            other => other.to_string(),
+        }
+    }
+
     pub async fn reset(&self, key: &str) {
         let mut state = self.state.write().await;
         state.remove(key);
@@ -52,6 +152,108 @@ impl StateManager {
         state.clone()
     }
 
+    /// Get the state entries whose key starts with `prefix`
+    pub async fn get_by_prefix(&self, prefix: &str) -> HashMap<String, Value> {
+        let state = self.state.read().await;
+        state
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Apply all operations under a single lock, or none of them if any is
+    /// invalid or an increment would overflow. Returns the number of
+    /// operations applied, or one error per failing operation.
+    pub async fn apply_batch(&self, operations: &[StateOperation]) -> Result<usize, Vec<String>> {
+        let errors: Vec<String> = operations
+            .iter()
+            .enumerate()
+            .filter_map(|(i, op)| Self::validate(op).err().map(|e| format!("operations[{}]: {}", i, e)))
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut state = self.state.write().await;
+        let sums = Self::increment_sums(&state, operations)?;
+        for (op, sum) in operations.iter().zip(sums) {
+            match op.operation {
+                StateOperationKind::Set => {
+                    state.insert(op.key.clone(), op.value.clone().unwrap_or(Value::Null));
+                }
+                StateOperationKind::Delete => {
+                    state.remove(&op.key);
+                }
+                StateOperationKind::Increment => {
+                    state.insert(op.key.clone(), Value::Number(sum.unwrap_or_default().into()));
+                }
+            }
+        }
+        Ok(operations.len())
+    }
+
+    /// The value each increment of `operations` leaves its key at, given the
+    /// operations before it, or one error per increment that overflows. Checked
+    /// before anything is applied so an overflow leaves the state untouched.
+    fn increment_sums(
+        state: &HashMap<String, Value>,
+        operations: &[StateOperation],
+    ) -> Result<Vec<Option<i64>>, Vec<String>> {
+        let mut counters: HashMap<&str, Option<i64>> = HashMap::new();
+        let mut sums = Vec::with_capacity(operations.len());
+        let mut errors = Vec::new();
+        for (i, op) in operations.iter().enumerate() {
+            let (value, sum) = match op.operation {
+                StateOperationKind::Set => (op.value.as_ref().and_then(Value::as_i64), None),
+                StateOperationKind::Delete => (None, None),
+                StateOperationKind::Increment => {
+                    let step = op.value.as_ref().and_then(Value::as_i64).unwrap_or(1);
+                    let current = counters
+                        .get(op.key.as_str())
+                        .copied()
+                        .unwrap_or_else(|| state.get(&op.key).and_then(Value::as_i64))
+                        .unwrap_or(0);
+                    let sum = current.checked_add(step);
+                    if sum.is_none() {
+                        errors.push(format!("operations[{}]: incrementing '{}' by {} overflows", i, op.key, step));
+                    }
+                    (sum, sum)
+                }
+            };
+            sums.push(sum);
+            counters.insert(&op.key, value);
+        }
+        if errors.is_empty() {
+            Ok(sums)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate(op: &StateOperation) -> Result<(), String> {
+        if op.key.is_empty() {
+            return Err("key must not be empty".to_string());
+        }
+        match (op.operation, &op.value) {
+            (StateOperationKind::Set, None) => Err(format!("set '{}' requires a value", op.key)),
+            (StateOperationKind::Increment, Some(step)) if step.as_i64().is_none() => {
+                Err(format!("increment '{}' requires an integer value", op.key))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Load `entries` under a single lock. Returns the number of keys afterwards.
+    pub async fn import(&self, entries: HashMap<String, Value>, mode: StateImportMode) -> usize {
+        let mut state = self.state.write().await;
+        if mode == StateImportMode::Replace {
+            state.clear();
+        }
+        state.extend(entries);
+        state.len()
+    }
+
     /// Clear all state (alias for reset_all)
     pub async fn clear(&self) {
         self.reset_all().await;
@@ -63,8 +265,137 @@ impl StateManager {
     }
 }
 
+/// Drop a session's state when its client ends it with `DELETE /mcp`
+pub async fn end_session_middleware(
+    State(state_manager): State<Arc<StateManager>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ended = (request.method() == Method::DELETE)
+        .then(|| request.headers().get("mcp-session-id"))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if let Some(session) = ended {
+        state_manager.end_session(&session).await;
+    }
+    next.run(request).await
+}
+
 impl Default for StateManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn op(key: &str, operation: StateOperationKind, value: Option<Value>) -> StateOperation {
+        StateOperation { key: key.to_string(), operation, value }
+    }
+
+    #[tokio::test]
+    async fn test_apply_batch() {
+        let manager = StateManager::new();
+        manager.set("user_stale".to_string(), json!(true)).await;
+
+        let applied = manager.apply_batch(&[
+            op("user_count", StateOperationKind::Set, Some(json!(5))),
+            op("user_count", StateOperationKind::Increment, Some(json!(2))),
+            op("visits", StateOperationKind::Increment, None),
+            op("user_stale", StateOperationKind::Delete, None),
+        ]).await.unwrap();
+
+        assert_eq!(applied, 4);
+        assert_eq!(manager.get_by_prefix("user_").await, HashMap::from([("user_count".to_string(), json!(7))]));
+        assert_eq!(manager.get("visits").await, Some(json!(1)));
+    }
+
+    #[tokio::test]
+    async fn test_import_replaces_or_merges() {
+        let manager = StateManager::new();
+        manager.set("a".to_string(), json!(1)).await;
+        manager.set("b".to_string(), json!(2)).await;
+
+        let merged = manager
+            .import(HashMap::from([("b".to_string(), json!(3))]), StateImportMode::Merge)
+            .await;
+        assert_eq!(merged, 2);
+        assert_eq!(manager.get("a").await, Some(json!(1)));
+        assert_eq!(manager.get("b").await, Some(json!(3)));
+
+        let replaced = manager
+            .import(HashMap::from([("c".to_string(), json!(4))]), StateImportMode::Replace)
+            .await;
+        assert_eq!(replaced, 1);
+        assert_eq!(manager.get_all().await, HashMap::from([("c".to_string(), json!(4))]));
+    }
+
+    #[tokio::test]
+    async fn test_apply_batch_is_all_or_nothing() {
+        let manager = StateManager::new();
+
+        let errors = manager.apply_batch(&[
+            op("a", StateOperationKind::Set, Some(json!(1))),
+            op("b", StateOperationKind::Set, None),
+            op("c", StateOperationKind::Increment, Some(json!("x"))),
+        ]).await.unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("operations[1]"));
+        assert!(manager.get_all().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_batch_rejects_overflowing_increment() {
+        let manager = StateManager::new();
+        manager.set("n".to_string(), json!(i64::MAX - 1)).await;
+
+        let errors = manager.apply_batch(&[
+            op("m", StateOperationKind::Set, Some(json!(1))),
+            op("n", StateOperationKind::Increment, None),
+            op("n", StateOperationKind::Increment, None),
+        ]).await.unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("operations[2]"));
+        assert_eq!(manager.get("n").await, Some(json!(i64::MAX - 1)));
+        assert_eq!(manager.get("m").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_stable_id() {
+        let manager = StateManager::new();
+
+        let user = manager.stable_id("user").await;
+        assert!(uuid::Uuid::parse_str(&user).is_ok());
+        assert_eq!(manager.stable_id("user").await, user);
+        assert_ne!(manager.stable_id("order").await, user);
+        assert_eq!(manager.get("__id_user").await, Some(json!(user)));
+
+        manager.set("__id_seeded".to_string(), json!(42)).await;
+        assert_eq!(manager.stable_id("seeded").await, "42");
+    }
+
+    #[tokio::test]
+    async fn test_session_state_is_dropped_when_idle_or_ended() {
+        let manager = StateManager::new();
+        let start = Instant::now();
+        manager.touch_session_at("a", start).await;
+        manager.set("__session_a:cart".to_string(), json!(1)).await;
+        manager.set("cart".to_string(), json!(0)).await;
+
+        let later = start + SESSION_STATE_IDLE + Duration::from_secs(1);
+        manager.touch_session_at("b", later).await;
+        manager.set("__session_b:cart".to_string(), json!(2)).await;
+        assert_eq!(manager.get("__session_a:cart").await, None);
+        assert_eq!(manager.get("__session_b:cart").await, Some(json!(2)));
+
+        manager.end_session("b").await;
+        assert_eq!(manager.get("__session_b:cart").await, None);
+        assert_eq!(manager.get("cart").await, Some(json!(0)));
+    }
+}
@@ -0,0 +1,239 @@
+//! Catalog of the mock strategies, served by `GET /api/strategies`
+//!
+//! Describes every [`MockStrategyType`] along with a JSON Schema of the
+//! `MockConfig` fields it reads, so the UI can offer a strategy picker and
+//! render the matching config form.
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::config::{
+    CallbackConfig, CsvConfig, DataLakeCrudConfig, DatabaseConfig, FakerSchemaConfig, FileConfig, LLMConfig, MatrixConfig,
+    MockStrategyType, ScriptLang, StatefulConfig,
+};
+
+/// Every strategy, in the order the catalog lists them
+pub const ALL_STRATEGIES: [MockStrategyType; 13] = [
+    MockStrategyType::Static,
+    MockStrategyType::Template,
+    MockStrategyType::Random,
+    MockStrategyType::Pattern,
+    MockStrategyType::Stateful,
+    MockStrategyType::Matrix,
+    MockStrategyType::File,
+    MockStrategyType::Csv,
+    MockStrategyType::Script,
+    MockStrategyType::Callback,
+    MockStrategyType::DataLakeCrud,
+    MockStrategyType::Database,
+    MockStrategyType::LLM,
+];
+
+/// How much setup a strategy needs before it returns something useful
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Complexity {
+    Low,
+    Medium,
+    High,
+}
+
+/// Description of a mock strategy
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyInfo {
+    /// Value of `mock.strategy` that selects this strategy
+    pub name: String,
+    pub description: String,
+    pub use_cases: Vec<String>,
+    /// JSON Schema of the `MockConfig` fields this strategy reads
+    pub config_schema: Value,
+    pub min_complexity: Complexity,
+    /// Whether the strategy talks to a service outside Metis (a database or an LLM provider)
+    pub requires_external: bool,
+}
+
+/// Describe every strategy
+pub fn strategy_catalog() -> Vec<StrategyInfo> {
+    let mut generator = SchemaSettings::draft07()
+        .with(|s| s.inline_subschemas = true)
+        .into_generator();
+    ALL_STRATEGIES.iter().map(|s| strategy_info(s, &mut generator)).collect()
+}
+
+/// Description, use cases, minimum complexity and config fields of a strategy
+type Details = (&'static str, &'static [&'static str], Complexity, Vec<ConfigField>);
+
+fn strategy_info(strategy: &MockStrategyType, generator: &mut SchemaGenerator) -> StrategyInfo {
+    let (description, use_cases, min_complexity, fields): Details = match strategy {
+        MockStrategyType::Static => (
+            "Returns the item's static response unchanged",
+            &["Fixed responses", "Contract examples"],
+            Complexity::Low,
+            vec![],
+        ),
+        MockStrategyType::Template => (
+            "Renders a Tera template with the call arguments as variables",
+            &["Echoing arguments back", "Responses shaped by the input"],
+            Complexity::Low,
+            vec![ConfigField::required::<String>("template", generator)],
+        ),
+        MockStrategyType::Random => (
+            "Generates fake data, either a single faker type or a value per schema field",
+            &["Realistic sample data", "Filling an output schema"],
+            Complexity::Low,
+            vec![
+                ConfigField::optional::<String>("faker_type", generator),
+                ConfigField::optional::<FakerSchemaConfig>("faker_schema", generator),
+            ],
+        ),
+        MockStrategyType::Pattern => (
+            "Expands a pattern such as `ORD-\\d\\d\\d\\d` into a random string",
+            &["IDs and codes", "Formatted random strings"],
+            Complexity::Low,
+            vec![ConfigField::required::<String>("pattern", generator)],
+        ),
+        MockStrategyType::Stateful => (
+            "Reads, sets or increments a value kept between calls",
+            &["Counters", "Values that persist across calls"],
+            Complexity::Medium,
+            vec![ConfigField::required::<StatefulConfig>("stateful", generator)],
+        ),
+        MockStrategyType::Matrix => (
+            "Returns one of several canned responses, in turn, by matching arguments, or at random",
+            &["Input-dependent canned responses", "Cycling through outcomes"],
+            Complexity::Medium,
+            vec![ConfigField::required::<MatrixConfig>("matrix", generator)],
+        ),
+        MockStrategyType::File => (
+            "Picks entries from a JSON file",
+            &["Replaying recorded fixtures", "Large sample datasets"],
+            Complexity::Medium,
+            vec![ConfigField::required::<FileConfig>("file", generator)],
+        ),
+        MockStrategyType::Csv => (
+            "Returns a row of a CSV file as an object keyed by its column headers",
+            &["Spreadsheet-maintained test data", "Looking up records by key"],
+            Complexity::Medium,
+            vec![ConfigField::required::<CsvConfig>("csv", generator)],
+        ),
+        MockStrategyType::Script => (
+            "Runs a Rhai, Lua, JavaScript or Python script with the call arguments",
+            &["Custom logic", "Computed responses"],
+            Complexity::High,
+            vec![
+                ConfigField::required::<String>("script", generator),
+                ConfigField::optional::<ScriptLang>("script_lang", generator),
+            ],
+        ),
+        MockStrategyType::Callback => (
+            "Answers right away and POSTs a payload to a URL after a delay",
+            &["Webhooks", "Asynchronous job completion"],
+            Complexity::Medium,
+            vec![ConfigField::required::<CallbackConfig>("callback", generator)],
+        ),
+        MockStrategyType::DataLakeCrud => (
+            "Creates, reads and filters records in a data lake",
+            &["CRUD APIs backed by sample data", "Stateful record stores"],
+            Complexity::High,
+            vec![ConfigField::required::<DataLakeCrudConfig>("data_lake_crud", generator)],
+        ),
+        MockStrategyType::Database => (
+            "Runs a SQL query with the call arguments bound as parameters",
+            &["Responses from an existing database", "Querying data lakes with DataFusion"],
+            Complexity::High,
+            vec![ConfigField::required::<DatabaseConfig>("database", generator)],
+        ),
+        MockStrategyType::LLM => (
+            "Asks an LLM provider to generate the response",
+            &["Free-form text", "Plausible responses without writing them"],
+            Complexity::Medium,
+            vec![ConfigField::required::<LLMConfig>("llm", generator)],
+        ),
+    };
+
+    StrategyInfo {
+        name: serde_json::to_value(strategy)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default(),
+        description: description.to_string(),
+        use_cases: use_cases.iter().map(|u| u.to_string()).collect(),
+        config_schema: object_schema(fields),
+        min_complexity,
+        requires_external: matches!(strategy, MockStrategyType::Database | MockStrategyType::LLM),
+    }
+}
+
+/// A `MockConfig` field read by a strategy
+struct ConfigField {
+    name: &'static str,
+    schema: Value,
+    required: bool,
+}
+
+impl ConfigField {
+    fn required<T: JsonSchema>(name: &'static str, generator: &mut SchemaGenerator) -> Self {
+        Self::new::<T>(name, generator, true)
+    }
+
+    fn optional<T: JsonSchema>(name: &'static str, generator: &mut SchemaGenerator) -> Self {
+        Self::new::<T>(name, generator, false)
+    }
+
+    fn new<T: JsonSchema>(name: &'static str, generator: &mut SchemaGenerator, required: bool) -> Self {
+        let schema = serde_json::to_value(generator.subschema_for::<T>()).unwrap_or_default();
+        Self { name, schema, required }
+    }
+}
+
+fn object_schema(fields: Vec<ConfigField>) -> Value {
+    let required: Vec<&str> = fields.iter().filter(|f| f.required).map(|f| f.name).collect();
+    let properties: Map<String, Value> = fields.into_iter().map(|f| (f.name.to_string(), f.schema)).collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_lists_every_strategy() {
+        let catalog = strategy_catalog();
+        assert_eq!(catalog.len(), ALL_STRATEGIES.len());
+
+        // Names round-trip into the strategy type
+        for info in &catalog {
+            let parsed: MockStrategyType = serde_json::from_value(json!(info.name)).unwrap();
+            assert_eq!(serde_json::to_value(parsed).unwrap(), json!(info.name));
+            assert!(!info.description.is_empty());
+            assert!(!info.use_cases.is_empty());
+        }
+
+        let external: Vec<&str> = catalog.iter().filter(|i| i.requires_external).map(|i| i.name.as_str()).collect();
+        assert_eq!(external, ["database", "llm"]);
+    }
+
+    #[test]
+    fn test_config_schema_inlines_strategy_config() {
+        let catalog = strategy_catalog();
+        let llm = catalog.iter().find(|i| i.name == "llm").unwrap();
+        assert_eq!(llm.config_schema["required"], json!(["llm"]));
+        let llm_config = &llm.config_schema["properties"]["llm"];
+        assert!(llm_config.get("$ref").is_none());
+        assert_eq!(llm_config["properties"]["model"]["type"], "string");
+
+        let random = catalog.iter().find(|i| i.name == "random").unwrap();
+        assert_eq!(random.config_schema["required"], json!([]));
+        assert!(random.config_schema["properties"]["faker_schema"].is_object());
+
+        let static_info = catalog.iter().find(|i| i.name == "static").unwrap();
+        assert_eq!(static_info.config_schema["properties"], json!({}));
+        assert_eq!(static_info.min_complexity, Complexity::Low);
+    }
+}
@@ -0,0 +1,82 @@
+//! Recent mock strategy failures
+//!
+//! Intermittent failures in a long test run are easy to miss in the logs. The
+//! log keeps the last failures of tool mock strategies for `GET /api/errors`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Failures kept for inspection; older ones are dropped first
+const MAX_RECORDS: usize = 200;
+
+/// A failed strategy execution
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyErrorRecord {
+    pub tool: String,
+    pub strategy: String,
+    pub args: Value,
+    pub error: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Bounded log of recent strategy failures
+#[derive(Default)]
+pub struct StrategyErrorLog {
+    records: Mutex<VecDeque<StrategyErrorRecord>>,
+}
+
+impl StrategyErrorLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a failure of `tool`'s `strategy` on `args`
+    pub fn record(&self, tool: &str, strategy: &str, args: &Value, error: &anyhow::Error) {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        if records.len() == MAX_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(StrategyErrorRecord {
+            tool: tool.to_string(),
+            strategy: strategy.to_string(),
+            args: args.clone(),
+            error: error.to_string(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Recorded failures, newest first
+    pub fn list(&self) -> Vec<StrategyErrorRecord> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.iter().rev().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keeps_the_latest_failures() {
+        let log = StrategyErrorLog::new();
+        for i in 0..MAX_RECORDS + 5 {
+            log.record("search", "script", &json!({"i": i}), &anyhow::anyhow!("failure {}", i));
+        }
+
+        let records = log.list();
+        assert_eq!(records.len(), MAX_RECORDS);
+        assert_eq!(records[0].error, format!("failure {}", MAX_RECORDS + 4));
+        assert_eq!(records.last().unwrap().args, json!({"i": 5}));
+
+        log.clear();
+        assert!(log.list().is_empty());
+    }
+}
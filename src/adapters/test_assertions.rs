@@ -0,0 +1,243 @@
+//! Assertions checked against the output of a test call
+//!
+//! `POST /api/tools/:name/test` accepts `assertions`, each comparing the values
+//! at a JSONPath-style path (see [`json_path`]) with an expected value, so CI
+//! scripts can check a tool's contract without inspecting the output.
+
+use crate::adapters::json_path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Comparison made by an [`Assertion`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// A string containing the expected substring, an array containing the
+    /// expected element, or an object containing the expected key
+    Contains,
+    /// The path matches something (the value is ignored)
+    Exists,
+    /// The path matches nothing (the value is ignored)
+    NotExists,
+    /// The JSON type: `null`, `boolean`, `number`, `string`, `array` or `object`
+    Type,
+    /// The length of a string, array or object
+    Length,
+}
+
+impl AssertionOp {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "eq",
+            Self::Ne => "ne",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::Contains => "contains",
+            Self::Exists => "exists",
+            Self::NotExists => "not_exists",
+            Self::Type => "type",
+            Self::Length => "length",
+        }
+    }
+}
+
+/// A check on the values at `path`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Assertion {
+    pub path: String,
+    pub op: AssertionOp,
+    #[serde(default)]
+    pub value: Value,
+}
+
+/// Outcome of one assertion
+#[derive(Debug, Serialize)]
+pub struct AssertionResult {
+    pub path: String,
+    pub op: AssertionOp,
+    pub expected: Value,
+    /// The matched value, or an array of them when the path has a `[*]`
+    pub actual: Value,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Outcome of all assertions of a test call
+#[derive(Debug, Serialize)]
+pub struct AssertionReport {
+    /// Whether every assertion passed
+    pub passed: bool,
+    pub results: Vec<AssertionResult>,
+}
+
+impl AssertionReport {
+    /// Check `assertions` against `output`, or fail them all when the call
+    /// produced no output. `None` when there is nothing to check.
+    pub fn evaluate(assertions: &[Assertion], output: Option<&Value>) -> Option<Self> {
+        if assertions.is_empty() {
+            return None;
+        }
+        let results: Vec<AssertionResult> = assertions
+            .iter()
+            .map(|assertion| match output {
+                Some(output) => check(assertion, output),
+                None => failed(assertion, Value::Null, "the call failed"),
+            })
+            .collect();
+        Some(Self {
+            passed: results.iter().all(|result| result.passed),
+            results,
+        })
+    }
+}
+
+fn check(assertion: &Assertion, output: &Value) -> AssertionResult {
+    let segments = match json_path::parse(&assertion.path) {
+        Ok(segments) => segments,
+        Err(e) => return failed(assertion, Value::Null, &e.to_string()),
+    };
+    let wildcard = segments.contains(&json_path::Segment::All);
+    let matches = json_path::select(output, &segments);
+    let actual = match (wildcard, matches.first()) {
+        (true, _) => Value::Array(matches.iter().map(|value| (*value).clone()).collect()),
+        (false, Some(value)) => (*value).clone(),
+        (false, None) => Value::Null,
+    };
+
+    let outcome = match assertion.op {
+        AssertionOp::NotExists if !matches.is_empty() => Err("a value exists at path".to_string()),
+        AssertionOp::NotExists => Ok(()),
+        _ if matches.is_empty() => Err("no value at path".to_string()),
+        AssertionOp::Exists => Ok(()),
+        op => matches
+            .iter()
+            .try_for_each(|value| compare(op, value, &assertion.value)),
+    };
+    match outcome {
+        Ok(()) => AssertionResult {
+            path: assertion.path.clone(),
+            op: assertion.op,
+            expected: assertion.value.clone(),
+            actual,
+            passed: true,
+            message: None,
+        },
+        Err(message) => failed(assertion, actual, &message),
+    }
+}
+
+/// Compare one matched value with the expected one
+fn compare(op: AssertionOp, actual: &Value, expected: &Value) -> Result<(), String> {
+    let passed = match op {
+        AssertionOp::Eq => actual == expected,
+        AssertionOp::Ne => actual != expected,
+        AssertionOp::Gt | AssertionOp::Gte | AssertionOp::Lt | AssertionOp::Lte => {
+            let (Some(actual), Some(expected)) = (actual.as_f64(), expected.as_f64()) else {
+                return Err(format!("{} and {} are not both numbers", actual, expected));
+            };
+            match op {
+                AssertionOp::Gt => actual > expected,
+                AssertionOp::Gte => actual >= expected,
+                AssertionOp::Lt => actual < expected,
+                _ => actual <= expected,
+            }
+        }
+        AssertionOp::Contains => match (actual, expected) {
+            (Value::String(text), Value::String(part)) => text.contains(part.as_str()),
+            (Value::Array(items), expected) => items.contains(expected),
+            (Value::Object(map), Value::String(key)) => map.contains_key(key),
+            _ => return Err(format!("{} cannot contain {}", actual, expected)),
+        },
+        AssertionOp::Type => Some(type_name(actual)) == expected.as_str(),
+        AssertionOp::Length => {
+            let length = match actual {
+                Value::String(text) => text.chars().count(),
+                Value::Array(items) => items.len(),
+                Value::Object(map) => map.len(),
+                _ => return Err(format!("{} has no length", actual)),
+            };
+            Some(length as u64) == expected.as_u64()
+        }
+        AssertionOp::Exists | AssertionOp::NotExists => true,
+    };
+    if passed {
+        Ok(())
+    } else {
+        Err(format!("{} does not satisfy {} {}", actual, op.as_str(), expected))
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn failed(assertion: &Assertion, actual: Value, message: &str) -> AssertionResult {
+    AssertionResult {
+        path: assertion.path.clone(),
+        op: assertion.op,
+        expected: assertion.value.clone(),
+        actual,
+        passed: false,
+        message: Some(message.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn assertions(value: Value) -> Vec<Assertion> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_assertions_against_output() {
+        let output = json!({"status": "ok", "items": [{"price": 5}, {"price": 12}], "tags": ["a"]});
+        let report = AssertionReport::evaluate(
+            &assertions(json!([
+                {"path": "$.status", "op": "eq", "value": "ok"},
+                {"path": "$.items[*].price", "op": "gt", "value": 1},
+                {"path": "$.items", "op": "length", "value": 2},
+                {"path": "$.tags", "op": "contains", "value": "a"},
+                {"path": "$.error", "op": "not_exists"},
+                {"path": "$.items[*].price", "op": "lt", "value": 10},
+                {"path": "$.missing", "op": "eq", "value": null}
+            ])),
+            Some(&output),
+        )
+        .unwrap();
+
+        let passed: Vec<bool> = report.results.iter().map(|result| result.passed).collect();
+        assert_eq!(passed, vec![true, true, true, true, true, false, false]);
+        assert!(!report.passed);
+        assert_eq!(report.results[5].actual, json!([5, 12]));
+        assert_eq!(report.results[6].message.as_deref(), Some("no value at path"));
+    }
+
+    #[test]
+    fn test_assertions_fail_without_output() {
+        assert!(AssertionReport::evaluate(&[], None).is_none());
+
+        let report =
+            AssertionReport::evaluate(&assertions(json!([{"path": "$", "op": "exists"}])), None).unwrap();
+        assert!(!report.passed);
+        assert_eq!(report.results[0].message.as_deref(), Some("the call failed"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Server-wide limit on concurrent tool calls
+//!
+//! With `server.max_concurrent_tool_calls` set, each tool call holds a slot
+//! while it runs. Calls over the limit wait for one, up to
+//! `server.tool_call_queue_timeout_ms`, which keeps expensive strategies (LLM,
+//! HTTP, database) from exhausting connections under load. The limit is read
+//! on every call, so a config reload applies it to new calls. Tool calls
+//! nested in a workflow or agent run on the slot of the call that started
+//! them.
+
+use crate::adapters::metrics_handler::MetricsCollector;
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Time a call waits for a slot when `tool_call_queue_timeout_ms` is unset
+pub const DEFAULT_QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Slots for running tool calls, sized by the current limit
+#[derive(Default)]
+pub struct ToolCallLimiter {
+    slots: Mutex<Option<(usize, Arc<Semaphore>)>>,
+}
+
+/// A running call's slot, given back when dropped
+pub struct ToolCallPermit {
+    _permit: OwnedSemaphorePermit,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl Drop for ToolCallPermit {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.tool_calls_in_flight.dec();
+        }
+    }
+}
+
+/// Counts a call as queued for as long as it waits, even if the wait is cancelled
+struct Queued<'a>(Option<&'a MetricsCollector>);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        if let Some(metrics) = self.0 {
+            metrics.tool_calls_queued.dec();
+        }
+    }
+}
+
+impl ToolCallLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait up to `timeout` for a slot under `limit`. `None` when there is no
+    /// limit; an error when no slot freed up in time.
+    pub async fn acquire(
+        &self,
+        limit: Option<usize>,
+        timeout: Duration,
+        metrics: Option<&Arc<MetricsCollector>>,
+    ) -> Result<Option<ToolCallPermit>> {
+        let Some(limit) = limit else {
+            return Ok(None);
+        };
+        let semaphore = self.semaphore(limit);
+
+        let permit = {
+            if let Some(metrics) = metrics {
+                metrics.tool_calls_queued.inc();
+            }
+            let _queued = Queued(metrics.map(Arc::as_ref));
+            tokio::time::timeout(timeout, semaphore.acquire_owned()).await
+        };
+        let permit = match permit {
+            Ok(permit) => permit?,
+            Err(_) => {
+                if let Some(metrics) = metrics {
+                    metrics.tool_call_queue_timeouts.inc();
+                }
+                anyhow::bail!(
+                    "Too many concurrent tool calls: no slot of {} freed up within {} ms",
+                    limit,
+                    timeout.as_millis()
+                );
+            }
+        };
+
+        if let Some(metrics) = metrics {
+            metrics.tool_calls_in_flight.inc();
+        }
+        Ok(Some(ToolCallPermit {
+            _permit: permit,
+            metrics: metrics.cloned(),
+        }))
+    }
+
+    /// The semaphore for `limit`, replaced when the limit changes. Calls
+    /// holding slots of the old one finish normally.
+    fn semaphore(&self, limit: usize) -> Arc<Semaphore> {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        match slots.as_ref() {
+            Some((current, semaphore)) if *current == limit => semaphore.clone(),
+            _ => {
+                let semaphore = Arc::new(Semaphore::new(limit));
+                *slots = Some((limit, semaphore.clone()));
+                semaphore
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_calls_over_the_limit_wait_then_time_out() {
+        let limiter = ToolCallLimiter::new();
+        let timeout = Duration::from_millis(20);
+
+        assert!(limiter.acquire(None, timeout, None).await.unwrap().is_none());
+
+        let first = limiter.acquire(Some(1), timeout, None).await.unwrap();
+        assert!(first.is_some());
+        let err = limiter.acquire(Some(1), timeout, None).await.err().unwrap();
+        assert!(err.to_string().starts_with("Too many concurrent tool calls"));
+
+        drop(first);
+        assert!(limiter.acquire(Some(1), timeout, None).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_gauges_track_running_and_waiting_calls() {
+        let limiter = ToolCallLimiter::new();
+        let metrics = Arc::new(MetricsCollector::new().unwrap());
+        let timeout = Duration::from_millis(20);
+
+        let permit = limiter.acquire(Some(1), timeout, Some(&metrics)).await.unwrap();
+        assert_eq!(metrics.tool_calls_in_flight.get(), 1.0);
+        assert!(limiter.acquire(Some(1), timeout, Some(&metrics)).await.is_err());
+        assert_eq!(metrics.tool_calls_queued.get(), 0.0);
+        assert_eq!(metrics.tool_call_queue_timeouts.get(), 1.0);
+
+        drop(permit);
+        assert_eq!(metrics.tool_calls_in_flight.get(), 0.0);
+    }
+}
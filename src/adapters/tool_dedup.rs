@@ -0,0 +1,88 @@
+//! Deduplication of repeated tool calls
+//!
+//! Clients may retry a tool call after a network hiccup. For tools with a
+//! `dedup_window_ms`, an identical call (same tool, same arguments) within the
+//! window returns the first call's result instead of generating a new one.
+
+use dashmap::DashMap;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+/// Recent tool results keyed by a hash of the tool name and arguments, each
+/// with the time its tool's window ends
+#[derive(Default)]
+pub struct ToolCallDedup {
+    entries: DashMap<String, (Instant, Value)>,
+}
+
+impl ToolCallDedup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// SHA-256 of the tool name and its serialized arguments
+    pub fn key(tool: &str, args: &Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(tool.as_bytes());
+        hasher.update([0]);
+        hasher.update(args.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// The result recorded for `key`, unless its window has ended
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let now = Instant::now();
+        self.entries.remove_if(key, |_, (expires_at, _)| *expires_at <= now);
+        self.entries.get(key).map(|entry| entry.1.clone())
+    }
+
+    /// Record the result of a call for `window`, dropping entries whose own
+    /// window has ended
+    pub fn insert(&self, key: String, value: Value, window: Duration) {
+        let now = Instant::now();
+        self.entries.retain(|_, (expires_at, _)| *expires_at > now);
+        self.entries.insert(key, (now + window, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_depends_on_tool_and_args() {
+        let args = json!({"query": "rust"});
+        assert_eq!(ToolCallDedup::key("search", &args), ToolCallDedup::key("search", &args));
+        assert_ne!(ToolCallDedup::key("search", &args), ToolCallDedup::key("lookup", &args));
+        assert_ne!(
+            ToolCallDedup::key("search", &args),
+            ToolCallDedup::key("search", &json!({"query": "go"}))
+        );
+    }
+
+    #[test]
+    fn test_entries_expire_after_window() {
+        let dedup = ToolCallDedup::new();
+        let key = ToolCallDedup::key("search", &json!({}));
+
+        dedup.insert(key.clone(), json!(1), Duration::from_secs(60));
+        assert_eq!(dedup.get(&key), Some(json!(1)));
+        dedup.insert(key.clone(), json!(2), Duration::ZERO);
+        assert_eq!(dedup.get(&key), None);
+    }
+
+    #[test]
+    fn test_entries_keep_their_own_window() {
+        let dedup = ToolCallDedup::new();
+        let slow = ToolCallDedup::key("report", &json!({}));
+        let fast = ToolCallDedup::key("search", &json!({}));
+
+        // Recording a call with a shorter window doesn't expire longer ones
+        dedup.insert(slow.clone(), json!("report"), Duration::from_secs(60));
+        dedup.insert(fast.clone(), json!("search"), Duration::ZERO);
+        assert_eq!(dedup.get(&slow), Some(json!("report")));
+        assert_eq!(dedup.get(&fast), None);
+    }
+}
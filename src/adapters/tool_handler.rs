@@ -1,15 +1,27 @@
+use crate::adapters::callback_tracker::CallbackTracker;
 use crate::adapters::mcp_client::McpClientManager;
+use crate::adapters::metrics_handler::MetricsCollector;
+use crate::adapters::db_strategy_pool::DatabaseStrategyPool;
+use crate::adapters::fixture_cache::FixtureCache;
+use crate::adapters::logging::execution_span;
 use crate::adapters::mock_strategy::MockStrategyHandler;
+use crate::adapters::strategy_errors::StrategyErrorLog;
+use crate::adapters::tool_concurrency::{ToolCallLimiter, DEFAULT_QUEUE_TIMEOUT};
+use crate::adapters::tool_dedup::ToolCallDedup;
+use crate::adapters::tool_latency::LatencyProfiles;
+use crate::adapters::tool_overrides::{ToolOverrideResponse, ToolOverrides};
 use crate::adapters::workflow_engine::WorkflowEngine;
-use crate::agents::domain::AgentPort;
+use crate::agents::domain::{AgentInitReport, AgentPort};
 use crate::config::{Settings, ToolConfig, WorkflowConfig};
 use crate::config::schema::{resolve_schema_refs, SchemaConfig};
+use crate::domain::execution::ExecutionContext;
 use crate::domain::{Tool, ToolPort};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
+use tracing::Instrument;
 
 /// Prefix for agent tools
 pub const AGENT_TOOL_PREFIX: &str = "agent_";
@@ -25,6 +37,10 @@ struct InnerToolHandler {
     mock_strategy: Arc<MockStrategyHandler>,
     /// Shared agent handler for calling agents from workflow steps
     agent_handler: Arc<RwLock<Option<Arc<dyn AgentPort>>>>,
+    /// Runtime overrides, also applied to tools called from workflow steps
+    overrides: Arc<ToolOverrides>,
+    /// External MCP servers, for aliases that point at their tools
+    mcp_client: Arc<McpClientManager>,
 }
 
 impl InnerToolHandler {
@@ -32,51 +48,45 @@ impl InnerToolHandler {
         settings: Arc<RwLock<Settings>>,
         mock_strategy: Arc<MockStrategyHandler>,
         agent_handler: Arc<RwLock<Option<Arc<dyn AgentPort>>>>,
+        mcp_client: Arc<McpClientManager>,
     ) -> Self {
         Self {
             settings,
             mock_strategy,
             agent_handler,
+            overrides: Arc::new(ToolOverrides::new()),
+            mcp_client,
         }
     }
 
+    /// Response from an active runtime override for `name`, if there is one
+    async fn execute_override(&self, name: &str, args: &Value) -> Option<Result<Value>> {
+        let response = self.overrides.get(name)?;
+        tracing::debug!("Using runtime override for tool '{}'", name);
+        Some(match response {
+            ToolOverrideResponse::Value(value) => Ok(value),
+            ToolOverrideResponse::Mock(mock_config) => self.mock_strategy.generate(&mock_config, Some(args)).await,
+        })
+    }
+
     async fn find_tool_config(&self, name: &str) -> Option<ToolConfig> {
         let settings = self.settings.read().await;
         settings.tools.iter().find(|t| t.name == name).cloned()
     }
-}
 
-#[async_trait]
-impl ToolPort for InnerToolHandler {
-    async fn list_tools(&self) -> Result<Vec<Tool>> {
-        let settings = self.settings.read().await;
-        let schemas = &settings.schemas;
+    /// Run a tool call, inside the span [`ToolPort::execute_tool`] opens
+    async fn call_tool(&self, name: &str, args: Value) -> Result<Value> {
+        ensure_enabled(&self.settings, &self.mcp_client, name).await?;
 
-        // Helper to resolve schema refs with logging on error
-        let resolve = |schema: &Value| -> Value {
-            resolve_schema_refs(schema, schemas).unwrap_or_else(|e| {
-                tracing::warn!("Failed to resolve schema reference: {}", e);
-                schema.clone()
-            })
-        };
-        let resolve_opt = |schema: &Option<Value>| -> Option<Value> {
-            schema.as_ref().map(|s| resolve(s))
-        };
+        if let Some(result) = self.execute_override(name, &args).await {
+            return result;
+        }
 
-        let tools = settings
-            .tools
-            .iter()
-            .map(|t| Tool {
-                name: t.name.clone(),
-                description: t.description.clone(),
-                input_schema: resolve(&t.input_schema),
-                output_schema: resolve_opt(&t.output_schema),
-            })
-            .collect();
-        Ok(tools)
-    }
+        // Aliases can point workflow steps at tools of external MCP servers
+        if self.mcp_client.routes_tool(name).await {
+            return self.mcp_client.call_tool(name, args).await;
+        }
 
-    async fn execute_tool(&self, name: &str, args: Value) -> Result<Value> {
         // Check if this is an agent tool
         if let Some(agent_name) = name.strip_prefix(AGENT_TOOL_PREFIX) {
             if let Some(agent_handler) = self.agent_handler.read().await.as_ref() {
@@ -116,11 +126,15 @@ impl ToolPort for InnerToolHandler {
                         return Ok(static_response.clone());
                     }
                 }
-                self.mock_strategy.generate(mock_config, Some(&args)).await
+                let result = self.mock_strategy.generate(mock_config, Some(&args)).await;
+                if let Err(e) = &result {
+                    self.mock_strategy.errors().record(name, &strategy_name(mock_config), &args, e);
+                }
+                result
             } else if let Some(static_response) = &config.static_response {
                 Ok(static_response.clone())
             } else {
-                Ok(Value::Null)
+                self.settings.read().await.default_tool_response.resolve(name)
             }
         } else {
             Err(anyhow::anyhow!("Tool not found: {}", name))
@@ -128,6 +142,83 @@ impl ToolPort for InnerToolHandler {
     }
 }
 
+#[async_trait]
+impl ToolPort for InnerToolHandler {
+    async fn list_tools(&self) -> Result<Vec<Tool>> {
+        let settings = self.settings.read().await;
+        let schemas = &settings.schemas;
+
+        // Helper to resolve schema refs with logging on error
+        let resolve = |schema: &Value| -> Value {
+            resolve_schema_refs(schema, schemas).unwrap_or_else(|e| {
+                tracing::warn!("Failed to resolve schema reference: {}", e);
+                schema.clone()
+            })
+        };
+        let resolve_opt = |schema: &Option<Value>| -> Option<Value> {
+            schema.as_ref().map(&resolve)
+        };
+
+        let tools = settings
+            .tools
+            .iter()
+            .map(|t| Tool {
+                name: t.name.clone(),
+                description: t.description.clone(),
+                input_schema: resolve(&t.input_schema),
+                output_schema: resolve_opt(&t.output_schema),
+            })
+            .collect();
+        Ok(tools)
+    }
+
+    async fn execute_tool(&self, name: &str, args: Value) -> Result<Value> {
+        let (name, max_call_depth) = {
+            let settings = self.settings.read().await;
+            (settings.resolve_tool_alias(name), settings.server.max_call_depth)
+        };
+        let name = name.as_str();
+        let context = nested_context(name, max_call_depth)?;
+        context
+            .scope(self.call_tool(name, args))
+            .instrument(execution_span("tool", name))
+            .await
+    }
+}
+
+/// Fail a call to an agent tool or an external MCP server's tool while
+/// `[features]` turns that subsystem off
+async fn ensure_enabled(settings: &RwLock<Settings>, mcp_client: &McpClientManager, name: &str) -> Result<()> {
+    let features = settings.read().await.features.clone();
+    if !features.agents && name.starts_with(AGENT_TOOL_PREFIX) {
+        return Err(anyhow::anyhow!("Agents are disabled (features.agents = false): {}", name));
+    }
+    if !features.mcp_aggregation && mcp_client.routes_tool(name).await {
+        return Err(anyhow::anyhow!(
+            "External MCP servers are disabled (features.mcp_aggregation = false): {}",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Context of a call to `name` nested in the current one, or an error when
+/// the calls are already `max_depth` deep (e.g. an agent calling itself)
+fn nested_context(name: &str, max_depth: usize) -> Result<ExecutionContext> {
+    let context = ExecutionContext::current().unwrap_or_default();
+    if context.depth >= max_depth {
+        anyhow::bail!(
+            "Max call depth exceeded: calling '{}' would nest more than {} tool calls (server.max_call_depth)",
+            name,
+            max_depth
+        );
+    }
+    Ok(ExecutionContext {
+        depth: context.depth + 1,
+        ..context
+    })
+}
+
 /// Main tool handler that combines regular tools, workflow tools, MCP tools, and agents.
 /// Workflows and agents are exposed as tools that can be called via MCP or by other agents.
 pub struct BasicToolHandler {
@@ -137,24 +228,18 @@ pub struct BasicToolHandler {
     mcp_client: Arc<McpClientManager>,
     /// Optional agent handler for exposing agents as tools
     agent_handler: Arc<RwLock<Option<Arc<dyn AgentPort>>>>,
+    /// Recent results of tools with a dedup window
+    dedup: ToolCallDedup,
+    /// Latency profiles of tools that sample their delay from one
+    latency: LatencyProfiles,
+    /// Slots under `server.max_concurrent_tool_calls`
+    concurrency: Arc<ToolCallLimiter>,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 impl BasicToolHandler {
     pub fn new(settings: Arc<RwLock<Settings>>, mock_strategy: Arc<MockStrategyHandler>) -> Self {
-        // Create shared agent handler that will be used by both BasicToolHandler and InnerToolHandler
-        let agent_handler = Arc::new(RwLock::new(None));
-        let inner_handler = Arc::new(InnerToolHandler::new(
-            settings.clone(),
-            mock_strategy,
-            agent_handler.clone(),
-        ));
-        Self {
-            settings,
-            inner_handler,
-            workflow_engine: OnceLock::new(),
-            mcp_client: Arc::new(McpClientManager::new()),
-            agent_handler,
-        }
+        Self::with_mcp_client(settings, mock_strategy, Arc::new(McpClientManager::new()))
     }
 
     /// Create with an existing MCP client manager
@@ -169,6 +254,7 @@ impl BasicToolHandler {
             settings.clone(),
             mock_strategy,
             agent_handler.clone(),
+            mcp_client.clone(),
         ));
         Self {
             settings,
@@ -176,22 +262,50 @@ impl BasicToolHandler {
             workflow_engine: OnceLock::new(),
             mcp_client,
             agent_handler,
+            dedup: ToolCallDedup::new(),
+            latency: LatencyProfiles::new(),
+            concurrency: Arc::new(ToolCallLimiter::new()),
+            metrics: None,
         }
     }
 
+    /// Record metrics such as deduplicated tool calls
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Count calls against `limiter`, so the server-wide limit covers every
+    /// tool handler sharing it
+    pub fn with_call_limiter(mut self, limiter: Arc<ToolCallLimiter>) -> Self {
+        self.concurrency = limiter;
+        self
+    }
+
     /// Set the agent handler to expose agents as tools
     pub async fn set_agent_handler(&self, handler: Arc<dyn AgentPort>) {
         *self.agent_handler.write().await = Some(handler);
     }
 
-    /// Reinitialize agents (e.g., after API keys change)
-    /// This causes agents to be recreated with updated credentials
-    pub async fn reinitialize_agents(&self) -> Result<()> {
-        if let Some(handler) = self.agent_handler.read().await.as_ref() {
-            handler.reinitialize().await?;
-            tracing::info!("Agents reinitialized successfully");
-        }
-        Ok(())
+    /// Get the agent handler exposing agents as tools, if set
+    pub async fn agent_handler(&self) -> Option<Arc<dyn AgentPort>> {
+        self.agent_handler.read().await.clone()
+    }
+
+    /// Reinitialize agents (e.g., after API keys change or a config reload)
+    /// This causes agents to be recreated with updated credentials. `None`
+    /// when no agent handler is set.
+    pub async fn reinitialize_agents(&self) -> Result<Option<AgentInitReport>> {
+        let Some(handler) = self.agent_handler.read().await.clone() else {
+            return Ok(None);
+        };
+        let report = handler.reinitialize().await?;
+        tracing::info!(
+            "Agents reinitialized: {} loaded, {} failed",
+            report.loaded.len(),
+            report.failed.len()
+        );
+        Ok(Some(report))
     }
 
     /// Get the MCP client manager
@@ -199,9 +313,43 @@ impl BasicToolHandler {
         &self.mcp_client
     }
 
+    /// Get the slots under `server.max_concurrent_tool_calls`
+    pub fn call_limiter(&self) -> &Arc<ToolCallLimiter> {
+        &self.concurrency
+    }
+
+    /// Get the runtime tool overrides
+    pub fn overrides(&self) -> &Arc<ToolOverrides> {
+        &self.inner_handler.overrides
+    }
+
+    /// Get the callbacks scheduled by tools using the callback strategy
+    pub fn callbacks(&self) -> &Arc<CallbackTracker> {
+        self.inner_handler.mock_strategy.callbacks()
+    }
+
+    /// Get the log of recent strategy failures
+    pub fn strategy_errors(&self) -> &Arc<StrategyErrorLog> {
+        self.inner_handler.mock_strategy.errors()
+    }
+
+    /// Get the connection pools used by tools with the database strategy
+    pub fn db_pools(&self) -> &Arc<DatabaseStrategyPool> {
+        self.inner_handler.mock_strategy.db_pools()
+    }
+
+    /// Get the cache of parsed File-strategy fixtures, if fixture directories are watched
+    pub fn fixtures(&self) -> Option<&Arc<FixtureCache>> {
+        self.inner_handler.mock_strategy.fixtures()
+    }
+
     /// Initialize MCP connections (should be called after construction)
     pub async fn initialize_mcp(&self) -> Result<()> {
         let settings = self.settings.read().await;
+        if !settings.features.mcp_aggregation {
+            tracing::info!("External MCP servers disabled by features.mcp_aggregation");
+            return Ok(());
+        }
         self.mcp_client.initialize(&settings.mcp_servers).await
     }
 
@@ -245,6 +393,269 @@ impl BasicToolHandler {
     pub async fn get_mcp_tools(&self, specs: &[String]) -> Vec<Tool> {
         self.mcp_client.get_tools_for_specs(specs).await
     }
+
+    /// What a call to `name` would run: the mock strategy, or the kind of
+    /// handler for tools without one
+    async fn dry_run_target(&self, name: &str) -> Result<String> {
+        if let Some(response) = self.inner_handler.overrides.get(name) {
+            return Ok(match response {
+                ToolOverrideResponse::Value(_) => "override".to_string(),
+                ToolOverrideResponse::Mock(mock_config) => strategy_name(&mock_config),
+            });
+        }
+        if name.starts_with(AGENT_TOOL_PREFIX) {
+            return Ok("agent".to_string());
+        }
+        if let Some(resource_name) = name.strip_prefix(RESOURCE_TOOL_PREFIX) {
+            let config = self.find_resource_config(resource_name).await
+                .ok_or_else(|| anyhow::anyhow!("Resource not found: {}", resource_name))?;
+            return Ok(config.mock.as_ref().map_or_else(|| "content".to_string(), strategy_name));
+        }
+        if let Some(template_name) = name.strip_prefix(RESOURCE_TEMPLATE_TOOL_PREFIX) {
+            let config = self.find_resource_template_config(template_name).await
+                .ok_or_else(|| anyhow::anyhow!("Resource template not found: {}", template_name))?;
+            return Ok(config.mock.as_ref().map_or_else(|| "content".to_string(), strategy_name));
+        }
+        if self.mcp_client.routes_tool(name).await {
+            return Ok("mcp".to_string());
+        }
+        if self.is_workflow(name).await {
+            return Ok("workflow".to_string());
+        }
+
+        let config = self.find_tool_config(name).await
+            .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", name))?;
+        Ok(match (&config.mock, &config.static_response) {
+            (Some(mock_config), _) => strategy_name(mock_config),
+            (None, Some(_)) => "static".to_string(),
+            (None, None) => "default".to_string(),
+        })
+    }
+
+    /// Response of a configured tool from its mock or static response
+    async fn execute_regular_tool(&self, name: &str, config: &ToolConfig, args: &Value) -> Result<Value> {
+        if let Some(mock_config) = &config.mock {
+            // Special handling for Static strategy: use static_response if available
+            if matches!(mock_config.strategy, crate::config::MockStrategyType::Static) {
+                if let Some(static_response) = &config.static_response {
+                    return Ok(static_response.clone());
+                }
+            }
+            let mock_strategy = &self.inner_handler.mock_strategy;
+            let result = mock_strategy.generate(mock_config, Some(args)).await;
+            if let Err(e) = &result {
+                mock_strategy.errors().record(name, &strategy_name(mock_config), args, e);
+            }
+            result
+        } else if let Some(static_response) = &config.static_response {
+            Ok(static_response.clone())
+        } else {
+            self.settings.read().await.default_tool_response.resolve(name)
+        }
+    }
+
+    /// Run a tool call, inside the span [`ToolPort::execute_tool`] opens
+    async fn call_tool(&self, name: &str, args: Value) -> Result<Value> {
+        ensure_enabled(&self.settings, &self.mcp_client, name).await?;
+
+        // `_dry_run: true` reports what would run without running it
+        let (args, dry_run) = take_dry_run_flag(args);
+        if dry_run {
+            return Ok(json!({
+                "dry_run": true,
+                "would_execute": self.dry_run_target(name).await?,
+                "args_received": args
+            }));
+        }
+
+        // Hold a slot under the server-wide limit for the rest of the call.
+        // Calls nested in a workflow or agent run on their caller's slot:
+        // waiting for a second one while holding the first would deadlock
+        // once every slot is held by a caller waiting on its nested calls.
+        let (limit, timeout) = {
+            let settings = self.settings.read().await;
+            let timeout = settings.server.tool_call_queue_timeout_ms.map(std::time::Duration::from_millis);
+            (settings.server.max_concurrent_tool_calls, timeout.unwrap_or(DEFAULT_QUEUE_TIMEOUT))
+        };
+        let nested = ExecutionContext::current().is_some_and(|context| context.depth > 1);
+        let _permit = if nested {
+            None
+        } else {
+            self.concurrency.acquire(limit, timeout, self.metrics.as_ref()).await?
+        };
+
+        // Runtime overrides take precedence over everything configured
+        if let Some(result) = self.inner_handler.execute_override(name, &args).await {
+            return result;
+        }
+
+        // Check if this is an agent tool
+        if let Some(agent_name) = name.strip_prefix(AGENT_TOOL_PREFIX) {
+            if let Some(agent_handler) = self.agent_handler.read().await.as_ref() {
+                // Get agent info to check schemas
+                let agent_info = agent_handler.get_agent(agent_name).await?;
+                let agent_info = agent_info.ok_or_else(|| anyhow::anyhow!("Agent not found: {}", agent_name))?;
+
+                // Extract session_id if provided
+                let session_id = args
+                    .get("session_id")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                // Transform input: if custom input_schema, serialize structured input to prompt
+                let agent_input = transform_agent_input(&args, &agent_info.input_schema);
+
+                // Execute the agent
+                let response = agent_handler
+                    .execute(agent_name, agent_input, session_id)
+                    .await?;
+
+                // Transform output: if output_schema defined, try to match output to schema
+                let output = transform_agent_output(&response.output, &agent_info.output_schema);
+
+                // Return agent response as tool result
+                return Ok(json!({
+                    "output": output,
+                    "session_id": response.session_id,
+                    "iterations": response.iterations,
+                    "tool_calls": response.tool_calls.len(),
+                    "reasoning_steps": response.reasoning_steps.len(),
+                    "execution_time_ms": response.execution_time_ms
+                }));
+            } else {
+                return Err(anyhow::anyhow!("Agent handler not available for agent: {}", agent_name));
+            }
+        }
+
+        // Check if this is a resource tool
+        if let Some(resource_name) = name.strip_prefix(RESOURCE_TOOL_PREFIX) {
+            if let Some(config) = self.find_resource_config(resource_name).await {
+                // Return resource content
+                if let Some(mock_config) = &config.mock {
+                    return self.inner_handler
+                        .mock_strategy
+                        .generate(mock_config, Some(&args))
+                        .await;
+                } else if let Some(content) = &config.content {
+                    return Ok(json!({
+                        "uri": config.uri,
+                        "content": content,
+                        "mime_type": config.mime_type
+                    }));
+                } else {
+                    return Ok(json!({
+                        "uri": config.uri,
+                        "content": null,
+                        "mime_type": config.mime_type
+                    }));
+                }
+            } else {
+                return Err(anyhow::anyhow!("Resource not found: {}", resource_name));
+            }
+        }
+
+        // Check if this is a resource template tool
+        if let Some(template_name) = name.strip_prefix(RESOURCE_TEMPLATE_TOOL_PREFIX) {
+            if let Some(config) = self.find_resource_template_config(template_name).await {
+                // Resolve the URI template with provided arguments
+                let mut resolved_uri = config.uri_template.clone();
+                if let Some(obj) = args.as_object() {
+                    for (key, value) in obj {
+                        let placeholder = format!("{{{}}}", key);
+                        if let Some(val_str) = value.as_str() {
+                            resolved_uri = resolved_uri.replace(&placeholder, val_str);
+                        }
+                    }
+                }
+
+                // Return resource template content
+                if let Some(mock_config) = &config.mock {
+                    return self.inner_handler
+                        .mock_strategy
+                        .generate(mock_config, Some(&args))
+                        .await;
+                } else if let Some(content) = &config.content {
+                    // Also resolve template variables in content
+                    let mut resolved_content = content.clone();
+                    if let Some(obj) = args.as_object() {
+                        for (key, value) in obj {
+                            let placeholder = format!("{{{}}}", key);
+                            if let Some(val_str) = value.as_str() {
+                                resolved_content = resolved_content.replace(&placeholder, val_str);
+                            }
+                        }
+                    }
+                    return Ok(json!({
+                        "uri": resolved_uri,
+                        "content": resolved_content,
+                        "mime_type": config.mime_type
+                    }));
+                } else {
+                    return Ok(json!({
+                        "uri": resolved_uri,
+                        "content": null,
+                        "mime_type": config.mime_type
+                    }));
+                }
+            } else {
+                return Err(anyhow::anyhow!("Resource template not found: {}", template_name));
+            }
+        }
+
+        // Check if this is an MCP tool
+        if self.mcp_client.routes_tool(name).await {
+            return self.mcp_client.call_tool(name, args).await;
+        }
+
+        // Check if this is a workflow
+        if self.is_workflow(name).await {
+            if let Some(workflow) = self.find_workflow_config(name).await {
+                let engine = self.get_workflow_engine();
+                return engine.execute(&workflow, args).await;
+            }
+        }
+
+        // Otherwise, treat as regular tool
+        if let Some(config) = self.find_tool_config(name).await {
+            // Stateful results depend on call order, so repeating one would be wrong
+            let dedup_window = config.dedup_window_ms
+                .filter(|_| !config.mock.as_ref().is_some_and(|m| {
+                    matches!(m.strategy, crate::config::MockStrategyType::Stateful)
+                }))
+                .map(std::time::Duration::from_millis);
+            let dedup_key = dedup_window.map(|_| ToolCallDedup::key(name, &args));
+            if let Some(key) = &dedup_key {
+                if let Some(result) = self.dedup.get(key) {
+                    tracing::debug!("Returning deduplicated result for tool '{}'", name);
+                    if let Some(metrics) = &self.metrics {
+                        metrics.tool_dedup_hits.inc();
+                    }
+                    return Ok(result);
+                }
+            }
+
+            if let Some(latency) = &config.latency {
+                tokio::time::sleep(self.latency.delay(latency, name).await?).await;
+            }
+            let mut result = self.execute_regular_tool(name, &config, &args).await?;
+            let (normalize_all, big_integers_as_strings) = {
+                let settings = self.settings.read().await;
+                (settings.server.normalize_all_responses, settings.server.big_integers_as_strings)
+            };
+            if big_integers_as_strings {
+                stringify_big_integers(&mut result);
+            }
+            if config.normalize_response.unwrap_or(normalize_all) {
+                result = normalize_envelope(result);
+            }
+            if let (Some(window), Some(key)) = (dedup_window, dedup_key) {
+                self.dedup.insert(key, result.clone(), window);
+            }
+            Ok(result)
+        } else {
+            Err(anyhow::anyhow!("Tool not found: {}", name))
+        }
+    }
 }
 
 #[async_trait]
@@ -358,10 +769,11 @@ impl ToolPort for BasicToolHandler {
         }
 
         // Drop the settings lock before async call
+        let features = settings.features.clone();
         drop(settings);
 
         // Agent tools (agents exposed as tools with agent_ prefix)
-        if let Some(agent_handler) = self.agent_handler.read().await.as_ref() {
+        if let Some(agent_handler) = self.agent_handler.read().await.as_ref().filter(|_| features.agents) {
             if let Ok(agents) = agent_handler.list_agents().await {
                 for agent in agents {
                     // Build input schema for agent
@@ -384,6 +796,11 @@ impl ToolPort for BasicToolHandler {
                                 "session_id": {
                                     "type": "string",
                                     "description": "Optional session ID for multi-turn conversations"
+                                },
+                                "images": {
+                                    "type": "array",
+                                    "description": "Optional images for the agent: URLs, data: URLs or {media_type, data} objects",
+                                    "items": {}
                                 }
                             },
                             "required": ["prompt"]
@@ -401,163 +818,81 @@ impl ToolPort for BasicToolHandler {
         }
 
         // MCP tools from external servers
-        let mcp_tools = self.mcp_client.list_all_tools().await;
-        for (_, tool) in mcp_tools {
-            tools.push(tool);
+        if features.mcp_aggregation {
+            let mcp_tools = self.mcp_client.list_all_tools().await;
+            for (_, tool) in mcp_tools {
+                tools.push(tool);
+            }
         }
 
         Ok(tools)
     }
 
     async fn execute_tool(&self, name: &str, args: Value) -> Result<Value> {
-        // Check if this is an agent tool
-        if let Some(agent_name) = name.strip_prefix(AGENT_TOOL_PREFIX) {
-            if let Some(agent_handler) = self.agent_handler.read().await.as_ref() {
-                // Get agent info to check schemas
-                let agent_info = agent_handler.get_agent(agent_name).await?;
-                let agent_info = agent_info.ok_or_else(|| anyhow::anyhow!("Agent not found: {}", agent_name))?;
-
-                // Extract session_id if provided
-                let session_id = args
-                    .get("session_id")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                // Transform input: if custom input_schema, serialize structured input to prompt
-                let agent_input = transform_agent_input(&args, &agent_info.input_schema);
-
-                // Execute the agent
-                let response = agent_handler
-                    .execute(agent_name, agent_input, session_id)
-                    .await?;
-
-                // Transform output: if output_schema defined, try to match output to schema
-                let output = transform_agent_output(&response.output, &agent_info.output_schema);
-
-                // Return agent response as tool result
-                return Ok(json!({
-                    "output": output,
-                    "session_id": response.session_id,
-                    "iterations": response.iterations,
-                    "tool_calls": response.tool_calls.len(),
-                    "reasoning_steps": response.reasoning_steps.len(),
-                    "execution_time_ms": response.execution_time_ms
-                }));
-            } else {
-                return Err(anyhow::anyhow!("Agent handler not available for agent: {}", agent_name));
-            }
-        }
-
-        // Check if this is a resource tool
-        if let Some(resource_name) = name.strip_prefix(RESOURCE_TOOL_PREFIX) {
-            if let Some(config) = self.find_resource_config(resource_name).await {
-                // Return resource content
-                if let Some(mock_config) = &config.mock {
-                    return self.inner_handler
-                        .mock_strategy
-                        .generate(mock_config, Some(&args))
-                        .await;
-                } else if let Some(content) = &config.content {
-                    return Ok(json!({
-                        "uri": config.uri,
-                        "content": content,
-                        "mime_type": config.mime_type
-                    }));
-                } else {
-                    return Ok(json!({
-                        "uri": config.uri,
-                        "content": null,
-                        "mime_type": config.mime_type
-                    }));
-                }
-            } else {
-                return Err(anyhow::anyhow!("Resource not found: {}", resource_name));
-            }
-        }
+        let (name, max_call_depth) = {
+            let settings = self.settings.read().await;
+            (settings.resolve_tool_alias(name), settings.server.max_call_depth)
+        };
+        let name = name.as_str();
+        let context = nested_context(name, max_call_depth)?;
+        context
+            .scope(self.call_tool(name, args))
+            .instrument(execution_span("tool", name))
+            .await
+    }
+}
 
-        // Check if this is a resource template tool
-        if let Some(template_name) = name.strip_prefix(RESOURCE_TEMPLATE_TOOL_PREFIX) {
-            if let Some(config) = self.find_resource_template_config(template_name).await {
-                // Resolve the URI template with provided arguments
-                let mut resolved_uri = config.uri_template.clone();
-                if let Some(obj) = args.as_object() {
-                    for (key, value) in obj {
-                        let placeholder = format!("{{{}}}", key);
-                        if let Some(val_str) = value.as_str() {
-                            resolved_uri = resolved_uri.replace(&placeholder, val_str);
-                        }
-                    }
-                }
+/// Wrap a tool result in an MCP `{ content: [...] }` envelope unless it already
+/// is one. Strings become the text as-is, anything else its JSON.
+fn normalize_envelope(result: Value) -> Value {
+    let text = match result {
+        Value::Object(ref obj) if obj.get("content").is_some_and(Value::is_array) => return result,
+        Value::String(text) => text,
+        other => other.to_string(),
+    };
+    json!({ "content": [{ "type": "text", "text": text }] })
+}
 
-                // Return resource template content
-                if let Some(mock_config) = &config.mock {
-                    return self.inner_handler
-                        .mock_strategy
-                        .generate(mock_config, Some(&args))
-                        .await;
-                } else if let Some(content) = &config.content {
-                    // Also resolve template variables in content
-                    let mut resolved_content = content.clone();
-                    if let Some(obj) = args.as_object() {
-                        for (key, value) in obj {
-                            let placeholder = format!("{{{}}}", key);
-                            if let Some(val_str) = value.as_str() {
-                                resolved_content = resolved_content.replace(&placeholder, val_str);
-                            }
-                        }
-                    }
-                    return Ok(json!({
-                        "uri": resolved_uri,
-                        "content": resolved_content,
-                        "mime_type": config.mime_type
-                    }));
-                } else {
-                    return Ok(json!({
-                        "uri": resolved_uri,
-                        "content": null,
-                        "mime_type": config.mime_type
-                    }));
-                }
-            } else {
-                return Err(anyhow::anyhow!("Resource template not found: {}", template_name));
+/// `Number.MAX_SAFE_INTEGER` of JavaScript: larger integers lose precision as
+/// JavaScript numbers
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Replace integers that JavaScript clients would round with their digits as a string
+fn stringify_big_integers(value: &mut Value) {
+    match value {
+        Value::Number(number) => {
+            let digits = number.to_string();
+            let is_integer = !digits.contains(['.', 'e', 'E']);
+            let is_safe = number
+                .as_i64()
+                .map(i64::unsigned_abs)
+                .or_else(|| number.as_u64())
+                .is_some_and(|n| n <= MAX_SAFE_INTEGER);
+            if is_integer && !is_safe {
+                *value = Value::String(digits);
             }
         }
+        Value::Array(items) => items.iter_mut().for_each(stringify_big_integers),
+        Value::Object(fields) => fields.values_mut().for_each(stringify_big_integers),
+        _ => {}
+    }
+}
 
-        // Check if this is an MCP tool
-        if McpClientManager::is_mcp_tool(name) {
-            return self.mcp_client.call_tool(name, args).await;
-        }
+/// Argument that turns a tool call into a dry run
+const DRY_RUN_ARG: &str = "_dry_run";
 
-        // Check if this is a workflow
-        if self.is_workflow(name).await {
-            if let Some(workflow) = self.find_workflow_config(name).await {
-                let engine = self.get_workflow_engine();
-                return engine.execute(&workflow, args).await;
-            }
-        }
+/// Remove the dry-run flag from the arguments, returning whether it was set
+fn take_dry_run_flag(mut args: Value) -> (Value, bool) {
+    let flag = args.as_object_mut().and_then(|obj| obj.remove(DRY_RUN_ARG));
+    (args, flag.and_then(|v| v.as_bool()).unwrap_or(false))
+}
 
-        // Otherwise, treat as regular tool
-        if let Some(config) = self.find_tool_config(name).await {
-            if let Some(mock_config) = &config.mock {
-                // Special handling for Static strategy: use static_response if available
-                if matches!(mock_config.strategy, crate::config::MockStrategyType::Static) {
-                    if let Some(static_response) = &config.static_response {
-                        return Ok(static_response.clone());
-                    }
-                }
-                self.inner_handler
-                    .mock_strategy
-                    .generate(mock_config, Some(&args))
-                    .await
-            } else if let Some(static_response) = &config.static_response {
-                Ok(static_response.clone())
-            } else {
-                Ok(Value::Null)
-            }
-        } else {
-            Err(anyhow::anyhow!("Tool not found: {}", name))
-        }
-    }
+/// Config name of a mock strategy, e.g. "stateful"
+pub(crate) fn strategy_name(mock_config: &crate::config::MockConfig) -> String {
+    serde_json::to_value(&mock_config.strategy)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
 }
 
 /// Transform structured input to agent prompt format
@@ -1,7 +1,7 @@
 use super::tool_handler::BasicToolHandler;
 use crate::adapters::mock_strategy::MockStrategyHandler;
 use crate::adapters::state_manager::StateManager;
-use crate::config::{MockConfig, MockStrategyType, ToolConfig, Settings, ServerSettings};
+use crate::config::{LogFormat, MockConfig, MockStrategyType, ToolConfig, Settings, ServerSettings};
 use crate::domain::ToolPort;
 use serde_json::json;
 use std::sync::Arc;
@@ -18,18 +18,34 @@ async fn test_execute_tool_static() {
         static_response: Some(json!({ "result": "success" })),
         mock: None,
         tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
     }];
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: vec![],
         resource_templates: vec![],
         tools: config,
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -39,6 +55,8 @@ async fn test_execute_tool_static() {
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -49,6 +67,60 @@ async fn test_execute_tool_static() {
     assert_eq!(value["result"], "success");
 }
 
+#[tokio::test]
+async fn test_static_response_preserves_large_integers() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings = Settings {
+        tools: vec![ToolConfig {
+            name: "test_tool".to_string(),
+            description: "Test Tool".to_string(),
+            input_schema: json!({}),
+            output_schema: None,
+            static_response: Some(json!({ "id": 9007199254740993u64, "small": 42 })),
+            mock: None,
+            tags: vec![],
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
+        }],
+        ..Default::default()
+    };
+    let settings = Arc::new(RwLock::new(settings));
+    let handler = BasicToolHandler::new(settings.clone(), mock_strategy);
+
+    // 2^53 + 1 is the first integer an f64 cannot represent
+    let value = handler.execute_tool("test_tool", json!({})).await.unwrap();
+    assert_eq!(value["id"].as_u64(), Some(9007199254740993));
+    assert_eq!(value.to_string(), r#"{"id":9007199254740993,"small":42}"#);
+
+    // For JavaScript clients, which would round it
+    settings.write().await.server.big_integers_as_strings = true;
+    let value = handler.execute_tool("test_tool", json!({})).await.unwrap();
+    assert_eq!(value, json!({"id": "9007199254740993", "small": 42}));
+}
+
+#[cfg(feature = "arbitrary-precision")]
+#[tokio::test]
+async fn test_template_response_keeps_numbers_as_written() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    // 2^64 + 1 is beyond every 64-bit integer type
+    let body = r#"{"id":18446744073709551617,"ratio":0.10000000000000000555}"#;
+    let settings = Settings {
+        tools: vec![serde_json::from_value(json!({
+            "name": "test_tool",
+            "description": "Test Tool",
+            "input_schema": {},
+            "mock": {"strategy": "template", "template": body}
+        }))
+        .unwrap()],
+        ..Default::default()
+    };
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    let value = handler.execute_tool("test_tool", json!({})).await.unwrap();
+    assert_eq!(value.to_string(), body);
+}
+
 #[tokio::test]
 async fn test_execute_tool_mock() {
     let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
@@ -71,20 +143,41 @@ async fn test_execute_tool_mock() {
             database: None,
             faker_schema: None,
             data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: None,
+            transform: vec![],
         }),
         tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
     }];
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: vec![],
         resource_templates: vec![],
         tools: config,
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -94,6 +187,8 @@ async fn test_execute_tool_mock() {
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -115,18 +210,34 @@ async fn test_execute_tool_echo_fallback() {
         static_response: None,
         mock: None,
         tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
     }];
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: crate::config::DefaultToolResponse::Null,
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: vec![],
         resource_templates: vec![],
         tools: config,
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -136,6 +247,8 @@ async fn test_execute_tool_echo_fallback() {
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -151,14 +264,27 @@ async fn test_execute_tool_not_found() {
     let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: vec![],
         resource_templates: vec![],
         tools: vec![],
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -168,6 +294,8 @@ async fn test_execute_tool_not_found() {
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -188,6 +316,9 @@ async fn test_list_tools() {
             static_response: None,
             mock: None,
             tags: vec![],
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
         },
         ToolConfig {
             name: "t2".to_string(),
@@ -197,19 +328,35 @@ async fn test_list_tools() {
             static_response: None,
             mock: None,
             tags: vec![],
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
         },
     ];
 
     let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
-        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000 },
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        server: ServerSettings { host: "127.0.0.1".to_string(), port: 3000, http_mock_mode: false, config_history_depth: 50, log_format: LogFormat::Text, log_level: "info".to_string(), log_file: None, normalize_all_responses: false, max_response_bytes: None, max_concurrent_tool_calls: None, tool_call_queue_timeout_ms: None, max_call_depth: 10, base_path: None, health_at_root: false, idempotency_ttl_seconds: 86_400, config_backups: 5, big_integers_as_strings: false },
         auth: Default::default(),
         resources: vec![],
         resource_templates: vec![],
         tools: config,
         prompts: vec![],
         rate_limit: None,
+        access_control: None,
         s3: None,
         workflows: vec![],
         agents: vec![],
@@ -219,6 +366,8 @@ async fn test_list_tools() {
         schemas: vec![],
         data_lakes: vec![],
         database: None,
+        databases: Default::default(),
+        mcp: Default::default(),
         file_storage: None,
     };
     let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
@@ -229,4 +378,385 @@ async fn test_list_tools() {
     assert_eq!(list.len(), 2);
 }
 
+#[tokio::test]
+async fn test_execute_tool_without_response_uses_default_tool_response() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let mut settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "tools": [{"name": "empty", "description": "Empty", "input_schema": {}, "static_response": null}]
+    }))
+    .unwrap();
+    assert_eq!(settings.default_tool_response, crate::config::DefaultToolResponse::Error);
+
+    settings.default_tool_response = crate::config::DefaultToolResponse::Value {
+        value: json!({"status": "not implemented"}),
+    };
+    let settings = Arc::new(RwLock::new(settings));
+    let handler = BasicToolHandler::new(settings.clone(), mock_strategy);
+
+    let value = handler.execute_tool("empty", json!({})).await.unwrap();
+    assert_eq!(value, json!({"status": "not implemented"}));
+
+    settings.write().await.default_tool_response = crate::config::DefaultToolResponse::Error;
+    let err = handler.execute_tool("empty", json!({})).await.unwrap_err();
+    assert_eq!(err.to_string(), "no response configured for tool empty");
+}
+
+#[tokio::test]
+async fn test_execute_tool_uses_runtime_override() {
+    use crate::adapters::tool_overrides::ToolOverrideResponse;
+
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {"temp": 20}}]
+    }))
+    .unwrap();
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    handler
+        .overrides()
+        .set("weather", ToolOverrideResponse::Value(json!({"temp": -40})), None)
+        .unwrap();
+    assert_eq!(handler.execute_tool("weather", json!({})).await.unwrap(), json!({"temp": -40}));
+
+    let mock = serde_json::from_value(json!({"strategy": "template", "template": "{\"city\": \"{{ city }}\"}"})).unwrap();
+    handler.overrides().set("weather", ToolOverrideResponse::Mock(Box::new(mock)), None).unwrap();
+    assert_eq!(
+        handler.execute_tool("weather", json!({"city": "Oslo"})).await.unwrap(),
+        json!({"city": "Oslo"})
+    );
+
+    handler.overrides().remove("weather");
+    assert_eq!(handler.execute_tool("weather", json!({})).await.unwrap(), json!({"temp": 20}));
+}
+
+#[tokio::test]
+async fn test_execute_tool_resolves_tool_aliases() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {"temp": 20}}],
+        "workflows": [{
+            "name": "report",
+            "description": "Report",
+            "steps": [{"id": "fetch", "tool": "forecast"}],
+            "output_mapping": "{{ steps.fetch.result | json_encode() }}"
+        }],
+        "tool_aliases": {"forecast": "weather", "outlook": "forecast", "lookup": "remote/lookup"}
+    }))
+    .unwrap();
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    assert_eq!(handler.execute_tool("forecast", json!({})).await.unwrap(), json!({"temp": 20}));
+    assert_eq!(handler.execute_tool("report", json!({})).await.unwrap(), json!({"temp": 20}));
+
+    // Aliases are resolved once, so one pointing at another doesn't reach its tool
+    let err = handler.execute_tool("outlook", json!({})).await.unwrap_err();
+    assert_eq!(err.to_string(), "Tool not found: forecast");
+
+    // `server/tool` targets call the tool on the external MCP server
+    let err = handler.execute_tool("lookup", json!({})).await.unwrap_err();
+    assert_eq!(err.to_string(), "MCP server not found: remote");
+}
+
+#[tokio::test]
+async fn test_execute_tool_deduplicates_repeated_calls() {
+    use crate::adapters::metrics_handler::MetricsCollector;
+
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "tools": [
+            {"name": "quote", "description": "Quote", "input_schema": {}, "static_response": null,
+             "mock": {"strategy": "random", "faker_type": "paragraph"}, "dedup_window_ms": 60000},
+            {"name": "counter", "description": "Counter", "input_schema": {}, "static_response": null,
+             "mock": {"strategy": "stateful", "stateful": {"state_key": "hits", "operation": "increment"}},
+             "dedup_window_ms": 60000}
+        ]
+    }))
+    .unwrap();
+    let metrics = Arc::new(MetricsCollector::new().unwrap());
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy)
+        .with_metrics(metrics.clone());
+
+    let first = handler.execute_tool("quote", json!({"topic": "rust"})).await.unwrap();
+    assert_eq!(handler.execute_tool("quote", json!({"topic": "rust"})).await.unwrap(), first);
+    assert_eq!(metrics.tool_dedup_hits.get(), 1.0);
+
+    // Stateful tools are never deduplicated
+    let a = handler.execute_tool("counter", json!({})).await.unwrap();
+    let b = handler.execute_tool("counter", json!({})).await.unwrap();
+    assert_ne!(a, b);
+    assert_eq!(metrics.tool_dedup_hits.get(), 1.0);
+}
+
+#[tokio::test]
+async fn test_execute_tool_dry_run() {
+    let state_manager = Arc::new(StateManager::new());
+    let mock_strategy = Arc::new(MockStrategyHandler::new(state_manager.clone()));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "tools": [
+            {"name": "counter", "description": "Counter", "input_schema": {}, "static_response": null,
+             "mock": {"strategy": "stateful", "stateful": {"state_key": "hits", "operation": "increment"}}},
+            {"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {"temp": 20}}
+        ]
+    }))
+    .unwrap();
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
 
+    let result = handler
+        .execute_tool("counter", json!({"_dry_run": true, "step": 2}))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        json!({"dry_run": true, "would_execute": "stateful", "args_received": {"step": 2}})
+    );
+    assert_eq!(state_manager.get("hits").await, None);
+
+    let result = handler.execute_tool("weather", json!({"_dry_run": true})).await.unwrap();
+    assert_eq!(result["would_execute"], "static");
+    assert!(handler.execute_tool("missing", json!({"_dry_run": true})).await.is_err());
+
+    // The flag is stripped from real calls too
+    assert_eq!(
+        handler.execute_tool("weather", json!({"_dry_run": false})).await.unwrap(),
+        json!({"temp": 20})
+    );
+}
+
+#[tokio::test]
+async fn test_execute_tool_normalizes_response_envelope() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000, "normalize_all_responses": true},
+        "default_tool_response": {"mode": "null"},
+        "tools": [
+            {"name": "empty", "description": "Empty", "input_schema": {}, "static_response": null},
+            {"name": "greeting", "description": "Greeting", "input_schema": {}, "static_response": "hi"},
+            {"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {"temp": 20}},
+            {"name": "wrapped", "description": "Wrapped", "input_schema": {},
+             "static_response": {"content": [{"type": "text", "text": "ok"}], "isError": false}},
+            {"name": "raw", "description": "Raw", "input_schema": {}, "static_response": {"temp": 20},
+             "normalize_response": false}
+        ]
+    }))
+    .unwrap();
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    let text = |text: &str| json!({"content": [{"type": "text", "text": text}]});
+    assert_eq!(handler.execute_tool("empty", json!({})).await.unwrap(), text("null"));
+    assert_eq!(handler.execute_tool("greeting", json!({})).await.unwrap(), text("hi"));
+    assert_eq!(handler.execute_tool("weather", json!({})).await.unwrap(), text(r#"{"temp":20}"#));
+    assert_eq!(
+        handler.execute_tool("wrapped", json!({})).await.unwrap(),
+        json!({"content": [{"type": "text", "text": "ok"}], "isError": false})
+    );
+    assert_eq!(handler.execute_tool("raw", json!({})).await.unwrap(), json!({"temp": 20}));
+}
+
+#[tokio::test]
+async fn test_strategy_failures_are_logged() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000},
+        "tools": [{"name": "flaky", "description": "Flaky", "input_schema": {},
+                   "mock": {"strategy": "script", "script": "throw \"boom\""}}]
+    }))
+    .unwrap();
+    let handler = BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy);
+
+    assert!(handler.execute_tool("flaky", json!({"id": 7})).await.is_err());
+
+    let errors = handler.strategy_errors().list();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].tool, "flaky");
+    assert_eq!(errors[0].strategy, "script");
+    assert_eq!(errors[0].args, json!({"id": 7}));
+    assert!(errors[0].error.contains("boom"), "{}", errors[0].error);
+}
+
+/// An agent calling one tool on every run, by default itself
+struct SelfCallingAgent {
+    tools: std::sync::OnceLock<std::sync::Weak<BasicToolHandler>>,
+    runs: std::sync::atomic::AtomicUsize,
+    /// Tool to call instead of the agent itself
+    tool: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl crate::agents::domain::AgentPort for SelfCallingAgent {
+    async fn execute(
+        &self,
+        name: &str,
+        input: serde_json::Value,
+        _session_id: Option<String>,
+    ) -> anyhow::Result<crate::agents::domain::AgentResponse> {
+        self.runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let tools = self.tools.get().and_then(|tools| tools.upgrade()).unwrap();
+        let tool = self.tool.clone().unwrap_or_else(|| format!("agent_{}", name));
+        let output = tools.execute_tool(&tool, input).await?;
+        Ok(crate::agents::domain::AgentResponse {
+            output,
+            ..Default::default()
+        })
+    }
+
+    fn execute_stream(
+        &self,
+        _name: &str,
+        _input: serde_json::Value,
+        _session_id: Option<String>,
+    ) -> crate::agents::domain::AgentStream {
+        crate::agents::domain::AgentStream::channel(1).1
+    }
+
+    async fn list_agents(&self) -> anyhow::Result<Vec<crate::agents::domain::AgentInfo>> {
+        Ok(vec![])
+    }
+
+    async fn get_agent(&self, name: &str) -> anyhow::Result<Option<crate::agents::domain::AgentInfo>> {
+        Ok(Some(crate::agents::domain::AgentInfo {
+            name: name.to_string(),
+            description: String::new(),
+            agent_type: crate::agents::domain::AgentType::ReAct,
+            input_schema: json!({}),
+            output_schema: None,
+            available_tools: vec![format!("agent_{}", name)],
+            mcp_tools: vec![],
+            llm_provider: "openai".to_string(),
+            llm_model: "gpt-4".to_string(),
+        }))
+    }
+
+    async fn get_session(&self, _session_id: &str) -> anyhow::Result<Option<crate::agents::domain::ConversationSession>> {
+        Ok(None)
+    }
+
+    async fn list_sessions(
+        &self,
+        _agent_name: &str,
+        _limit: usize,
+        _offset: usize,
+    ) -> anyhow::Result<Vec<crate::agents::domain::SessionSummary>> {
+        Ok(vec![])
+    }
+
+    async fn delete_session(&self, _session_id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn export_sessions(&self, _agent_name: &str) -> anyhow::Result<Vec<crate::agents::domain::ConversationSession>> {
+        Ok(vec![])
+    }
+
+    async fn import_sessions(
+        &self,
+        _agent_name: &str,
+        _sessions: Vec<crate::agents::domain::ConversationSession>,
+        _replace: bool,
+    ) -> anyhow::Result<crate::agents::domain::SessionImportSummary> {
+        Ok(Default::default())
+    }
+
+    fn pending_confirmations(&self, _agent_name: &str) -> Vec<crate::agents::confirmation::PendingConfirmation> {
+        vec![]
+    }
+
+    fn resolve_confirmation(&self, _agent_name: &str, _token: &str, _approved: bool) -> bool {
+        false
+    }
+
+    async fn reinitialize(&self) -> anyhow::Result<crate::agents::domain::AgentInitReport> {
+        Ok(Default::default())
+    }
+}
+
+#[tokio::test]
+async fn test_recursive_agent_calls_stop_at_max_call_depth() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {"host": "127.0.0.1", "port": 3000, "max_call_depth": 3}
+    }))
+    .unwrap();
+    let handler = Arc::new(BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy));
+    let agent = Arc::new(SelfCallingAgent {
+        tools: std::sync::OnceLock::new(),
+        runs: std::sync::atomic::AtomicUsize::new(0),
+        tool: None,
+    });
+    agent.tools.set(Arc::downgrade(&handler)).unwrap();
+    handler.set_agent_handler(agent.clone()).await;
+
+    let err = handler.execute_tool("agent_ouroboros", json!({})).await.unwrap_err();
+    assert!(err.to_string().contains("Max call depth exceeded"), "{}", err);
+    assert_eq!(agent.runs.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+    // The depth is per call chain, so the next call starts from the top again
+    assert!(handler.execute_tool("agent_ouroboros", json!({})).await.is_err());
+    assert_eq!(agent.runs.load(std::sync::atomic::Ordering::SeqCst), 6);
+}
+
+#[tokio::test]
+async fn test_tools_called_by_agents_share_the_agent_call_slot() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {
+            "host": "127.0.0.1",
+            "port": 3000,
+            "max_concurrent_tool_calls": 1,
+            "tool_call_queue_timeout_ms": 200
+        },
+        "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {"temp": 20}}]
+    }))
+    .unwrap();
+    let handler = Arc::new(BasicToolHandler::new(Arc::new(RwLock::new(settings)), mock_strategy));
+    let agent = Arc::new(SelfCallingAgent {
+        tools: std::sync::OnceLock::new(),
+        runs: std::sync::atomic::AtomicUsize::new(0),
+        tool: Some("weather".to_string()),
+    });
+    agent.tools.set(Arc::downgrade(&handler)).unwrap();
+    handler.set_agent_handler(agent.clone()).await;
+
+    // The agent holds the only slot while it calls `weather`, which must not
+    // wait for a second one
+    let result = handler.execute_tool("agent_forecaster", json!({})).await.unwrap();
+    assert_eq!(result["output"], json!({"temp": 20}));
+    assert_eq!(agent.runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // The slot is free again afterwards
+    assert_eq!(handler.execute_tool("weather", json!({})).await.unwrap(), json!({"temp": 20}));
+}
+
+#[tokio::test]
+async fn test_handlers_sharing_a_limiter_share_its_slots() {
+    let mock_strategy = Arc::new(MockStrategyHandler::new(Arc::new(StateManager::new())));
+    let settings: Settings = serde_json::from_value(json!({
+        "server": {
+            "host": "127.0.0.1",
+            "port": 3000,
+            "max_concurrent_tool_calls": 1,
+            "tool_call_queue_timeout_ms": 20
+        },
+        "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {"temp": 20}}]
+    }))
+    .unwrap();
+    let settings = Arc::new(RwLock::new(settings));
+    let server = BasicToolHandler::new(settings.clone(), mock_strategy.clone());
+    let api = BasicToolHandler::new(settings, mock_strategy).with_call_limiter(server.call_limiter().clone());
+
+    // A call running on the server's handler holds the only slot
+    let slot = server
+        .call_limiter()
+        .acquire(Some(1), std::time::Duration::from_millis(20), None)
+        .await
+        .unwrap();
+    let err = api.execute_tool("weather", json!({})).await.unwrap_err();
+    assert!(err.to_string().starts_with("Too many concurrent tool calls"), "{}", err);
+
+    drop(slot);
+    assert_eq!(api.execute_tool("weather", json!({})).await.unwrap(), json!({"temp": 20}));
+}
@@ -0,0 +1,225 @@
+//! Simulated tool latency
+//!
+//! Tools with a `latency` wait before responding: a fixed time, a uniformly
+//! random one, or one sampled from a latency profile captured from real
+//! traffic. Profiles are files holding either percentiles or a histogram:
+//!
+//! ```json
+//! {"percentiles": {"p50": 120, "p90": 340, "p99": 1200}}
+//! {"histogram": [{"le_ms": 50, "count": 810}, {"le_ms": 200, "count": 150}, {"le_ms": 1000, "count": 40}]}
+//! ```
+//!
+//! A file can also map names to profiles, e.g. one per tool. Profiles are
+//! parsed on first use and re-read when their file changes.
+
+use crate::config::LatencyConfig;
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Distribution of latencies recorded for an upstream
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyProfile {
+    /// Latency in milliseconds at each percentile, keyed like `p50` or `p99.9`
+    Percentiles(HashMap<String, f64>),
+    /// Counts of calls per bucket, each covering latencies up to `le_ms`
+    /// from the previous bucket's bound
+    Histogram(Vec<HistogramBucket>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistogramBucket {
+    pub le_ms: f64,
+    pub count: u64,
+}
+
+/// Contents of a profile file: a single profile, or profiles by name
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ProfileFile {
+    Single(LatencyProfile),
+    Named(HashMap<String, LatencyProfile>),
+}
+
+impl LatencyProfile {
+    /// Draw a latency in milliseconds from the profile
+    pub fn sample(&self, rng: &mut impl Rng) -> Result<f64> {
+        match self {
+            Self::Percentiles(percentiles) => {
+                let mut points = percentiles
+                    .iter()
+                    .map(|(key, ms)| {
+                        key.strip_prefix('p')
+                            .and_then(|p| p.parse::<f64>().ok())
+                            .filter(|p| (0.0..=100.0).contains(p))
+                            .map(|p| (p, *ms))
+                            .ok_or_else(|| anyhow::anyhow!("Invalid percentile '{}', expected e.g. p50 or p99.9", key))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                    anyhow::bail!("Latency profile has no percentiles");
+                };
+
+                // Interpolate between the percentiles around a uniform draw,
+                // holding the end values beyond the first and last ones
+                let p = rng.gen_range(0.0..=100.0);
+                if p <= first.0 {
+                    return Ok(first.1);
+                }
+                let ms = points
+                    .windows(2)
+                    .find(|pair| p <= pair[1].0)
+                    .map(|pair| {
+                        let (low, high) = (pair[0], pair[1]);
+                        low.1 + (high.1 - low.1) * (p - low.0) / (high.0 - low.0)
+                    })
+                    .unwrap_or(last.1);
+                Ok(ms)
+            }
+            Self::Histogram(buckets) => {
+                let total: u64 = buckets.iter().map(|b| b.count).sum();
+                if total == 0 {
+                    anyhow::bail!("Latency histogram has no counts");
+                }
+
+                // Pick a bucket weighted by its count, then a latency within it
+                let mut remaining = rng.gen_range(0..total);
+                let mut lower = 0.0;
+                for bucket in buckets {
+                    if remaining < bucket.count {
+                        let upper = bucket.le_ms.max(lower);
+                        return Ok(rng.gen_range(lower..=upper));
+                    }
+                    remaining -= bucket.count;
+                    lower = bucket.le_ms;
+                }
+                Ok(lower)
+            }
+        }
+    }
+}
+
+/// Profile files by path, with the modification time they were read at
+#[derive(Default)]
+pub struct LatencyProfiles {
+    files: DashMap<String, (Option<SystemTime>, Arc<ProfileFile>)>,
+}
+
+impl LatencyProfiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long `tool` waits per `config`
+    pub async fn delay(&self, config: &LatencyConfig, tool: &str) -> Result<Duration> {
+        let ms = match config {
+            LatencyConfig::Fixed { ms } => *ms as f64,
+            LatencyConfig::Uniform { min_ms, max_ms } => {
+                rand::thread_rng().gen_range(*min_ms.min(max_ms)..=*max_ms.max(min_ms)) as f64
+            }
+            LatencyConfig::Profile { path, key } => {
+                let file = self.load(path).await?;
+                let profile = match file.as_ref() {
+                    ProfileFile::Single(profile) => profile,
+                    ProfileFile::Named(profiles) => {
+                        let key = key.as_deref().unwrap_or(tool);
+                        profiles
+                            .get(key)
+                            .ok_or_else(|| anyhow::anyhow!("Latency profile '{}' not found in {}", key, path))?
+                    }
+                };
+                profile.sample(&mut rand::thread_rng())?
+            }
+        };
+        Ok(Duration::from_micros((ms.max(0.0) * 1000.0).round() as u64))
+    }
+
+    /// The parsed profile file at `path`, read again if it changed since it was cached
+    async fn load(&self, path: &str) -> Result<Arc<ProfileFile>> {
+        let modified = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("Failed to read latency profile {}", path))?
+            .modified()
+            .ok();
+        if let Some(entry) = self.files.get(path) {
+            if entry.0 == modified {
+                return Ok(entry.1.clone());
+            }
+        }
+
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read latency profile {}", path))?;
+        let file: ProfileFile = if path.ends_with(".json") {
+            serde_json::from_str(&content)?
+        } else {
+            serde_yaml::from_str(&content)?
+        };
+        let file = Arc::new(file);
+        self.files.insert(path.to_string(), (modified, file.clone()));
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_percentile_samples_stay_within_profile() {
+        let profile = LatencyProfile::Percentiles(HashMap::from([
+            ("p50".to_string(), 100.0),
+            ("p99".to_string(), 900.0),
+        ]));
+        let mut rng = StdRng::seed_from_u64(1);
+        let samples: Vec<f64> = (0..1000).map(|_| profile.sample(&mut rng).unwrap()).collect();
+
+        assert!(samples.iter().all(|ms| (100.0..=900.0).contains(ms)));
+        // Half the draws fall at or below the median
+        let at_median = samples.iter().filter(|ms| **ms <= 100.0).count();
+        assert!((400..600).contains(&at_median), "{}", at_median);
+    }
+
+    #[test]
+    fn test_histogram_samples_follow_counts() {
+        let profile = LatencyProfile::Histogram(vec![
+            HistogramBucket { le_ms: 10.0, count: 9 },
+            HistogramBucket { le_ms: 1000.0, count: 1 },
+        ]);
+        let mut rng = StdRng::seed_from_u64(1);
+        let samples: Vec<f64> = (0..1000).map(|_| profile.sample(&mut rng).unwrap()).collect();
+
+        assert!(samples.iter().all(|ms| (0.0..=1000.0).contains(ms)));
+        let fast = samples.iter().filter(|ms| **ms <= 10.0).count();
+        assert!((850..950).contains(&fast), "{}", fast);
+    }
+
+    #[test]
+    fn test_invalid_percentile_key_is_an_error() {
+        let profile = LatencyProfile::Percentiles(HashMap::from([("median".to_string(), 100.0)]));
+        assert!(profile.sample(&mut StdRng::seed_from_u64(1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_profile_file_by_tool_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("latency.json");
+        std::fs::write(&path, r#"{"search": {"percentiles": {"p0": 40, "p100": 40}}}"#).unwrap();
+        let config = LatencyConfig::Profile {
+            path: path.to_string_lossy().to_string(),
+            key: None,
+        };
+
+        let profiles = LatencyProfiles::new();
+        assert_eq!(profiles.delay(&config, "search").await.unwrap(), Duration::from_millis(40));
+        assert!(profiles.delay(&config, "lookup").await.is_err());
+    }
+}
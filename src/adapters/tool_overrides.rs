@@ -0,0 +1,168 @@
+//! Runtime tool overrides
+//!
+//! Temporarily force a tool to return a specific value or use a different mock
+//! strategy while debugging, without editing the config. Overrides live only
+//! in memory: they are never written to disk or S3, and they expire after
+//! their TTL.
+
+use crate::config::MockConfig;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// What an overridden tool returns instead of its configured response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOverrideResponse {
+    /// Generate the response with this mock config
+    Mock(Box<MockConfig>),
+    /// Return this value as-is
+    Value(Value),
+}
+
+/// An active override as reported by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveOverride {
+    pub tool: String,
+    #[serde(flatten)]
+    pub response: ToolOverrideResponse,
+    /// Seconds until the override expires, `None` if it never does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<u64>,
+}
+
+struct OverrideEntry {
+    response: ToolOverrideResponse,
+    expires_at: Option<Instant>,
+}
+
+impl OverrideEntry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|at| now >= at)
+    }
+}
+
+/// Overrides keyed by tool name
+#[derive(Default)]
+pub struct ToolOverrides {
+    entries: DashMap<String, OverrideEntry>,
+}
+
+impl ToolOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set or replace the override for `tool`. Without a TTL the override
+    /// stays until it is removed or the server restarts. Fails when the TTL
+    /// is too long to represent an expiry time.
+    pub fn set(&self, tool: &str, response: ToolOverrideResponse, ttl: Option<Duration>) -> anyhow::Result<()> {
+        let expires_at = match ttl {
+            Some(ttl) => Some(
+                Instant::now()
+                    .checked_add(ttl)
+                    .ok_or_else(|| anyhow::anyhow!("TTL of {} seconds is too long", ttl.as_secs()))?,
+            ),
+            None => None,
+        };
+        self.entries.insert(tool.to_string(), OverrideEntry { response, expires_at });
+        tracing::info!("Override set for tool '{}' (ttl: {:?})", tool, ttl);
+        Ok(())
+    }
+
+    /// Remove the override for `tool`, returning whether one was active
+    pub fn remove(&self, tool: &str) -> bool {
+        let now = Instant::now();
+        self.entries
+            .remove(tool)
+            .is_some_and(|(_, entry)| !entry.is_expired(now))
+    }
+
+    /// The active override for `tool`, if any. Expired overrides are dropped.
+    pub fn get(&self, tool: &str) -> Option<ToolOverrideResponse> {
+        let now = Instant::now();
+        self.entries.remove_if(tool, |_, entry| entry.is_expired(now));
+        self.entries.get(tool).map(|entry| entry.response.clone())
+    }
+
+    /// All active overrides, sorted by tool name
+    pub fn list(&self) -> Vec<ActiveOverride> {
+        let now = Instant::now();
+        self.entries.retain(|_, entry| !entry.is_expired(now));
+
+        let mut overrides: Vec<ActiveOverride> = self
+            .entries
+            .iter()
+            .map(|entry| ActiveOverride {
+                tool: entry.key().clone(),
+                response: entry.response.clone(),
+                expires_in_secs: entry
+                    .expires_at
+                    .map(|at| at.saturating_duration_since(now).as_secs()),
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.tool.cmp(&b.tool));
+        overrides
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_set_get_and_remove() {
+        let overrides = ToolOverrides::new();
+        assert!(overrides.get("echo").is_none());
+
+        overrides.set("echo", ToolOverrideResponse::Value(json!({"forced": true})), None).unwrap();
+        match overrides.get("echo") {
+            Some(ToolOverrideResponse::Value(value)) => assert_eq!(value, json!({"forced": true})),
+            other => panic!("unexpected override: {:?}", other),
+        }
+
+        let listed = overrides.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].tool, "echo");
+        assert!(listed[0].expires_in_secs.is_none());
+
+        assert!(overrides.remove("echo"));
+        assert!(!overrides.remove("echo"));
+        assert!(overrides.get("echo").is_none());
+    }
+
+    #[test]
+    fn test_expired_overrides_are_dropped() {
+        let overrides = ToolOverrides::new();
+        overrides.set("expired", ToolOverrideResponse::Value(json!(1)), Some(Duration::ZERO)).unwrap();
+        overrides.set("active", ToolOverrideResponse::Value(json!(2)), Some(Duration::from_secs(60))).unwrap();
+
+        assert!(overrides.get("expired").is_none());
+        let listed = overrides.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].tool, "active");
+        assert!(listed[0].expires_in_secs.unwrap() <= 60);
+    }
+
+    #[test]
+    fn test_ttl_too_long_is_rejected() {
+        let overrides = ToolOverrides::new();
+        let err = overrides
+            .set("echo", ToolOverrideResponse::Value(json!(1)), Some(Duration::from_secs(u64::MAX)))
+            .unwrap_err();
+        assert!(err.to_string().contains("too long"), "{}", err);
+        assert!(overrides.get("echo").is_none());
+    }
+
+    #[test]
+    fn test_override_response_serialization() {
+        let value: ToolOverrideResponse = serde_json::from_value(json!({"value": null})).unwrap();
+        assert!(matches!(value, ToolOverrideResponse::Value(Value::Null)));
+
+        let mock: ToolOverrideResponse =
+            serde_json::from_value(json!({"mock": {"strategy": "template", "template": "{}"}})).unwrap();
+        assert!(matches!(mock, ToolOverrideResponse::Mock(_)));
+    }
+}
@@ -1,6 +1,7 @@
 use axum::{
     http::{header, StatusCode, Uri},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
+    Extension,
 };
 use rust_embed::RustEmbed;
 
@@ -8,32 +9,80 @@ use rust_embed::RustEmbed;
 #[folder = "ui/dist"]
 struct Asset;
 
+/// `server.base_path` the app is served under, e.g. `/metis`. `create_app`
+/// adds it to requests when it is set, so the UI's page can point its asset
+/// URLs, links and API calls under the prefix.
+#[derive(Clone)]
+pub struct UiBasePath(pub String);
+
 pub struct UIHandler;
 
 impl UIHandler {
-    pub async fn serve(uri: Uri) -> impl IntoResponse {
+    pub async fn serve(base_path: Option<Extension<UiBasePath>>, uri: Uri) -> impl IntoResponse {
         let path = uri.path().trim_start_matches('/');
-        
-        let path = if path.is_empty() || path == "index.html" {
-            "index.html"
-        } else {
-            path
-        };
+        let base_path = base_path.as_ref().map(|Extension(UiBasePath(base_path))| base_path.as_str());
+
+        if path.is_empty() || path == "index.html" {
+            return index(base_path);
+        }
 
         match Asset::get(path) {
             Some(content) => {
                 let mime = mime_guess::from_path(path).first_or_octet_stream();
                 ([(header::CONTENT_TYPE, mime.as_ref())], content.data).into_response()
             }
-            None => {
-                // SPA Fallback: serve index.html for unknown paths (handled by client-side router)
-                if let Some(content) = Asset::get("index.html") {
-                    let mime = mime_guess::from_path("index.html").first_or_octet_stream();
-                    ([(header::CONTENT_TYPE, mime.as_ref())], content.data).into_response()
-                } else {
-                    (StatusCode::NOT_FOUND, "404 Not Found").into_response()
-                }
-            }
+            // SPA Fallback: serve index.html for unknown paths (handled by client-side router)
+            None => index(base_path),
+        }
+    }
+}
+
+fn index(base_path: Option<&str>) -> Response {
+    let Some(content) = Asset::get("index.html") else {
+        return (StatusCode::NOT_FOUND, "404 Not Found").into_response();
+    };
+    let mime = mime_guess::from_path("index.html").first_or_octet_stream();
+    match base_path {
+        Some(base_path) => {
+            let html = with_base_path(&String::from_utf8_lossy(&content.data), base_path);
+            ([(header::CONTENT_TYPE, mime.as_ref())], html).into_response()
         }
+        None => ([(header::CONTENT_TYPE, mime.as_ref())], content.data).into_response(),
+    }
+}
+
+/// `index.html` with its root-relative URLs, including `<base href>`, moved
+/// under `base_path`, and a `metis-base-path` meta tag the UI reads to prefix
+/// its routes and API calls
+fn with_base_path(html: &str, base_path: &str) -> String {
+    let html = ["href=\"", "src=\"", "'"].iter().fold(html.to_string(), |html, quote| {
+        html.replace(&format!("{}/", quote), &format!("{}{}/", quote, base_path))
+    });
+    html.replacen(
+        "<head>",
+        &format!("<head>\n    <meta name=\"metis-base-path\" content=\"{}\" />", base_path),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_base_path_moves_root_relative_urls() {
+        let html = r#"<head>
+    <base href="/" />
+    <script src="https://cdn.example.com"></script>
+    <link rel="stylesheet" href="/style.css"/>
+</head>
+<script type="module">import init from '/app.js';</script>"#;
+
+        let html = with_base_path(html, "/metis");
+        assert!(html.contains(r#"<meta name="metis-base-path" content="/metis" />"#));
+        assert!(html.contains(r#"<base href="/metis/" />"#));
+        assert!(html.contains(r#"href="/metis/style.css""#));
+        assert!(html.contains("from '/metis/app.js'"));
+        assert!(html.contains(r#"src="https://cdn.example.com""#));
     }
 }
@@ -5,18 +5,24 @@
 //! - Conditional branching using Rhai expressions
 //! - Loop iteration over arrays (sequential or parallel)
 //! - Error handling strategies (fail, continue, retry, fallback)
+//! - Named state locks to serialize parallel steps touching shared state
+//! - Caller details injected into step args (`inject_context`)
 
-use crate::config::{ErrorStrategy, WorkflowConfig, WorkflowStep};
+use crate::adapters::logging::execution_span;
+use crate::config::{ContextField, ErrorStrategy, WorkflowConfig, WorkflowStep};
+use crate::domain::execution::ExecutionContext;
 use crate::domain::ToolPort;
 use anyhow::{anyhow, Result};
+use dashmap::DashMap;
 use futures::stream::{self, StreamExt};
 use rhai::{Dynamic, Engine as RhaiEngine, Scope};
 use serde_json::{json, Map, Value};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tera::{Context, Tera};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
 use tokio::time::{sleep, Duration};
+use tracing::Instrument;
 
 /// Result of a single workflow step execution
 #[derive(Debug, Clone)]
@@ -61,10 +67,30 @@ impl WorkflowContext {
     }
 }
 
+/// State locks held by a step. Dropping releases them and removes the locks
+/// no other step holds or waits for, so the engine doesn't keep a lock for
+/// every key ever used.
+struct StateLockGuards<'a> {
+    locks: &'a DashMap<String, Arc<Mutex<()>>>,
+    guards: Vec<(String, OwnedMutexGuard<()>)>,
+}
+
+impl Drop for StateLockGuards<'_> {
+    fn drop(&mut self) {
+        for (key, guard) in self.guards.drain(..) {
+            drop(guard);
+            // Steps clone a lock under the same shard lock, so the count can't grow meanwhile
+            self.locks.remove_if(&key, |_, lock| Arc::strong_count(lock) == 1);
+        }
+    }
+}
+
 /// Workflow execution engine
 pub struct WorkflowEngine {
     tool_handler: Arc<dyn ToolPort>,
     rhai_engine: RhaiEngine,
+    /// Named locks referenced by `WorkflowStep::state_lock_keys`
+    state_locks: DashMap<String, Arc<Mutex<()>>>,
 }
 
 impl WorkflowEngine {
@@ -75,14 +101,48 @@ impl WorkflowEngine {
         Self {
             tool_handler,
             rhai_engine,
+            state_locks: DashMap::new(),
         }
     }
 
+    /// Acquire the state locks for a step.
+    ///
+    /// Keys are deduplicated and taken in lexicographic order so that steps
+    /// with overlapping key sets can never deadlock each other.
+    async fn acquire_state_locks(&self, keys: &[String]) -> StateLockGuards<'_> {
+        let mut keys: Vec<&String> = keys.iter().collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut held = StateLockGuards {
+            locks: &self.state_locks,
+            guards: Vec::with_capacity(keys.len()),
+        };
+        for key in keys {
+            // Clone the Arc out so the DashMap shard isn't held across the await
+            let lock = self.state_locks.entry(key.clone()).or_default().clone();
+            held.guards.push((key.clone(), lock.lock_owned().await));
+        }
+        held
+    }
+
     /// Execute a workflow with the given input using DAG-based execution
     ///
     /// Steps are executed based on their dependencies. Steps with no dependencies
     /// or whose dependencies have all completed are executed in parallel.
     pub async fn execute(&self, workflow: &WorkflowConfig, input: Value) -> Result<Value> {
+        let raw = self.execute_unmapped(workflow, input.clone()).await?;
+        self.map_output(workflow, &input, raw)
+    }
+
+    /// Execute a workflow without applying its output mapping
+    pub async fn execute_unmapped(&self, workflow: &WorkflowConfig, input: Value) -> Result<Value> {
+        self.run_steps(workflow, input)
+            .instrument(execution_span("workflow", &workflow.name))
+            .await
+    }
+
+    async fn run_steps(&self, workflow: &WorkflowConfig, input: Value) -> Result<Value> {
         let context = Arc::new(RwLock::new(WorkflowContext::new(input)));
         let results = Arc::new(RwLock::new(Vec::<StepResult>::new()));
 
@@ -263,6 +323,50 @@ impl WorkflowEngine {
         }))
     }
 
+    /// Reshape a workflow result with its `output_transform` script or `output_mapping`
+    /// template. Both see `input` and each step as `steps.<id>.result`,
+    /// `steps.<id>.success` and `steps.<id>.error`. The script takes precedence.
+    pub fn map_output(&self, workflow: &WorkflowConfig, input: &Value, raw: Value) -> Result<Value> {
+        if workflow.output_transform.is_none() && workflow.output_mapping.is_none() {
+            return Ok(raw);
+        }
+
+        let mut steps = Map::new();
+        if let Some(results) = raw["results"].as_array() {
+            for result in results {
+                let Some(step_id) = result["step_id"].as_str() else {
+                    continue;
+                };
+                steps.insert(
+                    step_id.to_string(),
+                    json!({
+                        "result": raw["steps"].get(step_id).cloned().unwrap_or(Value::Null),
+                        "success": result["success"],
+                        "error": result["error"]
+                    }),
+                );
+            }
+        }
+
+        if let Some(script) = &workflow.output_transform {
+            let mut scope = Scope::new();
+            scope.push("input", json_to_dynamic(input));
+            scope.push("steps", json_to_dynamic(&Value::Object(steps)));
+            let result: Dynamic = self
+                .rhai_engine
+                .eval_with_scope(&mut scope, script)
+                .map_err(|e| anyhow!("output_transform failed: {}", e))?;
+            return dynamic_to_json(&result);
+        }
+
+        let template = workflow.output_mapping.as_deref().unwrap_or_default();
+        let context = json!({ "input": input, "steps": steps });
+        let rendered = render_template(template, &context)
+            .map_err(|e| anyhow!("output_mapping failed: {}", e))?;
+        serde_json::from_str(&rendered)
+            .map_err(|e| anyhow!("output_mapping did not render valid JSON: {}", e))
+    }
+
     /// Validate that the workflow steps form a valid DAG
     fn validate_dag(
         &self,
@@ -287,13 +391,13 @@ impl WorkflowEngine {
         let mut rec_stack = HashSet::new();
 
         for step in steps {
-            if !visited.contains(&step.id) {
-                if self.has_cycle(&step.id, step_map, &mut visited, &mut rec_stack) {
-                    return Err(anyhow!(
-                        "Workflow contains a cycle involving step '{}'",
-                        step.id
-                    ));
-                }
+            if !visited.contains(&step.id)
+                && self.has_cycle(&step.id, step_map, &mut visited, &mut rec_stack)
+            {
+                return Err(anyhow!(
+                    "Workflow contains a cycle involving step '{}'",
+                    step.id
+                ));
             }
         }
 
@@ -334,6 +438,9 @@ impl WorkflowEngine {
         context: Arc<RwLock<WorkflowContext>>,
         workflow_error_strategy: &ErrorStrategy,
     ) -> Result<StepResult> {
+        // Held until the step (including retries and loop iterations) finishes
+        let _state_guards = self.acquire_state_locks(&step.state_lock_keys).await;
+
         // Check condition if present
         if let Some(condition) = &step.condition {
             let ctx = context.read().await;
@@ -485,10 +592,11 @@ impl WorkflowEngine {
                 obj.insert(loop_var.to_string(), item.clone());
                 obj.insert("index".to_string(), json!(index));
             }
-            self.render_args(args_template, &context_value)?
+            render_args(args_template, &context_value)?
         } else {
             item.clone()
         };
+        let args = inject_context(args, &step.inject_context);
 
         // Execute tool
         self.tool_handler.execute_tool(&step.tool, args).await
@@ -507,11 +615,12 @@ impl WorkflowEngine {
         let args = {
             let ctx = context.read().await;
             if let Some(args_template) = &step.args {
-                self.render_args(args_template, &ctx.to_value())?
+                render_args(args_template, &ctx.to_value())?
             } else {
                 Value::Null
             }
         };
+        let args = inject_context(args, &step.inject_context);
 
         // Execute with retry if configured
         match error_strategy {
@@ -581,53 +690,78 @@ impl WorkflowEngine {
 
         dynamic_to_json(&result)
     }
+}
 
-    /// Render step arguments using Tera templates
-    fn render_args(&self, args: &Value, context: &Value) -> Result<Value> {
-        match args {
-            Value::String(s) => {
-                // Render string template
-                let rendered = self.render_template(s, context)?;
-                // Try to parse as JSON, otherwise return as string
-                match serde_json::from_str(&rendered) {
-                    Ok(v) => Ok(v),
-                    Err(_) => Ok(Value::String(rendered)),
-                }
-            }
-            Value::Object(obj) => {
-                // Recursively render object values
-                let mut result = Map::new();
-                for (k, v) in obj {
-                    result.insert(k.clone(), self.render_args(v, context)?);
-                }
-                Ok(Value::Object(result))
+/// Add the requested caller details to object (or empty) args. Details the
+/// call doesn't carry are injected as null; other args are left alone.
+pub(crate) fn inject_context(args: Value, fields: &[ContextField]) -> Value {
+    if fields.is_empty() {
+        return args;
+    }
+    let mut obj = match args {
+        Value::Object(obj) => obj,
+        Value::Null => Map::new(),
+        other => return other,
+    };
+    let context = ExecutionContext::current().unwrap_or_default();
+    for field in fields {
+        let value = match field {
+            ContextField::CallerIp => context.caller_ip.clone(),
+            ContextField::AuthIdentity => context.identity.clone(),
+            ContextField::TraceId => context.trace_id.clone(),
+            ContextField::SessionId => context.session_id.clone(),
+            ContextField::Timestamp => Some(context.timestamp.to_rfc3339()),
+        };
+        obj.insert(field.key().to_string(), value.map_or(Value::Null, Value::String));
+    }
+    Value::Object(obj)
+}
+
+/// Render step arguments using Tera templates
+pub(crate) fn render_args(args: &Value, context: &Value) -> Result<Value> {
+    match args {
+        Value::String(s) => {
+            // Render string template
+            let rendered = render_template(s, context)?;
+            // Try to parse as JSON, otherwise return as string
+            match serde_json::from_str(&rendered) {
+                Ok(v) => Ok(v),
+                Err(_) => Ok(Value::String(rendered)),
             }
-            Value::Array(arr) => {
-                // Recursively render array items
-                let result: Result<Vec<Value>> =
-                    arr.iter().map(|v| self.render_args(v, context)).collect();
-                Ok(Value::Array(result?))
+        }
+        Value::Object(obj) => {
+            // Recursively render object values
+            let mut result = Map::new();
+            for (k, v) in obj {
+                result.insert(k.clone(), render_args(v, context)?);
             }
-            _ => Ok(args.clone()),
+            Ok(Value::Object(result))
+        }
+        Value::Array(arr) => {
+            // Recursively render array items
+            let result: Result<Vec<Value>> =
+                arr.iter().map(|v| render_args(v, context)).collect();
+            Ok(Value::Array(result?))
         }
+        _ => Ok(args.clone()),
     }
+}
 
-    /// Render a single template string
-    fn render_template(&self, template: &str, context: &Value) -> Result<String> {
-        let mut tera = Tera::default();
-        tera.add_raw_template("template", template)
-            .map_err(|e| anyhow!("template parse error: {}", e))?;
+/// Render a single template string
+fn render_template(template: &str, context: &Value) -> Result<String> {
+    let mut tera = Tera::default();
+    tera.add_raw_template("template", template)
+        .map_err(|e| anyhow!("template parse error: {}", e))?;
 
-        let mut tera_context = Context::new();
-        if let Some(obj) = context.as_object() {
-            for (k, v) in obj {
-                tera_context.insert(k, v);
-            }
+    let mut tera_context = Context::new();
+    if let Some(obj) = context.as_object() {
+        for (k, v) in obj {
+            tera_context.insert(k, v);
         }
-
-        tera.render("template", &tera_context)
-            .map_err(|e| anyhow!("template render error: {}", e))
     }
+
+    tera.render("template", &tera_context)
+        .map_err(|e| anyhow!("template render error: {}", e))
 }
 
 /// Convert JSON Value to Rhai Dynamic
@@ -663,13 +797,13 @@ fn json_to_dynamic(value: &Value) -> Dynamic {
 fn dynamic_to_json(value: &Dynamic) -> Result<Value> {
     if value.is_unit() {
         Ok(Value::Null)
-    } else if let Some(b) = value.as_bool().ok() {
+    } else if let Ok(b) = value.as_bool() {
         Ok(Value::Bool(b))
-    } else if let Some(i) = value.as_int().ok() {
+    } else if let Ok(i) = value.as_int() {
         Ok(json!(i))
-    } else if let Some(f) = value.as_float().ok() {
+    } else if let Ok(f) = value.as_float() {
         Ok(json!(f))
-    } else if let Some(s) = value.clone().into_string().ok() {
+    } else if let Ok(s) = value.clone().into_string() {
         Ok(Value::String(s))
     } else if value.is_array() {
         let arr = value.clone().into_array().unwrap();
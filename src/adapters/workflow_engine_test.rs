@@ -72,6 +72,8 @@ async fn test_simple_workflow() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
             WorkflowStep {
                 id: "step2".to_string(),
@@ -83,10 +85,14 @@ async fn test_simple_workflow() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
         ],
         on_error: ErrorStrategy::Fail,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let input = json!({"x": 42});
@@ -118,6 +124,8 @@ async fn test_workflow_with_condition() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
             WorkflowStep {
                 id: "skip_me".to_string(),
@@ -129,10 +137,14 @@ async fn test_workflow_with_condition() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
         ],
         on_error: ErrorStrategy::Fail,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     // With condition false
@@ -163,9 +175,13 @@ async fn test_workflow_with_loop() {
             loop_var: "item".to_string(),
             loop_concurrency: 1,
             on_error: ErrorStrategy::Fail,
+            state_lock_keys: vec![],
+            inject_context: vec![],
         }],
         on_error: ErrorStrategy::Fail,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let input = json!({
@@ -203,9 +219,13 @@ async fn test_workflow_parallel_loop() {
             loop_var: "item".to_string(),
             loop_concurrency: 3, // Process 3 at a time
             on_error: ErrorStrategy::Fail,
+            state_lock_keys: vec![],
+            inject_context: vec![],
         }],
         on_error: ErrorStrategy::Fail,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let input = json!({
@@ -240,6 +260,8 @@ async fn test_workflow_error_continue() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Continue,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
             WorkflowStep {
                 id: "should_run".to_string(),
@@ -251,10 +273,14 @@ async fn test_workflow_error_continue() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
         ],
         on_error: ErrorStrategy::Continue,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let result = engine.execute(&workflow, json!({})).await.unwrap();
@@ -286,11 +312,15 @@ async fn test_workflow_error_fallback() {
             on_error: ErrorStrategy::Fallback {
                 value: json!({"default": true}),
             },
+            state_lock_keys: vec![],
+            inject_context: vec![],
         }],
         on_error: ErrorStrategy::Fallback {
             value: json!({"default": true}),
         },
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let result = engine.execute(&workflow, json!({})).await.unwrap();
@@ -320,6 +350,8 @@ async fn test_workflow_data_passing() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
             WorkflowStep {
                 id: "consumer".to_string(),
@@ -331,10 +363,14 @@ async fn test_workflow_data_passing() {
                 loop_var: "item".to_string(),
                 loop_concurrency: 1,
                 on_error: ErrorStrategy::Fail,
+                state_lock_keys: vec![],
+                inject_context: vec![],
             },
         ],
         on_error: ErrorStrategy::Fail,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let result = engine.execute(&workflow, json!({"value": "test"})).await.unwrap();
@@ -364,9 +400,13 @@ async fn test_empty_loop() {
             loop_var: "item".to_string(),
             loop_concurrency: 1,
             on_error: ErrorStrategy::Fail,
+            state_lock_keys: vec![],
+            inject_context: vec![],
         }],
         on_error: ErrorStrategy::Fail,
         tags: vec![],
+        output_mapping: None,
+        output_transform: None,
     };
 
     let result = engine.execute(&workflow, json!({"items": []})).await.unwrap();
@@ -375,3 +415,177 @@ async fn test_empty_loop() {
     let loop_results = result["steps"]["empty"].as_array().unwrap();
     assert_eq!(loop_results.len(), 0);
 }
+
+/// Tool that performs a non-atomic read-modify-write on a shared counter.
+/// Parallel steps are polled in turn, so yielding between the read and the
+/// write lets every unserialized step read the same value.
+struct CounterToolHandler {
+    counter: AtomicUsize,
+}
+
+#[async_trait]
+impl ToolPort for CounterToolHandler {
+    async fn execute_tool(&self, _name: &str, _args: Value) -> anyhow::Result<Value> {
+        let current = self.counter.load(Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        self.counter.store(current + 1, Ordering::SeqCst);
+        Ok(json!({"counter": current + 1}))
+    }
+
+    async fn list_tools(&self) -> anyhow::Result<Vec<crate::domain::Tool>> {
+        Ok(vec![])
+    }
+}
+
+/// Tool whose calls only return once `barrier` has as many calls waiting as it has parties
+struct BarrierToolHandler {
+    barrier: tokio::sync::Barrier,
+}
+
+#[async_trait]
+impl ToolPort for BarrierToolHandler {
+    async fn execute_tool(&self, _name: &str, _args: Value) -> anyhow::Result<Value> {
+        self.barrier.wait().await;
+        Ok(json!({}))
+    }
+
+    async fn list_tools(&self) -> anyhow::Result<Vec<crate::domain::Tool>> {
+        Ok(vec![])
+    }
+}
+
+fn counter_workflow(lock_keys: &[Vec<&str>]) -> WorkflowConfig {
+    WorkflowConfig {
+        name: "counter".to_string(),
+        description: "Parallel steps touching a shared counter".to_string(),
+        input_schema: json!({}),
+        output_schema: None,
+        steps: lock_keys
+            .iter()
+            .enumerate()
+            .map(|(i, keys)| WorkflowStep {
+                id: format!("increment_{}", i),
+                tool: "increment".to_string(),
+                args: None,
+                depends_on: vec![],
+                condition: None,
+                loop_over: None,
+                loop_var: "item".to_string(),
+                loop_concurrency: 1,
+                on_error: ErrorStrategy::Fail,
+                state_lock_keys: keys.iter().map(|k| k.to_string()).collect(),
+                inject_context: vec![],
+            })
+            .collect(),
+        on_error: ErrorStrategy::Fail,
+        tags: vec![],
+        output_mapping: None,
+        output_transform: None,
+    }
+}
+
+#[tokio::test]
+async fn test_state_lock_keys_serialize_overlapping_steps() {
+    let handler = Arc::new(CounterToolHandler {
+        counter: AtomicUsize::new(0),
+    });
+    let engine = WorkflowEngine::new(handler.clone());
+
+    // Keys are listed in different orders to exercise lock ordering
+    let workflow = counter_workflow(&[
+        vec!["counter"],
+        vec!["other", "counter"],
+        vec!["counter", "other"],
+        vec!["counter", "counter"],
+    ]);
+    let result = engine.execute(&workflow, json!({})).await.unwrap();
+
+    assert!(result["success"].as_bool().unwrap());
+    assert_eq!(handler.counter.load(Ordering::SeqCst), 4);
+
+    // Locks are dropped once no step holds them
+    assert!(engine.state_locks.is_empty());
+}
+
+#[tokio::test]
+async fn test_steps_without_shared_keys_run_concurrently() {
+    let handler = Arc::new(BarrierToolHandler {
+        barrier: tokio::sync::Barrier::new(3),
+    });
+    let engine = WorkflowEngine::new(handler);
+
+    // Disjoint keys don't block each other, so all three steps reach the
+    // barrier together; serialized, the first would wait forever
+    let workflow = counter_workflow(&[vec!["a"], vec!["b"], vec![]]);
+    let result = tokio::time::timeout(Duration::from_secs(5), engine.execute(&workflow, json!({})))
+        .await
+        .expect("steps with disjoint keys were serialized")
+        .unwrap();
+
+    assert!(result["success"].as_bool().unwrap());
+}
+
+#[tokio::test]
+async fn test_output_mapping_reshapes_result() {
+    let handler = Arc::new(MockToolHandler::new());
+    let engine = WorkflowEngine::new(handler.clone());
+
+    let mut workflow = counter_workflow(&[vec![]]);
+    workflow.output_mapping = Some(
+        r#"{"tool": "{{ steps.increment_0.result.tool }}", "ok": {{ steps.increment_0.success }}, "x": {{ input.x }}}"#
+            .to_string(),
+    );
+    let result = engine.execute(&workflow, json!({"x": 7})).await.unwrap();
+    assert_eq!(result, json!({"tool": "increment", "ok": true, "x": 7}));
+
+    // The script wins when both are set
+    workflow.output_transform = Some(r#"#{ tool: steps.increment_0.result.tool, doubled: input.x * 2 }"#.to_string());
+    let result = engine.execute(&workflow, json!({"x": 7})).await.unwrap();
+    assert_eq!(result, json!({"tool": "increment", "doubled": 14}));
+
+    workflow.output_transform = None;
+    workflow.output_mapping = Some("not json".to_string());
+    assert!(engine.execute(&workflow, json!({"x": 7})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_inject_context_adds_caller_details_to_args() {
+    use crate::config::ContextField;
+    use crate::domain::execution::ExecutionContext;
+
+    let handler = Arc::new(MockToolHandler::new());
+    let engine = WorkflowEngine::new(handler.clone());
+
+    let mut workflow = counter_workflow(&[vec![]]);
+    workflow.steps[0].args = Some(json!({"event": "login"}));
+    workflow.steps[0].inject_context = vec![
+        ContextField::CallerIp,
+        ContextField::AuthIdentity,
+        ContextField::TraceId,
+        ContextField::SessionId,
+        ContextField::Timestamp,
+    ];
+
+    let context = ExecutionContext {
+        caller_ip: Some("10.0.0.7".to_string()),
+        identity: Some("alice".to_string()),
+        trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+        session_id: None,
+        ..Default::default()
+    };
+    let timestamp = context.timestamp.to_rfc3339();
+    let result = context.scope(engine.execute(&workflow, json!({}))).await.unwrap();
+
+    let args = &result["steps"]["increment_0"]["args"];
+    assert_eq!(args["event"], "login");
+    assert_eq!(args["_ctx_caller_ip"], "10.0.0.7");
+    assert_eq!(args["_ctx_identity"], "alice");
+    assert_eq!(args["_ctx_trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(args["_ctx_session_id"], Value::Null);
+    assert_eq!(args["_ctx_timestamp"], json!(timestamp));
+
+    // Steps without inject_context get their args untouched
+    workflow.steps[0].inject_context.clear();
+    let result = engine.execute(&workflow, json!({})).await.unwrap();
+    assert_eq!(result["steps"]["increment_0"]["args"], json!({"event": "login"}));
+}
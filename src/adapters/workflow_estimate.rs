@@ -0,0 +1,198 @@
+//! Token and cost estimates for workflows
+//!
+//! A step calling an LLM-strategy tool is estimated from the tool's system
+//! prompt and the prompt the step would send, priced with the `pricing` entry
+//! for the tool's provider and model. Other steps cost nothing. Every step is
+//! counted once, including loop steps and steps behind conditions.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+use crate::adapters::mock_strategy::DEFAULT_LLM_PROMPT;
+use crate::adapters::workflow_engine::render_args;
+use crate::config::{LLMConfig, MockStrategyType, ModelPricing, ToolConfig, WorkflowConfig, WorkflowStep};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepEstimate {
+    pub step_id: String,
+    pub tokens: u32,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowEstimate {
+    pub estimated_tokens: u32,
+    pub estimated_cost_usd: f64,
+    pub breakdown: Vec<StepEstimate>,
+    /// LLM steps whose model has no `pricing` entry; their cost is counted as 0
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unpriced_steps: Vec<String>,
+}
+
+/// LLM settings of a tool using the LLM mock strategy
+pub fn llm_config(tool: &ToolConfig) -> Option<&LLMConfig> {
+    tool.mock
+        .as_ref()
+        .filter(|mock| matches!(mock.strategy, MockStrategyType::LLM))
+        .and_then(|mock| mock.llm.as_ref())
+}
+
+/// Estimate the tokens and cost of running a workflow with `input`.
+/// `count_tokens` counts the tokens of a text for an LLM.
+pub fn estimate_workflow(
+    workflow: &WorkflowConfig,
+    tools: &[ToolConfig],
+    pricing: &[ModelPricing],
+    input: &Value,
+    count_tokens: impl Fn(&LLMConfig, &str) -> u32,
+) -> Result<WorkflowEstimate, String> {
+    let mut estimate = WorkflowEstimate {
+        estimated_tokens: 0,
+        estimated_cost_usd: 0.0,
+        breakdown: Vec::with_capacity(workflow.steps.len()),
+        unpriced_steps: Vec::new(),
+    };
+
+    for step in dag_order(&workflow.steps)? {
+        let llm = tools.iter().find(|t| t.name == step.tool).and_then(llm_config);
+        let (tokens, cost) = match llm {
+            Some(llm) => {
+                let system_prompt = llm.system_prompt.as_deref().unwrap_or_default();
+                let tokens = count_tokens(llm, system_prompt) + count_tokens(llm, &step_prompt(step, input));
+                let price = pricing
+                    .iter()
+                    .find(|p| p.provider == llm.provider && p.model == llm.model)
+                    .map(|p| p.input_price());
+                if price.is_none() {
+                    estimate.unpriced_steps.push(step.id.clone());
+                }
+                (tokens, tokens as f64 * price.unwrap_or_default())
+            }
+            None => (0, 0.0),
+        };
+
+        estimate.estimated_tokens += tokens;
+        estimate.estimated_cost_usd += cost;
+        estimate.breakdown.push(StepEstimate {
+            step_id: step.id.clone(),
+            tokens,
+            cost,
+        });
+    }
+
+    Ok(estimate)
+}
+
+/// Prompt an LLM step would send. Templates that depend on earlier step
+/// outputs can't be rendered ahead of time, so they are counted as written.
+fn step_prompt(step: &WorkflowStep, input: &Value) -> String {
+    let Some(args) = &step.args else {
+        return DEFAULT_LLM_PROMPT.to_string();
+    };
+    let context = json!({ "input": input, "steps": {} });
+    let args = render_args(args, &context).unwrap_or_else(|_| args.clone());
+
+    match args.get("prompt") {
+        Some(Value::String(prompt)) => prompt.clone(),
+        Some(prompt) => prompt.to_string(),
+        None => DEFAULT_LLM_PROMPT.to_string(),
+    }
+}
+
+/// Steps in the order they run: each wave holds the steps whose dependencies are done
+fn dag_order(steps: &[WorkflowStep]) -> Result<Vec<&WorkflowStep>, String> {
+    let mut ordered = Vec::with_capacity(steps.len());
+    let mut done: HashSet<&str> = HashSet::new();
+
+    while ordered.len() < steps.len() {
+        let ready: Vec<&WorkflowStep> = steps
+            .iter()
+            .filter(|s| !done.contains(s.id.as_str()))
+            .filter(|s| s.depends_on.iter().all(|dep| done.contains(dep.as_str())))
+            .collect();
+        if ready.is_empty() {
+            return Err("Workflow steps have missing dependencies or a cycle".to_string());
+        }
+        done.extend(ready.iter().map(|s| s.id.as_str()));
+        ordered.extend(ready);
+    }
+
+    Ok(ordered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LLMProvider;
+
+    fn workflow() -> WorkflowConfig {
+        serde_json::from_value(json!({
+            "name": "report",
+            "description": "Summarize and store a topic",
+            "steps": [
+                {"id": "store", "tool": "save", "depends_on": ["summarize"]},
+                {"id": "summarize", "tool": "summarizer", "args": {"prompt": "Summarize {{ input.topic }}"}}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn tools() -> Vec<ToolConfig> {
+        serde_json::from_value(json!([
+            {
+                "name": "summarizer",
+                "description": "LLM summary",
+                "input_schema": {"type": "object"},
+                "mock": {
+                    "strategy": "llm",
+                    "llm": {"provider": "openai", "model": "gpt-4o", "system_prompt": "Be brief."}
+                }
+            },
+            {
+                "name": "save",
+                "description": "Static save",
+                "input_schema": {"type": "object"},
+                "static_response": {"ok": true}
+            }
+        ]))
+        .unwrap()
+    }
+
+    /// One token per character makes the expected counts easy to read
+    fn count_chars(_: &LLMConfig, text: &str) -> u32 {
+        text.len() as u32
+    }
+
+    #[test]
+    fn test_llm_steps_are_priced_in_dag_order() {
+        let pricing = [ModelPricing {
+            provider: LLMProvider::OpenAI,
+            model: "gpt-4o".to_string(),
+            price_per_token: 0.5,
+            input_price_per_token: None,
+            output_price_per_token: None,
+        }];
+        let input = json!({"topic": "rust"});
+
+        let estimate = estimate_workflow(&workflow(), &tools(), &pricing, &input, count_chars).unwrap();
+
+        // "Be brief." + "Summarize rust"
+        assert_eq!(estimate.estimated_tokens, 9 + 14);
+        assert_eq!(estimate.estimated_cost_usd, 11.5);
+        let steps: Vec<_> = estimate.breakdown.iter().map(|s| (s.step_id.as_str(), s.tokens)).collect();
+        assert_eq!(steps, [("summarize", 23), ("store", 0)]);
+        assert!(estimate.unpriced_steps.is_empty());
+    }
+
+    #[test]
+    fn test_unpriced_models_and_cycles() {
+        let estimate = estimate_workflow(&workflow(), &tools(), &[], &json!({}), count_chars).unwrap();
+        assert_eq!(estimate.estimated_cost_usd, 0.0);
+        assert_eq!(estimate.unpriced_steps, ["summarize"]);
+
+        let mut cyclic = workflow();
+        cyclic.steps[1].depends_on = vec!["store".to_string()];
+        assert!(estimate_workflow(&cyclic, &tools(), &[], &json!({}), count_chars).is_err());
+    }
+}
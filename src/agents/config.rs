@@ -1,12 +1,14 @@
 //! Configuration types for AI Agents
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 use super::domain::AgentType;
 
 /// Configuration for an AI agent
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct AgentConfig {
     /// Unique agent name
     pub name: String,
@@ -62,6 +64,43 @@ pub struct AgentConfig {
     /// Max tokens override (if not set, uses LLM config default)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
+    /// Sequences that end a completion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Nucleus sampling probability mass (not supported by Ollama)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Frequency penalty (OpenAI, Azure and Gemini only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty (OpenAI, Azure and Gemini only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Sampling seed for reproducible completions (OpenAI, Azure and Gemini only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Let user messages switch a multi-turn agent's persona with `@persona:<name>`
+    #[serde(default)]
+    pub persona_commands: bool,
+    /// System prompts by persona name, selected with `@persona:<name>`
+    /// (`@persona:default` restores `system_prompt`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub personas: HashMap<String, String>,
+    /// Glob patterns of tools a ReAct agent may only call once an operator approves
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub require_confirmation_for: Vec<String>,
+    /// How long a tool call waits for approval before it is denied
+    #[serde(default = "default_confirmation_timeout_ms")]
+    pub confirmation_timeout_ms: u64,
+    /// Most earlier messages sent with each request of a multi-turn or ReAct
+    /// agent, on top of the memory strategy and regardless of the token budget.
+    /// The system prompt is always sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_window: Option<usize>,
+    /// Most tokens of a tool result a ReAct agent feeds back to its model;
+    /// longer results are cut and end with `...[truncated]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_result_tokens: Option<u32>,
 }
 
 fn default_input_schema() -> Value {
@@ -71,6 +110,11 @@ fn default_input_schema() -> Value {
             "prompt": {
                 "type": "string",
                 "description": "The input prompt for the agent"
+            },
+            "images": {
+                "type": "array",
+                "description": "Optional images for the agent: URLs, data: URLs or {media_type, data} objects",
+                "items": {}
             }
         },
         "required": ["prompt"]
@@ -85,8 +129,53 @@ fn default_timeout() -> u64 {
     120
 }
 
+fn default_confirmation_timeout_ms() -> u64 {
+    300_000
+}
+
+/// Settings shared by all agents (`[agent_defaults]`)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct AgentDefaults {
+    /// Text put before every agent's system prompt, e.g. a safety preamble
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_system_prefix: Option<String>,
+    /// Text put after every agent's system prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_system_suffix: Option<String>,
+}
+
+impl AgentDefaults {
+    pub fn is_empty(&self) -> bool {
+        self.default_system_prefix.is_none() && self.default_system_suffix.is_none()
+    }
+
+    /// `config` with the prefix and suffix around its system prompt and
+    /// persona prompts. They are added before templates are rendered, so
+    /// they can use input variables too.
+    pub fn apply(&self, config: &AgentConfig) -> AgentConfig {
+        let mut config = config.clone();
+        if self.is_empty() {
+            return config;
+        }
+        config.system_prompt = self.wrap(&config.system_prompt);
+        for prompt in config.personas.values_mut() {
+            *prompt = self.wrap(prompt);
+        }
+        config
+    }
+
+    fn wrap(&self, prompt: &str) -> String {
+        [self.default_system_prefix.as_deref(), Some(prompt), self.default_system_suffix.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
 /// LLM provider configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct LlmProviderConfig {
     /// Provider type
     pub provider: LlmProviderType,
@@ -113,8 +202,23 @@ fn default_stream() -> bool {
     true
 }
 
+impl From<&crate::config::LLMConfig> for LlmProviderConfig {
+    /// Provider settings of an LLM mock strategy
+    fn from(config: &crate::config::LLMConfig) -> Self {
+        Self {
+            provider: LlmProviderType::from(&config.provider),
+            model: config.model.clone(),
+            api_key_env: config.api_key_env.clone(),
+            base_url: config.base_url.clone(),
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            stream: config.stream,
+        }
+    }
+}
+
 /// Supported LLM providers
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LlmProviderType {
     /// OpenAI (GPT-4, GPT-3.5, etc.)
@@ -130,6 +234,23 @@ pub enum LlmProviderType {
     /// Azure OpenAI
     #[serde(alias = "azure")]
     AzureOpenAI,
+    /// A provider registered under this name in the
+    /// [`ProviderRegistry`](crate::agents::llm::ProviderRegistry)
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl From<&crate::config::LLMProvider> for LlmProviderType {
+    fn from(provider: &crate::config::LLMProvider) -> Self {
+        use crate::config::LLMProvider;
+        match provider {
+            LLMProvider::OpenAI => LlmProviderType::OpenAI,
+            LLMProvider::Anthropic => LlmProviderType::Anthropic,
+            LLMProvider::Gemini => LlmProviderType::Gemini,
+            LLMProvider::Ollama => LlmProviderType::Ollama,
+            LLMProvider::AzureOpenAI => LlmProviderType::AzureOpenAI,
+        }
+    }
 }
 
 impl std::fmt::Display for LlmProviderType {
@@ -140,12 +261,13 @@ impl std::fmt::Display for LlmProviderType {
             LlmProviderType::Gemini => write!(f, "gemini"),
             LlmProviderType::Ollama => write!(f, "ollama"),
             LlmProviderType::AzureOpenAI => write!(f, "azure"),
+            LlmProviderType::Custom(name) => write!(f, "{}", name),
         }
     }
 }
 
 /// Memory/persistence configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct MemoryConfig {
     /// Storage backend type
     #[serde(default)]
@@ -162,6 +284,9 @@ pub struct MemoryConfig {
     /// Database URL for database storage
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_url: Option<String>,
+    /// Periodically compress old messages into a summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<SummaryConfig>,
 }
 
 impl Default for MemoryConfig {
@@ -172,6 +297,7 @@ impl Default for MemoryConfig {
             max_messages: default_max_messages(),
             file_path: None,
             database_url: None,
+            summary: None,
         }
     }
 }
@@ -180,8 +306,22 @@ fn default_max_messages() -> u32 {
     100
 }
 
+/// Conversation summarization settings
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SummaryConfig {
+    /// Summarize once this many messages have accumulated since the last summary
+    pub every_n_messages: u32,
+    /// Instructions given to the LLM when summarizing
+    #[serde(default = "default_summary_prompt")]
+    pub summary_prompt: String,
+}
+
+fn default_summary_prompt() -> String {
+    "Summarize the following conversation. Keep facts, decisions, names and open questions; drop pleasantries.".to_string()
+}
+
 /// Memory storage backends
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MemoryBackend {
     /// Store in memory only (lost on restart)
@@ -194,10 +334,11 @@ pub enum MemoryBackend {
 }
 
 /// Memory management strategies
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MemoryStrategy {
     /// Keep all messages (up to max_messages limit)
+    #[default]
     Full,
     /// Sliding window of most recent messages
     SlidingWindow {
@@ -213,14 +354,8 @@ pub enum MemoryStrategy {
     },
 }
 
-impl Default for MemoryStrategy {
-    fn default() -> Self {
-        Self::Full
-    }
-}
-
 /// Configuration for multi-agent orchestration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct OrchestrationConfig {
     /// Unique orchestration name
     pub name: String,
@@ -255,7 +390,7 @@ fn default_orchestration_timeout() -> u64 {
 }
 
 /// Orchestration patterns
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum OrchestrationPattern {
     /// Agents execute in sequence, passing results
@@ -268,7 +403,7 @@ pub enum OrchestrationPattern {
 }
 
 /// Reference to an agent in an orchestration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct AgentReference {
     /// Agent name
     pub agent: String,
@@ -284,7 +419,7 @@ pub struct AgentReference {
 }
 
 /// Strategies for merging results in collaborative orchestration
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MergeStrategy {
     /// Concatenate all outputs
@@ -298,3 +433,36 @@ pub enum MergeStrategy {
         script: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_agent_defaults_wrap_system_and_persona_prompts() {
+        let config: AgentConfig = serde_json::from_value(json!({
+            "name": "support",
+            "description": "Support agent",
+            "llm": {"provider": "openai", "model": "gpt-4o"},
+            "system_prompt": "You help {{user}}.",
+            "personas": {"pirate": "You are a pirate"}
+        }))
+        .unwrap();
+        let defaults = AgentDefaults {
+            default_system_prefix: Some("Never share secrets.".to_string()),
+            default_system_suffix: Some("Answer in English.".to_string()),
+        };
+
+        let applied = defaults.apply(&config);
+        assert_eq!(applied.system_prompt, "Never share secrets.\n\nYou help {{user}}.\n\nAnswer in English.");
+        assert_eq!(applied.personas["pirate"], "Never share secrets.\n\nYou are a pirate\n\nAnswer in English.");
+
+        let prefix_only = AgentDefaults {
+            default_system_prefix: Some("Never share secrets.".to_string()),
+            default_system_suffix: None,
+        };
+        assert_eq!(prefix_only.apply(&config).system_prompt, "Never share secrets.\n\nYou help {{user}}.");
+        assert_eq!(AgentDefaults::default().apply(&config).system_prompt, config.system_prompt);
+    }
+}
@@ -0,0 +1,155 @@
+//! Operator approval of agent tool calls
+//!
+//! A ReAct agent whose `require_confirmation_for` patterns match a tool does
+//! not call it straight away: it registers the call here, emits a
+//! `tool_confirmation_required` chunk with the confirmation token and waits
+//! for an operator to approve or deny it (or for `confirmation_timeout_ms`).
+
+use dashmap::DashMap;
+use serde::Serialize;
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Tool result the agent sees for a call the operator denied
+pub const DENIED_BY_OPERATOR: &str = "execution denied by operator";
+
+/// Tool result the agent sees for a call nobody confirmed in time
+pub const CONFIRMATION_TIMED_OUT: &str = "execution denied: confirmation timed out";
+
+/// Whether `tool_name` matches one of the glob `patterns`
+pub fn requires_confirmation(patterns: &[String], tool_name: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        glob::Pattern::new(pattern)
+            .map(|p| p.matches(tool_name))
+            .unwrap_or(false)
+    })
+}
+
+/// A tool call waiting for an operator's decision
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingConfirmation {
+    pub token: String,
+    pub agent_name: String,
+    pub tool_name: String,
+    pub arguments: Value,
+    /// Unix epoch milliseconds when the agent asked for approval
+    pub requested_at: u64,
+}
+
+/// Outcome of waiting for a confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationDecision {
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+/// Tool calls waiting for approval, keyed by confirmation token
+#[derive(Default)]
+pub struct ToolConfirmations {
+    pending: DashMap<String, (PendingConfirmation, oneshot::Sender<bool>)>,
+}
+
+impl ToolConfirmations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tool call awaiting approval. Returns its confirmation token
+    /// and the receiver the decision arrives on.
+    pub fn request(
+        &self,
+        agent_name: &str,
+        tool_name: &str,
+        arguments: Value,
+    ) -> (String, oneshot::Receiver<bool>) {
+        let token = Uuid::new_v4().to_string();
+        let (sender, receiver) = oneshot::channel();
+        let pending = PendingConfirmation {
+            token: token.clone(),
+            agent_name: agent_name.to_string(),
+            tool_name: tool_name.to_string(),
+            arguments,
+            requested_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        };
+        self.pending.insert(token.clone(), (pending, sender));
+        (token, receiver)
+    }
+
+    /// Wait up to `timeout` for the decision on `token`, dropping it if none arrives
+    pub async fn wait(
+        &self,
+        token: &str,
+        receiver: oneshot::Receiver<bool>,
+        timeout: Duration,
+    ) -> ConfirmationDecision {
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(true)) => ConfirmationDecision::Approved,
+            Ok(_) => ConfirmationDecision::Denied,
+            Err(_) => {
+                self.pending.remove(token);
+                ConfirmationDecision::TimedOut
+            }
+        }
+    }
+
+    /// Approve or deny a pending call of `agent_name`. Returns false if there
+    /// is no such call (unknown token, another agent's, or already decided).
+    pub fn resolve(&self, agent_name: &str, token: &str, approved: bool) -> bool {
+        match self.pending.remove_if(token, |_, (pending, _)| pending.agent_name == agent_name) {
+            Some((_, (_, sender))) => sender.send(approved).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Calls of `agent_name` waiting for approval, oldest first
+    pub fn list(&self, agent_name: &str) -> Vec<PendingConfirmation> {
+        let mut pending: Vec<PendingConfirmation> = self
+            .pending
+            .iter()
+            .filter(|entry| entry.value().0.agent_name == agent_name)
+            .map(|entry| entry.value().0.clone())
+            .collect();
+        pending.sort_by_key(|p| p.requested_at);
+        pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_requires_confirmation_matches_globs() {
+        let patterns = vec!["delete_*".to_string(), "drop_table".to_string()];
+        assert!(requires_confirmation(&patterns, "delete_user"));
+        assert!(requires_confirmation(&patterns, "drop_table"));
+        assert!(!requires_confirmation(&patterns, "get_user"));
+        assert!(!requires_confirmation(&[], "delete_user"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_and_timeout() {
+        let confirmations = ToolConfirmations::new();
+
+        let (token, receiver) = confirmations.request("ops", "delete_user", json!({"id": 1}));
+        assert_eq!(confirmations.list("ops")[0].tool_name, "delete_user");
+        assert!(confirmations.list("other").is_empty());
+        assert!(!confirmations.resolve("other", &token, true));
+        assert!(confirmations.resolve("ops", &token, false));
+        assert!(!confirmations.resolve("ops", &token, true));
+        let decision = confirmations.wait(&token, receiver, Duration::from_secs(1)).await;
+        assert_eq!(decision, ConfirmationDecision::Denied);
+
+        let (token, receiver) = confirmations.request("ops", "delete_user", json!({}));
+        let decision = confirmations.wait(&token, receiver, Duration::from_millis(10)).await;
+        assert_eq!(decision, ConfirmationDecision::TimedOut);
+        assert!(confirmations.list("ops").is_empty());
+    }
+}
@@ -18,8 +18,10 @@ use std::sync::Arc;
 use serde_json::Value;
 use tera::{Context, Tera};
 
+use crate::adapters::metrics_handler::MetricsCollector;
 use crate::agents::config::AgentConfig;
-use crate::agents::domain::AgentType;
+use crate::agents::confirmation::ToolConfirmations;
+use crate::agents::domain::{AgentType, ImagePart};
 use crate::agents::error::AgentResult;
 use crate::agents::llm::LlmProvider;
 use crate::agents::memory::ConversationStore;
@@ -138,6 +140,16 @@ pub fn render_user_prompt(prompt_template: Option<&str>, input: &Value) -> Strin
     }
 }
 
+/// Images of an agent input's `images` array (URLs, `data:` URLs or
+/// `{"media_type", "data"}` objects), sent along with the user prompt
+pub fn input_images(input: &Value) -> Vec<ImagePart> {
+    input
+        .get("images")
+        .and_then(Value::as_array)
+        .map(|images| images.iter().filter_map(ImagePart::from_value).collect())
+        .unwrap_or_default()
+}
+
 /// Generate a fallback prompt from input
 /// First tries the "prompt" field, then serializes structured input as JSON
 fn fallback_prompt(input: &Value) -> String {
@@ -151,10 +163,11 @@ fn fallback_prompt(input: &Value) -> String {
     // For structured input (custom schema), serialize as JSON
     // This allows the LLM to work directly with the schema fields
     if let Some(obj) = input.as_object() {
-        // Filter out session_id as it's not part of the actual input
+        // Filter out session_id as it's not part of the actual input, and
+        // images, which are sent as image parts
         let filtered: serde_json::Map<String, Value> = obj
             .iter()
-            .filter(|(k, _)| *k != "session_id")
+            .filter(|(k, _)| *k != "session_id" && *k != "images")
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
 
@@ -187,6 +200,8 @@ pub fn create_agent(
     llm_provider: Arc<dyn LlmProvider>,
     memory_store: Arc<dyn ConversationStore>,
     tool_handler: Arc<dyn ToolPort>,
+    metrics: Option<Arc<MetricsCollector>>,
+    confirmations: Arc<ToolConfirmations>,
 ) -> AgentResult<Arc<dyn Agent>> {
     match config.agent_type {
         AgentType::SingleTurn => {
@@ -194,11 +209,12 @@ pub fn create_agent(
             Ok(Arc::new(agent))
         }
         AgentType::MultiTurn => {
-            let agent = MultiTurnAgent::new(config, llm_provider, memory_store);
+            let agent = MultiTurnAgent::new(config, llm_provider, memory_store, metrics);
             Ok(Arc::new(agent))
         }
         AgentType::ReAct => {
-            let agent = ReActAgent::new(config, llm_provider, memory_store, tool_handler);
+            let agent = ReActAgent::new(config, llm_provider, memory_store, tool_handler)
+                .with_confirmations(confirmations);
             Ok(Arc::new(agent))
         }
     }
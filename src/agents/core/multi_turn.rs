@@ -4,22 +4,33 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use serde_json::{json, Value};
+use tracing::Instrument;
 use uuid::Uuid;
 
-use super::{render_system_prompt, render_user_prompt, Agent};
+use super::{input_images, render_system_prompt, render_user_prompt, Agent};
+use crate::adapters::metrics_handler::MetricsCollector;
 use crate::agents::config::AgentConfig;
 use crate::agents::domain::{
-    AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender, Message,
+    AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender, ConversationSession, Message,
 };
 use crate::agents::llm::{CompletionRequest, LlmProvider};
-use crate::agents::memory::{apply_strategy, ConversationStore};
+use crate::agents::memory::{apply_history_window, apply_strategy, ConversationStore, ConversationSummary};
+use crate::domain::execution::ExecutionContext;
 use futures::StreamExt;
 
+/// Directive in a user message that switches the agent's persona
+const PERSONA_DIRECTIVE: &str = "@persona:";
+/// Persona name restoring the agent's own system prompt
+const DEFAULT_PERSONA: &str = "default";
+/// Session metadata key holding the active persona's name
+const PERSONA_METADATA_KEY: &str = "persona";
+
 /// Multi-turn conversational agent with history
 pub struct MultiTurnAgent {
     config: AgentConfig,
     llm: Arc<dyn LlmProvider>,
     memory: Arc<dyn ConversationStore>,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 impl MultiTurnAgent {
@@ -28,14 +39,16 @@ impl MultiTurnAgent {
         config: AgentConfig,
         llm: Arc<dyn LlmProvider>,
         memory: Arc<dyn ConversationStore>,
+        metrics: Option<Arc<MetricsCollector>>,
     ) -> Self {
-        Self { config, llm, memory }
+        Self { config, llm, memory, metrics }
     }
 
     async fn execute_internal(
         config: AgentConfig,
         llm: Arc<dyn LlmProvider>,
         memory: Arc<dyn ConversationStore>,
+        metrics: Option<Arc<MetricsCollector>>,
         input: Value,
         session_id: Option<String>,
         sender: AgentStreamSender,
@@ -58,16 +71,39 @@ impl MultiTurnAgent {
             }
         };
 
-        // Render system prompt with input values (Tera templating)
-        let rendered_system_prompt = render_system_prompt(&config.system_prompt, &input);
-
         // Render user prompt from template or use raw prompt field
         let prompt = render_user_prompt(config.prompt_template.as_deref(), &input);
 
+        if config.persona_commands {
+            switch_persona(&config, &mut session, &prompt, &input);
+        }
+
+        // Render system prompt with input values (Tera templating)
+        let rendered_system_prompt = render_system_prompt(active_system_prompt(&config, &session), &input);
+
         // Add user message to session
-        let user_message = Message::user(&prompt);
+        let user_message = Message::user(&prompt).with_images(input_images(&input));
         session.add_message(user_message.clone());
 
+        // Compress old messages into a summary once enough have accumulated
+        if let Some(summary_config) = &config.memory.summary {
+            let summary = ConversationSummary::new(summary_config.clone());
+            if summary.needs_summary(&session.messages) {
+                match summary.compress(llm.as_ref(), &mut session.messages).await {
+                    Ok(true) => {
+                        if let Some(metrics) = &metrics {
+                            metrics.agent_conversation_summaries.inc();
+                        }
+                        if let Err(e) = memory.save(&session).await {
+                            tracing::warn!("Failed to save summarized session: {}", e);
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Failed to summarize conversation: {}", e),
+                }
+            }
+        }
+
         // Build messages with system prompt + history
         let mut messages = vec![Message::system(&rendered_system_prompt)];
 
@@ -77,7 +113,7 @@ impl MultiTurnAgent {
             &config.memory.strategy,
             None,
         );
-        messages.extend(history_messages);
+        messages.extend(apply_history_window(history_messages, config.history_window));
 
         // Build completion request
         let request = CompletionRequest {
@@ -85,6 +121,11 @@ impl MultiTurnAgent {
             model: Some(config.llm.model.clone()),
             temperature: config.temperature.or(config.llm.temperature),
             max_tokens: config.max_tokens.or(config.llm.max_tokens),
+            stop: config.stop.clone(),
+            top_p: config.top_p,
+            frequency_penalty: config.frequency_penalty,
+            presence_penalty: config.presence_penalty,
+            seed: config.seed,
             stream: true,
             ..Default::default()
         };
@@ -138,6 +179,7 @@ impl MultiTurnAgent {
             output: json!({ "content": full_content }),
             tool_calls: Vec::new(),
             reasoning_steps: Vec::new(),
+            trace: Vec::new(),
             session_id: Some(session_id),
             iterations: 1,
             usage: None,
@@ -148,6 +190,48 @@ impl MultiTurnAgent {
     }
 }
 
+/// Name in the first `@persona:<name>` directive of a message
+fn persona_directive(text: &str) -> Option<&str> {
+    let start = text.find(PERSONA_DIRECTIVE)? + PERSONA_DIRECTIVE.len();
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+/// System prompt of the session's active persona, or the agent's own
+fn active_system_prompt<'a>(config: &'a AgentConfig, session: &ConversationSession) -> &'a str {
+    session
+        .metadata
+        .get(PERSONA_METADATA_KEY)
+        .and_then(|name| name.as_str())
+        .and_then(|name| config.personas.get(name))
+        .unwrap_or(&config.system_prompt)
+}
+
+/// Apply a persona directive in `prompt` to the session. The switch is
+/// recorded in the history as a system message holding the new prompt.
+fn switch_persona(config: &AgentConfig, session: &mut ConversationSession, prompt: &str, input: &Value) {
+    let Some(name) = persona_directive(prompt) else {
+        return;
+    };
+
+    if name == DEFAULT_PERSONA {
+        if session.metadata.remove(PERSONA_METADATA_KEY).is_none() {
+            return;
+        }
+    } else if config.personas.contains_key(name) {
+        session.metadata.insert(PERSONA_METADATA_KEY.to_string(), json!(name));
+    } else {
+        tracing::warn!("Agent '{}' has no persona '{}'", config.name, name);
+        return;
+    }
+
+    let system_prompt = render_system_prompt(active_system_prompt(config, session), input);
+    session.add_message(Message::system(system_prompt));
+}
+
 impl Agent for MultiTurnAgent {
     fn config(&self) -> &AgentConfig {
         &self.config
@@ -159,11 +243,172 @@ impl Agent for MultiTurnAgent {
         let config = self.config.clone();
         let llm = self.llm.clone();
         let memory = self.memory.clone();
+        let metrics = self.metrics.clone();
 
-        tokio::spawn(async move {
-            Self::execute_internal(config, llm, memory, input, session_id, sender).await;
-        });
+        tokio::spawn(ExecutionContext::propagate(
+            async move {
+                Self::execute_internal(config, llm, memory, metrics, input, session_id, sender).await;
+            }
+            .in_current_span(),
+        ));
 
         stream
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::domain::Role;
+    use crate::agents::error::{LlmError, LlmResult};
+    use crate::agents::llm::{CompletionResponse, FinishReason, LlmStream, StreamChunk};
+    use crate::agents::memory::InMemoryStore;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// LLM that records the system prompt and messages of every request
+    #[derive(Default)]
+    struct RecordingLlm {
+        system_prompts: Mutex<Vec<String>>,
+        requests: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for RecordingLlm {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn model(&self) -> &str {
+            "recording"
+        }
+
+        async fn complete(&self, _request: CompletionRequest) -> LlmResult<CompletionResponse> {
+            Err(LlmError::InvalidRequest("not supported".to_string()))
+        }
+
+        fn complete_stream(&self, request: CompletionRequest) -> LlmStream {
+            self.system_prompts.lock().unwrap().push(request.messages[0].content.clone());
+            let contents = request.messages.iter().map(|m| m.content.clone()).collect();
+            self.requests.lock().unwrap().push(contents);
+
+            let (tx, rx) = tokio::sync::mpsc::channel(2);
+            tx.try_send(Ok(StreamChunk::text("ok"))).unwrap();
+            tx.try_send(Ok(StreamChunk::finish(FinishReason::Stop, None))).unwrap();
+            LlmStream::new(rx)
+        }
+
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.len() as u32
+        }
+
+        fn context_window(&self) -> u32 {
+            8192
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            1024
+        }
+    }
+
+    fn agent(persona_commands: bool) -> (MultiTurnAgent, Arc<RecordingLlm>, Arc<InMemoryStore>) {
+        agent_with(json!({"persona_commands": persona_commands}))
+    }
+
+    /// Test agent with `overrides` applied to its config
+    fn agent_with(overrides: Value) -> (MultiTurnAgent, Arc<RecordingLlm>, Arc<InMemoryStore>) {
+        let mut config = json!({
+            "name": "chameleon",
+            "description": "Test agent",
+            "agent_type": "multi_turn",
+            "llm": {"provider": "openai", "model": "recording"},
+            "system_prompt": "You are helpful",
+            "personas": {"pirate": "You are a pirate"}
+        });
+        if let (Some(config), Some(overrides)) = (config.as_object_mut(), overrides.as_object()) {
+            config.extend(overrides.clone());
+        }
+        let config: AgentConfig = serde_json::from_value(config).unwrap();
+        let llm = Arc::new(RecordingLlm::default());
+        let memory = Arc::new(InMemoryStore::new(100));
+        let agent = MultiTurnAgent::new(config, llm.clone(), memory.clone(), None);
+        (agent, llm, memory)
+    }
+
+    async fn say(agent: &MultiTurnAgent, prompt: &str) {
+        agent
+            .execute(json!({"prompt": prompt}), Some("s1".to_string()))
+            .collect()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_persona_directives_switch_system_prompt() {
+        let (agent, llm, memory) = agent(true);
+
+        say(&agent, "hello").await;
+        say(&agent, "@persona:pirate where is the treasure?").await;
+        say(&agent, "and then?").await;
+        say(&agent, "@persona:unknown hi").await;
+        say(&agent, "@persona:default thanks").await;
+
+        assert_eq!(
+            *llm.system_prompts.lock().unwrap(),
+            [
+                "You are helpful",
+                "You are a pirate",
+                "You are a pirate",
+                "You are a pirate",
+                "You are helpful"
+            ]
+        );
+
+        // Each switch is recorded in the history; "unknown" changed nothing
+        let session = memory.load("s1").await.unwrap().unwrap();
+        let switches: Vec<_> = session
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(switches, ["You are a pirate", "You are helpful"]);
+        assert!(!session.metadata.contains_key(PERSONA_METADATA_KEY));
+    }
+
+    #[tokio::test]
+    async fn test_persona_directives_ignored_unless_enabled() {
+        let (agent, llm, _) = agent(false);
+
+        say(&agent, "@persona:pirate ahoy").await;
+
+        assert_eq!(*llm.system_prompts.lock().unwrap(), ["You are helpful"]);
+    }
+
+    #[tokio::test]
+    async fn test_history_window_drops_older_messages_from_requests() {
+        let (agent, llm, memory) = agent_with(json!({"history_window": 2}));
+
+        say(&agent, "one").await;
+        say(&agent, "two").await;
+        say(&agent, "three").await;
+
+        {
+            let requests = llm.requests.lock().unwrap();
+            assert_eq!(requests[1], ["You are helpful", "one", "ok", "two"]);
+            assert_eq!(requests[2], ["You are helpful", "two", "ok", "three"]);
+        }
+
+        // Only the requests are windowed; the session keeps everything
+        let session = memory.load("s1").await.unwrap().unwrap();
+        assert_eq!(session.messages.len(), 6);
+    }
+
+    #[test]
+    fn test_persona_directive_parsing() {
+        assert_eq!(persona_directive("@persona:pirate, ahoy"), Some("pirate"));
+        assert_eq!(persona_directive("switch to @persona:old-salt"), Some("old-salt"));
+        assert_eq!(persona_directive("@persona: pirate"), None);
+        assert_eq!(persona_directive("no directive"), None);
+    }
+}
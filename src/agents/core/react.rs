@@ -1,28 +1,61 @@
 //! ReAct agent implementation (Reasoning + Acting)
 
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use futures::StreamExt;
 use serde_json::{json, Value};
+use tracing::Instrument;
 use uuid::Uuid;
 
-use super::{render_system_prompt, render_user_prompt, Agent};
+use super::{input_images, render_system_prompt, render_user_prompt, Agent};
 use crate::agents::config::AgentConfig;
+use crate::agents::confirmation::{
+    requires_confirmation, ConfirmationDecision, ToolConfirmations, CONFIRMATION_TIMED_OUT,
+    DENIED_BY_OPERATOR,
+};
 use crate::agents::domain::{
     AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender,
-    Message, ToolCallResult, ToolDefinition,
+    Message, ToolCallResult, ToolDefinition, TraceStep,
 };
 use crate::agents::llm::{CompletionRequest, LlmProvider, ToolCallAccumulator};
-use crate::agents::memory::{apply_strategy, ConversationStore};
+use crate::agents::memory::{apply_history_window, apply_strategy, ConversationStore};
 use crate::domain::ToolPort;
+use crate::domain::execution::ExecutionContext;
+
+/// Marker ending a tool result cut to `max_tool_result_tokens`
+pub const TRUNCATION_MARKER: &str = "...[truncated]";
+
+/// `content` if it fits in `max_tokens` tokens of `llm`, otherwise its longest
+/// prefix that fits together with [`TRUNCATION_MARKER`]
+fn truncate_tool_result(content: String, max_tokens: u32, llm: &dyn LlmProvider) -> String {
+    if llm.count_tokens(&content) <= max_tokens {
+        return content;
+    }
+
+    let ends: Vec<usize> = content.char_indices().map(|(i, _)| i).collect();
+    let fits = |chars: usize| llm.count_tokens(&format!("{}{}", &content[..ends[chars]], TRUNCATION_MARKER)) <= max_tokens;
+    let (mut low, mut high) = (0, ends.len() - 1);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    format!("{}{}", &content[..ends[low]], TRUNCATION_MARKER)
+}
 
 /// ReAct agent: Reasoning + Action loop with tool calling
+#[derive(Clone)]
 pub struct ReActAgent {
     config: AgentConfig,
     llm: Arc<dyn LlmProvider>,
     memory: Arc<dyn ConversationStore>,
     tool_handler: Arc<dyn ToolPort>,
+    /// Tool calls waiting for operator approval
+    confirmations: Arc<ToolConfirmations>,
 }
 
 impl ReActAgent {
@@ -38,18 +71,24 @@ impl ReActAgent {
             llm,
             memory,
             tool_handler,
+            confirmations: Arc::new(ToolConfirmations::new()),
         }
     }
 
-    async fn execute_internal(
-        config: AgentConfig,
-        llm: Arc<dyn LlmProvider>,
-        memory: Arc<dyn ConversationStore>,
-        tool_handler: Arc<dyn ToolPort>,
-        input: Value,
-        session_id: Option<String>,
-        sender: AgentStreamSender,
-    ) {
+    /// Register tool calls that need approval in `confirmations`
+    pub fn with_confirmations(mut self, confirmations: Arc<ToolConfirmations>) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    async fn execute_internal(self, input: Value, session_id: Option<String>, sender: AgentStreamSender) {
+        let Self {
+            config,
+            llm,
+            memory,
+            tool_handler,
+            confirmations,
+        } = self;
         let start_time = Instant::now();
 
         // Send starting status
@@ -75,7 +114,7 @@ impl ReActAgent {
         let prompt = render_user_prompt(config.prompt_template.as_deref(), &input);
 
         // Add user message to session
-        let user_message = Message::user(&prompt);
+        let user_message = Message::user(&prompt).with_images(input_images(&input));
         session.add_message(user_message.clone());
 
         // Build messages with system prompt + history
@@ -87,7 +126,7 @@ impl ReActAgent {
             &config.memory.strategy,
             None,
         );
-        messages.extend(history_messages);
+        messages.extend(apply_history_window(history_messages, config.history_window));
 
         // Build tool definitions (includes regular tools, MCP tools, agent tools, and resources)
         let tools = Self::build_tool_definitions(
@@ -101,6 +140,7 @@ impl ReActAgent {
 
         let mut all_tool_calls: Vec<ToolCallResult> = Vec::new();
         let mut reasoning_steps: Vec<String> = Vec::new();
+        let mut trace: Vec<TraceStep> = Vec::new();
         let mut final_content = String::new();
 
         // ReAct loop
@@ -116,6 +156,11 @@ impl ReActAgent {
                 model: Some(config.llm.model.clone()),
                 temperature: config.temperature.or(config.llm.temperature),
                 max_tokens: config.max_tokens.or(config.llm.max_tokens),
+                stop: config.stop.clone(),
+                top_p: config.top_p,
+                frequency_penalty: config.frequency_penalty,
+                presence_penalty: config.presence_penalty,
+                seed: config.seed,
                 tools: if tools.is_empty() { None } else { Some(tools.clone()) },
                 stream: true,
                 ..Default::default()
@@ -154,6 +199,12 @@ impl ReActAgent {
             // If no tool calls, we're done (prioritize tool calls over finish_reason)
             // Some providers may return Stop even with tool calls
             if tool_calls.is_empty() {
+                trace.push(TraceStep {
+                    iteration: iteration + 1,
+                    thought: (!content.is_empty()).then(|| content.clone()),
+                    tool_calls: Vec::new(),
+                    is_final: true,
+                });
                 final_content = content;
                 break;
             }
@@ -168,6 +219,7 @@ impl ReActAgent {
             messages.push(Message::assistant_with_tools(&content, tool_calls.clone()));
 
             // Execute tool calls
+            let mut step_results: Vec<ToolCallResult> = Vec::new();
             for tool_call in &tool_calls {
                 // Send tool call status
                 if sender.send(AgentChunk::status(AgentStatus::CallingTool {
@@ -181,6 +233,40 @@ impl ReActAgent {
                     return;
                 }
 
+                // Wait for an operator to approve tools that need it
+                if requires_confirmation(&config.require_confirmation_for, &tool_call.name) {
+                    let (token, decision) =
+                        confirmations.request(&config.name, &tool_call.name, tool_call.arguments.clone());
+                    if sender.send(AgentChunk::tool_confirmation_required(tool_call, &token)).await.is_err() {
+                        confirmations.resolve(&config.name, &token, false);
+                        return;
+                    }
+
+                    let wait_start = Instant::now();
+                    let timeout = Duration::from_millis(config.confirmation_timeout_ms);
+                    let denial = match confirmations.wait(&token, decision, timeout).await {
+                        ConfirmationDecision::Approved => None,
+                        ConfirmationDecision::Denied => Some(DENIED_BY_OPERATOR),
+                        ConfirmationDecision::TimedOut => Some(CONFIRMATION_TIMED_OUT),
+                    };
+                    if let Some(denial) = denial {
+                        let tool_result = ToolCallResult::failure(
+                            tool_call.id.clone(),
+                            tool_call.name.clone(),
+                            tool_call.arguments.clone(),
+                            denial.to_string(),
+                            wait_start.elapsed().as_millis() as u64,
+                        );
+                        if sender.send(AgentChunk::tool_result(&tool_result)).await.is_err() {
+                            return;
+                        }
+                        messages.push(Message::tool_result(&tool_call.id, &json!(denial)));
+                        step_results.push(tool_result.clone());
+                        all_tool_calls.push(tool_result);
+                        continue;
+                    }
+                }
+
                 // Execute the tool
                 let tool_start = Instant::now();
                 let result = tool_handler.execute_tool(&tool_call.name, tool_call.arguments.clone()).await;
@@ -212,12 +298,24 @@ impl ReActAgent {
                     return;
                 }
 
-                // Add tool result to messages
-                messages.push(Message::tool_result(&tool_call.id, &tool_result.output));
+                // Add tool result to messages, cut to the agent's budget
+                let mut tool_message = Message::tool_result(&tool_call.id, &tool_result.output);
+                if let Some(max_tokens) = config.max_tool_result_tokens {
+                    tool_message.content = truncate_tool_result(tool_message.content, max_tokens, llm.as_ref());
+                }
+                messages.push(tool_message);
 
                 // Track tool call
+                step_results.push(tool_result.clone());
                 all_tool_calls.push(tool_result);
             }
+
+            trace.push(TraceStep {
+                iteration: iteration + 1,
+                thought: (!content.is_empty()).then(|| content.clone()),
+                tool_calls: step_results,
+                is_final: false,
+            });
         }
 
         // Save assistant response to session
@@ -231,11 +329,12 @@ impl ReActAgent {
 
         // Send complete response
         let execution_time = start_time.elapsed().as_millis() as u64;
-        let iterations = config.max_iterations.min(reasoning_steps.len() as u32 + 1);
+        let iterations = trace.len() as u32;
         let response = AgentResponse {
             output: json!({ "content": final_content }),
             tool_calls: all_tool_calls,
             reasoning_steps,
+            trace,
             session_id: Some(session_id),
             iterations,
             usage: None,
@@ -327,15 +426,15 @@ impl ReActAgent {
                     if let Some((server, tool_name)) = name_part.split_once('_') {
                         for spec in mcp_tools {
                             if let Some((spec_server, spec_tool)) = spec.split_once(':') {
-                                if spec_server == server {
-                                    if spec_tool == "*" || spec_tool == tool_name {
-                                        definitions.push(ToolDefinition {
-                                            name: tool.name.clone(),
-                                            description: tool.description.clone(),
-                                            parameters: tool.input_schema.clone(),
-                                        });
-                                        break;
-                                    }
+                                if spec_server == server
+                                    && (spec_tool == "*" || spec_tool == tool_name)
+                                {
+                                    definitions.push(ToolDefinition {
+                                        name: tool.name.clone(),
+                                        description: tool.description.clone(),
+                                        parameters: tool.input_schema.clone(),
+                                    });
+                                    break;
                                 }
                             }
                         }
@@ -356,15 +455,223 @@ impl Agent for ReActAgent {
     fn execute(&self, input: Value, session_id: Option<String>) -> AgentStream {
         let (sender, stream) = AgentStream::channel(64);
 
-        let config = self.config.clone();
-        let llm = self.llm.clone();
-        let memory = self.memory.clone();
-        let tool_handler = self.tool_handler.clone();
-
-        tokio::spawn(async move {
-            Self::execute_internal(config, llm, memory, tool_handler, input, session_id, sender).await;
-        });
+        let agent = self.clone();
+        // Keep the caller's span and execution context, so the run's log lines
+        // carry its request ID and its tool calls count toward the call depth
+        tokio::spawn(ExecutionContext::propagate(
+            async move {
+                agent.execute_internal(input, session_id, sender).await;
+            }
+            .in_current_span(),
+        ));
 
         stream
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::error::{LlmError, LlmResult};
+    use crate::agents::llm::{CompletionResponse, FinishReason, LlmStream, StreamChunk, ToolCallDelta};
+    use crate::agents::memory::InMemoryStore;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// LLM that calls `lookup` on the first turn and answers on the second
+    struct ScriptedLlm {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedLlm {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn model(&self) -> &str {
+            "scripted"
+        }
+
+        async fn complete(&self, _request: CompletionRequest) -> LlmResult<CompletionResponse> {
+            Err(LlmError::InvalidRequest("not supported".to_string()))
+        }
+
+        fn complete_stream(&self, _request: CompletionRequest) -> LlmStream {
+            let chunks = if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                vec![
+                    StreamChunk::text("I should look it up"),
+                    StreamChunk::tool_call(
+                        ToolCallDelta::new(0)
+                            .with_id("call_1")
+                            .with_name("lookup")
+                            .with_arguments(r#"{"q":"x"}"#),
+                    ),
+                    StreamChunk::finish(FinishReason::ToolCalls, None),
+                ]
+            } else {
+                vec![
+                    StreamChunk::text("The answer is 42"),
+                    StreamChunk::finish(FinishReason::Stop, None),
+                ]
+            };
+
+            let (tx, rx) = tokio::sync::mpsc::channel(chunks.len());
+            for chunk in chunks {
+                tx.try_send(Ok(chunk)).unwrap();
+            }
+            LlmStream::new(rx)
+        }
+
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.len() as u32
+        }
+
+        fn context_window(&self) -> u32 {
+            8192
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            1024
+        }
+    }
+
+    struct LookupTool;
+
+    #[async_trait]
+    impl ToolPort for LookupTool {
+        async fn execute_tool(&self, _name: &str, args: Value) -> anyhow::Result<Value> {
+            Ok(json!({"found": args["q"]}))
+        }
+
+        async fn list_tools(&self) -> anyhow::Result<Vec<crate::domain::Tool>> {
+            Ok(vec![crate::domain::Tool {
+                name: "lookup".to_string(),
+                description: "Look something up".to_string(),
+                input_schema: json!({"type": "object"}),
+                output_schema: None,
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_react_agent_collects_trace() {
+        let config: AgentConfig = serde_json::from_value(json!({
+            "name": "researcher",
+            "description": "Test agent",
+            "agent_type": "react",
+            "llm": {"provider": "openai", "model": "scripted"},
+            "system_prompt": "You are helpful",
+            "available_tools": ["lookup"]
+        }))
+        .unwrap();
+
+        let agent = ReActAgent::new(
+            config,
+            Arc::new(ScriptedLlm { calls: AtomicUsize::new(0) }),
+            Arc::new(InMemoryStore::new(100)),
+            Arc::new(LookupTool),
+        );
+
+        let response = agent.execute(json!({"prompt": "what?"}), None).collect().await.unwrap();
+
+        assert_eq!(response.iterations, 2);
+        assert_eq!(response.trace.len(), 2);
+
+        let first = &response.trace[0];
+        assert_eq!(first.thought.as_deref(), Some("I should look it up"));
+        assert_eq!(first.tool_calls.len(), 1);
+        assert_eq!(first.tool_calls[0].input, json!({"q": "x"}));
+        assert_eq!(first.tool_calls[0].output, json!({"found": "x"}));
+        assert!(!first.is_final);
+
+        let last = &response.trace[1];
+        assert_eq!(last.thought.as_deref(), Some("The answer is 42"));
+        assert!(last.is_final);
+    }
+
+    #[test]
+    fn test_oversized_tool_results_are_truncated() {
+        // ScriptedLlm counts one token per byte
+        let llm = ScriptedLlm { calls: AtomicUsize::new(0) };
+        let content = "x".repeat(100);
+
+        assert_eq!(truncate_tool_result(content.clone(), 100, &llm), content);
+        let truncated = truncate_tool_result(content, 30, &llm);
+        assert_eq!(truncated, format!("{}{}", "x".repeat(16), TRUNCATION_MARKER));
+
+        // Cuts fall on character boundaries
+        let truncated = truncate_tool_result("é".repeat(50), 20, &llm);
+        assert_eq!(truncated, format!("{}{}", "é".repeat(3), TRUNCATION_MARKER));
+        assert_eq!(truncate_tool_result("y".repeat(50), 5, &llm), TRUNCATION_MARKER);
+    }
+
+    fn confirming_agent(timeout_ms: u64, confirmations: Arc<ToolConfirmations>) -> ReActAgent {
+        let config: AgentConfig = serde_json::from_value(json!({
+            "name": "operator",
+            "description": "Test agent",
+            "agent_type": "react",
+            "llm": {"provider": "openai", "model": "scripted"},
+            "system_prompt": "You are careful",
+            "available_tools": ["lookup"],
+            "require_confirmation_for": ["look*"],
+            "confirmation_timeout_ms": timeout_ms
+        }))
+        .unwrap();
+
+        ReActAgent::new(
+            config,
+            Arc::new(ScriptedLlm { calls: AtomicUsize::new(0) }),
+            Arc::new(InMemoryStore::new(100)),
+            Arc::new(LookupTool),
+        )
+        .with_confirmations(confirmations)
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_waits_for_approval() {
+        let confirmations = Arc::new(ToolConfirmations::new());
+        let agent = confirming_agent(60_000, confirmations.clone());
+
+        let mut stream = agent.execute(json!({"prompt": "what?"}), None);
+        let mut result = None;
+        while let Some(chunk) = stream.next().await {
+            match chunk.unwrap() {
+                AgentChunk::ToolConfirmationRequired { tool_name, args, confirmation_token } => {
+                    assert_eq!(tool_name, "lookup");
+                    assert_eq!(args, json!({"q": "x"}));
+                    assert!(confirmations.resolve("operator", &confirmation_token, true));
+                }
+                AgentChunk::ToolResult { result: output, success, .. } => result = Some((output, success)),
+                _ => {}
+            }
+        }
+
+        assert_eq!(result, Some((json!({"found": "x"}), true)));
+    }
+
+    #[tokio::test]
+    async fn test_denied_and_timed_out_tool_calls_are_not_executed() {
+        let confirmations = Arc::new(ToolConfirmations::new());
+        let agent = confirming_agent(60_000, confirmations.clone());
+        let execution = tokio::spawn(agent.execute(json!({"prompt": "what?"}), None).collect());
+
+        let token = loop {
+            if let Some(pending) = confirmations.list("operator").pop() {
+                break pending.token;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        assert!(confirmations.resolve("operator", &token, false));
+
+        let response = execution.await.unwrap().unwrap();
+        let call = &response.trace[0].tool_calls[0];
+        assert!(!call.success);
+        assert_eq!(call.error.as_deref(), Some(DENIED_BY_OPERATOR));
+        assert_eq!(response.output["content"], "The answer is 42");
+
+        let agent = confirming_agent(10, Arc::new(ToolConfirmations::new()));
+        let response = agent.execute(json!({"prompt": "what?"}), None).collect().await.unwrap();
+        assert_eq!(response.trace[0].tool_calls[0].error.as_deref(), Some(CONFIRMATION_TIMED_OUT));
+    }
+}
@@ -4,13 +4,15 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use serde_json::{json, Value};
+use tracing::Instrument;
 
-use super::{render_system_prompt, render_user_prompt, Agent};
+use super::{input_images, render_system_prompt, render_user_prompt, Agent};
 use crate::agents::config::AgentConfig;
 use crate::agents::domain::{
     AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender, Message,
 };
 use crate::agents::llm::{CompletionRequest, LlmProvider};
+use crate::domain::execution::ExecutionContext;
 use futures::StreamExt;
 
 /// Single-turn agent: one request → one response, no history
@@ -46,7 +48,7 @@ impl SingleTurnAgent {
 
         let messages = vec![
             Message::system(&rendered_system_prompt),
-            Message::user(&prompt),
+            Message::user(&prompt).with_images(input_images(&input)),
         ];
 
         // Build completion request
@@ -55,6 +57,11 @@ impl SingleTurnAgent {
             model: Some(config.llm.model.clone()),
             temperature: config.temperature.or(config.llm.temperature),
             max_tokens: config.max_tokens.or(config.llm.max_tokens),
+            stop: config.stop.clone(),
+            top_p: config.top_p,
+            frequency_penalty: config.frequency_penalty,
+            presence_penalty: config.presence_penalty,
+            seed: config.seed,
             stream: true,
             ..Default::default()
         };
@@ -100,6 +107,7 @@ impl SingleTurnAgent {
             output: json!({ "content": full_content }),
             tool_calls: Vec::new(),
             reasoning_steps: Vec::new(),
+            trace: Vec::new(),
             session_id: None,
             iterations: 1,
             usage: None,
@@ -121,9 +129,12 @@ impl Agent for SingleTurnAgent {
         let config = self.config.clone();
         let llm = self.llm.clone();
 
-        tokio::spawn(async move {
-            Self::execute_internal(config, llm, input, sender).await;
-        });
+        tokio::spawn(ExecutionContext::propagate(
+            async move {
+                Self::execute_internal(config, llm, input, sender).await;
+            }
+            .in_current_span(),
+        ));
 
         stream
     }
@@ -0,0 +1,323 @@
+//! LLM cost accounting for agents
+//!
+//! Each agent's LLM provider is wrapped in a [`CostTrackingProvider`], which
+//! prices the token usage of every completion with the `pricing` entry of its
+//! provider and model. Costs add up per agent, provider and model in the
+//! [`CostLedger`] served by `GET /api/costs`, and in the
+//! `metis_llm_cost_usd_total` counter.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::adapters::metrics_handler::MetricsCollector;
+use crate::agents::config::LlmProviderType;
+use crate::agents::domain::Message;
+use crate::agents::error::LlmResult;
+use crate::agents::llm::{CompletionRequest, CompletionResponse, LlmProvider, LlmStream, TokenUsage};
+use crate::config::{ModelPricing, Settings};
+
+/// Completions of one agent with one provider and model
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentCost {
+    pub agent: String,
+    pub provider: String,
+    pub model: String,
+    pub completions: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+    /// Completions made while the model had no `pricing` entry; they cost 0
+    pub unpriced_completions: u64,
+}
+
+/// Costs of all agents
+#[derive(Debug, Serialize)]
+pub struct CostReport {
+    pub total_cost_usd: f64,
+    pub agents: Vec<AgentCost>,
+}
+
+/// Accumulated costs per agent, provider and model
+#[derive(Default)]
+pub struct CostLedger {
+    entries: Mutex<HashMap<(String, String, String), AgentCost>>,
+}
+
+impl CostLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a completion of `agent` that used `usage` and cost `cost_usd`
+    /// (`None` when unpriced)
+    pub fn record(&self, agent: &str, provider: &str, model: &str, usage: &TokenUsage, cost_usd: Option<f64>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (agent.to_string(), provider.to_string(), model.to_string());
+        let entry = entries.entry(key).or_insert_with(|| AgentCost {
+            agent: agent.to_string(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        });
+        entry.completions += 1;
+        entry.prompt_tokens += u64::from(usage.prompt_tokens);
+        entry.completion_tokens += u64::from(usage.completion_tokens);
+        match cost_usd {
+            Some(cost) => entry.cost_usd += cost,
+            None => entry.unpriced_completions += 1,
+        }
+    }
+
+    /// Costs so far, sorted by agent, provider and model
+    pub fn report(&self) -> CostReport {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut agents: Vec<AgentCost> = entries.values().cloned().collect();
+        agents.sort_by(|a, b| (&a.agent, &a.provider, &a.model).cmp(&(&b.agent, &b.provider, &b.model)));
+        CostReport {
+            total_cost_usd: agents.iter().map(|a| a.cost_usd).sum(),
+            agents,
+        }
+    }
+}
+
+/// The `pricing` entry of `provider`'s `model`
+pub fn find_pricing<'a>(
+    pricing: &'a [ModelPricing],
+    provider: &LlmProviderType,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    pricing
+        .iter()
+        .find(|p| LlmProviderType::from(&p.provider) == *provider && p.model == model)
+}
+
+/// Prices an agent's completions and records them
+#[derive(Clone)]
+struct CostRecorder {
+    agent: String,
+    provider: LlmProviderType,
+    settings: Arc<RwLock<Settings>>,
+    metrics: Arc<MetricsCollector>,
+}
+
+impl CostRecorder {
+    async fn record(&self, model: &str, usage: &TokenUsage) {
+        let cost = {
+            let settings = self.settings.read().await;
+            find_pricing(&settings.pricing, &self.provider, model)
+                .map(|p| p.cost(usage.prompt_tokens, usage.completion_tokens))
+        };
+        let provider = self.provider.to_string();
+        if let Some(cost) = cost {
+            self.metrics
+                .llm_cost_usd
+                .with_label_values(&[&self.agent, &provider, model])
+                .inc_by(cost);
+        }
+        self.metrics.llm_costs.record(&self.agent, &provider, model, usage, cost);
+    }
+}
+
+/// An agent's LLM provider that records the cost of each completion
+pub struct CostTrackingProvider {
+    inner: Arc<dyn LlmProvider>,
+    recorder: CostRecorder,
+}
+
+impl CostTrackingProvider {
+    pub fn new(
+        inner: Arc<dyn LlmProvider>,
+        agent: &str,
+        provider: LlmProviderType,
+        settings: Arc<RwLock<Settings>>,
+        metrics: Arc<MetricsCollector>,
+    ) -> Self {
+        Self {
+            inner,
+            recorder: CostRecorder {
+                agent: agent.to_string(),
+                provider,
+                settings,
+                metrics,
+            },
+        }
+    }
+
+    fn request_model(&self, request: &CompletionRequest) -> String {
+        request.model.clone().unwrap_or_else(|| self.inner.model().to_string())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CostTrackingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> LlmResult<CompletionResponse> {
+        let model = self.request_model(&request);
+        let response = self.inner.complete(request).await?;
+        if let Some(usage) = &response.usage {
+            self.recorder.record(&model, usage).await;
+        }
+        Ok(response)
+    }
+
+    fn complete_stream(&self, request: CompletionRequest) -> LlmStream {
+        let model = self.request_model(&request);
+        let mut inner = self.inner.complete_stream(request);
+        let recorder = self.recorder.clone();
+        let (sender, stream) = LlmStream::channel(64);
+
+        // Usage comes with the final chunk
+        tokio::spawn(async move {
+            while let Some(result) = inner.next().await {
+                let sent = match result {
+                    Ok(chunk) => {
+                        if let Some(usage) = &chunk.usage {
+                            recorder.record(&model, usage).await;
+                        }
+                        sender.send(chunk).await
+                    }
+                    Err(e) => sender.send_error(e).await,
+                };
+                if sent.is_err() {
+                    break;
+                }
+            }
+        });
+
+        stream
+    }
+
+    fn count_tokens(&self, text: &str) -> u32 {
+        self.inner.count_tokens(text)
+    }
+
+    fn count_message_tokens(&self, messages: &[Message]) -> u32 {
+        self.inner.count_message_tokens(messages)
+    }
+
+    fn context_window(&self) -> u32 {
+        self.inner.context_window()
+    }
+
+    fn max_output_tokens(&self) -> u32 {
+        self.inner.max_output_tokens()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::llm::FinishReason;
+    use serde_json::json;
+
+    /// Answers every request with fixed token usage
+    struct FixedUsageProvider;
+
+    #[async_trait]
+    impl LlmProvider for FixedUsageProvider {
+        fn name(&self) -> &str {
+            "openai"
+        }
+
+        fn model(&self) -> &str {
+            "gpt-4o"
+        }
+
+        async fn complete(&self, _request: CompletionRequest) -> LlmResult<CompletionResponse> {
+            Ok(CompletionResponse {
+                message: Message::assistant("done"),
+                finish_reason: FinishReason::Stop,
+                usage: Some(TokenUsage {
+                    prompt_tokens: 100,
+                    completion_tokens: 20,
+                    total_tokens: 120,
+                }),
+            })
+        }
+
+        fn complete_stream(&self, _request: CompletionRequest) -> LlmStream {
+            // Streams nothing; the sender is dropped, so the stream ends at once
+            let (_tx, rx) = tokio::sync::mpsc::channel(1);
+            LlmStream::new(rx)
+        }
+
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.len() as u32
+        }
+
+        fn context_window(&self) -> u32 {
+            128_000
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            4096
+        }
+    }
+
+    fn settings() -> Arc<RwLock<Settings>> {
+        let settings: Settings = serde_json::from_value(json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "pricing": [{
+                "provider": "openai",
+                "model": "gpt-4o",
+                "price_per_token": 0.001,
+                "output_price_per_token": 0.01
+            }]
+        }))
+        .unwrap();
+        Arc::new(RwLock::new(settings))
+    }
+
+    #[tokio::test]
+    async fn test_completions_are_priced_per_agent() {
+        let metrics = Arc::new(MetricsCollector::new().unwrap());
+        let provider = CostTrackingProvider::new(
+            Arc::new(FixedUsageProvider),
+            "writer",
+            LlmProviderType::OpenAI,
+            settings(),
+            metrics.clone(),
+        );
+
+        provider.complete(CompletionRequest::default()).await.unwrap();
+        provider.complete(CompletionRequest::default()).await.unwrap();
+        let unpriced = CompletionRequest {
+            model: Some("gpt-4o-mini".to_string()),
+            ..Default::default()
+        };
+        provider.complete(unpriced).await.unwrap();
+
+        let report = metrics.llm_costs.report();
+        assert_eq!(report.agents.len(), 2);
+        let priced = &report.agents[0];
+        assert_eq!((priced.model.as_str(), priced.completions), ("gpt-4o", 2));
+        assert_eq!((priced.prompt_tokens, priced.completion_tokens), (200, 40));
+        // 2 * (100 * 0.001 + 20 * 0.01)
+        assert!((priced.cost_usd - 0.6).abs() < 1e-9);
+        assert_eq!(report.agents[1].unpriced_completions, 1);
+        assert!((report.total_cost_usd - 0.6).abs() < 1e-9);
+
+        let counter = metrics.llm_cost_usd.with_label_values(&["writer", "openai", "gpt-4o"]);
+        assert!((counter.get() - 0.6).abs() < 1e-9);
+    }
+}
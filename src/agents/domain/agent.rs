@@ -1,10 +1,11 @@
 //! Agent domain types
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Agent type enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentType {
     /// Single request → single response, no history
@@ -70,3 +71,30 @@ pub struct SessionSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_message_preview: Option<String>,
 }
+
+/// Outcome of importing conversation sessions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionImportSummary {
+    /// Sessions that did not exist before
+    pub imported: usize,
+    /// Existing sessions overwritten by the import
+    pub replaced: usize,
+    /// Existing sessions left untouched
+    pub skipped: usize,
+}
+
+/// Outcome of (re)initializing agents from the config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentInitReport {
+    /// Agents ready to run
+    pub loaded: Vec<String>,
+    /// Agents that could not be created, e.g. for a missing API key
+    pub failed: Vec<AgentInitFailure>,
+}
+
+/// An agent that failed to initialize
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentInitFailure {
+    pub name: String,
+    pub error: String,
+}
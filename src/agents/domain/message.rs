@@ -47,6 +47,79 @@ pub struct Message {
     /// Optional name for the message sender
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Images sent along with the text of a user message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImagePart>,
+}
+
+/// An image in a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImagePart {
+    /// Image the provider fetches from a URL
+    Url { url: String },
+    /// Inline image data
+    Base64 { media_type: String, data: String },
+}
+
+impl ImagePart {
+    /// Parse an image of an agent input: a URL or `data:` URL string, or an
+    /// object like `{"url": ...}` or `{"media_type": ..., "data": ...}`
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(s) => Some(Self::from_url(s)),
+            Value::Object(obj) => {
+                if let Some(url) = obj.get("url").and_then(Value::as_str) {
+                    return Some(Self::from_url(url));
+                }
+                let media_type = obj.get("media_type").and_then(Value::as_str)?;
+                let data = obj.get("data").and_then(Value::as_str)?;
+                Some(Self::Base64 {
+                    media_type: media_type.to_string(),
+                    data: data.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// A `data:<media type>;base64,<data>` URL becomes inline data
+    fn from_url(url: &str) -> Self {
+        let inline = url
+            .strip_prefix("data:")
+            .and_then(|rest| rest.split_once(";base64,"));
+        match inline {
+            Some((media_type, data)) => Self::Base64 {
+                media_type: media_type.to_string(),
+                data: data.to_string(),
+            },
+            None => Self::Url { url: url.to_string() },
+        }
+    }
+
+    /// The image as a URL, inline data as a `data:` URL
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Url { url } => url.clone(),
+            Self::Base64 { media_type, data } => format!("data:{};base64,{}", media_type, data),
+        }
+    }
+
+    /// Media type of the image, guessed from the file extension for URLs
+    pub fn media_type(&self) -> &str {
+        match self {
+            Self::Base64 { media_type, .. } => media_type,
+            Self::Url { url } => {
+                let path = url.split(['?', '#']).next().unwrap_or(url).to_ascii_lowercase();
+                match path.rsplit('.').next() {
+                    Some("png") => "image/png",
+                    Some("gif") => "image/gif",
+                    Some("webp") => "image/webp",
+                    _ => "image/jpeg",
+                }
+            }
+        }
+    }
 }
 
 impl Message {
@@ -58,6 +131,7 @@ impl Message {
             tool_calls: None,
             tool_call_id: None,
             name: None,
+            images: Vec::new(),
         }
     }
 
@@ -69,6 +143,7 @@ impl Message {
             tool_calls: None,
             tool_call_id: None,
             name: None,
+            images: Vec::new(),
         }
     }
 
@@ -80,6 +155,7 @@ impl Message {
             tool_calls: None,
             tool_call_id: None,
             name: None,
+            images: Vec::new(),
         }
     }
 
@@ -95,6 +171,7 @@ impl Message {
             },
             tool_call_id: None,
             name: None,
+            images: Vec::new(),
         }
     }
 
@@ -106,8 +183,15 @@ impl Message {
             tool_calls: None,
             tool_call_id: Some(tool_call_id.into()),
             name: None,
+            images: Vec::new(),
         }
     }
+
+    /// Attach `images` to the message
+    pub fn with_images(mut self, images: Vec<ImagePart>) -> Self {
+        self.images = images;
+        self
+    }
 }
 
 /// A conversation session containing message history
@@ -54,7 +54,25 @@ pub trait AgentPort: Send + Sync {
     /// Delete a session
     async fn delete_session(&self, session_id: &str) -> anyhow::Result<()>;
 
-    /// Reinitialize agents (e.g., after API keys change)
+    /// All sessions of an agent with their full message history, oldest first
+    async fn export_sessions(&self, agent_name: &str) -> anyhow::Result<Vec<ConversationSession>>;
+
+    /// Save sessions to an agent's store. Sessions whose ID already exists are
+    /// overwritten if `replace` is set and skipped otherwise.
+    async fn import_sessions(
+        &self,
+        agent_name: &str,
+        sessions: Vec<ConversationSession>,
+        replace: bool,
+    ) -> anyhow::Result<SessionImportSummary>;
+
+    /// Tool calls of an agent waiting for operator approval
+    fn pending_confirmations(&self, agent_name: &str) -> Vec<crate::agents::confirmation::PendingConfirmation>;
+
+    /// Approve or deny a pending tool call; false if the token isn't pending for the agent
+    fn resolve_confirmation(&self, agent_name: &str, token: &str, approved: bool) -> bool;
+
+    /// Reinitialize agents (e.g., after API keys change or a config reload)
     /// This reloads agent configurations and recreates agents that may now be available
-    async fn reinitialize(&self) -> anyhow::Result<()>;
+    async fn reinitialize(&self) -> anyhow::Result<AgentInitReport>;
 }
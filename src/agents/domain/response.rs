@@ -20,6 +20,9 @@ pub struct AgentResponse {
     /// Reasoning steps (for ReAct agents)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub reasoning_steps: Vec<String>,
+    /// Step-by-step execution trace (for ReAct agents)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<TraceStep>,
     /// Session ID (for multi-turn agents)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
@@ -39,6 +42,7 @@ impl Default for AgentResponse {
             output: Value::Null,
             tool_calls: Vec::new(),
             reasoning_steps: Vec::new(),
+            trace: Vec::new(),
             session_id: None,
             iterations: 0,
             usage: None,
@@ -47,6 +51,22 @@ impl Default for AgentResponse {
     }
 }
 
+/// A single iteration of an agent's reasoning loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Iteration number (1-based)
+    pub iteration: u32,
+    /// Text produced by the LLM in this iteration (thought, or final answer)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thought: Option<String>,
+    /// Tool calls made in this iteration, with their inputs and observations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallResult>,
+    /// Whether this iteration produced the final answer
+    #[serde(default)]
+    pub is_final: bool,
+}
+
 /// Token usage information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenUsage {
@@ -99,6 +119,12 @@ pub enum AgentChunk {
         #[serde(skip_serializing_if = "Option::is_none")]
         error: Option<String>,
     },
+    /// Tool call paused until an operator approves or denies it
+    ToolConfirmationRequired {
+        tool_name: String,
+        args: Value,
+        confirmation_token: String,
+    },
     /// Reasoning/thought step (for ReAct)
     Thought { content: String },
     /// Status update
@@ -139,6 +165,15 @@ impl AgentChunk {
         }
     }
 
+    /// Create a tool confirmation request chunk
+    pub fn tool_confirmation_required(tool_call: &ToolCall, confirmation_token: impl Into<String>) -> Self {
+        Self::ToolConfirmationRequired {
+            tool_name: tool_call.name.clone(),
+            args: tool_call.arguments.clone(),
+            confirmation_token: confirmation_token.into(),
+        }
+    }
+
     /// Create a thought chunk
     pub fn thought(content: impl Into<String>) -> Self {
         Self::Thought {
@@ -239,6 +274,7 @@ impl AgentStream {
                 output: serde_json::json!({ "content": text_content }),
                 tool_calls,
                 reasoning_steps,
+                trace: Vec::new(),
                 session_id: None,
                 iterations: 0,
                 usage: None,
@@ -26,7 +26,7 @@ impl ToolCall {
 
     /// Generate a unique ID for a tool call
     pub fn generate_id() -> String {
-        format!("call_{}", uuid::Uuid::new_v4().to_string().replace("-", "")[..24].to_string())
+        format!("call_{}", &uuid::Uuid::new_v4().to_string().replace("-", "")[..24])
     }
 }
 
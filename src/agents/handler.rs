@@ -5,18 +5,25 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde_json::Value;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
+use tracing::Instrument;
 
+use crate::adapters::logging::execution_span;
+use crate::adapters::metrics_handler::MetricsCollector;
 use crate::adapters::secrets::SharedSecretsStore;
 use crate::agents::config::{AgentConfig, OrchestrationConfig};
+use crate::agents::confirmation::{PendingConfirmation, ToolConfirmations};
 use crate::agents::core::{create_agent, Agent};
+use crate::agents::costs::CostTrackingProvider;
 use crate::agents::domain::{
-    AgentInfo, AgentPort, AgentResponse, AgentStream, ConversationSession, SessionSummary,
+    AgentInfo, AgentInitFailure, AgentInitReport, AgentType, AgentPort, AgentResponse, AgentStream,
+    ConversationSession, SessionImportSummary, SessionSummary,
 };
 use crate::agents::error::{AgentError, AgentResult};
-use crate::agents::llm::{create_provider, create_provider_with_secrets, LlmProvider};
+use crate::agents::llm::{create_provider, create_provider_with_secrets, LlmProvider, ProviderRegistry};
 use crate::agents::memory::{create_store, ConversationStore};
 use crate::agents::orchestration::OrchestrationEngine;
+use crate::agents::traces::AgentTraceStore;
 use crate::config::Settings;
 use crate::domain::ToolPort;
 
@@ -28,12 +35,22 @@ pub struct AgentHandler {
     agents: Arc<RwLock<HashMap<String, Arc<dyn Agent>>>>,
     /// Cached LLM providers (config hash -> provider)
     providers: Arc<RwLock<HashMap<String, Arc<dyn LlmProvider>>>>,
+    /// Custom LLM providers agents can name
+    llm_providers: Arc<ProviderRegistry>,
     /// Default memory store
     default_store: Arc<dyn ConversationStore>,
     /// Orchestration engine
     orchestration: Arc<RwLock<Option<OrchestrationEngine>>>,
     /// Secrets store for API keys
     secrets: Option<SharedSecretsStore>,
+    /// Metrics collector for agent metrics
+    metrics: Option<Arc<MetricsCollector>>,
+    /// Tool calls of this handler's agents waiting for operator approval
+    confirmations: Arc<ToolConfirmations>,
+    /// Where tool-calling traces of ReAct executions are saved, if anywhere
+    traces: Option<AgentTraceStore>,
+    /// Held while agents are being rebuilt, so reloads don't interleave
+    initializing: Mutex<()>,
 }
 
 impl AgentHandler {
@@ -46,9 +63,14 @@ impl AgentHandler {
             tool_handler,
             agents: Arc::new(RwLock::new(HashMap::new())),
             providers: Arc::new(RwLock::new(HashMap::new())),
+            llm_providers: Arc::new(ProviderRegistry::new()),
             default_store,
             orchestration: Arc::new(RwLock::new(None)),
             secrets: None,
+            metrics: None,
+            confirmations: Arc::new(ToolConfirmations::new()),
+            traces: None,
+            initializing: Mutex::new(()),
         }
     }
 
@@ -65,43 +87,94 @@ impl AgentHandler {
             tool_handler,
             agents: Arc::new(RwLock::new(HashMap::new())),
             providers: Arc::new(RwLock::new(HashMap::new())),
+            llm_providers: Arc::new(ProviderRegistry::new()),
             default_store,
             orchestration: Arc::new(RwLock::new(None)),
             secrets: Some(secrets),
+            metrics: None,
+            confirmations: Arc::new(ToolConfirmations::new()),
+            traces: None,
+            initializing: Mutex::new(()),
         }
     }
 
-    /// Initialize agents from configuration
-    pub async fn initialize(&self) -> AgentResult<()> {
-        let settings = self.settings.read().await;
+    /// Record agent metrics (e.g. conversation summaries and LLM costs) in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Look up agents' custom LLM providers in `llm_providers`
+    pub fn with_llm_providers(mut self, llm_providers: Arc<ProviderRegistry>) -> Self {
+        self.llm_providers = llm_providers;
+        self
+    }
+
+    /// Keep in-memory conversation sessions in `store`, so handlers sharing it
+    /// see each other's sessions
+    pub fn with_session_store(mut self, store: Arc<dyn ConversationStore>) -> Self {
+        self.default_store = store;
+        self
+    }
+
+    /// Save a trace of every completed ReAct execution in `traces`
+    pub fn with_traces(mut self, traces: AgentTraceStore) -> Self {
+        self.traces = Some(traces);
+        self
+    }
+
+    /// Initialize agents from configuration, replacing any agents created
+    /// before. Safe to call again after the config changes: concurrent calls
+    /// run one after the other, and each rebuilds every agent from the current
+    /// config, so removed agents go away and failed ones are not kept.
+    /// `agent_defaults` are applied to each agent's config.
+    pub async fn initialize(&self) -> AgentResult<AgentInitReport> {
+        let _rebuilding = self.initializing.lock().await;
+        let configs: Vec<AgentConfig> = {
+            let settings = self.settings.read().await;
+            settings.agents.iter().map(|a| settings.agent_defaults.apply(a)).collect()
+        };
 
         let mut agents = HashMap::new();
+        let mut report = AgentInitReport::default();
 
-        for config in &settings.agents {
+        for config in &configs {
             match self.create_agent_from_config(config).await {
                 Ok(agent) => {
                     agents.insert(config.name.clone(), agent);
+                    report.loaded.push(config.name.clone());
                 }
                 Err(e) => {
                     tracing::warn!("Failed to create agent '{}': {}", config.name, e);
+                    report.failed.push(AgentInitFailure {
+                        name: config.name.clone(),
+                        error: e.to_string(),
+                    });
                 }
             }
         }
 
-        // Update cached agents
-        *self.agents.write().await = agents.clone();
-
-        // Create orchestration engine
-        let orchestration = OrchestrationEngine::new(agents);
+        let orchestration = OrchestrationEngine::new(agents.clone());
+        *self.agents.write().await = agents;
         *self.orchestration.write().await = Some(orchestration);
 
-        Ok(())
+        Ok(report)
     }
 
     /// Create an agent from configuration
     async fn create_agent_from_config(&self, config: &AgentConfig) -> AgentResult<Arc<dyn Agent>> {
-        // Get or create LLM provider
+        // Get or create LLM provider, recording the costs of this agent's completions
         let provider = self.get_or_create_provider(&config.llm).await?;
+        let provider: Arc<dyn LlmProvider> = match &self.metrics {
+            Some(metrics) => Arc::new(CostTrackingProvider::new(
+                provider,
+                &config.name,
+                config.llm.provider.clone(),
+                self.settings.clone(),
+                metrics.clone(),
+            )),
+            None => provider,
+        };
 
         // Get or create memory store
         let store = self.get_or_create_store(&config.memory).await?;
@@ -112,6 +185,8 @@ impl AgentHandler {
             provider,
             store,
             self.tool_handler.clone(),
+            self.metrics.clone(),
+            self.confirmations.clone(),
         )
     }
 
@@ -129,11 +204,11 @@ impl AgentHandler {
 
         // Create new provider, using secrets store if available
         let provider = if let Some(secrets) = &self.secrets {
-            create_provider_with_secrets(config, secrets.clone())
+            create_provider_with_secrets(config, secrets.clone(), &self.llm_providers)
                 .await
                 .map_err(|e| AgentError::Configuration(e.to_string()))?
         } else {
-            create_provider(config).map_err(|e| AgentError::Configuration(e.to_string()))?
+            create_provider(config, &self.llm_providers).map_err(|e| AgentError::Configuration(e.to_string()))?
         };
 
         // Cache it
@@ -158,6 +233,20 @@ impl AgentHandler {
         }
     }
 
+    /// Store configured for a named agent's memory
+    async fn store_for_agent(&self, agent_name: &str) -> AgentResult<Arc<dyn ConversationStore>> {
+        let memory = self
+            .settings
+            .read()
+            .await
+            .agents
+            .iter()
+            .find(|a| a.name == agent_name)
+            .map(|a| a.memory.clone())
+            .ok_or_else(|| AgentError::NotFound(agent_name.to_string()))?;
+        self.get_or_create_store(&memory).await
+    }
+
     /// Get orchestration configurations from settings
     pub async fn get_orchestrations(&self) -> Vec<OrchestrationConfig> {
         self.settings.read().await.orchestrations.clone()
@@ -169,7 +258,9 @@ impl AgentHandler {
         match self.orchestration.try_read() {
             Ok(orchestration) => {
                 match orchestration.as_ref() {
-                    Some(engine) => Ok(engine.execute(config, input)),
+                    Some(engine) => {
+                        Ok(execution_span("orchestration", &config.name).in_scope(|| engine.execute(config, input)))
+                    }
                     None => Err(AgentError::Internal("Orchestration engine not initialized".to_string())),
                 }
             }
@@ -193,8 +284,21 @@ impl AgentPort for AgentHandler {
             Some(agent) => {
                 let agent = agent.clone();
                 drop(agents); // Release the lock before executing
-                let stream = agent.execute(input, session_id);
-                stream.collect().await.map_err(|e| anyhow::anyhow!("{}", e))
+                let span = execution_span("agent", name);
+                let stream = span.in_scope(|| agent.execute(input.clone(), session_id.clone()));
+                let response = stream
+                    .collect()
+                    .instrument(span)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                if let Some(traces) = &self.traces {
+                    if agent.config().agent_type == AgentType::ReAct {
+                        if let Err(e) = traces.record(name, &input, session_id, &response).await {
+                            tracing::warn!("Failed to save trace of agent '{}': {}", name, e);
+                        }
+                    }
+                }
+                Ok(response)
             }
             None => Err(anyhow::anyhow!("Agent not found: {}", name)),
         }
@@ -211,7 +315,7 @@ impl AgentPort for AgentHandler {
         match self.agents.try_read() {
             Ok(agents) => {
                 match agents.get(name) {
-                    Some(agent) => agent.execute(input, session_id),
+                    Some(agent) => execution_span("agent", name).in_scope(|| agent.execute(input, session_id)),
                     None => {
                         let (sender, stream) = AgentStream::channel(1);
                         let name = name.to_string();
@@ -293,7 +397,59 @@ impl AgentPort for AgentHandler {
         self.default_store.delete(session_id).await.map_err(|e| anyhow::anyhow!("{}", e))
     }
 
-    async fn reinitialize(&self) -> anyhow::Result<()> {
+    async fn export_sessions(&self, agent_name: &str) -> anyhow::Result<Vec<ConversationSession>> {
+        let store = self.store_for_agent(agent_name).await.map_err(|e| anyhow::anyhow!("{}", e))?;
+        let summaries = store
+            .list(Some(agent_name), usize::MAX, 0)
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let mut sessions = Vec::with_capacity(summaries.len());
+        for summary in summaries {
+            if let Some(session) = store.load(&summary.session_id).await.map_err(|e| anyhow::anyhow!("{}", e))? {
+                sessions.push(session);
+            }
+        }
+        sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.session_id.cmp(&b.session_id)));
+        Ok(sessions)
+    }
+
+    async fn import_sessions(
+        &self,
+        agent_name: &str,
+        sessions: Vec<ConversationSession>,
+        replace: bool,
+    ) -> anyhow::Result<SessionImportSummary> {
+        let store = self.store_for_agent(agent_name).await.map_err(|e| anyhow::anyhow!("{}", e))?;
+        let mut summary = SessionImportSummary::default();
+
+        for mut session in sessions {
+            let exists = store.load(&session.session_id).await.map_err(|e| anyhow::anyhow!("{}", e))?.is_some();
+            if exists && !replace {
+                summary.skipped += 1;
+                continue;
+            }
+
+            session.agent_name = agent_name.to_string();
+            store.save(&session).await.map_err(|e| anyhow::anyhow!("{}", e))?;
+            if exists {
+                summary.replaced += 1;
+            } else {
+                summary.imported += 1;
+            }
+        }
+        Ok(summary)
+    }
+
+    fn pending_confirmations(&self, agent_name: &str) -> Vec<PendingConfirmation> {
+        self.confirmations.list(agent_name)
+    }
+
+    fn resolve_confirmation(&self, agent_name: &str, token: &str, approved: bool) -> bool {
+        self.confirmations.resolve(agent_name, token, approved)
+    }
+
+    async fn reinitialize(&self) -> anyhow::Result<AgentInitReport> {
         // Clear provider cache so API keys are re-fetched
         self.providers.write().await.clear();
 
@@ -12,7 +12,7 @@ use super::{
 };
 use crate::adapters::secrets::SharedSecretsStore;
 use crate::agents::config::LlmProviderConfig;
-use crate::agents::domain::{Message, Role, ToolCall};
+use crate::agents::domain::{ImagePart, Message, Role, ToolCall};
 use crate::agents::error::{LlmError, LlmResult};
 
 /// Anthropic LLM Provider
@@ -107,12 +107,20 @@ impl AnthropicProvider {
             body["stop_sequences"] = json!(stop);
         }
 
+        if let Some(top_p) = request.top_p {
+            body["top_p"] = json!(top_p);
+        }
+
+        if request.frequency_penalty.is_some() || request.presence_penalty.is_some() || request.seed.is_some() {
+            tracing::debug!("Anthropic does not support frequency_penalty, presence_penalty or seed; ignoring them");
+        }
+
         if let Some(tools) = &request.tools {
             if !tools.is_empty() {
                 body["tools"] = json!(tools.iter().map(|t| {
                     // Ensure input_schema is a valid JSON Schema object
                     // Anthropic requires {"type": "object", "properties": {...}} for input_schema
-                    let schema = if t.parameters.is_null() || t.parameters.as_object().map_or(true, |o| o.is_empty()) {
+                    let schema = if t.parameters.is_null() || t.parameters.as_object().is_none_or(|o| o.is_empty()) {
                         // Empty or null schema - provide complete default
                         json!({
                             "type": "object",
@@ -162,20 +170,47 @@ impl AnthropicProvider {
 
     /// Convert internal messages to Anthropic format
     /// Returns (system_prompt, messages)
+    ///
+    /// System messages (the prompt, summaries, persona switches) are joined
+    /// into the system prompt, and the results of one turn's tool calls go
+    /// into a single user message, as the API expects.
     fn convert_messages(&self, messages: &[Message]) -> (Option<String>, Vec<Value>) {
-        let mut system_prompt = None;
-        let mut converted = Vec::new();
+        let mut system_parts: Vec<&str> = Vec::new();
+        let mut converted: Vec<Value> = Vec::new();
 
         for m in messages {
             match m.role {
                 Role::System => {
-                    system_prompt = Some(m.content.clone());
+                    system_parts.push(&m.content);
                 }
                 Role::User => {
-                    converted.push(json!({
-                        "role": "user",
-                        "content": m.content
-                    }));
+                    if m.images.is_empty() {
+                        converted.push(json!({
+                            "role": "user",
+                            "content": m.content
+                        }));
+                    } else {
+                        let mut content: Vec<Value> = m
+                            .images
+                            .iter()
+                            .map(|image| {
+                                let source = match image {
+                                    ImagePart::Url { url } => json!({ "type": "url", "url": url }),
+                                    ImagePart::Base64 { media_type, data } => json!({
+                                        "type": "base64",
+                                        "media_type": media_type,
+                                        "data": data
+                                    }),
+                                };
+                                json!({ "type": "image", "source": source })
+                            })
+                            .collect();
+                        content.push(json!({ "type": "text", "text": m.content }));
+                        converted.push(json!({
+                            "role": "user",
+                            "content": content
+                        }));
+                    }
                 }
                 Role::Assistant => {
                     if let Some(tool_calls) = &m.tool_calls {
@@ -189,11 +224,17 @@ impl AnthropicProvider {
                         }
 
                         for tc in tool_calls {
+                            // `input` has to be an object, even for tools without arguments
+                            let input = if tc.arguments.is_object() {
+                                tc.arguments.clone()
+                            } else {
+                                json!({})
+                            };
                             content.push(json!({
                                 "type": "tool_use",
                                 "id": tc.id,
                                 "name": tc.name,
-                                "input": tc.arguments
+                                "input": input
                             }));
                         }
 
@@ -210,18 +251,23 @@ impl AnthropicProvider {
                 }
                 Role::Tool => {
                     // Anthropic expects tool results in user messages
-                    converted.push(json!({
-                        "role": "user",
-                        "content": [{
-                            "type": "tool_result",
-                            "tool_use_id": m.tool_call_id.as_ref().unwrap_or(&String::new()),
-                            "content": m.content
-                        }]
-                    }));
+                    let result = json!({
+                        "type": "tool_result",
+                        "tool_use_id": m.tool_call_id.as_ref().unwrap_or(&String::new()),
+                        "content": m.content
+                    });
+                    match converted.last_mut().and_then(tool_results_mut) {
+                        Some(results) => results.push(result),
+                        None => converted.push(json!({
+                            "role": "user",
+                            "content": [result]
+                        })),
+                    }
                 }
             }
         }
 
+        let system_prompt = (!system_parts.is_empty()).then(|| system_parts.join("\n\n"));
         (system_prompt, converted)
     }
 
@@ -279,6 +325,15 @@ impl AnthropicProvider {
     }
 }
 
+/// The content blocks of `message` if it is a user message of tool results
+fn tool_results_mut(message: &mut Value) -> Option<&mut Vec<Value>> {
+    if message["role"] != "user" {
+        return None;
+    }
+    let blocks = message["content"].as_array_mut()?;
+    blocks.iter().all(|b| b["type"] == "tool_result").then_some(blocks)
+}
+
 #[async_trait]
 impl LlmProvider for AnthropicProvider {
     fn name(&self) -> &str {
@@ -394,6 +449,8 @@ impl AnthropicProvider {
         #[allow(unused_assignments)]
         let mut current_tool_name = String::new();
         let mut tool_call_index = 0usize;
+        // Prompt tokens are only reported by `message_start`
+        let mut input_tokens = 0u32;
 
         while let Some(chunk_result) = stream.next().await {
             let chunk = chunk_result.map_err(|e| LlmError::Streaming(e.to_string()))?;
@@ -410,16 +467,18 @@ impl AnthropicProvider {
                 }
 
                 // Parse SSE event
-                if line.starts_with("event: ") {
-                    let _event_type = &line[7..];
+                if let Some(_event_type) = line.strip_prefix("event: ") {
                     continue;
                 }
 
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-
+                if let Some(data) = line.strip_prefix("data: ") {
                     if let Ok(event) = serde_json::from_str::<AnthropicStreamEvent>(data) {
                         match event.event_type.as_str() {
+                            "message_start" => {
+                                if let Some(usage) = event.message.and_then(|m| m.usage) {
+                                    input_tokens = usage.input_tokens.unwrap_or(0);
+                                }
+                            }
                             "content_block_start" => {
                                 if let Some(content_block) = &event.content_block {
                                     if content_block.block_type == "tool_use" {
@@ -473,10 +532,14 @@ impl AnthropicProvider {
                                             _ => FinishReason::Stop,
                                         };
 
-                                        let usage = event.usage.map(|u| TokenUsage {
-                                            prompt_tokens: u.input_tokens.unwrap_or(0),
-                                            completion_tokens: u.output_tokens.unwrap_or(0),
-                                            total_tokens: u.input_tokens.unwrap_or(0) + u.output_tokens.unwrap_or(0),
+                                        let usage = event.usage.map(|u| {
+                                            let prompt_tokens = u.input_tokens.unwrap_or(input_tokens);
+                                            let completion_tokens = u.output_tokens.unwrap_or(0);
+                                            TokenUsage {
+                                                prompt_tokens,
+                                                completion_tokens,
+                                                total_tokens: prompt_tokens + completion_tokens,
+                                            }
                                         });
 
                                         if sender.send(StreamChunk::finish(finish_reason, usage)).await.is_err() {
@@ -528,11 +591,17 @@ struct AnthropicUsage {
 struct AnthropicStreamEvent {
     #[serde(rename = "type")]
     event_type: String,
+    message: Option<StreamMessage>,
     content_block: Option<StreamContentBlock>,
     delta: Option<StreamDelta>,
     usage: Option<StreamUsage>,
 }
 
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    usage: Option<StreamUsage>,
+}
+
 #[derive(Debug, Deserialize)]
 struct StreamContentBlock {
     #[serde(rename = "type")]
@@ -555,3 +624,81 @@ struct StreamUsage {
     input_tokens: Option<u32>,
     output_tokens: Option<u32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::domain::ToolDefinition;
+
+    fn provider() -> AnthropicProvider {
+        AnthropicProvider {
+            client: reqwest::Client::new(),
+            api_key: "test".to_string(),
+            base_url: "http://localhost".to_string(),
+            model: "claude-3-5-sonnet-latest".to_string(),
+            default_temperature: None,
+            default_max_tokens: None,
+        }
+    }
+
+    /// A tool-use response as returned by the Messages API
+    const TOOL_USE_RESPONSE: &str = r#"{
+        "id": "msg_01Aq9w938a90dw8q",
+        "type": "message",
+        "role": "assistant",
+        "model": "claude-3-5-sonnet-20241022",
+        "content": [
+            {"type": "text", "text": "Let me check both cities."},
+            {"type": "tool_use", "id": "toolu_01A09q90qw90lq917835lq9", "name": "get_weather", "input": {"city": "Paris"}},
+            {"type": "tool_use", "id": "toolu_01B19q90qw90lq917835lq9", "name": "get_weather", "input": {"city": "Rome"}}
+        ],
+        "stop_reason": "tool_use",
+        "stop_sequence": null,
+        "usage": {"input_tokens": 472, "output_tokens": 91}
+    }"#;
+
+    #[test]
+    fn test_tool_calls_round_trip() {
+        let provider = provider();
+        let response: AnthropicResponse = serde_json::from_str(TOOL_USE_RESPONSE).unwrap();
+        let parsed = provider.parse_response(&response).unwrap();
+
+        assert_eq!(parsed.finish_reason, FinishReason::ToolCalls);
+        let calls = parsed.message.tool_calls.clone().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id, "toolu_01A09q90qw90lq917835lq9");
+        assert_eq!(calls[0].name, "get_weather");
+        assert_eq!(calls[0].arguments, json!({"city": "Paris"}));
+
+        let request = CompletionRequest {
+            messages: vec![
+                Message::system("You report the weather"),
+                Message::user("Weather in Paris and Rome?"),
+                parsed.message,
+                Message::tool_result(&calls[0].id, &json!({"temp": 18})),
+                Message::tool_result(&calls[1].id, &json!({"temp": 24})),
+            ],
+            tools: Some(vec![ToolDefinition::new("get_weather", "Current weather", json!({
+                "properties": {"city": {"type": "string"}}
+            }))]),
+            ..Default::default()
+        };
+        let body = provider.build_request_body(&request);
+
+        assert_eq!(body["system"], "You report the weather");
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["content"][1]["type"], "tool_use");
+        assert_eq!(messages[1]["content"][1]["input"], json!({"city": "Paris"}));
+
+        // Both results answer the assistant turn in one user message
+        let results = messages[2]["content"].as_array().unwrap();
+        assert_eq!(messages[2]["role"], "user");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["type"], "tool_result");
+        assert_eq!(results[0]["tool_use_id"], "toolu_01A09q90qw90lq917835lq9");
+        assert_eq!(results[1]["tool_use_id"], "toolu_01B19q90qw90lq917835lq9");
+        assert_eq!(results[1]["content"], r#"{"temp":24}"#);
+    }
+}
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use futures::StreamExt;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::env;
 
 use super::{
@@ -12,7 +13,7 @@ use super::{
 };
 use crate::adapters::secrets::SharedSecretsStore;
 use crate::agents::config::LlmProviderConfig;
-use crate::agents::domain::{Message, Role, ToolCall};
+use crate::agents::domain::{ImagePart, Message, Role, ToolCall};
 use crate::agents::error::{LlmError, LlmResult};
 
 /// Google Gemini LLM Provider
@@ -114,7 +115,23 @@ impl GeminiProvider {
             generation_config["stopSequences"] = json!(stop);
         }
 
-        if generation_config.as_object().map_or(false, |o| !o.is_empty()) {
+        if let Some(top_p) = request.top_p {
+            generation_config["topP"] = json!(top_p);
+        }
+
+        if let Some(penalty) = request.frequency_penalty {
+            generation_config["frequencyPenalty"] = json!(penalty);
+        }
+
+        if let Some(penalty) = request.presence_penalty {
+            generation_config["presencePenalty"] = json!(penalty);
+        }
+
+        if let Some(seed) = request.seed {
+            generation_config["seed"] = json!(seed);
+        }
+
+        if generation_config.as_object().is_some_and(|o| !o.is_empty()) {
             body["generationConfig"] = generation_config;
         }
 
@@ -125,7 +142,7 @@ impl GeminiProvider {
                     "function_declarations": tools.iter().map(|t| {
                         // Ensure parameters is a valid JSON Schema object
                         // Gemini requires {"type": "object", "properties": {...}} for parameters
-                        let params = if t.parameters.is_null() || t.parameters.as_object().map_or(true, |o| o.is_empty()) {
+                        let params = if t.parameters.is_null() || t.parameters.as_object().is_none_or(|o| o.is_empty()) {
                             // Empty or null schema - provide complete default
                             json!({
                                 "type": "object",
@@ -180,9 +197,14 @@ impl GeminiProvider {
     }
 
     /// Convert internal messages to Gemini format
+    ///
+    /// Function responses are matched to their calls by name, so a tool
+    /// result takes the name of the call it answers, and the results of one
+    /// turn go into a single content with one part per call.
     fn convert_messages(&self, messages: &[Message], needs_thought_sig: bool) -> Vec<Value> {
-        let mut contents = Vec::new();
+        let mut contents: Vec<Value> = Vec::new();
         let mut system_instruction: Option<String> = None;
+        let mut call_names: HashMap<&str, &str> = HashMap::new();
 
         for m in messages {
             match m.role {
@@ -193,6 +215,14 @@ impl GeminiProvider {
                 }
                 Role::User => {
                     let mut parts = vec![json!({ "text": m.content })];
+                    parts.extend(m.images.iter().map(|image| match image {
+                        ImagePart::Url { url } => json!({
+                            "fileData": { "mimeType": image.media_type(), "fileUri": url }
+                        }),
+                        ImagePart::Base64 { media_type, data } => json!({
+                            "inlineData": { "mimeType": media_type, "data": data }
+                        }),
+                    }));
 
                     // Include system instruction in first user message if present
                     if let Some(sys) = system_instruction.take() {
@@ -215,13 +245,19 @@ impl GeminiProvider {
                     // Gemini 3/2.5 models require thought_signature as sibling to functionCall
                     if let Some(tool_calls) = &m.tool_calls {
                         for (i, tc) in tool_calls.iter().enumerate() {
+                            call_names.insert(&tc.id, &tc.name);
+                            let args = if tc.arguments.is_object() {
+                                tc.arguments.clone()
+                            } else {
+                                json!({})
+                            };
                             if needs_thought_sig && i == 0 {
                                 // Only the first function call needs the thought_signature
                                 // Note: thoughtSignature is a sibling to functionCall, not nested inside
                                 parts.push(json!({
                                     "functionCall": {
                                         "name": tc.name,
-                                        "args": tc.arguments
+                                        "args": args
                                     },
                                     "thoughtSignature": "skip_thought_signature_validator"
                                 }));
@@ -229,7 +265,7 @@ impl GeminiProvider {
                                 parts.push(json!({
                                     "functionCall": {
                                         "name": tc.name,
-                                        "args": tc.arguments
+                                        "args": args
                                     }
                                 }));
                             }
@@ -245,19 +281,32 @@ impl GeminiProvider {
                 }
                 Role::Tool => {
                     // Tool results in Gemini format
-                    let tool_name = m.name.clone().unwrap_or_else(|| "tool".to_string());
-                    let response_value: Value = serde_json::from_str(&m.content)
-                        .unwrap_or_else(|_| json!({ "result": m.content }));
+                    let tool_name = m
+                        .tool_call_id
+                        .as_deref()
+                        .and_then(|id| call_names.get(id).copied())
+                        .or(m.name.as_deref())
+                        .unwrap_or("tool");
+                    // The response has to be an object
+                    let response_value = match serde_json::from_str::<Value>(&m.content) {
+                        Ok(value) if value.is_object() => value,
+                        Ok(value) => json!({ "result": value }),
+                        Err(_) => json!({ "result": m.content }),
+                    };
+                    let part = json!({
+                        "functionResponse": {
+                            "name": tool_name,
+                            "response": response_value
+                        }
+                    });
 
-                    contents.push(json!({
-                        "role": "user",
-                        "parts": [{
-                            "functionResponse": {
-                                "name": tool_name,
-                                "response": response_value
-                            }
-                        }]
-                    }));
+                    match contents.last_mut().and_then(function_responses_mut) {
+                        Some(parts) => parts.push(part),
+                        None => contents.push(json!({
+                            "role": "user",
+                            "parts": [part]
+                        })),
+                    }
                 }
             }
         }
@@ -275,13 +324,13 @@ impl GeminiProvider {
         let mut tool_calls = Vec::new();
 
         if let Some(parts) = &candidate.content.parts {
-            for (index, part) in parts.iter().enumerate() {
+            for part in parts {
                 if let Some(text) = &part.text {
                     content.push_str(text);
                 }
                 if let Some(fc) = &part.function_call {
                     tool_calls.push(ToolCall {
-                        id: format!("call_{}", index),
+                        id: format!("call_{}", tool_calls.len()),
                         name: fc.name.clone(),
                         arguments: fc.args.clone().unwrap_or(Value::Object(Default::default())),
                     });
@@ -289,13 +338,9 @@ impl GeminiProvider {
             }
         }
 
-        let message = if tool_calls.is_empty() {
-            Message::assistant(content)
-        } else {
-            Message::assistant_with_tools(content, tool_calls)
-        };
-
+        // Gemini finishes with STOP when it calls functions
         let finish_reason = match candidate.finish_reason.as_deref() {
+            _ if !tool_calls.is_empty() => FinishReason::ToolCalls,
             Some("STOP") => FinishReason::Stop,
             Some("MAX_TOKENS") => FinishReason::Length,
             Some("SAFETY") => FinishReason::ContentFilter,
@@ -304,6 +349,12 @@ impl GeminiProvider {
             _ => FinishReason::Stop,
         };
 
+        let message = if tool_calls.is_empty() {
+            Message::assistant(content)
+        } else {
+            Message::assistant_with_tools(content, tool_calls)
+        };
+
         let usage = response.usage_metadata.as_ref().map(|u| TokenUsage {
             prompt_tokens: u.prompt_token_count.unwrap_or(0),
             completion_tokens: u.candidates_token_count.unwrap_or(0),
@@ -318,6 +369,15 @@ impl GeminiProvider {
     }
 }
 
+/// The parts of `content` if it is a user content of function responses
+fn function_responses_mut(content: &mut Value) -> Option<&mut Vec<Value>> {
+    if content["role"] != "user" {
+        return None;
+    }
+    let parts = content["parts"].as_array_mut()?;
+    parts.iter().all(|p| p.get("functionResponse").is_some()).then_some(parts)
+}
+
 #[async_trait]
 impl LlmProvider for GeminiProvider {
     fn name(&self) -> &str {
@@ -467,9 +527,7 @@ impl GeminiProvider {
                 }
 
                 // Gemini SSE format: data: {...}
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-
+                if let Some(data) = line.strip_prefix("data: ") {
                     if let Ok(parsed) = serde_json::from_str::<GeminiStreamResponse>(data) {
                         if let Some(candidate) = parsed.candidates.and_then(|c| c.into_iter().next()) {
                             let mut stream_chunk = StreamChunk {
@@ -490,10 +548,11 @@ impl GeminiProvider {
 
                                         // Handle function calls
                                         if let Some(fc) = part.function_call {
+                                            let args = fc.args.unwrap_or(Value::Object(Default::default()));
                                             let delta = ToolCallDelta::new(tool_call_index)
-                                                .with_id(&format!("call_{}", tool_call_index))
+                                                .with_id(format!("call_{}", tool_call_index))
                                                 .with_name(&fc.name)
-                                                .with_arguments(&serde_json::to_string(&fc.args.unwrap_or(Value::Null)).unwrap_or_default());
+                                                .with_arguments(serde_json::to_string(&args).unwrap_or_default());
                                             stream_chunk.tool_calls.push(delta);
                                             tool_call_index += 1;
                                         }
@@ -504,6 +563,7 @@ impl GeminiProvider {
                             // Handle finish reason
                             if let Some(reason) = candidate.finish_reason {
                                 stream_chunk.finish_reason = Some(match reason.as_str() {
+                                    _ if tool_call_index > 0 => FinishReason::ToolCalls,
                                     "STOP" => FinishReason::Stop,
                                     "MAX_TOKENS" => FinishReason::Length,
                                     "SAFETY" => FinishReason::ContentFilter,
@@ -522,14 +582,13 @@ impl GeminiProvider {
                             }
 
                             // Send chunk if it has content or is a finish
-                            if !stream_chunk.content.is_empty()
+                            if (!stream_chunk.content.is_empty()
                                 || !stream_chunk.tool_calls.is_empty()
                                 || stream_chunk.finish_reason.is_some()
-                                || stream_chunk.usage.is_some()
+                                || stream_chunk.usage.is_some())
+                                && sender.send(stream_chunk).await.is_err()
                             {
-                                if sender.send(stream_chunk).await.is_err() {
-                                    return Ok(()); // Receiver dropped
-                                }
+                                return Ok(()); // Receiver dropped
                             }
                         }
                     }
@@ -605,3 +664,81 @@ struct GeminiStreamCandidate {
     content: Option<GeminiContent>,
     finish_reason: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::domain::ToolDefinition;
+
+    fn provider() -> GeminiProvider {
+        GeminiProvider {
+            client: reqwest::Client::new(),
+            api_key: "test".to_string(),
+            base_url: "http://localhost".to_string(),
+            model: "gemini-2.0-flash".to_string(),
+            default_temperature: None,
+            default_max_tokens: None,
+        }
+    }
+
+    /// A function-calling response as returned by generateContent
+    const FUNCTION_CALL_RESPONSE: &str = r#"{
+        "candidates": [{
+            "content": {
+                "parts": [
+                    {"text": "Checking both cities."},
+                    {"functionCall": {"name": "get_weather", "args": {"city": "Paris"}}},
+                    {"functionCall": {"name": "list_cities"}}
+                ],
+                "role": "model"
+            },
+            "finishReason": "STOP",
+            "index": 0
+        }],
+        "usageMetadata": {"promptTokenCount": 61, "candidatesTokenCount": 18, "totalTokenCount": 79},
+        "modelVersion": "gemini-2.0-flash"
+    }"#;
+
+    #[test]
+    fn test_function_calls_round_trip() {
+        let provider = provider();
+        let response: GeminiResponse = serde_json::from_str(FUNCTION_CALL_RESPONSE).unwrap();
+        let parsed = provider.parse_response(&response).unwrap();
+
+        assert_eq!(parsed.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(parsed.message.content, "Checking both cities.");
+        let calls = parsed.message.tool_calls.clone().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[0].id.as_str(), calls[0].name.as_str()), ("call_0", "get_weather"));
+        assert_eq!(calls[0].arguments, json!({"city": "Paris"}));
+        assert_eq!((calls[1].id.as_str(), calls[1].arguments.clone()), ("call_1", json!({})));
+
+        let request = CompletionRequest {
+            messages: vec![
+                Message::user("Weather in Paris?"),
+                parsed.message,
+                Message::tool_result(&calls[0].id, &json!({"temp": 18})),
+                Message::tool_result(&calls[1].id, &json!(["Paris", "Rome"])),
+            ],
+            tools: Some(vec![ToolDefinition::new("get_weather", "Current weather", Value::Null)]),
+            ..Default::default()
+        };
+        let body = provider.build_request_body(&request);
+
+        let declaration = &body["tools"][0]["function_declarations"][0];
+        assert_eq!(declaration["parameters"]["type"], "object");
+        let contents = body["contents"].as_array().unwrap();
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents[1]["role"], "model");
+        assert_eq!(contents[1]["parts"][1]["functionCall"]["args"], json!({"city": "Paris"}));
+
+        // Both responses answer the model turn in one content, named after their calls
+        let parts = contents[2]["parts"].as_array().unwrap();
+        assert_eq!(contents[2]["role"], "user");
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0]["functionResponse"]["name"], "get_weather");
+        assert_eq!(parts[0]["functionResponse"]["response"], json!({"temp": 18}));
+        assert_eq!(parts[1]["functionResponse"]["name"], "list_cities");
+        assert_eq!(parts[1]["functionResponse"]["response"], json!({"result": ["Paris", "Rome"]}));
+    }
+}
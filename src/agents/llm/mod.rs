@@ -6,6 +6,8 @@
 //! - Google Gemini
 //! - Ollama (local models)
 //! - Azure OpenAI
+//!
+//! Other providers can be added at startup through [`register_provider`].
 
 mod stream;
 mod openai;
@@ -13,6 +15,7 @@ mod anthropic;
 mod gemini;
 mod ollama;
 mod azure;
+mod registry;
 
 pub use stream::*;
 pub use openai::OpenAiProvider;
@@ -20,6 +23,7 @@ pub use anthropic::AnthropicProvider;
 pub use gemini::GeminiProvider;
 pub use ollama::OllamaProvider;
 pub use azure::AzureOpenAiProvider;
+pub use registry::{ProviderFactory, ProviderRegistry};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -69,8 +73,13 @@ pub trait LlmProvider: Send + Sync {
     fn max_output_tokens(&self) -> u32;
 }
 
+/// Rough token count (~4 characters per token), for when no provider can be created
+pub fn approximate_token_count(text: &str) -> u32 {
+    (text.len() / 4) as u32
+}
+
 /// Request for LLM completion
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CompletionRequest {
     /// Messages in the conversation
     pub messages: Vec<Message>,
@@ -92,26 +101,23 @@ pub struct CompletionRequest {
     /// Stop sequences
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<Vec<String>>,
+    /// Nucleus sampling probability mass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Penalty on tokens by how often they already occurred
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Penalty on tokens that already occurred at all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Seed for reproducible sampling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
     /// Whether to stream the response
     #[serde(default)]
     pub stream: bool,
 }
 
-impl Default for CompletionRequest {
-    fn default() -> Self {
-        Self {
-            messages: Vec::new(),
-            model: None,
-            temperature: None,
-            max_tokens: None,
-            tools: None,
-            tool_choice: None,
-            stop: None,
-            stream: false,
-        }
-    }
-}
-
 /// Tool choice mode
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -162,9 +168,10 @@ pub struct TokenUsage {
 
 use crate::adapters::secrets::SharedSecretsStore;
 
-/// Create an LLM provider from configuration
-pub fn create_provider(config: &LlmProviderConfig) -> LlmResult<Arc<dyn LlmProvider>> {
-    match config.provider {
+/// Create an LLM provider from configuration, looking custom providers up in
+/// `registry`
+pub fn create_provider(config: &LlmProviderConfig, registry: &ProviderRegistry) -> LlmResult<Arc<dyn LlmProvider>> {
+    match &config.provider {
         LlmProviderType::OpenAI => {
             let provider = OpenAiProvider::new(config)?;
             Ok(Arc::new(provider))
@@ -185,6 +192,7 @@ pub fn create_provider(config: &LlmProviderConfig) -> LlmResult<Arc<dyn LlmProvi
             let provider = AzureOpenAiProvider::new(config)?;
             Ok(Arc::new(provider))
         }
+        LlmProviderType::Custom(name) => registry.create(name, config),
     }
 }
 
@@ -196,8 +204,9 @@ pub fn create_provider(config: &LlmProviderConfig) -> LlmResult<Arc<dyn LlmProvi
 pub async fn create_provider_with_secrets(
     config: &LlmProviderConfig,
     secrets: SharedSecretsStore,
+    registry: &ProviderRegistry,
 ) -> LlmResult<Arc<dyn LlmProvider>> {
-    match config.provider {
+    match &config.provider {
         LlmProviderType::OpenAI => {
             let provider = OpenAiProvider::new_with_secrets(config, secrets).await?;
             Ok(Arc::new(provider))
@@ -219,5 +228,7 @@ pub async fn create_provider_with_secrets(
             let provider = AzureOpenAiProvider::new_with_secrets(config, secrets).await?;
             Ok(Arc::new(provider))
         }
+        // Custom providers find their own credentials
+        LlmProviderType::Custom(name) => registry.create(name, config),
     }
 }
@@ -212,10 +212,10 @@ impl OllamaProvider {
 
                 if let Ok(stream_response) = serde_json::from_str::<OllamaStreamResponse>(&line) {
                     if let Some(content) = &stream_response.message.content {
-                        if !content.is_empty() {
-                            if sender.send(StreamChunk::text(content)).await.is_err() {
-                                return Ok(());
-                            }
+                        if !content.is_empty()
+                            && sender.send(StreamChunk::text(content)).await.is_err()
+                        {
+                            return Ok(());
                         }
                     }
 
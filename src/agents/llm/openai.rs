@@ -106,12 +106,28 @@ impl OpenAiProvider {
             body["stop"] = json!(stop);
         }
 
+        if let Some(top_p) = request.top_p {
+            body["top_p"] = json!(top_p);
+        }
+
+        if let Some(penalty) = request.frequency_penalty {
+            body["frequency_penalty"] = json!(penalty);
+        }
+
+        if let Some(penalty) = request.presence_penalty {
+            body["presence_penalty"] = json!(penalty);
+        }
+
+        if let Some(seed) = request.seed {
+            body["seed"] = json!(seed);
+        }
+
         if let Some(tools) = &request.tools {
             if !tools.is_empty() {
                 body["tools"] = json!(tools.iter().map(|t| {
                     // Ensure parameters is a valid JSON Schema object
                     // OpenAI requires {"type": "object", "properties": {...}} for function parameters
-                    let params = if t.parameters.is_null() || t.parameters.as_object().map_or(true, |o| o.is_empty()) {
+                    let params = if t.parameters.is_null() || t.parameters.as_object().is_none_or(|o| o.is_empty()) {
                         // Empty or null schema - provide complete default
                         json!({
                             "type": "object",
@@ -180,6 +196,17 @@ impl OpenAiProvider {
                     "content": m.content,
                 });
 
+                if !m.images.is_empty() {
+                    let mut parts = vec![json!({ "type": "text", "text": m.content })];
+                    parts.extend(m.images.iter().map(|image| {
+                        json!({
+                            "type": "image_url",
+                            "image_url": { "url": image.to_url() }
+                        })
+                    }));
+                    msg["content"] = json!(parts);
+                }
+
                 if let Some(tool_calls) = &m.tool_calls {
                     msg["tool_calls"] = json!(tool_calls.iter().map(|tc| {
                         json!({
@@ -451,8 +478,7 @@ impl OpenAiProvider {
                     continue;
                 }
 
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
+                if let Some(data) = line.strip_prefix("data: ") {
                     if data == "[DONE]" {
                         return Ok(());
                     }
@@ -587,3 +613,74 @@ struct OpenAiStreamFunction {
     name: Option<String>,
     arguments: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::core::input_images;
+
+    fn provider() -> OpenAiProvider {
+        OpenAiProvider {
+            client: reqwest::Client::new(),
+            api_key: "test".to_string(),
+            base_url: "http://localhost".to_string(),
+            model: "gpt-4o".to_string(),
+            default_temperature: None,
+            default_max_tokens: None,
+        }
+    }
+
+    #[test]
+    fn test_images_are_sent_as_content_parts() {
+        let input = json!({
+            "prompt": "What is in these pictures?",
+            "images": [
+                "https://example.com/cat.png",
+                "data:image/png;base64,iVBORw0KGgo=",
+                {"media_type": "image/jpeg", "data": "/9j/4AAQ"}
+            ]
+        });
+        let request = CompletionRequest {
+            messages: vec![
+                Message::system("You describe images"),
+                Message::user("What is in these pictures?").with_images(input_images(&input)),
+            ],
+            ..Default::default()
+        };
+        let body = provider().build_request_body(&request);
+
+        assert_eq!(body["messages"][0]["content"], "You describe images");
+        assert_eq!(
+            body["messages"][1]["content"],
+            json!([
+                {"type": "text", "text": "What is in these pictures?"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw0KGgo="}},
+                {"type": "image_url", "image_url": {"url": "data:image/jpeg;base64,/9j/4AAQ"}}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_sampling_parameters_are_passed_through() {
+        let request = CompletionRequest {
+            messages: vec![Message::user("Pick a number")],
+            stop: Some(vec!["\n".to_string()]),
+            top_p: Some(0.5),
+            frequency_penalty: Some(0.25),
+            presence_penalty: Some(-0.5),
+            seed: Some(42),
+            ..Default::default()
+        };
+        let body = provider().build_request_body(&request);
+
+        assert_eq!(body["stop"], json!(["\n"]));
+        assert_eq!(body["top_p"], json!(0.5));
+        assert_eq!(body["frequency_penalty"], json!(0.25));
+        assert_eq!(body["presence_penalty"], json!(-0.5));
+        assert_eq!(body["seed"], json!(42));
+
+        let body = provider().build_request_body(&CompletionRequest::default());
+        assert!(body.get("top_p").is_none() && body.get("seed").is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! Custom LLM providers registered at startup
+//!
+//! An agent whose `llm.provider` isn't one of the built-in vendors names a
+//! provider registered here, so a binary embedding Metis can add its own
+//! (e.g. an in-house gateway) without changing [`LlmProviderType`]. `main`
+//! builds the registry and hands it to `create_app` and the agent handler:
+//!
+//! ```ignore
+//! let llm_providers = ProviderRegistry::new();
+//! llm_providers.register_provider("gateway", |config| {
+//!     Ok(Arc::new(GatewayProvider::new(&config.model)?))
+//! });
+//! let llm_providers = Arc::new(llm_providers);
+//! ```
+//!
+//! [`LlmProviderType`]: crate::agents::config::LlmProviderType
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use super::LlmProvider;
+use crate::agents::config::LlmProviderConfig;
+use crate::agents::error::{LlmError, LlmResult};
+
+/// Builds a provider for an agent's LLM config
+pub type ProviderFactory = Arc<dyn Fn(&LlmProviderConfig) -> LlmResult<Arc<dyn LlmProvider>> + Send + Sync>;
+
+/// Factories of custom providers by `provider` name
+#[derive(Default)]
+pub struct ProviderRegistry {
+    factories: RwLock<HashMap<String, ProviderFactory>>,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `factory` the provider for agents with `provider = "<name>"`,
+    /// replacing any registered under the same name
+    pub fn register_provider<F>(&self, name: impl Into<String>, factory: F) -> &Self
+    where
+        F: Fn(&LlmProviderConfig) -> LlmResult<Arc<dyn LlmProvider>> + Send + Sync + 'static,
+    {
+        let mut factories = self.factories.write().unwrap_or_else(|e| e.into_inner());
+        factories.insert(name.into(), Arc::new(factory));
+        self
+    }
+
+    /// Names of the registered providers, sorted
+    pub fn names(&self) -> Vec<String> {
+        let factories = self.factories.read().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<String> = factories.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Whether a provider is registered as `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.read().unwrap_or_else(|e| e.into_inner()).contains_key(name)
+    }
+
+    /// Build the provider registered as `name` for `config`
+    pub fn create(&self, name: &str, config: &LlmProviderConfig) -> LlmResult<Arc<dyn LlmProvider>> {
+        let factory = self
+            .factories
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+            .ok_or_else(|| LlmError::ProviderNotFound(name.to_string()))?;
+        factory(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::llm::{CompletionRequest, CompletionResponse, LlmStream};
+    use async_trait::async_trait;
+
+    struct Gateway {
+        model: String,
+    }
+
+    #[async_trait]
+    impl LlmProvider for Gateway {
+        fn name(&self) -> &str {
+            "gateway"
+        }
+
+        fn model(&self) -> &str {
+            &self.model
+        }
+
+        async fn complete(&self, _request: CompletionRequest) -> LlmResult<CompletionResponse> {
+            Err(LlmError::InvalidRequest("not supported".to_string()))
+        }
+
+        fn complete_stream(&self, _request: CompletionRequest) -> LlmStream {
+            let (_tx, rx) = tokio::sync::mpsc::channel(1);
+            LlmStream::new(rx)
+        }
+
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.len() as u32
+        }
+
+        fn context_window(&self) -> u32 {
+            8192
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            1024
+        }
+    }
+
+    fn llm_config(provider: &str) -> LlmProviderConfig {
+        serde_json::from_value(serde_json::json!({"provider": provider, "model": "house-model"})).unwrap()
+    }
+
+    #[test]
+    fn test_custom_provider_from_config() {
+        let registry = ProviderRegistry::new();
+        registry.register_provider("gateway", |config| {
+            Ok(Arc::new(Gateway {
+                model: config.model.clone(),
+            }) as Arc<dyn LlmProvider>)
+        });
+        assert_eq!(registry.names(), vec!["gateway".to_string()]);
+        assert!(registry.contains("gateway") && !registry.contains("elsewhere"));
+
+        let config = llm_config("gateway");
+        assert_eq!(config.provider.to_string(), "gateway");
+        let provider = registry.create("gateway", &config).unwrap();
+        assert_eq!((provider.name(), provider.model()), ("gateway", "house-model"));
+
+        let err = registry.create("elsewhere", &llm_config("elsewhere")).err().unwrap();
+        assert!(matches!(err, LlmError::ProviderNotFound(name) if name == "elsewhere"));
+    }
+
+    #[test]
+    fn test_create_provider_looks_up_custom_providers() {
+        let registry = ProviderRegistry::new();
+        registry.register_provider("gateway", |config| {
+            Ok(Arc::new(Gateway {
+                model: config.model.clone(),
+            }) as Arc<dyn LlmProvider>)
+        });
+        let provider = crate::agents::llm::create_provider(&llm_config("gateway"), &registry).unwrap();
+        assert_eq!(provider.name(), "gateway");
+
+        let err = crate::agents::llm::create_provider(&llm_config("gateway"), &ProviderRegistry::new()).err().unwrap();
+        assert!(matches!(err, LlmError::ProviderNotFound(_)));
+    }
+}
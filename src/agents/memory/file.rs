@@ -1,16 +1,52 @@
 //! File-based conversation store
+//!
+//! Each session is a `<session_id>.json` file in the store's directory.
+//! Sessions are written to a temporary file and renamed into place, so a
+//! reader (or another process compacting the directory) never sees a
+//! half-written session. Writers also lock the directory's `.lock` file, so
+//! `metis session-gc` can compact the directory while a server is using it.
 
 use async_trait::async_trait;
+use serde::Serialize;
+use std::io::{Seek, Write};
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::fs;
+use tokio::sync::{Mutex, MutexGuard};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
 
 use super::ConversationStore;
 use crate::agents::domain::{ConversationSession, Message, SessionSummary};
 use crate::agents::error::{AgentError, AgentResult};
 
+/// Directory used when `memory.file_path` isn't set
+pub const DEFAULT_SESSION_DIR: &str = "data/sessions";
+
+/// File in the store's directory that writers lock, across processes
+const LOCK_FILE: &str = ".lock";
+
+/// Outcome of [`FileStore::compact`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionReport {
+    /// Sessions deleted for being too old
+    pub removed: usize,
+    /// Sessions left in place
+    pub kept: usize,
+}
+
 /// File-based conversation store
 pub struct FileStore {
     base_path: PathBuf,
+    /// Held while writing or deleting session files, so compaction doesn't
+    /// race a save in the same process
+    writes: Mutex<()>,
+}
+
+/// Exclusive right to write the store's directory, released on drop
+struct WriteLock<'a> {
+    _writes: MutexGuard<'a, ()>,
+    _file: std::fs::File,
 }
 
 impl FileStore {
@@ -23,12 +59,130 @@ impl FileStore {
             AgentError::Memory(format!("Failed to create directory: {}", e))
         })?;
 
-        Ok(Self { base_path })
+        Ok(Self {
+            base_path,
+            writes: Mutex::new(()),
+        })
+    }
+
+    /// Lock the directory against writers in this process and, through its
+    /// lock file, in other processes
+    async fn lock_writes(&self) -> AgentResult<WriteLock<'_>> {
+        let writes = self.writes.lock().await;
+        let path = self.base_path.join(LOCK_FILE);
+        let file = tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)?;
+            file.lock()?;
+            Ok::<_, std::io::Error>(file)
+        })
+        .await
+        .map_err(|e| AgentError::Memory(format!("Failed to lock session directory: {}", e)))?
+        .map_err(|e| AgentError::Memory(format!("Failed to lock session directory: {}", e)))?;
+        Ok(WriteLock { _writes: writes, _file: file })
     }
 
     fn session_path(&self, session_id: &str) -> PathBuf {
         self.base_path.join(format!("{}.json", session_id))
     }
+
+    /// Session files in the directory, in name order
+    async fn session_files(&self) -> AgentResult<Vec<PathBuf>> {
+        let mut entries = fs::read_dir(&self.base_path).await.map_err(|e| {
+            AgentError::Memory(format!("Failed to read directory: {}", e))
+        })?;
+
+        let mut paths = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| {
+            AgentError::Memory(format!("Failed to read directory entry: {}", e))
+        })? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Delete sessions not updated for `max_age`. A session file rewritten
+    /// since it was read, e.g. by a server sharing the directory, is kept.
+    /// Files that aren't sessions are left alone.
+    pub async fn compact(&self, max_age: Duration) -> AgentResult<CompactionReport> {
+        let _writes = self.lock_writes().await?;
+        let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(UNIX_EPOCH);
+        let cutoff_ms = cutoff.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+
+        let mut report = CompactionReport::default();
+        for path in self.session_files().await? {
+            let Ok(content) = fs::read_to_string(&path).await else {
+                continue;
+            };
+            let Ok(session) = serde_json::from_str::<ConversationSession>(&content) else {
+                continue;
+            };
+            if session.updated_at >= cutoff_ms {
+                report.kept += 1;
+                continue;
+            }
+
+            let rewritten = match fs::metadata(&path).await.and_then(|m| m.modified()) {
+                Ok(modified) => modified >= cutoff,
+                Err(_) => true,
+            };
+            if rewritten {
+                report.kept += 1;
+                continue;
+            }
+            match fs::remove_file(&path).await {
+                Ok(()) => report.removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(AgentError::Memory(format!(
+                        "Failed to delete session file {}: {}",
+                        path.display(),
+                        e
+                    )));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Add every session to `archive` as `<prefix><session_id>.json`,
+    /// returning the number of sessions added
+    pub async fn export_all<W: Write + Seek>(
+        &self,
+        archive: &mut ZipWriter<W>,
+        prefix: &str,
+    ) -> AgentResult<usize> {
+        let mut sessions = Vec::new();
+        for path in self.session_files().await? {
+            let Ok(content) = fs::read_to_string(&path).await else {
+                continue;
+            };
+            if serde_json::from_str::<ConversationSession>(&content).is_err() {
+                continue;
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            sessions.push((name, content));
+        }
+
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, content) in &sessions {
+            let failed = |e: &dyn std::fmt::Display| {
+                AgentError::Memory(format!("Failed to archive session {}: {}", name, e))
+            };
+            archive
+                .start_file(format!("{}{}", prefix, name), options)
+                .map_err(|e| failed(&e))?;
+            archive.write_all(content.as_bytes()).map_err(|e| failed(&e))?;
+        }
+        Ok(sessions.len())
+    }
 }
 
 #[async_trait]
@@ -37,7 +191,12 @@ impl ConversationStore for FileStore {
         let path = self.session_path(&session.session_id);
         let content = serde_json::to_string_pretty(session)?;
 
-        fs::write(&path, content).await.map_err(|e| {
+        let _writes = self.lock_writes().await?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content).await.map_err(|e| {
+            AgentError::Memory(format!("Failed to write session file: {}", e))
+        })?;
+        fs::rename(&temp_path, &path).await.map_err(|e| {
             AgentError::Memory(format!("Failed to write session file: {}", e))
         })?;
 
@@ -62,6 +221,7 @@ impl ConversationStore for FileStore {
     async fn delete(&self, session_id: &str) -> AgentResult<()> {
         let path = self.session_path(session_id);
 
+        let _writes = self.lock_writes().await?;
         if path.exists() {
             fs::remove_file(&path).await.map_err(|e| {
                 AgentError::Memory(format!("Failed to delete session file: {}", e))
@@ -88,10 +248,10 @@ impl ConversationStore for FileStore {
         })? {
             let path = entry.path();
 
-            if path.extension().map_or(false, |ext| ext == "json") {
+            if path.extension().is_some_and(|ext| ext == "json") {
                 if let Ok(content) = fs::read_to_string(&path).await {
                     if let Ok(session) = serde_json::from_str::<ConversationSession>(&content) {
-                        if agent_name.map_or(true, |name| session.agent_name == name) {
+                        if agent_name.is_none_or(|name| session.agent_name == name) {
                             summaries.push(session.to_summary());
                         }
                     }
@@ -100,7 +260,7 @@ impl ConversationStore for FileStore {
         }
 
         // Sort by updated_at descending
-        summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
 
         // Apply pagination
         Ok(summaries.into_iter().skip(offset).take(limit).collect())
@@ -115,3 +275,79 @@ impl ConversationStore for FileStore {
         self.save(&session).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    async fn session(store: &FileStore, id: &str, updated_at: u64) {
+        let mut session = ConversationSession::new(id.to_string(), "assistant".to_string());
+        session.add_message(Message::user("hello"));
+        session.updated_at = updated_at;
+        store.save(&session).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_compact_removes_old_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path()).unwrap();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        session(&store, "fresh", now).await;
+        session(&store, "stale", now - 3_600_000).await;
+        std::fs::write(dir.path().join("notes.json"), "not a session").unwrap();
+
+        // The stale file was written just now, so it counts as rewritten
+        let report = store.compact(Duration::from_secs(60)).await.unwrap();
+        assert_eq!(report, CompactionReport { removed: 0, kept: 2 });
+
+        let old = SystemTime::now() - Duration::from_secs(7200);
+        std::fs::File::options()
+            .write(true)
+            .open(dir.path().join("stale.json"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        let report = store.compact(Duration::from_secs(60)).await.unwrap();
+        assert_eq!(report, CompactionReport { removed: 1, kept: 1 });
+        assert!(store.load("stale").await.unwrap().is_none());
+        assert!(store.load("fresh").await.unwrap().is_some());
+        assert!(dir.path().join("notes.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_writers_lock_the_directory_across_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path()).unwrap();
+        let lock_file = || std::fs::File::open(dir.path().join(LOCK_FILE)).unwrap();
+
+        let held = store.lock_writes().await.unwrap();
+        assert!(matches!(lock_file().try_lock(), Err(std::fs::TryLockError::WouldBlock)));
+        drop(held);
+        assert!(lock_file().try_lock().is_ok());
+
+        // The lock file isn't mistaken for a session
+        session(&store, "a", 1).await;
+        assert_eq!(store.list(None, 10, 0).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_export_all_archives_every_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path()).unwrap();
+        session(&store, "a", 1).await;
+        session(&store, "b", 2).await;
+
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        assert_eq!(store.export_all(&mut archive, "sessions/").await.unwrap(), 2);
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(names, ["sessions/a.json", "sessions/b.json"]);
+        let session: ConversationSession =
+            serde_json::from_reader(archive.by_name("sessions/b.json").unwrap()).unwrap();
+        assert_eq!(session.updated_at, 2);
+    }
+}
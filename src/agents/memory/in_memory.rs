@@ -54,12 +54,12 @@ impl ConversationStore for InMemoryStore {
 
         let mut summaries: Vec<SessionSummary> = sessions
             .values()
-            .filter(|s| agent_name.map_or(true, |name| s.agent_name == name))
+            .filter(|s| agent_name.is_none_or(|name| s.agent_name == name))
             .map(|s| s.to_summary())
             .collect();
 
         // Sort by updated_at descending
-        summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
 
         // Apply pagination
         Ok(summaries.into_iter().skip(offset).take(limit).collect())
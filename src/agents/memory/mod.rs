@@ -9,11 +9,13 @@ mod in_memory;
 mod file;
 mod database;
 mod strategy;
+mod summary;
 
 pub use in_memory::InMemoryStore;
-pub use file::FileStore;
+pub use file::{CompactionReport, FileStore, DEFAULT_SESSION_DIR};
 pub use database::DatabaseStore;
 pub use strategy::*;
+pub use summary::{ConversationSummary, SUMMARY_PREFIX};
 
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -64,7 +66,7 @@ pub fn create_store(config: &MemoryConfig) -> AgentResult<Arc<dyn ConversationSt
             Ok(Arc::new(InMemoryStore::new(config.max_messages as usize)))
         }
         MemoryBackend::File => {
-            let path = config.file_path.clone().unwrap_or_else(|| "data/sessions".to_string());
+            let path = config.file_path.clone().unwrap_or_else(|| DEFAULT_SESSION_DIR.to_string());
             Ok(Arc::new(FileStore::new(path)?))
         }
         MemoryBackend::Database => {
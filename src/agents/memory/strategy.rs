@@ -1,7 +1,7 @@
 //! Memory management strategies for conversation history
 
 use crate::agents::config::MemoryStrategy;
-use crate::agents::domain::Message;
+use crate::agents::domain::{Message, Role};
 
 /// Apply a memory strategy to a list of messages
 pub fn apply_strategy(messages: &[Message], strategy: &MemoryStrategy, budget_tokens: Option<u32>) -> Vec<Message> {
@@ -24,6 +24,26 @@ pub fn apply_strategy(messages: &[Message], strategy: &MemoryStrategy, budget_to
     }
 }
 
+/// Keep the current (last) message and at most `window` messages before it.
+/// System messages, such as summaries, don't count and are always kept.
+pub fn apply_history_window(messages: Vec<Message>, window: Option<usize>) -> Vec<Message> {
+    let Some(window) = window else {
+        return messages;
+    };
+    let conversation = messages.iter().filter(|m| m.role != Role::System).count();
+    let mut to_drop = conversation.saturating_sub(window + 1);
+    messages
+        .into_iter()
+        .filter(|m| {
+            if to_drop == 0 || m.role == Role::System {
+                return true;
+            }
+            to_drop -= 1;
+            false
+        })
+        .collect()
+}
+
 /// Keep only the last N messages (plus system message if present)
 fn apply_sliding_window(messages: &[Message], window_size: usize) -> Vec<Message> {
     if messages.is_empty() {
@@ -33,7 +53,7 @@ fn apply_sliding_window(messages: &[Message], window_size: usize) -> Vec<Message
     let mut result = Vec::new();
 
     // Always keep system message if it's first
-    let start_idx = if !messages.is_empty() && matches!(messages[0].role, crate::agents::domain::Role::System) {
+    let start_idx = if !messages.is_empty() && matches!(messages[0].role, Role::System) {
         result.push(messages[0].clone());
         1
     } else {
@@ -79,7 +99,6 @@ fn apply_first_last(messages: &[Message], first_count: usize, last_count: usize)
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::agents::domain::Role;
 
     fn make_msg(role: Role, content: &str) -> Message {
         Message {
@@ -88,6 +107,7 @@ mod tests {
             tool_calls: None,
             tool_call_id: None,
             name: None,
+            images: Vec::new(),
         }
     }
 
@@ -124,6 +144,25 @@ mod tests {
         assert_eq!(result[2].content, "4");
     }
 
+    #[test]
+    fn test_history_window_keeps_current_message_and_system() {
+        let messages = vec![
+            make_msg(Role::System, "summary"),
+            make_msg(Role::User, "1"),
+            make_msg(Role::Assistant, "2"),
+            make_msg(Role::User, "3"),
+            make_msg(Role::Assistant, "4"),
+            make_msg(Role::User, "5"),
+        ];
+
+        let result = apply_history_window(messages.clone(), Some(2));
+        let contents: Vec<_> = result.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["summary", "3", "4", "5"]);
+
+        assert_eq!(apply_history_window(messages.clone(), Some(0)).len(), 2);
+        assert_eq!(apply_history_window(messages, None).len(), 6);
+    }
+
     #[test]
     fn test_first_last() {
         let messages = vec![
@@ -0,0 +1,176 @@
+//! Conversation summarization
+//!
+//! Long conversations are compressed by asking the LLM to summarize the oldest
+//! messages and replacing them with a single system message.
+
+use crate::agents::config::SummaryConfig;
+use crate::agents::domain::{Message, Role};
+use crate::agents::error::AgentResult;
+use crate::agents::llm::{CompletionRequest, LlmProvider};
+
+/// Prefix marking a system message as a conversation summary
+pub const SUMMARY_PREFIX: &str = "[Summary of earlier conversation]:";
+
+/// Compresses old messages of a conversation into summary messages
+pub struct ConversationSummary {
+    config: SummaryConfig,
+}
+
+impl ConversationSummary {
+    pub fn new(config: SummaryConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether more than `every_n_messages` messages were added since the last summary
+    pub fn needs_summary(&self, messages: &[Message]) -> bool {
+        let since_summary = messages[summary_end(messages)..]
+            .iter()
+            .filter(|m| m.role != Role::System)
+            .count();
+        since_summary > self.config.every_n_messages as usize
+    }
+
+    /// Summarize the oldest half of the non-system messages and replace them with
+    /// a summary message, placed after any earlier summaries.
+    /// Returns `false` if there was nothing to summarize.
+    pub async fn compress(&self, llm: &dyn LlmProvider, messages: &mut Vec<Message>) -> AgentResult<bool> {
+        let candidates: Vec<usize> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.role != Role::System)
+            .map(|(idx, _)| idx)
+            .collect();
+        let to_summarize = &candidates[..candidates.len() / 2];
+        if to_summarize.is_empty() {
+            return Ok(false);
+        }
+
+        let transcript = to_summarize
+            .iter()
+            .map(|idx| format!("{}: {}", messages[*idx].role, messages[*idx].content))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let request = CompletionRequest {
+            messages: vec![Message::system(&self.config.summary_prompt), Message::user(transcript)],
+            ..Default::default()
+        };
+        let response = llm.complete(request).await?;
+        let summary = Message::system(format!("{} {}", SUMMARY_PREFIX, response.message.content.trim()));
+
+        let insert_at = summary_end(messages);
+        let mut idx = 0;
+        messages.retain(|_| {
+            let keep = !to_summarize.contains(&idx);
+            idx += 1;
+            keep
+        });
+        let removed_before = to_summarize.iter().filter(|i| **i < insert_at).count();
+        messages.insert(insert_at - removed_before, summary);
+
+        Ok(true)
+    }
+}
+
+/// Index just past the last summary message, or 0 if there is none
+fn summary_end(messages: &[Message]) -> usize {
+    messages
+        .iter()
+        .rposition(is_summary)
+        .map(|idx| idx + 1)
+        .unwrap_or(0)
+}
+
+fn is_summary(message: &Message) -> bool {
+    message.role == Role::System && message.content.starts_with(SUMMARY_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::error::{LlmError, LlmResult};
+    use crate::agents::llm::{CompletionResponse, FinishReason, LlmStream};
+
+    /// LLM that replies with the number of lines it was asked to summarize
+    struct CountingLlm;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for CountingLlm {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn model(&self) -> &str {
+            "counting"
+        }
+
+        async fn complete(&self, request: CompletionRequest) -> LlmResult<CompletionResponse> {
+            let lines = request.messages.last().map(|m| m.content.lines().count()).unwrap_or(0);
+            Ok(CompletionResponse {
+                message: Message::assistant(format!("{} messages", lines)),
+                finish_reason: FinishReason::Stop,
+                usage: None,
+            })
+        }
+
+        fn complete_stream(&self, _request: CompletionRequest) -> LlmStream {
+            let (tx, rx) = tokio::sync::mpsc::channel(1);
+            tx.try_send(Err(LlmError::InvalidRequest("not supported".to_string()))).unwrap();
+            LlmStream::new(rx)
+        }
+
+        fn count_tokens(&self, text: &str) -> u32 {
+            text.len() as u32
+        }
+
+        fn context_window(&self) -> u32 {
+            8192
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            1024
+        }
+    }
+
+    fn compressor(every_n_messages: u32) -> ConversationSummary {
+        ConversationSummary::new(SummaryConfig {
+            every_n_messages,
+            summary_prompt: "Summarize".to_string(),
+        })
+    }
+
+    fn conversation(turns: usize) -> Vec<Message> {
+        (0..turns)
+            .flat_map(|i| [Message::user(format!("q{}", i)), Message::assistant(format!("a{}", i))])
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_compress_replaces_oldest_half() {
+        let summary = compressor(4);
+        let mut messages = conversation(3);
+        assert!(summary.needs_summary(&messages));
+
+        assert!(summary.compress(&CountingLlm, &mut messages).await.unwrap());
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(messages[0].content, format!("{} 3 messages", SUMMARY_PREFIX));
+        assert_eq!(messages[1].content, "a1");
+        assert!(!summary.needs_summary(&messages));
+    }
+
+    #[tokio::test]
+    async fn test_compress_keeps_earlier_summaries() {
+        let summary = compressor(2);
+        let mut messages = vec![Message::system(format!("{} earlier", SUMMARY_PREFIX))];
+        messages.extend(conversation(2));
+
+        assert!(summary.needs_summary(&messages));
+        summary.compress(&CountingLlm, &mut messages).await.unwrap();
+
+        assert_eq!(messages.len(), 4);
+        assert!(messages[0].content.ends_with("earlier"));
+        assert_eq!(messages[1].content, format!("{} 2 messages", SUMMARY_PREFIX));
+        assert_eq!(messages[2].content, "q1");
+    }
+}
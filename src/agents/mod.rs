@@ -13,8 +13,12 @@
 //! - `core/` - Agent implementations (SingleTurn, MultiTurn, ReAct)
 //! - `orchestration/` - Multi-agent patterns
 //! - `memory/` - Persistence backends
+//! - `traces` - Persisted tool-calling traces of ReAct agents
+//! - `costs` - Per-agent cost accounting of LLM completions
 
 pub mod config;
+pub mod confirmation;
+pub mod costs;
 pub mod domain;
 pub mod error;
 pub mod handler;
@@ -23,6 +27,7 @@ pub mod memory;
 pub mod core;
 pub mod orchestration;
 pub mod token;
+pub mod traces;
 
 // Re-export commonly used types
 pub use config::*;
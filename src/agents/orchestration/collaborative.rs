@@ -8,10 +8,12 @@ use futures::future::join_all;
 use futures::StreamExt;
 use rhai::{Engine, Scope};
 use serde_json::{json, Value};
+use tracing::Instrument;
 
 use crate::agents::config::{MergeStrategy, OrchestrationConfig};
 use crate::agents::core::Agent;
 use crate::agents::domain::{AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender};
+use crate::domain::execution::ExecutionContext;
 
 /// Collaborative orchestrator: agents work in parallel
 pub struct CollaborativeOrchestrator;
@@ -28,9 +30,12 @@ impl CollaborativeOrchestrator {
         let agents = agents.clone();
         let config = config.clone();
 
-        tokio::spawn(async move {
-            Self::execute_internal(agents, config, input, sender).await;
-        });
+        tokio::spawn(ExecutionContext::propagate(
+            async move {
+                Self::execute_internal(agents, config, input, sender).await;
+            }
+            .in_current_span(),
+        ));
 
         stream
     }
@@ -109,6 +114,7 @@ impl CollaborativeOrchestrator {
             output: merged_output,
             tool_calls: all_tool_calls,
             reasoning_steps: Vec::new(),
+            trace: Vec::new(),
             session_id: None,
             iterations: config.agents.len() as u32,
             usage: None,
@@ -5,10 +5,12 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use serde_json::{json, Value};
+use tracing::Instrument;
 
 use crate::agents::config::OrchestrationConfig;
 use crate::agents::core::Agent;
 use crate::agents::domain::{AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender};
+use crate::domain::execution::ExecutionContext;
 
 /// Hierarchical orchestrator: manager delegates to workers
 pub struct HierarchicalOrchestrator;
@@ -25,9 +27,12 @@ impl HierarchicalOrchestrator {
         let agents = agents.clone();
         let config = config.clone();
 
-        tokio::spawn(async move {
-            Self::execute_internal(agents, config, input, sender).await;
-        });
+        tokio::spawn(ExecutionContext::propagate(
+            async move {
+                Self::execute_internal(agents, config, input, sender).await;
+            }
+            .in_current_span(),
+        ));
 
         stream
     }
@@ -86,6 +91,7 @@ impl HierarchicalOrchestrator {
             }),
             tool_calls: Vec::new(),
             reasoning_steps: Vec::new(),
+            trace: Vec::new(),
             session_id: None,
             iterations: 0,
             usage: None,
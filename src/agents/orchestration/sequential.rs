@@ -7,10 +7,12 @@ use std::time::Instant;
 use futures::StreamExt;
 use rhai::{Engine, Scope};
 use serde_json::{json, Value};
+use tracing::Instrument;
 
 use crate::agents::config::OrchestrationConfig;
 use crate::agents::core::Agent;
 use crate::agents::domain::{AgentChunk, AgentResponse, AgentStatus, AgentStream, AgentStreamSender};
+use crate::domain::execution::ExecutionContext;
 
 /// Sequential orchestrator: agents execute in order
 pub struct SequentialOrchestrator;
@@ -27,9 +29,12 @@ impl SequentialOrchestrator {
         let agents = agents.clone();
         let config = config.clone();
 
-        tokio::spawn(async move {
-            Self::execute_internal(agents, config, input, sender).await;
-        });
+        tokio::spawn(ExecutionContext::propagate(
+            async move {
+                Self::execute_internal(agents, config, input, sender).await;
+            }
+            .in_current_span(),
+        ));
 
         stream
     }
@@ -177,6 +182,7 @@ impl SequentialOrchestrator {
             output: json!({ "results": all_results }),
             tool_calls: all_tool_calls,
             reasoning_steps: Vec::new(),
+            trace: Vec::new(),
             session_id: None,
             iterations: config.agents.len() as u32,
             usage: None,
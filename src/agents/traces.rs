@@ -0,0 +1,166 @@
+//! Persisted function-calling traces of ReAct agents
+//!
+//! Every completed ReAct execution is saved as a `DataRecord` in the
+//! `agent_traces` namespace (the record's data lake), with the agent name as
+//! its schema. A trace holds the input, the session, the final output and each
+//! tool call with its arguments and result, so tool usage can be inspected
+//! after the fact through `/api/agents/:name/traces`.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agents::domain::{AgentResponse, ToolCallResult};
+use crate::config::DataRecord;
+use crate::persistence::{DataRecordRepository, PersistenceError};
+
+/// Data lake agent traces are stored under
+pub const TRACE_NAMESPACE: &str = "agent_traces";
+
+/// Records scanned per page when filtering traces by session
+const SCAN_PAGE_SIZE: usize = 200;
+
+/// A stored agent execution with its tool calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTrace {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub created_at: String,
+    pub agent_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub input: Value,
+    #[serde(default)]
+    pub output: Value,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallResult>,
+    #[serde(default)]
+    pub iterations: u32,
+    #[serde(default)]
+    pub execution_time_ms: u64,
+}
+
+/// Trace listing entry: the execution without tool call arguments and results
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentTraceSummary {
+    pub id: String,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub input: Value,
+    /// Names of the tools called, in call order
+    pub tools: Vec<String>,
+    pub iterations: u32,
+    pub execution_time_ms: u64,
+}
+
+impl From<&AgentTrace> for AgentTraceSummary {
+    fn from(trace: &AgentTrace) -> Self {
+        Self {
+            id: trace.id.clone(),
+            created_at: trace.created_at.clone(),
+            session_id: trace.session_id.clone(),
+            input: trace.input.clone(),
+            tools: trace.tool_calls.iter().map(|c| c.tool_name.clone()).collect(),
+            iterations: trace.iterations,
+            execution_time_ms: trace.execution_time_ms,
+        }
+    }
+}
+
+impl AgentTrace {
+    fn from_record(record: DataRecord) -> Option<Self> {
+        let mut trace: AgentTrace = serde_json::from_value(record.data).ok()?;
+        trace.id = record.id;
+        trace.created_at = record.created_at;
+        Some(trace)
+    }
+}
+
+/// Reads and writes agent traces in a data record repository
+#[derive(Clone)]
+pub struct AgentTraceStore {
+    records: Arc<dyn DataRecordRepository>,
+}
+
+impl AgentTraceStore {
+    pub fn new(records: Arc<dyn DataRecordRepository>) -> Self {
+        Self { records }
+    }
+
+    /// Save the trace of one agent execution. Returns the trace ID.
+    pub async fn record(
+        &self,
+        agent_name: &str,
+        input: &Value,
+        session_id: Option<String>,
+        response: &AgentResponse,
+    ) -> Result<String, PersistenceError> {
+        let trace = AgentTrace {
+            id: String::new(),
+            created_at: String::new(),
+            agent_name: agent_name.to_string(),
+            session_id: response.session_id.clone().or(session_id),
+            input: input.clone(),
+            output: response.output.clone(),
+            tool_calls: response.tool_calls.clone(),
+            iterations: response.iterations,
+            execution_time_ms: response.execution_time_ms,
+        };
+        let data = serde_json::to_value(&trace).map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+        let record = DataRecord::new(TRACE_NAMESPACE, agent_name, data).with_source("agent:react");
+        Ok(self.records.create(&record).await?.id)
+    }
+
+    /// Most recent traces of an agent, newest first, optionally only those of one session
+    pub async fn list(
+        &self,
+        agent_name: &str,
+        session_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AgentTraceSummary>, PersistenceError> {
+        let Some(session_id) = session_id else {
+            let records = self.records.list(TRACE_NAMESPACE, Some(agent_name), limit, 0).await?;
+            return Ok(records
+                .into_iter()
+                .filter_map(AgentTrace::from_record)
+                .map(|t| AgentTraceSummary::from(&t))
+                .collect());
+        };
+
+        let mut summaries = Vec::new();
+        let mut offset = 0;
+        while summaries.len() < limit {
+            let records = self
+                .records
+                .list(TRACE_NAMESPACE, Some(agent_name), SCAN_PAGE_SIZE, offset)
+                .await?;
+            let page_len = records.len();
+            summaries.extend(
+                records
+                    .into_iter()
+                    .filter_map(AgentTrace::from_record)
+                    .filter(|t| t.session_id.as_deref() == Some(session_id))
+                    .map(|t| AgentTraceSummary::from(&t)),
+            );
+            if page_len < SCAN_PAGE_SIZE {
+                break;
+            }
+            offset += page_len;
+        }
+        summaries.truncate(limit);
+        Ok(summaries)
+    }
+
+    /// A single trace of an agent with its full tool calls
+    pub async fn get(&self, agent_name: &str, trace_id: &str) -> Result<Option<AgentTrace>, PersistenceError> {
+        Ok(self
+            .records
+            .get(trace_id)
+            .await?
+            .filter(|r| r.data_lake == TRACE_NAMESPACE && r.schema_name == agent_name)
+            .and_then(AgentTrace::from_record))
+    }
+}
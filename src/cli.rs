@@ -17,6 +17,11 @@ pub struct Cli {
     #[arg(long, env = "METIS_PORT")]
     pub port: Option<u16>,
 
+    /// Config profile overlaid on the base config: `metis.<profile>.toml`
+    /// and `config/<profile>/<type>/` (e.g. dev, staging, prod)
+    #[arg(long, env = "METIS_PROFILE")]
+    pub profile: Option<String>,
+
     /// Passphrase for decrypting AGE-encrypted secrets in config
     /// Can also be set via METIS_SECRET_PASSPHRASE environment variable
     #[arg(long, env = "METIS_SECRET_PASSPHRASE")]
@@ -46,6 +51,10 @@ pub struct Cli {
     #[arg(long, env = "METIS_S3_POLL_INTERVAL")]
     pub s3_poll_interval: Option<u64>,
 
+    /// Don't print the ASCII banner at startup
+    #[arg(long, env = "METIS_NO_BANNER")]
+    pub no_banner: bool,
+
     /// Subcommand
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -94,6 +103,75 @@ pub enum Commands {
         #[arg(long)]
         from_database: bool,
     },
+    /// Print the configuration a running server is serving, with schema `$ref`s
+    /// expanded and runtime tool overrides applied
+    Effective {
+        /// URL of the server (defaults to the configured host and port)
+        #[arg(long)]
+        url: Option<String>,
+        /// API key, when the server uses API key auth
+        #[arg(long, env = "METIS_API_KEY")]
+        api_key: Option<String>,
+        /// Bearer token, when the server uses bearer token or OAuth2 auth
+        #[arg(long, env = "METIS_API_TOKEN")]
+        token: Option<String>,
+        /// Output file path (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Output format: toml, json, or yaml
+        #[arg(short, long, default_value = "toml")]
+        format: String,
+    },
+    /// Save all stateful mock state of a running server to a JSON snapshot
+    ExportState {
+        /// URL of the server (defaults to the configured host and port)
+        #[arg(long)]
+        url: Option<String>,
+        /// API key, when the server uses API key auth
+        #[arg(long, env = "METIS_API_KEY")]
+        api_key: Option<String>,
+        /// Bearer token, when the server uses bearer token or OAuth2 auth
+        #[arg(long, env = "METIS_API_TOKEN")]
+        token: Option<String>,
+        /// Output file path (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Load a snapshot written by `export-state` into a running server
+    ImportState {
+        /// Snapshot file path
+        input: PathBuf,
+        /// URL of the server (defaults to the configured host and port)
+        #[arg(long)]
+        url: Option<String>,
+        /// API key, when the server uses API key auth
+        #[arg(long, env = "METIS_API_KEY")]
+        api_key: Option<String>,
+        /// Bearer token, when the server uses bearer token or OAuth2 auth
+        #[arg(long, env = "METIS_API_TOKEN")]
+        token: Option<String>,
+        /// Merge with the existing state instead of replacing it
+        #[arg(long)]
+        merge: bool,
+    },
+    /// Print the JSON Schema of the config file
+    ConfigSchema {
+        /// Output file path (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Inline nested types instead of referencing `$defs`
+        #[arg(long)]
+        inline: bool,
+    },
+    /// Rewrite item files (`config/<type>/*.json|yaml`) in canonical form:
+    /// sorted keys and consistent indentation
+    Fmt {
+        /// Config file whose item files are formatted (defaults to --config)
+        config: Option<PathBuf>,
+        /// Write the files in place instead of printing them
+        #[arg(short, long)]
+        write: bool,
+    },
     /// Import configuration from a file
     Import {
         /// Input file path
@@ -145,6 +223,24 @@ pub enum Commands {
         #[arg(long, env = "DATABASE_URL")]
         database_url: Option<String>,
     },
+    /// Delete agent sessions of the file memory backend that haven't been
+    /// updated for a while
+    SessionGc {
+        /// Session directory (defaults to those of the agents using file memory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+        /// Delete sessions not updated for this many days
+        #[arg(long, default_value = "30")]
+        max_age_days: u64,
+    },
+    /// Bundle every agent session of the file memory backend into a ZIP archive
+    SessionExportAll {
+        /// Archive path
+        output: PathBuf,
+        /// Session directory (defaults to those of the agents using file memory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
 }
 
 impl Cli {
@@ -169,8 +265,10 @@ mod tests {
         assert_eq!(cli.config, PathBuf::from("metis.toml"));
         assert!(cli.host.is_none());
         assert!(cli.port.is_none());
+        assert!(cli.profile.is_none());
         assert!(cli.s3_enabled.is_none());
         assert!(cli.s3_bucket.is_none());
+        assert!(!cli.no_banner);
     }
 
     #[test]
@@ -183,6 +281,8 @@ mod tests {
             "0.0.0.0",
             "--port",
             "8080",
+            "--profile",
+            "staging",
             "--s3-enabled",
             "--s3-bucket",
             "my-bucket",
@@ -196,6 +296,7 @@ mod tests {
         assert_eq!(cli.config, PathBuf::from("custom.toml"));
         assert_eq!(cli.host, Some("0.0.0.0".to_string()));
         assert_eq!(cli.port, Some(8080));
+        assert_eq!(cli.profile, Some("staging".to_string()));
         assert_eq!(cli.s3_enabled, Some(true));
         assert_eq!(cli.s3_bucket, Some("my-bucket".to_string()));
         assert_eq!(cli.s3_prefix, Some("config/".to_string()));
@@ -211,4 +312,34 @@ mod tests {
         let cli_with_bucket = Cli::parse_from(["metis", "--s3-bucket", "test"]);
         assert!(cli_with_bucket.has_s3_config());
     }
+
+    #[test]
+    fn test_fmt_command() {
+        let cli = Cli::parse_from(["metis", "fmt"]);
+        assert!(matches!(cli.command, Some(Commands::Fmt { config: None, write: false })));
+
+        let cli = Cli::parse_from(["metis", "fmt", "mocks/metis.toml", "--write"]);
+        match cli.command {
+            Some(Commands::Fmt { config, write }) => {
+                assert_eq!(config, Some(PathBuf::from("mocks/metis.toml")));
+                assert!(write);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_session_gc_command() {
+        let cli = Cli::parse_from(["metis", "session-gc"]);
+        assert!(matches!(cli.command, Some(Commands::SessionGc { path: None, max_age_days: 30 })));
+
+        let cli = Cli::parse_from(["metis", "session-gc", "--path", "data/chats", "--max-age-days", "7"]);
+        match cli.command {
+            Some(Commands::SessionGc { path, max_age_days }) => {
+                assert_eq!(path, Some(PathBuf::from("data/chats")));
+                assert_eq!(max_age_days, 7);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,131 @@
+//! Backups of the config file taken before it is overwritten
+//!
+//! Saving the config copies the current file to `<file>.bak.<timestamp>` next
+//! to it first, keeping the newest `server.config_backups` copies. The
+//! timestamp is UTC and sorts in time order, so names sort oldest first.
+
+use chrono::Utc;
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A backup of the config file
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigBackup {
+    /// File name, used to pick the backup to restore
+    pub name: String,
+    pub size: u64,
+}
+
+fn prefix(config_path: &Path) -> String {
+    let file_name = config_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "metis.toml".to_string());
+    format!("{}.bak.", file_name)
+}
+
+fn directory(config_path: &Path) -> &Path {
+    match config_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Backups of `config_path`, newest first
+pub fn list_backups(config_path: &Path) -> io::Result<Vec<ConfigBackup>> {
+    let prefix = prefix(config_path);
+    let entries = match std::fs::read_dir(directory(config_path)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut backups = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(&prefix) || !entry.file_type()?.is_file() {
+            continue;
+        }
+        backups.push(ConfigBackup {
+            name,
+            size: entry.metadata()?.len(),
+        });
+    }
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// Path of the backup of `config_path` called `name`, if there is one. Names
+/// that aren't backups of this file, such as paths, are rejected.
+pub fn backup_path(config_path: &Path, name: &str) -> Option<PathBuf> {
+    let is_backup = name.starts_with(&prefix(config_path))
+        && !name.contains(['/', '\\'])
+        && !name.contains("..");
+    let path = directory(config_path).join(name);
+    (is_backup && path.is_file()).then_some(path)
+}
+
+/// Copy `config_path` to a new timestamped backup and delete all but the
+/// newest `keep` backups. Does nothing when `keep` is 0 or there is no file
+/// yet; returns the path of the new backup.
+pub fn backup_config(config_path: &Path, keep: usize) -> io::Result<Option<PathBuf>> {
+    if keep == 0 || !config_path.is_file() {
+        return Ok(None);
+    }
+
+    let name = format!("{}{}", prefix(config_path), Utc::now().format("%Y%m%dT%H%M%S%.3fZ"));
+    let backup = directory(config_path).join(name);
+    std::fs::copy(config_path, &backup)?;
+
+    for old in list_backups(config_path)?.iter().skip(keep) {
+        let path = directory(config_path).join(&old.name);
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!("Failed to remove old config backup {}: {}", path.display(), e);
+        }
+    }
+    Ok(Some(backup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backups_are_rotated() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("metis.toml");
+
+        // Nothing to back up before the first save
+        assert!(backup_config(&config_path, 2).unwrap().is_none());
+
+        for version in 1..=3 {
+            std::fs::write(&config_path, format!("version = {}", version)).unwrap();
+            backup_config(&config_path, 2).unwrap().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        std::fs::write(dir.path().join("other.toml.bak.1"), "").unwrap();
+
+        let backups = list_backups(&config_path).unwrap();
+        assert_eq!(backups.len(), 2);
+        let newest = backup_path(&config_path, &backups[0].name).unwrap();
+        assert_eq!(std::fs::read_to_string(newest).unwrap(), "version = 3");
+        let oldest = backup_path(&config_path, &backups[1].name).unwrap();
+        assert_eq!(std::fs::read_to_string(oldest).unwrap(), "version = 2");
+
+        assert!(backup_config(&config_path, 0).unwrap().is_none());
+        assert_eq!(list_backups(&config_path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_backup_path_rejects_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("metis.toml");
+        std::fs::write(&config_path, "").unwrap();
+
+        assert!(backup_path(&config_path, "metis.toml").is_none());
+        assert!(backup_path(&config_path, "metis.toml.bak.missing").is_none());
+        assert!(backup_path(&config_path, "metis.toml.bak./../metis.toml").is_none());
+    }
+}
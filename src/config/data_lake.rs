@@ -24,6 +24,7 @@
 //! reference can optionally pin to a specific version and provide an alias for
 //! use within the data lake context.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -31,7 +32,7 @@ use super::file_storage::{DataLakeFileFormat, DataLakeStorageMode};
 use super::MockStrategyType;
 
 /// Configuration for a Data Lake (data model + records)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DataLakeConfig {
     /// Unique name for this data lake
     pub name: String,
@@ -69,7 +70,7 @@ pub struct DataLakeConfig {
 }
 
 /// Reference to a schema within a data lake
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DataLakeSchemaRef {
     /// Name of the schema (references /api/schemas)
     pub schema_name: String,
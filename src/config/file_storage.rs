@@ -3,10 +3,11 @@
 //! Provides configuration types for storing data lake records in local filesystem
 //! or S3-compatible object storage, supporting both Parquet and JSONL formats.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Storage mode for data lake records
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DataLakeStorageMode {
     /// Store only in database (default, backward compatible)
@@ -31,7 +32,7 @@ impl DataLakeStorageMode {
 }
 
 /// File format for data lake storage
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DataLakeFileFormat {
     /// Apache Parquet - columnar format, best for analytics queries
@@ -60,7 +61,7 @@ impl DataLakeFileFormat {
 }
 
 /// Global file storage configuration for data lakes
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct FileStorageConfig {
     /// Whether file storage is enabled globally
     #[serde(default)]
@@ -141,7 +142,7 @@ impl FileStorageConfig {
 }
 
 /// S3-specific configuration for data file storage
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct S3DataConfig {
     /// S3 bucket for data storage
     pub bucket: String,
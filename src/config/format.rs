@@ -0,0 +1,174 @@
+//! Canonical formatting of item files
+//!
+//! `metis fmt` rewrites the JSON and YAML files under `config/<type>/` so that
+//! hand edits don't show up as ordering or whitespace noise in diffs. Object
+//! keys are sorted, JSON is indented by two spaces, and every file ends with a
+//! single newline. Values are kept as written; defaults are not filled in.
+
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+use super::Settings;
+
+/// Item files the settings were loaded from, in path order.
+/// The main config file is not included.
+pub fn item_files(settings: &Settings) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = settings
+        .sources
+        .values()
+        .flatten()
+        .filter(|path| settings.config_path.as_deref() != Some(path.as_path()))
+        .cloned()
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Canonical form of an item file, picking JSON or YAML by its extension
+pub fn canonicalize(path: &Path, content: &str) -> Result<String, anyhow::Error> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            let value: Value = serde_json::from_str(content)?;
+            Ok(format!("{}\n", serde_json::to_string_pretty(&sort_keys(value))?))
+        }
+        Some("yaml" | "yml") => {
+            let value: Value = serde_yaml::from_str(content)?;
+            let formatted = serde_yaml::to_string(&sort_keys(value))?;
+            Ok(format!("{}\n", formatted.trim_end()))
+        }
+        _ => Err(anyhow::anyhow!("Unsupported item file format: {}", path.display())),
+    }
+}
+
+/// What rewriting an item file in canonical form would drop, if anything.
+/// Comments, anchors and aliases in YAML don't survive being parsed and
+/// serialized again.
+pub fn rewrite_would_drop(path: &Path, content: &str) -> Option<&'static str> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => yaml_extras(content),
+        _ => None,
+    }
+}
+
+/// First comment, anchor or alias found in `content`, outside of quoted
+/// strings and block scalars
+fn yaml_extras(content: &str) -> Option<&'static str> {
+    // Indentation of the line that opened the block scalar being skipped
+    let mut block_indent: Option<usize> = None;
+
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        if let Some(parent) = block_indent {
+            if line.trim().is_empty() || indent > parent {
+                continue;
+            }
+            block_indent = None;
+        }
+
+        let mut quote: Option<char> = None;
+        let mut escaped = false;
+        let mut previous = ' ';
+        // Last non-blank character before this one, `\n` at the start of the line
+        let mut last_significant = '\n';
+        for (i, ch) in line.char_indices() {
+            match quote {
+                Some('"') if escaped => escaped = false,
+                Some('"') if ch == '\\' => escaped = true,
+                Some(q) if ch == q => quote = None,
+                Some(_) => {}
+                None => {
+                    // Quotes, anchors and aliases only count where a value starts
+                    let node_start = (previous.is_whitespace() || matches!(previous, '[' | '{' | ','))
+                        && matches!(last_significant, '\n' | ':' | '-' | '?' | '[' | '{' | ',');
+                    let next = line[i + ch.len_utf8()..].chars().next();
+                    match ch {
+                        '"' | '\'' if node_start => quote = Some(ch),
+                        '#' if previous.is_whitespace() => return Some("comments"),
+                        '&' | '*' if node_start && next.is_some_and(|c| c.is_alphanumeric() || c == '_') => {
+                            return Some("anchors or aliases");
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if !ch.is_whitespace() {
+                last_significant = ch;
+            }
+            previous = ch;
+        }
+
+        let opens_block = line.trim_end().rsplit(char::is_whitespace).next().is_some_and(|token| {
+            token.starts_with(['|', '>']) && token[1..].chars().all(|c| c.is_ascii_digit() || c == '-' || c == '+')
+        });
+        if opens_block && quote.is_none() {
+            block_indent = Some(indent);
+        }
+    }
+    None
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut entries: Vec<(String, Value)> = obj.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, sort_keys(v))).collect::<Map<_, _>>())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_json_sorts_keys_and_indents() {
+        let content = r#"{"name":"echo",  "description": "Echo", "input_schema": {"type": "object", "properties": {}}}"#;
+        let formatted = canonicalize(Path::new("echo.json"), content).unwrap();
+        assert_eq!(
+            formatted,
+            "{\n  \"description\": \"Echo\",\n  \"input_schema\": {\n    \"properties\": {},\n    \"type\": \"object\"\n  },\n  \"name\": \"echo\"\n}\n"
+        );
+        // Formatting is idempotent
+        assert_eq!(canonicalize(Path::new("echo.json"), &formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_canonicalize_yaml_sorts_keys() {
+        let content = "name: greet\ndescription:   Greeting\narguments:\n  - required: true\n    name: who\n\n\n";
+        let formatted = canonicalize(Path::new("greet.yaml"), content).unwrap();
+        assert_eq!(
+            formatted,
+            "arguments:\n- name: who\n  required: true\ndescription: Greeting\nname: greet\n"
+        );
+        assert_eq!(canonicalize(Path::new("greet.yml"), &formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_rewrite_would_drop_yaml_comments_and_anchors() {
+        let yaml = Path::new("greet.yaml");
+        assert_eq!(rewrite_would_drop(yaml, "# Greeting prompt\nname: greet\n"), Some("comments"));
+        assert_eq!(rewrite_would_drop(yaml, "name: greet  # the name\n"), Some("comments"));
+        assert_eq!(
+            rewrite_would_drop(yaml, "base: &base\n  type: object\ninput_schema: *base\n"),
+            Some("anchors or aliases")
+        );
+
+        // `#`, `&` and `*` inside strings and block scalars are content
+        let plain = "name: 'a # b'\ndescription: \"C# & *stars\"\nurl: http://x/#top\ntemplate: |\n  # heading\n  &nbsp; *bold*\nafter: 1\n";
+        assert_eq!(rewrite_would_drop(yaml, plain), None);
+        assert_eq!(rewrite_would_drop(yaml, &canonicalize(yaml, plain).unwrap()), None);
+
+        // JSON has no comments to lose
+        assert_eq!(rewrite_would_drop(Path::new("echo.json"), "{\"name\": \"# x\"}"), None);
+    }
+
+    #[test]
+    fn test_canonicalize_rejects_unknown_extension() {
+        assert!(canonicalize(Path::new("tool.toml"), "name = 'x'").is_err());
+        assert!(canonicalize(Path::new("tool.json"), "{not json").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Config schema migrations
+//!
+//! Older `metis.toml` files are upgraded to the current layout before they are
+//! deserialized into [`Settings`](super::Settings). Each migration step is a
+//! plain JSON transformation that handles exactly one breaking change, and the
+//! steps are chained in order from the file's `config_version` up to
+//! [`CURRENT_VERSION`].
+//!
+//! Version 1 is the baseline layout and no breaking change has been made
+//! since, so [`MIGRATIONS`] is empty. To add a migration, bump
+//! [`CURRENT_VERSION`], write a `migrate_vN_to_vM` function and append it to
+//! [`MIGRATIONS`].
+
+use serde_json::Value;
+
+/// The config schema version produced by this build of Metis
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Version assumed for config files that predate the `config_version` field
+pub const LEGACY_VERSION: u32 = 1;
+
+/// A single migration step, upgrading a raw config from version N to N + 1
+type Migration = fn(Value) -> Value;
+
+/// Migration chain, indexed by source version (index 0 upgrades v1 to v2)
+const MIGRATIONS: &[Migration] = &[];
+
+/// Read the `config_version` of a raw config, falling back to [`LEGACY_VERSION`]
+pub fn detect_version(raw: &Value) -> u32 {
+    raw.get("config_version")
+        .and_then(|v| v.as_u64())
+        .map(|v| u32::try_from(v).unwrap_or(u32::MAX))
+        .unwrap_or(LEGACY_VERSION)
+}
+
+/// Apply every migration step from `from_version` up to [`CURRENT_VERSION`].
+/// The returned value always has `config_version` set to [`CURRENT_VERSION`].
+pub fn migrate(raw: Value, from_version: u32) -> Value {
+    run_chain(raw, from_version, MIGRATIONS)
+}
+
+/// Apply the steps of `migrations` from `from_version` on, then stamp the
+/// version the chain ends at
+fn run_chain(raw: Value, from_version: u32, migrations: &[Migration]) -> Value {
+    let target = LEGACY_VERSION + migrations.len() as u32;
+    let start = from_version.max(LEGACY_VERSION);
+    let mut value = raw;
+
+    for version in start..target {
+        let step = migrations[(version - LEGACY_VERSION) as usize];
+        tracing::info!(
+            "Migrating config schema from v{} to v{}",
+            version,
+            version + 1
+        );
+        value = step(value);
+    }
+
+    if let Value::Object(obj) = &mut value {
+        obj.insert("config_version".to_string(), Value::from(target));
+    }
+
+    value
+}
+
+/// Migrate a raw config only if it is older than [`CURRENT_VERSION`]. A
+/// config written for a newer schema is refused rather than misread.
+pub fn migrate_if_needed(raw: Value) -> anyhow::Result<Value> {
+    let from_version = detect_version(&raw);
+    if from_version > CURRENT_VERSION {
+        anyhow::bail!(
+            "config is from a newer metis: config_version {} is above {}, the newest this build reads",
+            from_version,
+            CURRENT_VERSION
+        );
+    }
+    if from_version < CURRENT_VERSION {
+        Ok(migrate(raw, from_version))
+    } else {
+        Ok(raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Step of a made-up chain: renames `a` to `b`
+    fn rename_a_to_b(mut raw: Value) -> Value {
+        if let Some(a) = raw.as_object_mut().and_then(|obj| obj.remove("a")) {
+            raw["b"] = a;
+        }
+        raw
+    }
+
+    /// Step of a made-up chain: nests `b` under `c`
+    fn nest_b_under_c(mut raw: Value) -> Value {
+        if let Some(b) = raw.as_object_mut().and_then(|obj| obj.remove("b")) {
+            raw["c"] = json!({"b": b});
+        }
+        raw
+    }
+
+    #[test]
+    fn test_current_version_matches_migration_chain() {
+        assert_eq!(CURRENT_VERSION, LEGACY_VERSION + MIGRATIONS.len() as u32);
+    }
+
+    #[test]
+    fn test_detect_version_defaults_to_legacy() {
+        assert_eq!(detect_version(&json!({})), LEGACY_VERSION);
+        assert_eq!(detect_version(&json!({"config_version": 2})), 2);
+    }
+
+    #[test]
+    fn test_chain_applies_steps_from_version() {
+        let chain: &[Migration] = &[rename_a_to_b, nest_b_under_c];
+
+        let migrated = run_chain(json!({"a": 1}), 1, chain);
+        assert_eq!(migrated, json!({"c": {"b": 1}, "config_version": 3}));
+
+        // Starting at v2 skips the first step
+        let migrated = run_chain(json!({"a": 1, "b": 2, "config_version": 2}), 2, chain);
+        assert_eq!(migrated, json!({"a": 1, "c": {"b": 2}, "config_version": 3}));
+    }
+
+    #[test]
+    fn test_current_version_is_untouched() {
+        let raw = json!({"config_version": CURRENT_VERSION, "server": {"port": 3000}});
+        assert_eq!(migrate_if_needed(raw.clone()).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_newer_version_is_refused() {
+        let err = migrate_if_needed(json!({"config_version": CURRENT_VERSION + 1})).unwrap_err();
+        assert!(err.to_string().starts_with("config is from a newer metis"), "{}", err);
+        assert!(migrate_if_needed(json!({"config_version": u64::from(u32::MAX) + 2})).is_err());
+    }
+
+    #[test]
+    fn test_legacy_config_round_trip() {
+        let raw = json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "rate_limit": {"enabled": true, "requests_per_second": 10, "burst_size": 20}
+        });
+
+        let migrated = migrate(raw, detect_version(&json!({})));
+        let settings: super::super::Settings = serde_json::from_value(migrated).unwrap();
+        assert_eq!(settings.config_version, CURRENT_VERSION);
+        assert_eq!(settings.rate_limit.as_ref().unwrap().requests_per_second, 10);
+
+        // Serializing and re-loading the migrated settings is a no-op
+        let serialized = serde_json::to_value(&settings).unwrap();
+        assert_eq!(migrate_if_needed(serialized.clone()).unwrap(), serialized);
+    }
+}
@@ -1,11 +1,17 @@
 use config::{Config, File};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use thiserror::Error;
 
+pub mod backup;
 pub mod data_lake;
 pub mod file_storage;
+pub mod format;
+pub mod migrations;
+pub mod redact;
 pub mod s3;
 pub mod s3_watcher;
 pub mod schema;
@@ -18,9 +24,15 @@ pub use s3::S3Config;
 pub use s3_watcher::S3Watcher;
 pub use schema::SchemaConfig;
 
-use crate::agents::config::{AgentConfig, OrchestrationConfig};
+use crate::agents::config::{AgentConfig, AgentDefaults, LlmProviderConfig, OrchestrationConfig};
 use crate::cli::Cli;
 use crate::persistence::PersistenceConfig;
+use crate::persistence::models::ArchetypeType;
+
+/// Files that config items were read from, keyed by archetype and item key
+/// (the name, or the URI of resources). An item read more than once has one
+/// path per read, in load order.
+pub type ItemSources = HashMap<(ArchetypeType, String), Vec<PathBuf>>;
 
 /// Error returned when optimistic locking detects a version conflict
 #[derive(Debug, Error)]
@@ -30,32 +42,72 @@ pub struct VersionConflictError {
     pub actual: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct Settings {
     /// Path to the configuration file (not serialized, set at runtime)
     #[serde(skip)]
     pub config_path: Option<PathBuf>,
+    /// Active config profile (not serialized, set at runtime from `--profile`)
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// Item files loaded from the config directories (not serialized)
+    #[serde(skip)]
+    pub sources: ItemSources,
     /// Version number for optimistic locking (incremented on each save)
     /// Used to detect concurrent modifications and prevent lost updates
     #[serde(default)]
     pub version: u64,
+    /// Schema version of the config file format, used to migrate older files on load
+    #[serde(default = "default_config_version")]
+    pub config_version: u32,
+    /// Other config files (relative to the config root) merged over this one at load time.
+    /// Later files override earlier ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
     pub server: ServerSettings,
     #[serde(default)]
     pub auth: crate::domain::auth::AuthConfig,
+    /// Subsystems that can be turned off for minimal deployments
+    #[serde(default)]
+    pub features: FeaturesConfig,
     #[serde(default)]
     pub resources: Vec<ResourceConfig>,
     #[serde(default)]
     pub resource_templates: Vec<ResourceTemplateConfig>,
     #[serde(default)]
     pub tools: Vec<ToolConfig>,
+    /// What tools without a `mock` or `static_response` return when called
+    #[serde(default)]
+    pub default_tool_response: DefaultToolResponse,
+    /// Validate items written through the API before applying them
+    #[serde(default = "default_enabled")]
+    pub validate_on_write: bool,
+    /// Short names for tools, mapped to the tool they call: a local tool or
+    /// workflow, or `server/tool` for a tool of an external MCP server
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_aliases: HashMap<String, String>,
+    /// Faker types of the `random` strategy defined by a pattern, e.g. for
+    /// internal SKU formats; they take precedence over built-in types
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub custom_fakers: HashMap<String, CustomFaker>,
     #[serde(default)]
     pub prompts: Vec<PromptConfig>,
     #[serde(default)]
     pub workflows: Vec<WorkflowConfig>,
     #[serde(default)]
     pub agents: Vec<AgentConfig>,
+    /// Settings applied to every agent, e.g. a system prompt preamble
+    #[serde(default, skip_serializing_if = "AgentDefaults::is_empty")]
+    pub agent_defaults: AgentDefaults,
     #[serde(default)]
     pub orchestrations: Vec<OrchestrationConfig>,
+    /// LLM used for AI-assisted config generation (e.g. suggesting mocks).
+    /// Falls back to the first agent's LLM when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation_provider: Option<LlmProviderConfig>,
+    /// Per-token prices of LLM models, used to estimate workflow costs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pricing: Vec<ModelPricing>,
     /// Reusable JSON schema definitions that can be referenced via $ref
     #[serde(default)]
     pub schemas: Vec<SchemaConfig>,
@@ -64,11 +116,17 @@ pub struct Settings {
     pub data_lakes: Vec<DataLakeConfig>,
     #[serde(default)]
     pub rate_limit: Option<RateLimitConfig>,
+    /// Client IP allow/deny lists
+    #[serde(default)]
+    pub access_control: Option<AccessControlConfig>,
     #[serde(default)]
     pub s3: Option<S3Config>,
     /// External MCP servers that can be connected to for tools
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
+    /// How the server presents itself to MCP clients
+    #[serde(default)]
+    pub mcp: McpConfig,
     /// Encrypted secrets that can be stored in the config file
     /// Values can be plain text or AGE-encrypted (prefixed with "age:")
     /// Encrypted values require METIS_SECRET_PASSPHRASE env var or --secret-passphrase flag
@@ -79,14 +137,22 @@ pub struct Settings {
     /// Config files are used for initial seeding only
     #[serde(default)]
     pub database: Option<PersistenceConfig>,
+    /// Databases the `database` mock strategy can connect to by name, so tools
+    /// querying the same database share its pool and credentials live in one place
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub databases: HashMap<String, NamedDatabaseConfig>,
     /// File storage configuration for data lake records (optional)
     /// Enables storing records in Parquet/JSONL files on local filesystem or S3
     #[serde(default)]
     pub file_storage: Option<FileStorageConfig>,
 }
 
+fn default_config_version() -> u32 {
+    migrations::CURRENT_VERSION
+}
+
 /// Configuration for embedded secrets (can be encrypted with AGE)
-#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct SecretsConfig {
     /// OpenAI API key (plain or AGE-encrypted)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -133,8 +199,91 @@ impl SecretsConfig {
     }
 }
 
+/// Subsystems the server runs, all on by default. A subsystem turned off
+/// answers its API routes with 404 and its tools and resources are hidden
+/// from MCP clients.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct FeaturesConfig {
+    /// The `/api` admin API and the web UI that uses it. Only read at startup.
+    #[serde(default = "default_enabled")]
+    pub admin_api: bool,
+    /// Agents and orchestrations, and agents exposed as tools
+    #[serde(default = "default_enabled")]
+    pub agents: bool,
+    /// Data lakes and their records, files and resources
+    #[serde(default = "default_enabled")]
+    pub data_lake: bool,
+    /// Connections to `mcp_servers` and their tools
+    #[serde(default = "default_enabled")]
+    pub mcp_aggregation: bool,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> Self {
+        Self {
+            admin_api: true,
+            agents: true,
+            data_lake: true,
+            mcp_aggregation: true,
+        }
+    }
+}
+
+/// Server details sent in the MCP `initialize` response
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct McpConfig {
+    /// Server name reported to clients
+    #[serde(default = "default_mcp_server_name")]
+    pub name: String,
+    /// Server version reported to clients (the Metis version if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Instructions telling clients how to use the server; none are sent if empty
+    #[serde(default = "default_mcp_instructions")]
+    pub instructions: String,
+    /// Capabilities to advertise. If unset, those with configured items are advertised.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<McpCapability>>,
+    /// Most tools, resources or prompts returned per list page; 0 returns whole lists
+    #[serde(default = "default_mcp_page_size")]
+    pub page_size: usize,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            name: default_mcp_server_name(),
+            version: None,
+            instructions: default_mcp_instructions(),
+            capabilities: None,
+            page_size: default_mcp_page_size(),
+        }
+    }
+}
+
+fn default_mcp_server_name() -> String {
+    "metis-mock-server".to_string()
+}
+
+fn default_mcp_instructions() -> String {
+    "Metis MCP Mock Server - A configurable mock server for MCP protocol testing".to_string()
+}
+
+fn default_mcp_page_size() -> usize {
+    100
+}
+
+/// Server capability advertised to MCP clients
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum McpCapability {
+    Tools,
+    Resources,
+    Prompts,
+}
+
 /// Configuration for connecting to an external MCP server
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct McpServerConfig {
     /// Unique name for this MCP server connection
     pub name: String,
@@ -152,6 +301,37 @@ pub struct McpServerConfig {
     /// Connection timeout in seconds
     #[serde(default = "default_mcp_timeout")]
     pub timeout_seconds: u64,
+    /// How to retry when the server can't be reached
+    #[serde(default)]
+    pub reconnect: McpReconnectConfig,
+    /// Glob patterns of the server's tool names to expose (all when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Glob patterns of the server's tool names to hide, even if included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Names to expose tools under instead of `mcp__<server>_<tool>`, keyed by the server's tool name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rename: HashMap<String, String>,
+}
+
+impl McpServerConfig {
+    /// Whether the server's tool `tool` passes the `include` and `exclude` globs
+    pub fn exposes_tool(&self, tool: &str) -> bool {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|p| glob::Pattern::new(p).is_ok_and(|p| p.matches(tool)))
+        };
+        (self.include.is_empty() || matches(&self.include)) && !matches(&self.exclude)
+    }
+
+    /// Name the server's tool `tool` is listed and called under
+    pub fn exposed_tool_name(&self, tool: &str) -> String {
+        self.rename.get(tool).cloned().unwrap_or_else(|| {
+            format!("{}{}_{}", crate::adapters::mcp_client::MCP_TOOL_PREFIX, self.name, tool)
+        })
+    }
 }
 
 fn default_enabled() -> bool {
@@ -162,20 +342,186 @@ fn default_mcp_timeout() -> u64 {
     30
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// Exponential backoff for reconnecting to an external MCP server
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct McpReconnectConfig {
+    /// Delay before the first retry, in milliseconds
+    #[serde(default = "default_reconnect_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+    /// Upper bound for the doubling delay, in milliseconds
+    #[serde(default = "default_reconnect_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Retries before the server is marked down (0 retries forever)
+    #[serde(default = "default_reconnect_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for McpReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: default_reconnect_initial_delay_ms(),
+            max_delay_ms: default_reconnect_max_delay_ms(),
+            max_attempts: default_reconnect_max_attempts(),
+        }
+    }
+}
+
+impl McpReconnectConfig {
+    /// Delay before retry number `attempt` (starting at 0)
+    pub fn delay(&self, attempt: u32) -> std::time::Duration {
+        let delay = self.initial_delay_ms.saturating_mul(1u64 << attempt.min(32));
+        std::time::Duration::from_millis(delay.min(self.max_delay_ms.max(self.initial_delay_ms)))
+    }
+}
+
+fn default_reconnect_initial_delay_ms() -> u64 {
+    1000
+}
+
+fn default_reconnect_max_delay_ms() -> u64 {
+    60_000
+}
+
+fn default_reconnect_max_attempts() -> u32 {
+    10
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct RateLimitConfig {
     pub enabled: bool,
     pub requests_per_second: u32,
     pub burst_size: u32,
+    /// Tokens that skip rate limiting when sent in the `X-Rate-Limit-Bypass` header
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bypass_tokens: Vec<String>,
+    /// How requests are counted against the limit
+    #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+}
+
+/// Algorithm deciding when `rate_limit` rejects a request
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    /// Bursts of up to `burst_size` requests, refilled at `requests_per_second`
+    #[default]
+    TokenBucket,
+    /// At most `burst_size` requests in any window of `burst_size /
+    /// requests_per_second` seconds, freed up as requests leave the window
+    SlidingWindow,
+    /// Requests evenly spaced at `requests_per_second`, with no bursts
+    /// (`burst_size` must be 1)
+    Gcra,
+}
+
+/// Network-level access restriction by client IP
+#[derive(Debug, Deserialize, Serialize, Clone, Default, JsonSchema)]
+pub struct AccessControlConfig {
+    /// IPs or CIDRs allowed to connect; any client if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// IPs or CIDRs rejected even if allowed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// Proxies whose `X-Forwarded-For` header is trusted to name the client
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_proxies: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ServerSettings {
     pub host: String,
     pub port: u16,
+    /// Also expose tools, resources and prompts as plain REST endpoints
+    #[serde(default)]
+    pub http_mock_mode: bool,
+    /// Number of config snapshots kept for `/api/config/undo`; 0 disables undo
+    #[serde(default = "default_config_history_depth")]
+    pub config_history_depth: usize,
+    /// Format of log lines written to stdout (and `log_file`)
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// Most verbose level logged: trace, debug, info, warn, error or off
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// File that logs are also appended to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<String>,
+    /// Wrap tool results in an MCP `{ content: [...] }` envelope, for tools that
+    /// don't set `normalize_response` themselves
+    #[serde(default)]
+    pub normalize_all_responses: bool,
+    /// Largest serialized mock response, in bytes. A strategy result over the
+    /// limit is turned into an error instead of being sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_response_bytes: Option<usize>,
+    /// Most tool calls executing at once across the server. Calls over the
+    /// limit wait for a slot; unlimited when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_tool_calls: Option<usize>,
+    /// How long a call waits for a slot under `max_concurrent_tool_calls`
+    /// before failing, in milliseconds (default 30000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_queue_timeout_ms: Option<u64>,
+    /// Most tool calls nested in one another, counting workflows and agents
+    /// that call tools. A call deeper than this fails, which bounds agents or
+    /// workflows that end up calling themselves.
+    #[serde(default = "default_max_call_depth")]
+    pub max_call_depth: usize,
+    /// Path prefix all routes are served under, e.g. `/metis` behind a
+    /// reverse proxy; served at the root when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+    /// Also serve the health checks at the root with `base_path` set, for
+    /// probes that can't be pointed at the prefix
+    #[serde(default)]
+    pub health_at_root: bool,
+    /// How long the response to a POST with an `Idempotency-Key` header is
+    /// replayed to retries with the same key, in seconds
+    #[serde(default = "default_idempotency_ttl_seconds")]
+    pub idempotency_ttl_seconds: u64,
+    /// Copies of the config file kept as `<file>.bak.<timestamp>` when a save
+    /// overwrites it; 0 disables backups
+    #[serde(default = "default_config_backups")]
+    pub config_backups: usize,
+    /// Send integers in tool results that JavaScript clients would round,
+    /// those beyond 2^53 - 1, as strings
+    #[serde(default)]
+    pub big_integers_as_strings: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+fn default_config_history_depth() -> usize {
+    50
+}
+
+fn default_idempotency_ttl_seconds() -> u64 {
+    86_400
+}
+
+fn default_config_backups() -> usize {
+    5
+}
+
+fn default_max_call_depth() -> usize {
+    10
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+/// Log line format
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct ResourceConfig {
     pub uri: String,
     pub name: String,
@@ -194,7 +540,7 @@ pub struct ResourceConfig {
 /// Configuration for a resource template with URI pattern variables
 /// Resource templates use URI patterns with {placeholder} syntax
 /// e.g., "postgres://db/users/{id}" or "file:///home/{username}/{filename}"
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct ResourceTemplateConfig {
     /// URI template pattern with {variable} placeholders
     pub uri_template: String,
@@ -214,7 +560,7 @@ pub struct ResourceTemplateConfig {
     pub mock: Option<MockConfig>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct ToolConfig {
     pub name: String,
     pub description: String,
@@ -227,9 +573,68 @@ pub struct ToolConfig {
     pub output_schema: Option<Value>,
     pub static_response: Option<Value>, // Simple static response for now
     pub mock: Option<MockConfig>,
+    /// Identical calls (same arguments) within this many milliseconds return
+    /// the first call's result. Ignored for the Stateful strategy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window_ms: Option<u64>,
+    /// Wrap results that aren't an MCP `{ content: [...] }` envelope in one.
+    /// Defaults to `server.normalize_all_responses`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_response: Option<bool>,
+    /// Delay added before the tool responds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyConfig>,
+}
+
+/// How long a tool waits before responding, to behave like a slow upstream
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum LatencyConfig {
+    /// Always wait `ms` milliseconds
+    Fixed { ms: u64 },
+    /// Wait a uniformly random time from `min_ms` to `max_ms`
+    Uniform { min_ms: u64, max_ms: u64 },
+    /// Sample the wait from a latency profile captured from real traffic
+    Profile {
+        /// JSON or YAML file with percentiles or a histogram of latencies
+        path: String,
+        /// Profile to use from a file holding one per tool; defaults to the tool's name
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+}
+
+/// Response for tools that have neither a `mock` nor a `static_response`
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum DefaultToolResponse {
+    /// Fail the call with "no response configured for tool X"
+    #[default]
+    Error,
+    /// Return `null`
+    Null,
+    /// Return a fixed value
+    Value { value: Value },
+}
+
+impl DefaultToolResponse {
+    /// Resolve the response for `tool_name`, which has nothing configured
+    pub fn resolve(&self, tool_name: &str) -> Result<Value, anyhow::Error> {
+        match self {
+            Self::Error => Err(anyhow::anyhow!("no response configured for tool {}", tool_name)),
+            Self::Null => {
+                tracing::warn!("Tool '{}' has no mock or static_response, returning null", tool_name);
+                Ok(Value::Null)
+            }
+            Self::Value { value } => {
+                tracing::warn!("Tool '{}' has no mock or static_response, returning default_tool_response", tool_name);
+                Ok(value.clone())
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum MockStrategyType {
     Static,
@@ -245,9 +650,24 @@ pub enum MockStrategyType {
     Database,
     #[serde(rename = "data_lake_crud")]
     DataLakeCrud,
+    Callback,
+    Matrix,
+    Csv,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// A faker type defined in `custom_fakers`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum CustomFaker {
+    /// Regex-like pattern with the syntax of the `pattern` strategy, e.g.
+    /// `SKU-[A-Z]{3}-\d{4}`
+    Regex(String),
+    /// Template where `#` is a digit, `?` a lowercase letter and `*` either,
+    /// e.g. `INV-####-??`
+    Template { template: String },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct MockConfig {
     pub strategy: MockStrategyType,
     pub template: Option<String>,
@@ -265,10 +685,53 @@ pub struct MockConfig {
     /// Data Lake CRUD configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_lake_crud: Option<DataLakeCrudConfig>,
+    /// Callback configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback: Option<CallbackConfig>,
+    /// Canned responses for the Matrix strategy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixConfig>,
+    /// Spreadsheet rows for the Csv strategy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv: Option<CsvConfig>,
+    /// Fail a share of calls with an MCP error code instead of generating a response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_mock: Option<ErrorMockConfig>,
+    /// Steps post-processing the generated response, applied in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transform: Vec<ResponseTransform>,
+}
+
+/// A step reshaping a mock's output, whatever strategy generated it
+///
+/// Paths are dot-separated keys with optional array indexes, e.g. `user.email`,
+/// `items[0].id` or `items[*].id` for every element. An empty path is the
+/// whole output.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseTransform {
+    /// Nest the output under `key` in a new object
+    Wrap { key: String },
+    /// Replace the values at `path` with `replacement`, if they exist
+    Redact {
+        path: String,
+        #[serde(default = "default_redaction")]
+        replacement: Value,
+    },
+    /// Set the values at `path`, creating missing objects on the way. String
+    /// values are Tera templates seeing the output as `output`, e.g.
+    /// `"{{ now() }}"` for a timestamp.
+    Set { path: String, value: Value },
+    /// Replace the output with the result of a Rhai expression over `output`
+    Expr { expression: String },
+}
+
+fn default_redaction() -> Value {
+    Value::String("[REDACTED]".to_string())
 }
 
 /// Schema-driven faker configuration for generating structured fake data
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema)]
 pub struct FakerSchemaConfig {
     /// Field configurations keyed by property path (e.g., "user.name", "items[*].id")
     #[serde(default)]
@@ -276,10 +739,15 @@ pub struct FakerSchemaConfig {
     /// Array configurations keyed by array path
     #[serde(default)]
     pub arrays: std::collections::HashMap<String, FakerArrayConfig>,
+    /// JSON Schema describing the generated value. Its nested objects and
+    /// arrays (with `minItems`/`maxItems`) are generated recursively, and
+    /// `fields` and `arrays` override it by path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
 }
 
 /// Configuration for a single faker field
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct FakerFieldConfig {
     /// The faker type to use (e.g., "name", "email", "integer")
     pub faker_type: FakerFieldType,
@@ -296,7 +764,7 @@ pub struct FakerFieldConfig {
 }
 
 /// Array generation configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct FakerArrayConfig {
     /// Minimum number of items to generate
     pub min_items: usize,
@@ -305,7 +773,7 @@ pub struct FakerArrayConfig {
 }
 
 /// Faker field types for schema-driven generation
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum FakerFieldType {
     // Personal
@@ -339,7 +807,44 @@ pub enum FakerFieldType {
     Lorem,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+impl FakerFieldType {
+    /// Faker types suited to a JSON Schema type and format, the default first
+    pub fn for_schema_type(schema_type: &str, format: Option<&str>) -> Vec<FakerFieldType> {
+        match format {
+            Some("email") => return vec![FakerFieldType::Email],
+            Some("uuid") => return vec![FakerFieldType::Uuid],
+            _ => {}
+        }
+
+        match schema_type {
+            "string" => vec![
+                FakerFieldType::Lorem,
+                FakerFieldType::Sentence,
+                FakerFieldType::Word,
+                FakerFieldType::Paragraph,
+                FakerFieldType::FullName,
+                FakerFieldType::FirstName,
+                FakerFieldType::LastName,
+                FakerFieldType::Email,
+                FakerFieldType::Username,
+                FakerFieldType::Phone,
+                FakerFieldType::Uuid,
+                FakerFieldType::City,
+                FakerFieldType::Country,
+                FakerFieldType::StreetAddress,
+                FakerFieldType::PostalCode,
+                FakerFieldType::Constant,
+                FakerFieldType::Pattern,
+                FakerFieldType::Enum,
+            ],
+            "integer" => vec![FakerFieldType::Integer, FakerFieldType::Constant],
+            "number" => vec![FakerFieldType::Float, FakerFieldType::Integer, FakerFieldType::Constant],
+            _ => vec![FakerFieldType::Constant],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ScriptLang {
     Rhai,
@@ -349,7 +854,7 @@ pub enum ScriptLang {
 }
 
 /// Database type for mock strategy
-#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DatabaseType {
     #[default]
@@ -361,7 +866,7 @@ pub enum DatabaseType {
 
 /// DataFusion-specific configuration for querying datalakes
 /// Storage settings are inherited from the data lake configuration
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct DataFusionConfig {
     /// Data lake name to query (storage settings are inherited from data lake)
     pub data_lake: String,
@@ -370,7 +875,7 @@ pub struct DataFusionConfig {
 }
 
 /// CRUD operation type for DataLakeCrud mock strategy
-#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DataLakeCrudOperation {
     /// Create a new record - input: schema fields, output: created record
@@ -397,7 +902,7 @@ fn default_read_limit() -> usize {
 }
 
 /// Configuration for DataLakeCrud mock strategy
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct DataLakeCrudConfig {
     /// Name of the data lake to operate on (required)
     pub data_lake: String,
@@ -420,10 +925,150 @@ pub struct DataLakeCrudConfig {
     pub read_limit: usize,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Configuration for the Callback mock strategy: acknowledge the call right
+/// away and POST a payload to an external URL after a delay
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CallbackConfig {
+    /// Tera template for the URL the payload is posted to
+    pub url: String,
+    /// Tera template for the JSON payload; the tool arguments are sent as-is if omitted
+    /// Available variables: all input fields and `callback_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_template: Option<String>,
+    /// Delay before the callback is sent, in milliseconds
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Extra HTTP headers sent with the callback
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Tera template for the immediate response; defaults to
+    /// `{"status": "accepted", "callback_id": "..."}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ack_template: Option<String>,
+}
+
+/// Simulated MCP protocol error, e.g. `-32602` (invalid params) or an
+/// application-defined code
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ErrorMockConfig {
+    /// JSON-RPC error code returned to the client
+    #[serde(default)]
+    pub code: i32,
+    /// Tera template for the error message; the tool arguments are available
+    #[serde(default)]
+    pub message_template: String,
+    /// Share of calls that fail, from 0.0 (never) to 1.0 (always)
+    #[serde(default)]
+    pub rate: f64,
+    /// Errors to rotate through, one per failed call, instead of `code` and
+    /// `message_template`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codes: Vec<ErrorMockConfig>,
+}
+
+/// Configuration for the Matrix mock strategy: a fixed list of responses
+/// returned in turn, by matching the call arguments, or at random
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MatrixConfig {
+    pub pairs: Vec<MatrixPair>,
+    #[serde(default)]
+    pub mode: MatrixMode,
+}
+
+/// A response of the Matrix strategy
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MatrixPair {
+    /// Arguments this pair answers in `input_match` mode: the call's arguments
+    /// must contain it. A pair without one matches any call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_match: Option<Value>,
+    pub output: Value,
+}
+
+/// How the Matrix strategy picks a pair
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixMode {
+    /// Each call returns the next pair, wrapping around after the last
+    #[default]
+    RoundRobin,
+    /// The first pair whose `input_match` is contained in the call's arguments
+    InputMatch,
+    /// A random pair
+    Random,
+}
+
+/// Configuration for the Csv mock strategy: one row of a CSV file, returned
+/// as an object keyed by the column headers
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CsvConfig {
+    /// CSV file whose first row holds the column headers
+    pub path: String,
+    #[serde(default)]
+    pub selection: CsvSelection,
+    /// Row returned with `selection = "index"`, counting from 0 after the header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// Column looked up with `selection = "key"`: the first row whose cell
+    /// equals the call argument of the same name is returned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_column: Option<String>,
+    /// Largest file that is read, in bytes (default 100 MiB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+/// How the Csv strategy picks a row
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSelection {
+    /// A random row
+    #[default]
+    Random,
+    /// Each call returns the next row, wrapping around after the last
+    Sequential,
+    /// The row at `index`
+    Index,
+    /// The row whose `key_column` matches the call's argument
+    Key,
+}
+
+/// Shape of the Database strategy's response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseResultMode {
+    /// An array with an object per row
+    #[default]
+    Rows,
+    /// The first row's object, or null when there are no rows
+    FirstRow,
+    /// The single column of the first row, or null when there are no rows
+    Scalar,
+}
+
+fn default_db_max_connections() -> u32 {
+    5
+}
+
+/// An entry of `[databases]`
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct NamedDatabaseConfig {
+    /// Database URL (for sqlite, postgres, mysql)
+    pub url: String,
+    /// Maximum connections in the pool shared by every tool using this database
+    #[serde(default = "default_db_max_connections")]
+    pub max_connections: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct DatabaseConfig {
     /// Database URL (for sqlite, postgres, mysql) or empty for datafusion
+    /// or when `connection` is set
+    #[serde(default)]
     pub url: String,
+    /// Name of a `[databases]` entry to query instead of `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
     /// SQL query to execute
     pub query: String,
     /// Parameter names to bind from input arguments
@@ -432,12 +1077,118 @@ pub struct DatabaseConfig {
     /// Database type (determines how to connect)
     #[serde(default)]
     pub db_type: DatabaseType,
+    /// Maximum connections in the pool shared by all queries to `url`
+    #[serde(default = "default_db_max_connections")]
+    pub max_connections: u32,
+    /// Whether to return all rows, the first row, or a single value
+    #[serde(default)]
+    pub result_mode: DatabaseResultMode,
+    /// Names to return columns under, keyed by column name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub column_aliases: HashMap<String, String>,
+    /// CSV or JSON file (an array of objects) to fill a SQLite table from on
+    /// first use. The table is named after the file and is left alone if it exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed_file: Option<String>,
     /// DataFusion-specific configuration (when db_type is DataFusion)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub datafusion: Option<DataFusionConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Opening tag of a Postgres dollar-quoted string at the start of `chars`:
+/// `$$`, or `$tag$` with an identifier as the tag
+fn dollar_quote_tag(chars: &[char]) -> Option<Vec<char>> {
+    let len = chars[1..].iter().position(|&c| c == '$')?;
+    let tag = &chars[1..=len];
+    let is_identifier = tag.first().is_none_or(|c| c.is_alphabetic() || *c == '_')
+        && tag.iter().all(|c| c.is_alphanumeric() || *c == '_');
+    is_identifier.then(|| chars[..len + 2].to_vec())
+}
+
+impl DatabaseConfig {
+    /// Number of bind placeholders in `query`.
+    ///
+    /// Counts positional `?` placeholders, or the highest index of numbered
+    /// `$N`/`?N` ones. Placeholders inside string literals, quoted identifiers,
+    /// dollar-quoted strings and comments are ignored. Postgres only binds `$N`,
+    /// so a `?` in a Postgres query is the jsonb operator; a query run through
+    /// a `connection` is only known to be Postgres with `db_type = "postgres"`.
+    pub fn placeholder_count(&self) -> usize {
+        let postgres = self.db_type == DatabaseType::Postgres || self.url.starts_with("postgres");
+        let chars: Vec<char> = self.query.chars().collect();
+        let mut positional = 0;
+        let mut highest_numbered = 0;
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                quote @ ('\'' | '"' | '`') => {
+                    // A doubled quote inside a literal closes and reopens it, which skips the same way
+                    i += 1;
+                    while i < chars.len() && chars[i] != quote {
+                        i += 1;
+                    }
+                }
+                '-' if chars.get(i + 1) == Some(&'-') => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+                '/' if chars.get(i + 1) == Some(&'*') => {
+                    i += 2;
+                    while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                '$' if dollar_quote_tag(&chars[i..]).is_some() => {
+                    // `$$...$$` or `$tag$...$tag$`, skipped up to the closing tag
+                    let tag = dollar_quote_tag(&chars[i..]).unwrap_or_default();
+                    i += tag.len();
+                    while i < chars.len() && !chars[i..].starts_with(&tag) {
+                        i += 1;
+                    }
+                    i += tag.len() - 1;
+                }
+                '?' if postgres => {}
+                marker @ ('?' | '$') => {
+                    let digits: String = chars[i + 1..].iter().take_while(|c| c.is_ascii_digit()).collect();
+                    match digits.parse::<usize>() {
+                        Ok(index) => highest_numbered = highest_numbered.max(index),
+                        Err(_) if marker == '?' => positional += 1,
+                        // `$name` is not a bind placeholder (e.g. `$table` in DataFusion queries)
+                        Err(_) => {}
+                    }
+                    i += digits.len();
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        positional.max(highest_numbered)
+    }
+
+    /// Check that `params` supplies exactly one value per placeholder in `query`.
+    /// DataFusion queries don't bind parameters and are not checked.
+    pub fn check_params(&self) -> Result<(), String> {
+        if self.db_type == DatabaseType::DataFusion {
+            return Ok(());
+        }
+        let placeholders = self.placeholder_count();
+        if placeholders == self.params.len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "query has {} placeholder(s) but {} param(s) are configured",
+                placeholders,
+                self.params.len()
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct LLMConfig {
     pub provider: LLMProvider,
     #[serde(default)]
@@ -446,6 +1197,21 @@ pub struct LLMConfig {
     pub system_prompt: Option<String>,
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
+    /// Sequences that end a completion (not used for Ollama)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Nucleus sampling probability mass (not used for Ollama)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Frequency penalty (OpenAI, Azure OpenAI and Gemini)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty (OpenAI, Azure OpenAI and Gemini)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Sampling seed for reproducible completions (OpenAI, Azure OpenAI and Gemini)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
     #[serde(default)]
     pub stream: bool,
     /// Base URL for API endpoint (required for Ollama and AzureOpenAI)
@@ -453,7 +1219,7 @@ pub struct LLMConfig {
     pub base_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq, Hash, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LLMProvider {
     #[default]
@@ -468,25 +1234,77 @@ pub enum LLMProvider {
     AzureOpenAI,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Price of an LLM model, for workflow cost estimates and agent cost accounting
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ModelPricing {
+    pub provider: LLMProvider,
+    pub model: String,
+    /// Price in USD of one token, for the directions without their own price
+    #[serde(default)]
+    pub price_per_token: f64,
+    /// Price in USD of one prompt token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_price_per_token: Option<f64>,
+    /// Price in USD of one completion token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_price_per_token: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn input_price(&self) -> f64 {
+        self.input_price_per_token.unwrap_or(self.price_per_token)
+    }
+
+    pub fn output_price(&self) -> f64 {
+        self.output_price_per_token.unwrap_or(self.price_per_token)
+    }
+
+    /// Cost in USD of a completion with these token counts
+    pub fn cost(&self, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+        prompt_tokens as f64 * self.input_price() + completion_tokens as f64 * self.output_price()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct FileConfig {
     pub path: String,
     #[serde(default = "default_selection")]
     pub selection: String, // "random", "sequential", "weighted"
+    /// Return the whole file instead of selecting one record. In HTTP mock mode,
+    /// `GET /resources/{uri}` streams it in chunks rather than loading it into memory.
+    #[serde(default)]
+    pub stream: bool,
+    /// Largest file that is read or streamed, in bytes (default 100 MiB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
 }
 
 fn default_selection() -> String {
     "random".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct StatefulConfig {
     pub state_key: String,
     pub operation: StateOperation,
     pub template: Option<String>,
+    /// Whether the state is shared by all clients or kept per MCP session
+    #[serde(default)]
+    pub scope: StateScope,
+}
+
+/// Who sees a stateful mock's state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StateScope {
+    /// One value shared by every client
+    #[default]
+    Global,
+    /// A separate value for each MCP session; calls without a session use the global one
+    Session,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum StateOperation {
     Get,
@@ -494,7 +1312,7 @@ pub enum StateOperation {
     Increment,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct PromptConfig {
     pub name: String,
     pub description: String,
@@ -508,14 +1326,14 @@ pub struct PromptConfig {
     pub messages: Option<Vec<PromptMessage>>, // Static messages for now
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct PromptArgument {
     pub name: String,
     pub description: Option<String>,
     pub required: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct PromptMessage {
     pub role: String,
     pub content: String,
@@ -526,7 +1344,7 @@ pub struct PromptMessage {
 // ============================================================================
 
 /// Configuration for a workflow that can be executed as a tool
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct WorkflowConfig {
     /// Unique name for the workflow (becomes the tool name)
     pub name: String,
@@ -546,6 +1364,13 @@ pub struct WorkflowConfig {
     /// Default error handling strategy for the workflow
     #[serde(default)]
     pub on_error: ErrorStrategy,
+    /// Tera template rendered to JSON to reshape the workflow result
+    /// (step results are available as `{{ steps.step_id.result }}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mapping: Option<String>,
+    /// Rhai script reshaping the workflow result; takes precedence over `output_mapping`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_transform: Option<String>,
 }
 
 fn default_workflow_schema() -> Value {
@@ -556,7 +1381,7 @@ fn default_workflow_schema() -> Value {
 }
 
 /// A single step in a workflow
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct WorkflowStep {
     /// Unique identifier for this step (used for referencing results)
     pub id: String,
@@ -583,6 +1408,45 @@ pub struct WorkflowStep {
     /// Error handling strategy for this step
     #[serde(default)]
     pub on_error: ErrorStrategy,
+    /// Named locks held while this step runs. Parallel steps sharing a key are
+    /// serialized; steps with disjoint keys still run concurrently.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_lock_keys: Vec<String>,
+    /// Caller details added to the step's args under reserved `_ctx_` keys
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inject_context: Vec<ContextField>,
+}
+
+/// Prefix of the arg keys workflow steps receive from `inject_context`
+pub const CONTEXT_KEY_PREFIX: &str = "_ctx_";
+
+/// Caller detail a workflow step can receive in its args
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextField {
+    /// Client IP address, as `_ctx_caller_ip`
+    CallerIp,
+    /// Authenticated user ID, as `_ctx_identity`
+    AuthIdentity,
+    /// Trace ID of the request, as `_ctx_trace_id`
+    TraceId,
+    /// MCP session ID, as `_ctx_session_id`
+    SessionId,
+    /// RFC 3339 time the call was received, as `_ctx_timestamp`
+    Timestamp,
+}
+
+impl ContextField {
+    /// Arg key the field is injected under
+    pub fn key(self) -> &'static str {
+        match self {
+            ContextField::CallerIp => "_ctx_caller_ip",
+            ContextField::AuthIdentity => "_ctx_identity",
+            ContextField::TraceId => "_ctx_trace_id",
+            ContextField::SessionId => "_ctx_session_id",
+            ContextField::Timestamp => "_ctx_timestamp",
+        }
+    }
 }
 
 fn default_loop_var() -> String {
@@ -594,7 +1458,7 @@ fn default_concurrency() -> u32 {
 }
 
 /// Strategy for handling errors in workflow execution
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ErrorStrategy {
     /// Stop workflow execution and return error
@@ -616,6 +1480,15 @@ pub enum ErrorStrategy {
     },
 }
 
+/// Settings of a config that sets nothing, with the host and port the loader
+/// defaults to
+impl Default for Settings {
+    fn default() -> Self {
+        serde_json::from_value(serde_json::json!({"server": {"host": "127.0.0.1", "port": 3000}}))
+            .expect("an empty config deserializes")
+    }
+}
+
 impl Settings {
     pub fn new() -> Result<Self, anyhow::Error> {
         Self::from_root(".")
@@ -623,39 +1496,7 @@ impl Settings {
 
     /// Create settings from CLI arguments (includes config file and CLI overrides)
     pub fn new_with_cli(cli: &Cli) -> Result<Self, anyhow::Error> {
-        let config_path = &cli.config;
-        let root = config_path
-            .parent()
-            .map(|p| p.to_str().unwrap_or("."))
-            .unwrap_or(".");
-
-        // Build config from file
-        let s = Config::builder()
-            .add_source(File::from(config_path.clone()).required(false))
-            .set_default("server.host", "127.0.0.1")?
-            .set_default("server.port", 3000)?
-            .build()?;
-
-        let mut settings: Settings = s.try_deserialize()?;
-
-        // Store the config path as-is (don't canonicalize to preserve symlinks)
-        // This is important for Kubernetes ConfigMaps which use symlinks that change
-        // during updates. Canonicalizing would resolve to a path that may become stale.
-        settings.config_path = Some(config_path.clone());
-
-        // Apply CLI overrides (CLI > env vars > config file)
-        settings.apply_cli_overrides(cli);
-
-        settings.load_external_configs(root)?;
-
-        // Validate configuration
-        validator::ConfigValidator::validate(&settings).map_err(|errors| {
-            let error_messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
-            anyhow::anyhow!(
-                "Configuration validation failed:\n{}",
-                error_messages.join("\n")
-            )
-        })?;
+        let settings = Self::load(&cli.config, &cli.config, cli.profile.as_deref(), Some(cli))?;
 
         // Validate S3 configuration if present
         if let Some(s3_config) = &settings.s3 {
@@ -692,21 +1533,75 @@ impl Settings {
     }
 
     pub fn from_root(root: &str) -> Result<Self, anyhow::Error> {
+        Self::from_root_with_profile(root, None)
+    }
+
+    /// Load `metis.toml` and the config directories under `root`, overlaid with `profile`
+    pub fn from_root_with_profile(root: &str, profile: Option<&str>) -> Result<Self, anyhow::Error> {
         let config_path = std::path::Path::new(root).join("metis.toml");
+        Self::load(&config_path, &config_path, profile, None)
+    }
+
+    /// Load a backup of the config file at `config_path` in its place: with
+    /// the includes, profile and config directories next to `config_path`,
+    /// parsed in the format of `config_path`
+    pub fn from_backup(
+        config_path: &std::path::Path,
+        backup: &std::path::Path,
+        profile: Option<&str>,
+    ) -> Result<Self, anyhow::Error> {
+        Self::load(config_path, backup, profile, None)
+    }
+
+    /// Load `main_file` as the config file at `config_path`, merge the
+    /// includes, profile and config directories next to `config_path` and
+    /// validate the result. `main_file` is `config_path` itself except when
+    /// restoring a backup.
+    fn load(
+        config_path: &std::path::Path,
+        main_file: &std::path::Path,
+        profile: Option<&str>,
+        cli: Option<&Cli>,
+    ) -> Result<Self, anyhow::Error> {
+        // A bare file name has an empty parent, which would put the config dirs at `/config`
+        let root = config_path
+            .parent()
+            .and_then(|p| p.to_str())
+            .filter(|p| !p.is_empty())
+            .unwrap_or(".");
+
+        let mut file = File::from(main_file.to_path_buf()).required(main_file != config_path);
+        if main_file != config_path {
+            // Backups are named `<file>.bak.<timestamp>`, so their own extension says nothing
+            file = file.format(Self::file_format(config_path));
+        }
         let s = Config::builder()
-            .add_source(File::from(config_path.clone()).required(false))
+            .add_source(file)
             .set_default("server.host", "127.0.0.1")?
             .set_default("server.port", 3000)?
             .build()?;
 
-        let mut settings: Settings = s.try_deserialize()?;
+        let mut settings = Self::deserialize_migrated(s)?;
+        settings.load_includes(std::path::Path::new(root), config_path)?;
+        if let Some(profile) = profile {
+            settings.load_profile_file(std::path::Path::new(root), config_path, profile)?;
+        }
 
         // Store the config path as-is (don't canonicalize to preserve symlinks)
         // This is important for Kubernetes ConfigMaps which use symlinks that change
         // during updates. Canonicalizing would resolve to a path that may become stale.
-        settings.config_path = Some(config_path);
+        settings.config_path = Some(config_path.to_path_buf());
 
-        settings.load_external_configs(root)?;
+        // Apply CLI overrides (CLI > env vars > config file)
+        if let Some(cli) = cli {
+            settings.apply_cli_overrides(cli);
+        }
+
+        settings.load_external_configs(&format!("{}/config", root))?;
+        if let Some(profile) = profile {
+            settings.load_profile_dirs(root, profile)?;
+            settings.profile = Some(profile.to_string());
+        }
 
         // Validate configuration
         validator::ConfigValidator::validate(&settings)
@@ -718,16 +1613,201 @@ impl Settings {
         Ok(settings)
     }
 
-    fn load_external_configs(&mut self, root: &str) -> Result<(), anyhow::Error> {
-        self.load_tools_from_dir(&format!("{}/config/tools", root))?;
-        self.load_resources_from_dir(&format!("{}/config/resources", root))?;
-        self.load_resource_templates_from_dir(&format!("{}/config/resource_templates", root))?;
-        self.load_prompts_from_dir(&format!("{}/config/prompts", root))?;
-        self.load_workflows_from_dir(&format!("{}/config/workflows", root))?;
-        self.load_agents_from_dir(&format!("{}/config/agents", root))?;
-        self.load_orchestrations_from_dir(&format!("{}/config/orchestrations", root))?;
-        self.load_schemas_from_dir(&format!("{}/config/schemas", root))?;
-        self.load_data_lakes_from_dir(&format!("{}/config/data_lakes", root))?;
+    /// Format of a config file, by its extension (TOML when it has none)
+    fn file_format(path: &std::path::Path) -> config::FileFormat {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => config::FileFormat::Json,
+            Some("yaml" | "yml") => config::FileFormat::Yaml,
+            _ => config::FileFormat::Toml,
+        }
+    }
+
+    /// Deserialize a raw config, migrating it from an older schema version first if needed
+    fn deserialize_migrated(config: Config) -> Result<Self, anyhow::Error> {
+        let raw: Value = config.try_deserialize()?;
+        let raw = migrations::migrate_if_needed(raw)?;
+        Ok(serde_json::from_value(raw)?)
+    }
+
+    /// Merge the files listed in `includes` over these settings, in order.
+    /// `config_path` is the file these settings were loaded from, used to detect
+    /// files that include themselves.
+    fn load_includes(&mut self, root: &std::path::Path, config_path: &std::path::Path) -> Result<(), anyhow::Error> {
+        let mut visiting = HashSet::new();
+        if let Ok(canonical) = config_path.canonicalize() {
+            visiting.insert(canonical);
+        }
+        let includes = self.includes.clone();
+        self.merge_includes(root, &includes, &mut visiting)
+    }
+
+    /// Recursively merge includes. `visiting` holds the files on the current
+    /// include chain; meeting one of them again means the includes are circular.
+    fn merge_includes(
+        &mut self,
+        root: &std::path::Path,
+        includes: &[String],
+        visiting: &mut HashSet<PathBuf>,
+    ) -> Result<(), anyhow::Error> {
+        for include in includes {
+            let path = root.join(include);
+            let canonical = path
+                .canonicalize()
+                .map_err(|e| anyhow::anyhow!("Failed to resolve include '{}': {}", include, e))?;
+            if !visiting.insert(canonical.clone()) {
+                return Err(anyhow::anyhow!("Circular include detected: '{}'", include));
+            }
+
+            let included = self
+                .parse_include(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to load include '{}': {}", include, e))?;
+            let nested = included.includes.clone();
+            self.merge(included);
+            self.merge_includes(root, &nested, visiting)?;
+
+            visiting.remove(&canonical);
+        }
+        Ok(())
+    }
+
+    /// Parse an included file. Sections that `merge` always overwrites keep
+    /// their current values unless the included file sets them.
+    fn parse_include(&self, path: &std::path::Path) -> Result<Settings, anyhow::Error> {
+        let config = Config::builder()
+            .add_source(File::from(path.to_path_buf()))
+            .build()?;
+        let raw = migrations::migrate_if_needed(config.try_deserialize::<Value>()?)?;
+        self.overlay_from_raw(raw)
+    }
+
+    /// Settings to `merge` over these ones, built from a raw config that may
+    /// leave out the sections `merge` always overwrites
+    fn overlay_from_raw(&self, mut raw: Value) -> Result<Settings, anyhow::Error> {
+        if let Value::Object(obj) = &mut raw {
+            let current = serde_json::to_value(self)?;
+            for key in ["server", "auth", "default_tool_response", "validate_on_write"] {
+                if !obj.contains_key(key) {
+                    obj.insert(key.to_string(), current[key].clone());
+                }
+            }
+        }
+
+        Ok(serde_json::from_value(raw)?)
+    }
+
+    /// Merge `<config stem>.<profile>.<ext>` (e.g. `metis.staging.toml`) over
+    /// these settings. Fails if the profile has neither that file nor a
+    /// `config/<profile>/` directory.
+    fn load_profile_file(
+        &mut self,
+        root: &std::path::Path,
+        config_path: &std::path::Path,
+        profile: &str,
+    ) -> Result<(), anyhow::Error> {
+        if profile.is_empty() || !profile.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(anyhow::anyhow!(
+                "Invalid profile '{}': use letters, numbers, underscores and hyphens",
+                profile
+            ));
+        }
+
+        let file_name = Self::profile_file_name(config_path, profile);
+        if !root.join(&file_name).exists() {
+            if root.join("config").join(profile).is_dir() {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!(
+                "Profile '{}' not found: expected {} or config/{}/",
+                profile,
+                file_name,
+                profile
+            ));
+        }
+
+        let mut visiting = HashSet::new();
+        if let Ok(canonical) = config_path.canonicalize() {
+            visiting.insert(canonical);
+        }
+        self.merge_includes(root, &[file_name], &mut visiting)
+    }
+
+    /// File name of a profile's config file next to `config_path`
+    pub fn profile_file_name(config_path: &std::path::Path, profile: &str) -> String {
+        let stem = config_path.file_stem().and_then(|s| s.to_str()).unwrap_or("metis");
+        let extension = config_path.extension().and_then(|e| e.to_str()).unwrap_or("toml");
+        format!("{}.{}.{}", stem, profile, extension)
+    }
+
+    /// Merge the items in `config/<profile>/<type>/` over those already loaded,
+    /// replacing items with the same name
+    fn load_profile_dirs(&mut self, root: &str, profile: &str) -> Result<(), anyhow::Error> {
+        let mut overlay = self.overlay_from_raw(serde_json::json!({}))?;
+        overlay.load_external_configs(&format!("{}/config/{}", root, profile))?;
+        self.merge(overlay);
+        Ok(())
+    }
+
+    /// Capabilities to advertise to MCP clients: `mcp.capabilities`, or else
+    /// those whose lists would not be empty
+    pub fn mcp_capabilities(&self) -> Vec<McpCapability> {
+        if let Some(capabilities) = &self.mcp.capabilities {
+            return capabilities.clone();
+        }
+
+        // Resources, templates, agents and external servers' tools are listed as tools too
+        let has_tools = !self.tools.is_empty()
+            || !self.workflows.is_empty()
+            || !self.agents.is_empty()
+            || !self.mcp_servers.is_empty()
+            || !self.resources.is_empty()
+            || !self.resource_templates.is_empty();
+        let has_resources = !self.resources.is_empty() || !self.resource_templates.is_empty();
+        [
+            (McpCapability::Tools, has_tools),
+            (McpCapability::Resources, has_resources),
+            (McpCapability::Prompts, !self.prompts.is_empty()),
+        ]
+        .into_iter()
+        .filter_map(|(capability, in_use)| in_use.then_some(capability))
+        .collect()
+    }
+
+    /// Name of the tool that a call to `name` runs: the target of its
+    /// `tool_aliases` entry, with `server/tool` targets turned into the
+    /// `mcp__server_tool` name of the external tool, or `name` itself. The
+    /// target is never looked up again, so aliases don't chain.
+    pub fn resolve_tool_alias(&self, name: &str) -> String {
+        match self.tool_aliases.get(name) {
+            Some(target) => match target.split_once('/') {
+                Some((server, tool)) => match self.mcp_servers.iter().find(|m| m.name == server) {
+                    Some(mcp_server) => mcp_server.exposed_tool_name(tool),
+                    None => format!("{}{}_{}", crate::adapters::mcp_client::MCP_TOOL_PREFIX, server, tool),
+                },
+                None => target.clone(),
+            },
+            None => name.to_string(),
+        }
+    }
+
+    /// Remember that the item `key` of `item_type` was read from `path`
+    fn record_source(&mut self, item_type: ArchetypeType, key: &str, path: &std::path::Path) {
+        self.sources
+            .entry((item_type, key.to_string()))
+            .or_default()
+            .push(path.to_path_buf());
+    }
+
+    /// Load item files from the per-type subdirectories of `dir` (e.g. `<dir>/tools`)
+    fn load_external_configs(&mut self, dir: &str) -> Result<(), anyhow::Error> {
+        self.load_tools_from_dir(&format!("{}/tools", dir))?;
+        self.load_resources_from_dir(&format!("{}/resources", dir))?;
+        self.load_resource_templates_from_dir(&format!("{}/resource_templates", dir))?;
+        self.load_prompts_from_dir(&format!("{}/prompts", dir))?;
+        self.load_workflows_from_dir(&format!("{}/workflows", dir))?;
+        self.load_agents_from_dir(&format!("{}/agents", dir))?;
+        self.load_orchestrations_from_dir(&format!("{}/orchestrations", dir))?;
+        self.load_schemas_from_dir(&format!("{}/schemas", dir))?;
+        self.load_data_lakes_from_dir(&format!("{}/data_lakes", dir))?;
         Ok(())
     }
 
@@ -746,16 +1826,44 @@ impl Settings {
         // Auth: other overrides self
         self.auth = other.auth;
 
+        // Features: other overrides self
+        self.features = other.features;
+
+        // MCP server info: other overrides self
+        self.mcp = other.mcp;
+
+        // Default tool response: other overrides self
+        self.default_tool_response = other.default_tool_response;
+
+        // Validate on write: other overrides self
+        self.validate_on_write = other.validate_on_write;
+
+        // Tool aliases: other's entries override self's
+        self.tool_aliases.extend(other.tool_aliases);
+
+        // Named databases: other's entries override self's
+        self.databases.extend(other.databases);
+
         // Rate limit: other overrides if present
         if other.rate_limit.is_some() {
             self.rate_limit = other.rate_limit;
         }
 
+        // Access control: other overrides if present
+        if other.access_control.is_some() {
+            self.access_control = other.access_control;
+        }
+
         // S3 config: other overrides if present
         if other.s3.is_some() {
             self.s3 = other.s3;
         }
 
+        // Generation provider: other overrides if present
+        if other.generation_provider.is_some() {
+            self.generation_provider = other.generation_provider;
+        }
+
         // Secrets: merge individual fields, other overrides if present
         self.secrets.merge(&other.secrets);
 
@@ -770,6 +1878,15 @@ impl Settings {
         Self::merge_vec_by_key(&mut self.schemas, other.schemas, |s| s.name.clone());
         Self::merge_vec_by_key(&mut self.data_lakes, other.data_lakes, |d| d.name.clone());
         Self::merge_vec_by_key(&mut self.mcp_servers, other.mcp_servers, |m| m.name.clone());
+        Self::merge_vec_by_key(&mut self.pricing, other.pricing, |p| (p.provider.clone(), p.model.clone()));
+
+        // An item of `other` replaces the last item with its key, so its file
+        // replaces that item's file
+        for (key, paths) in other.sources {
+            let sources = self.sources.entry(key).or_default();
+            sources.pop();
+            sources.extend(paths);
+        }
     }
 
     /// Merge two vectors by a key function.
@@ -819,6 +1936,91 @@ impl Settings {
         }
     }
 
+    /// Directories holding the fixture files of File-strategy tools, resources and
+    /// resource templates, without duplicates
+    pub fn fixture_dirs(&self) -> Vec<std::path::PathBuf> {
+        let mocks = self
+            .tools
+            .iter()
+            .map(|t| &t.mock)
+            .chain(self.resources.iter().map(|r| &r.mock))
+            .chain(self.resource_templates.iter().map(|r| &r.mock))
+            .flatten();
+
+        let mut dirs = Vec::new();
+        for mock in mocks {
+            let Some(file) = mock.file.as_ref().filter(|_| matches!(mock.strategy, MockStrategyType::File)) else {
+                continue;
+            };
+            let dir = match std::path::Path::new(&file.path).parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => std::path::PathBuf::from("."),
+            };
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
+
+    /// JSON Schema of a config file, with descriptions taken from the doc comments.
+    /// Nested types are listed under `$defs`, or inlined where they are used when
+    /// `inline_refs` is set (recursive types still go through `$defs`).
+    pub fn json_schema(inline_refs: bool) -> Value {
+        let mut generator = schemars::gen::SchemaSettings::draft2019_09();
+        generator.definitions_path = "#/$defs/".to_string();
+        generator.inline_subschemas = inline_refs;
+        let root = generator.into_generator().into_root_schema_for::<Settings>();
+
+        let mut schema = serde_json::to_value(root).unwrap_or_default();
+        if let Some(object) = schema.as_object_mut() {
+            if let Some(definitions) = object.remove("definitions") {
+                object.insert("$defs".to_string(), definitions);
+            }
+        }
+        schema
+    }
+
+    /// Expand schema `$ref`s in the input and output schemas of every item, as
+    /// they are served to clients. References that don't resolve are kept as-is.
+    pub fn expand_schema_refs(&mut self) {
+        let schemas = std::mem::take(&mut self.schemas);
+        let expand = |schema: &mut Value| {
+            if let Ok(resolved) = schema::resolve_schema_refs(schema, &schemas) {
+                *schema = resolved;
+            }
+        };
+
+        for resource in &mut self.resources {
+            resource.output_schema.iter_mut().for_each(expand);
+        }
+        for template in &mut self.resource_templates {
+            template.input_schema.iter_mut().for_each(expand);
+            template.output_schema.iter_mut().for_each(expand);
+        }
+        for tool in &mut self.tools {
+            expand(&mut tool.input_schema);
+            tool.output_schema.iter_mut().for_each(expand);
+        }
+        for prompt in &mut self.prompts {
+            prompt.input_schema.iter_mut().for_each(expand);
+        }
+        for workflow in &mut self.workflows {
+            expand(&mut workflow.input_schema);
+            workflow.output_schema.iter_mut().for_each(expand);
+        }
+        for agent in &mut self.agents {
+            expand(&mut agent.input_schema);
+            agent.output_schema.iter_mut().for_each(expand);
+        }
+        for orchestration in &mut self.orchestrations {
+            expand(&mut orchestration.input_schema);
+            orchestration.output_schema.iter_mut().for_each(expand);
+        }
+
+        self.schemas = schemas;
+    }
+
     /// Merge S3 configuration files into this Settings.
     /// Each file in the list is parsed and merged with precedence (later files override earlier).
     /// Supports TOML, YAML, and JSON formats based on file extension.
@@ -1057,6 +2259,7 @@ impl Settings {
                             } else {
                                 serde_yaml::from_str(&content)?
                             };
+                            self.record_source(ArchetypeType::Tool, &tool.name, &path);
                             self.tools.push(tool);
                         }
                     }
@@ -1080,6 +2283,7 @@ impl Settings {
                             } else {
                                 serde_yaml::from_str(&content)?
                             };
+                            self.record_source(ArchetypeType::Resource, &resource.uri, &path);
                             self.resources.push(resource);
                         }
                     }
@@ -1103,6 +2307,7 @@ impl Settings {
                             } else {
                                 serde_yaml::from_str(&content)?
                             };
+                            self.record_source(ArchetypeType::ResourceTemplate, &resource_template.uri_template, &path);
                             self.resource_templates.push(resource_template);
                         }
                     }
@@ -1126,6 +2331,7 @@ impl Settings {
                             } else {
                                 serde_yaml::from_str(&content)?
                             };
+                            self.record_source(ArchetypeType::Prompt, &prompt.name, &path);
                             self.prompts.push(prompt);
                         }
                     }
@@ -1149,6 +2355,7 @@ impl Settings {
                                 "toml" => toml::from_str(&content)?,
                                 _ => serde_yaml::from_str(&content)?,
                             };
+                            self.record_source(ArchetypeType::Workflow, &workflow.name, &path);
                             self.workflows.push(workflow);
                         }
                     }
@@ -1172,6 +2379,7 @@ impl Settings {
                                 "toml" => toml::from_str(&content)?,
                                 _ => serde_yaml::from_str(&content)?,
                             };
+                            self.record_source(ArchetypeType::Agent, &agent.name, &path);
                             self.agents.push(agent);
                         }
                     }
@@ -1195,6 +2403,7 @@ impl Settings {
                                 "toml" => toml::from_str(&content)?,
                                 _ => serde_yaml::from_str(&content)?,
                             };
+                            self.record_source(ArchetypeType::Orchestration, &orchestration.name, &path);
                             self.orchestrations.push(orchestration);
                         }
                     }
@@ -1218,6 +2427,7 @@ impl Settings {
                                 "toml" => toml::from_str(&content)?,
                                 _ => serde_yaml::from_str(&content)?,
                             };
+                            self.record_source(ArchetypeType::Schema, &schema.name, &path);
                             self.schemas.push(schema);
                         }
                     }
@@ -1241,6 +2451,7 @@ impl Settings {
                                 "toml" => toml::from_str(&content)?,
                                 _ => serde_yaml::from_str(&content)?,
                             };
+                            self.record_source(ArchetypeType::DataLake, &data_lake.name, &path);
                             self.data_lakes.push(data_lake);
                         }
                     }
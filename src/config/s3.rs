@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::cli::Cli;
 
 /// S3 configuration for remote configuration source
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct S3Config {
     /// Whether S3 configuration source is enabled
     #[serde(default)]
@@ -242,7 +243,7 @@ mod tests {
 
         // Deserialize and verify roundtrip
         let deserialized: S3Config = toml::from_str(&toml_str).unwrap();
-        assert_eq!(deserialized.enabled, true);
+        assert!(deserialized.enabled);
         assert_eq!(deserialized.bucket, Some("my-bucket".to_string()));
         assert_eq!(deserialized.region, Some("us-east-1".to_string()));
     }
@@ -30,11 +30,12 @@
 //!   $ref: UserInput
 //! ```
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Configuration for a reusable JSON schema definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SchemaConfig {
     /// Unique name for this schema (used in $ref references)
     pub name: String,
@@ -1,23 +1,97 @@
-use std::collections::HashMap;
-use thiserror::Error;
-
-use crate::config::{ResourceConfig, Settings, ToolConfig};
-
-#[derive(Debug, Error)]
-pub enum ValidationError {
-    #[error("Missing required field: {0}")]
-    MissingField(String),
-    
-    #[error("Invalid value for {field}: {reason}")]
-    InvalidValue { field: String, reason: String },
-    
-    #[error("Cross-reference error: {0}")]
-    CrossReference(String),
-    
-    #[error("Duplicate entry: {0}")]
-    Duplicate(String),
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use crate::adapters::tool_handler::AGENT_TOOL_PREFIX;
+use crate::agents::config::{AgentConfig, LlmProviderType, OrchestrationConfig};
+use crate::agents::llm::ProviderRegistry;
+use crate::config::{
+    CustomFaker, DataLakeConfig, DatabaseConfig, DatabaseType, DefaultToolResponse, LatencyConfig, MockConfig, PromptConfig, ResourceConfig,
+    ResourceTemplateConfig, SchemaConfig, Settings, ToolConfig, WorkflowConfig,
+};
+use crate::domain::ResourceContentKind;
+use crate::persistence::models::ArchetypeType;
+
+/// Kind of problem a [`ValidationError`] reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationErrorKind {
+    MissingField,
+    InvalidValue,
+    CrossReference,
+    Duplicate,
+}
+
+/// A problem found in the config, with enough structure for the UI to point
+/// at the offending item and field
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationError {
+    pub kind: ValidationErrorKind,
+    /// Type of the archetype the error is in, if any
+    pub archetype: Option<ArchetypeType>,
+    /// Name (or URI) of the item the error is in
+    pub item: Option<String>,
+    /// Path of the offending field, e.g. `tools[2].description`
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn missing_field(field: impl Into<String>) -> Self {
+        Self::new(ValidationErrorKind::MissingField, Some(field.into()), "Missing required field")
+    }
+
+    pub fn invalid_value(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::new(ValidationErrorKind::InvalidValue, Some(field.into()), reason)
+    }
+
+    pub fn duplicate(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(ValidationErrorKind::Duplicate, Some(field.into()), message)
+    }
+
+    pub fn cross_reference(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(ValidationErrorKind::CrossReference, Some(field.into()), message)
+    }
+
+    fn new(kind: ValidationErrorKind, field: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            archetype: None,
+            item: None,
+            field,
+            message: message.into(),
+        }
+    }
+
+    /// Attribute the error to an archetype item
+    pub fn in_item(mut self, archetype: ArchetypeType, item: impl Into<String>) -> Self {
+        self.archetype = Some(archetype);
+        self.item = Some(item.into()).filter(|i| !i.is_empty());
+        self
+    }
 }
 
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let field = self.field.as_deref().unwrap_or_default();
+        match self.kind {
+            ValidationErrorKind::MissingField => write!(f, "Missing required field: {}", field),
+            ValidationErrorKind::InvalidValue => write!(f, "Invalid value for {}: {}", field, self.message),
+            ValidationErrorKind::CrossReference => write!(f, "Cross-reference error: {}", self.message),
+            ValidationErrorKind::Duplicate => write!(f, "Duplicate entry: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Fields of a config item that hold a JSON Schema
+const SCHEMA_FIELDS: &[&str] = &["input_schema", "output_schema", "schema"];
+
+/// Valid values of the JSON Schema `type` keyword
+const SCHEMA_TYPES: &[&str] = &["object", "array", "string", "number", "integer", "boolean", "null"];
+
 pub struct ConfigValidator;
 
 impl ConfigValidator {
@@ -29,13 +103,42 @@ impl ConfigValidator {
             errors.extend(e);
         }
 
+        // Validate rate limit
+        if let Some(rate_limit) = &settings.rate_limit {
+            if let Err(e) = Self::validate_rate_limit(rate_limit) {
+                errors.extend(e);
+            }
+        }
+
+        // Validate access control lists
+        if let Some(access_control) = &settings.access_control {
+            if let Err(e) = Self::validate_access_control(access_control) {
+                errors.extend(e);
+            }
+        }
+
+        // Validate model prices
+        if let Err(e) = Self::validate_pricing(&settings.pricing) {
+            errors.extend(e);
+        }
+
+        // Validate custom faker patterns
+        if let Err(e) = Self::validate_custom_fakers(&settings.custom_fakers) {
+            errors.extend(e);
+        }
+
         // Validate resources
         if let Err(e) = Self::validate_resources(&settings.resources) {
             errors.extend(e);
         }
 
+        // Validate resource templates
+        if let Err(e) = Self::validate_resource_templates(&settings.resource_templates) {
+            errors.extend(e);
+        }
+
         // Validate tools
-        if let Err(e) = Self::validate_tools(&settings.tools) {
+        if let Err(e) = Self::validate_tools(&settings.tools, &settings.default_tool_response) {
             errors.extend(e);
         }
 
@@ -44,6 +147,11 @@ impl ConfigValidator {
             errors.extend(e);
         }
 
+        // Items defined more than once
+        if let Err(e) = Self::validate_duplicates(settings) {
+            errors.extend(e);
+        }
+
         // Cross-reference validation
         if let Err(e) = Self::validate_cross_references(settings) {
             errors.extend(e);
@@ -56,22 +164,247 @@ impl ConfigValidator {
         }
     }
 
+    /// Validate a single item in isolation, e.g. before an API write applies it.
+    ///
+    /// Checks that the item deserializes into its config type (required fields
+    /// and types), the per-item rules of the full validator, that embedded JSON
+    /// Schemas are well-formed, and that `$ref`s don't loop back on themselves.
+    /// Rules that depend on other items, such as duplicate names, are left to
+    /// [`ConfigValidator::validate`].
+    pub fn validate_partial(item_type: ArchetypeType, item: &Value) -> Result<(), Vec<ValidationError>> {
+        let section = item_type.section();
+        let mut errors = Vec::new();
+
+        let per_item = match item_type {
+            ArchetypeType::Resource => parse_item::<ResourceConfig>(section, item)
+                .and_then(|r| Self::validate_resources(std::slice::from_ref(&r))),
+            ArchetypeType::ResourceTemplate => parse_item::<ResourceTemplateConfig>(section, item)
+                .and_then(|t| Self::validate_resource_templates(std::slice::from_ref(&t))),
+            ArchetypeType::Prompt => parse_item::<PromptConfig>(section, item)
+                .and_then(|p| Self::validate_prompts(std::slice::from_ref(&p))),
+            ArchetypeType::Tool => parse_item::<ToolConfig>(section, item).and_then(|t| {
+                require_text(section, &[("name", &t.name), ("description", &t.description)])?;
+                match check_database_params(t.mock.as_ref(), section.to_string()) {
+                    Some(error) => Err(vec![error]),
+                    None => Ok(()),
+                }
+            }),
+            ArchetypeType::Workflow => {
+                parse_item::<WorkflowConfig>(section, item).and_then(|w| require_text(section, &[("name", &w.name)]))
+            }
+            ArchetypeType::Agent => {
+                parse_item::<AgentConfig>(section, item).and_then(|a| require_text(section, &[("name", &a.name)]))
+            }
+            ArchetypeType::Orchestration => parse_item::<OrchestrationConfig>(section, item)
+                .and_then(|o| require_text(section, &[("name", &o.name)])),
+            ArchetypeType::Schema => {
+                parse_item::<SchemaConfig>(section, item).and_then(|s| require_text(section, &[("name", &s.name)]))
+            }
+            ArchetypeType::DataLake => {
+                parse_item::<DataLakeConfig>(section, item).and_then(|d| require_text(section, &[("name", &d.name)]))
+            }
+        };
+        if let Err(e) = per_item {
+            errors.extend(e);
+        }
+
+        for field in SCHEMA_FIELDS {
+            let Some(schema) = item.get(*field).filter(|s| !s.is_null()) else {
+                continue;
+            };
+            let path = format!("{}.{}", section, field);
+
+            check_schema_syntax(schema, &path, &mut errors);
+
+            // A named schema may reference itself by name
+            let own_name = (item_type == ArchetypeType::Schema)
+                .then(|| item.get("name").and_then(|n| n.as_str()))
+                .flatten();
+            if let Some(reason) = find_ref_loop(schema, own_name) {
+                errors.push(ValidationError::invalid_value(path, reason));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            let name = item.get(item_type.key_field()).and_then(|n| n.as_str()).unwrap_or_default();
+            Err(errors.into_iter().map(|e| e.in_item(item_type, name)).collect())
+        }
+    }
+
+    /// Check that every agent's `llm.provider` is built in or registered in
+    /// `providers`. Any name deserializes, so that embedders can add their own
+    /// providers, which would otherwise let a typo like `opnai` through until
+    /// the agent fails to load.
+    pub fn validate_llm_providers(settings: &Settings, providers: &ProviderRegistry) -> Result<(), Vec<ValidationError>> {
+        let errors: Vec<ValidationError> = settings
+            .agents
+            .iter()
+            .enumerate()
+            .filter_map(|(i, agent)| unknown_llm_provider(agent, format!("agents[{}].llm.provider", i), providers))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// [`ConfigValidator::validate_llm_providers`] for a single agent, e.g.
+    /// before an API write applies it
+    pub fn validate_agent_llm_provider(agent: &AgentConfig, providers: &ProviderRegistry) -> Result<(), Vec<ValidationError>> {
+        match unknown_llm_provider(agent, "agents.llm.provider".to_string(), providers) {
+            Some(error) => Err(vec![error]),
+            None => Ok(()),
+        }
+    }
+
     fn validate_server(server: &crate::config::ServerSettings) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
 
         if server.host.is_empty() {
-            errors.push(ValidationError::MissingField("server.host".to_string()));
+            errors.push(ValidationError::missing_field("server.host"));
         }
 
         if server.port == 0 {
-            errors.push(ValidationError::InvalidValue {
-                field: "server.port".to_string(),
-                reason: "Port must be greater than 0".to_string(),
-            });
+            errors.push(ValidationError::invalid_value("server.port", "Port must be greater than 0"));
         }
 
         // Note: u16 max is 65535, so no need to check upper bound
 
+        if server.max_concurrent_tool_calls == Some(0) {
+            errors.push(ValidationError::invalid_value(
+                "server.max_concurrent_tool_calls",
+                "Must be greater than 0; leave it unset for no limit",
+            ));
+        }
+
+        if server.max_call_depth == 0 {
+            errors.push(ValidationError::invalid_value(
+                "server.max_call_depth",
+                "Must be greater than 0, or no tool call could run",
+            ));
+        }
+
+        if let Some(base_path) = &server.base_path {
+            if !base_path.starts_with('/') || base_path.contains(['*', ':', '{', '}']) {
+                errors.push(ValidationError::invalid_value(
+                    "server.base_path",
+                    format!("'{}' must start with '/' and be a plain path, like '/metis'", base_path),
+                ));
+            }
+        }
+
+        if server.log_level.parse::<tracing_subscriber::filter::LevelFilter>().is_err() {
+            errors.push(ValidationError::invalid_value(
+                "server.log_level",
+                format!(
+                    "Unknown log level '{}', expected trace, debug, info, warn, error or off",
+                    server.log_level
+                ),
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_rate_limit(config: &crate::config::RateLimitConfig) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if config.requests_per_second == 0 {
+            errors.push(ValidationError::invalid_value(
+                "rate_limit.requests_per_second",
+                "Must be greater than 0",
+            ));
+        }
+        if config.burst_size == 0 {
+            errors.push(ValidationError::invalid_value("rate_limit.burst_size", "Must be greater than 0"));
+        }
+        if config.algorithm == crate::config::RateLimitAlgorithm::Gcra && config.burst_size > 1 {
+            errors.push(ValidationError::invalid_value(
+                "rate_limit.burst_size",
+                "gcra spaces requests evenly and allows no bursts; set burst_size = 1, or use token_bucket or sliding_window",
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_access_control(config: &crate::config::AccessControlConfig) -> Result<(), Vec<ValidationError>> {
+        let lists = [
+            ("allow", &config.allow),
+            ("deny", &config.deny),
+            ("trusted_proxies", &config.trusted_proxies),
+        ];
+        let errors: Vec<ValidationError> = lists
+            .iter()
+            .flat_map(|(name, values)| {
+                values.iter().enumerate().filter_map(move |(i, value)| {
+                    crate::adapters::access_control::parse_network(value).err().map(|reason| {
+                        ValidationError::invalid_value(format!("access_control.{}[{}]", name, i), reason)
+                    })
+                })
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_pricing(pricing: &[crate::config::ModelPricing]) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        for (i, p) in pricing.iter().enumerate() {
+            let prices = [
+                ("price_per_token", Some(p.price_per_token)),
+                ("input_price_per_token", p.input_price_per_token),
+                ("output_price_per_token", p.output_price_per_token),
+            ];
+            for (field, price) in prices {
+                if price.is_some_and(|price| !price.is_finite() || price < 0.0) {
+                    errors.push(ValidationError::invalid_value(
+                        format!("pricing[{}].{}", i, field),
+                        format!("Price for model '{}' must be a non-negative number", p.model),
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_custom_fakers(fakers: &HashMap<String, CustomFaker>) -> Result<(), Vec<ValidationError>> {
+        let mut names: Vec<_> = fakers.keys().collect();
+        names.sort();
+        let errors: Vec<ValidationError> = names
+            .into_iter()
+            .filter_map(|name| match &fakers[name] {
+                CustomFaker::Regex(pattern) => unsupported_pattern_operator(pattern).map(|op| {
+                    ValidationError::invalid_value(
+                        format!("custom_fakers.{}", name),
+                        format!("Pattern uses '{}', which patterns don't support; escape it as '\\{}' for a literal", op, op),
+                    )
+                }),
+                CustomFaker::Template { .. } => None,
+            })
+            .collect();
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -81,35 +414,39 @@ impl ConfigValidator {
 
     fn validate_resources(resources: &[ResourceConfig]) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
-        let mut seen_uris = HashMap::new();
 
         for (idx, resource) in resources.iter().enumerate() {
-            // Check for duplicate URIs
-            if let Some(prev_idx) = seen_uris.insert(&resource.uri, idx) {
-                errors.push(ValidationError::Duplicate(
-                    format!("Resource URI '{}' appears at indices {} and {}", resource.uri, prev_idx, idx)
-                ));
-            }
+            let in_item = |e: ValidationError| e.in_item(ArchetypeType::Resource, &resource.uri);
 
             // Validate required fields
             if resource.uri.is_empty() {
-                errors.push(ValidationError::MissingField(
-                    format!("resources[{}].uri", idx)
-                ));
+                errors.push(in_item(ValidationError::missing_field(format!("resources[{}].uri", idx))));
             }
 
             if resource.name.is_empty() {
-                errors.push(ValidationError::MissingField(
-                    format!("resources[{}].name", idx)
-                ));
+                errors.push(in_item(ValidationError::missing_field(format!("resources[{}].name", idx))));
             }
 
             // Validate that either content or mock is provided
             if resource.content.is_none() && resource.mock.is_none() {
-                errors.push(ValidationError::InvalidValue {
-                    field: format!("resources[{}]", idx),
-                    reason: "Either 'content' or 'mock' must be provided".to_string(),
-                });
+                errors.push(in_item(ValidationError::invalid_value(
+                    format!("resources[{}]", idx),
+                    "Either 'content' or 'mock' must be provided",
+                )));
+            }
+
+            if let Some(error) = check_database_params(resource.mock.as_ref(), format!("resources[{}]", idx)) {
+                errors.push(in_item(error));
+            }
+
+            // Static content must be consistent with the declared MIME type
+            if let Some(content) = &resource.content {
+                if let Err(reason) = check_content_matches_mime(resource.mime_type.as_deref(), content) {
+                    errors.push(in_item(ValidationError::invalid_value(
+                        format!("resources[{}].content", idx),
+                        reason,
+                    )));
+                }
             }
         }
 
@@ -120,37 +457,125 @@ impl ConfigValidator {
         }
     }
 
-    fn validate_tools(tools: &[ToolConfig]) -> Result<(), Vec<ValidationError>> {
+    fn validate_resource_templates(templates: &[ResourceTemplateConfig]) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
-        let mut seen_names = HashMap::new();
 
-        for (idx, tool) in tools.iter().enumerate() {
-            // Check for duplicate names
-            if let Some(prev_idx) = seen_names.insert(&tool.name, idx) {
-                errors.push(ValidationError::Duplicate(
-                    format!("Tool name '{}' appears at indices {} and {}", tool.name, prev_idx, idx)
-                ));
+        for (idx, template) in templates.iter().enumerate() {
+            let in_item = |e: ValidationError| e.in_item(ArchetypeType::ResourceTemplate, &template.uri_template);
+
+            if template.uri_template.is_empty() {
+                errors.push(in_item(ValidationError::missing_field(format!(
+                    "resource_templates[{}].uri_template",
+                    idx
+                ))));
+            }
+
+            let path = format!("resource_templates[{}]", idx);
+            if let Some(error) = check_database_params(template.mock.as_ref(), path) {
+                errors.push(in_item(error));
+            }
+
+            let variables = match template_variables(&template.uri_template) {
+                Ok(variables) => variables,
+                Err(reason) => {
+                    errors.push(in_item(ValidationError::invalid_value(
+                        format!("resource_templates[{}].uri_template", idx),
+                        reason,
+                    )));
+                    continue;
+                }
+            };
+
+            // Variables and the input schema must describe the same parameters,
+            // otherwise a concrete URI can't be reverse-resolved into valid args
+            let Some(schema) = &template.input_schema else {
+                continue;
+            };
+            let field = format!("resource_templates[{}].input_schema", idx);
+
+            if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+                for variable in &variables {
+                    if !properties.contains_key(variable) {
+                        errors.push(in_item(ValidationError::invalid_value(
+                            field.clone(),
+                            format!("Template variable '{{{}}}' is not declared in properties", variable),
+                        )));
+                    }
+                }
             }
 
+            if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+                for name in required.iter().filter_map(|r| r.as_str()) {
+                    if !variables.iter().any(|v| v == name) {
+                        errors.push(in_item(ValidationError::invalid_value(
+                            field.clone(),
+                            format!("Required property '{}' has no matching '{{{}}}' in the URI template", name, name),
+                        )));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_tools(
+        tools: &[ToolConfig],
+        default_response: &DefaultToolResponse,
+    ) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        for (idx, tool) in tools.iter().enumerate() {
+            let in_item = |e: ValidationError| e.in_item(ArchetypeType::Tool, &tool.name);
+
             // Validate required fields
             if tool.name.is_empty() {
-                errors.push(ValidationError::MissingField(
-                    format!("tools[{}].name", idx)
-                ));
+                errors.push(in_item(ValidationError::missing_field(format!("tools[{}].name", idx))));
             }
 
             if tool.description.is_empty() {
-                errors.push(ValidationError::MissingField(
-                    format!("tools[{}].description", idx)
-                ));
+                errors.push(in_item(ValidationError::missing_field(format!("tools[{}].description", idx))));
             }
 
-            // Validate that either static_response or mock is provided
+            // Validate that either static_response or mock is provided, unless a
+            // default_tool_response covers tools without one
             if tool.static_response.is_none() && tool.mock.is_none() {
-                errors.push(ValidationError::InvalidValue {
-                    field: format!("tools[{}]", idx),
-                    reason: "Either 'static_response' or 'mock' must be provided".to_string(),
-                });
+                if *default_response == DefaultToolResponse::Error {
+                    errors.push(in_item(ValidationError::invalid_value(
+                        format!("tools[{}]", idx),
+                        "Either 'static_response' or 'mock' must be provided",
+                    )));
+                } else {
+                    tracing::warn!(
+                        "tools[{}] ('{}') has no 'static_response' or 'mock' and will use default_tool_response",
+                        idx,
+                        tool.name
+                    );
+                }
+            }
+
+            if let Some(error) = check_database_params(tool.mock.as_ref(), format!("tools[{}]", idx)) {
+                errors.push(in_item(error));
+            }
+            if let Some(error) = check_callback_delay(tool.mock.as_ref(), format!("tools[{}]", idx)) {
+                errors.push(in_item(error));
+            }
+
+            match &tool.latency {
+                Some(LatencyConfig::Uniform { min_ms, max_ms }) if min_ms > max_ms => {
+                    errors.push(in_item(ValidationError::invalid_value(
+                        format!("tools[{}].latency", idx),
+                        "'min_ms' must not be greater than 'max_ms'",
+                    )));
+                }
+                Some(LatencyConfig::Profile { path, .. }) if path.is_empty() => {
+                    errors.push(in_item(ValidationError::missing_field(format!("tools[{}].latency.path", idx))));
+                }
+                _ => {}
             }
         }
 
@@ -163,27 +588,17 @@ impl ConfigValidator {
 
     fn validate_prompts(prompts: &[crate::config::PromptConfig]) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
-        let mut seen_names = HashMap::new();
 
         for (idx, prompt) in prompts.iter().enumerate() {
-            // Check for duplicate names
-            if let Some(prev_idx) = seen_names.insert(&prompt.name, idx) {
-                errors.push(ValidationError::Duplicate(
-                    format!("Prompt name '{}' appears at indices {} and {}", prompt.name, prev_idx, idx)
-                ));
-            }
+            let in_item = |e: ValidationError| e.in_item(ArchetypeType::Prompt, &prompt.name);
 
             // Validate required fields
             if prompt.name.is_empty() {
-                errors.push(ValidationError::MissingField(
-                    format!("prompts[{}].name", idx)
-                ));
+                errors.push(in_item(ValidationError::missing_field(format!("prompts[{}].name", idx))));
             }
 
             if prompt.description.is_empty() {
-                errors.push(ValidationError::MissingField(
-                    format!("prompts[{}].description", idx)
-                ));
+                errors.push(in_item(ValidationError::missing_field(format!("prompts[{}].description", idx))));
             }
         }
 
@@ -194,11 +609,56 @@ impl ConfigValidator {
         }
     }
 
-    fn validate_cross_references(_settings: &Settings) -> Result<(), Vec<ValidationError>> {
-        let errors = Vec::new();
+    /// Report item keys that appear more than once in an archetype collection,
+    /// naming the files they were loaded from when known
+    fn validate_duplicates(settings: &Settings) -> Result<(), Vec<ValidationError>> {
+        let collections: [(ArchetypeType, &str, Vec<&str>); 9] = [
+            (ArchetypeType::Resource, "Resource URI", settings.resources.iter().map(|r| r.uri.as_str()).collect()),
+            (
+                ArchetypeType::ResourceTemplate,
+                "Resource template",
+                settings.resource_templates.iter().map(|t| t.uri_template.as_str()).collect(),
+            ),
+            (ArchetypeType::Tool, "Tool name", settings.tools.iter().map(|t| t.name.as_str()).collect()),
+            (ArchetypeType::Prompt, "Prompt name", settings.prompts.iter().map(|p| p.name.as_str()).collect()),
+            (ArchetypeType::Workflow, "Workflow name", settings.workflows.iter().map(|w| w.name.as_str()).collect()),
+            (ArchetypeType::Agent, "Agent name", settings.agents.iter().map(|a| a.name.as_str()).collect()),
+            (
+                ArchetypeType::Orchestration,
+                "Orchestration name",
+                settings.orchestrations.iter().map(|o| o.name.as_str()).collect(),
+            ),
+            (ArchetypeType::Schema, "Schema name", settings.schemas.iter().map(|s| s.name.as_str()).collect()),
+            (ArchetypeType::DataLake, "Data lake name", settings.data_lakes.iter().map(|d| d.name.as_str()).collect()),
+        ];
+
+        let mut errors = Vec::new();
+        for (item_type, label, keys) in collections {
+            let mut indices: HashMap<&str, Vec<usize>> = HashMap::new();
+            for (idx, key) in keys.iter().enumerate().filter(|(_, key)| !key.is_empty()) {
+                indices.entry(*key).or_default().push(idx);
+            }
 
-        // TODO: Add cross-reference validation
-        // For example: validate that tool references to resources exist
+            // One error per key, in the order the keys first appear
+            for (idx, key) in keys.iter().enumerate() {
+                let Some(at) = indices.get(key).filter(|at| at.len() > 1 && at[0] == idx) else {
+                    continue;
+                };
+                let mut message = format!("{} '{}' appears at indices {}", label, key, join_and(at));
+                let sources = duplicate_sources(settings, item_type, key, at.len());
+                if !sources.is_empty() {
+                    message.push_str(&format!(" (in {})", join_and(&sources)));
+                }
+                let last = at[at.len() - 1];
+                errors.push(
+                    ValidationError::duplicate(
+                        format!("{}[{}].{}", item_type.section(), last, item_type.key_field()),
+                        message,
+                    )
+                    .in_item(item_type, *key),
+                );
+            }
+        }
 
         if errors.is_empty() {
             Ok(())
@@ -206,21 +666,587 @@ impl ConfigValidator {
             Err(errors)
         }
     }
+
+    fn validate_cross_references(settings: &Settings) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let mut aliases: Vec<_> = settings.tool_aliases.iter().collect();
+        aliases.sort();
+        for (alias, target) in aliases {
+            let field = format!("tool_aliases.{}", alias);
+            if is_local_tool(settings, alias) {
+                errors.push(ValidationError::invalid_value(
+                    field.clone(),
+                    format!("Alias '{}' hides the tool of the same name", alias),
+                ));
+            }
+            if settings.tool_aliases.contains_key(target) && !is_local_tool(settings, target) {
+                errors.push(ValidationError::cross_reference(
+                    field,
+                    format!("Tool alias '{}' points to alias '{}', not to a tool", alias, target),
+                ));
+            } else if !alias_target_exists(settings, target) {
+                errors.push(ValidationError::cross_reference(
+                    field,
+                    format!("Tool alias '{}' points to unknown tool '{}'", alias, target),
+                ));
+            }
+        }
+
+        let mut renamed_tools = HashSet::new();
+        for (idx, mcp_server) in settings.mcp_servers.iter().enumerate() {
+            for (list, patterns) in [("include", &mcp_server.include), ("exclude", &mcp_server.exclude)] {
+                for pattern in patterns {
+                    if let Err(e) = glob::Pattern::new(pattern) {
+                        errors.push(ValidationError::invalid_value(
+                            format!("mcp_servers[{}].{}", idx, list),
+                            format!("Invalid glob pattern '{}': {}", pattern, e),
+                        ));
+                    }
+                }
+            }
+
+            let mut renames: Vec<_> = mcp_server.rename.iter().collect();
+            renames.sort();
+            for (tool, renamed) in renames {
+                let field = format!("mcp_servers[{}].rename.{}", idx, tool);
+                if is_local_tool(settings, renamed) || settings.tool_aliases.contains_key(renamed) {
+                    errors.push(ValidationError::duplicate(
+                        field,
+                        format!(
+                            "Tool '{}' of MCP server '{}' is renamed to '{}', which is already a local tool or alias",
+                            tool, mcp_server.name, renamed
+                        ),
+                    ));
+                } else if !renamed_tools.insert(renamed) {
+                    errors.push(ValidationError::duplicate(
+                        field,
+                        format!("More than one MCP tool is renamed to '{}'", renamed),
+                    ));
+                }
+            }
+        }
+
+        for (idx, tool) in settings.tools.iter().enumerate() {
+            let Some(response) = &tool.static_response else {
+                continue;
+            };
+            if let Err(e) = crate::adapters::resource_links::check_links(response, settings) {
+                errors.push(
+                    ValidationError::cross_reference(format!("tools[{}].static_response", idx), e.to_string())
+                        .in_item(ArchetypeType::Tool, &tool.name),
+                );
+            }
+        }
+
+        for (idx, agent) in settings.agents.iter().enumerate() {
+            let mut unknown = |field: &str, message: String| {
+                errors.push(
+                    ValidationError::cross_reference(format!("agents[{}].{}", idx, field), message)
+                        .in_item(ArchetypeType::Agent, &agent.name),
+                );
+            };
+            for tool in &agent.available_tools {
+                if !agent_tool_exists(settings, tool) {
+                    unknown(
+                        "available_tools",
+                        format!("Agent '{}' references unknown tool '{}'", agent.name, tool),
+                    );
+                }
+            }
+            for name in &agent.agent_tools {
+                let name = name.strip_prefix(AGENT_TOOL_PREFIX).unwrap_or(name);
+                if !settings.agents.iter().any(|a| a.name == name) {
+                    unknown(
+                        "agent_tools",
+                        format!("Agent '{}' references unknown agent '{}'", agent.name, name),
+                    );
+                }
+            }
+            for tool in &agent.mcp_tools {
+                let server = tool.split_once(':').map_or(tool.as_str(), |(server, _)| server);
+                if !settings.mcp_servers.iter().any(|m| m.name == server) {
+                    unknown(
+                        "mcp_tools",
+                        format!("Agent '{}' references unknown MCP server '{}'", agent.name, server),
+                    );
+                }
+            }
+        }
+
+        let mut databases: Vec<_> = settings.databases.iter().collect();
+        databases.sort_by_key(|(name, _)| *name);
+        for (name, database) in databases {
+            if database.url.is_empty() {
+                errors.push(ValidationError::missing_field(format!("databases.{}.url", name)));
+            }
+        }
+
+        for (path, item_type, item, database) in database_mocks(settings) {
+            match &database.connection {
+                Some(name) if !settings.databases.contains_key(name) => {
+                    errors.push(
+                        ValidationError::cross_reference(
+                            format!("{}.mock.database.connection", path),
+                            format!("Database connection '{}' is not defined in [databases]", name),
+                        )
+                        .in_item(item_type, item),
+                    );
+                }
+                None if database.url.is_empty() && database.db_type != DatabaseType::DataFusion => {
+                    errors.push(
+                        ValidationError::invalid_value(
+                            format!("{}.mock.database", path),
+                            "Either 'url' or 'connection' must be provided",
+                        )
+                        .in_item(item_type, item),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Database mocks of the tools, resources and resource templates, with the
+/// path, type and key of the item they belong to
+fn database_mocks(settings: &Settings) -> Vec<(String, ArchetypeType, &str, &DatabaseConfig)> {
+    fn database(mock: &Option<MockConfig>) -> Option<&DatabaseConfig> {
+        mock.as_ref().and_then(|m| m.database.as_ref())
+    }
+    let mut mocks = Vec::new();
+
+    for (idx, tool) in settings.tools.iter().enumerate() {
+        if let Some(db) = database(&tool.mock) {
+            mocks.push((format!("tools[{}]", idx), ArchetypeType::Tool, tool.name.as_str(), db));
+        }
+    }
+    for (idx, resource) in settings.resources.iter().enumerate() {
+        if let Some(db) = database(&resource.mock) {
+            mocks.push((format!("resources[{}]", idx), ArchetypeType::Resource, resource.uri.as_str(), db));
+        }
+    }
+    for (idx, template) in settings.resource_templates.iter().enumerate() {
+        if let Some(db) = database(&template.mock) {
+            let path = format!("resource_templates[{}]", idx);
+            mocks.push((path, ArchetypeType::ResourceTemplate, template.uri_template.as_str(), db));
+        }
+    }
+    mocks
+}
+
+/// First regex operator in `pattern` that the `pattern` syntax would emit as a
+/// literal, outside escapes and `[..]` classes
+fn unsupported_pattern_operator(pattern: &str) -> Option<char> {
+    let mut chars = pattern.chars();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            '+' | '*' | '?' | '|' | '(' | ')' if !in_class => return Some(c),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Error for an agent whose custom LLM provider isn't registered
+fn unknown_llm_provider(agent: &AgentConfig, field: String, providers: &ProviderRegistry) -> Option<ValidationError> {
+    let LlmProviderType::Custom(name) = &agent.llm.provider else {
+        return None;
+    };
+    if providers.contains(name) {
+        return None;
+    }
+    let mut known: Vec<String> = ["openai", "anthropic", "gemini", "ollama", "azure"].map(String::from).to_vec();
+    known.extend(providers.names());
+    Some(
+        ValidationError::invalid_value(
+            field,
+            format!("Unknown LLM provider '{}'; expected one of {}", name, known.join(", ")),
+        )
+        .in_item(ArchetypeType::Agent, &agent.name),
+    )
+}
+
+/// Whether `name` is a tool or workflow defined in the config
+fn is_local_tool(settings: &Settings, name: &str) -> bool {
+    settings.tools.iter().any(|t| t.name == name) || settings.workflows.iter().any(|w| w.name == name)
+}
+
+/// Whether a `tool_aliases` target can be called: a local tool or workflow, or
+/// a `server/tool` (or `mcp__server_tool`) tool of a configured MCP server.
+/// The tools of an MCP server are only known once it is connected, so any
+/// tool name on a configured server is accepted.
+fn alias_target_exists(settings: &Settings, target: &str) -> bool {
+    let is_mcp_server = |server: &str| settings.mcp_servers.iter().any(|m| m.name == server);
+
+    if let Some((server, tool)) = target.split_once('/') {
+        return !tool.is_empty() && is_mcp_server(server);
+    }
+    if let Some(rest) = target.strip_prefix(crate::adapters::mcp_client::MCP_TOOL_PREFIX) {
+        return rest.split_once('_').is_some_and(|(server, tool)| !tool.is_empty() && is_mcp_server(server));
+    }
+    is_local_tool(settings, target)
+}
+
+/// Whether an agent's `available_tools` entry can be called: a tool, workflow
+/// or alias, a tool of a configured MCP server (possibly renamed), or another
+/// agent under its `agent_` tool name
+fn agent_tool_exists(settings: &Settings, name: &str) -> bool {
+    if let Some(agent) = name.strip_prefix(AGENT_TOOL_PREFIX) {
+        if settings.agents.iter().any(|a| a.name == agent) {
+            return true;
+        }
+    }
+    settings.tool_aliases.contains_key(name)
+        || settings.mcp_servers.iter().any(|m| m.rename.values().any(|renamed| renamed == name))
+        || alias_target_exists(settings, name)
+}
+
+/// Where each of the `count` definitions of an item came from. Definitions
+/// without a recorded file precede the directory loads, so they come from the
+/// config file itself. Empty if no file was recorded.
+fn duplicate_sources(settings: &Settings, item_type: ArchetypeType, key: &str, count: usize) -> Vec<String> {
+    let Some(files) = settings.sources.get(&(item_type, key.to_string())) else {
+        return Vec::new();
+    };
+    let config_file = settings
+        .config_path
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "the config file".to_string());
+    let unrecorded = count.saturating_sub(files.len());
+
+    std::iter::repeat_n(config_file, unrecorded)
+        .chain(files.iter().map(|f| f.display().to_string()))
+        .take(count)
+        .collect()
+}
+
+/// Join items as "a, b and c"
+fn join_and<T: std::fmt::Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => items.join(""),
+    }
+}
+
+/// Deserialize an item into its config type, reporting missing or mistyped fields
+fn parse_item<T: DeserializeOwned>(section: &str, item: &Value) -> Result<T, Vec<ValidationError>> {
+    serde_json::from_value(item.clone()).map_err(|e| {
+        let message = e.to_string();
+        match message.strip_prefix("missing field `").and_then(|rest| rest.split('`').next()) {
+            Some(field) => vec![ValidationError::missing_field(format!("{}.{}", section, field))],
+            None => vec![ValidationError::invalid_value(section, message)],
+        }
+    })
+}
+
+/// Report each of the named text fields that is empty
+fn require_text(section: &str, fields: &[(&str, &str)]) -> Result<(), Vec<ValidationError>> {
+    let errors: Vec<ValidationError> = fields
+        .iter()
+        .filter(|(_, value)| value.is_empty())
+        .map(|(name, _)| ValidationError::missing_field(format!("{}.{}", section, name)))
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check the structure of a JSON Schema: keyword values must have the types the
+/// spec requires, recursing into subschemas
+fn check_schema_syntax(schema: &Value, path: &str, errors: &mut Vec<ValidationError>) {
+    let obj = match schema {
+        // `true` and `false` are valid schemas
+        Value::Bool(_) => return,
+        Value::Object(obj) => obj,
+        _ => {
+            errors.push(ValidationError::invalid_value(path, "Schema must be an object or a boolean"));
+            return;
+        }
+    };
+    let mut invalid = |keyword: &str, reason: &str| {
+        errors.push(ValidationError::invalid_value(format!("{}.{}", path, keyword), reason));
+    };
+
+    if let Some(schema_type) = obj.get("type") {
+        let valid = match schema_type {
+            Value::String(t) => SCHEMA_TYPES.contains(&t.as_str()),
+            Value::Array(types) => types
+                .iter()
+                .all(|t| t.as_str().is_some_and(|t| SCHEMA_TYPES.contains(&t))),
+            _ => false,
+        };
+        if !valid {
+            invalid("type", "Must be one of object, array, string, number, integer, boolean or null");
+        }
+    }
+    if obj.get("required").is_some_and(|r| !r.as_array().is_some_and(|r| r.iter().all(|v| v.is_string()))) {
+        invalid("required", "Must be an array of property names");
+    }
+    if obj.get("enum").is_some_and(|e| !e.is_array()) {
+        invalid("enum", "Must be an array");
+    }
+    if obj.get("$ref").is_some_and(|r| !r.is_string()) {
+        invalid("$ref", "Must be a string");
+    }
+
+    // Keywords whose value is a map of subschemas
+    for keyword in ["properties", "patternProperties", "definitions", "$defs"] {
+        match obj.get(keyword) {
+            None => {}
+            Some(Value::Object(subschemas)) => {
+                for (name, subschema) in subschemas {
+                    check_schema_syntax(subschema, &format!("{}.{}.{}", path, keyword, name), errors);
+                }
+            }
+            Some(_) => errors.push(ValidationError::invalid_value(
+                format!("{}.{}", path, keyword),
+                "Must be an object of schemas",
+            )),
+        }
+    }
+
+    // Keywords whose value is a list of subschemas
+    for keyword in ["allOf", "anyOf", "oneOf", "prefixItems"] {
+        match obj.get(keyword) {
+            None => {}
+            Some(Value::Array(subschemas)) => {
+                for (idx, subschema) in subschemas.iter().enumerate() {
+                    check_schema_syntax(subschema, &format!("{}.{}[{}]", path, keyword, idx), errors);
+                }
+            }
+            Some(_) => errors.push(ValidationError::invalid_value(
+                format!("{}.{}", path, keyword),
+                "Must be an array of schemas",
+            )),
+        }
+    }
+
+    // Keywords whose value is a single subschema (`items` may also be a list)
+    for keyword in ["items", "additionalProperties", "not", "contains", "if", "then", "else"] {
+        match obj.get(keyword) {
+            Some(Value::Array(subschemas)) if keyword == "items" => {
+                for (idx, subschema) in subschemas.iter().enumerate() {
+                    check_schema_syntax(subschema, &format!("{}.items[{}]", path, idx), errors);
+                }
+            }
+            Some(subschema) => check_schema_syntax(subschema, &format!("{}.{}", path, keyword), errors),
+            None => {}
+        }
+    }
+}
+
+/// Find a `$ref` that can never resolve because it loops back on itself: a
+/// chain of references, local `#/...` ones or the schema's own name, that
+/// ends where it started without reaching a schema with anything but a
+/// `$ref`. Recursion through such a schema, like a tree node whose children
+/// reference the node, is fine. Returns a description of the loop.
+fn find_ref_loop(schema: &Value, own_name: Option<&str>) -> Option<String> {
+    let mut refs = Vec::new();
+    collect_refs(schema, &mut refs);
+
+    // The part of `schema` a reference points at; other named schemas are
+    // checked on their own
+    let resolve = |reference: &str| {
+        if reference == "#" || own_name == Some(reference) {
+            Some(schema)
+        } else if let Some(pointer) = reference.strip_prefix('#') {
+            schema.pointer(pointer)
+        } else {
+            None
+        }
+    };
+
+    for reference in refs {
+        // Follow the chain for as long as each target is nothing but another reference
+        let mut seen = HashSet::new();
+        let mut current = reference;
+        loop {
+            if !seen.insert(current) {
+                return Some(format!("'$ref: {}' resolves to itself", reference));
+            }
+            match resolve(current).and_then(bare_ref) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+    }
+    None
+}
+
+/// The reference of a schema that consists of only a `$ref`
+fn bare_ref(schema: &Value) -> Option<&str> {
+    let obj = schema.as_object()?;
+    if obj.len() != 1 {
+        return None;
+    }
+    obj.get("$ref")?.as_str()
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(obj) => {
+            for (key, child) in obj {
+                match (key.as_str(), child) {
+                    ("$ref", Value::String(reference)) => refs.push(reference),
+                    _ => collect_refs(child, refs),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_refs(item, refs)),
+        _ => {}
+    }
+}
+
+/// Database mocks must configure one param per query placeholder, since every
+/// param is bound rather than spliced into the SQL
+fn check_database_params(mock: Option<&MockConfig>, path: String) -> Option<ValidationError> {
+    let database = mock?.database.as_ref()?;
+    database
+        .check_params()
+        .err()
+        .map(|reason| ValidationError::invalid_value(format!("{}.mock.database.params", path), reason))
+}
+
+/// Callback mocks must be deliverable at a time a timestamp can hold
+fn check_callback_delay(mock: Option<&MockConfig>, path: String) -> Option<ValidationError> {
+    let callback = mock?.callback.as_ref()?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let delay = std::time::Duration::from_millis(callback.delay_ms);
+    match crate::adapters::callback_tracker::deliver_at(now, delay) {
+        Some(_) => None,
+        None => Some(ValidationError::invalid_value(
+            format!("{}.mock.callback.delay_ms", path),
+            format!("Delay of {} ms is too long", callback.delay_ms),
+        )),
+    }
+}
+
+/// Check that static resource content can be served as the declared MIME type
+fn check_content_matches_mime(mime_type: Option<&str>, content: &str) -> Result<(), String> {
+    match ResourceContentKind::from_mime_type(mime_type) {
+        ResourceContentKind::Json => serde_json::from_str::<serde_json::Value>(content)
+            .map(|_| ())
+            .map_err(|e| format!("Content is not valid JSON: {}", e)),
+        ResourceContentKind::Csv => {
+            let mut counts = content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(csv_field_count);
+            let expected = counts.next().unwrap_or_default();
+            match counts.position(|count| count != expected) {
+                Some(row) => Err(format!(
+                    "CSV row {} has a different number of fields than the header ({})",
+                    row + 2,
+                    expected
+                )),
+                None => Ok(()),
+            }
+        }
+        ResourceContentKind::Binary | ResourceContentKind::Text => Ok(()),
+    }
+}
+
+/// Count the fields of a single CSV line, honouring double-quoted fields
+fn csv_field_count(line: &str) -> usize {
+    let mut in_quotes = false;
+    let mut count = 1;
+    for c in line.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Collect the `{variable}` names of a URI template, rejecting malformed placeholders
+fn template_variables(template: &str) -> Result<Vec<String>, String> {
+    let mut variables: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| "Unclosed '{' in URI template".to_string())?;
+        let name = &after[..end];
+
+        if name.is_empty() || name.contains('{') {
+            return Err(format!("Invalid placeholder '{{{}}}' in URI template", name));
+        }
+        if variables.iter().any(|v| v == name) {
+            return Err(format!("Template variable '{{{}}}' is used more than once", name));
+        }
+
+        variables.push(name.to_string());
+        rest = &after[end + 1..];
+    }
+
+    Ok(variables)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{ServerSettings, Settings};
+    use crate::config::{LogFormat, ServerSettings, Settings};
 
     #[test]
     fn test_valid_config() {
         let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![],
@@ -228,6 +1254,7 @@ mod tests {
             tools: vec![],
             prompts: vec![],
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -237,6 +1264,8 @@ mod tests {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         };
 
@@ -248,10 +1277,37 @@ mod tests {
     fn test_invalid_port() {
         let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 0,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![],
@@ -259,6 +1315,7 @@ mod tests {
             tools: vec![],
             prompts: vec![],
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -268,6 +1325,8 @@ mod tests {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         };
 
@@ -277,14 +1336,88 @@ mod tests {
         assert_eq!(errors.len(), 1);
     }
 
+    #[test]
+    fn test_invalid_access_control_cidr() {
+        let config = crate::config::AccessControlConfig {
+            allow: vec!["10.0.0.0/8".to_string(), "10.0.0.0/40".to_string()],
+            deny: vec!["not-an-ip".to_string()],
+            trusted_proxies: vec!["127.0.0.1".to_string()],
+        };
+
+        let errors = ConfigValidator::validate_access_control(&config).unwrap_err();
+        let fields: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 2);
+        assert!(fields[0].contains("access_control.allow[1]"));
+        assert!(fields[1].contains("access_control.deny[0]"));
+    }
+
+    #[test]
+    fn test_gcra_rate_limit_has_no_burst() {
+        let settings = |algorithm: &str, burst_size: u32| -> Settings {
+            serde_json::from_value(serde_json::json!({
+                "server": {"host": "127.0.0.1", "port": 3000},
+                "rate_limit": {"enabled": true, "requests_per_second": 10, "burst_size": burst_size, "algorithm": algorithm}
+            }))
+            .unwrap()
+        };
+
+        assert!(ConfigValidator::validate(&settings("gcra", 1)).is_ok());
+        assert!(ConfigValidator::validate(&settings("sliding_window", 20)).is_ok());
+        let errors = ConfigValidator::validate(&settings("gcra", 20)).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field.as_deref(), Some("rate_limit.burst_size"));
+    }
+
+    #[test]
+    fn test_negative_model_price() {
+        let pricing: Vec<crate::config::ModelPricing> = serde_json::from_value(serde_json::json!([
+            {"provider": "openai", "model": "gpt-4o", "price_per_token": 0.0000025},
+            {"provider": "anthropic", "model": "claude", "price_per_token": -1.0},
+            {"provider": "gemini", "model": "flash", "input_price_per_token": 0.0000001, "output_price_per_token": -0.1}
+        ]))
+        .unwrap();
+
+        let errors = ConfigValidator::validate_pricing(&pricing).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].to_string().contains("pricing[1].price_per_token"));
+        assert!(errors[1].to_string().contains("pricing[2].output_price_per_token"));
+    }
+
     #[test]
     fn test_duplicate_resource_uris() {
         let settings = Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: crate::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![
@@ -313,6 +1446,7 @@ mod tests {
             tools: vec![],
             prompts: vec![],
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -322,12 +1456,435 @@ mod tests {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         };
 
         let result = ConfigValidator::validate(&settings);
         assert!(result.is_err());
         let errors = result.unwrap_err();
-        assert!(errors.iter().any(|e| matches!(e, ValidationError::Duplicate(_))));
+        let duplicate = errors.iter().find(|e| e.kind == ValidationErrorKind::Duplicate).unwrap();
+        assert_eq!(duplicate.archetype, Some(ArchetypeType::Resource));
+        assert_eq!(duplicate.item.as_deref(), Some("test://same"));
+        assert_eq!(duplicate.field.as_deref(), Some("resources[1].uri"));
+        assert_eq!(
+            duplicate.to_string(),
+            "Duplicate entry: Resource URI 'test://same' appears at indices 0 and 1"
+        );
+    }
+
+    fn settings_with_template(uri_template: &str, input_schema: serde_json::Value) -> Settings {
+        serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "resource_templates": [{
+                "uri_template": uri_template,
+                "name": "Template",
+                "description": null,
+                "mime_type": null,
+                "input_schema": input_schema,
+                "content": "content"
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resource_template_matches_input_schema() {
+        let settings = settings_with_template(
+            "db://{schema}/{table}",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"schema": {"type": "string"}, "table": {"type": "string"}},
+                "required": ["table"]
+            }),
+        );
+        assert!(ConfigValidator::validate(&settings).is_ok());
+    }
+
+    #[test]
+    fn test_resource_template_schema_mismatch() {
+        let settings = settings_with_template(
+            "db://{schema}/{table}",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"table": {"type": "string"}, "limit": {"type": "integer"}},
+                "required": ["limit"]
+            }),
+        );
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.to_string().contains("'{schema}' is not declared")));
+        assert!(errors.iter().any(|e| e.to_string().contains("Required property 'limit'")));
+    }
+
+    #[test]
+    fn test_malformed_resource_template() {
+        assert!(template_variables("db://{schema").is_err());
+        assert!(template_variables("db://{}/x").is_err());
+        assert!(template_variables("db://{id}/{id}").is_err());
+        assert_eq!(template_variables("db://{a}/{b}").unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_resource_content_matches_mime_type() {
+        assert!(check_content_matches_mime(Some("application/json"), "{\"a\": 1}").is_ok());
+        assert!(check_content_matches_mime(Some("application/json"), "{a: 1}").is_err());
+        assert!(check_content_matches_mime(Some("text/csv"), "id,name\n1,\"Ada, L.\"\n").is_ok());
+        assert!(check_content_matches_mime(Some("text/csv"), "id,name\n1\n").is_err());
+        assert!(check_content_matches_mime(Some("text/plain"), "{a: 1}").is_ok());
+    }
+
+    #[test]
+    fn test_callback_delay_must_fit_a_timestamp() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [{
+                "name": "job", "description": "Job", "input_schema": {}, "static_response": null,
+                "mock": {"strategy": "callback", "callback": {"url": "http://localhost/hook", "delay_ms": u64::MAX}}
+            }]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field.as_deref(), Some("tools[0].mock.callback.delay_ms"));
+    }
+
+    #[test]
+    fn test_tool_alias_targets_must_exist() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {}}],
+            "mcp_servers": [{"name": "remote", "url": "http://localhost:9000/mcp"}],
+            "tool_aliases": {
+                "forecast": "weather",
+                "outlook": "forecast",
+                "lookup": "remote/lookup",
+                "search": "mcp__remote_search",
+                "missing": "nowhere",
+                "offline": "other/lookup",
+                "weather": "remote/weather"
+            }
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Cross-reference error: Tool alias 'missing' points to unknown tool 'nowhere'",
+                "Cross-reference error: Tool alias 'offline' points to unknown tool 'other/lookup'",
+                "Cross-reference error: Tool alias 'outlook' points to alias 'forecast', not to a tool",
+                "Invalid value for tool_aliases.weather: Alias 'weather' hides the tool of the same name",
+            ]
+        );
+        assert_eq!(errors[0].field.as_deref(), Some("tool_aliases.missing"));
+    }
+
+    #[test]
+    fn test_custom_faker_patterns_reject_regex_operators() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "custom_fakers": {
+                "sku": "SKU-[A-Z+]{3}-\\d{4}\\?",
+                "code": "[A-Z]+",
+                "either": "(a|b)",
+                "invoice": {"template": "INV-####-??"}
+            }
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_deref().unwrap()).collect();
+        assert_eq!(fields, ["custom_fakers.code", "custom_fakers.either"]);
+        assert!(errors[0].message.starts_with("Pattern uses '+'"));
+        assert!(errors[1].message.starts_with("Pattern uses '('"));
+    }
+
+    #[test]
+    fn test_agent_llm_providers_must_be_known() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "agents": [
+                {"name": "typo", "description": "T", "agent_type": "react", "llm": {"provider": "opnai", "model": "gpt-4o"}, "system_prompt": "You help."},
+                {"name": "builtin", "description": "B", "agent_type": "react", "llm": {"provider": "openai", "model": "gpt-4o"}, "system_prompt": "You help."},
+                {"name": "house", "description": "H", "agent_type": "react", "llm": {"provider": "gateway", "model": "m"}, "system_prompt": "You help."}
+            ]
+        }))
+        .unwrap();
+
+        let providers = ProviderRegistry::new();
+        let errors = ConfigValidator::validate_llm_providers(&settings, &providers).unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_deref().unwrap()).collect();
+        assert_eq!(fields, ["agents[0].llm.provider", "agents[2].llm.provider"]);
+        assert_eq!(
+            errors[0].message,
+            "Unknown LLM provider 'opnai'; expected one of openai, anthropic, gemini, ollama, azure"
+        );
+        assert_eq!(errors[0].item.as_deref(), Some("typo"));
+
+        providers.register_provider("gateway", |_| Err(crate::agents::error::LlmError::InvalidRequest("unused".to_string())));
+        let errors = ConfigValidator::validate_llm_providers(&settings, &providers).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.ends_with("azure, gateway"));
+        assert!(ConfigValidator::validate_agent_llm_provider(&settings.agents[2], &providers).is_ok());
+    }
+
+    #[test]
+    fn test_agent_tools_must_exist() {
+        let agent = |name: &str, tools: serde_json::Value| {
+            let mut agent = serde_json::json!({
+                "name": name, "description": name, "agent_type": "react",
+                "llm": {"provider": "ollama", "model": "llama3"}, "system_prompt": "You help."
+            });
+            agent.as_object_mut().unwrap().extend(tools.as_object().unwrap().clone());
+            agent
+        };
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {}}],
+            "mcp_servers": [{"name": "remote", "url": "http://localhost:9000/mcp"}],
+            "agents": [
+                agent("planner", serde_json::json!({
+                    "available_tools": ["weather", "agent_researcher", "remote/search"],
+                    "agent_tools": ["researcher"],
+                    "mcp_tools": ["remote:*"]
+                })),
+                agent("researcher", serde_json::json!({}))
+            ]
+        }))
+        .unwrap();
+        assert!(ConfigValidator::validate(&settings).is_ok());
+
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {}}],
+            "agents": [agent("planner", serde_json::json!({
+                "available_tools": ["weather", "forecast", "agent_writer"],
+                "agent_tools": ["agent_editor"],
+                "mcp_tools": ["offline:search"]
+            }))]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Cross-reference error: Agent 'planner' references unknown tool 'forecast'",
+                "Cross-reference error: Agent 'planner' references unknown tool 'agent_writer'",
+                "Cross-reference error: Agent 'planner' references unknown agent 'editor'",
+                "Cross-reference error: Agent 'planner' references unknown MCP server 'offline'",
+            ]
+        );
+        assert_eq!(errors[0].field.as_deref(), Some("agents[0].available_tools"));
+        assert_eq!(errors[0].item.as_deref(), Some("planner"));
+    }
+
+    #[test]
+    fn test_static_resource_links_must_resolve() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "resources": [{"uri": "file:///report.md", "name": "Report", "content": "# Report"}],
+            "tools": [
+                {"name": "latest", "description": "Latest report", "input_schema": {},
+                 "static_response": {"content": [{"type": "resource_link", "uri": "file:///report.md", "name": "Report"}]}},
+                {"name": "stale", "description": "Old report", "input_schema": {},
+                 "static_response": {"content": [{"type": "resource_link", "uri": "file:///old.md", "name": "Old"}]}}
+            ]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field.as_deref(), Some("tools[1].static_response"));
+        assert!(errors[0].message.contains("file:///old.md"));
+    }
+
+    #[test]
+    fn test_tool_latency_must_be_well_formed() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [
+                {"name": "a", "description": "A", "input_schema": {}, "static_response": {},
+                 "latency": {"mode": "uniform", "min_ms": 500, "max_ms": 100}},
+                {"name": "b", "description": "B", "input_schema": {}, "static_response": {},
+                 "latency": {"mode": "profile", "path": ""}},
+                {"name": "c", "description": "C", "input_schema": {}, "static_response": {},
+                 "latency": {"mode": "fixed", "ms": 20}}
+            ]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_deref().unwrap()).collect();
+        assert_eq!(fields, ["tools[0].latency", "tools[1].latency.path"]);
+    }
+
+    #[test]
+    fn test_mcp_server_renames_must_not_collide() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [{"name": "weather", "description": "Weather", "input_schema": {}, "static_response": {}}],
+            "mcp_servers": [
+                {"name": "a", "url": "http://localhost:9000/mcp", "include": ["get_[*"], "rename": {"forecast": "weather", "lookup": "find"}},
+                {"name": "b", "url": "http://localhost:9001/mcp", "rename": {"search": "find", "other": "b_other"}}
+            ]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_deref().unwrap()).collect();
+        assert_eq!(fields, ["mcp_servers[0].include", "mcp_servers[0].rename.forecast", "mcp_servers[1].rename.search"]);
+        assert_eq!(
+            errors[1].to_string(),
+            "Duplicate entry: Tool 'forecast' of MCP server 'a' is renamed to 'weather', which is already a local tool or alias"
+        );
+        assert_eq!(errors[2].to_string(), "Duplicate entry: More than one MCP tool is renamed to 'find'");
+    }
+
+    #[test]
+    fn test_validate_partial_required_fields() {
+        let tool = serde_json::json!({"name": "t", "description": "", "input_schema": {}});
+        let errors = ConfigValidator::validate_partial(ArchetypeType::Tool, &tool).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "Missing required field: tools.description");
+        assert_eq!(errors[0].archetype, Some(ArchetypeType::Tool));
+        assert_eq!(errors[0].item.as_deref(), Some("t"));
+
+        let missing = serde_json::json!({"name": "t"});
+        let errors = ConfigValidator::validate_partial(ArchetypeType::Tool, &missing).unwrap_err();
+        assert_eq!(errors[0].to_string(), "Missing required field: tools.description");
+
+        let valid = serde_json::json!({"name": "t", "description": "d", "input_schema": {"type": "object"}});
+        assert!(ConfigValidator::validate_partial(ArchetypeType::Tool, &valid).is_ok());
+    }
+
+    #[test]
+    fn test_validate_partial_schema_syntax() {
+        let tool = serde_json::json!({
+            "name": "t",
+            "description": "d",
+            "input_schema": {
+                "type": "object",
+                "properties": {"a": {"type": "strng"}, "b": {"items": 5}},
+                "required": "a"
+            }
+        });
+        let errors = ConfigValidator::validate_partial(ArchetypeType::Tool, &tool).unwrap_err();
+        let fields: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 3, "{:?}", fields);
+        assert!(fields.iter().any(|f| f.contains("tools.input_schema.properties.a.type")));
+        assert!(fields.iter().any(|f| f.contains("tools.input_schema.properties.b.items")));
+        assert!(fields.iter().any(|f| f.contains("tools.input_schema.required")));
+    }
+
+    #[test]
+    fn test_validate_partial_ref_loops() {
+        let self_named = serde_json::json!({"name": "Node", "schema": {"$ref": "Node"}});
+        let errors = ConfigValidator::validate_partial(ArchetypeType::Schema, &self_named).unwrap_err();
+        assert!(errors[0].to_string().contains("resolves to itself"));
+
+        // A tree node whose children are nodes again
+        let tree_node = serde_json::json!({
+            "name": "Node",
+            "schema": {
+                "type": "object",
+                "properties": {"children": {"type": "array", "items": {"$ref": "Node"}}}
+            }
+        });
+        assert!(ConfigValidator::validate_partial(ArchetypeType::Schema, &tree_node).is_ok());
+
+        let local_loop = serde_json::json!({
+            "name": "t",
+            "description": "d",
+            "input_schema": {
+                "$defs": {"a": {"$ref": "#/$defs/b"}, "b": {"$ref": "#/$defs/a"}},
+                "properties": {"x": {"$ref": "#/$defs/a"}}
+            }
+        });
+        let errors = ConfigValidator::validate_partial(ArchetypeType::Tool, &local_loop).unwrap_err();
+        assert!(errors[0].to_string().contains("resolves to itself"));
+
+        // Recursion through a real schema is fine
+        let tree = serde_json::json!({
+            "name": "t",
+            "description": "d",
+            "input_schema": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#"}}}}
+        });
+        assert!(ConfigValidator::validate_partial(ArchetypeType::Tool, &tree).is_ok());
+    }
+
+    #[test]
+    fn test_database_params_must_match_placeholders() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "tools": [
+                {
+                    "name": "lookup", "description": "Lookup", "input_schema": {},
+                    "mock": {"strategy": "database", "database": {
+                        "url": "sqlite::memory:",
+                        "query": "SELECT * FROM users WHERE id = ? AND name = ?",
+                        "params": ["id"]
+                    }}
+                },
+                {
+                    "name": "search", "description": "Search", "input_schema": {},
+                    "mock": {"strategy": "database", "database": {
+                        "url": "postgres://localhost/db",
+                        "query": "SELECT * FROM users WHERE name = $1 OR nick = $1 -- and age = $2",
+                        "params": ["name"],
+                        "db_type": "postgres"
+                    }}
+                }
+            ]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "Invalid value for tools[0].mock.database.params: query has 2 placeholder(s) but 1 param(s) are configured"
+        );
+        assert_eq!(errors[0].item.as_deref(), Some("lookup"));
+    }
+
+    #[test]
+    fn test_database_connections_must_exist() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "server": {"host": "127.0.0.1", "port": 3000},
+            "databases": {"orders": {"url": "sqlite://orders.db"}, "broken": {"url": ""}},
+            "tools": [
+                {
+                    "name": "order", "description": "Order", "input_schema": {},
+                    "mock": {"strategy": "database", "database": {"connection": "orders", "query": "SELECT 1"}}
+                },
+                {
+                    "name": "invoice", "description": "Invoice", "input_schema": {},
+                    "mock": {"strategy": "database", "database": {"connection": "billing", "query": "SELECT 1"}}
+                },
+                {
+                    "name": "nowhere", "description": "Nowhere", "input_schema": {},
+                    "mock": {"strategy": "database", "database": {"query": "SELECT 1"}}
+                }
+            ]
+        }))
+        .unwrap();
+
+        let errors = ConfigValidator::validate(&settings).unwrap_err();
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Missing required field: databases.broken.url",
+                "Cross-reference error: Database connection 'billing' is not defined in [databases]",
+                "Invalid value for tools[2].mock.database: Either 'url' or 'connection' must be provided",
+            ]
+        );
+        assert_eq!(errors[1].item.as_deref(), Some("invoice"));
     }
 }
\ No newline at end of file
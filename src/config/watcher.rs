@@ -1,7 +1,9 @@
 use anyhow::Result;
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
+use std::sync::Mutex;
 use std::time::Duration;
 use tracing::{error, info};
 
@@ -52,3 +54,78 @@ impl ConfigWatcher {
         Ok(Self { _watcher: watcher })
     }
 }
+
+/// Watches the directories of File-strategy fixtures. Unlike `ConfigWatcher`,
+/// the set of watched directories can change while running, as config reloads
+/// add or remove File strategies.
+pub struct FixtureWatcher {
+    watcher: Mutex<RecommendedWatcher>,
+    watched: Mutex<HashSet<PathBuf>>,
+}
+
+impl FixtureWatcher {
+    /// Create a watcher that calls `on_change` with the paths of each change
+    pub fn new<F>(on_change: F) -> Result<Self>
+    where
+        F: Fn(&[PathBuf]) + Send + Sync + 'static,
+    {
+        let (tx, rx) = channel::<notify::Result<notify::Event>>();
+        let watcher = RecommendedWatcher::new(tx, Config::default())?;
+
+        std::thread::spawn(move || loop {
+            match rx.recv() {
+                Ok(Ok(event)) => on_change(&event.paths),
+                Ok(Err(e)) => error!("Fixture watch error: {:?}", e),
+                Err(_) => break,
+            }
+        });
+
+        Ok(Self {
+            watcher: Mutex::new(watcher),
+            watched: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Watch exactly `dirs`: start watching new ones and stop watching the rest
+    pub fn watch_dirs(&self, dirs: Vec<PathBuf>) {
+        let dirs: HashSet<PathBuf> = dirs
+            .into_iter()
+            .filter_map(|dir| match std::fs::canonicalize(&dir) {
+                Ok(dir) => Some(dir),
+                Err(_) => {
+                    tracing::warn!("Fixture directory does not exist, skipping: {}", dir.display());
+                    None
+                }
+            })
+            .collect();
+
+        let mut watcher = self.watcher.lock().unwrap_or_else(|e| e.into_inner());
+        let mut watched = self.watched.lock().unwrap_or_else(|e| e.into_inner());
+
+        for dir in watched.difference(&dirs) {
+            if let Err(e) = watcher.unwatch(dir) {
+                tracing::warn!("Failed to stop watching fixture directory {}: {}", dir.display(), e);
+            }
+        }
+        watched.retain(|dir| dirs.contains(dir));
+
+        for dir in dirs {
+            if watched.contains(&dir) {
+                continue;
+            }
+            match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    info!("Watching fixture directory: {}", dir.display());
+                    watched.insert(dir);
+                }
+                Err(e) => tracing::warn!("Failed to watch fixture directory {}: {}", dir.display(), e),
+            }
+        }
+    }
+
+    /// Directories currently watched
+    pub fn watched_dirs(&self) -> Vec<PathBuf> {
+        let watched = self.watched.lock().unwrap_or_else(|e| e.into_inner());
+        watched.iter().cloned().collect()
+    }
+}
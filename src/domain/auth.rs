@@ -1,6 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum AuthMode {
     None,
     ApiKey,
@@ -10,7 +13,7 @@ pub enum AuthMode {
     Custom,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AuthConfig {
     pub enabled: bool,
     pub mode: AuthMode,
@@ -19,6 +22,67 @@ pub struct AuthConfig {
     pub jwt_algorithm: Option<String>,
     pub basic_users: Option<std::collections::HashMap<String, String>>,
     pub jwks_url: Option<String>,
+    /// How often JWKS keys are refetched, in seconds (default 900)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwks_refresh_secs: Option<u64>,
+    /// Claim requirements checked after a JWT (BearerToken or OAuth2) is validated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claim_rules: Vec<ClaimRule>,
+    /// Let unauthenticated clients read (GET under `/api`, MCP list/read
+    /// requests) while writes still require credentials
+    #[serde(default)]
+    pub anonymous_read: bool,
+    /// Serve `/metrics` without credentials, for Prometheus scrapers. Anyone
+    /// who can reach the server can then read the metrics.
+    #[serde(default)]
+    pub public_metrics: bool,
+    /// Bearer token `/metrics` requires instead of the regular credentials,
+    /// so scrapers don't need an API key that also opens the rest of the API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_token: Option<String>,
+}
+
+/// Claims a token must carry to access matching requests
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClaimRule {
+    /// HTTP methods the rule applies to; all methods if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    /// Path prefix the rule applies to; all paths if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    /// Required claims with their allowed values. A claim holding an array
+    /// matches if any of its elements is allowed.
+    pub claims: HashMap<String, Vec<String>>,
+}
+
+impl ClaimRule {
+    /// Whether the rule applies to a request
+    pub fn applies_to(&self, method: &str, path: &str) -> bool {
+        let method_matches = self.methods.is_empty()
+            || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method));
+        let path_matches = self
+            .path_prefix
+            .as_ref()
+            .is_none_or(|prefix| path.starts_with(prefix.as_str()));
+        method_matches && path_matches
+    }
+
+    /// Whether every required claim is present with an allowed value
+    pub fn is_satisfied_by(&self, claims: &HashMap<String, Value>) -> bool {
+        self.claims.iter().all(|(name, allowed)| {
+            claims.get(name).is_some_and(|value| claim_value_allowed(value, allowed))
+        })
+    }
+}
+
+fn claim_value_allowed(value: &Value, allowed: &[String]) -> bool {
+    match value {
+        Value::String(s) => allowed.contains(s),
+        Value::Array(values) => values.iter().any(|v| claim_value_allowed(v, allowed)),
+        Value::Null | Value::Object(_) => false,
+        other => allowed.contains(&other.to_string()),
+    }
 }
 
 impl Default for AuthConfig {
@@ -31,6 +95,11 @@ impl Default for AuthConfig {
             jwt_algorithm: Some("HS256".to_string()),
             basic_users: None,
             jwks_url: None,
+            jwks_refresh_secs: None,
+            claim_rules: Vec::new(),
+            anonymous_read: false,
+            public_metrics: false,
+            metrics_token: None,
         }
     }
 }
@@ -40,4 +109,7 @@ pub struct AuthContext {
     pub authenticated: bool,
     pub user_id: Option<String>,
     pub roles: Vec<String>,
+    /// All claims of the validated token (JWT modes only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub claims: HashMap<String, Value>,
 }
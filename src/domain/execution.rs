@@ -0,0 +1,69 @@
+//! Who is behind the tool call being handled
+//!
+//! The MCP server runs each `tools/call` inside [`ExecutionContext::scope`].
+//! Code further down the call, such as workflow steps with `inject_context`,
+//! reads it back with [`ExecutionContext::current`]. Work handed to another
+//! task, such as an agent run, keeps it with [`ExecutionContext::propagate`].
+
+use chrono::{DateTime, Utc};
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: ExecutionContext;
+}
+
+/// Caller details of a tool call
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionContext {
+    /// Client address, after looking through trusted proxies
+    pub caller_ip: Option<String>,
+    /// User ID of the authenticated caller
+    pub identity: Option<String>,
+    /// Trace ID of the HTTP request
+    pub trace_id: Option<String>,
+    /// MCP session the call belongs to
+    pub session_id: Option<String>,
+    /// Seed from the `X-Metis-Seed` header, making the call's random output reproducible
+    pub seed: Option<u64>,
+    /// When the call was received
+    pub timestamp: DateTime<Utc>,
+    /// Number of tool calls this one is nested in (through workflows and agents)
+    pub depth: usize,
+}
+
+impl Default for ExecutionContext {
+    fn default() -> Self {
+        Self {
+            caller_ip: None,
+            identity: None,
+            trace_id: None,
+            session_id: None,
+            seed: None,
+            timestamp: Utc::now(),
+            depth: 0,
+        }
+    }
+}
+
+impl ExecutionContext {
+    /// Run `future` with this context as the current one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Context of the call being handled, if any
+    pub fn current() -> Option<ExecutionContext> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// `future` running with the current context, for spawning on another task
+    pub fn propagate<F: Future>(future: F) -> impl Future<Output = F::Output> {
+        let context = Self::current();
+        async move {
+            match context {
+                Some(context) => context.scope(future).await,
+                None => future.await,
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 pub mod auth;
+pub mod execution;
 pub mod sampling;
+pub mod search;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Resource {
@@ -40,7 +43,7 @@ pub struct Prompt {
     pub arguments: Option<Vec<PromptArgument>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PromptArgument {
     pub name: String,
     pub description: Option<String>,
@@ -53,13 +56,13 @@ pub struct GetPromptResult {
     pub messages: Vec<PromptMessage>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PromptMessage {
     pub role: String,
     pub content: PromptContent,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct PromptContent {
     #[serde(rename = "type")]
     pub type_: String,
@@ -73,6 +76,42 @@ pub struct ResourceReadResult {
     pub content: String,
 }
 
+/// How resource content is encoded, derived from its declared MIME type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceContentKind {
+    /// `application/json` and `+json` types: pretty-printed JSON text
+    Json,
+    /// `text/csv`: comma-separated rows with a header line
+    Csv,
+    /// `application/octet-stream`: base64-encoded bytes
+    Binary,
+    /// Anything else is passed through as plain text
+    Text,
+}
+
+impl ResourceContentKind {
+    pub fn from_mime_type(mime_type: Option<&str>) -> Self {
+        let Some(mime_type) = mime_type else {
+            return Self::Text;
+        };
+        // Ignore parameters such as "; charset=utf-8"
+        let essence = mime_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match essence.as_str() {
+            "application/json" => Self::Json,
+            "text/csv" => Self::Csv,
+            "application/octet-stream" => Self::Binary,
+            other if other.ends_with("+json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
 #[async_trait]
 pub trait ResourcePort: Send + Sync {
     async fn get_resource(&self, uri: &str) -> anyhow::Result<ResourceReadResult>;
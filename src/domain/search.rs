@@ -0,0 +1,85 @@
+//! Case-insensitive full-text matching of archetypes
+//!
+//! Every term of a query must match an item's name, description or tags. Each
+//! term scores by where it matched best, and a name match always outranks a
+//! description match.
+
+/// Points for a term that is the whole name
+const EXACT_NAME_SCORE: u32 = 100;
+/// Points for a term contained in the name
+const NAME_SCORE: u32 = 50;
+/// Points for a term contained in a tag
+const TAG_SCORE: u32 = 20;
+/// Points for a term contained in the description
+const DESCRIPTION_SCORE: u32 = 10;
+
+/// Text of an item to search
+pub struct SearchDocument<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub tags: &'a [String],
+}
+
+/// Lowercased terms of a query
+pub fn query_terms(query: &str) -> Vec<String> {
+    query.split_whitespace().map(str::to_lowercase).collect()
+}
+
+/// Score of a document for the query terms, or None if any term doesn't match
+pub fn score(terms: &[String], document: &SearchDocument) -> Option<u32> {
+    if terms.is_empty() {
+        return None;
+    }
+
+    let name = document.name.to_lowercase();
+    let description = document.description.unwrap_or_default().to_lowercase();
+    let tags: Vec<String> = document.tags.iter().map(|t| t.to_lowercase()).collect();
+
+    terms.iter().try_fold(0, |total, term| {
+        let term_score = if name == *term {
+            EXACT_NAME_SCORE
+        } else if name.contains(term.as_str()) {
+            NAME_SCORE
+        } else if tags.iter().any(|t| t.contains(term.as_str())) {
+            TAG_SCORE
+        } else if description.contains(term.as_str()) {
+            DESCRIPTION_SCORE
+        } else {
+            return None;
+        };
+        Some(total + term_score)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document<'a>(name: &'a str, description: &'a str, tags: &'a [String]) -> SearchDocument<'a> {
+        SearchDocument { name, description: Some(description), tags }
+    }
+
+    #[test]
+    fn test_name_matches_outrank_description_matches() {
+        let terms = query_terms("Weather");
+        let no_tags = [];
+
+        let exact = score(&terms, &document("weather", "Forecasts", &no_tags)).unwrap();
+        let in_name = score(&terms, &document("get_weather", "Forecasts", &no_tags)).unwrap();
+        let in_description = score(&terms, &document("forecast", "Current weather", &no_tags)).unwrap();
+
+        assert!(exact > in_name);
+        assert!(in_name > in_description);
+        assert_eq!(score(&terms, &document("stocks", "Quotes", &no_tags)), None);
+    }
+
+    #[test]
+    fn test_every_term_must_match() {
+        let tags = ["finance".to_string()];
+        let doc = document("quote", "Latest stock price", &tags);
+
+        assert_eq!(score(&query_terms("stock FINANCE"), &doc), Some(DESCRIPTION_SCORE + TAG_SCORE));
+        assert_eq!(score(&query_terms("stock weather"), &doc), None);
+        assert_eq!(score(&query_terms("  "), &doc), None);
+    }
+}
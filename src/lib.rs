@@ -46,7 +46,9 @@ pub mod persistence;
 
 use crate::adapters::api_handler::{self, ApiState, SecretsApiState};
 use crate::adapters::data_lake_handler;
-use crate::adapters::auth_middleware::{auth_middleware, AuthMiddleware, SharedAuthMiddleware};
+use crate::adapters::auth_middleware::{
+    auth_middleware, metrics_token_middleware, AuthMiddleware, MetricsToken, SharedAuthMiddleware,
+};
 use crate::adapters::health_handler::HealthHandler;
 use crate::adapters::metrics_handler::MetricsHandler;
 use crate::adapters::mock_strategy::MockStrategyHandler;
@@ -55,6 +57,7 @@ use crate::adapters::secrets::{SharedSecretsStore, SharedPassphraseStore};
 use crate::adapters::state_manager::StateManager;
 use crate::agents::domain::AgentPort;
 use crate::agents::handler::AgentHandler;
+use crate::agents::traces::AgentTraceStore;
 use crate::persistence::DataStore;
 use axum::{routing::{delete, get, post}, Router};
 use rmcp::transport::streamable_http_server::{
@@ -76,10 +79,13 @@ use tokio::sync::RwLock;
 /// * `passphrase_store` - In-memory passphrase store for encrypting secrets when saving config
 /// * `tool_handler` - Tool handler for agents (used to reinitialize when API keys change)
 /// * `data_store` - Optional database store for archetypes (when database persistence is enabled)
+/// * `llm_providers` - Custom LLM providers agents can name
+/// * `agent_sessions` - In-memory conversation sessions, shared by every agent handler
 ///
 /// # Returns
 ///
 /// Configured Axum Router
+#[allow(clippy::too_many_arguments)]
 pub async fn create_app(
     metis_server: MetisServer,
     health_handler: Arc<HealthHandler>,
@@ -92,9 +98,19 @@ pub async fn create_app(
     data_store: Option<Arc<DataStore>>,
     file_storage: Option<Arc<crate::adapters::file_storage::FileStorageHandler>>,
     datafusion: Option<Arc<crate::adapters::datafusion_handler::DataFusionHandler>>,
+    llm_providers: Arc<crate::agents::llm::ProviderRegistry>,
+    agent_sessions: Arc<dyn crate::agents::memory::ConversationStore>,
 ) -> Router {
     // Get the broadcaster before moving metis_server into the closure
     let broadcaster = metis_server.broadcaster().clone();
+    // Reconnected MCP servers bring their tools back, so clients need telling
+    tool_handler.mcp_client().set_broadcaster(broadcaster.clone()).await;
+    let http_mock_state = crate::adapters::http_mock::HttpMockState {
+        settings: settings.clone(),
+        resources: metis_server.resource_handler().clone(),
+        tools: metis_server.tool_handler().clone(),
+        prompts: metis_server.prompt_handler().clone(),
+    };
 
     // Create rmcp HTTP transport service
     // Use stateless mode (stateful_mode: false) to handle each request independently
@@ -111,9 +127,8 @@ pub async fn create_app(
         config,
     );
 
-    // Public routes (no authentication required)
-    let public_router = Router::new()
-        // Health check endpoints
+    // Health check endpoints
+    let health_router = Router::new()
         .route("/health", get({
             let handler = health_handler.clone();
             move || {
@@ -136,50 +151,86 @@ pub async fn create_app(
             }
         }));
 
-    // Protected routes (authentication applied when enabled)
-    let protected_router = Router::new()
-        // Metrics endpoint
+    // `features.admin_api` is only read here, so turning it on takes a restart
+    let admin_api = settings.read().await.features.admin_api;
+
+    // Public routes (no authentication required)
+    let mut public_router = health_router.clone();
+    if admin_api {
+        // Config file JSON Schema
+        public_router = public_router.route("/api/config/schema", get(api_handler::get_config_schema));
+    }
+
+    // Metrics endpoint, protected unless `auth.public_metrics` or `auth.metrics_token` is set
+    let metrics_router = Router::new()
         .route("/metrics", get({
             let handler = metrics_handler.clone();
             move || {
                 let h = handler.clone();
                 async move { h.metrics().await }
             }
-        }))
+        }));
+
+    // Protected routes (authentication applied when enabled)
+    let protected_router = Router::new()
         // MCP protocol endpoint using rmcp streamable HTTP transport
-        .nest_service("/mcp", mcp_service);
+        .nest_service("/mcp", mcp_service)
+        .layer(axum::middleware::from_fn_with_state(
+            state_manager.clone(),
+            crate::adapters::state_manager::end_session_middleware,
+        ));
 
     // Create mock strategy handler for test endpoints (with DataFusion support for scripts)
-    let mock_strategy = Arc::new(MockStrategyHandler::new_with_datafusion(
+    let mock_strategy = MockStrategyHandler::new_with_datafusion(
         state_manager.clone(),
         datafusion.clone(),
         Some(settings.clone()),
         file_storage.clone(),
-    ));
-
-    // Try to create agent handler if agents are configured
-    let agent_handler: Option<Arc<dyn AgentPort>> = {
-        let settings_read = settings.read().await;
-        if !settings_read.agents.is_empty() {
-            // Create a tool handler that uses mock strategies for agent tool calls
-            let tool_handler = Arc::new(crate::adapters::tool_handler::BasicToolHandler::new(
-                settings.clone(),
-                mock_strategy.clone(),
-            ));
+    )
+    .with_callback_tracker(tool_handler.callbacks().clone())
+    .with_error_log(tool_handler.strategy_errors().clone())
+    .with_db_pools(tool_handler.db_pools().clone())
+    .with_metrics(metrics_handler.collector().clone());
+    let mock_strategy = Arc::new(match tool_handler.fixtures() {
+        Some(fixtures) => mock_strategy.with_fixture_cache(fixtures.clone()),
+        None => mock_strategy,
+    });
 
-            let handler = AgentHandler::new_with_secrets(settings.clone(), tool_handler, secrets_store.clone());
+    // Serve agents with the MCP server's agent handler, so config reloads that
+    // rebuild its agents apply to the API too. Without one, create a handler
+    // even if no agents are configured yet, so agents added later can run.
+    let agents_enabled = settings.read().await.features.agents;
+    let agent_handler: Option<Arc<dyn AgentPort>> = if !agents_enabled {
+        None
+    } else if let Some(handler) = tool_handler.agent_handler().await {
+        Some(handler)
+    } else {
+        // Create a tool handler that uses mock strategies for agent tool calls
+        let tool_handler = Arc::new(
+            crate::adapters::tool_handler::BasicToolHandler::new(settings.clone(), mock_strategy.clone())
+                .with_call_limiter(tool_handler.call_limiter().clone()),
+        );
 
-            // Initialize agents - this loads them into memory
-            if let Err(e) = handler.initialize().await {
-                tracing::warn!("Failed to initialize agents: {}", e);
-            } else {
-                tracing::info!("AgentHandler initialized with {} agents", settings_read.agents.len());
-            }
+        let handler = AgentHandler::new_with_secrets(settings.clone(), tool_handler, secrets_store.clone())
+            .with_metrics(metrics_handler.collector().clone())
+            .with_llm_providers(llm_providers.clone())
+            .with_session_store(agent_sessions.clone());
+        let handler = match &data_store {
+            Some(store) => handler.with_traces(AgentTraceStore::new(store.records().clone())),
+            None => handler,
+        };
 
-            Some(Arc::new(handler) as Arc<dyn AgentPort>)
-        } else {
-            None
+        // Initialize agents - this loads them into memory
+        match handler.initialize().await {
+            Ok(report) => tracing::info!(
+                "AgentHandler initialized with {} agents ({} failed)",
+                report.loaded.len(),
+                report.failed.len()
+            ),
+            Err(e) => tracing::warn!("Failed to initialize agents: {}", e),
         }
+
+        Some(Arc::new(handler) as Arc<dyn AgentPort>)
     };
 
     // Create shared test agent handler (shared between ApiState and SecretsApiState)
@@ -200,18 +251,34 @@ pub async fn create_app(
         data_store,
         file_storage,
         datafusion,
+        mock_generation_limiter: crate::adapters::rate_limit::create_per_minute_limiter(
+            crate::adapters::mock_generator::REQUESTS_PER_MINUTE,
+        ),
+        tool_overrides: tool_handler.overrides().clone(),
+        config_history: Arc::new(crate::adapters::config_history::ConfigHistory::new()),
+        metrics: metrics_handler.collector().clone(),
+        idempotency: Arc::new(crate::adapters::idempotency::IdempotencyCache::new()),
+        llm_providers,
+        agent_sessions,
     };
 
     // API routes for Web UI
     let api_router = Router::new()
         // Config overview and settings
         .route("/config", get(api_handler::get_config_overview))
+        .route("/config/validate", get(api_handler::validate_config))
         .route("/config/settings", get(api_handler::get_server_settings).put(api_handler::update_server_settings))
         .route("/config/save-disk", post(api_handler::save_config_to_disk))
         .route("/config/save-s3", post(api_handler::save_config_to_s3))
         .route("/config/export", get(api_handler::export_config))
+        .route("/config/effective", get(api_handler::get_effective_config))
         .route("/config/import", post(api_handler::import_config))
         .route("/config/merge", post(api_handler::merge_config))
+        .route("/config/apply", post(api_handler::apply_config))
+        .route("/config/undo", post(api_handler::undo_config))
+        .route("/config/redo", post(api_handler::redo_config))
+        .route("/config/backups", get(api_handler::list_config_backups))
+        .route("/config/restore-backup", post(api_handler::restore_config_backup))
         .route("/metrics/json", get(api_handler::get_metrics_json))
         // Resources CRUD + Test
         .route("/resources", get(api_handler::list_resources).post(api_handler::create_resource))
@@ -220,33 +287,62 @@ pub async fn create_app(
         // Tools CRUD + Test
         .route("/tools", get(api_handler::list_tools).post(api_handler::create_tool))
         .route("/tools/:name", get(api_handler::get_tool).put(api_handler::update_tool).delete(api_handler::delete_tool))
+        .route("/tools/:name/clone", post(api_handler::clone_tool))
         .route("/tools/:name/test", post(api_handler::test_tool))
+        .route("/tools/:name/generate-mock", post(api_handler::generate_tool_mock))
+        .route("/tools/:name/override", post(api_handler::set_tool_override).delete(api_handler::delete_tool_override))
+        .route("/overrides", get(api_handler::list_tool_overrides))
+        .route("/callbacks", get(api_handler::list_callbacks).delete(api_handler::clear_callbacks))
+        .route("/errors", get(api_handler::list_strategy_errors).delete(api_handler::clear_strategy_errors))
+        .route("/mcp-servers", get(api_handler::list_mcp_servers))
+        .route("/strategies", get(api_handler::list_strategies))
+        .route("/strategies/preview", post(api_handler::preview_strategy))
+        .route("/faker/choices", post(api_handler::get_faker_choices))
         // Prompts CRUD + Test
         .route("/prompts", get(api_handler::list_prompts).post(api_handler::create_prompt))
         .route("/prompts/:name", get(api_handler::get_prompt).put(api_handler::update_prompt).delete(api_handler::delete_prompt))
+        .route("/prompts/:name/clone", post(api_handler::clone_prompt))
         .route("/prompts/:name/test", post(api_handler::test_prompt))
         // Workflows CRUD + Test
         .route("/workflows", get(api_handler::list_workflows).post(api_handler::create_workflow))
         .route("/workflows/:name", get(api_handler::get_workflow).put(api_handler::update_workflow).delete(api_handler::delete_workflow))
+        .route("/workflows/:name/clone", post(api_handler::clone_workflow))
         .route("/workflows/:name/test", post(api_handler::test_workflow))
+        .route("/workflows/:name/estimate", post(api_handler::estimate_workflow))
         // Resource Templates CRUD + Test
         .route("/resource-templates", get(api_handler::list_resource_templates).post(api_handler::create_resource_template))
         .route("/resource-templates/:uri_template", get(api_handler::get_resource_template).put(api_handler::update_resource_template).delete(api_handler::delete_resource_template))
         .route("/resource-templates/:uri_template/test", post(api_handler::test_resource_template))
         // State management
         .route("/state", get(api_handler::get_state).delete(api_handler::reset_state))
+        .route("/state/bulk", post(api_handler::bulk_state))
+        .route("/state/export", get(api_handler::export_state))
+        .route("/state/import", post(api_handler::import_state))
         .route("/state/:key", delete(api_handler::delete_state_key))
         // Agents CRUD + Test
         .route("/agents", get(api_handler::list_agents).post(api_handler::create_agent))
+        .route("/agents/reload", post(api_handler::reload_agents))
         .route("/agents/:name", get(api_handler::get_agent).put(api_handler::update_agent).delete(api_handler::delete_agent))
+        .route("/agents/:name/clone", post(api_handler::clone_agent))
         .route("/agents/:name/test", post(api_handler::test_agent))
+        .route("/agents/:name/sessions/export", get(api_handler::export_agent_sessions))
+        .route("/agents/:name/sessions/import", post(api_handler::import_agent_sessions))
+        .route("/agents/:name/confirmations", get(api_handler::list_agent_confirmations))
+        .route("/agents/:name/confirmations/:token", post(api_handler::resolve_agent_confirmation))
+        .route("/agents/:name/traces", get(api_handler::list_agent_traces))
+        .route("/agents/:name/traces/:trace_id", get(api_handler::get_agent_trace))
+        .route("/costs", get(api_handler::get_costs))
+        // LLM models discovery, for configuring agents
+        .route("/llm/models/:provider", get(api_handler::fetch_llm_models))
         // Orchestrations CRUD + Test
         .route("/orchestrations", get(api_handler::list_orchestrations).post(api_handler::create_orchestration))
         .route("/orchestrations/:name", get(api_handler::get_orchestration).put(api_handler::update_orchestration).delete(api_handler::delete_orchestration))
+        .route("/orchestrations/:name/clone", post(api_handler::clone_orchestration))
         .route("/orchestrations/:name/test", post(api_handler::test_orchestration))
         // Schemas CRUD
         .route("/schemas", get(api_handler::list_schemas).post(api_handler::create_schema))
         .route("/schemas/:name", get(api_handler::get_schema).put(api_handler::update_schema).delete(api_handler::delete_schema))
+        .route("/schemas/:name/clone", post(api_handler::clone_schema))
         // Data Lakes CRUD
         .route("/data-lakes", get(data_lake_handler::list_data_lakes).post(data_lake_handler::create_data_lake))
         .route("/data-lakes/:name", get(data_lake_handler::get_data_lake).put(data_lake_handler::update_data_lake).delete(data_lake_handler::delete_data_lake))
@@ -262,7 +358,6 @@ pub async fn create_app(
         .route("/data-lakes/:name/sync", post(data_lake_handler::sync_to_files))
         .route("/data-lakes/:name/schema-info/:schema_name", get(data_lake_handler::get_schema_info))
         // LLM models discovery
-        .route("/llm/models/:provider", get(api_handler::fetch_llm_models))
         // Database & Version History
         .route("/database/status", get(api_handler::get_database_status))
         .route("/commits", get(api_handler::list_commits))
@@ -271,7 +366,13 @@ pub async fn create_app(
         .route("/commits/:commit_hash/changesets", get(api_handler::get_commit_changesets))
         .route("/commits/:commit_hash/tags", post(api_handler::create_tag))
         .route("/tags", get(api_handler::list_tags))
+        .route("/tags/bulk", post(api_handler::bulk_update_tags))
+        .route("/search", get(api_handler::search))
         .route("/tags/:name", get(api_handler::get_tag).delete(api_handler::delete_tag))
+        .layer(axum::middleware::from_fn_with_state(
+            api_state.clone(),
+            crate::adapters::idempotency::idempotency_middleware,
+        ))
         .with_state(api_state);
 
     // Secrets API routes (separate state for secrets store, but shares test_agent_handler and broadcaster)
@@ -286,26 +387,56 @@ pub async fn create_app(
         .route("/secrets/:key", post(api_handler::set_secret).delete(api_handler::delete_secret))
         .with_state(secrets_state);
 
-    // Merge API routers
-    let api_router = api_router.merge(secrets_router);
+    // Merge API routers; routes of subsystems turned off in `[features]` answer 404
+    let api_router = api_router.merge(secrets_router).layer(axum::middleware::from_fn_with_state(
+        settings.clone(),
+        crate::adapters::features::features_middleware,
+    ));
+
+    // REST endpoints for tools, resources and prompts (server.http_mock_mode)
+    let mut protected_router = protected_router.merge(crate::adapters::http_mock::router(http_mock_state));
 
-    // Build protected router with API routes
-    let mut protected_router = protected_router
-        .nest("/api", api_router)
-        // UI endpoint (catch-all for SPA)
-        .fallback(crate::adapters::ui_handler::UIHandler::serve);
+    // API routes and the UI, which only talks to them, unless the admin API is off
+    if admin_api {
+        protected_router = protected_router
+            .nest("/api", api_router)
+            // UI endpoint (catch-all for SPA)
+            .fallback(crate::adapters::ui_handler::UIHandler::serve);
+    } else {
+        tracing::info!("Admin API and UI disabled by features.admin_api");
+    }
 
-    // Apply Rate Limiting to protected routes if enabled
+    // Scrapers reach `/metrics` without credentials, with only the metrics
+    // token, or like any other protected route
     let settings_read = settings.read().await;
+    if settings_read.auth.public_metrics {
+        public_router = public_router.merge(metrics_router);
+    } else if let Some(token) = &settings_read.auth.metrics_token {
+        let token: MetricsToken = Arc::new(token.clone());
+        public_router = public_router
+            .merge(metrics_router.layer(axum::middleware::from_fn_with_state(token, metrics_token_middleware)));
+    } else {
+        protected_router = protected_router.merge(metrics_router);
+    }
+
+    // Apply Rate Limiting to protected routes if enabled
+    // (public routes such as the health checks are never rate limited)
     if let Some(rate_limit) = &settings_read.rate_limit {
         if rate_limit.enabled {
             let limiter = crate::adapters::rate_limit::create_limiter(
+                rate_limit.algorithm,
                 rate_limit.requests_per_second,
                 rate_limit.burst_size,
             );
 
-            protected_router = protected_router.layer(axum::middleware::from_fn_with_state(
+            let rate_limit_state = crate::adapters::rate_limit::RateLimitState::new(
                 limiter,
+                settings.clone(),
+                Some(metrics_handler.collector().clone()),
+            );
+
+            protected_router = protected_router.layer(axum::middleware::from_fn_with_state(
+                rate_limit_state,
                 crate::adapters::rate_limit::rate_limit_middleware,
             ));
         }
@@ -319,7 +450,34 @@ pub async fn create_app(
 
     // Merge public and protected routers
     // Public routes are checked first, then protected routes
-    let router = public_router.merge(protected_router);
+    let mut router = public_router.merge(protected_router);
+
+    // Serve everything under `server.base_path`, e.g. behind a reverse proxy
+    let base_path = settings_read.server.base_path.as_deref().map(|path| path.trim_end_matches('/'));
+    if let Some(base_path) = base_path.filter(|path| !path.is_empty()) {
+        router = Router::new()
+            .nest(base_path, router)
+            .layer(axum::Extension(crate::adapters::ui_handler::UiBasePath(base_path.to_string())));
+        if settings_read.server.health_at_root {
+            router = router.merge(health_router);
+        }
+    }
+
+    // Apply the IP allow/deny lists to every route, including health checks
+    if let Some(access_control) = &settings_read.access_control {
+        use crate::adapters::access_control::{access_control_middleware, AccessControl, SharedAccessControl};
+
+        // Fail closed: a typo in the lists must not open the server to everyone
+        let access = AccessControl::new(access_control).unwrap_or_else(|e| {
+            tracing::error!("Invalid access_control config, rejecting every request: {}", e);
+            AccessControl::deny_all()
+        });
+        let access: SharedAccessControl = Arc::new(access);
+        router = router.layer(axum::middleware::from_fn_with_state(access, access_control_middleware));
+    }
+
+    // Give every request an ID for its log lines
+    let router = router.layer(axum::middleware::from_fn(crate::adapters::logging::request_context_middleware));
 
     router.layer(
         tower_http::cors::CorsLayer::new()
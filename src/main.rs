@@ -5,10 +5,16 @@ use metis::adapters::prompt_handler::InMemoryPromptHandler;
 use metis::adapters::resource_handler::InMemoryResourceHandler;
 use metis::adapters::rmcp_server::MetisServer;
 use metis::adapters::secrets::{create_passphrase_store, create_secrets_store, keys};
+use metis::adapters::startup::{self, StartupSummary};
 use metis::adapters::state_manager::StateManager;
 use metis::adapters::tool_handler::BasicToolHandler;
 use metis::cli::{Cli, Commands};
-use metis::config::{watcher::ConfigWatcher, s3_watcher::AwsCredentials, S3Watcher, Settings};
+use metis::adapters::fixture_cache::FixtureCache;
+use metis::config::{format, watcher::{ConfigWatcher, FixtureWatcher}, s3_watcher::AwsCredentials, validator::ConfigValidator, LogFormat, S3Watcher, Settings};
+use metis::agents::config::MemoryBackend;
+use metis::agents::llm::ProviderRegistry;
+use metis::agents::memory::{ConversationStore, FileStore, InMemoryStore, DEFAULT_SESSION_DIR};
+use metis::agents::traces::AgentTraceStore;
 use metis::persistence::DataStore;
 use std::io::{self, Write};
 use std::net::SocketAddr;
@@ -26,11 +32,16 @@ async fn main() -> anyhow::Result<()> {
         return handle_command(cmd, &cli).await;
     }
 
-    // Initialize tracing (only for server mode)
-    tracing_subscriber::fmt::init();
-
     // Load configuration with CLI overrides
     let settings = Settings::new_with_cli(&cli)?;
+
+    // The banner would break JSON log lines, so it is only printed above text logs
+    if !cli.no_banner && settings.server.log_format == LogFormat::Text {
+        println!("{}", startup::banner());
+    }
+
+    // Initialize tracing (only for server mode) with the configured log format
+    metis::adapters::logging::init(&settings.server)?;
     let host = settings.server.host.clone();
     let port = settings.server.port;
     let s3_config = settings.s3.clone();
@@ -45,31 +56,69 @@ async fn main() -> anyhow::Result<()> {
         .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|| ".".to_string());
     let config_root_for_reload = config_root.clone();
+    let profile = settings.profile.clone();
+    let profile_for_reload = profile.clone();
 
     info!("Starting Metis MCP Mock Server on {}:{}", host, port);
     info!("Using configuration file: {}", config_path.display());
+    if let Some(profile) = &profile {
+        info!("Using config profile: {}", profile);
+    }
+
+    // Custom LLM providers agents can name; register in-house ones here with
+    // `llm_providers.register_provider(name, factory)`
+    let llm_providers = Arc::new(ProviderRegistry::new());
+    if let Err(errors) = ConfigValidator::validate_llm_providers(&settings, &llm_providers) {
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        anyhow::bail!("Configuration validation failed:\n{}", messages.join("\n"));
+    }
 
     // Wrap settings in Arc<RwLock> for live reload
     let settings = Arc::new(RwLock::new(settings));
 
+    // Watch File-strategy fixture directories so edited fixtures are picked up
+    let fixtures = Arc::new(FixtureCache::new());
+    let fixture_watcher = {
+        let fixtures = fixtures.clone();
+        Arc::new(FixtureWatcher::new(move |paths| fixtures.invalidate(paths))?)
+    };
+    fixture_watcher.watch_dirs(settings.read().await.fixture_dirs());
+    let fixture_watcher_for_reload = fixture_watcher.clone();
+
+    // Config reloads rebuild the agents, once they are set up below
+    let (agents_reload_tx, mut agents_reload_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+
     // Start config watcher for local file changes
     let settings_for_watcher = settings.clone();
+    let agents_reload_for_watcher = agents_reload_tx.clone();
     // Build watch paths based on the actual config location
-    let paths = vec![
-        config_path.to_string_lossy().to_string(),
-        format!("{}/config/tools", config_root),
-        format!("{}/config/resources", config_root),
-        format!("{}/config/resource_templates", config_root),
-        format!("{}/config/prompts", config_root),
-        format!("{}/config/schemas", config_root),
+    let item_dirs = [
+        "tools",
+        "resources",
+        "resource_templates",
+        "prompts",
+        "workflows",
+        "agents",
+        "orchestrations",
+        "schemas",
     ];
+    let mut paths = vec![config_path.to_string_lossy().to_string()];
+    paths.extend(item_dirs.iter().map(|dir| format!("{}/config/{}", config_root, dir)));
+    if let Some(profile) = &profile {
+        paths.push(format!("{}/{}", config_root, Settings::profile_file_name(&config_path, profile)));
+        for dir in item_dirs {
+            paths.push(format!("{}/config/{}/{}", config_root, profile, dir));
+        }
+    }
     let _watcher = ConfigWatcher::new(paths, move || {
-        match Settings::from_root(&config_root_for_reload) {
+        match Settings::from_root_with_profile(&config_root_for_reload, profile_for_reload.as_deref()) {
             Ok(new_settings) => {
                 let mut w = settings_for_watcher.blocking_write();
                 // Merge local config changes (local config is base, gets overridden by S3/UI)
                 w.merge(new_settings);
                 info!("Configuration merged from local files successfully");
+                fixture_watcher_for_reload.watch_dirs(w.fixture_dirs());
+                let _ = agents_reload_for_watcher.send(());
             }
             Err(e) => error!("Failed to reload configuration: {}", e),
         }
@@ -130,15 +179,18 @@ async fn main() -> anyhow::Result<()> {
                 match S3Watcher::new_with_credentials(s3_cfg, credentials).await {
                     Ok(s3_watcher) => {
                         let settings_for_s3 = settings.clone();
+                        let agents_reload_for_s3 = agents_reload_tx.clone();
                         if let Err(e) = s3_watcher
                             .start_with_callback(move |s3_configs| {
                                 let settings_clone = settings_for_s3.clone();
+                                let agents_reload = agents_reload_for_s3.clone();
                                 // Spawn a new task to handle the async settings update
                                 tokio::spawn(async move {
                                     let mut w = settings_clone.write().await;
                                     // Merge S3 configs into existing settings (S3 takes precedence)
                                     w.merge_s3_configs(s3_configs);
                                     info!("Configuration merged from S3 successfully");
+                                    let _ = agents_reload.send(());
                                 });
                             })
                             .await
@@ -236,13 +288,24 @@ async fn main() -> anyhow::Result<()> {
             None
         };
 
+    // Connection pools for the database mock strategy, closed on shutdown
+    let db_pools = Arc::new(
+        metis::adapters::db_strategy_pool::DatabaseStrategyPool::new()
+            .with_metrics(metrics_handler.collector().clone()),
+    );
+
     // Initialize mock strategy handler (after DataFusion so scripts can query data lakes)
-    let mock_strategy = Arc::new(MockStrategyHandler::new_with_datafusion(
-        state_manager.clone(),
-        datafusion.clone(),
-        Some(settings.clone()),
-        file_storage.clone(),
-    ));
+    let mock_strategy = Arc::new(
+        MockStrategyHandler::new_with_datafusion(
+            state_manager.clone(),
+            datafusion.clone(),
+            Some(settings.clone()),
+            file_storage.clone(),
+        )
+        .with_db_pools(db_pools.clone())
+        .with_fixture_cache(fixtures)
+        .with_metrics(metrics_handler.collector().clone()),
+    );
 
     // Initialize handlers that depend on mock_strategy
     let resource_handler: Arc<InMemoryResourceHandler> = if let Some(fs) = &file_storage {
@@ -257,37 +320,9 @@ async fn main() -> anyhow::Result<()> {
             mock_strategy.clone(),
         ))
     };
-    let tool_handler = Arc::new(BasicToolHandler::new(
-        settings.clone(),
-        mock_strategy.clone(),
-    ));
-
-    // Create agent handler with secrets support
-    let agent_handler = metis::agents::handler::AgentHandler::new_with_secrets(
-        settings.clone(),
-        tool_handler.clone(),
-        secrets_store.clone(),
-    );
-
-    // Initialize agents
-    if let Err(e) = agent_handler.initialize().await {
-        tracing::warn!("Failed to initialize agents: {}", e);
-    } else {
-        info!("Agents initialized successfully");
-    }
-
-    // Wrap in Arc for sharing
-    let agent_handler: Arc<dyn metis::agents::domain::AgentPort> = Arc::new(agent_handler);
-
-    // Wire up agent handler to tool handler so agents can call other agents
-    // (tool_handler handles agent tools, MCP tools, workflows, and regular tools)
-    tool_handler.set_agent_handler(agent_handler).await;
-
-    // Create MetisServer (tool_handler already includes agent support)
-    let metis_server = MetisServer::new(
-        resource_handler,
-        tool_handler.clone(),
-        prompt_handler,
+    let tool_handler = Arc::new(
+        BasicToolHandler::new(settings.clone(), mock_strategy.clone())
+            .with_metrics(metrics_handler.collector().clone()),
     );
 
     // Initialize database if configured
@@ -321,18 +356,122 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // Conversation sessions, shared with the agent handlers of the REST API
+    let agent_sessions: Arc<dyn ConversationStore> = Arc::new(InMemoryStore::new(100));
+
+    // Create agent handler with secrets support
+    let agent_handler = metis::agents::handler::AgentHandler::new_with_secrets(
+        settings.clone(),
+        tool_handler.clone(),
+        secrets_store.clone(),
+    )
+    .with_metrics(metrics_handler.collector().clone())
+    .with_llm_providers(llm_providers.clone())
+    .with_session_store(agent_sessions.clone());
+    let agent_handler = match &data_store {
+        Some(store) => agent_handler.with_traces(AgentTraceStore::new(store.records().clone())),
+        None => agent_handler,
+    };
+
+    // Initialize agents, unless `features.agents` turns them off
+    let agents_enabled = settings.read().await.features.agents;
+    if agents_enabled {
+        match agent_handler.initialize().await {
+            Ok(report) => info!("Agents initialized: {} loaded, {} failed", report.loaded.len(), report.failed.len()),
+            Err(e) => tracing::warn!("Failed to initialize agents: {}", e),
+        }
+    } else {
+        info!("Agents disabled by features.agents");
+    }
+
+    // Wrap in Arc for sharing
+    let agent_handler: Arc<dyn metis::agents::domain::AgentPort> = Arc::new(agent_handler);
+
+    // Wire up agent handler to tool handler so agents can call other agents
+    // (tool_handler handles agent tools, MCP tools, workflows, and regular tools)
+    tool_handler.set_agent_handler(agent_handler).await;
+
+    // Rebuild agents after config reloads, so added or changed agents work without a restart
+    if agents_enabled {
+        let tool_handler = tool_handler.clone();
+        let settings = settings.clone();
+        tokio::spawn(async move {
+            while agents_reload_rx.recv().await.is_some() {
+                // A burst of file events needs only one rebuild
+                while agents_reload_rx.try_recv().is_ok() {}
+                if !settings.read().await.features.agents {
+                    continue;
+                }
+                if let Err(e) = tool_handler.reinitialize_agents().await {
+                    warn!("Failed to reinitialize agents after config reload: {}", e);
+                }
+            }
+        });
+    }
+
+    // Create MetisServer (tool_handler already includes agent support)
+    let metis_server = MetisServer::new(
+        resource_handler,
+        tool_handler.clone(),
+        prompt_handler,
+    )
+    .with_settings(settings.clone());
+
+    let summary = StartupSummary::new(&*settings.read().await, &format!("{}:{}", host, port), data_store.is_some());
+
     // Create application using the library function
-    let app = metis::create_app(metis_server, health_handler, metrics_handler, settings, state_manager, secrets_store, passphrase_store, tool_handler, data_store, file_storage, datafusion).await;
+    let app = metis::create_app(metis_server, health_handler, metrics_handler, settings, state_manager, secrets_store, passphrase_store, tool_handler.clone(), data_store, file_storage, datafusion, llm_providers, agent_sessions).await;
+
+    // Connect to external MCP servers; unreachable ones are retried in the background
+    if let Err(e) = tool_handler.initialize_mcp().await {
+        warn!("Failed to initialize MCP servers: {}", e);
+    }
 
     // Start server
     let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
     info!("Listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    summary.log();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    info!("Shutting down");
+    db_pools.close_all().await;
 
     Ok(())
 }
 
+/// Resolves on Ctrl+C, or SIGTERM on Unix
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
 /// Handle CLI subcommands
 async fn handle_command(cmd: &Commands, cli: &Cli) -> anyhow::Result<()> {
     use metis::persistence::{ArchetypeRepository, CommitRepository, DataStore, PersistenceConfig};
@@ -477,6 +616,154 @@ async fn handle_command(cmd: &Commands, cli: &Cli) -> anyhow::Result<()> {
             }
             Ok(())
         }
+        Commands::Effective {
+            url,
+            api_key,
+            token,
+            output,
+            format,
+        } => {
+            let url = server_url(url.as_deref(), cli)?;
+            let accept = match format.to_lowercase().as_str() {
+                "toml" => "application/toml",
+                "json" => "application/json",
+                "yaml" | "yml" => "application/yaml",
+                _ => {
+                    eprintln!("Unknown format: {}. Use toml, json, or yaml.", format);
+                    std::process::exit(1);
+                }
+            };
+
+            let request = reqwest::Client::new()
+                .get(format!("{}/api/config/effective", url))
+                .header(reqwest::header::ACCEPT, accept);
+            let response = with_api_auth(request, api_key.as_deref(), token.as_deref()).send().await?;
+            let status = response.status();
+            let body = response.text().await?;
+            if !status.is_success() {
+                anyhow::bail!("Failed to get effective config from {} ({}): {}", url, status, body);
+            }
+
+            // JSON comes wrapped in the API envelope
+            let content = if accept == "application/json" {
+                let envelope: serde_json::Value = serde_json::from_str(&body)?;
+                serde_json::to_string_pretty(&envelope["data"])?
+            } else {
+                body
+            };
+
+            if let Some(path) = output {
+                std::fs::write(path, &content)?;
+                println!("Effective configuration written to: {}", path.display());
+            } else {
+                println!("{}", content);
+            }
+            Ok(())
+        }
+        Commands::ExportState {
+            url,
+            api_key,
+            token,
+            output,
+        } => {
+            let url = server_url(url.as_deref(), cli)?;
+            let request = reqwest::Client::new().get(format!("{}/api/state/export", url));
+            let response = with_api_auth(request, api_key.as_deref(), token.as_deref()).send().await?;
+            let status = response.status();
+            let body = response.text().await?;
+            if !status.is_success() {
+                anyhow::bail!("Failed to export state from {} ({}): {}", url, status, body);
+            }
+
+            let envelope: serde_json::Value = serde_json::from_str(&body)?;
+            let content = serde_json::to_string_pretty(&envelope["data"])?;
+            if let Some(path) = output {
+                std::fs::write(path, &content)?;
+                println!("State written to: {}", path.display());
+            } else {
+                println!("{}", content);
+            }
+            Ok(())
+        }
+        Commands::ImportState {
+            input,
+            url,
+            api_key,
+            token,
+            merge,
+        } => {
+            let url = server_url(url.as_deref(), cli)?;
+            let snapshot: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(input)?)?;
+            let Some(entries) = snapshot.get("entries").filter(|entries| entries.is_object()) else {
+                anyhow::bail!("{} is not a state snapshot: expected an 'entries' object", input.display());
+            };
+            let mode = if *merge { "merge" } else { "replace" };
+
+            let request = reqwest::Client::new()
+                .post(format!("{}/api/state/import", url))
+                .json(&serde_json::json!({"entries": entries, "mode": mode}));
+            let response = with_api_auth(request, api_key.as_deref(), token.as_deref()).send().await?;
+            let status = response.status();
+            let body = response.text().await?;
+            if !status.is_success() {
+                anyhow::bail!("Failed to import state into {} ({}): {}", url, status, body);
+            }
+
+            let envelope: serde_json::Value = serde_json::from_str(&body)?;
+            println!(
+                "Imported {} state keys into {} ({} keys in total)",
+                envelope["data"]["imported"], url, envelope["data"]["total"]
+            );
+            Ok(())
+        }
+        Commands::ConfigSchema { output, inline } => {
+            let content = serde_json::to_string_pretty(&Settings::json_schema(*inline))?;
+            if let Some(path) = output {
+                std::fs::write(path, &content)?;
+                println!("Config schema written to: {}", path.display());
+            } else {
+                println!("{}", content);
+            }
+            Ok(())
+        }
+        Commands::Fmt { config, write } => {
+            let mut cli = cli.clone();
+            if let Some(config) = config {
+                cli.config = config.clone();
+            }
+            let settings = Settings::new_with_cli(&cli)?;
+
+            let mut changed = 0;
+            let mut skipped = 0;
+            for path in format::item_files(&settings) {
+                let content = std::fs::read_to_string(&path)?;
+                let formatted = format::canonicalize(&path, &content)
+                    .map_err(|e| anyhow::anyhow!("Failed to format {}: {}", path.display(), e))?;
+                if *write {
+                    if formatted == content {
+                        continue;
+                    }
+                    if let Some(dropped) = format::rewrite_would_drop(&path, &content) {
+                        eprintln!("Skipped {}: rewriting it would drop its {}", path.display(), dropped);
+                        skipped += 1;
+                    } else {
+                        std::fs::write(&path, &formatted)?;
+                        println!("Formatted {}", path.display());
+                        changed += 1;
+                    }
+                } else {
+                    println!("==> {} <==", path.display());
+                    print!("{}", formatted);
+                }
+            }
+            if *write {
+                println!("{} file(s) reformatted", changed);
+                if skipped > 0 {
+                    anyhow::bail!("{} file(s) left as they are to keep their comments or anchors", skipped);
+                }
+            }
+            Ok(())
+        }
         Commands::Import {
             input,
             format,
@@ -651,7 +938,61 @@ async fn handle_command(cmd: &Commands, cli: &Cli) -> anyhow::Result<()> {
             println!("Rollback completed. Created rollback commit: {}", &rollback_commit.commit_hash[..8]);
             Ok(())
         }
+        Commands::SessionGc { path, max_age_days } => {
+            let max_age = std::time::Duration::from_secs(max_age_days.saturating_mul(86_400));
+            for dir in session_dirs(path.as_ref(), cli)? {
+                let store = FileStore::new(&dir)?;
+                let report = store.compact(max_age).await?;
+                println!(
+                    "{}: removed {} session(s), kept {}",
+                    dir.display(),
+                    report.removed,
+                    report.kept
+                );
+            }
+            Ok(())
+        }
+        Commands::SessionExportAll { output, path } => {
+            let dirs = session_dirs(path.as_ref(), cli)?;
+            let mut archive = zip::ZipWriter::new(std::fs::File::create(output)?);
+            let mut exported = 0;
+            for dir in &dirs {
+                let store = FileStore::new(dir)?;
+                // Each directory gets its own folder when there's more than one
+                let prefix = if dirs.len() > 1 {
+                    format!("{}/", dir.display().to_string().trim_matches('/'))
+                } else {
+                    String::new()
+                };
+                exported += store.export_all(&mut archive, &prefix).await?;
+            }
+            archive.finish()?;
+            println!("Exported {} session(s) to: {}", exported, output.display());
+            Ok(())
+        }
+    }
+}
+
+/// Session directories of the file memory backend: `path` if given, otherwise
+/// the directory of each configured agent using file memory
+fn session_dirs(path: Option<&std::path::PathBuf>, cli: &Cli) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    if let Some(path) = path {
+        return Ok(vec![path.clone()]);
     }
+
+    let settings = Settings::new_with_cli(cli)?;
+    let mut dirs: Vec<std::path::PathBuf> = settings
+        .agents
+        .iter()
+        .filter(|agent| agent.memory.backend == MemoryBackend::File)
+        .map(|agent| agent.memory.file_path.as_deref().unwrap_or(DEFAULT_SESSION_DIR).into())
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    if dirs.is_empty() {
+        anyhow::bail!("No agent uses file memory. Use --path to name a session directory.");
+    }
+    Ok(dirs)
 }
 
 /// Get database URL from CLI arg, config, or error
@@ -675,6 +1016,36 @@ fn get_database_url(cli_url: Option<&str>, cli: &Cli) -> anyhow::Result<String>
     }
 }
 
+/// Base URL of a running server: `url` if given, otherwise the configured host,
+/// port and base path
+fn server_url(url: Option<&str>, cli: &Cli) -> anyhow::Result<String> {
+    if let Some(url) = url {
+        return Ok(url.trim_end_matches('/').to_string());
+    }
+    let settings = Settings::new_with_cli(cli)?;
+    let host = match settings.server.host.as_str() {
+        "0.0.0.0" => "127.0.0.1",
+        host => host,
+    };
+    let base_path = settings.server.base_path.as_deref().unwrap_or_default().trim_end_matches('/');
+    Ok(format!("http://{}:{}{}", host, settings.server.port, base_path))
+}
+
+/// Add the API key or bearer token a server's auth expects
+fn with_api_auth(
+    mut request: reqwest::RequestBuilder,
+    api_key: Option<&str>,
+    token: Option<&str>,
+) -> reqwest::RequestBuilder {
+    if let Some(api_key) = api_key {
+        request = request.header("x-api-key", api_key);
+    }
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request
+}
+
 /// Get passphrase from argument or prompt user
 fn get_passphrase(provided: Option<&str>, prompt: &str) -> anyhow::Result<String> {
     if let Some(pass) = provided {
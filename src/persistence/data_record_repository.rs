@@ -153,7 +153,7 @@ impl DataRecordRepository for SqlxDataRecordRepository {
         let metadata_str = record
             .metadata
             .as_ref()
-            .map(|m| serde_json::to_string(m))
+            .map(serde_json::to_string)
             .transpose()?;
 
         sqlx::query(
@@ -190,7 +190,7 @@ impl DataRecordRepository for SqlxDataRecordRepository {
         let metadata_str = record
             .metadata
             .as_ref()
-            .map(|m| serde_json::to_string(m))
+            .map(serde_json::to_string)
             .transpose()?;
 
         let result = sqlx::query(
@@ -125,6 +125,25 @@ fn get_migrations() -> Vec<Migration> {
     ]
 }
 
+/// The statements of a migration script, without their comment lines.
+///
+/// Each table in the scripts is preceded by a `--` comment, so skipping every
+/// chunk that starts with one would skip the tables too.
+fn split_statements(sql: &str) -> Vec<String> {
+    sql.split(';')
+        .map(|statement| {
+            statement
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        })
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
 /// Migration runner for the persistence layer
 pub struct MigrationRunner {
     pool: ConnectionPool,
@@ -156,13 +175,8 @@ impl MigrationRunner {
 
             // Execute migration SQL
             // For SQLite, we need to execute statements one by one
-            for statement in migration.sql.split(';') {
-                let statement = statement.trim();
-                if statement.is_empty() || statement.starts_with("--") {
-                    continue;
-                }
-
-                sqlx::query(statement)
+            for statement in split_statements(migration.sql) {
+                sqlx::query(&statement)
                     .execute(self.pool.pool())
                     .await
                     .map_err(|e| {
@@ -310,3 +324,44 @@ pub struct MigrationStatus {
     /// When the migration was applied (if applied)
     pub applied_at: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements_keeps_statements_after_comments() {
+        let sql = "\n-- Widgets\nCREATE TABLE widgets (id TEXT);\n\n-- Indexes\nCREATE INDEX idx_a ON widgets(id);\nCREATE INDEX idx_b ON widgets(id);\n-- trailing comment\n";
+        assert_eq!(
+            split_statements(sql),
+            vec![
+                "CREATE TABLE widgets (id TEXT)",
+                "CREATE INDEX idx_a ON widgets(id)",
+                "CREATE INDEX idx_b ON widgets(id)",
+            ]
+        );
+
+        // Every table of the initial schema follows a comment
+        let statements = split_statements(MIGRATION_001_INITIAL);
+        let tables = statements.iter().filter(|s| s.starts_with("CREATE TABLE")).count();
+        assert_eq!(tables, 6);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_up_creates_tables() {
+        let pool = ConnectionPool::new("sqlite::memory:", 1, 5).await.unwrap();
+        let runner = MigrationRunner::new(pool.clone());
+
+        let result = runner.migrate_up().await.unwrap();
+        assert_eq!(result.applied, 2);
+        for table in ["archetypes", "commits", "changesets", "snapshots", "tags", "data_records"] {
+            sqlx::query(&format!("SELECT COUNT(*) FROM {}", table))
+                .execute(pool.pool())
+                .await
+                .unwrap_or_else(|e| panic!("table {} missing: {}", table, e));
+        }
+
+        let result = runner.migrate_up().await.unwrap();
+        assert_eq!((result.applied, result.skipped), (0, 2));
+    }
+}
@@ -52,11 +52,12 @@ pub use repository::{
 };
 
 use crate::config::Settings;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// Configuration for the persistence layer
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct PersistenceConfig {
     /// Database connection URL
     /// - SQLite: `sqlite://metis.db` or `sqlite::memory:`
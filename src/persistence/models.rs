@@ -92,7 +92,7 @@ pub struct TagRow {
     pub created_at: String,
 }
 
-/// API response types for version history
+// API response types for version history
 
 /// Commit information for API responses
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -231,6 +231,30 @@ impl ArchetypeType {
         }
     }
 
+    /// Config section holding items of this type
+    pub fn section(&self) -> &'static str {
+        match self {
+            Self::Resource => "resources",
+            Self::ResourceTemplate => "resource_templates",
+            Self::Tool => "tools",
+            Self::Prompt => "prompts",
+            Self::Workflow => "workflows",
+            Self::Agent => "agents",
+            Self::Orchestration => "orchestrations",
+            Self::Schema => "schemas",
+            Self::DataLake => "data_lakes",
+        }
+    }
+
+    /// Field that identifies an item of this type
+    pub fn key_field(&self) -> &'static str {
+        match self {
+            Self::Resource => "uri",
+            Self::ResourceTemplate => "uri_template",
+            _ => "name",
+        }
+    }
+
     pub fn all() -> &'static [ArchetypeType] {
         &[
             Self::Resource,
@@ -246,6 +270,31 @@ impl ArchetypeType {
     }
 }
 
+/// Order of archetypes in a listing. Timestamps tie-break by name, so the
+/// order is the same on every call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListSort {
+    /// Name ascending
+    #[default]
+    Name,
+    /// Oldest created first
+    Created,
+    /// Least recently updated first
+    Updated,
+}
+
+impl ListSort {
+    /// SQL `ORDER BY` clause for the archetypes table
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Created => "created_at, name",
+            Self::Updated => "updated_at, name",
+        }
+    }
+}
+
 impl std::fmt::Display for ArchetypeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -2,14 +2,16 @@
 
 use crate::persistence::error::PersistenceError;
 use crate::persistence::models::{
-    ArchetypeType, Changeset, Commit, Operation, Tag,
+    ArchetypeType, Changeset, Commit, ListSort, Operation, Tag,
 };
 use crate::persistence::pool::ConnectionPool;
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use sqlx::Row;
 use std::collections::HashMap;
+use tokio_stream::wrappers::ReceiverStream;
 
 /// Repository for archetype CRUD operations
 #[async_trait]
@@ -138,6 +140,52 @@ impl SqlxArchetypeRepository {
     pub fn new(pool: ConnectionPool) -> Self {
         Self { pool }
     }
+
+    /// Archetypes of a specific type in `sort` order
+    pub async fn list_sorted(&self, archetype_type: &str, sort: ListSort) -> Result<Vec<Value>, PersistenceError> {
+        let query = format!(
+            "SELECT definition FROM archetypes WHERE archetype_type = ? AND deleted_at IS NULL ORDER BY {}",
+            sort.order_by()
+        );
+        let rows = sqlx::query(&query)
+            .bind(archetype_type)
+            .fetch_all(self.pool.pool())
+            .await?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let definition: String = row.try_get("definition")?;
+            result.push(serde_json::from_str(&definition)?);
+        }
+        Ok(result)
+    }
+
+    /// Archetypes of a specific type in `sort` order, sent as their rows are
+    /// read rather than collected first. The stream ends early with the first
+    /// error.
+    pub fn list_stream(&self, archetype_type: &str, sort: ListSort) -> ReceiverStream<Result<Value, PersistenceError>> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(64);
+        let pool = self.pool.pool().clone();
+        let archetype_type = archetype_type.to_string();
+        let query = format!(
+            "SELECT definition FROM archetypes WHERE archetype_type = ? AND deleted_at IS NULL ORDER BY {}",
+            sort.order_by()
+        );
+        tokio::spawn(async move {
+            let mut rows = sqlx::query(&query).bind(archetype_type).fetch(&pool);
+            while let Some(row) = rows.next().await {
+                let value = row.map_err(PersistenceError::from).and_then(|row| {
+                    let definition: String = row.try_get("definition")?;
+                    Ok(serde_json::from_str(&definition)?)
+                });
+                let failed = value.is_err();
+                if sender.send(value).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        ReceiverStream::new(receiver)
+    }
 }
 
 #[async_trait]
@@ -166,21 +214,7 @@ impl ArchetypeRepository for SqlxArchetypeRepository {
     }
 
     async fn list(&self, archetype_type: &str) -> Result<Vec<Value>, PersistenceError> {
-        let rows = sqlx::query(
-            "SELECT definition FROM archetypes WHERE archetype_type = ? AND deleted_at IS NULL ORDER BY name",
-        )
-        .bind(archetype_type)
-        .fetch_all(self.pool.pool())
-        .await?;
-
-        let mut result = Vec::new();
-        for row in rows {
-            let definition: String = row.try_get("definition")?;
-            let value: Value = serde_json::from_str(&definition)?;
-            result.push(value);
-        }
-
-        Ok(result)
+        self.list_sorted(archetype_type, ListSort::Name).await
     }
 
     async fn create(
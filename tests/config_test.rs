@@ -85,3 +85,207 @@ messages:
 
     Ok(())
 }
+
+#[test]
+fn test_includes_are_merged_in_order() -> anyhow::Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("config/shared"))?;
+
+    let metis_toml = r#"
+includes = ["config/tools.toml", "config/overrides.toml"]
+
+[server]
+host = "127.0.0.1"
+port = 4000
+"#;
+    fs::write(root.join("metis.toml"), metis_toml)?;
+
+    // Defines two tools and pulls in a nested include
+    let tools_toml = r#"
+includes = ["config/shared/prompts.toml"]
+
+[[tools]]
+name = "first"
+description = "From tools.toml"
+input_schema = {}
+mock = { strategy = "static" }
+
+[[tools]]
+name = "second"
+description = "From tools.toml"
+input_schema = {}
+mock = { strategy = "static" }
+"#;
+    fs::write(root.join("config/tools.toml"), tools_toml)?;
+
+    let prompts_toml = r#"
+[[prompts]]
+name = "shared_prompt"
+description = "From a nested include"
+"#;
+    fs::write(root.join("config/shared/prompts.toml"), prompts_toml)?;
+
+    // Included later, so it wins over tools.toml
+    let overrides_toml = r#"
+[[tools]]
+name = "second"
+description = "From overrides.toml"
+input_schema = {}
+mock = { strategy = "static" }
+"#;
+    fs::write(root.join("config/overrides.toml"), overrides_toml)?;
+
+    let settings = Settings::from_root(root.to_str().unwrap())?;
+
+    // Includes without a [server] section keep the main file's settings
+    assert_eq!(settings.server.port, 4000);
+
+    assert_eq!(settings.tools.len(), 2);
+    assert_eq!(settings.tools[0].description, "From tools.toml");
+    assert_eq!(settings.tools[1].description, "From overrides.toml");
+    assert_eq!(settings.prompts.len(), 1);
+    assert_eq!(settings.prompts[0].name, "shared_prompt");
+
+    Ok(())
+}
+
+#[test]
+fn test_circular_includes_are_rejected() -> anyhow::Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+
+    fs::write(root.join("metis.toml"), "includes = [\"a.toml\"]\n")?;
+    fs::write(root.join("a.toml"), "includes = [\"b.toml\"]\n")?;
+    fs::write(root.join("b.toml"), "includes = [\"a.toml\"]\n")?;
+
+    let err = Settings::from_root(root.to_str().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("Circular include"), "{}", err);
+
+    // A file including itself is circular too
+    fs::write(root.join("metis.toml"), "includes = [\"metis.toml\"]\n")?;
+    let err = Settings::from_root(root.to_str().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("Circular include"), "{}", err);
+
+    // The same file included twice side by side is not circular
+    fs::write(root.join("metis.toml"), "includes = [\"c.toml\", \"c.toml\"]\n")?;
+    fs::write(root.join("c.toml"), "")?;
+    assert!(Settings::from_root(root.to_str().unwrap()).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_profile_overlays_base_config() -> anyhow::Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("config/tools"))?;
+    fs::create_dir_all(root.join("config/staging/tools"))?;
+
+    let metis_toml = r#"
+[server]
+host = "127.0.0.1"
+port = 4000
+
+[[prompts]]
+name = "greeting"
+description = "Base greeting"
+"#;
+    fs::write(root.join("metis.toml"), metis_toml)?;
+
+    // Only overrides the port and the prompt; everything else comes from the base
+    let staging_toml = r#"
+[server]
+host = "127.0.0.1"
+port = 4100
+
+[[prompts]]
+name = "greeting"
+description = "Staging greeting"
+"#;
+    fs::write(root.join("metis.staging.toml"), staging_toml)?;
+
+    let tool = |name: &str, description: &str| {
+        format!(
+            r#"{{"name": "{}", "description": "{}", "input_schema": {{}}, "mock": {{"strategy": "static"}}}}"#,
+            name, description
+        )
+    };
+    fs::write(root.join("config/tools/search.json"), tool("search", "Base search"))?;
+    fs::write(root.join("config/tools/lookup.json"), tool("lookup", "Base lookup"))?;
+    fs::write(root.join("config/staging/tools/search.json"), tool("search", "Staging search"))?;
+
+    let root_str = root.to_str().unwrap();
+    let settings = Settings::from_root_with_profile(root_str, Some("staging"))?;
+    assert_eq!(settings.profile.as_deref(), Some("staging"));
+    assert_eq!(settings.server.port, 4100);
+    assert_eq!(settings.prompts[0].description, "Staging greeting");
+
+    let mut tools: Vec<_> = settings.tools.iter().map(|t| (t.name.as_str(), t.description.as_str())).collect();
+    tools.sort();
+    assert_eq!(tools, [("lookup", "Base lookup"), ("search", "Staging search")]);
+
+    // Without a profile only the base config is loaded
+    let settings = Settings::from_root(root_str)?;
+    assert!(settings.profile.is_none());
+    assert_eq!(settings.server.port, 4000);
+    assert_eq!(settings.tools.len(), 2);
+
+    let err = Settings::from_root_with_profile(root_str, Some("prod")).unwrap_err();
+    assert!(err.to_string().contains("Profile 'prod' not found"), "{}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_items_across_files_are_rejected() -> anyhow::Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("config/tools"))?;
+    fs::create_dir_all(root.join("config/workflows"))?;
+
+    let metis_toml = r#"
+[server]
+host = "127.0.0.1"
+port = 3000
+
+[[tools]]
+name = "search"
+description = "From metis.toml"
+input_schema = {}
+static_response = {}
+"#;
+    fs::write(root.join("metis.toml"), metis_toml)?;
+
+    let tool = r#"{"name": "search", "description": "From a file", "input_schema": {}, "mock": {"strategy": "static"}}"#;
+    fs::write(root.join("config/tools/a.json"), tool)?;
+    fs::write(root.join("config/tools/b.json"), tool)?;
+
+    let workflow = "name: sync\ndescription: Sync\nsteps: []\n";
+    fs::write(root.join("config/workflows/sync.yaml"), workflow)?;
+    fs::write(root.join("config/workflows/sync_copy.yaml"), workflow)?;
+
+    let err = Settings::from_root(root.to_str().unwrap()).unwrap_err().to_string();
+    let path = |file: &str| root.join(file).display().to_string();
+    assert!(
+        err.contains(&format!(
+            "Tool name 'search' appears at indices 0, 1 and 2 (in {}, {} and {})",
+            path("metis.toml"),
+            path("config/tools/a.json"),
+            path("config/tools/b.json")
+        )),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains(&format!(
+            "Workflow name 'sync' appears at indices 0 and 1 (in {} and {})",
+            path("config/workflows/sync.yaml"),
+            path("config/workflows/sync_copy.yaml")
+        )),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
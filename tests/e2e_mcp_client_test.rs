@@ -14,8 +14,8 @@ use metis::adapters::{
     tool_handler::BasicToolHandler,
 };
 use metis::config::{
-    MockConfig, MockStrategyType, PromptArgument, PromptConfig, PromptMessage, ResourceConfig,
-    Settings, ToolConfig,
+    ErrorMockConfig, MockConfig, MockStrategyType, PromptArgument, PromptConfig, PromptMessage,
+    ResourceConfig, Settings, ToolConfig,
 };
 use rmcp::{
     model::{CallToolRequestParam, ClientCapabilities, ClientInfo, Implementation},
@@ -49,10 +49,37 @@ impl TestServer {
     ) -> Self {
         let settings = Arc::new(RwLock::new(Settings {
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             version: 0,
+            config_version: metis::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
             server: metis::config::ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 0,
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: metis::config::LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources,
@@ -60,6 +87,7 @@ impl TestServer {
             tools,
             prompts,
             rate_limit: None,
+            access_control: None,
             s3: None,
             workflows: vec![],
             agents: vec![],
@@ -69,6 +97,8 @@ impl TestServer {
             schemas: vec![],
             data_lakes: vec![],
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
         }));
 
@@ -83,14 +113,15 @@ impl TestServer {
         let metrics_handler = Arc::new(MetricsHandler::new(metrics_collector));
 
         // Create MetisServer using rmcp SDK
-        let metis_server = MetisServer::new(resource_handler, tool_handler.clone(), prompt_handler);
+        let metis_server = MetisServer::new(resource_handler, tool_handler.clone(), prompt_handler)
+            .with_settings(settings.clone());
 
         // Create test secrets store and passphrase store
         let secrets_store = metis::adapters::secrets::create_secrets_store();
         let passphrase_store = metis::adapters::secrets::create_passphrase_store();
 
         let app =
-            metis::create_app(metis_server, health_handler, metrics_handler, settings, state_manager, secrets_store, passphrase_store, tool_handler, None, None, None).await;
+            metis::create_app(metis_server, health_handler, metrics_handler, settings, state_manager, secrets_store, passphrase_store, tool_handler, None, None, None, Arc::new(metis::agents::llm::ProviderRegistry::new()), Arc::new(metis::agents::memory::InMemoryStore::new(100))).await;
 
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
         let addr = listener.local_addr().unwrap();
@@ -138,8 +169,17 @@ impl TestServer {
                 pattern: None,
                 llm: None,
                 database: None,
+                data_lake_crud: None,
+                callback: None,
+                matrix: None,
+                csv: None,
+                error_mock: None,
+                transform: vec![],
             }),
             tags: vec![],
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
         }];
 
         Self::with_config(vec![], tools, vec![]).await
@@ -165,6 +205,12 @@ impl TestServer {
                 pattern: None,
                 llm: None,
                 database: None,
+                data_lake_crud: None,
+                callback: None,
+                matrix: None,
+                csv: None,
+                error_mock: None,
+                transform: vec![],
             }),
             tags: vec![],
         }];
@@ -196,8 +242,17 @@ impl TestServer {
                 pattern: None,
                 llm: None,
                 database: None,
+                data_lake_crud: None,
+                callback: None,
+                matrix: None,
+                csv: None,
+                error_mock: None,
+                transform: vec![],
             }),
             tags: vec![],
+            dedup_window_ms: None,
+            normalize_response: None,
+            latency: None,
         }];
 
         let prompts = vec![PromptConfig {
@@ -267,8 +322,6 @@ async fn test_client_connect_and_initialize() {
             !info.server_info.name.is_empty(),
             "Server should have a name"
         );
-        // Protocol version is available - just verify we connected successfully
-        assert!(true, "Server should report protocol version");
     } else {
         panic!("Server info should be available after initialization");
     }
@@ -276,6 +329,20 @@ async fn test_client_connect_and_initialize() {
     client.cancel().await.unwrap();
 }
 
+#[tokio::test]
+async fn test_initialize_advertises_capabilities_in_use() {
+    let server = TestServer::with_sample_tools().await;
+    let client = create_client(&server).await.unwrap();
+
+    let info = client.peer_info().expect("server info after initialization");
+    assert_eq!(info.server_info.name, "metis-mock-server");
+    assert!(info.capabilities.tools.is_some());
+    assert!(info.capabilities.resources.is_none());
+    assert!(info.capabilities.prompts.is_none());
+
+    client.cancel().await.unwrap();
+}
+
 #[tokio::test]
 async fn test_list_tools_empty() {
     let server = TestServer::new().await;
@@ -357,8 +424,17 @@ async fn test_list_tools_with_configured_tools() {
             pattern: None,
             llm: None,
             database: None,
+            data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: None,
+            transform: vec![],
         }),
         tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
     }];
 
     let server = TestServer::with_config(vec![], tools, vec![]).await;
@@ -403,8 +479,17 @@ async fn test_call_tool() {
             pattern: None,
             llm: None,
             database: None,
+            data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: None,
+            transform: vec![],
         }),
         tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
     }];
 
     let server = TestServer::with_config(vec![], tools, vec![]).await;
@@ -422,6 +507,43 @@ async fn test_call_tool() {
     client.cancel().await.unwrap();
 }
 
+#[tokio::test]
+async fn test_call_tool_returns_normalized_envelope_content() {
+    let tools = vec![ToolConfig {
+        name: "status".to_string(),
+        description: "Report the service status".to_string(),
+        input_schema: json!({"type": "object"}),
+        output_schema: None,
+        static_response: Some(json!("All systems operational")),
+        mock: None,
+        tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: Some(true),
+        latency: None,
+    }];
+
+    let server = TestServer::with_config(vec![], tools, vec![]).await;
+    let client = create_client(&server).await.unwrap();
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "status".into(),
+            arguments: None,
+        })
+        .await
+        .unwrap();
+
+    // The envelope's items are the content, not the envelope serialized as text
+    let texts: Vec<&str> = result
+        .content
+        .iter()
+        .map(|item| item.as_text().expect("text content").text.as_str())
+        .collect();
+    assert_eq!(texts, vec!["All systems operational"]);
+
+    client.cancel().await.unwrap();
+}
+
 #[tokio::test]
 async fn test_call_nonexistent_tool() {
     let server = TestServer::new().await;
@@ -439,6 +561,65 @@ async fn test_call_nonexistent_tool() {
     client.cancel().await.unwrap();
 }
 
+#[tokio::test]
+async fn test_call_tool_with_error_mock_returns_configured_code() {
+    let tools = vec![ToolConfig {
+        name: "lookup".to_string(),
+        description: "Look up a record".to_string(),
+        input_schema: json!({"type": "object"}),
+        output_schema: None,
+        static_response: None,
+        mock: Some(MockConfig {
+            strategy: MockStrategyType::Template,
+            template: Some(r#"{"found": true}"#.to_string()),
+            faker_type: None,
+            faker_schema: None,
+            stateful: None,
+            script: None,
+            script_lang: None,
+            file: None,
+            pattern: None,
+            llm: None,
+            database: None,
+            data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: Some(ErrorMockConfig {
+                code: -32602,
+                message_template: "Unknown id {{ id }}".to_string(),
+                rate: 1.0,
+                codes: vec![],
+            }),
+            transform: vec![],
+        }),
+        tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
+    }];
+
+    let server = TestServer::with_config(vec![], tools, vec![]).await;
+    let client = create_client(&server).await.unwrap();
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "lookup".into(),
+            arguments: json!({"id": 42}).as_object().cloned(),
+        })
+        .await;
+
+    match result {
+        Err(rmcp::ServiceError::McpError(error)) => {
+            assert_eq!(error.code.0, -32602);
+            assert_eq!(error.message, "Unknown id 42");
+        }
+        other => panic!("Expected an MCP error, got {:?}", other),
+    }
+
+    client.cancel().await.unwrap();
+}
+
 // ============================================================================
 // Resource Tests
 // ============================================================================
@@ -464,6 +645,12 @@ async fn test_list_resources_with_configured_resources() {
             pattern: None,
             llm: None,
             database: None,
+            data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: None,
+            transform: vec![],
         }),
         tags: vec![],
     }];
@@ -506,6 +693,12 @@ async fn test_read_resource() {
             pattern: None,
             llm: None,
             database: None,
+            data_lake_crud: None,
+            callback: None,
+            matrix: None,
+            csv: None,
+            error_mock: None,
+            transform: vec![],
         }),
         tags: vec![],
     }];
@@ -0,0 +1,47 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_agent_confirmation_endpoints() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/agents"))
+        .json(&json!({
+            "name": "janitor",
+            "description": "Cleans up records",
+            "agent_type": "react",
+            "llm": {"provider": "ollama", "model": "llama3"},
+            "system_prompt": "You tidy up.",
+            "available_tools": ["delete_record"],
+            "require_confirmation_for": ["delete_*"],
+            "confirmation_timeout_ms": 30000
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let body: Value = client.get(server.url("/api/agents/janitor")).send().await.unwrap().json().await.unwrap();
+    assert_eq!(body["data"]["require_confirmation_for"], json!(["delete_*"]));
+    assert_eq!(body["data"]["confirmation_timeout_ms"], 30000);
+
+    let response = client.get(server.url("/api/agents/janitor/confirmations")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], json!([]));
+
+    let response = client
+        .post(server.url("/api/agents/janitor/confirmations/not-a-token"))
+        .json(&json!({"approved": true}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let response = client.get(server.url("/api/agents/nobody/confirmations")).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,49 @@
+use super::common;
+
+use common::test_server::TestServer;
+use metis::agents::config::AgentConfig;
+use serde_json::{json, Value};
+
+fn agent(name: &str, provider: &str) -> AgentConfig {
+    serde_json::from_value(json!({
+        "name": name,
+        "description": "Helps out",
+        "agent_type": "single_turn",
+        "llm": {"provider": provider, "model": "llama3"},
+        "system_prompt": "You help."
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_reload_registers_agents_added_to_config() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    // As a config file reload would
+    {
+        let mut settings = server.settings.write().await;
+        settings.agents.push(agent("helper", "ollama"));
+        settings.agents.push(agent("broken", "no_such_provider"));
+    }
+
+    let response = client.post(server.url("/api/agents/reload")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["loaded"], json!(["helper"]));
+    assert_eq!(body["data"]["failed"][0]["name"], "broken");
+    assert!(body["data"]["failed"][0]["error"].as_str().unwrap().contains("no_such_provider"));
+
+    // Reloading again rebuilds the same agents instead of adding to them
+    server.settings.write().await.agents.retain(|a| a.name != "broken");
+    let body: Value = client
+        .post(server.url("/api/agents/reload"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"]["loaded"], json!(["helper"]));
+    assert_eq!(body["data"]["failed"], json!([]));
+}
@@ -0,0 +1,99 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+fn session(id: &str, content: &str) -> Value {
+    json!({
+        "session_id": id,
+        "agent_name": "other_agent",
+        "messages": [
+            {"role": "user", "content": content},
+            {"role": "assistant", "content": "Noted."}
+        ],
+        "metadata": {"source": "backup"},
+        "created_at": 1700000000000u64,
+        "updated_at": 1700000005000u64
+    })
+}
+
+#[tokio::test]
+async fn test_export_and_import_agent_sessions() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/agents"))
+        .json(&json!({
+            "name": "support",
+            "description": "Support agent",
+            "llm": {"provider": "ollama", "model": "llama3"},
+            "system_prompt": "You help customers."
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let import = |on_conflict: &str, sessions: Value| {
+        client
+            .post(server.url(&format!("/api/agents/support/sessions/import?on_conflict={}", on_conflict)))
+            .json(&sessions)
+            .send()
+    };
+
+    let body: Value = import("skip", json!([session("s-1", "Hello"), session("s-2", "Hi")]))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"], json!({"imported": 2, "replaced": 0, "skipped": 0}));
+
+    let body: Value = import("skip", json!([session("s-1", "Changed")])).await.unwrap().json().await.unwrap();
+    assert_eq!(body["data"], json!({"imported": 0, "replaced": 0, "skipped": 1}));
+
+    let body: Value = import("replace", json!([session("s-2", "Changed")])).await.unwrap().json().await.unwrap();
+    assert_eq!(body["data"], json!({"imported": 0, "replaced": 1, "skipped": 0}));
+
+    // The export format is part of the API: keep this exact shape
+    let response = client
+        .get(server.url("/api/agents/support/sessions/export?format=json"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    let mut first = session("s-1", "Hello");
+    first["agent_name"] = json!("support");
+    let mut second = session("s-2", "Changed");
+    second["agent_name"] = json!("support");
+    assert_eq!(body["data"], json!([first, second]));
+
+    // Reloading agents rebuilds the handlers, but sessions are kept in one shared store
+    let response = client.post(server.url("/api/agents/reload")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = client
+        .get(server.url("/api/agents/support/sessions/export"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+
+    let response = client
+        .get(server.url("/api/agents/support/sessions/export?format=csv"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = client
+        .get(server.url("/api/agents/missing/sessions/export"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
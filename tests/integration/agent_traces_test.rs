@@ -0,0 +1,100 @@
+use super::common;
+
+use common::test_server::TestServer;
+use metis::agents::domain::{AgentResponse, ToolCallResult};
+use metis::agents::traces::AgentTraceStore;
+use metis::persistence::{DataStore, PersistenceConfig};
+use serde_json::{json, Value};
+
+fn tool_call(name: &str, input: Value, output: Value) -> ToolCallResult {
+    ToolCallResult {
+        tool_call_id: format!("call_{}", name),
+        tool_name: name.to_string(),
+        input,
+        output,
+        execution_time_ms: 3,
+        success: true,
+        error: None,
+    }
+}
+
+#[tokio::test]
+async fn test_agent_trace_store_round_trip() {
+    let config = PersistenceConfig {
+        url: "sqlite::memory:".to_string(),
+        max_connections: 1,
+        ..Default::default()
+    };
+    let store = DataStore::new(&config).await.unwrap();
+    store.migrate().await.unwrap();
+    let traces = AgentTraceStore::new(store.records().clone());
+
+    let response = AgentResponse {
+        output: json!("It is sunny"),
+        tool_calls: vec![tool_call("get_weather", json!({"city": "Oslo"}), json!({"sky": "clear"}))],
+        iterations: 2,
+        ..Default::default()
+    };
+    let first = traces
+        .record("forecaster", &json!({"prompt": "Weather?"}), Some("s1".to_string()), &response)
+        .await
+        .unwrap();
+    traces
+        .record("forecaster", &json!({"prompt": "Again?"}), Some("s2".to_string()), &AgentResponse::default())
+        .await
+        .unwrap();
+    traces
+        .record("other", &json!({"prompt": "Hi"}), Some("s1".to_string()), &response)
+        .await
+        .unwrap();
+
+    let all = traces.list("forecaster", None, 20).await.unwrap();
+    assert_eq!(all.len(), 2);
+
+    let in_session = traces.list("forecaster", Some("s1"), 20).await.unwrap();
+    assert_eq!(in_session.len(), 1);
+    assert_eq!(in_session[0].id, first);
+    assert_eq!(in_session[0].tools, vec!["get_weather".to_string()]);
+
+    assert_eq!(traces.list("forecaster", None, 1).await.unwrap().len(), 1);
+
+    let trace = traces.get("forecaster", &first).await.unwrap().unwrap();
+    assert_eq!(trace.session_id.as_deref(), Some("s1"));
+    assert_eq!(trace.input, json!({"prompt": "Weather?"}));
+    assert_eq!(trace.iterations, 2);
+    assert_eq!(trace.tool_calls[0].input, json!({"city": "Oslo"}));
+    assert_eq!(trace.tool_calls[0].output, json!({"sky": "clear"}));
+
+    // A trace is only visible under the agent that produced it
+    assert!(traces.get("other", &first).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_agent_trace_endpoints_without_database() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/agents"))
+        .json(&json!({
+            "name": "forecaster",
+            "description": "Looks up the weather",
+            "agent_type": "react",
+            "llm": {"provider": "ollama", "model": "llama3"},
+            "system_prompt": "You forecast.",
+            "available_tools": ["get_weather"]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = client.get(server.url("/api/agents/forecaster/traces?limit=5")).send().await.unwrap();
+    assert_eq!(response.status(), 503);
+
+    let response = client.get(server.url("/api/agents/nobody/traces")).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    let response = client.get(server.url("/api/agents/nobody/traces/abc")).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
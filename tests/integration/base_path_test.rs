@@ -0,0 +1,75 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_routes_resolve_under_base_path() {
+    let server = TestServer::with_settings(|settings| {
+        settings.server.base_path = Some("/metis/".to_string());
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(server.url("/metis/health")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client.get(server.url("/metis/api/tools")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["success"], true);
+
+    let response = client.get(server.url("/metis/api/config/schema")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Nothing is left at the root, not even the web UI
+    for path in ["/api/tools", "/health", "/"] {
+        let response = client.get(server.url(path)).send().await.unwrap();
+        assert_eq!(response.status(), 404, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn test_health_checks_can_stay_at_root() {
+    let server = TestServer::with_settings(|settings| {
+        settings.server.base_path = Some("/metis".to_string());
+        settings.server.health_at_root = true;
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    for path in ["/health", "/health/live", "/metis/health"] {
+        let response = client.get(server.url(path)).send().await.unwrap();
+        assert_eq!(response.status(), 200, "{}", path);
+    }
+    let response = client.get(server.url("/api/tools")).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_web_ui_loads_under_base_path() {
+    let server = TestServer::with_settings(|settings| {
+        settings.server.base_path = Some("/metis".to_string());
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    // Client-side routes get the page, pointed at the prefix
+    let response = client.get(server.url("/metis/tools")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let page = response.text().await.unwrap();
+    assert!(page.contains(r#"<meta name="metis-base-path" content="/metis" />"#), "{}", page);
+    assert!(page.contains(r#"<base href="/metis/" />"#), "{}", page);
+
+    // The stylesheet the page links to is served under the prefix
+    let stylesheet = page
+        .split("href=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .find(|href| href.ends_with(".css"))
+        .unwrap();
+    assert!(stylesheet.starts_with("/metis/"), "{}", stylesheet);
+    let response = client.get(server.url(stylesheet)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/css");
+}
@@ -0,0 +1,44 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_clone_tool() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/tools"))
+        .json(&json!({
+            "name": "get_weather",
+            "description": "Current weather",
+            "tags": ["weather"],
+            "input_schema": {"type": "object", "properties": {"city": {"type": "string"}}},
+            "static_response": {"temp": 21}
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let clone = |source: &str, new_name: &str| {
+        client
+            .post(server.url(&format!("/api/tools/{}/clone", source)))
+            .json(&json!({"new_name": new_name}))
+            .send()
+    };
+
+    let response = clone("get_weather", "get_forecast").await.unwrap();
+    assert_eq!(response.status(), 201);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["name"], "get_forecast");
+    assert_eq!(body["data"]["description"], "Current weather");
+    assert_eq!(body["data"]["tags"], json!(["weather"]));
+    assert_eq!(body["data"]["static_response"], json!({"temp": 21}));
+    assert!(server.settings.read().await.tools.iter().any(|t| t.name == "get_forecast"));
+
+    assert_eq!(clone("get_weather", "get_forecast").await.unwrap().status(), 409);
+    assert_eq!(clone("missing_tool", "anything").await.unwrap().status(), 404);
+    assert_eq!(clone("get_weather", " ").await.unwrap().status(), 400);
+}
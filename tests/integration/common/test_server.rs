@@ -10,22 +10,43 @@ use metis::adapters::{
     secrets::{SecretsStore, PassphraseStore},
 };
 use metis::config::Settings;
-use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub struct TestServer {
-    pub addr: SocketAddr,
     pub base_url: String,
+    /// Live settings of the running server
+    pub settings: Arc<RwLock<Settings>>,
 }
 
 impl TestServer {
     pub async fn new() -> Self {
+        Self::with_settings(|_| {}).await
+    }
+
+    /// Start a server with the test configuration as changed by `configure`,
+    /// for settings only read when the app is built
+    pub async fn with_settings(configure: impl FnOnce(&mut Settings)) -> Self {
         // Create test configuration
-        let settings = Arc::new(RwLock::new(Settings {
+        let mut settings = Settings {
             server: metis::config::ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 0, // Random port
+                http_mock_mode: false,
+                config_history_depth: 50,
+                log_format: metis::config::LogFormat::Text,
+                log_level: "info".to_string(),
+                log_file: None,
+                normalize_all_responses: false,
+                max_response_bytes: None,
+                max_concurrent_tool_calls: None,
+                tool_call_queue_timeout_ms: None,
+                max_call_depth: 10,
+                base_path: None,
+                health_at_root: false,
+                idempotency_ttl_seconds: 86_400,
+                config_backups: 5,
+                big_integers_as_strings: false,
             },
             auth: Default::default(),
             resources: vec![],
@@ -39,13 +60,30 @@ impl TestServer {
             data_lakes: vec![],
             secrets: Default::default(),
             rate_limit: None,
+            access_control: None,
             s3: None,
             database: None,
+            databases: Default::default(),
+            mcp: Default::default(),
             file_storage: None,
             config_path: None,
+            sources: Default::default(),
+            profile: None,
             mcp_servers: vec![],
             version: 1,
-        }));
+            config_version: metis::config::migrations::CURRENT_VERSION,
+            generation_provider: None,
+            pricing: vec![],
+            agent_defaults: Default::default(),
+            default_tool_response: Default::default(),
+            includes: vec![],
+            validate_on_write: true,
+            tool_aliases: Default::default(),
+            custom_fakers: Default::default(),
+            features: Default::default(),
+        };
+        configure(&mut settings);
+        let settings = Arc::new(RwLock::new(settings));
 
         // Initialize handlers
         let state_manager = Arc::new(StateManager::new());
@@ -67,21 +105,24 @@ impl TestServer {
         let passphrase_store = Arc::new(PassphraseStore::new());
 
         // Create MetisServer using rmcp SDK
-        let metis_server = MetisServer::new(resource_handler, tool_handler.clone(), prompt_handler);
+        let metis_server = MetisServer::new(resource_handler, tool_handler.clone(), prompt_handler)
+            .with_settings(settings.clone());
 
         // Create app
         let app = metis::create_app(
             metis_server,
             health_handler,
             metrics_handler,
-            settings,
+            settings.clone(),
             state_manager,
             secrets_store,
             passphrase_store,
             tool_handler,
             None,
             None,
-            None
+            None,
+            Arc::new(metis::agents::llm::ProviderRegistry::new()),
+            Arc::new(metis::agents::memory::InMemoryStore::new(100)),
         ).await;
 
         // Start server on random port
@@ -96,7 +137,7 @@ impl TestServer {
         // Wait for server to be ready
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-        TestServer { addr, base_url }
+        TestServer { base_url, settings }
     }
 
     pub fn url(&self, path: &str) -> String {
@@ -0,0 +1,151 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_save_backs_up_config_and_restores_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("metis.toml");
+    // Saved files are validated on restore, which the test server's port 0 would fail
+    let server = TestServer::with_settings(|s| {
+        s.config_path = Some(config_path.clone());
+        s.server.port = 3000;
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let has_tool = |settings: &metis::config::Settings| settings.tools.iter().any(|t| t.name == "backup_me");
+
+    // The first save has no file to back up
+    let response = client.post(server.url("/api/config/save-disk")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let response = client.get(server.url("/api/config/backups")).send().await.unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], json!([]));
+
+    let response = client
+        .post(server.url("/api/tools"))
+        .json(&json!({
+            "name": "backup_me",
+            "description": "Tool added after the first save",
+            "input_schema": {"type": "object"},
+            "static_response": {"ok": true}
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let response = client.post(server.url("/api/config/save-disk")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(std::fs::read_to_string(&config_path).unwrap().contains("backup_me"));
+
+    let response = client.get(server.url("/api/config/backups")).send().await.unwrap();
+    let body: Value = response.json().await.unwrap();
+    let backups = body["data"].as_array().unwrap();
+    assert_eq!(backups.len(), 1);
+    let name = backups[0]["name"].as_str().unwrap().to_string();
+    assert!(name.starts_with("metis.toml.bak."));
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let response = client
+        .post(server.url("/api/config/restore-backup"))
+        .json(&json!({"name": name}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200, "{}", response.text().await.unwrap());
+    assert!(!has_tool(&*server.settings.read().await));
+    assert!(!std::fs::read_to_string(&config_path).unwrap().contains("backup_me"));
+
+    // The replaced file was backed up too, so the restore can be reverted
+    let response = client.get(server.url("/api/config/backups")).send().await.unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+
+    let response = client
+        .post(server.url("/api/config/restore-backup"))
+        .json(&json!({"name": "metis.toml"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_restore_loads_backup_like_the_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("metis.yaml");
+    std::fs::write(&config_path, "server:\n  host: 127.0.0.1\n  port: 3000\n").unwrap();
+    std::fs::create_dir_all(dir.path().join("config/tools")).unwrap();
+    std::fs::write(
+        dir.path().join("config/tools/from_dir.json"),
+        r#"{"name": "from_dir", "description": "Tool file", "input_schema": {"type": "object"}, "static_response": {}}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("metis.yaml.bak.20260101T000000.000Z"),
+        concat!(
+            "server:\n  host: 127.0.0.1\n  port: 3000\n",
+            "tools:\n  - name: from_backup\n    description: Tool in the backup\n",
+            "    input_schema:\n      type: object\n    static_response: {}\n",
+        ),
+    )
+    .unwrap();
+    let server = TestServer::with_settings(|s| s.config_path = Some(config_path.clone())).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/config/restore-backup"))
+        .json(&json!({"name": "metis.yaml.bak.20260101T000000.000Z"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200, "{}", response.text().await.unwrap());
+
+    // The YAML backup replaced the main file, and the config directory was loaded with it
+    let settings = server.settings.read().await;
+    let mut tools: Vec<&str> = settings.tools.iter().map(|t| t.name.as_str()).collect();
+    tools.sort();
+    assert_eq!(tools, vec!["from_backup", "from_dir"]);
+}
+
+#[tokio::test]
+async fn test_restore_oldest_backup_at_capacity() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("metis.yaml");
+    std::fs::write(&config_path, "server:\n  host: 127.0.0.1\n  port: 3000\n").unwrap();
+    let oldest = "metis.yaml.bak.20260101T000000.000Z";
+    std::fs::write(
+        dir.path().join(oldest),
+        concat!(
+            "server:\n  host: 127.0.0.1\n  port: 3000\n",
+            "tools:\n  - name: from_oldest\n    description: Tool in the oldest backup\n",
+            "    input_schema:\n      type: object\n    static_response: {}\n",
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("metis.yaml.bak.20260102T000000.000Z"),
+        "server:\n  host: 127.0.0.1\n  port: 3000\n",
+    )
+    .unwrap();
+    let server = TestServer::with_settings(|s| {
+        s.config_path = Some(config_path.clone());
+        s.server.config_backups = 2;
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    // Backing up the current file rotates the oldest backup out
+    let response = client
+        .post(server.url("/api/config/restore-backup"))
+        .json(&json!({"name": oldest}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200, "{}", response.text().await.unwrap());
+
+    assert!(std::fs::read_to_string(&config_path).unwrap().contains("from_oldest"));
+    assert!(server.settings.read().await.tools.iter().any(|t| t.name == "from_oldest"));
+    assert!(!dir.path().join(oldest).exists());
+}
@@ -0,0 +1,103 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_effective_config_expands_refs_and_applies_overrides() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let schema = json!({
+        "name": "Greeting",
+        "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+    });
+    let response = client.post(server.url("/api/schemas")).json(&schema).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let tool = json!({
+        "name": "greet",
+        "description": "Greet someone",
+        "input_schema": {"$ref": "Greeting"},
+        "static_response": {"greeting": "hello"}
+    });
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let response = client
+        .post(server.url("/api/tools/greet/override"))
+        .json(&json!({"value": {"greeting": "overridden"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body: Value = client
+        .get(server.url("/api/config/effective"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let tool = &body["data"]["tools"][0];
+    assert_eq!(tool["input_schema"]["properties"]["name"]["type"], "string");
+    assert_eq!(tool["static_response"], json!({"greeting": "overridden"}));
+
+    // The live config keeps the reference and the configured response
+    let settings = server.settings.read().await;
+    assert_eq!(settings.tools[0].input_schema, json!({"$ref": "Greeting"}));
+    assert_eq!(settings.tools[0].static_response, Some(json!({"greeting": "hello"})));
+}
+
+#[tokio::test]
+async fn test_effective_config_negotiates_format() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(server.url("/api/config/effective"))
+        .header("Accept", "application/toml")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/toml");
+    let settings: metis::config::Settings = toml::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(settings.server.host, "127.0.0.1");
+}
+
+#[tokio::test]
+async fn test_effective_config_redacts_credentials() {
+    let server = TestServer::with_settings(|settings| {
+        settings.auth.jwt_secret = Some("signing-key".to_string());
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let tool = json!({
+        "name": "lookup",
+        "description": "Look up a user",
+        "input_schema": {"type": "object"},
+        "mock": {
+            "strategy": "database",
+            "database": {"url": "postgres://app:hunter2@db:5432/app", "query": "SELECT 1"}
+        }
+    });
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let body: Value = client
+        .get(server.url("/api/config/effective"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"]["auth"]["jwt_secret"], "[REDACTED]");
+    assert_eq!(
+        body["data"]["tools"][0]["mock"]["database"]["url"],
+        "postgres://app:[REDACTED]@db:5432/app"
+    );
+}
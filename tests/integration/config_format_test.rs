@@ -0,0 +1,52 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_config_export_negotiates_format() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let tool = json!({
+        "name": "echo",
+        "description": "Echo",
+        "input_schema": {"type": "object", "properties": {"text": {"type": "string", "default": null}}},
+        "static_response": {"ok": true}
+    });
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let response = client
+        .get(server.url("/api/config/export"))
+        .header("Accept", "application/yaml")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/yaml");
+    let yaml: Value = serde_yaml::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(yaml["tools"][0]["name"], "echo");
+
+    let response = client
+        .get(server.url("/api/config/export"))
+        .header("Accept", "application/toml")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/toml");
+    let settings: metis::config::Settings = toml::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(settings.tools[0].name, "echo");
+
+    // JSON stays the default, wrapped in the usual envelope
+    let body: Value = client
+        .get(server.url("/api/config"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"]["tools_count"], 1);
+}
@@ -0,0 +1,47 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_undo_and_redo_config_edits() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+    let tool_names = |settings: &metis::config::Settings| {
+        settings.tools.iter().map(|t| t.name.clone()).collect::<Vec<_>>()
+    };
+    let initial_tools = tool_names(&*server.settings.read().await);
+
+    let response = client
+        .post(server.url("/api/tools"))
+        .json(&json!({
+            "name": "undo_me",
+            "description": "Tool created to be undone",
+            "input_schema": {"type": "object"},
+            "static_response": {"ok": true}
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    assert!(tool_names(&*server.settings.read().await).contains(&"undo_me".to_string()));
+
+    let response = client.post(server.url("/api/config/undo")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], json!({"undo_depth": 0, "redo_depth": 1}));
+    assert_eq!(tool_names(&*server.settings.read().await), initial_tools);
+
+    let response = client.post(server.url("/api/config/undo")).send().await.unwrap();
+    assert_eq!(response.status(), 409);
+
+    let response = client.post(server.url("/api/config/redo")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(tool_names(&*server.settings.read().await).contains(&"undo_me".to_string()));
+
+    // Requests that don't change the config leave the history alone
+    client.post(server.url("/api/tools/undo_me/test")).json(&json!({"args": {}})).send().await.unwrap();
+    let response = client.post(server.url("/api/config/undo")).send().await.unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], json!({"undo_depth": 0, "redo_depth": 1}));
+}
@@ -0,0 +1,39 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_config_schema_lists_nested_types() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(server.url("/api/config/schema")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let schema: Value = response.json().await.unwrap();
+
+    assert_eq!(schema["title"], "Settings");
+    assert_eq!(schema["properties"]["tools"]["items"]["$ref"], "#/$defs/ToolConfig");
+    assert_eq!(schema["$defs"]["ToolConfig"]["properties"]["name"]["type"], "string");
+    assert!(schema["$defs"]["AgentConfig"].is_object());
+    assert!(schema["$defs"]["MockConfig"].is_object());
+    // Doc comments become descriptions
+    assert_eq!(
+        schema["properties"]["validate_on_write"]["description"],
+        "Validate items written through the API before applying them"
+    );
+}
+
+#[tokio::test]
+async fn test_config_schema_inlines_refs() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(server.url("/api/config/schema?inline=true")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let schema: Value = response.json().await.unwrap();
+
+    let tool = &schema["properties"]["tools"]["items"];
+    assert!(tool.get("$ref").is_none());
+    assert!(tool["properties"]["name"].is_object());
+}
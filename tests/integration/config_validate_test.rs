@@ -0,0 +1,46 @@
+use super::common;
+
+use common::test_server::TestServer;
+use metis::config::ToolConfig;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_validate_config_reports_structured_errors() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    server.settings.write().await.tools.push(ToolConfig {
+        name: "lookup".to_string(),
+        description: String::new(),
+        input_schema: json!({"type": "object"}),
+        output_schema: None,
+        static_response: Some(json!({"ok": true})),
+        mock: None,
+        tags: vec![],
+        dedup_window_ms: None,
+        normalize_response: None,
+        latency: None,
+    });
+
+    let response = client.get(server.url("/api/config/validate")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    let report = &body["data"];
+    assert_eq!(report["valid"], false);
+
+    let errors = report["errors"].as_array().unwrap();
+    let tool_error = errors
+        .iter()
+        .find(|e| e["archetype"] == "tool")
+        .expect("an error attributed to the tool");
+    assert_eq!(
+        tool_error,
+        &json!({
+            "kind": "missing_field",
+            "archetype": "tool",
+            "item": "lookup",
+            "field": "tools[0].description",
+            "message": "Missing required field"
+        })
+    );
+}
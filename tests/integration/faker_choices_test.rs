@@ -0,0 +1,45 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_faker_choices_for_nested_schema() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/faker/choices"))
+        .json(&json!({
+            "schema": {
+                "type": "object",
+                "properties": {
+                    "users": {
+                        "type": "array",
+                        "minItems": 2,
+                        "maxItems": 4,
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "email": {"type": "string", "format": "email"},
+                                "age": {"type": "integer"}
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+
+    let fields = body["data"]["fields"].as_array().unwrap();
+    let email = fields.iter().find(|f| f["path"] == "users[*].email").unwrap();
+    assert_eq!(email["faker_types"], json!(["email"]));
+    let age = fields.iter().find(|f| f["path"] == "users[*].age").unwrap();
+    assert_eq!(age["schema_type"], "integer");
+    assert_eq!(age["faker_types"][0], "integer");
+    assert_eq!(body["data"]["arrays"]["users"], json!({"min_items": 2, "max_items": 4}));
+}
@@ -0,0 +1,42 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_disabled_features_answer_not_found() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(server.url("/api/agents")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Turned off at runtime, e.g. by a config reload
+    {
+        let mut settings = server.settings.write().await;
+        settings.features.agents = false;
+        settings.features.data_lake = false;
+    }
+
+    for path in ["/api/agents", "/api/orchestrations", "/api/data-lakes"] {
+        let response = client.get(server.url(path)).send().await.unwrap();
+        assert_eq!(response.status(), 404, "{}", path);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["success"], false);
+    }
+    let response = client.get(server.url("/api/tools")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_admin_api_off_leaves_api_unmounted() {
+    let server = TestServer::with_settings(|s| s.features.admin_api = false).await;
+    let client = reqwest::Client::new();
+
+    for path in ["/api/tools", "/api/config/schema", "/"] {
+        let response = client.get(server.url(path)).send().await.unwrap();
+        assert_eq!(response.status(), 404, "{}", path);
+    }
+    let response = client.get(server.url("/health")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+}
@@ -70,3 +70,21 @@ async fn test_metrics_endpoint() {
     // Should contain Prometheus metrics
     assert!(body.contains("metis_"));
 }
+
+#[tokio::test]
+async fn test_request_id_is_echoed() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(server.url("/health"))
+        .header("x-request-id", "req-42")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-request-id"], "req-42");
+
+    // Requests without one get a generated ID
+    let response = client.get(server.url("/health")).send().await.unwrap();
+    assert!(!response.headers()["x-request-id"].is_empty());
+}
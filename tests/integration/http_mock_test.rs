@@ -0,0 +1,128 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_http_mock_mode_exposes_rest_endpoints() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let tool = json!({
+        "name": "greet",
+        "description": "Greet someone",
+        "input_schema": {"type": "object", "properties": {"name": {"type": "string"}}},
+        "static_response": null,
+        "mock": {"strategy": "template", "template": "{\"greeting\": \"Hello, {{ name }}!\"}"}
+    });
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    // Disabled by default: the path belongs to the web UI
+    let response = client.post(server.url("/tools/greet")).json(&json!({"name": "Ada"})).send().await.unwrap();
+    assert_ne!(response.headers()["content-type"], "application/json");
+
+    server.settings.write().await.server.http_mock_mode = true;
+
+    let response = client.post(server.url("/tools/greet")).json(&json!({"name": "Ada"})).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body, json!({"greeting": "Hello, Ada!"}));
+
+    let response = client.post(server.url("/tools/missing")).json(&json!({})).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    // Tools removed through the API disappear from the REST endpoints too
+    let response = client.delete(server.url("/api/tools/greet")).send().await.unwrap();
+    assert!(response.status().is_success());
+    let response = client.post(server.url("/tools/greet")).json(&json!({})).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    // UI pages under the same prefixes keep working
+    let response = client.get(server.url("/tools/new")).send().await.unwrap();
+    assert_ne!(response.status(), 405);
+}
+
+#[tokio::test]
+async fn test_http_mock_mode_serves_resources_and_prompts() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let resource = json!({
+        "uri": "docs/readme",
+        "name": "Readme",
+        "mime_type": "text/markdown",
+        "content": "# Hello"
+    });
+    let response = client.post(server.url("/api/resources")).json(&resource).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let prompt = json!({
+        "name": "review",
+        "description": "Review code",
+        "arguments": [{"name": "language", "required": true}],
+        "messages": [{"role": "user", "content": "Review this code"}]
+    });
+    let response = client.post(server.url("/api/prompts")).json(&prompt).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    server.settings.write().await.server.http_mock_mode = true;
+
+    let response = client.get(server.url("/resources/docs/readme")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/markdown");
+    assert_eq!(response.text().await.unwrap(), "# Hello");
+
+    let response = client.get(server.url("/prompts/review?language=Rust")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["description"], "Review code");
+    assert_eq!(body["messages"][0]["content"]["text"], "Review this code");
+
+    // Unknown prompts fall through to the web UI
+    let response = client.get(server.url("/prompts/new")).send().await.unwrap();
+    assert_ne!(response.headers()["content-type"], "application/json");
+}
+
+#[tokio::test]
+async fn test_http_mock_mode_streams_file_resources() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let path = std::env::temp_dir().join("metis_http_mock_stream.csv");
+    let rows: String = (0..10_000).map(|i| format!("{},user{}\n", i, i)).collect();
+    let content = format!("id,name\n{}", rows);
+    std::fs::write(&path, &content).unwrap();
+
+    let resource = json!({
+        "uri": "exports/users",
+        "name": "Users export",
+        "mime_type": "text/csv",
+        "mock": {"strategy": "file", "file": {"path": path, "stream": true}}
+    });
+    let response = client.post(server.url("/api/resources")).json(&resource).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+    let limited = json!({
+        "uri": "exports/limited",
+        "name": "Limited export",
+        "mock": {"strategy": "file", "file": {"path": path, "stream": true, "max_bytes": 1024}}
+    });
+    let response = client.post(server.url("/api/resources")).json(&limited).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    server.settings.write().await.server.http_mock_mode = true;
+
+    let response = client.get(server.url("/resources/exports/users")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/csv");
+    assert_eq!(response.headers()["content-length"], content.len().to_string());
+    assert_eq!(response.text().await.unwrap(), content);
+
+    let response = client.get(server.url("/resources/exports/limited")).send().await.unwrap();
+    assert_eq!(response.status(), 500);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("over the 1024 byte limit"));
+
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,39 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_retried_post_with_idempotency_key_creates_once() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+    let tool = json!({"name": "once", "description": "Created once", "input_schema": {}, "static_response": null});
+
+    let post = |body: &Value| {
+        client
+            .post(server.url("/api/tools"))
+            .header("Idempotency-Key", "create-once")
+            .json(body)
+            .send()
+    };
+
+    let first = post(&tool).await.unwrap();
+    assert_eq!(first.status(), 201);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+    let first_body: Value = first.json().await.unwrap();
+
+    // The retry gets the original response instead of a duplicate-name conflict
+    let retry = post(&tool).await.unwrap();
+    assert_eq!(retry.status(), 201);
+    assert_eq!(retry.headers()["idempotent-replayed"], "true");
+    assert_eq!(retry.json::<Value>().await.unwrap(), first_body);
+    assert_eq!(server.settings.read().await.tools.iter().filter(|t| t.name == "once").count(), 1);
+
+    let mut other = tool.clone();
+    other["description"] = json!("Something else");
+    assert_eq!(post(&other).await.unwrap().status(), 422);
+
+    // Without a key, the same request is a duplicate
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 409);
+}
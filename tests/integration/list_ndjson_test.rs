@@ -0,0 +1,32 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_list_tools_streams_ndjson() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    for name in ["alpha", "beta"] {
+        let tool = json!({"name": name, "description": name, "input_schema": {}, "static_response": null});
+        let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+        assert_eq!(response.status(), 201);
+    }
+
+    let response = client
+        .get(server.url("/api/tools"))
+        .header("Accept", "application/x-ndjson")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+
+    let body = response.text().await.unwrap();
+    let names: Vec<String> = body
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["alpha", "beta"]);
+}
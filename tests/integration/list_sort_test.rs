@@ -0,0 +1,42 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+async fn tool_names(client: &reqwest::Client, url: String) -> Vec<String> {
+    let body: Value = client.get(url).send().await.unwrap().json().await.unwrap();
+    body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_order_is_stable() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    for name in ["gamma", "alpha", "beta"] {
+        let tool = json!({"name": name, "description": name, "input_schema": {}, "static_response": null});
+        let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+        assert_eq!(response.status(), 201);
+    }
+
+    // Name ascending by default, the same on every call
+    for _ in 0..3 {
+        assert_eq!(tool_names(&client, server.url("/api/tools")).await, ["alpha", "beta", "gamma"]);
+    }
+    assert_eq!(
+        tool_names(&client, server.url("/api/tools?sort=name")).await,
+        ["alpha", "beta", "gamma"]
+    );
+    assert_eq!(
+        tool_names(&client, server.url("/api/tools?sort=created")).await,
+        ["gamma", "alpha", "beta"]
+    );
+
+    let response = client.get(server.url("/api/tools?sort=size")).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+}
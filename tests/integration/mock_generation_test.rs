@@ -0,0 +1,20 @@
+use super::common;
+
+use common::test_server::TestServer;
+use metis::adapters::mock_generator::REQUESTS_PER_MINUTE;
+
+#[tokio::test]
+async fn test_requests_for_unknown_tools_keep_the_rate_limit() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    // None of these reach the LLM, so none of them uses up the limit
+    for _ in 0..=REQUESTS_PER_MINUTE {
+        let response = client
+            .post(server.url("/api/tools/missing/generate-mock"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+    }
+}
@@ -1,3 +1,30 @@
+pub mod agent_confirmations_test;
+pub mod agent_reload_test;
+pub mod agent_sessions_test;
+pub mod agent_traces_test;
+pub mod base_path_test;
+pub mod clone_test;
 pub mod common;
+pub mod config_backup_test;
+pub mod config_effective_test;
+pub mod config_format_test;
+pub mod config_schema_test;
+pub mod config_history_test;
+pub mod config_validate_test;
+pub mod faker_choices_test;
+pub mod features_test;
 pub mod health_test;
+pub mod http_mock_test;
+pub mod idempotency_test;
+pub mod list_ndjson_test;
+pub mod list_sort_test;
+pub mod mock_generation_test;
 pub mod resource_tags_test;
+pub mod search_test;
+pub mod state_bulk_test;
+pub mod state_export_test;
+pub mod strategy_catalog_test;
+pub mod strategy_preview_test;
+pub mod tool_test_assertions_test;
+pub mod validate_on_write_test;
+pub mod workflow_output_test;
@@ -2,7 +2,6 @@ use super::common;
 
 use common::test_server::TestServer;
 use metis::adapters::api_handler::{ApiResponse, ResourceDto};
-use serde_json::json;
 
 #[tokio::test]
 async fn test_resource_tags_persistence() {
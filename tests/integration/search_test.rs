@@ -0,0 +1,38 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_search_ranks_name_matches_first() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    for (name, description) in [("forecast", "Weather forecast"), ("weather", "Current conditions")] {
+        let tool = json!({"name": name, "description": description, "input_schema": {}, "static_response": {}});
+        let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+        assert_eq!(response.status(), 201);
+    }
+    let resource = json!({"uri": "file:///weather.md", "name": "Guide", "description": "WEATHER guide", "content": "..."});
+    let response = client.post(server.url("/api/resources")).json(&resource).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let body: Value = client.get(server.url("/api/search?q=weather")).send().await.unwrap().json().await.unwrap();
+    let names: Vec<&str> = body["data"].as_array().unwrap().iter().map(|r| r["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["weather", "Guide", "forecast"]);
+    assert_eq!(body["data"][1]["type"], "resource");
+    assert_eq!(body["data"][1]["uri"], "file:///weather.md");
+
+    let body: Value = client
+        .get(server.url("/api/search?q=weather&types=resource"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+
+    let response = client.get(server.url("/api/search?q=weather&types=agent")).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+}
@@ -0,0 +1,49 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_bulk_state_operations_and_prefix_filter() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/state/bulk"))
+        .json(&json!({"operations": [
+            {"key": "user_count", "operation": "set", "value": 10},
+            {"key": "user_count", "operation": "increment"},
+            {"key": "orders", "operation": "set", "value": []}
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], json!({"applied": 3, "errors": []}));
+
+    // An invalid operation rejects the whole batch
+    let response = client
+        .post(server.url("/api/state/bulk"))
+        .json(&json!({"operations": [
+            {"key": "user_name", "operation": "set", "value": "ada"},
+            {"key": "user_count", "operation": "set"}
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["applied"], 0);
+    assert_eq!(body["data"]["errors"].as_array().unwrap().len(), 1);
+
+    let body: Value = client
+        .get(server.url("/api/state?prefix=user_"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"], json!({"user_count": 11}));
+}
@@ -0,0 +1,56 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_state_export_round_trips_through_import() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/state/bulk"))
+        .json(&json!({"operations": [
+            {"key": "visits", "operation": "set", "value": 3},
+            {"key": "cart", "operation": "set", "value": {"items": ["book"]}}
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let exported: Value = client
+        .get(server.url("/api/state/export"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let snapshot = exported["data"].clone();
+    assert_eq!(snapshot["entries"], json!({"visits": 3, "cart": {"items": ["book"]}}));
+
+    // Merging keeps keys missing from the import
+    let response = client
+        .post(server.url("/api/state/import"))
+        .json(&json!({"entries": {"visits": 10, "user": "ada"}, "mode": "merge"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["data"], json!({"imported": 2, "total": 3}));
+
+    // Replacing restores the snapshot exactly
+    let response = client.post(server.url("/api/state/import")).json(&snapshot).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = client
+        .get(server.url("/api/state"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"], json!({"visits": 3, "cart": {"items": ["book"]}}));
+}
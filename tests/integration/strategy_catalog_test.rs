@@ -0,0 +1,25 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_list_strategies() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(server.url("/api/strategies")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    let strategies = body["data"].as_array().unwrap();
+    assert_eq!(strategies.len(), 13);
+
+    let database = strategies.iter().find(|s| s["name"] == "database").unwrap();
+    assert_eq!(database["requires_external"], true);
+    assert_eq!(database["min_complexity"], "high");
+    assert_eq!(database["config_schema"]["properties"]["database"]["properties"]["query"]["type"], "string");
+
+    let template = strategies.iter().find(|s| s["name"] == "template").unwrap();
+    assert_eq!(template["requires_external"], false);
+    assert_eq!(template["config_schema"]["required"][0], "template");
+}
@@ -0,0 +1,36 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_preview_strategy_without_saving() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+    let tools_before = server.settings.read().await.tools.len();
+
+    let preview = |body: Value| client.post(server.url("/api/strategies/preview")).json(&body).send();
+
+    let response = preview(json!({
+        "mock": {"strategy": "template", "template": "{\"greeting\": \"Hello, {{ name }}!\"}"},
+        "args": {"name": "Ada"}
+    }))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["data"]["error"].is_null(), "{}", body);
+    assert_eq!(body["data"]["output"]["greeting"], "Hello, Ada!");
+    assert!(body["data"]["execution_time_ms"].is_u64());
+
+    // Broken configs report the error instead of failing the request
+    let response = preview(json!({"mock": {"strategy": "script", "script": "let x = ;"}}))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["data"]["output"].is_null());
+    assert!(body["data"]["error"].as_str().unwrap().starts_with("Mock strategy error"));
+
+    assert_eq!(server.settings.read().await.tools.len(), tools_before);
+}
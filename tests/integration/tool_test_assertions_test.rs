@@ -0,0 +1,54 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_tool_test_reports_assertions() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let tool = json!({
+        "name": "weather",
+        "description": "Weather",
+        "input_schema": {"type": "object"},
+        "static_response": {"city": "Oslo", "temps": [3, 5, 8]}
+    });
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    let body: Value = client
+        .post(server.url("/api/tools/weather/test"))
+        .json(&json!({
+            "args": {},
+            "assertions": [
+                {"path": "$.city", "op": "eq", "value": "Oslo"},
+                {"path": "$.temps", "op": "length", "value": 3},
+                {"path": "$.temps[*]", "op": "gte", "value": 5}
+            ]
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let report = &body["data"]["assertions"];
+    assert_eq!(report["passed"], false);
+    let passed: Vec<&Value> = report["results"].as_array().unwrap().iter().map(|r| &r["passed"]).collect();
+    assert_eq!(passed, vec![&json!(true), &json!(true), &json!(false)]);
+    assert_eq!(report["results"][2]["actual"], json!([3, 5, 8]));
+
+    // Without assertions the result has no report
+    let body: Value = client
+        .post(server.url("/api/tools/weather/test"))
+        .json(&json!({"args": {}}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body["data"].get("assertions").is_none());
+}
@@ -0,0 +1,48 @@
+use super::common;
+
+use common::test_server::TestServer;
+use metis::adapters::api_handler::{ApiResponse, ToolDto};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_invalid_tool_update_is_rejected() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let tool = json!({
+        "name": "lookup",
+        "description": "Look something up",
+        "input_schema": {"type": "object", "properties": {"query": {"type": "string"}}},
+        "static_response": {"ok": true}
+    });
+
+    let response = client.post(server.url("/api/tools")).json(&tool).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+
+    // An unknown type in the input schema must not replace the valid definition
+    let mut invalid = tool.clone();
+    invalid["input_schema"]["properties"]["query"]["type"] = json!("strng");
+
+    let response = client
+        .put(server.url("/api/tools/lookup"))
+        .json(&invalid)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 422);
+    let body: ApiResponse<ToolDto> = response.json().await.unwrap();
+    assert!(body.error.unwrap().contains("tools.input_schema.properties.query.type"));
+
+    let response = client.get(server.url("/api/tools/lookup")).send().await.unwrap();
+    let body: ApiResponse<ToolDto> = response.json().await.unwrap();
+    assert_eq!(body.data.unwrap().input_schema["properties"]["query"]["type"], "string");
+
+    // Missing required fields are rejected on create as well
+    let response = client
+        .post(server.url("/api/tools"))
+        .json(&json!({"name": "nameless", "description": "", "input_schema": {}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 422);
+}
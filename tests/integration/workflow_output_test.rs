@@ -0,0 +1,51 @@
+use super::common;
+
+use common::test_server::TestServer;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_workflow_output_mapping_keeps_raw_output() {
+    let server = TestServer::new().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(server.url("/api/tools"))
+        .json(&json!({
+            "name": "get_user",
+            "description": "Static user",
+            "input_schema": {"type": "object"},
+            "static_response": {"id": 7, "name": "Ada"}
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let response = client
+        .post(server.url("/api/workflows"))
+        .json(&json!({
+            "name": "lookup",
+            "description": "Fetch a user",
+            "input_schema": {"type": "object"},
+            "steps": [{"id": "fetch", "tool": "get_user"}],
+            "output_mapping": "{\"user\": {{ steps.fetch.result | json_encode() }}, \"ok\": {{ steps.fetch.success }}}"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let response = client
+        .post(server.url("/api/workflows/lookup/test"))
+        .json(&json!({"args": {}}))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    let data = &body["data"];
+    assert!(data["error"].is_null(), "{}", body);
+    assert_eq!(data["output"]["ok"], true);
+    assert_eq!(data["output"]["user"]["name"], "Ada");
+    assert_eq!(data["raw_output"]["success"], true);
+    assert!(data["raw_output"]["steps"]["fetch"].is_object());
+}
@@ -5,30 +5,50 @@
 use crate::types::*;
 use gloo_net::http::Request;
 
-const API_BASE: &str = "/api";
+/// Path the server is mounted under (`server.base_path`), e.g. `/metis`. The
+/// server names it in a `metis-base-path` meta tag of the page; it is empty
+/// when the server is mounted at the root.
+pub fn base_path() -> String {
+    leptos::prelude::document()
+        .query_selector("meta[name=metis-base-path]")
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.get_attribute("content"))
+        .unwrap_or_default()
+}
+
+fn api_base() -> String {
+    format!("{}/api", base_path())
+}
 
 /// Fetch configuration overview
 pub async fn get_config() -> Result<ConfigOverview, String> {
-    let url = format!("{}/config", API_BASE);
+    let url = format!("{}/config", api_base());
     fetch_json::<ConfigOverview>(&url).await
 }
 
+/// Validate the live configuration
+pub async fn validate_config() -> Result<ConfigValidationReport, String> {
+    let url = format!("{}/config/validate", api_base());
+    fetch_json::<ConfigValidationReport>(&url).await
+}
+
 /// Fetch editable server settings
 pub async fn get_server_settings() -> Result<ServerSettings, String> {
-    let url = format!("{}/config/settings", API_BASE);
+    let url = format!("{}/config/settings", api_base());
     fetch_json::<ServerSettings>(&url).await
 }
 
 /// Update server settings
 pub async fn update_server_settings(settings: &ServerSettings) -> Result<ServerSettings, String> {
-    let url = format!("{}/config/settings", API_BASE);
+    let url = format!("{}/config/settings", api_base());
     put_json::<ServerSettings, ServerSettings>(&url, settings).await
 }
 
 /// Save config to disk (metis.toml) with optimistic locking
 /// Returns the new version number on success, or an error message on failure
 pub async fn save_config_to_disk(expected_version: Option<u64>) -> Result<SaveConfigResponse, String> {
-    let url = format!("{}/config/save-disk", API_BASE);
+    let url = format!("{}/config/save-disk", api_base());
     let request = SaveConfigRequest { expected_version };
     post_json::<SaveConfigRequest, SaveConfigResponse>(&url, &request).await
 }
@@ -36,20 +56,20 @@ pub async fn save_config_to_disk(expected_version: Option<u64>) -> Result<SaveCo
 /// Save config to S3 with optimistic locking
 /// Returns the new version number on success, or an error message on failure
 pub async fn save_config_to_s3(expected_version: Option<u64>) -> Result<SaveConfigResponse, String> {
-    let url = format!("{}/config/save-s3", API_BASE);
+    let url = format!("{}/config/save-s3", api_base());
     let request = SaveConfigRequest { expected_version };
     post_json::<SaveConfigRequest, SaveConfigResponse>(&url, &request).await
 }
 
 /// Export config as JSON
 pub async fn export_config() -> Result<serde_json::Value, String> {
-    let url = format!("{}/config/export", API_BASE);
+    let url = format!("{}/config/export", api_base());
     fetch_json::<serde_json::Value>(&url).await
 }
 
 /// Import config from JSON
 pub async fn import_config(config: &serde_json::Value) -> Result<(), String> {
-    let url = format!("{}/config/import", API_BASE);
+    let url = format!("{}/config/import", api_base());
     post_empty(&url, config).await
 }
 
@@ -114,13 +134,13 @@ impl MergeResult {
 
 /// Merge config from JSON (only adds new elements)
 pub async fn merge_config(config: &serde_json::Value) -> Result<MergeResult, String> {
-    let url = format!("{}/config/merge", API_BASE);
+    let url = format!("{}/config/merge", api_base());
     post_json::<serde_json::Value, MergeResult>(&url, config).await
 }
 
 /// Fetch metrics as JSON
 pub async fn get_metrics() -> Result<serde_json::Value, String> {
-    let url = format!("{}/metrics/json", API_BASE);
+    let url = format!("{}/metrics/json", api_base());
     fetch_json::<serde_json::Value>(&url).await
 }
 
@@ -128,37 +148,43 @@ pub async fn get_metrics() -> Result<serde_json::Value, String> {
 // Resources
 // ============================================================================
 
+/// Search tools, resources, resource templates, prompts and workflows
+pub async fn search(query: &str) -> Result<Vec<SearchResult>, String> {
+    let url = format!("{}/search?q={}", api_base(), urlencoding_encode(query));
+    fetch_json::<Vec<SearchResult>>(&url).await
+}
+
 pub async fn list_resources() -> Result<Vec<Resource>, String> {
-    let url = format!("{}/resources", API_BASE);
+    let url = format!("{}/resources", api_base());
     fetch_json::<Vec<Resource>>(&url).await
 }
 
 pub async fn get_resource(uri: &str) -> Result<Resource, String> {
     let encoded_uri = urlencoding_encode(uri);
-    let url = format!("{}/resources/{}", API_BASE, encoded_uri);
+    let url = format!("{}/resources/{}", api_base(), encoded_uri);
     fetch_json::<Resource>(&url).await
 }
 
 pub async fn create_resource(resource: &Resource) -> Result<Resource, String> {
-    let url = format!("{}/resources", API_BASE);
+    let url = format!("{}/resources", api_base());
     post_json::<Resource, Resource>(&url, resource).await
 }
 
 pub async fn update_resource(uri: &str, resource: &Resource) -> Result<Resource, String> {
     let encoded_uri = urlencoding_encode(uri);
-    let url = format!("{}/resources/{}", API_BASE, encoded_uri);
+    let url = format!("{}/resources/{}", api_base(), encoded_uri);
     put_json::<Resource, Resource>(&url, resource).await
 }
 
 pub async fn delete_resource(uri: &str) -> Result<(), String> {
     let encoded_uri = urlencoding_encode(uri);
-    let url = format!("{}/resources/{}", API_BASE, encoded_uri);
+    let url = format!("{}/resources/{}", api_base(), encoded_uri);
     delete_request(&url).await
 }
 
 pub async fn test_resource(uri: &str, args: &serde_json::Value) -> Result<crate::types::TestResult, String> {
     let encoded_uri = urlencoding_encode(uri);
-    let url = format!("{}/resources/{}/test", API_BASE, encoded_uri);
+    let url = format!("{}/resources/{}/test", api_base(), encoded_uri);
     let req = crate::types::TestRequest { args: args.clone(), session_id: None };
     post_json::<crate::types::TestRequest, crate::types::TestResult>(&url, &req).await
 }
@@ -168,36 +194,36 @@ pub async fn test_resource(uri: &str, args: &serde_json::Value) -> Result<crate:
 // ============================================================================
 
 pub async fn list_resource_templates() -> Result<Vec<ResourceTemplate>, String> {
-    let url = format!("{}/resource-templates", API_BASE);
+    let url = format!("{}/resource-templates", api_base());
     fetch_json::<Vec<ResourceTemplate>>(&url).await
 }
 
 pub async fn get_resource_template(uri_template: &str) -> Result<ResourceTemplate, String> {
     let encoded_uri = urlencoding_encode(uri_template);
-    let url = format!("{}/resource-templates/{}", API_BASE, encoded_uri);
+    let url = format!("{}/resource-templates/{}", api_base(), encoded_uri);
     fetch_json::<ResourceTemplate>(&url).await
 }
 
 pub async fn create_resource_template(template: &ResourceTemplate) -> Result<ResourceTemplate, String> {
-    let url = format!("{}/resource-templates", API_BASE);
+    let url = format!("{}/resource-templates", api_base());
     post_json::<ResourceTemplate, ResourceTemplate>(&url, template).await
 }
 
 pub async fn update_resource_template(uri_template: &str, template: &ResourceTemplate) -> Result<ResourceTemplate, String> {
     let encoded_uri = urlencoding_encode(uri_template);
-    let url = format!("{}/resource-templates/{}", API_BASE, encoded_uri);
+    let url = format!("{}/resource-templates/{}", api_base(), encoded_uri);
     put_json::<ResourceTemplate, ResourceTemplate>(&url, template).await
 }
 
 pub async fn delete_resource_template(uri_template: &str) -> Result<(), String> {
     let encoded_uri = urlencoding_encode(uri_template);
-    let url = format!("{}/resource-templates/{}", API_BASE, encoded_uri);
+    let url = format!("{}/resource-templates/{}", api_base(), encoded_uri);
     delete_request(&url).await
 }
 
 pub async fn test_resource_template(uri_template: &str, args: &serde_json::Value) -> Result<crate::types::TestResult, String> {
     let encoded_uri = urlencoding_encode(uri_template);
-    let url = format!("{}/resource-templates/{}/test", API_BASE, encoded_uri);
+    let url = format!("{}/resource-templates/{}/test", api_base(), encoded_uri);
     let req = crate::types::TestRequest { args: args.clone(), session_id: None };
     post_json::<crate::types::TestRequest, crate::types::TestResult>(&url, &req).await
 }
@@ -207,32 +233,32 @@ pub async fn test_resource_template(uri_template: &str, args: &serde_json::Value
 // ============================================================================
 
 pub async fn list_tools() -> Result<Vec<Tool>, String> {
-    let url = format!("{}/tools", API_BASE);
+    let url = format!("{}/tools", api_base());
     fetch_json::<Vec<Tool>>(&url).await
 }
 
 pub async fn get_tool(name: &str) -> Result<Tool, String> {
-    let url = format!("{}/tools/{}", API_BASE, name);
+    let url = format!("{}/tools/{}", api_base(), name);
     fetch_json::<Tool>(&url).await
 }
 
 pub async fn create_tool(tool: &Tool) -> Result<Tool, String> {
-    let url = format!("{}/tools", API_BASE);
+    let url = format!("{}/tools", api_base());
     post_json::<Tool, Tool>(&url, tool).await
 }
 
 pub async fn update_tool(name: &str, tool: &Tool) -> Result<Tool, String> {
-    let url = format!("{}/tools/{}", API_BASE, name);
+    let url = format!("{}/tools/{}", api_base(), name);
     put_json::<Tool, Tool>(&url, tool).await
 }
 
 pub async fn delete_tool(name: &str) -> Result<(), String> {
-    let url = format!("{}/tools/{}", API_BASE, name);
+    let url = format!("{}/tools/{}", api_base(), name);
     delete_request(&url).await
 }
 
 pub async fn test_tool(name: &str, args: &serde_json::Value) -> Result<crate::types::TestResult, String> {
-    let url = format!("{}/tools/{}/test", API_BASE, name);
+    let url = format!("{}/tools/{}/test", api_base(), name);
     let req = crate::types::TestRequest { args: args.clone(), session_id: None };
     post_json::<crate::types::TestRequest, crate::types::TestResult>(&url, &req).await
 }
@@ -242,32 +268,32 @@ pub async fn test_tool(name: &str, args: &serde_json::Value) -> Result<crate::ty
 // ============================================================================
 
 pub async fn list_prompts() -> Result<Vec<Prompt>, String> {
-    let url = format!("{}/prompts", API_BASE);
+    let url = format!("{}/prompts", api_base());
     fetch_json::<Vec<Prompt>>(&url).await
 }
 
 pub async fn get_prompt(name: &str) -> Result<Prompt, String> {
-    let url = format!("{}/prompts/{}", API_BASE, name);
+    let url = format!("{}/prompts/{}", api_base(), name);
     fetch_json::<Prompt>(&url).await
 }
 
 pub async fn create_prompt(prompt: &Prompt) -> Result<Prompt, String> {
-    let url = format!("{}/prompts", API_BASE);
+    let url = format!("{}/prompts", api_base());
     post_json::<Prompt, Prompt>(&url, prompt).await
 }
 
 pub async fn update_prompt(name: &str, prompt: &Prompt) -> Result<Prompt, String> {
-    let url = format!("{}/prompts/{}", API_BASE, name);
+    let url = format!("{}/prompts/{}", api_base(), name);
     put_json::<Prompt, Prompt>(&url, prompt).await
 }
 
 pub async fn delete_prompt(name: &str) -> Result<(), String> {
-    let url = format!("{}/prompts/{}", API_BASE, name);
+    let url = format!("{}/prompts/{}", api_base(), name);
     delete_request(&url).await
 }
 
 pub async fn test_prompt(name: &str, args: &serde_json::Value) -> Result<crate::types::TestResult, String> {
-    let url = format!("{}/prompts/{}/test", API_BASE, name);
+    let url = format!("{}/prompts/{}/test", api_base(), name);
     let req = crate::types::TestRequest { args: args.clone(), session_id: None };
     post_json::<crate::types::TestRequest, crate::types::TestResult>(&url, &req).await
 }
@@ -277,32 +303,32 @@ pub async fn test_prompt(name: &str, args: &serde_json::Value) -> Result<crate::
 // ============================================================================
 
 pub async fn list_workflows() -> Result<Vec<Workflow>, String> {
-    let url = format!("{}/workflows", API_BASE);
+    let url = format!("{}/workflows", api_base());
     fetch_json::<Vec<Workflow>>(&url).await
 }
 
 pub async fn get_workflow(name: &str) -> Result<Workflow, String> {
-    let url = format!("{}/workflows/{}", API_BASE, name);
+    let url = format!("{}/workflows/{}", api_base(), name);
     fetch_json::<Workflow>(&url).await
 }
 
 pub async fn create_workflow(workflow: &Workflow) -> Result<Workflow, String> {
-    let url = format!("{}/workflows", API_BASE);
+    let url = format!("{}/workflows", api_base());
     post_json::<Workflow, Workflow>(&url, workflow).await
 }
 
 pub async fn update_workflow(name: &str, workflow: &Workflow) -> Result<Workflow, String> {
-    let url = format!("{}/workflows/{}", API_BASE, name);
+    let url = format!("{}/workflows/{}", api_base(), name);
     put_json::<Workflow, Workflow>(&url, workflow).await
 }
 
 pub async fn delete_workflow(name: &str) -> Result<(), String> {
-    let url = format!("{}/workflows/{}", API_BASE, name);
+    let url = format!("{}/workflows/{}", api_base(), name);
     delete_request(&url).await
 }
 
 pub async fn test_workflow(name: &str, args: &serde_json::Value) -> Result<crate::types::TestResult, String> {
-    let url = format!("{}/workflows/{}/test", API_BASE, name);
+    let url = format!("{}/workflows/{}/test", api_base(), name);
     let req = crate::types::TestRequest { args: args.clone(), session_id: None };
     post_json::<crate::types::TestRequest, crate::types::TestResult>(&url, &req).await
 }
@@ -312,32 +338,32 @@ pub async fn test_workflow(name: &str, args: &serde_json::Value) -> Result<crate
 // ============================================================================
 
 pub async fn list_agents() -> Result<Vec<Agent>, String> {
-    let url = format!("{}/agents", API_BASE);
+    let url = format!("{}/agents", api_base());
     fetch_json::<Vec<Agent>>(&url).await
 }
 
 pub async fn get_agent(name: &str) -> Result<Agent, String> {
-    let url = format!("{}/agents/{}", API_BASE, name);
+    let url = format!("{}/agents/{}", api_base(), name);
     fetch_json::<Agent>(&url).await
 }
 
 pub async fn create_agent(agent: &Agent) -> Result<Agent, String> {
-    let url = format!("{}/agents", API_BASE);
+    let url = format!("{}/agents", api_base());
     post_json::<Agent, Agent>(&url, agent).await
 }
 
 pub async fn update_agent(name: &str, agent: &Agent) -> Result<Agent, String> {
-    let url = format!("{}/agents/{}", API_BASE, name);
+    let url = format!("{}/agents/{}", api_base(), name);
     put_json::<Agent, Agent>(&url, agent).await
 }
 
 pub async fn delete_agent(name: &str) -> Result<(), String> {
-    let url = format!("{}/agents/{}", API_BASE, name);
+    let url = format!("{}/agents/{}", api_base(), name);
     delete_request(&url).await
 }
 
 pub async fn test_agent(name: &str, args: &serde_json::Value, session_id: Option<String>) -> Result<crate::types::TestResult, String> {
-    let url = format!("{}/agents/{}/test", API_BASE, name);
+    let url = format!("{}/agents/{}/test", api_base(), name);
     let req = crate::types::TestRequest {
         args: args.clone(),
         session_id,
@@ -350,32 +376,32 @@ pub async fn test_agent(name: &str, args: &serde_json::Value, session_id: Option
 // ============================================================================
 
 pub async fn list_orchestrations() -> Result<Vec<Orchestration>, String> {
-    let url = format!("{}/orchestrations", API_BASE);
+    let url = format!("{}/orchestrations", api_base());
     fetch_json::<Vec<Orchestration>>(&url).await
 }
 
 pub async fn get_orchestration(name: &str) -> Result<Orchestration, String> {
-    let url = format!("{}/orchestrations/{}", API_BASE, name);
+    let url = format!("{}/orchestrations/{}", api_base(), name);
     fetch_json::<Orchestration>(&url).await
 }
 
 pub async fn create_orchestration(orchestration: &Orchestration) -> Result<Orchestration, String> {
-    let url = format!("{}/orchestrations", API_BASE);
+    let url = format!("{}/orchestrations", api_base());
     post_json::<Orchestration, Orchestration>(&url, orchestration).await
 }
 
 pub async fn update_orchestration(name: &str, orchestration: &Orchestration) -> Result<Orchestration, String> {
-    let url = format!("{}/orchestrations/{}", API_BASE, name);
+    let url = format!("{}/orchestrations/{}", api_base(), name);
     put_json::<Orchestration, Orchestration>(&url, orchestration).await
 }
 
 pub async fn delete_orchestration(name: &str) -> Result<(), String> {
-    let url = format!("{}/orchestrations/{}", API_BASE, name);
+    let url = format!("{}/orchestrations/{}", api_base(), name);
     delete_request(&url).await
 }
 
 pub async fn test_orchestration(name: &str, args: &serde_json::Value) -> Result<crate::types::TestResult, String> {
-    let url = format!("{}/orchestrations/{}/test", API_BASE, name);
+    let url = format!("{}/orchestrations/{}/test", api_base(), name);
     let req = crate::types::TestRequest { args: args.clone(), session_id: None };
     post_json::<crate::types::TestRequest, crate::types::TestResult>(&url, &req).await
 }
@@ -385,27 +411,27 @@ pub async fn test_orchestration(name: &str, args: &serde_json::Value) -> Result<
 // ============================================================================
 
 pub async fn list_schemas() -> Result<Vec<Schema>, String> {
-    let url = format!("{}/schemas", API_BASE);
+    let url = format!("{}/schemas", api_base());
     fetch_json::<Vec<Schema>>(&url).await
 }
 
 pub async fn get_schema(name: &str) -> Result<Schema, String> {
-    let url = format!("{}/schemas/{}", API_BASE, name);
+    let url = format!("{}/schemas/{}", api_base(), name);
     fetch_json::<Schema>(&url).await
 }
 
 pub async fn create_schema(schema: &Schema) -> Result<Schema, String> {
-    let url = format!("{}/schemas", API_BASE);
+    let url = format!("{}/schemas", api_base());
     post_json::<Schema, Schema>(&url, schema).await
 }
 
 pub async fn update_schema(name: &str, schema: &Schema) -> Result<Schema, String> {
-    let url = format!("{}/schemas/{}", API_BASE, name);
+    let url = format!("{}/schemas/{}", api_base(), name);
     put_json::<Schema, Schema>(&url, schema).await
 }
 
 pub async fn delete_schema(name: &str) -> Result<(), String> {
-    let url = format!("{}/schemas/{}", API_BASE, name);
+    let url = format!("{}/schemas/{}", api_base(), name);
     delete_request(&url).await
 }
 
@@ -414,27 +440,27 @@ pub async fn delete_schema(name: &str) -> Result<(), String> {
 // ============================================================================
 
 pub async fn list_data_lakes() -> Result<Vec<DataLake>, String> {
-    let url = format!("{}/data-lakes", API_BASE);
+    let url = format!("{}/data-lakes", api_base());
     fetch_json::<Vec<DataLake>>(&url).await
 }
 
 pub async fn get_data_lake(name: &str) -> Result<DataLake, String> {
-    let url = format!("{}/data-lakes/{}", API_BASE, urlencoding_encode(name));
+    let url = format!("{}/data-lakes/{}", api_base(), urlencoding_encode(name));
     fetch_json::<DataLake>(&url).await
 }
 
 pub async fn create_data_lake(data_lake: &DataLake) -> Result<DataLake, String> {
-    let url = format!("{}/data-lakes", API_BASE);
+    let url = format!("{}/data-lakes", api_base());
     post_json::<DataLake, DataLake>(&url, data_lake).await
 }
 
 pub async fn update_data_lake(name: &str, data_lake: &DataLake) -> Result<DataLake, String> {
-    let url = format!("{}/data-lakes/{}", API_BASE, urlencoding_encode(name));
+    let url = format!("{}/data-lakes/{}", api_base(), urlencoding_encode(name));
     put_json::<DataLake, DataLake>(&url, data_lake).await
 }
 
 pub async fn delete_data_lake(name: &str) -> Result<(), String> {
-    let url = format!("{}/data-lakes/{}", API_BASE, urlencoding_encode(name));
+    let url = format!("{}/data-lakes/{}", api_base(), urlencoding_encode(name));
     delete_request(&url).await
 }
 
@@ -471,7 +497,7 @@ pub async fn list_records(
     limit: Option<usize>,
     offset: Option<usize>,
 ) -> Result<ListRecordsResponse, String> {
-    let mut url = format!("{}/data-lakes/{}/records", API_BASE, urlencoding_encode(data_lake));
+    let mut url = format!("{}/data-lakes/{}/records", api_base(), urlencoding_encode(data_lake));
 
     let mut params = Vec::new();
     if let Some(schema) = schema_name {
@@ -493,31 +519,31 @@ pub async fn list_records(
 
 /// Get a specific record by ID
 pub async fn get_record(data_lake: &str, id: &str) -> Result<DataRecord, String> {
-    let url = format!("{}/data-lakes/{}/records/{}", API_BASE, urlencoding_encode(data_lake), urlencoding_encode(id));
+    let url = format!("{}/data-lakes/{}/records/{}", api_base(), urlencoding_encode(data_lake), urlencoding_encode(id));
     fetch_json::<DataRecord>(&url).await
 }
 
 /// Create a new record
 pub async fn create_record(data_lake: &str, request: &CreateRecordRequest) -> Result<DataRecord, String> {
-    let url = format!("{}/data-lakes/{}/records", API_BASE, urlencoding_encode(data_lake));
+    let url = format!("{}/data-lakes/{}/records", api_base(), urlencoding_encode(data_lake));
     post_json::<CreateRecordRequest, DataRecord>(&url, request).await
 }
 
 /// Update an existing record
 pub async fn update_record(data_lake: &str, id: &str, request: &UpdateRecordRequest) -> Result<DataRecord, String> {
-    let url = format!("{}/data-lakes/{}/records/{}", API_BASE, urlencoding_encode(data_lake), urlencoding_encode(id));
+    let url = format!("{}/data-lakes/{}/records/{}", api_base(), urlencoding_encode(data_lake), urlencoding_encode(id));
     put_json::<UpdateRecordRequest, DataRecord>(&url, request).await
 }
 
 /// Delete a record
 pub async fn delete_record(data_lake: &str, id: &str) -> Result<(), String> {
-    let url = format!("{}/data-lakes/{}/records/{}", API_BASE, urlencoding_encode(data_lake), urlencoding_encode(id));
+    let url = format!("{}/data-lakes/{}/records/{}", api_base(), urlencoding_encode(data_lake), urlencoding_encode(id));
     delete_request(&url).await
 }
 
 /// Count records in a data lake
 pub async fn count_records(data_lake: &str, schema_name: Option<&str>) -> Result<CountRecordsResponse, String> {
-    let mut url = format!("{}/data-lakes/{}/records/count", API_BASE, urlencoding_encode(data_lake));
+    let mut url = format!("{}/data-lakes/{}/records/count", api_base(), urlencoding_encode(data_lake));
 
     if let Some(schema) = schema_name {
         url = format!("{}?schema={}", url, urlencoding_encode(schema));
@@ -528,13 +554,13 @@ pub async fn count_records(data_lake: &str, schema_name: Option<&str>) -> Result
 
 /// Generate records using a mock strategy
 pub async fn generate_records(data_lake: &str, request: &GenerateRecordsRequest) -> Result<GenerateRecordsResponse, String> {
-    let url = format!("{}/data-lakes/{}/records/generate", API_BASE, urlencoding_encode(data_lake));
+    let url = format!("{}/data-lakes/{}/records/generate", api_base(), urlencoding_encode(data_lake));
     post_json::<GenerateRecordsRequest, GenerateRecordsResponse>(&url, request).await
 }
 
 /// Delete all records in a data lake (optionally filtered by schema)
 pub async fn delete_all_records(data_lake: &str, schema_name: Option<&str>) -> Result<(), String> {
-    let mut url = format!("{}/data-lakes/{}/records", API_BASE, urlencoding_encode(data_lake));
+    let mut url = format!("{}/data-lakes/{}/records", api_base(), urlencoding_encode(data_lake));
 
     if let Some(schema) = schema_name {
         url = format!("{}?schema={}", url, urlencoding_encode(schema));
@@ -559,26 +585,26 @@ pub struct BulkDeleteRecordsResponse {
 
 /// Bulk delete multiple records by IDs
 pub async fn bulk_delete_records(data_lake: &str, ids: Vec<String>) -> Result<BulkDeleteRecordsResponse, String> {
-    let url = format!("{}/data-lakes/{}/records/bulk-delete", API_BASE, urlencoding_encode(data_lake));
+    let url = format!("{}/data-lakes/{}/records/bulk-delete", api_base(), urlencoding_encode(data_lake));
     let request = BulkDeleteRecordsRequest { ids };
     post_json::<BulkDeleteRecordsRequest, BulkDeleteRecordsResponse>(&url, &request).await
 }
 
 /// Execute SQL query via DataFusion
 pub async fn execute_query(data_lake: &str, request: &crate::types::SqlQueryRequest) -> Result<crate::types::SqlQueryResponse, String> {
-    let url = format!("{}/data-lakes/{}/query", API_BASE, urlencoding_encode(data_lake));
+    let url = format!("{}/data-lakes/{}/query", api_base(), urlencoding_encode(data_lake));
     post_json::<crate::types::SqlQueryRequest, crate::types::SqlQueryResponse>(&url, request).await
 }
 
 /// List data files for a data lake
 pub async fn list_files(data_lake: &str) -> Result<Vec<crate::types::FileInfo>, String> {
-    let url = format!("{}/data-lakes/{}/files", API_BASE, urlencoding_encode(data_lake));
+    let url = format!("{}/data-lakes/{}/files", api_base(), urlencoding_encode(data_lake));
     fetch_json::<Vec<crate::types::FileInfo>>(&url).await
 }
 
 /// Sync records to file storage
 pub async fn sync_to_files(data_lake: &str, request: &crate::types::SyncRequest) -> Result<crate::types::SyncResponse, String> {
-    let url = format!("{}/data-lakes/{}/sync", API_BASE, urlencoding_encode(data_lake));
+    let url = format!("{}/data-lakes/{}/sync", api_base(), urlencoding_encode(data_lake));
     post_json::<crate::types::SyncRequest, crate::types::SyncResponse>(&url, request).await
 }
 
@@ -594,7 +620,7 @@ pub struct SchemaInfoResponse {
 
 /// Get schema information for a data lake schema (for DataLakeCrud UI)
 pub async fn get_schema_info(data_lake: &str, schema_name: &str) -> Result<SchemaInfoResponse, String> {
-    let url = format!("{}/data-lakes/{}/schema-info/{}", API_BASE, urlencoding_encode(data_lake), urlencoding_encode(schema_name));
+    let url = format!("{}/data-lakes/{}/schema-info/{}", api_base(), urlencoding_encode(data_lake), urlencoding_encode(schema_name));
     fetch_json::<SchemaInfoResponse>(&url).await
 }
 
@@ -603,12 +629,12 @@ pub async fn get_schema_info(data_lake: &str, schema_name: &str) -> Result<Schem
 // ============================================================================
 
 pub async fn get_state() -> Result<std::collections::HashMap<String, serde_json::Value>, String> {
-    let url = format!("{}/state", API_BASE);
+    let url = format!("{}/state", api_base());
     fetch_json::<std::collections::HashMap<String, serde_json::Value>>(&url).await
 }
 
 pub async fn reset_state() -> Result<(), String> {
-    let url = format!("{}/state", API_BASE);
+    let url = format!("{}/state", api_base());
     delete_request(&url).await
 }
 
@@ -637,13 +663,13 @@ pub struct SecretsStatusResponse {
 
 /// List all secrets and their status (not values)
 pub async fn list_secrets() -> Result<SecretsStatusResponse, String> {
-    let url = format!("{}/secrets", API_BASE);
+    let url = format!("{}/secrets", api_base());
     fetch_json::<SecretsStatusResponse>(&url).await
 }
 
 /// Set a secret value
 pub async fn set_secret(key: &str, value: &str) -> Result<(), String> {
-    let url = format!("{}/secrets/{}", API_BASE, key);
+    let url = format!("{}/secrets/{}", api_base(), key);
     #[derive(serde::Serialize)]
     struct SetSecretRequest {
         value: String,
@@ -656,13 +682,13 @@ pub async fn set_secret(key: &str, value: &str) -> Result<(), String> {
 
 /// Delete a secret
 pub async fn delete_secret(key: &str) -> Result<(), String> {
-    let url = format!("{}/secrets/{}", API_BASE, key);
+    let url = format!("{}/secrets/{}", api_base(), key);
     delete_request(&url).await
 }
 
 /// Clear all secrets
 pub async fn clear_secrets() -> Result<(), String> {
-    let url = format!("{}/secrets", API_BASE);
+    let url = format!("{}/secrets", api_base());
     delete_request(&url).await
 }
 
@@ -680,7 +706,7 @@ pub struct LlmModelInfo {
 
 /// Fetch available models for a given LLM provider
 pub async fn fetch_llm_models(provider: &str, base_url: Option<&str>, api_key_env: Option<&str>) -> Result<Vec<LlmModelInfo>, String> {
-    let mut url = format!("{}/llm/models/{}", API_BASE, provider);
+    let mut url = format!("{}/llm/models/{}", api_base(), provider);
 
     let mut params = Vec::new();
     if let Some(base) = base_url {
@@ -818,31 +844,31 @@ async fn post_empty<T: serde::Serialize>(url: &str, body: &T) -> Result<(), Stri
 
 /// Get database status
 pub async fn get_database_status() -> Result<DatabaseStatus, String> {
-    let url = format!("{}/database/status", API_BASE);
+    let url = format!("{}/database/status", api_base());
     fetch_json::<DatabaseStatus>(&url).await
 }
 
 /// List commits with pagination
 pub async fn list_commits(limit: usize, offset: usize) -> Result<Vec<Commit>, String> {
-    let url = format!("{}/commits?limit={}&offset={}", API_BASE, limit, offset);
+    let url = format!("{}/commits?limit={}&offset={}", api_base(), limit, offset);
     fetch_json::<Vec<Commit>>(&url).await
 }
 
 /// Get a specific commit
 pub async fn get_commit(commit_hash: &str) -> Result<Commit, String> {
-    let url = format!("{}/commits/{}", API_BASE, commit_hash);
+    let url = format!("{}/commits/{}", api_base(), commit_hash);
     fetch_json::<Commit>(&url).await
 }
 
 /// Get changesets for a commit
 pub async fn get_commit_changesets(commit_hash: &str) -> Result<Vec<Changeset>, String> {
-    let url = format!("{}/commits/{}/changesets", API_BASE, commit_hash);
+    let url = format!("{}/commits/{}/changesets", api_base(), commit_hash);
     fetch_json::<Vec<Changeset>>(&url).await
 }
 
 /// Rollback to a specific commit
 pub async fn rollback_to_commit(commit_hash: &str) -> Result<Commit, String> {
-    let url = format!("{}/commits/rollback", API_BASE);
+    let url = format!("{}/commits/rollback", api_base());
     let req = RollbackRequest {
         commit_hash: commit_hash.to_string(),
     };
@@ -851,19 +877,19 @@ pub async fn rollback_to_commit(commit_hash: &str) -> Result<Commit, String> {
 
 /// List all tags
 pub async fn list_tags() -> Result<Vec<Tag>, String> {
-    let url = format!("{}/tags", API_BASE);
+    let url = format!("{}/tags", api_base());
     fetch_json::<Vec<Tag>>(&url).await
 }
 
 /// Get a tag by name
 pub async fn get_tag(name: &str) -> Result<Tag, String> {
-    let url = format!("{}/tags/{}", API_BASE, name);
+    let url = format!("{}/tags/{}", api_base(), name);
     fetch_json::<Tag>(&url).await
 }
 
 /// Create a tag for a commit
 pub async fn create_tag(commit_hash: &str, name: &str, message: Option<&str>) -> Result<Tag, String> {
-    let url = format!("{}/commits/{}/tags", API_BASE, commit_hash);
+    let url = format!("{}/commits/{}/tags", api_base(), commit_hash);
     let req = CreateTagRequest {
         name: name.to_string(),
         message: message.map(|s| s.to_string()),
@@ -873,6 +899,6 @@ pub async fn create_tag(commit_hash: &str, name: &str, message: Option<&str>) ->
 
 /// Delete a tag
 pub async fn delete_tag(name: &str) -> Result<(), String> {
-    let url = format!("{}/tags/{}", API_BASE, name);
+    let url = format!("{}/tags/{}", api_base(), name);
     delete_request(&url).await
 }
@@ -82,7 +82,7 @@ fn render_markdown(text: &str) -> String {
 
     for line in text.lines() {
         // Handle code blocks
-        if line.starts_with("```") {
+        if let Some(lang) = line.strip_prefix("```") {
             // Close any open list before code block
             if in_list {
                 html.push_str(if list_type == "ol" { "</ol>" } else { "</ul>" });
@@ -114,7 +114,7 @@ fn render_markdown(text: &str) -> String {
                 in_code_block = false;
             } else {
                 // Start code block - extract language
-                code_block_lang = line[3..].trim().to_string();
+                code_block_lang = lang.trim().to_string();
                 in_code_block = true;
             }
             continue;
@@ -201,7 +201,7 @@ fn render_markdown(text: &str) -> String {
         }
 
         // Handle blockquotes
-        if trimmed.starts_with("> ") {
+        if let Some(quoted) = trimmed.strip_prefix("> ") {
             if in_list {
                 html.push_str(if list_type == "ol" { "</ol>" } else { "</ul>" });
                 in_list = false;
@@ -211,7 +211,7 @@ fn render_markdown(text: &str) -> String {
             } else {
                 blockquote_content.push_str("<br/>");
             }
-            blockquote_content.push_str(&trimmed[2..]);
+            blockquote_content.push_str(quoted);
             continue;
         } else if in_blockquote {
             html.push_str(&format!("<blockquote class=\"border-l-4 border-blue-400 dark:border-blue-500 pl-4 py-1 my-2 text-gray-600 dark:text-gray-300 italic bg-blue-50/50 dark:bg-blue-900/20 rounded-r\">{}</blockquote>", process_inline_markdown(&blockquote_content)));
@@ -326,28 +326,26 @@ fn process_markdown_line(line: &str, in_list: &mut bool, list_type: &mut &str, h
     let trimmed = line.trim();
 
     // Headers - close any open list first
-    if trimmed.starts_with('#') {
-        if *in_list {
-            html.push_str(if *list_type == "ol" { "</ol>" } else { "</ul>" });
-            *in_list = false;
-        }
+    if trimmed.starts_with('#') && *in_list {
+        html.push_str(if *list_type == "ol" { "</ol>" } else { "</ul>" });
+        *in_list = false;
     }
 
     // H4
-    if trimmed.starts_with("#### ") {
-        return format!("<h4 class=\"text-sm font-semibold mt-3 mb-1 text-gray-700 dark:text-gray-300\">{}</h4>", process_inline_markdown(&trimmed[5..]));
+    if let Some(heading) = trimmed.strip_prefix("#### ") {
+        return format!("<h4 class=\"text-sm font-semibold mt-3 mb-1 text-gray-700 dark:text-gray-300\">{}</h4>", process_inline_markdown(heading));
     }
     // H3
-    if trimmed.starts_with("### ") {
-        return format!("<h3 class=\"text-base font-semibold mt-4 mb-2 text-gray-800 dark:text-gray-200 border-b border-gray-200 dark:border-gray-700 pb-1\">{}</h3>", process_inline_markdown(&trimmed[4..]));
+    if let Some(heading) = trimmed.strip_prefix("### ") {
+        return format!("<h3 class=\"text-base font-semibold mt-4 mb-2 text-gray-800 dark:text-gray-200 border-b border-gray-200 dark:border-gray-700 pb-1\">{}</h3>", process_inline_markdown(heading));
     }
     // H2
-    if trimmed.starts_with("## ") {
-        return format!("<h2 class=\"text-lg font-bold mt-4 mb-2 text-gray-900 dark:text-gray-100\">{}</h2>", process_inline_markdown(&trimmed[3..]));
+    if let Some(heading) = trimmed.strip_prefix("## ") {
+        return format!("<h2 class=\"text-lg font-bold mt-4 mb-2 text-gray-900 dark:text-gray-100\">{}</h2>", process_inline_markdown(heading));
     }
     // H1
-    if trimmed.starts_with("# ") {
-        return format!("<h1 class=\"text-xl font-bold mt-4 mb-2 text-gray-900 dark:text-gray-100 border-b-2 border-gray-300 dark:border-gray-600 pb-2\">{}</h1>", process_inline_markdown(&trimmed[2..]));
+    if let Some(heading) = trimmed.strip_prefix("# ") {
+        return format!("<h1 class=\"text-xl font-bold mt-4 mb-2 text-gray-900 dark:text-gray-100 border-b-2 border-gray-300 dark:border-gray-600 pb-2\">{}</h1>", process_inline_markdown(heading));
     }
 
     // Task list items (checkboxes)
@@ -362,7 +360,7 @@ fn process_markdown_line(line: &str, in_list: &mut bool, list_type: &mut &str, h
         }
         return format!("<li class=\"flex items-start gap-2 ml-1\"><span class=\"flex-shrink-0 w-4 h-4 mt-0.5 rounded border border-green-500 bg-green-500 flex items-center justify-center\"><svg class=\"w-3 h-3 text-white\" fill=\"none\" stroke=\"currentColor\" viewBox=\"0 0 24 24\"><path stroke-linecap=\"round\" stroke-linejoin=\"round\" stroke-width=\"3\" d=\"M5 13l4 4L19 7\"/></svg></span><span class=\"text-gray-600 dark:text-gray-400 line-through\">{}</span></li>", process_inline_markdown(&trimmed[6..]));
     }
-    if trimmed.starts_with("- [ ] ") {
+    if let Some(item) = trimmed.strip_prefix("- [ ] ") {
         if !*in_list || *list_type != "ul" {
             if *in_list {
                 html.push_str(if *list_type == "ol" { "</ol>" } else { "</ul>" });
@@ -371,7 +369,7 @@ fn process_markdown_line(line: &str, in_list: &mut bool, list_type: &mut &str, h
             *in_list = true;
             *list_type = "ul";
         }
-        return format!("<li class=\"flex items-start gap-2 ml-1\"><span class=\"flex-shrink-0 w-4 h-4 mt-0.5 rounded border-2 border-gray-300 dark:border-gray-500\"></span><span>{}</span></li>", process_inline_markdown(&trimmed[6..]));
+        return format!("<li class=\"flex items-start gap-2 ml-1\"><span class=\"flex-shrink-0 w-4 h-4 mt-0.5 rounded border-2 border-gray-300 dark:border-gray-500\"></span><span>{}</span></li>", process_inline_markdown(item));
     }
 
     // Unordered list items
@@ -537,12 +535,7 @@ fn process_inline_markdown(text: &str) -> String {
 
 /// Find position of double character (e.g., **)
 fn find_double_char(chars: &[char], c: char) -> Option<usize> {
-    for i in 0..chars.len().saturating_sub(1) {
-        if chars[i] == c && chars[i+1] == c {
-            return Some(i);
-        }
-    }
-    None
+    (0..chars.len().saturating_sub(1)).find(|&i| chars[i] == c && chars[i + 1] == c)
 }
 
 /// Escape HTML special characters
@@ -960,7 +953,7 @@ pub fn Agents() -> impl IntoView {
                     </div>
                     // New Agent button
                     <a
-                        href="/agents/new"
+                        href="./agents/new"
                         class="inline-flex items-center px-4 py-2 bg-blue-600 text-white text-sm font-medium rounded-lg hover:bg-blue-700 focus:ring-4 focus:ring-blue-300 dark:focus:ring-blue-800"
                     >
                         <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -1090,7 +1083,7 @@ pub fn Agents() -> impl IntoView {
                                                                     "Test"
                                                                 </button>
                                                                 <a
-                                                                    href=format!("/agents/edit/{}", name_for_edit)
+                                                                    href=format!("./agents/edit/{}", name_for_edit)
                                                                     class="text-blue-600 hover:text-blue-900 dark:text-blue-400"
                                                                 >
                                                                     "Edit"
@@ -1182,7 +1175,7 @@ pub fn Agents() -> impl IntoView {
                                                     }}
                                                     <div class="mt-4 pt-4 border-t border-gray-200 dark:border-gray-700 flex justify-end space-x-2">
                                                         <a
-                                                            href=format!("/agents/edit/{}", name_for_edit)
+                                                            href=format!("./agents/edit/{}", name_for_edit)
                                                             class="px-3 py-1 text-sm text-blue-600 hover:text-blue-800 dark:text-blue-400"
                                                         >
                                                             "Edit"
@@ -1220,7 +1213,7 @@ pub fn Agents() -> impl IntoView {
                                 <p class="mt-1 text-sm text-gray-500 dark:text-gray-400">"Get started by creating a new AI agent."</p>
                                 <div class="mt-6">
                                     <a
-                                        href="/agents/new"
+                                        href="./agents/new"
                                         class="inline-flex items-center px-4 py-2 bg-blue-600 text-white text-sm font-medium rounded-lg hover:bg-blue-700"
                                     >
                                         <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -1872,6 +1865,7 @@ pub fn AgentForm() -> impl IntoView {
                 max_messages: memory_max_messages.get(),
                 file_path: None,
                 database_url: None,
+                summary: None,
             },
             max_iterations: max_iterations.get(),
             timeout_seconds: 300,
@@ -1879,13 +1873,17 @@ pub fn AgentForm() -> impl IntoView {
             max_tokens: None,
             input_schema: input_schema_val,
             output_schema: output_schema_opt,
+            persona_commands: false,
+            personas: Default::default(),
+            require_confirmation_for: Vec::new(),
+            confirmation_timeout_ms: 300_000,
         };
 
         wasm_bindgen_futures::spawn_local(async move {
             match api::create_agent(&agent).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/agents");
+                        let _ = window.location().set_href("./agents");
                     }
                 }
                 Err(e) => {
@@ -1899,7 +1897,7 @@ pub fn AgentForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/agents" class="text-gray-500 hover:text-gray-700">
+                <a href="./agents" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -2538,7 +2536,7 @@ pub fn AgentForm() -> impl IntoView {
                 // Save button (outside tabs)
                 <div class="border-t border-gray-200 dark:border-gray-700 pt-6 mt-6 flex justify-end space-x-4">
                         <a
-                            href="/agents"
+                            href="./agents"
                             class="px-4 py-2 text-gray-700 bg-gray-200 rounded-md hover:bg-gray-300 dark:bg-gray-700 dark:text-gray-300"
                         >
                             "Cancel"
@@ -2571,6 +2569,10 @@ pub fn AgentEditForm() -> impl IntoView {
     let (api_key_env, set_api_key_env) = signal(String::new());
     let (base_url, set_base_url) = signal(String::new());
     let (system_prompt, set_system_prompt) = signal(String::new());
+    // Personas aren't editable in the form; keep what the agent had
+    let (personas, set_personas) = signal((false, std::collections::HashMap::<String, String>::new()));
+    // Same for tool confirmation settings
+    let (confirmation, set_confirmation) = signal((Vec::<String>::new(), 300_000u64));
     let (original_name, set_original_name) = signal(String::new());
     let (error, set_error) = signal(Option::<String>::None);
     let (saving, set_saving) = signal(false);
@@ -2674,6 +2676,8 @@ pub fn AgentEditForm() -> impl IntoView {
                     let loaded_base_url = agent.llm.base_url.clone().unwrap_or_default();
                     set_base_url.set(loaded_base_url.clone());
                     set_system_prompt.set(agent.system_prompt.clone());
+                    set_personas.set((agent.persona_commands, agent.personas.clone()));
+                    set_confirmation.set((agent.require_confirmation_for.clone(), agent.confirmation_timeout_ms));
                     set_prompt_template.set(agent.prompt_template.clone().unwrap_or_default());
                     // Combine all artifacts into unified selection
                     let mut artifacts = Vec::new();
@@ -2798,6 +2802,7 @@ pub fn AgentEditForm() -> impl IntoView {
                 max_messages: 100,
                 file_path: None,
                 database_url: None,
+                summary: None,
             },
             max_iterations: 10,
             timeout_seconds: 300,
@@ -2805,13 +2810,17 @@ pub fn AgentEditForm() -> impl IntoView {
             max_tokens: None,
             input_schema: input_schema_val,
             output_schema: output_schema_opt,
+            persona_commands: personas.get().0,
+            personas: personas.get().1,
+            require_confirmation_for: confirmation.get().0,
+            confirmation_timeout_ms: confirmation.get().1,
         };
 
         wasm_bindgen_futures::spawn_local(async move {
             match api::update_agent(&orig_name, &agent).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/agents");
+                        let _ = window.location().set_href("./agents");
                     }
                 }
                 Err(e) => {
@@ -2825,7 +2834,7 @@ pub fn AgentEditForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/agents" class="text-gray-500 hover:text-gray-700">
+                <a href="./agents" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -3370,7 +3379,7 @@ pub fn AgentEditForm() -> impl IntoView {
                 // Save button (outside tabs)
                 <div class="border-t border-gray-200 dark:border-gray-700 pt-6 mt-6 flex justify-end space-x-4">
                     <a
-                        href="/agents"
+                        href="./agents"
                         class="px-4 py-2 text-gray-700 bg-gray-200 rounded-md hover:bg-gray-300 dark:bg-gray-700 dark:text-gray-300"
                     >
                         "Cancel"
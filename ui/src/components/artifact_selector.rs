@@ -406,14 +406,12 @@ pub fn ArtifactSelector(
                 set_show_dropdown.set(false);
                 set_focused_index.set(None);
             }
-            "Backspace" => {
-                if search_query.get().is_empty() && mode == SelectionMode::Multi {
-                    // Remove last selected item
-                    let mut current = selected.get();
-                    if !current.is_empty() {
-                        current.pop();
-                        on_change.run(current);
-                    }
+            "Backspace" if search_query.get().is_empty() && mode == SelectionMode::Multi => {
+                // Remove last selected item
+                let mut current = selected.get();
+                if !current.is_empty() {
+                    current.pop();
+                    on_change.run(current);
                 }
             }
             _ => {}
@@ -0,0 +1,77 @@
+//! Callback Strategy Editor Component
+//!
+//! Provides UI for configuring the Callback mock strategy:
+//! - Callback URL template
+//! - Delay before the callback is sent
+//! - Payload and acknowledgment templates
+
+use leptos::prelude::*;
+use crate::types::CallbackConfig;
+
+/// Callback Strategy Editor component
+#[component]
+pub fn CallbackStrategyEditor(
+    /// Callback configuration signal
+    config: RwSignal<CallbackConfig>,
+) -> impl IntoView {
+    view! {
+        <div class="space-y-4">
+            <div class="grid grid-cols-3 gap-4">
+                <div class="col-span-2">
+                    <label class="block text-sm font-medium text-gray-700 mb-1">"Callback URL *"</label>
+                    <input
+                        type="text"
+                        class="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-green-500 font-mono"
+                        placeholder="http://localhost:9000/hooks/{{ job_id }}"
+                        prop:value=move || config.get().url
+                        on:input=move |ev| {
+                            let val = event_target_value(&ev);
+                            config.update(|c| c.url = val);
+                        }
+                    />
+                </div>
+                <div>
+                    <label class="block text-sm font-medium text-gray-700 mb-1">"Delay (ms)"</label>
+                    <input
+                        type="number"
+                        min="0"
+                        class="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-green-500"
+                        prop:value=move || config.get().delay_ms.to_string()
+                        on:input=move |ev| {
+                            let val = event_target_value(&ev).parse().unwrap_or(0);
+                            config.update(|c| c.delay_ms = val);
+                        }
+                    />
+                </div>
+            </div>
+            <div>
+                <label class="block text-sm font-medium text-gray-700 mb-1">"Payload Template"</label>
+                <textarea
+                    rows=4
+                    class="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-green-500 font-mono text-sm"
+                    placeholder=r#"{"id": "{{ callback_id }}", "status": "completed"}"#
+                    prop:value=move || config.get().payload_template.clone().unwrap_or_default()
+                    on:input=move |ev| {
+                        let val = event_target_value(&ev);
+                        config.update(|c| c.payload_template = if val.is_empty() { None } else { Some(val) });
+                    }
+                />
+                <p class="mt-1 text-xs text-gray-500">"Tera template posted to the URL. Leave empty to send the tool arguments."</p>
+            </div>
+            <div>
+                <label class="block text-sm font-medium text-gray-700 mb-1">"Acknowledgment Template"</label>
+                <textarea
+                    rows=2
+                    class="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-green-500 font-mono text-sm"
+                    placeholder=r#"{"status": "accepted", "callback_id": "{{ callback_id }}"}"#
+                    prop:value=move || config.get().ack_template.clone().unwrap_or_default()
+                    on:input=move |ev| {
+                        let val = event_target_value(&ev);
+                        config.update(|c| c.ack_template = if val.is_empty() { None } else { Some(val) });
+                    }
+                />
+                <p class="mt-1 text-xs text-gray-500">"Returned to the client immediately"</p>
+            </div>
+        </div>
+    }
+}
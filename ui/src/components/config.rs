@@ -173,6 +173,7 @@ fn ServerConfigCard(overview: ConfigOverview) -> impl IntoView {
                     <ConfigItem label="Host" value=overview.server.host.clone() />
                     <ConfigItem label="Port" value=overview.server.port.to_string() />
                     <ConfigItem label="Version" value=overview.server.version.clone() />
+                    <ConfigItem label="Profile" value=overview.profile.clone().unwrap_or_else(|| "default".to_string()) />
                     <ConfigItem
                         label="Status"
                         value="Running".to_string()
@@ -742,7 +743,7 @@ fn SettingsEditorCard(initial_settings: ServerSettings) -> impl IntoView {
     let on_file_selected = move |_| {
         if let Some(input) = file_input_ref.get() {
             // Get the native DOM element and cast to HtmlInputElement with files support
-            let input_el: web_sys::HtmlInputElement = input.clone().into();
+            let input_el: web_sys::HtmlInputElement = input.clone();
             if let Some(files) = input_el.files() {
                 if let Some(file) = files.get(0) {
                     set_importing.set(true);
@@ -799,7 +800,7 @@ fn SettingsEditorCard(initial_settings: ServerSettings) -> impl IntoView {
 
     let on_merge_file_selected = move |_| {
         if let Some(input) = merge_file_input_ref.get() {
-            let input_el: web_sys::HtmlInputElement = input.clone().into();
+            let input_el: web_sys::HtmlInputElement = input.clone();
             if let Some(files) = input_el.files() {
                 if let Some(file) = files.get(0) {
                     set_merging.set(true);
@@ -1758,46 +1759,46 @@ fn QuickLinksCard(overview: ConfigOverview) -> impl IntoView {
             <div class="p-6">
                 <div class="grid grid-cols-2 md:grid-cols-5 gap-4">
                     <QuickLinkItem
-                        href="/resources"
+                        href="./resources"
                         label="Resources"
                         count=overview.resources_count
                         color="blue"
                         secondary_count=overview.resource_templates_count
                         secondary_label="templates"
-                        secondary_href="/resource-templates"
+                        secondary_href="./resource-templates"
                     />
                     <QuickLinkItem
-                        href="/tools"
+                        href="./tools"
                         label="Tools"
                         count=overview.tools_count
                         color="green"
                     />
                     <QuickLinkItem
-                        href="/prompts"
+                        href="./prompts"
                         label="Prompts"
                         count=overview.prompts_count
                         color="purple"
                     />
                     <QuickLinkItem
-                        href="/workflows"
+                        href="./workflows"
                         label="Workflows"
                         count=overview.workflows_count
                         color="orange"
                     />
                     <QuickLinkItem
-                        href="/agents"
+                        href="./agents"
                         label="Agents"
                         count=overview.agents_count
                         color="indigo"
                     />
                     <QuickLinkItem
-                        href="/schemas"
+                        href="./schemas"
                         label="Schemas"
                         count=overview.schemas_count
                         color="teal"
                     />
                     <QuickLinkItem
-                        href="/data-lakes"
+                        href="./data-lakes"
                         label="Data Lakes"
                         count=overview.data_lakes_count
                         color="cyan"
@@ -29,7 +29,7 @@ pub fn Dashboard() -> impl IntoView {
                                             <p class="text-blue-800">
                                                 <strong>"No configuration file found."</strong>
                                                 " The server is running with default settings. "
-                                                <a href="/config" class="underline font-semibold">"Configure server settings"</a>
+                                                <a href="./config" class="underline font-semibold">"Configure server settings"</a>
                                                 " to create a configuration file."
                                             </p>
                                         </div>
@@ -178,46 +178,46 @@ fn StatsGrid(overview: ConfigOverview) -> impl IntoView {
                 title="Resources"
                 count=overview.resources_count
                 color="blue"
-                href="/resources"
+                href="./resources"
                 secondary_count=overview.resource_templates_count
                 secondary_label="templates"
-                secondary_href="/resource-templates"
+                secondary_href="./resource-templates"
             />
             <StatCard
                 title="Tools"
                 count=overview.tools_count
                 color="green"
-                href="/tools"
+                href="./tools"
             />
             <StatCard
                 title="Prompts"
                 count=overview.prompts_count
                 color="purple"
-                href="/prompts"
+                href="./prompts"
             />
             <StatCard
                 title="Workflows"
                 count=overview.workflows_count
                 color="orange"
-                href="/workflows"
+                href="./workflows"
             />
             <StatCard
                 title="Agents"
                 count=overview.agents_count
                 color="indigo"
-                href="/agents"
+                href="./agents"
             />
             <StatCard
                 title="Schemas"
                 count=overview.schemas_count
                 color="teal"
-                href="/schemas"
+                href="./schemas"
             />
             <StatCard
                 title="Data Lakes"
                 count=overview.data_lakes_count
                 color="cyan"
-                href="/data-lakes"
+                href="./data-lakes"
             />
         </div>
 
@@ -473,7 +473,7 @@ pub fn DataLakeForm() -> impl IntoView {
             match api::create_data_lake(&data_lake).await {
                 Ok(_) => {
                     let window = web_sys::window().unwrap();
-                    window.location().set_href("/data-lakes").ok();
+                    window.location().set_href("./data-lakes").ok();
                 }
                 Err(e) => {
                     set_error.set(Some(e));
@@ -678,7 +678,7 @@ pub fn DataLakeForm() -> impl IntoView {
 
                         <Suspense fallback=move || view! { <div class="text-gray-500 text-sm">"Loading schemas..."</div> }>
                             {move || {
-                                let schemas = available_schemas.get().map(|s| s).unwrap_or_default();
+                                let schemas = available_schemas.get().unwrap_or_default();
                                 if schemas.is_empty() {
                                     view! {
                                         <div class="bg-yellow-50 border border-yellow-200 text-yellow-700 px-4 py-3 rounded text-sm">
@@ -1002,7 +1002,7 @@ pub fn DataLakeEditForm() -> impl IntoView {
             match api::update_data_lake(&orig_name, &data_lake).await {
                 Ok(_) => {
                     let window = web_sys::window().unwrap();
-                    window.location().set_href("/data-lakes").ok();
+                    window.location().set_href("./data-lakes").ok();
                 }
                 Err(e) => {
                     set_error.set(Some(e));
@@ -1208,7 +1208,7 @@ pub fn DataLakeEditForm() -> impl IntoView {
 
                             <Suspense fallback=move || view! { <div class="text-gray-500 text-sm">"Loading schemas..."</div> }>
                                 {move || {
-                                    let schemas = available_schemas.get().map(|s| s).unwrap_or_default();
+                                    let schemas = available_schemas.get().unwrap_or_default();
                                     view! {
                                         <div class="space-y-3">
                                             <For
@@ -1847,7 +1847,7 @@ pub fn DataLakeRecords() -> impl IntoView {
                                 let current_page_val = page.get();
                                 let pages = count.get()
                                     .flatten()
-                                    .map(|c| (c.count + page_size - 1) / page_size)
+                                    .map(|c| c.count.div_ceil(page_size))
                                     .unwrap_or(1);
                                 // Must be closures for Leptos disabled attribute
                                 let is_first_page = move || current_page_val == 0;
@@ -2031,7 +2031,7 @@ pub fn DataLakeRecords() -> impl IntoView {
                             </p>
                         </div>
                         <a
-                            href="/docs/datafusion"
+                            href="./docs/datafusion"
                             target="_blank"
                             class="text-sm text-cyan-600 hover:text-cyan-800 flex items-center gap-1"
                         >
@@ -3136,10 +3136,7 @@ fn GenerateRecordsModal(
         let config: Option<serde_json::Value> = if strategy_val == "static" || strategy_val == "random" {
             let output = form_output.get();
             if !output.is_empty() && output != "{}" {
-                match serde_json::from_str(&output) {
-                    Ok(v) => Some(v),
-                    Err(_) => None,
-                }
+                serde_json::from_str(&output).ok()
             } else {
                 None
             }
@@ -3575,7 +3572,7 @@ fn AddRecordModal(
                             <div style=move || if input_mode.get() == AddRecordInputMode::Form { "display: block" } else { "display: none" }>
                                 <div class="border border-gray-200 rounded-lg p-4 bg-gray-50 max-h-[50vh] overflow-y-auto">
                                     <SchemaFormGenerator
-                                        schema=schema_json.into()
+                                        schema=schema_json
                                         mode=SchemaFormMode::StaticValue
                                         output=form_output
                                         color="cyan".to_string()
@@ -3834,7 +3831,7 @@ fn EditRecordModal(
                             <div style=move || if input_mode.get() == AddRecordInputMode::Form { "display: block" } else { "display: none" }>
                                 <div class="border border-gray-200 rounded-lg p-4 bg-gray-50 max-h-[50vh] overflow-y-auto">
                                     <SchemaFormGenerator
-                                        schema=schema_json.into()
+                                        schema=schema_json
                                         mode=SchemaFormMode::StaticValue
                                         output=form_output
                                         color="cyan".to_string()
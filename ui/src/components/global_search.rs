@@ -0,0 +1,104 @@
+//! Sidebar search box across all archetypes
+
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+use crate::api;
+use crate::types::SearchResult;
+
+/// Maximum number of results shown in the dropdown
+const MAX_RESULTS: usize = 10;
+
+/// Edit page of a search result
+fn result_href(result: &SearchResult) -> String {
+    let uri = result.uri.as_deref().unwrap_or_default();
+    match result.archetype_type.as_str() {
+        "resource" => format!("/resources/edit/{}", urlencoding::encode(uri)),
+        "resource_template" => format!("/resource-templates/edit/{}", urlencoding::encode(uri)),
+        "prompt" => format!("/prompts/edit/{}", result.name),
+        "workflow" => format!("/workflows/edit/{}", result.name),
+        _ => format!("/tools/edit/{}", result.name),
+    }
+}
+
+fn type_label(archetype_type: &str) -> &'static str {
+    match archetype_type {
+        "resource" => "Resource",
+        "resource_template" => "Template",
+        "prompt" => "Prompt",
+        "workflow" => "Workflow",
+        _ => "Tool",
+    }
+}
+
+#[component]
+pub fn GlobalSearch() -> impl IntoView {
+    let query = RwSignal::new(String::new());
+
+    let results = LocalResource::new(move || {
+        let q = query.get();
+        async move {
+            if q.trim().is_empty() {
+                return Vec::new();
+            }
+            api::search(&q).await.unwrap_or_default()
+        }
+    });
+
+    view! {
+        <div class="relative mb-6">
+            <input
+                type="text"
+                class="block w-full px-3 py-2 rounded-md bg-gray-700 text-white placeholder-gray-400 text-sm focus:outline-none focus:ring-2 focus:ring-green-500"
+                placeholder="Search..."
+                prop:value=move || query.get()
+                on:input=move |ev| query.set(event_target_value(&ev))
+                on:keydown=move |ev| {
+                    if ev.key() == "Escape" {
+                        query.set(String::new());
+                    }
+                }
+            />
+            <Suspense fallback=|| ()>
+                {move || {
+                    let results = results.get().unwrap_or_default();
+                    if query.get().trim().is_empty() {
+                        return None;
+                    }
+                    Some(view! {
+                        <div class="absolute z-20 mt-1 w-80 bg-white text-gray-800 rounded-md shadow-lg max-h-96 overflow-y-auto">
+                            {if results.is_empty() {
+                                view! { <p class="p-3 text-sm text-gray-500">"No matches"</p> }.into_any()
+                            } else {
+                                results
+                                    .into_iter()
+                                    .take(MAX_RESULTS)
+                                    .map(|result| {
+                                        let href = result_href(&result);
+                                        let label = type_label(&result.archetype_type);
+                                        view! {
+                                            <A
+                                                href=href
+                                                attr:class="block px-3 py-2 hover:bg-gray-100 border-b border-gray-100"
+                                                on:click=move |_| query.set(String::new())
+                                            >
+                                                <div class="flex items-center justify-between">
+                                                    <span class="font-medium text-sm truncate">{result.name.clone()}</span>
+                                                    <span class="text-xs text-gray-500 ml-2">{label}</span>
+                                                </div>
+                                                {result.description.clone().map(|d| view! {
+                                                    <p class="text-xs text-gray-500 truncate">{d}</p>
+                                                })}
+                                            </A>
+                                        }
+                                    })
+                                    .collect_view()
+                                    .into_any()
+                            }}
+                        </div>
+                    })
+                }}
+            </Suspense>
+        </div>
+    }
+}
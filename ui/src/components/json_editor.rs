@@ -46,7 +46,7 @@ fn tokenize_json(json: &str) -> Vec<(String, &'static str)> {
                 }
                 // Check if this looks like a key (followed by colon)
                 let remaining: String = chars.clone().take_while(|&c| c == ' ' || c == '\t').collect();
-                let next_meaningful = chars.clone().skip(remaining.len()).next();
+                let next_meaningful = chars.clone().nth(remaining.len());
                 if next_meaningful == Some(':') {
                     tokens.push((s, "json-key"));
                 } else {
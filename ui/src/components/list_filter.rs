@@ -32,7 +32,7 @@ impl SortField {
         }
     }
 
-    pub fn to_str(&self) -> &'static str {
+    pub fn to_str(self) -> &'static str {
         match self {
             SortField::Name => "name",
             SortField::Description => "description",
@@ -215,7 +215,7 @@ pub fn total_pages(total_items: usize, per_page: usize) -> usize {
     if per_page == 0 {
         0
     } else {
-        (total_items + per_page - 1) / per_page
+        total_items.div_ceil(per_page)
     }
 }
 
@@ -602,30 +602,24 @@ pub fn TagInput(
     let (new_tag, set_new_tag) = signal(String::new());
 
     // Use explicit get/set instead of update for better signal propagation through component hierarchies
-    let add_tag = {
-        let tags = tags;
-        move |_| {
-            let tag = new_tag.get().trim().to_string();
-            if !tag.is_empty() {
-                let mut current = tags.get();
-                if !current.contains(&tag) {
-                    current.push(tag.clone());
-                    web_sys::console::log_1(&format!("TagInput: Adding tag '{}', new tags: {:?}", tag, current).into());
-                    tags.set(current);
-                }
-                set_new_tag.set(String::new());
+    let add_tag = move |_| {
+        let tag = new_tag.get().trim().to_string();
+        if !tag.is_empty() {
+            let mut current = tags.get();
+            if !current.contains(&tag) {
+                current.push(tag.clone());
+                web_sys::console::log_1(&format!("TagInput: Adding tag '{}', new tags: {:?}", tag, current).into());
+                tags.set(current);
             }
+            set_new_tag.set(String::new());
         }
     };
 
-    let remove_tag = {
-        let tags = tags;
-        move |tag_to_remove: String| {
-            let mut current = tags.get();
-            current.retain(|x| x != &tag_to_remove);
-            web_sys::console::log_1(&format!("TagInput: Removing tag, new tags: {:?}", current).into());
-            tags.set(current);
-        }
+    let remove_tag = move |tag_to_remove: String| {
+        let mut current = tags.get();
+        current.retain(|x| x != &tag_to_remove);
+        web_sys::console::log_1(&format!("TagInput: Removing tag, new tags: {:?}", current).into());
+        tags.set(current);
     };
 
     view! {
@@ -636,7 +630,7 @@ pub fn TagInput(
             <div class="flex flex-wrap gap-2">
                 {move || tags.get().into_iter().map(|tag| {
                     let tag_clone = tag.clone();
-                    let remove = remove_tag.clone();
+                    let remove = remove_tag;
                     view! {
                         <span class="inline-flex items-center px-2.5 py-0.5 rounded-full text-sm font-medium bg-green-100 text-green-800">
                             {tag}
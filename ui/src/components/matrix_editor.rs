@@ -0,0 +1,86 @@
+//! Matrix Strategy Editor Component
+//!
+//! Provides UI for configuring the Matrix mock strategy:
+//! - How a pair is picked (round robin, input match, random)
+//! - The input/output pairs, edited as a JSON array
+
+use leptos::prelude::*;
+use crate::types::{MatrixConfig, MatrixMode, MatrixPair};
+
+/// Matrix Strategy Editor component
+#[component]
+pub fn MatrixStrategyEditor(
+    /// Matrix configuration signal
+    config: RwSignal<MatrixConfig>,
+) -> impl IntoView {
+    let pairs_text = RwSignal::new(
+        serde_json::to_string_pretty(&config.get_untracked().pairs).unwrap_or_else(|_| "[]".to_string()),
+    );
+    let (parse_error, set_parse_error) = signal(Option::<String>::None);
+
+    // Pairs loaded after the editor was created (e.g. when editing an existing tool)
+    Effect::new(move |_| {
+        let pairs = config.get().pairs;
+        let current: Option<Vec<MatrixPair>> = serde_json::from_str(&pairs_text.get_untracked()).ok();
+        let unchanged = current
+            .map(|c| serde_json::to_value(&c).ok() == serde_json::to_value(&pairs).ok())
+            .unwrap_or(false);
+        if !unchanged {
+            pairs_text.set(serde_json::to_string_pretty(&pairs).unwrap_or_else(|_| "[]".to_string()));
+        }
+    });
+
+    view! {
+        <div class="space-y-4">
+            <div>
+                <label class="block text-sm font-medium text-gray-700 mb-1">"Mode"</label>
+                <select
+                    class="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-green-500"
+                    prop:value=move || match config.get().mode {
+                        MatrixMode::RoundRobin => "round_robin",
+                        MatrixMode::InputMatch => "input_match",
+                        MatrixMode::Random => "random",
+                    }
+                    on:change=move |ev| {
+                        let mode = match event_target_value(&ev).as_str() {
+                            "input_match" => MatrixMode::InputMatch,
+                            "random" => MatrixMode::Random,
+                            _ => MatrixMode::RoundRobin,
+                        };
+                        config.update(|c| c.mode = mode);
+                    }
+                >
+                    <option value="round_robin">"Round Robin"</option>
+                    <option value="input_match">"Input Match"</option>
+                    <option value="random">"Random"</option>
+                </select>
+                <p class="mt-1 text-xs text-gray-500">
+                    "Round robin cycles through the pairs; input match returns the first pair whose input_match is contained in the arguments"
+                </p>
+            </div>
+            <div>
+                <label class="block text-sm font-medium text-gray-700 mb-1">"Pairs *"</label>
+                <textarea
+                    rows=8
+                    class="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-green-500 font-mono text-sm"
+                    placeholder=r#"[{"input_match": {"id": 1}, "output": {"name": "Ada"}}, {"output": {"error": "not found"}}]"#
+                    prop:value=move || pairs_text.get()
+                    on:input=move |ev| {
+                        let val = event_target_value(&ev);
+                        match serde_json::from_str::<Vec<MatrixPair>>(&val) {
+                            Ok(pairs) => {
+                                set_parse_error.set(None);
+                                config.update(|c| c.pairs = pairs);
+                            }
+                            Err(e) => set_parse_error.set(Some(e.to_string())),
+                        }
+                        pairs_text.set(val);
+                    }
+                />
+                {move || parse_error.get().map(|e| view! {
+                    <p class="mt-1 text-xs text-red-600">{e}</p>
+                })}
+            </div>
+        </div>
+    }
+}
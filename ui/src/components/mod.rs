@@ -3,12 +3,15 @@ pub mod artifact_selector;
 pub mod dashboard;
 pub mod config;
 pub mod data_lakes;
+pub mod global_search;
+pub mod callback_editor;
 pub mod data_lake_crud_editor;
 pub mod database_editor;
 pub mod json_editor;
 pub mod list_filter;
 pub mod sql_editor;
 pub mod logs;
+pub mod matrix_editor;
 pub mod resources;
 pub mod resource_templates;
 pub mod schemas;
@@ -201,7 +201,7 @@ pub fn Prompts() -> impl IntoView {
                             </span>
                         </button>
                     </div>
-                    <a href="/prompts/new" class="bg-purple-500 hover:bg-purple-600 text-white px-4 py-2 rounded flex items-center gap-2">
+                    <a href="./prompts/new" class="bg-purple-500 hover:bg-purple-600 text-white px-4 py-2 rounded flex items-center gap-2">
                         <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                             <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                         </svg>
@@ -488,7 +488,7 @@ fn EmptyState() -> impl IntoView {
             <h3 class="mt-2 text-sm font-medium text-gray-900">"No prompts"</h3>
             <p class="mt-1 text-sm text-gray-500">"Get started by creating a new prompt."</p>
             <div class="mt-6">
-                <a href="/prompts/new" class="inline-flex items-center px-4 py-2 bg-purple-500 text-white rounded hover:bg-purple-600">
+                <a href="./prompts/new" class="inline-flex items-center px-4 py-2 bg-purple-500 text-white rounded hover:bg-purple-600">
                     <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                     </svg>
@@ -580,7 +580,7 @@ fn PromptTable(
                                         "Test"
                                     </button>
                                     <a
-                                        href=format!("/prompts/edit/{}", name_for_edit)
+                                        href=format!("./prompts/edit/{}", name_for_edit)
                                         class="text-blue-600 hover:text-blue-900 mr-3"
                                     >
                                         "Edit"
@@ -646,7 +646,7 @@ fn PromptCards(
                                 "Test"
                             </button>
                             <a
-                                href=format!("/prompts/edit/{}", name_for_edit)
+                                href=format!("./prompts/edit/{}", name_for_edit)
                                 class="px-3 py-1 text-sm text-blue-600 hover:bg-blue-50 rounded"
                             >
                                 "Edit"
@@ -747,7 +747,7 @@ pub fn PromptForm() -> impl IntoView {
             match api::create_prompt(&prompt).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/prompts");
+                        let _ = window.location().set_href("./prompts");
                     }
                 }
                 Err(e) => {
@@ -761,7 +761,7 @@ pub fn PromptForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/prompts" class="text-gray-500 hover:text-gray-700">
+                <a href="./prompts" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -929,7 +929,7 @@ pub fn PromptForm() -> impl IntoView {
                         {move || if saving.get() { "Creating..." } else { "Create Prompt" }}
                     </button>
                     <a
-                        href="/prompts"
+                        href="./prompts"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -1067,7 +1067,7 @@ pub fn PromptEditForm() -> impl IntoView {
             match api::update_prompt(&orig_name, &prompt).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/prompts");
+                        let _ = window.location().set_href("./prompts");
                     }
                 }
                 Err(e) => {
@@ -1081,7 +1081,7 @@ pub fn PromptEditForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/prompts" class="text-gray-500 hover:text-gray-700">
+                <a href="./prompts" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -1263,7 +1263,7 @@ pub fn PromptEditForm() -> impl IntoView {
                         {move || if saving.get() { "Saving..." } else { "Save Changes" }}
                     </button>
                     <a
-                        href="/prompts"
+                        href="./prompts"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -148,7 +148,7 @@ pub fn ResourceTemplates() -> impl IntoView {
                             </span>
                         </button>
                     </div>
-                    <a href="/resource-templates/new" class="bg-purple-500 hover:bg-purple-600 text-white px-4 py-2 rounded flex items-center gap-2">
+                    <a href="./resource-templates/new" class="bg-purple-500 hover:bg-purple-600 text-white px-4 py-2 rounded flex items-center gap-2">
                         <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                             <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                         </svg>
@@ -401,7 +401,7 @@ fn EmptyState() -> impl IntoView {
             <h3 class="mt-2 text-sm font-medium text-gray-900">"No resource templates"</h3>
             <p class="mt-1 text-sm text-gray-500">"Create a resource template with URI pattern variables."</p>
             <div class="mt-6">
-                <a href="/resource-templates/new" class="inline-flex items-center px-4 py-2 bg-purple-500 text-white rounded hover:bg-purple-600">
+                <a href="./resource-templates/new" class="inline-flex items-center px-4 py-2 bg-purple-500 text-white rounded hover:bg-purple-600">
                     <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                     </svg>
@@ -494,7 +494,7 @@ fn ResourceTemplateTable(
                                         "Test"
                                     </button>
                                     <a
-                                        href=format!("/resource-templates/edit/{}", urlencoding::encode(&uri_for_edit))
+                                        href=format!("./resource-templates/edit/{}", urlencoding::encode(&uri_for_edit))
                                         class="text-purple-600 hover:text-purple-900 mr-3"
                                     >
                                         "Edit"
@@ -572,7 +572,7 @@ fn ResourceTemplateCards(
                                 "Test"
                             </button>
                             <a
-                                href=format!("/resource-templates/edit/{}", urlencoding::encode(&uri_for_edit))
+                                href=format!("./resource-templates/edit/{}", urlencoding::encode(&uri_for_edit))
                                 class="px-3 py-1 text-sm text-purple-600 hover:bg-purple-50 rounded"
                             >
                                 "Edit"
@@ -700,10 +700,8 @@ pub fn ResourceTemplateForm() -> impl IntoView {
             "template" => {
                 config.template = Some(mock_template.get());
             }
-            "random" => {
-                if !mock_faker_type.get().is_empty() {
-                    config.faker_type = Some(mock_faker_type.get());
-                }
+            "random" if !mock_faker_type.get().is_empty() => {
+                config.faker_type = Some(mock_faker_type.get());
             }
             "stateful" => {
                 config.stateful = Some(StatefulConfig {
@@ -729,6 +727,8 @@ pub fn ResourceTemplateForm() -> impl IntoView {
                 config.file = Some(FileConfig {
                     path: mock_file_path.get(),
                     selection: mock_file_selection.get(),
+                    stream: false,
+                    max_bytes: None,
                 });
             }
             "pattern" => {
@@ -817,7 +817,7 @@ pub fn ResourceTemplateForm() -> impl IntoView {
             match api::create_resource_template(&template).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/resource-templates");
+                        let _ = window.location().set_href("./resource-templates");
                     }
                 }
                 Err(e) => {
@@ -831,7 +831,7 @@ pub fn ResourceTemplateForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/resource-templates" class="text-gray-500 hover:text-gray-700">
+                <a href="./resource-templates" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -887,7 +887,7 @@ pub fn ResourceTemplateForm() -> impl IntoView {
                         {move || if saving.get() { "Creating..." } else { "Create Template" }}
                     </button>
                     <a
-                        href="/resource-templates"
+                        href="./resource-templates"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -1655,6 +1655,9 @@ pub fn ResourceTemplateEditForm() -> impl IntoView {
                             MockStrategyType::LLM => "llm",
                             MockStrategyType::Database => "database",
                             MockStrategyType::DataLakeCrud => "data_lake_crud",
+                            MockStrategyType::Callback => "callback",
+                            MockStrategyType::Matrix => "matrix",
+                            MockStrategyType::Csv => "csv",
                         };
                         set_mock_strategy.set(strategy.to_string());
 
@@ -1759,10 +1762,8 @@ pub fn ResourceTemplateEditForm() -> impl IntoView {
             "template" => {
                 config.template = Some(mock_template.get());
             }
-            "random" => {
-                if !mock_faker_type.get().is_empty() {
-                    config.faker_type = Some(mock_faker_type.get());
-                }
+            "random" if !mock_faker_type.get().is_empty() => {
+                config.faker_type = Some(mock_faker_type.get());
             }
             "stateful" => {
                 config.stateful = Some(StatefulConfig {
@@ -1788,6 +1789,8 @@ pub fn ResourceTemplateEditForm() -> impl IntoView {
                 config.file = Some(FileConfig {
                     path: mock_file_path.get(),
                     selection: mock_file_selection.get(),
+                    stream: false,
+                    max_bytes: None,
                 });
             }
             "pattern" => {
@@ -1878,7 +1881,7 @@ pub fn ResourceTemplateEditForm() -> impl IntoView {
             match api::update_resource_template(&orig, &template).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/resource-templates");
+                        let _ = window.location().set_href("./resource-templates");
                     }
                 }
                 Err(e) => {
@@ -1892,7 +1895,7 @@ pub fn ResourceTemplateEditForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/resource-templates" class="text-gray-500 hover:text-gray-700">
+                <a href="./resource-templates" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -1962,7 +1965,7 @@ pub fn ResourceTemplateEditForm() -> impl IntoView {
                         {move || if saving.get() { "Saving..." } else { "Save Changes" }}
                     </button>
                     <a
-                        href="/resource-templates"
+                        href="./resource-templates"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -138,7 +138,7 @@ pub fn Resources() -> impl IntoView {
                             </span>
                         </button>
                     </div>
-                    <a href="/resources/new" class="bg-blue-500 hover:bg-blue-600 text-white px-4 py-2 rounded flex items-center gap-2">
+                    <a href="./resources/new" class="bg-blue-500 hover:bg-blue-600 text-white px-4 py-2 rounded flex items-center gap-2">
                         <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                             <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                         </svg>
@@ -379,7 +379,7 @@ fn EmptyState() -> impl IntoView {
             <h3 class="mt-2 text-sm font-medium text-gray-900">"No resources"</h3>
             <p class="mt-1 text-sm text-gray-500">"Get started by creating a new resource."</p>
             <div class="mt-6">
-                <a href="/resources/new" class="inline-flex items-center px-4 py-2 bg-blue-500 text-white rounded hover:bg-blue-600">
+                <a href="./resources/new" class="inline-flex items-center px-4 py-2 bg-blue-500 text-white rounded hover:bg-blue-600">
                     <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                     </svg>
@@ -474,7 +474,7 @@ fn ResourceTable(
                                         "Test"
                                     </button>
                                     <a
-                                        href=format!("/resources/edit/{}", urlencoding::encode(&uri_for_edit))
+                                        href=format!("./resources/edit/{}", urlencoding::encode(&uri_for_edit))
                                         class="text-blue-600 hover:text-blue-900 mr-3"
                                     >
                                         "Edit"
@@ -548,7 +548,7 @@ fn ResourceCards(
                                 "Test"
                             </button>
                             <a
-                                href=format!("/resources/edit/{}", urlencoding::encode(&uri_for_edit))
+                                href=format!("./resources/edit/{}", urlencoding::encode(&uri_for_edit))
                                 class="px-3 py-1 text-sm text-blue-600 hover:bg-blue-50 rounded"
                             >
                                 "Edit"
@@ -672,10 +672,8 @@ pub fn ResourceForm() -> impl IntoView {
             "template" => {
                 config.template = Some(mock_template.get());
             }
-            "random" => {
-                if !mock_faker_type.get().is_empty() {
-                    config.faker_type = Some(mock_faker_type.get());
-                }
+            "random" if !mock_faker_type.get().is_empty() => {
+                config.faker_type = Some(mock_faker_type.get());
             }
             "stateful" => {
                 config.stateful = Some(StatefulConfig {
@@ -701,6 +699,8 @@ pub fn ResourceForm() -> impl IntoView {
                 config.file = Some(FileConfig {
                     path: mock_file_path.get(),
                     selection: mock_file_selection.get(),
+                    stream: false,
+                    max_bytes: None,
                 });
             }
             "pattern" => {
@@ -798,7 +798,7 @@ pub fn ResourceForm() -> impl IntoView {
             match api::create_resource(&resource).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/resources");
+                        let _ = window.location().set_href("./resources");
                     }
                 }
                 Err(e) => {
@@ -812,7 +812,7 @@ pub fn ResourceForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/resources" class="text-gray-500 hover:text-gray-700">
+                <a href="./resources" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -866,7 +866,7 @@ pub fn ResourceForm() -> impl IntoView {
                         {move || if saving.get() { "Creating..." } else { "Create Resource" }}
                     </button>
                     <a
-                        href="/resources"
+                        href="./resources"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -1536,6 +1536,8 @@ pub fn ResourceEditForm() -> impl IntoView {
     let (mock_script_lang, set_mock_script_lang) = signal("rhai".to_string());
     let (mock_file_path, set_mock_file_path) = signal(String::new());
     let (mock_file_selection, set_mock_file_selection) = signal("random".to_string());
+    // Not editable in the form yet, but kept when saving
+    let mock_file_stream = RwSignal::new((false, Option::<u64>::None));
     let (mock_pattern, set_mock_pattern) = signal(String::new());
     let (mock_llm_provider, set_mock_llm_provider) = signal("openai".to_string());
     let (mock_llm_model, set_mock_llm_model) = signal(String::new());
@@ -1598,6 +1600,9 @@ pub fn ResourceEditForm() -> impl IntoView {
                             MockStrategyType::LLM => "llm",
                             MockStrategyType::Database => "database",
                             MockStrategyType::DataLakeCrud => "data_lake_crud",
+                            MockStrategyType::Callback => "callback",
+                            MockStrategyType::Matrix => "matrix",
+                            MockStrategyType::Csv => "csv",
                         };
                         set_mock_strategy.set(strategy.to_string());
 
@@ -1634,6 +1639,7 @@ pub fn ResourceEditForm() -> impl IntoView {
                         if let Some(file) = &mock.file {
                             set_mock_file_path.set(file.path.clone());
                             set_mock_file_selection.set(file.selection.clone());
+                            mock_file_stream.set((file.stream, file.max_bytes));
                         }
                         if let Some(pattern) = &mock.pattern {
                             set_mock_pattern.set(pattern.clone());
@@ -1702,10 +1708,8 @@ pub fn ResourceEditForm() -> impl IntoView {
             "template" => {
                 config.template = Some(mock_template.get());
             }
-            "random" => {
-                if !mock_faker_type.get().is_empty() {
-                    config.faker_type = Some(mock_faker_type.get());
-                }
+            "random" if !mock_faker_type.get().is_empty() => {
+                config.faker_type = Some(mock_faker_type.get());
             }
             "stateful" => {
                 config.stateful = Some(StatefulConfig {
@@ -1731,6 +1735,8 @@ pub fn ResourceEditForm() -> impl IntoView {
                 config.file = Some(FileConfig {
                     path: mock_file_path.get(),
                     selection: mock_file_selection.get(),
+                    stream: mock_file_stream.get_untracked().0,
+                    max_bytes: mock_file_stream.get_untracked().1,
                 });
             }
             "pattern" => {
@@ -1829,7 +1835,7 @@ pub fn ResourceEditForm() -> impl IntoView {
             match api::update_resource(&orig_uri, &resource).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/resources");
+                        let _ = window.location().set_href("./resources");
                     }
                 }
                 Err(e) => {
@@ -1843,7 +1849,7 @@ pub fn ResourceEditForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/resources" class="text-gray-500 hover:text-gray-700">
+                <a href="./resources" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -1911,7 +1917,7 @@ pub fn ResourceEditForm() -> impl IntoView {
                         {move || if saving.get() { "Saving..." } else { "Save Changes" }}
                     </button>
                     <a
-                        href="/resources"
+                        href="./resources"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -747,8 +747,8 @@ impl SchemaProperty {
         match self.prop_type.as_str() {
             "array" => {
                 let items = if self.items_type == "object" && !self.nested_properties.is_empty() {
-                    let nested_schema = properties_to_schema(&self.nested_properties);
-                    nested_schema
+                    
+                    properties_to_schema(&self.nested_properties)
                 } else if self.items_type == "enum" && !self.enum_values.is_empty() {
                     // Array of enum values
                     json!({"type": "string", "enum": self.enum_values})
@@ -788,9 +788,7 @@ impl SchemaProperty {
                         prop.insert("additionalProperties".to_string(), json!(b));
                     }
                     AdditionalProperties::Schema(ref_val) => {
-                        if ref_val.starts_with("#/") || ref_val.starts_with("http") {
-                            prop.insert("additionalProperties".to_string(), json!({"$ref": ref_val}));
-                        } else if !ref_val.is_empty() {
+                        if !ref_val.is_empty() {
                             prop.insert("additionalProperties".to_string(), json!({"$ref": ref_val}));
                         }
                     }
@@ -115,7 +115,8 @@ pub fn SchemaFormGenerator(
                 }
             }
             SchemaFormMode::FakerConfig => {
-                let configs = faker_configs.get();
+                let mut configs = faker_configs.get();
+                configs.schema = Some(schema.get());
                 if let Ok(json_str) = serde_json::to_string_pretty(&configs) {
                     output.set(json_str);
                     set_json_text.set(output.get());
@@ -301,7 +302,7 @@ pub fn SchemaField(
         1 => "ml-4 border-l-2 border-gray-200 pl-3",
         _ => "ml-4 border-l-2 border-gray-200 pl-3",
     };
-    let bg_class = if depth % 2 == 0 { "bg-gray-50" } else { "bg-white" };
+    let bg_class = if depth.is_multiple_of(2) { "bg-gray-50" } else { "bg-white" };
 
     view! {
         <div class=format!("rounded-lg p-3 {} {}", bg_class, indent_class)>
@@ -311,8 +311,7 @@ fn extract_common_props(schema: &Value) -> ResolvedSchemaNode {
         default: schema.get("default").cloned(),
         examples: schema
             .get("examples")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.clone())
+            .and_then(|v| v.as_array()).cloned()
             .unwrap_or_default(),
         fake_strategy,
         fake_strategy_config,
@@ -94,25 +94,6 @@ impl PropertyPath {
         self.segments.iter()
     }
 
-    /// Convert to dot-notation string: "user.address.city" or "items[*].name"
-    pub fn to_string(&self) -> String {
-        self.segments
-            .iter()
-            .enumerate()
-            .map(|(i, seg)| match seg {
-                PathSegment::Property(name) => {
-                    if i == 0 {
-                        name.clone()
-                    } else {
-                        format!(".{}", name)
-                    }
-                }
-                PathSegment::Index(idx) => format!("[{}]", idx),
-                PathSegment::ArrayWildcard => "[*]".to_string(),
-            })
-            .collect()
-    }
-
     /// Parse a path string into PropertyPath
     pub fn parse(s: &str) -> Self {
         let mut segments = Vec::new();
@@ -161,9 +142,18 @@ impl PropertyPath {
     }
 }
 
+/// Dot-notation string: "user.address.city" or "items[*].name"
 impl fmt::Display for PropertyPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+        for (i, seg) in self.segments.iter().enumerate() {
+            match seg {
+                PathSegment::Property(name) if i == 0 => write!(f, "{}", name)?,
+                PathSegment::Property(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(idx) => write!(f, "[{}]", idx)?,
+                PathSegment::ArrayWildcard => write!(f, "[*]")?,
+            }
+        }
+        Ok(())
     }
 }
 
@@ -380,6 +370,9 @@ pub struct FakerSchemaConfig {
     /// Array path -> array size configuration
     #[serde(default)]
     pub arrays: HashMap<String, FakerArrayConfig>,
+    /// Schema the backend walks to generate nested objects and arrays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
 }
 
 #[allow(dead_code)]
@@ -1002,7 +1002,7 @@ pub fn SchemaForm() -> impl IntoView {
             match api::create_schema(&schema).await {
                 Ok(_) => {
                     let window = web_sys::window().unwrap();
-                    window.location().set_href("/schemas").ok();
+                    window.location().set_href("./schemas").ok();
                 }
                 Err(e) => {
                     set_error.set(Some(e));
@@ -1370,7 +1370,7 @@ pub fn SchemaEditForm() -> impl IntoView {
             match api::update_schema(&orig_name, &schema).await {
                 Ok(_) => {
                     let window = web_sys::window().unwrap();
-                    window.location().set_href("/schemas").ok();
+                    window.location().set_href("./schemas").ok();
                 }
                 Err(e) => {
                     set_error.set(Some(e));
@@ -489,7 +489,7 @@ pub fn SqlEditor(
         let current_word = &before_cursor[word_start..];
 
         // Check if we should show autocomplete
-        let should_show = !current_word.is_empty() && current_word.len() >= 1;
+        let should_show = !current_word.is_empty() && !current_word.is_empty();
 
         if should_show {
             let current_upper = current_word.to_uppercase();
@@ -595,8 +595,8 @@ pub fn SqlEditor(
                     ev.prevent_default();
                     set_selected_index.set(if current == 0 { items.len().saturating_sub(1) } else { current - 1 });
                 }
-                "Tab" | "Enter" => {
-                    if !items.is_empty() {
+                "Tab" | "Enter"
+                    if !items.is_empty() => {
                         ev.prevent_default();
                         // Insert the selected suggestion
                         let suggestion = &items[current].text;
@@ -617,7 +617,7 @@ pub fn SqlEditor(
                         set_show_autocomplete.set(false);
 
                         // Update cursor position after the DOM updates
-                        let textarea_ref_clone = textarea_ref.clone();
+                        let textarea_ref_clone = textarea_ref;
                         request_animation_frame(move || {
                             if let Some(textarea) = textarea_ref_clone.get() {
                                 let pos = new_cursor_pos as u32;
@@ -627,7 +627,6 @@ pub fn SqlEditor(
                             }
                         });
                     }
-                }
                 "Escape" => {
                     ev.prevent_default();
                     set_show_autocomplete.set(false);
@@ -657,7 +656,7 @@ pub fn SqlEditor(
         set_show_autocomplete.set(false);
 
         // Focus back on textarea and set cursor position after DOM updates
-        let textarea_ref_clone = textarea_ref.clone();
+        let textarea_ref_clone = textarea_ref;
         request_animation_frame(move || {
             if let Some(textarea) = textarea_ref_clone.get() {
                 let pos = new_cursor_pos as u32;
@@ -26,6 +26,10 @@ pub struct StepData {
     pub retry_max_attempts: u32,
     pub retry_delay_ms: u64,
     pub fallback_value: String,
+    /// Named state locks, carried through unchanged when editing
+    pub state_lock_keys: Vec<String>,
+    /// Injected context fields, carried through unchanged when editing
+    pub inject_context: Vec<String>,
 }
 
 static NEXT_STEP_KEY: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1);
@@ -50,6 +54,8 @@ impl StepData {
             retry_max_attempts: 3,
             retry_delay_ms: 1000,
             fallback_value: "null".to_string(),
+            state_lock_keys: Vec::new(),
+            inject_context: Vec::new(),
         }
     }
 }
@@ -106,6 +112,8 @@ pub fn step_data_to_workflow_step(data: &StepData) -> Result<WorkflowStep, Strin
         loop_var: data.loop_var.clone(),
         loop_concurrency: data.loop_concurrency,
         on_error,
+        state_lock_keys: data.state_lock_keys.clone(),
+        inject_context: data.inject_context.clone(),
     })
 }
 
@@ -138,6 +146,8 @@ pub fn workflow_step_to_step_data(step: &WorkflowStep) -> StepData {
         retry_max_attempts,
         retry_delay_ms,
         fallback_value,
+        state_lock_keys: step.state_lock_keys.clone(),
+        inject_context: step.inject_context.clone(),
     }
 }
 
@@ -7,13 +7,15 @@ use crate::api;
 use crate::types::{
     Tool, MockConfig, MockStrategyType, StatefulConfig, StateOperation,
     FileConfig, ScriptLang, LLMConfig, LLMProvider, MockDatabaseConfig, DatabaseType,
-    DataFusionConfig, DataLakeCrudConfig,
+    DataFusionConfig, DataLakeCrudConfig, CallbackConfig, MatrixConfig,
 };
 use crate::components::json_editor::JsonEditor;
 use crate::components::schema_editor::FullSchemaEditor;
 use crate::components::schema_form::{SchemaFormGenerator, SchemaFormMode};
 use crate::components::database_editor::DatabaseStrategyEditor;
 use crate::components::data_lake_crud_editor::DataLakeCrudEditor;
+use crate::components::callback_editor::CallbackStrategyEditor;
+use crate::components::matrix_editor::MatrixStrategyEditor;
 use crate::components::list_filter::{
     ListFilterBar, Pagination, TagBadges, TagInput,
     extract_tags, filter_items, paginate_items, total_pages,
@@ -239,7 +241,7 @@ pub fn Tools() -> impl IntoView {
                             </span>
                         </button>
                     </div>
-                    <a href="/tools/new" class="bg-green-500 hover:bg-green-600 text-white px-4 py-2 rounded flex items-center gap-2">
+                    <a href="./tools/new" class="bg-green-500 hover:bg-green-600 text-white px-4 py-2 rounded flex items-center gap-2">
                         <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                             <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                         </svg>
@@ -590,7 +592,7 @@ fn EmptyState() -> impl IntoView {
             <h3 class="mt-2 text-sm font-medium text-gray-900">"No tools"</h3>
             <p class="mt-1 text-sm text-gray-500">"Get started by creating a new tool."</p>
             <div class="mt-6">
-                <a href="/tools/new" class="inline-flex items-center px-4 py-2 bg-green-500 text-white rounded hover:bg-green-600">
+                <a href="./tools/new" class="inline-flex items-center px-4 py-2 bg-green-500 text-white rounded hover:bg-green-600">
                     <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                     </svg>
@@ -679,7 +681,7 @@ fn ToolTable(
                                         "Test"
                                     </button>
                                     <a
-                                        href=format!("/tools/edit/{}", name_for_edit)
+                                        href=format!("./tools/edit/{}", name_for_edit)
                                         class="text-blue-600 hover:text-blue-900 mr-3"
                                     >
                                         "Edit"
@@ -742,7 +744,7 @@ fn ToolCards(
                                 "Test"
                             </button>
                             <a
-                                href=format!("/tools/edit/{}", name_for_edit)
+                                href=format!("./tools/edit/{}", name_for_edit)
                                 class="px-3 py-1 text-sm text-blue-600 hover:bg-blue-50 rounded"
                             >
                                 "Edit"
@@ -804,6 +806,10 @@ pub fn ToolForm() -> impl IntoView {
     let mock_datafusion_config = RwSignal::new(DataFusionConfig::default());
     // DataLakeCrud strategy signal
     let mock_data_lake_crud_config = RwSignal::new(DataLakeCrudConfig::default());
+    // Callback strategy signal
+    let mock_callback_config = RwSignal::new(CallbackConfig::default());
+    // Matrix strategy signal
+    let mock_matrix_config = RwSignal::new(MatrixConfig::default());
 
     // Helper functions for LLM mock strategy
     let get_default_llm_model = |provider: &str| -> &'static str {
@@ -875,6 +881,9 @@ pub fn ToolForm() -> impl IntoView {
             "llm" => MockStrategyType::LLM,
             "database" => MockStrategyType::Database,
             "data_lake_crud" => MockStrategyType::DataLakeCrud,
+            "callback" => MockStrategyType::Callback,
+            "matrix" => MockStrategyType::Matrix,
+            "csv" => MockStrategyType::Csv,
             _ => return None,
         };
 
@@ -887,10 +896,8 @@ pub fn ToolForm() -> impl IntoView {
             "template" => {
                 config.template = Some(mock_template.get());
             }
-            "random" => {
-                if !mock_faker_type.get().is_empty() {
-                    config.faker_type = Some(mock_faker_type.get());
-                }
+            "random" if !mock_faker_type.get().is_empty() => {
+                config.faker_type = Some(mock_faker_type.get());
             }
             "stateful" => {
                 config.stateful = Some(StatefulConfig {
@@ -916,6 +923,8 @@ pub fn ToolForm() -> impl IntoView {
                 config.file = Some(FileConfig {
                     path: mock_file_path.get(),
                     selection: mock_file_selection.get(),
+                    stream: false,
+                    max_bytes: None,
                 });
             }
             "pattern" => {
@@ -957,6 +966,12 @@ pub fn ToolForm() -> impl IntoView {
             "data_lake_crud" => {
                 config.data_lake_crud = Some(mock_data_lake_crud_config.get());
             }
+            "callback" => {
+                config.callback = Some(mock_callback_config.get());
+            }
+            "matrix" => {
+                config.matrix = Some(mock_matrix_config.get());
+            }
             _ => {}
         }
 
@@ -1026,13 +1041,15 @@ pub fn ToolForm() -> impl IntoView {
             output_schema: output_schema_opt.clone(),
             static_response: static_resp,
             mock: build_mock_config(),
+            dedup_window_ms: None,
+            normalize_response: None,
         };
 
         wasm_bindgen_futures::spawn_local(async move {
             match api::create_tool(&tool).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/tools");
+                        let _ = window.location().set_href("./tools");
                     }
                 }
                 Err(e) => {
@@ -1048,7 +1065,7 @@ pub fn ToolForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/tools" class="text-gray-500 hover:text-gray-700">
+                <a href="./tools" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -1214,6 +1231,8 @@ pub fn ToolForm() -> impl IntoView {
                             <option value="llm">"LLM (AI Generated)"</option>
                             <option value="database">"Database Query"</option>
                             <option value="data_lake_crud">"Data Lake CRUD"</option>
+                            <option value="callback">"Callback (Webhook)"</option>
+                            <option value="matrix">"Matrix (Canned Responses)"</option>
                         </select>
                         <p class="mt-1 text-xs text-gray-500">"Choose how the tool response should be generated"</p>
                     </div>
@@ -1613,6 +1632,12 @@ pub fn ToolForm() -> impl IntoView {
                                         })
                                     />
                                 }.into_any(),
+                                "callback" => view! {
+                                    <CallbackStrategyEditor config=mock_callback_config />
+                                }.into_any(),
+                                "matrix" => view! {
+                                    <MatrixStrategyEditor config=mock_matrix_config />
+                                }.into_any(),
                                 _ => view! { <div></div> }.into_any(),
                             }
                         }}
@@ -1627,7 +1652,7 @@ pub fn ToolForm() -> impl IntoView {
                         {move || if saving.get() { "Creating..." } else { "Create Tool" }}
                     </button>
                     <a
-                        href="/tools"
+                        href="./tools"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -1655,6 +1680,9 @@ pub fn ToolEditForm() -> impl IntoView {
     let (input_schema, set_input_schema) = signal(default_schema.clone());
     let (output_schema, set_output_schema) = signal(default_schema);
     let static_response = RwSignal::new(String::new());
+    // Not editable in the form yet, but kept when saving
+    let dedup_window_ms = RwSignal::new(Option::<u64>::None);
+    let normalize_response = RwSignal::new(Option::<bool>::None);
     let (error, set_error) = signal(Option::<String>::None);
     let (saving, set_saving) = signal(false);
     let (loading, set_loading) = signal(true);
@@ -1671,6 +1699,8 @@ pub fn ToolEditForm() -> impl IntoView {
     let (mock_script_lang, set_mock_script_lang) = signal("rhai".to_string());
     let (mock_file_path, set_mock_file_path) = signal(String::new());
     let (mock_file_selection, set_mock_file_selection) = signal("random".to_string());
+    // Not editable in the form yet, but kept when saving
+    let mock_file_stream = RwSignal::new((false, Option::<u64>::None));
     let (mock_pattern, set_mock_pattern) = signal(String::new());
     let (mock_llm_provider, set_mock_llm_provider) = signal("openai".to_string());
     let (mock_llm_model, set_mock_llm_model) = signal("gpt-4o".to_string());
@@ -1687,6 +1717,10 @@ pub fn ToolEditForm() -> impl IntoView {
     let mock_datafusion_config = RwSignal::new(DataFusionConfig::default());
     // DataLakeCrud strategy signal
     let mock_data_lake_crud_config = RwSignal::new(DataLakeCrudConfig::default());
+    // Callback strategy signal
+    let mock_callback_config = RwSignal::new(CallbackConfig::default());
+    // Matrix strategy signal
+    let mock_matrix_config = RwSignal::new(MatrixConfig::default());
 
     // Helper functions for LLM mock strategy
     let get_default_llm_model = |provider: &str| -> &'static str {
@@ -1767,6 +1801,8 @@ pub fn ToolEditForm() -> impl IntoView {
                     if let Some(resp) = &tool.static_response {
                         static_response.set(serde_json::to_string_pretty(resp).unwrap_or_default());
                     }
+                    dedup_window_ms.set(tool.dedup_window_ms);
+                    normalize_response.set(tool.normalize_response);
 
                     // Load mock config
                     if let Some(mock) = &tool.mock {
@@ -1781,6 +1817,9 @@ pub fn ToolEditForm() -> impl IntoView {
                             MockStrategyType::LLM => "llm",
                             MockStrategyType::Database => "database",
                             MockStrategyType::DataLakeCrud => "data_lake_crud",
+                            MockStrategyType::Callback => "callback",
+                            MockStrategyType::Matrix => "matrix",
+                            MockStrategyType::Csv => "csv",
                         };
                         set_mock_strategy.set(strategy.to_string());
 
@@ -1817,6 +1856,7 @@ pub fn ToolEditForm() -> impl IntoView {
                         if let Some(file) = &mock.file {
                             set_mock_file_path.set(file.path.clone());
                             set_mock_file_selection.set(file.selection.clone());
+                            mock_file_stream.set((file.stream, file.max_bytes));
                         }
                         if let Some(pattern) = &mock.pattern {
                             set_mock_pattern.set(pattern.clone());
@@ -1852,6 +1892,12 @@ pub fn ToolEditForm() -> impl IntoView {
                         if let Some(data_lake_crud) = &mock.data_lake_crud {
                             mock_data_lake_crud_config.set(data_lake_crud.clone());
                         }
+                        if let Some(callback) = &mock.callback {
+                            mock_callback_config.set(callback.clone());
+                        }
+                        if let Some(matrix) = &mock.matrix {
+                            mock_matrix_config.set(matrix.clone());
+                        }
                     }
                     set_has_loaded.set(true);
                 }
@@ -1880,6 +1926,9 @@ pub fn ToolEditForm() -> impl IntoView {
             "llm" => MockStrategyType::LLM,
             "database" => MockStrategyType::Database,
             "data_lake_crud" => MockStrategyType::DataLakeCrud,
+            "callback" => MockStrategyType::Callback,
+            "matrix" => MockStrategyType::Matrix,
+            "csv" => MockStrategyType::Csv,
             _ => return None,
         };
 
@@ -1892,10 +1941,8 @@ pub fn ToolEditForm() -> impl IntoView {
             "template" => {
                 config.template = Some(mock_template.get());
             }
-            "random" => {
-                if !mock_faker_type.get().is_empty() {
-                    config.faker_type = Some(mock_faker_type.get());
-                }
+            "random" if !mock_faker_type.get().is_empty() => {
+                config.faker_type = Some(mock_faker_type.get());
             }
             "stateful" => {
                 config.stateful = Some(StatefulConfig {
@@ -1921,6 +1968,8 @@ pub fn ToolEditForm() -> impl IntoView {
                 config.file = Some(FileConfig {
                     path: mock_file_path.get(),
                     selection: mock_file_selection.get(),
+                    stream: mock_file_stream.get_untracked().0,
+                    max_bytes: mock_file_stream.get_untracked().1,
                 });
             }
             "pattern" => {
@@ -1962,6 +2011,12 @@ pub fn ToolEditForm() -> impl IntoView {
             "data_lake_crud" => {
                 config.data_lake_crud = Some(mock_data_lake_crud_config.get());
             }
+            "callback" => {
+                config.callback = Some(mock_callback_config.get());
+            }
+            "matrix" => {
+                config.matrix = Some(mock_matrix_config.get());
+            }
             _ => {}
         }
 
@@ -2031,13 +2086,15 @@ pub fn ToolEditForm() -> impl IntoView {
             output_schema: output_schema_opt.clone(),
             static_response: static_resp,
             mock: build_mock_config(),
+            dedup_window_ms: dedup_window_ms.get_untracked(),
+            normalize_response: normalize_response.get_untracked(),
         };
 
         wasm_bindgen_futures::spawn_local(async move {
             match api::update_tool(&orig_name, &tool).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/tools");
+                        let _ = window.location().set_href("./tools");
                     }
                 }
                 Err(e) => {
@@ -2053,7 +2110,7 @@ pub fn ToolEditForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/tools" class="text-gray-500 hover:text-gray-700">
+                <a href="./tools" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -2233,6 +2290,8 @@ pub fn ToolEditForm() -> impl IntoView {
                             <option value="llm">"LLM (AI Generated)"</option>
                             <option value="database">"Database Query"</option>
                             <option value="data_lake_crud">"Data Lake CRUD"</option>
+                            <option value="callback">"Callback (Webhook)"</option>
+                            <option value="matrix">"Matrix (Canned Responses)"</option>
                         </select>
                         <p class="mt-1 text-xs text-gray-500">"Choose how the tool response should be generated"</p>
                     </div>
@@ -2632,6 +2691,12 @@ pub fn ToolEditForm() -> impl IntoView {
                                                 })
                                             />
                                         }.into_any(),
+                                        "callback" => view! {
+                                            <CallbackStrategyEditor config=mock_callback_config />
+                                        }.into_any(),
+                                        "matrix" => view! {
+                                            <MatrixStrategyEditor config=mock_matrix_config />
+                                        }.into_any(),
                                         _ => view! { <div></div> }.into_any(),
                                     }
                                 }}
@@ -2646,7 +2711,7 @@ pub fn ToolEditForm() -> impl IntoView {
                         {move || if saving.get() { "Saving..." } else { "Save Changes" }}
                     </button>
                     <a
-                        href="/tools"
+                        href="./tools"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
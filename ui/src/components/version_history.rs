@@ -140,7 +140,7 @@ pub fn VersionHistory() -> impl IntoView {
                                     <p class="text-yellow-700 mb-4">
                                         "Version history requires database persistence to be enabled."
                                     </p>
-                                    <a href="/config" class="inline-block px-4 py-2 bg-yellow-600 text-white rounded hover:bg-yellow-700">
+                                    <a href="./config" class="inline-block px-4 py-2 bg-yellow-600 text-white rounded hover:bg-yellow-700">
                                         "Configure Database"
                                     </a>
                                 </div>
@@ -236,7 +236,7 @@ pub fn Workflows() -> impl IntoView {
                             </span>
                         </button>
                     </div>
-                    <a href="/workflows/new" class="bg-orange-500 hover:bg-orange-600 text-white px-4 py-2 rounded flex items-center gap-2">
+                    <a href="./workflows/new" class="bg-orange-500 hover:bg-orange-600 text-white px-4 py-2 rounded flex items-center gap-2">
                         <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                             <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                         </svg>
@@ -601,7 +601,7 @@ fn EmptyState() -> impl IntoView {
             <h3 class="mt-2 text-sm font-medium text-gray-900">"No workflows"</h3>
             <p class="mt-1 text-sm text-gray-500">"Get started by creating a new workflow."</p>
             <div class="mt-6">
-                <a href="/workflows/new" class="inline-flex items-center px-4 py-2 bg-orange-500 text-white rounded hover:bg-orange-600">
+                <a href="./workflows/new" class="inline-flex items-center px-4 py-2 bg-orange-500 text-white rounded hover:bg-orange-600">
                     <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                     </svg>
@@ -686,7 +686,7 @@ fn WorkflowTable(
                                         "Test"
                                     </button>
                                     <a
-                                        href=format!("/workflows/edit/{}", name_for_edit)
+                                        href=format!("./workflows/edit/{}", name_for_edit)
                                         class="text-blue-600 hover:text-blue-900 mr-3"
                                     >
                                         "Edit"
@@ -769,7 +769,7 @@ fn WorkflowCards(
                                 "Test"
                             </button>
                             <a
-                                href=format!("/workflows/edit/{}", name_for_edit)
+                                href=format!("./workflows/edit/{}", name_for_edit)
                                 class="px-3 py-1 text-sm text-blue-600 hover:bg-blue-50 rounded"
                             >
                                 "Edit"
@@ -924,13 +924,15 @@ pub fn WorkflowForm() -> impl IntoView {
             output_schema: output_schema_opt,
             steps,
             on_error: ErrorStrategy::Fail,
+            output_mapping: None,
+            output_transform: None,
         };
 
         wasm_bindgen_futures::spawn_local(async move {
             match api::create_workflow(&workflow).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/workflows");
+                        let _ = window.location().set_href("./workflows");
                     }
                 }
                 Err(e) => {
@@ -944,7 +946,7 @@ pub fn WorkflowForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/workflows" class="text-gray-500 hover:text-gray-700">
+                <a href="./workflows" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -1108,7 +1110,7 @@ pub fn WorkflowForm() -> impl IntoView {
                         {move || if saving.get() { "Creating..." } else { "Create Workflow" }}
                     </button>
                     <a
-                        href="/workflows"
+                        href="./workflows"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -1134,6 +1136,8 @@ pub fn WorkflowEditForm() -> impl IntoView {
     });
     let (input_schema, set_input_schema) = signal(default_schema_edit.clone());
     let (output_schema, set_output_schema) = signal(default_schema_edit);
+    // Output mapping isn't editable in the form; keep what the workflow had
+    let (output_mapping, set_output_mapping) = signal((Option::<String>::None, Option::<String>::None));
     let (steps_data, set_steps_data) = signal(Vec::<StepData>::new());
     let (error, set_error) = signal(Option::<String>::None);
     let (saving, set_saving) = signal(false);
@@ -1230,6 +1234,7 @@ pub fn WorkflowEditForm() -> impl IntoView {
                         set_output_schema.set(out_schema.clone());
                     }
                     set_steps_data.set(workflow_steps_to_steps_data(&workflow.steps));
+                    set_output_mapping.set((workflow.output_mapping.clone(), workflow.output_transform.clone()));
                     set_has_loaded.set(true);
                 }
                 Err(e) => {
@@ -1297,13 +1302,15 @@ pub fn WorkflowEditForm() -> impl IntoView {
             output_schema: output_schema_opt,
             steps,
             on_error: ErrorStrategy::Fail,
+            output_mapping: output_mapping.get().0,
+            output_transform: output_mapping.get().1,
         };
 
         wasm_bindgen_futures::spawn_local(async move {
             match api::update_workflow(&orig_name, &workflow).await {
                 Ok(_) => {
                     if let Some(window) = web_sys::window() {
-                        let _ = window.location().set_href("/workflows");
+                        let _ = window.location().set_href("./workflows");
                     }
                 }
                 Err(e) => {
@@ -1317,7 +1324,7 @@ pub fn WorkflowEditForm() -> impl IntoView {
     view! {
         <div class="p-6 max-w-4xl mx-auto">
             <div class="flex items-center gap-4 mb-6">
-                <a href="/workflows" class="text-gray-500 hover:text-gray-700">
+                <a href="./workflows" class="text-gray-500 hover:text-gray-700">
                     <svg class="w-6 h-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 19l-7-7m0 0l7-7m-7 7h18"/>
                     </svg>
@@ -1495,7 +1502,7 @@ pub fn WorkflowEditForm() -> impl IntoView {
                         {move || if saving.get() { "Saving..." } else { "Save Changes" }}
                     </button>
                     <a
-                        href="/workflows"
+                        href="./workflows"
                         class="px-4 py-2 border border-gray-300 text-gray-700 rounded hover:bg-gray-50"
                     >
                         "Cancel"
@@ -9,6 +9,7 @@ mod components;
 use components::dashboard::Dashboard;
 use components::config::Config;
 use components::data_lakes::{DataLakes, DataLakeForm, DataLakeEditForm, DataLakeRecords};
+use components::global_search::GlobalSearch;
 use components::logs::Logs;
 use components::resources::{Resources, ResourceForm, ResourceEditForm};
 use components::resource_templates::{ResourceTemplates, ResourceTemplateForm, ResourceTemplateEditForm};
@@ -22,11 +23,12 @@ use components::version_history::VersionHistory;
 #[component]
 pub fn App() -> impl IntoView {
     view! {
-        <Router>
+        <Router base=api::base_path()>
             <div class="flex h-screen bg-gray-100">
                 // Sidebar
                 <div class="w-64 bg-gray-800 text-white p-4 flex flex-col">
                     <h1 class="text-2xl font-bold mb-8">"Metis"</h1>
+                    <GlobalSearch />
                     <nav class="space-y-1 flex-1">
                         <NavLink href="/" label="Dashboard" />
                         <NavLink href="/resources" label="Resources" />
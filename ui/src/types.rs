@@ -13,6 +13,22 @@ pub struct ApiResponse<T> {
     pub error: Option<String>,
 }
 
+/// Archetype matching a global search
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchResult {
+    #[serde(rename = "type")]
+    pub archetype_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// URI of resources, URI template of resource templates
+    #[serde(default)]
+    pub uri: Option<String>,
+    pub score: u32,
+}
+
 /// Configuration overview
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConfigOverview {
@@ -38,6 +54,33 @@ pub struct ConfigOverview {
     /// Version number for optimistic locking
     #[serde(default)]
     pub config_version: u64,
+    /// Config profile selected at launch
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// A problem the config validator found, attributed to an item and field
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConfigValidationError {
+    /// missing_field, invalid_value, cross_reference or duplicate
+    pub kind: String,
+    /// Archetype type, e.g. "tool" or "resource_template"
+    #[serde(default)]
+    pub archetype: Option<String>,
+    /// Name (or URI) of the item
+    #[serde(default)]
+    pub item: Option<String>,
+    /// Path of the offending field, e.g. "tools[2].description"
+    #[serde(default)]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Result of validating the live configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConfigValidationReport {
+    pub valid: bool,
+    pub errors: Vec<ConfigValidationError>,
 }
 
 /// Request body for save operations with optimistic locking
@@ -246,6 +289,10 @@ pub struct Tool {
     pub static_response: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mock: Option<MockConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_response: Option<bool>,
 }
 
 /// Prompt configuration
@@ -295,6 +342,12 @@ pub struct Workflow {
     pub steps: Vec<WorkflowStep>,
     #[serde(default)]
     pub on_error: ErrorStrategy,
+    /// Tera template reshaping the workflow result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mapping: Option<String>,
+    /// Rhai script reshaping the workflow result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_transform: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -316,6 +369,12 @@ pub struct WorkflowStep {
     pub loop_concurrency: u32,
     #[serde(default)]
     pub on_error: ErrorStrategy,
+    /// Named locks that serialize this step with parallel steps sharing a key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_lock_keys: Vec<String>,
+    /// Caller details injected into the step's args (e.g. "caller_ip", "trace_id")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inject_context: Vec<String>,
 }
 
 fn default_loop_var() -> String {
@@ -360,6 +419,12 @@ pub struct MockConfig {
     pub database: Option<MockDatabaseConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_lake_crud: Option<DataLakeCrudConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback: Option<CallbackConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv: Option<CsvConfig>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -374,11 +439,15 @@ pub enum MockStrategyType {
     File,
     Pattern,
     #[serde(rename = "llm")]
+    #[allow(clippy::upper_case_acronyms)]
     LLM,
     #[serde(rename = "database")]
     Database,
     #[serde(rename = "data_lake_crud")]
     DataLakeCrud,
+    Callback,
+    Matrix,
+    Csv,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -413,6 +482,10 @@ pub struct FileConfig {
     pub path: String,
     #[serde(default = "default_selection")]
     pub selection: String,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
 }
 
 fn default_selection() -> String {
@@ -530,6 +603,70 @@ fn default_read_limit() -> usize {
     100
 }
 
+/// Configuration for Callback mock strategy
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CallbackConfig {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_template: Option<String>,
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub headers: std::collections::HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ack_template: Option<String>,
+}
+
+/// Configuration for Matrix mock strategy
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MatrixConfig {
+    #[serde(default)]
+    pub pairs: Vec<MatrixPair>,
+    #[serde(default)]
+    pub mode: MatrixMode,
+}
+
+/// A canned response of the Matrix strategy
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MatrixPair {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_match: Option<Value>,
+    pub output: Value,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixMode {
+    #[default]
+    RoundRobin,
+    InputMatch,
+    Random,
+}
+
+/// Configuration for Csv mock strategy
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CsvConfig {
+    pub path: String,
+    #[serde(default)]
+    pub selection: CsvSelection,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSelection {
+    #[default]
+    Random,
+    Sequential,
+    Index,
+    Key,
+}
+
 /// Configuration for DataLakeCrud mock strategy
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct DataLakeCrudConfig {
@@ -567,6 +704,9 @@ pub struct TestResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub execution_time_ms: u64,
+    /// Workflow result before its output mapping was applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<Value>,
 }
 
 // ============================================================================
@@ -616,6 +756,17 @@ pub struct Agent {
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
+    /// Let `@persona:<name>` in user messages switch a multi-turn agent's persona
+    #[serde(default)]
+    pub persona_commands: bool,
+    /// System prompts by persona name
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub personas: std::collections::HashMap<String, String>,
+    /// Glob patterns of tools that need operator approval before a call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub require_confirmation_for: Vec<String>,
+    #[serde(default = "default_confirmation_timeout_ms")]
+    pub confirmation_timeout_ms: u64,
 }
 
 fn default_max_iterations() -> u32 {
@@ -626,6 +777,10 @@ fn default_timeout() -> u64 {
     120
 }
 
+fn default_confirmation_timeout_ms() -> u64 {
+    300_000
+}
+
 /// Agent type
 #[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -684,12 +839,22 @@ pub struct MemoryConfig {
     pub file_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<SummaryConfig>,
 }
 
 fn default_max_messages() -> u32 {
     100
 }
 
+/// Conversation summarization settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SummaryConfig {
+    pub every_n_messages: u32,
+    #[serde(default)]
+    pub summary_prompt: String,
+}
+
 /// Memory storage backend
 #[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]